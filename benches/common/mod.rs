@@ -0,0 +1,26 @@
+//! Helpers shared by the benchmarks.
+
+// Each benchmark compiles this module separately and only uses part of it.
+#![allow(dead_code)]
+
+use nalgebra::{point, Point2};
+
+/// A tiny deterministic generator so benchmark inputs are stable between
+/// runs.
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    /// A value in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// A point with both coordinates in `[0, size)`.
+    pub fn point(&mut self, size: f32) -> Point2<f32> {
+        point![self.next_f32() * size, self.next_f32() * size]
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use {
+    common::Lcg,
+    compgeo::{
+        line::{DistanceToPoint, Segment},
+        query::nearest_in,
+    },
+    criterion::{black_box, criterion_group, criterion_main, Criterion},
+    nalgebra::{point, Point2},
+};
+
+fn random_segments(count: usize) -> Vec<Segment> {
+    let mut rng = Lcg(7);
+    (0..count)
+        .map(|_| Segment::new(rng.point(100.0), rng.point(100.0)))
+        .collect()
+}
+
+/// The straightforward approach which takes a sqrt for every comparison.
+fn nearest_naive(segments: &[Segment], p: &Point2<f32>) -> Option<usize> {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| (index, segment.distance_to_point(p)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+fn nearest_segment(c: &mut Criterion) {
+    let segments = random_segments(1_000);
+    let p = point![50.0, 50.0];
+
+    let mut group = c.benchmark_group("nearest segment of 1000");
+    group.bench_function("naive distance_to_point", |b| {
+        b.iter(|| nearest_naive(black_box(&segments), black_box(&p)))
+    });
+    group.bench_function("nearest_in", |b| {
+        b.iter(|| nearest_in(black_box(&segments), black_box(&p)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, nearest_segment);
+criterion_main!(benches);
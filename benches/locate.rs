@@ -0,0 +1,118 @@
+mod common;
+
+use {
+    common::Lcg,
+    compgeo::{
+        dcel::Dcel,
+        line::Segment,
+        snap::snap_round,
+        triangulation::{LocateResult, Triangulation},
+    },
+    criterion::{black_box, criterion_group, criterion_main, Criterion},
+    nalgebra::{point, Point2},
+};
+
+/// A triangulated 100x100 grid of unit squares.
+fn grid() -> Triangulation {
+    let (size, stride) = (100, 101);
+    let points = (0..stride * stride)
+        .map(|i| point![(i % stride) as f32, (i / stride) as f32])
+        .collect();
+    let mut triangles = vec![];
+    for y in 0..size {
+        for x in 0..size {
+            let corner = y * stride + x;
+            triangles.push([corner, corner + 1, corner + stride + 1]);
+            triangles.push([corner, corner + stride + 1, corner + stride]);
+        }
+    }
+    Triangulation::new(points, triangles)
+}
+
+/// A path of queries which wanders slowly across the grid, like a particle
+/// being tracked from frame to frame.
+fn wandering_queries(count: usize) -> Vec<Point2<f32>> {
+    let mut rng = Lcg(3);
+    let mut p = point![50.0, 50.0];
+    (0..count)
+        .map(|_| {
+            let step = (rng.point(100.0) - point![50.0, 50.0]) * 0.02;
+            p = (p + step).map(|v| v.clamp(0.0, 100.0));
+            p
+        })
+        .collect()
+}
+
+/// The straightforward approach which tests every triangle.
+fn locate_naive(
+    triangulation: &Triangulation,
+    p: &Point2<f32>,
+) -> Option<usize> {
+    let points = triangulation.points();
+    triangulation.triangles().iter().position(|&[a, b, c]| {
+        let (a, b, c) = (points[a], points[b], points[c]);
+        (b - a).perp(&(p - a)) >= 0.0
+            && (c - b).perp(&(p - b)) >= 0.0
+            && (a - c).perp(&(p - c)) >= 0.0
+    })
+}
+
+fn triangulation_locate(c: &mut Criterion) {
+    let grid = grid();
+    let queries = wandering_queries(1_000);
+
+    let mut group = c.benchmark_group("1000 queries in 20k triangles");
+    group.sample_size(10);
+    group.bench_function("naive scan", |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(locate_naive(black_box(&grid), query));
+            }
+        })
+    });
+    group.bench_function("locate with hints", |b| {
+        b.iter(|| {
+            let mut hint = None;
+            for query in &queries {
+                let result: LocateResult = grid.locate(black_box(query), hint);
+                hint = result.triangle();
+            }
+        })
+    });
+    group.finish();
+}
+
+fn face_locate(c: &mut Criterion) {
+    let mut segments = vec![];
+    for i in 0..=30 {
+        let t = i as f32;
+        segments.push(Segment::new(point![t, 0.0], point![t, 30.0]));
+        segments.push(Segment::new(point![0.0, t], point![30.0, t]));
+    }
+    let dcel = Dcel::from_segments(&snap_round(&segments, 1e-3), 1e-6);
+    let locator = dcel.face_locator();
+    let mut rng = Lcg(5);
+    let queries: Vec<Point2<f32>> =
+        (0..100).map(|_| rng.point(100.0) * 0.3).collect();
+
+    let mut group = c.benchmark_group("100 queries in 900 faces");
+    group.sample_size(10);
+    group.bench_function("Dcel::locate", |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(dcel.locate(black_box(query)));
+            }
+        })
+    });
+    group.bench_function("FaceLocator::locate", |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(locator.locate(black_box(query)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, triangulation_locate, face_locate);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+mod common;
+
+use {
+    common::Lcg,
+    compgeo::{
+        line::{intersection::raycast_many, Ray, Segment},
+        parallel::{par_nearest_for_points, par_raycast_many},
+        query::nearest_for_points,
+    },
+    criterion::{black_box, criterion_group, criterion_main, Criterion},
+    nalgebra::{vector, Point2, Unit},
+};
+
+fn scene() -> (Vec<Segment>, Vec<Ray>, Vec<Point2<f32>>) {
+    let mut rng = Lcg(11);
+    let segments = (0..100_000)
+        .map(|_| {
+            let start = rng.point(100.0);
+            let offset = vector![rng.next_f32(), rng.next_f32()];
+            Segment::new(start, start + offset)
+        })
+        .collect();
+    let rays = (0..64)
+        .map(|_| {
+            let direction = vector![rng.next_f32() - 0.5, rng.next_f32()];
+            Ray::new(rng.point(100.0), Unit::new_normalize(direction))
+        })
+        .collect();
+    let points = (0..64).map(|_| rng.point(100.0)).collect();
+    (segments, rays, points)
+}
+
+fn raycast(c: &mut Criterion) {
+    let (segments, rays, _) = scene();
+
+    let mut group = c.benchmark_group("64 rays against 100k segments");
+    group.sample_size(10);
+    group.bench_function("raycast_many", |b| {
+        b.iter(|| raycast_many(black_box(&rays), black_box(&segments)))
+    });
+    group.bench_function("par_raycast_many", |b| {
+        b.iter(|| par_raycast_many(black_box(&rays), black_box(&segments)))
+    });
+    group.finish();
+}
+
+fn nearest(c: &mut Criterion) {
+    let (segments, _, points) = scene();
+
+    let mut group = c.benchmark_group("64 nearest queries over 100k segments");
+    group.sample_size(10);
+    group.bench_function("nearest_for_points", |b| {
+        b.iter(|| nearest_for_points(black_box(&points), black_box(&segments)))
+    });
+    group.bench_function("par_nearest_for_points", |b| {
+        b.iter(|| {
+            par_nearest_for_points(black_box(&points), black_box(&segments))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, raycast, nearest);
+criterion_main!(benches);
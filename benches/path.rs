@@ -0,0 +1,56 @@
+mod common;
+
+use {
+    common::Lcg,
+    compgeo::{
+        path::{ArcLengthTable, PathFrame},
+        polygon::Polyline,
+    },
+    criterion::{black_box, criterion_group, criterion_main, Criterion},
+    nalgebra::point,
+};
+
+/// A random walk with 10k vertices.
+fn random_walk() -> Polyline {
+    let mut rng = Lcg(7);
+    let mut p = point![0.0, 0.0];
+    Polyline::new(
+        (0..10_000)
+            .map(|_| {
+                p += nalgebra::vector![rng.next_f32(), rng.next_f32()]
+                    - nalgebra::vector![0.5, 0.5];
+                p
+            })
+            .collect(),
+    )
+}
+
+fn point_at_length(c: &mut Criterion) {
+    let path = random_walk();
+    let length = path.length();
+    let mut rng = Lcg(11);
+    let queries: Vec<f32> =
+        (0..10_000).map(|_| rng.next_f32() * length).collect();
+
+    let mut group = c.benchmark_group("10k queries along 10k vertices");
+    group.sample_size(10);
+    group.bench_function("Polyline::frame_at_length", |b| {
+        b.iter(|| {
+            for &s in &queries {
+                black_box(black_box(&path).frame_at_length(s));
+            }
+        })
+    });
+    group.bench_function("ArcLengthTable::frame_at_length", |b| {
+        b.iter(|| {
+            let table = ArcLengthTable::new(black_box(&path));
+            for &s in &queries {
+                black_box(table.frame_at_length(s));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, point_at_length);
+criterion_main!(benches);
@@ -0,0 +1,190 @@
+//! The everyday queries: distances, segment intersection, raycasts, and
+//! convex hulls, each over scenes of 1k and 10k items.
+//!
+//! Compare against a saved baseline with
+//! `cargo bench --bench queries -- --baseline <name>`.
+//!
+//! When rerun against the tree before the squared distance audit, none of
+//! these queries measured faster than the run-to-run noise: `convex_hull`,
+//! which the audit didn't touch, moved by as much as the distance queries
+//! did. The audit is kept for clarity, not speed.
+
+mod common;
+
+use {
+    common::Lcg,
+    compgeo::{
+        hull::convex_hull,
+        line::{
+            intersection::{intersect_segments, raycast, SegmentIntersection},
+            DistanceToPoint, Ray, Segment,
+        },
+        polygon::{hausdorff_distance, Polygon, Polyline, Region},
+        query::nearest_in,
+        scene::Scene,
+        shape::{contact_circle_polygon, Circle},
+    },
+    criterion::{
+        black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
+    },
+    nalgebra::{point, Point2, Unit, Vector2},
+};
+
+const SIZES: [usize; 2] = [1_000, 10_000];
+
+/// Short segments scattered over a 100x100 square.
+fn segments(count: usize) -> Vec<Segment> {
+    let mut rng = Lcg(3);
+    (0..count)
+        .map(|_| {
+            let start = rng.point(100.0);
+            let offset = (rng.point(100.0) - point![50.0, 50.0]) * 0.05;
+            Segment::new(start, start + offset)
+        })
+        .collect()
+}
+
+/// A star shaped polygon around the middle of the square.
+fn star(count: usize) -> Polygon {
+    let mut rng = Lcg(5);
+    Polygon::new(
+        (0..count)
+            .map(|i| {
+                let angle = core::f32::consts::TAU * i as f32 / count as f32;
+                let radius = 20.0 + rng.next_f32() * 20.0;
+                point![50.0, 50.0]
+                    + Vector2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect(),
+    )
+}
+
+/// A random walk across the square.
+fn walk(count: usize, seed: u64) -> Polyline {
+    let mut rng = Lcg(seed);
+    let mut current = point![50.0, 50.0];
+    Polyline::new(
+        (0..count)
+            .map(|_| {
+                current += (rng.point(100.0) - point![50.0, 50.0]) * 0.02;
+                current
+            })
+            .collect(),
+    )
+}
+
+fn distance(c: &mut Criterion) {
+    // Outside the star, so filled shapes measure to their boundary.
+    let p = point![20.0, 47.0];
+    let mut group = c.benchmark_group("distance queries");
+    for size in SIZES {
+        let segments = segments(size);
+        let region = Region::new(star(size), vec![]);
+        let ball = Circle::new(point![70.0, 50.0], 1.0);
+        let mut scene = Scene::new();
+        let polygon = star(size);
+        scene.insert(polygon.clone(), ());
+
+        group.bench_with_input(
+            BenchmarkId::new("nearest_in", size),
+            &segments,
+            |b, segments| b.iter(|| nearest_in(black_box(segments), &p)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Region::distance_to_point", size),
+            &region,
+            |b, region| b.iter(|| black_box(region).distance_to_point(&p)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Scene::nearest", size),
+            &scene,
+            |b, scene| b.iter(|| black_box(scene).nearest(&p)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("contact_circle_polygon", size),
+            &polygon,
+            |b, polygon| {
+                b.iter(|| contact_circle_polygon(&ball, black_box(polygon)))
+            },
+        );
+    }
+    let (a, b) = (walk(1_000, 7), walk(1_000, 8));
+    group.sample_size(10);
+    group.bench_function("hausdorff_distance/1000", |bench| {
+        bench.iter(|| hausdorff_distance(black_box(&a), black_box(&b)))
+    });
+    group.finish();
+}
+
+fn intersection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segment intersection");
+    for size in SIZES {
+        let probes: Vec<Segment> = segments(100)
+            .iter()
+            .map(|s| Segment::new(s.start, s.end + (s.end - s.start) * 40.0))
+            .collect();
+        group.bench_with_input(
+            BenchmarkId::new("100 probes", size),
+            &segments(size),
+            |b, segments| {
+                b.iter(|| {
+                    let mut hits = 0;
+                    for probe in &probes {
+                        for segment in segments {
+                            let hit = intersect_segments(probe, segment);
+                            if hit != SegmentIntersection::None {
+                                hits += 1;
+                            }
+                        }
+                    }
+                    hits
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn raycasts(c: &mut Criterion) {
+    let mut rng = Lcg(13);
+    let rays: Vec<Ray> = (0..100)
+        .map(|_| {
+            let direction = rng.point(100.0) - point![50.0, 50.0];
+            Ray::new(rng.point(100.0), Unit::new_normalize(direction))
+        })
+        .collect();
+    let mut group = c.benchmark_group("raycast");
+    for size in SIZES {
+        let segments = segments(size);
+        group.bench_with_input(
+            BenchmarkId::new("100 rays", size),
+            &segments,
+            |b, segments| {
+                b.iter(|| {
+                    rays.iter()
+                        .filter_map(|ray| raycast(ray, black_box(segments)))
+                        .count()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hull(c: &mut Criterion) {
+    let mut group = c.benchmark_group("convex hull");
+    for size in SIZES.into_iter().chain([100_000]) {
+        let mut rng = Lcg(17);
+        let points: Vec<Point2<f32>> =
+            (0..size).map(|_| rng.point(100.0)).collect();
+        group.bench_with_input(
+            BenchmarkId::new("convex_hull", size),
+            &points,
+            |b, points| b.iter(|| convex_hull(black_box(points))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, distance, intersection, raycasts, hull);
+criterion_main!(benches);
@@ -0,0 +1,222 @@
+mod common;
+
+use {
+    common::Lcg,
+    compgeo::{
+        broadphase::SweepAndPrune,
+        line::{
+            intersection::{
+                intersect_ray_segment, intersect_ray_segment_within,
+            },
+            DistanceToPoint, Ray, Segment,
+        },
+        shape::Aabb,
+        spatial::{Bvh, Split},
+    },
+    criterion::{black_box, criterion_group, criterion_main, Criterion},
+    nalgebra::{point, Point2, Unit, Vector2},
+};
+
+/// 100k short segments scattered over a 1000x1000 square.
+fn scene() -> Vec<Segment> {
+    let mut rng = Lcg(11);
+    (0..100_000)
+        .map(|_| {
+            let start = rng.point(1000.0);
+            let offset = (rng.point(1000.0) - point![500.0, 500.0]) * 0.004;
+            Segment::new(start, start + offset)
+        })
+        .collect()
+}
+
+fn build(c: &mut Criterion) {
+    let segments = scene();
+
+    let mut group = c.benchmark_group("build over 100k segments");
+    group.sample_size(10);
+    for (name, split) in [
+        ("surface area", Split::SurfaceArea),
+        ("median", Split::Median),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| Bvh::with_split(black_box(segments.clone()), split))
+        });
+    }
+    group.finish();
+}
+
+fn closest(c: &mut Criterion) {
+    let segments = scene();
+    let bvh = Bvh::new(segments.clone());
+    let mut rng = Lcg(13);
+    let queries: Vec<Point2<f32>> =
+        (0..100).map(|_| rng.point(1000.0)).collect();
+
+    let mut group = c.benchmark_group("100 closest queries in 100k segments");
+    group.sample_size(10);
+    group.bench_function("naive scan", |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(
+                    segments
+                        .iter()
+                        .map(|segment| segment.distance_to_point(query))
+                        .fold(f32::INFINITY, f32::min),
+                );
+            }
+        })
+    });
+    group.bench_function("Bvh::closest", |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(bvh.closest(black_box(query), |segment| {
+                    segment.distance_to_point(query)
+                }));
+            }
+        })
+    });
+    group.finish();
+}
+
+/// 100 rays from random points in random directions.
+fn rays() -> Vec<Ray> {
+    let mut rng = Lcg(17);
+    (0..100)
+        .map(|_| {
+            let direction = rng.point(1000.0) - point![500.0, 500.0];
+            Ray::new(
+                rng.point(1000.0),
+                Unit::try_new(direction, 1e-3).unwrap_or(Vector2::x_axis()),
+            )
+        })
+        .collect()
+}
+
+fn raycast(c: &mut Criterion) {
+    let segments = scene();
+    let bvh = Bvh::new(segments.clone());
+    let rays = rays();
+
+    let mut group = c.benchmark_group("100 rays in 100k segments");
+    group.sample_size(10);
+    group.bench_function("naive scan", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                black_box(
+                    segments
+                        .iter()
+                        .filter_map(|segment| {
+                            intersect_ray_segment(ray, segment)
+                        })
+                        .fold(f32::INFINITY, f32::min),
+                );
+            }
+        })
+    });
+    group.bench_function("Bvh::query_ray", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                black_box(bvh.query_ray(black_box(ray), |segment| {
+                    intersect_ray_segment(ray, segment)
+                }));
+            }
+        })
+    });
+    group.finish();
+}
+
+/// Rays which only reach 5 units, about twice the length of a segment, so
+/// most of the scene is out of range. Clamping an unbounded hit afterwards
+/// still pays for the search beyond the range, while the bounded query
+/// skips those branches: about 80 µs against 52 µs when this was added.
+fn short_range_raycast(c: &mut Criterion) {
+    const MAX_T: f32 = 5.0;
+    let segments = scene();
+    let bvh = Bvh::new(segments.clone());
+    let rays = rays();
+
+    let mut group = c.benchmark_group("100 short rays in 100k segments");
+    group.sample_size(10);
+    group.bench_function("Bvh::query_ray then clamp", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                black_box(
+                    bvh.query_ray(black_box(ray), |segment| {
+                        intersect_ray_segment(ray, segment)
+                    })
+                    .filter(|&(_, t)| t <= MAX_T),
+                );
+            }
+        })
+    });
+    group.bench_function("Bvh::query_ray_within", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                black_box(bvh.query_ray_within(black_box(ray), MAX_T, |s| {
+                    intersect_ray_segment_within(ray, s, MAX_T)
+                }));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn sweep_and_prune(c: &mut Criterion) {
+    let mut rng = Lcg(19);
+    let boxes: Vec<Aabb> = (0..10_000)
+        .map(|_| {
+            let min = rng.point(1000.0);
+            let size = (rng.point(1000.0) - point![0.0, 0.0]) * 0.005;
+            Aabb::new(min, min + size)
+        })
+        .collect();
+    // Each frame every box drifts by a small fraction of its size.
+    let velocities: Vec<Vector2<f32>> = (0..boxes.len())
+        .map(|_| (rng.point(1000.0) - point![500.0, 500.0]) * 0.0002)
+        .collect();
+    let step = |boxes: &mut [Aabb], frame: f32| {
+        for (aabb, velocity) in boxes.iter_mut().zip(&velocities) {
+            let offset = velocity * frame.sin();
+            *aabb = Aabb::new(aabb.min + offset, aabb.max + offset);
+        }
+    };
+
+    let mut group = c.benchmark_group("one frame of 10k slowly moving boxes");
+    group.sample_size(10);
+    group.bench_function("rebuild from scratch", |b| {
+        let mut boxes = boxes.clone();
+        let mut frame = 0.0;
+        b.iter(|| {
+            frame += 0.1;
+            step(&mut boxes, frame);
+            let broadphase =
+                SweepAndPrune::from_boxes(boxes.iter().copied().enumerate());
+            black_box(broadphase.pairs().count())
+        })
+    });
+    group.bench_function("SweepAndPrune::update", |b| {
+        let mut boxes = boxes.clone();
+        let mut broadphase =
+            SweepAndPrune::from_boxes(boxes.iter().copied().enumerate());
+        let mut frame = 0.0;
+        b.iter(|| {
+            frame += 0.1;
+            step(&mut boxes, frame);
+            for (id, aabb) in boxes.iter().enumerate() {
+                broadphase.update(id, *aabb);
+            }
+            black_box(broadphase.pairs().count())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    build,
+    closest,
+    raycast,
+    short_range_raycast,
+    sweep_and_prune
+);
+criterion_main!(benches);
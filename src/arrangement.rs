@@ -0,0 +1,339 @@
+//! Arrangements of infinite lines clipped to a box.
+
+use {
+    crate::{
+        dcel::Dcel,
+        graph::parameter_of,
+        line::{
+            intersection::{intersect_all_pairs, SegmentIntersection},
+            Line, Segment,
+        },
+        polygon::Polygon,
+        shape::Aabb,
+        weld::Welder,
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// The planar subdivision formed by a collection of lines inside a box.
+///
+/// Every line is cut into pieces wherever it crosses another line, and the
+/// box's boundary is cut wherever a line meets it. Together the pieces
+/// divide the box into convex faces.
+#[derive(Debug, Clone)]
+pub struct Arrangement {
+    segments: Vec<Vec<Segment>>,
+    dcel: Dcel,
+    faces: Vec<Polygon>,
+}
+
+/// Divide a box into the faces formed by a collection of lines.
+///
+/// Each line is clipped to the bounds with [`Line::clip_to_aabb`] and then
+/// split at its crossings with every other line. Crossings are found between
+/// the clipped pieces, so lines which only meet outside the bounds, like
+/// nearly parallel lines, contribute no vertex. Points closer than a small
+/// fraction of the box's size are welded together, which keeps three lines
+/// through nearly the same point from creating sliver faces.
+///
+/// Lines which miss the bounds, or only touch one of its corners, have no
+/// pieces. Coincident lines share their pieces.
+///
+/// # Example
+///
+/// Three lines in general position cut a box into seven faces. Only the
+/// triangle in the middle doesn't touch the box's boundary.
+///
+///     use ::{
+///         compgeo::{arrangement::line_arrangement, line::Line, shape::Aabb},
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+///     let lines = [
+///         Line::try_new(vector![0.0, 1.0], -2.0).unwrap(),
+///         Line::try_new(vector![1.0, 0.0], -2.0).unwrap(),
+///         Line::try_new(vector![1.0, 1.0], -9.0 / 2.0f32.sqrt()).unwrap(),
+///     ];
+///
+///     let arrangement = line_arrangement(&lines, &bounds);
+///
+///     assert_eq!(arrangement.faces().len(), 7);
+///     assert_eq!(arrangement.line_segments(0).len(), 3);
+///     let total: f32 = arrangement.faces().iter().map(|f| f.area()).sum();
+///     assert_relative_eq!(total, 100.0, max_relative = 1e-5);
+///
+pub fn line_arrangement(lines: &[Line], bounds: &Aabb) -> Arrangement {
+    let (width, height) = (bounds.width(), bounds.height());
+    let tolerance = 1e-5 * (width + height);
+    let mut segments = vec![Vec::new(); lines.len()];
+    if !(width > 0.0 && height > 0.0) {
+        return Arrangement {
+            segments,
+            dcel: Dcel::from_segments(&[], tolerance),
+            faces: Vec::new(),
+        };
+    }
+
+    let (visible, chords): (Vec<usize>, Vec<Segment>) = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let chord = line.clip_to_aabb(bounds)?;
+            let start = onto_boundary(bounds, &chord.start);
+            let end = onto_boundary(bounds, &chord.end);
+            Some((index, Segment::new(start, end)))
+        })
+        .unzip();
+
+    // Weld every vertex before building pieces so the pieces of different
+    // lines which meet at a crossing share exactly the same point.
+    let mut welder = Welder::new(tolerance);
+    let mut boundary: Vec<(f32, usize)> = bounds
+        .corners()
+        .iter()
+        .enumerate()
+        .map(|(side, corner)| (side as f32, welder.weld(corner)))
+        .collect();
+    let mut splits: Vec<Vec<(f32, usize)>> = chords
+        .iter()
+        .map(|chord| {
+            let (start, end) =
+                (welder.weld(&chord.start), welder.weld(&chord.end));
+            boundary.push((perimeter_parameter(bounds, &chord.start), start));
+            boundary.push((perimeter_parameter(bounds, &chord.end), end));
+            vec![(0.0, start), (1.0, end)]
+        })
+        .collect();
+    for (i, j, intersection) in intersect_all_pairs(&chords) {
+        let points = match intersection {
+            SegmentIntersection::None => continue,
+            SegmentIntersection::Point(p) => [p, p],
+            SegmentIntersection::Overlap(s) => [s.start, s.end],
+        };
+        for p in &points {
+            let vertex = welder.weld(p);
+            for index in [i, j] {
+                splits[index].push((parameter_of(&chords[index], p), vertex));
+            }
+        }
+    }
+    let vertices = welder.into_points();
+
+    let mut pieces = Vec::new();
+    for (&line, split) in visible.iter().zip(splits.iter_mut()) {
+        segments[line] = chain(&vertices, split);
+        pieces.extend_from_slice(&segments[line]);
+    }
+    let first = boundary[0];
+    let mut boundary_pieces = chain(&vertices, &mut boundary);
+    if let Some(last) = boundary_pieces.last().map(|piece| piece.end) {
+        boundary_pieces.push(Segment::new(last, vertices[first.1]));
+    }
+    pieces.extend(boundary_pieces);
+
+    let dcel = Dcel::from_segments(&pieces, tolerance);
+    let faces = dcel
+        .bounded_faces()
+        .map(|face| dcel.face_polygon(face))
+        .collect();
+    Arrangement {
+        segments,
+        dcel,
+        faces,
+    }
+}
+
+impl Arrangement {
+    /// Every vertex in the arrangement: the crossings between lines, the
+    /// points where lines meet the bounds, and the corners of the bounds.
+    pub fn vertices(&self) -> &[Point2<f32>] {
+        self.dcel.vertices()
+    }
+
+    /// The pieces of a line between consecutive vertices, in order along the
+    /// line's direction.
+    ///
+    /// The index refers to the slice of lines used to build the arrangement.
+    /// Lines which miss the bounds have no pieces.
+    pub fn line_segments(&self, line: usize) -> &[Segment] {
+        &self.segments[line]
+    }
+
+    /// The faces inside the bounds as counterclockwise polygons.
+    ///
+    /// The faces are in the same order as the DCEL's bounded faces.
+    pub fn faces(&self) -> &[Polygon] {
+        &self.faces
+    }
+
+    /// The subdivision as a doubly connected edge list, for walking between
+    /// neighboring faces.
+    pub fn dcel(&self) -> &Dcel {
+        &self.dcel
+    }
+}
+
+/// Sort the vertices along a path and connect consecutive distinct vertices.
+fn chain(vertices: &[Point2<f32>], split: &mut [(f32, usize)]) -> Vec<Segment> {
+    split.sort_by(|a, b| a.0.total_cmp(&b.0));
+    split
+        .windows(2)
+        .filter(|pair| pair[0].1 != pair[1].1)
+        .map(|pair| Segment::new(vertices[pair[0].1], vertices[pair[1].1]))
+        .collect()
+}
+
+/// Snap a point onto the closest side of the box.
+///
+/// Clipped endpoints are already on the boundary up to rounding, this makes
+/// it exact so they can be ordered around the perimeter.
+fn onto_boundary(bounds: &Aabb, point: &Point2<f32>) -> Point2<f32> {
+    let distances = [
+        point.y - bounds.min.y,
+        bounds.max.x - point.x,
+        bounds.max.y - point.y,
+        point.x - bounds.min.x,
+    ];
+    let side = (0..4)
+        .min_by(|&a, &b| distances[a].total_cmp(&distances[b]))
+        .unwrap();
+    match side {
+        0 => Point2::new(point.x, bounds.min.y),
+        1 => Point2::new(bounds.max.x, point.y),
+        2 => Point2::new(point.x, bounds.max.y),
+        _ => Point2::new(bounds.min.x, point.y),
+    }
+}
+
+/// The position of a boundary point counterclockwise around the box, from 0
+/// at `min` through 1, 2, and 3 at the other corners.
+fn perimeter_parameter(bounds: &Aabb, point: &Point2<f32>) -> f32 {
+    let (width, height) = (bounds.width(), bounds.height());
+    if point.y == bounds.min.y && point.x < bounds.max.x {
+        (point.x - bounds.min.x) / width
+    } else if point.x == bounds.max.x && point.y < bounds.max.y {
+        1.0 + (point.y - bounds.min.y) / height
+    } else if point.y == bounds.max.y && point.x > bounds.min.x {
+        2.0 + (bounds.max.x - point.x) / width
+    } else {
+        3.0 + (bounds.max.y - point.y) / height
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::line_arrangement,
+        crate::{
+            line::{DistanceToPoint, Line},
+            shape::Aabb,
+            test_support::line,
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    fn bounds() -> Aabb {
+        Aabb::new(point![0.0, 0.0], point![10.0, 10.0])
+    }
+
+    fn on_boundary(bounds: &Aabb, p: &nalgebra::Point2<f32>) -> bool {
+        bounds
+            .edges()
+            .iter()
+            .any(|edge| edge.distance_to_point(p) < 1e-4)
+    }
+
+    #[test]
+    pub fn three_generic_lines_should_enclose_one_interior_triangle() {
+        let bounds = bounds();
+        let lines = [
+            Line::try_new(vector![0.0, 1.0], -2.0).unwrap(),
+            Line::try_new(vector![1.0, 0.0], -2.0).unwrap(),
+            Line::try_new(vector![1.0, 1.0], -9.0 / 2.0f32.sqrt()).unwrap(),
+        ];
+
+        let arrangement = line_arrangement(&lines, &bounds);
+
+        assert_eq!(arrangement.faces().len(), 7);
+        let interior: Vec<_> = arrangement
+            .faces()
+            .iter()
+            .filter(|face| {
+                face.vertices.iter().all(|v| !on_boundary(&bounds, v))
+            })
+            .collect();
+        assert_eq!(interior.len(), 1);
+        assert_eq!(interior[0].vertices.len(), 3);
+        assert_relative_eq!(interior[0].area(), 12.5, max_relative = 1e-5);
+        for line in 0..3 {
+            assert_eq!(arrangement.line_segments(line).len(), 3);
+        }
+    }
+
+    #[test]
+    pub fn nearly_parallel_lines_should_not_cross_inside_the_bounds() {
+        // These lines cross near x = -5e6, far outside the bounds.
+        let lines = [
+            Line::try_new(vector![0.0, 1.0], -1.0).unwrap(),
+            Line::try_new(vector![-1e-7, 1.0], -1.5).unwrap(),
+        ];
+
+        let arrangement = line_arrangement(&lines, &bounds());
+
+        assert_eq!(arrangement.vertices().len(), 8);
+        assert!(arrangement
+            .vertices()
+            .iter()
+            .all(|v| v.iter().all(|c| c.is_finite())));
+        assert_eq!(arrangement.faces().len(), 3);
+        assert_eq!(arrangement.line_segments(0).len(), 1);
+        assert_eq!(arrangement.line_segments(1).len(), 1);
+    }
+
+    #[test]
+    pub fn lines_outside_the_bounds_should_be_ignored() {
+        let lines = [
+            Line::try_new(vector![1.0, 0.0], -20.0).unwrap(),
+            Line::try_new(vector![1.0, 1.0], 0.0).unwrap(),
+        ];
+
+        let arrangement = line_arrangement(&lines, &bounds());
+
+        assert!(arrangement.line_segments(0).is_empty());
+        assert!(arrangement.line_segments(1).is_empty());
+        assert_eq!(arrangement.vertices().len(), 4);
+        assert_eq!(arrangement.faces().len(), 1);
+    }
+
+    #[test]
+    pub fn empty_bounds_should_have_no_faces() {
+        let lines = [Line::try_new(vector![1.0, 0.0], 0.0).unwrap()];
+        let bounds = Aabb::new(point![0.0, 0.0], point![0.0, 5.0]);
+
+        let arrangement = line_arrangement(&lines, &bounds);
+
+        assert!(arrangement.faces().is_empty());
+        assert!(arrangement.line_segments(0).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn faces_should_tile_the_bounds(
+            lines in prop::collection::vec(line(0.0..=10.0), 0..8),
+        ) {
+            let arrangement = line_arrangement(&lines, &bounds());
+
+            let total: f32 =
+                arrangement.faces().iter().map(|f| f.area()).sum();
+            assert_relative_eq!(total, 100.0, max_relative = 1e-3);
+            for face in arrangement.faces() {
+                prop_assert!(face.signed_area() > 0.0);
+            }
+        }
+    }
+}
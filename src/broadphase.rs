@@ -0,0 +1,474 @@
+//! Broadphase collision detection for boxes which move a little at a time.
+//!
+//! A [`SweepAndPrune`] keeps the x extents of every box in one sorted list
+//! of endpoints. When a box moves, its endpoints are shuffled into place
+//! with an insertion sort, and every endpoint they pass marks the start or
+//! end of an overlap with another box. Between frames of a simulation most
+//! boxes only pass a few neighbors, so keeping the overlapping pairs up to
+//! date costs little more than touching each box once.
+//!
+//! The [`Bvh`](crate::spatial::Bvh) is a better fit for geometry which
+//! doesn't move.
+
+use {
+    crate::shape::Aabb,
+    alloc::{
+        collections::{BTreeMap, BTreeSet},
+        vec::Vec,
+    },
+    core::cmp::Ordering,
+};
+
+/// One end of a box's extent along the x axis.
+#[derive(Debug, Copy, Clone)]
+struct Endpoint {
+    value: f32,
+    is_max: bool,
+    slot: usize,
+}
+
+impl Endpoint {
+    /// Endpoints sort by value, and minimums sort before maximums at the
+    /// same value so boxes which just touch overlap.
+    fn cmp(&self, other: &Endpoint) -> Ordering {
+        self.value
+            .total_cmp(&other.value)
+            .then(self.is_max.cmp(&other.is_max))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry<Id> {
+    id: Id,
+    aabb: Aabb,
+
+    /// The positions of the box's minimum and maximum endpoints.
+    min_at: usize,
+    max_at: usize,
+}
+
+/// A set of boxes with ids which keeps track of which boxes overlap as
+/// they move.
+///
+/// Boxes touch when they share at least one point, matching
+/// [`Aabb::intersects`]. Boxes which aren't finite or whose minimum is
+/// greater than their maximum never overlap anything.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{broadphase::SweepAndPrune, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let square = |x: f32, y: f32| {
+///         Aabb::new(point![x, y], point![x + 1.0, y + 1.0])
+///     };
+///     let mut boxes = SweepAndPrune::new();
+///     boxes.update("a", square(0.0, 0.0));
+///     boxes.update("b", square(0.5, 0.5));
+///     boxes.update("c", square(3.0, 0.0));
+///
+///     assert_eq!(boxes.pairs().collect::<Vec<_>>(), vec![("a", "b")]);
+///
+///     boxes.update("c", square(1.2, 1.2));
+///     let mut pairs: Vec<_> = boxes.pairs().collect();
+///     pairs.sort();
+///     assert_eq!(pairs, vec![("a", "b"), ("b", "c")]);
+///
+///     boxes.remove(&"b");
+///     assert_eq!(boxes.pairs().count(), 0);
+///
+#[derive(Debug, Clone)]
+pub struct SweepAndPrune<Id> {
+    slots: Vec<Option<Entry<Id>>>,
+    free: Vec<usize>,
+    ids: BTreeMap<Id, usize>,
+    endpoints: Vec<Endpoint>,
+
+    /// Pairs of slots, smallest first, whose x extents overlap.
+    overlaps: BTreeSet<(usize, usize)>,
+}
+
+impl<Id: Copy + Ord> Default for SweepAndPrune<Id> {
+    /// An empty set of boxes.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id: Copy + Ord> SweepAndPrune<Id> {
+    /// Create an empty set of boxes.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            ids: BTreeMap::new(),
+            endpoints: Vec::new(),
+            overlaps: BTreeSet::new(),
+        }
+    }
+
+    /// Create a set of boxes all at once.
+    ///
+    /// This sorts the endpoints and sweeps over them a single time, which
+    /// is much faster than adding boxes one by one with
+    /// [`update`](Self::update). When an id appears more than once, its last
+    /// box is kept.
+    pub fn from_boxes(boxes: impl IntoIterator<Item = (Id, Aabb)>) -> Self {
+        let mut scene = Self::new();
+        for (id, aabb) in boxes {
+            let slot = *scene.ids.entry(id).or_insert_with(|| {
+                scene.slots.push(None);
+                scene.slots.len() - 1
+            });
+            scene.slots[slot] = Some(Entry {
+                id,
+                aabb,
+                min_at: 0,
+                max_at: 0,
+            });
+        }
+
+        for (slot, entry) in scene.slots.iter().enumerate() {
+            let (min, max) = extent(&entry.as_ref().unwrap().aabb);
+            for (value, is_max) in [(min, false), (max, true)] {
+                scene.endpoints.push(Endpoint {
+                    value,
+                    is_max,
+                    slot,
+                });
+            }
+        }
+        scene.endpoints.sort_by(Endpoint::cmp);
+
+        let mut active = BTreeSet::new();
+        for (at, endpoint) in scene.endpoints.iter().enumerate() {
+            let entry = scene.slots[endpoint.slot].as_mut().unwrap();
+            if endpoint.is_max {
+                entry.max_at = at;
+                active.remove(&endpoint.slot);
+            } else {
+                entry.min_at = at;
+                for &other in &active {
+                    let pair =
+                        (endpoint.slot.min(other), endpoint.slot.max(other));
+                    scene.overlaps.insert(pair);
+                }
+                active.insert(endpoint.slot);
+            }
+        }
+        scene
+    }
+
+    /// The number of boxes.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns true when there are no boxes.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The box with an id, or `None` when there isn't one.
+    pub fn get(&self, id: &Id) -> Option<&Aabb> {
+        let slot = *self.ids.get(id)?;
+        self.slots[slot].as_ref().map(|entry| &entry.aabb)
+    }
+
+    /// Move the box with an id, or add it when there isn't one yet.
+    ///
+    /// The cost grows with the number of endpoints the box passes, so
+    /// small moves are cheap.
+    pub fn update(&mut self, id: Id, aabb: Aabb) {
+        let slot = match self.ids.get(&id) {
+            Some(&slot) => slot,
+            None => self.insert(id),
+        };
+        let (min, max) = extent(&aabb);
+        let entry = self.slots[slot].as_mut().unwrap();
+        entry.aabb = aabb;
+        let (min_at, max_at) = (entry.min_at, entry.max_at);
+
+        // Move whichever endpoint leads first so the box's own endpoints
+        // never have to pass each other.
+        let moving_right = min > self.endpoints[min_at].value;
+        self.endpoints[min_at].value = min;
+        self.endpoints[max_at].value = max;
+        if moving_right {
+            self.sift(max_at);
+            let min_at = self.slots[slot].as_ref().unwrap().min_at;
+            self.sift(min_at);
+        } else {
+            self.sift(min_at);
+            let max_at = self.slots[slot].as_ref().unwrap().max_at;
+            self.sift(max_at);
+        }
+    }
+
+    /// Remove the box with an id, returning it.
+    ///
+    /// Returns `None` when there isn't a box with the id. This takes time
+    /// proportional to the number of boxes.
+    pub fn remove(&mut self, id: &Id) -> Option<Aabb> {
+        let slot = self.ids.remove(id)?;
+        let entry = self.slots[slot].take().unwrap();
+        self.free.push(slot);
+        self.endpoints.retain(|endpoint| endpoint.slot != slot);
+        for (at, endpoint) in self.endpoints.iter().enumerate() {
+            let entry = self.slots[endpoint.slot].as_mut().unwrap();
+            if endpoint.is_max {
+                entry.max_at = at;
+            } else {
+                entry.min_at = at;
+            }
+        }
+        self.overlaps.retain(|&(a, b)| a != slot && b != slot);
+        Some(entry.aabb)
+    }
+
+    /// Iterate over every pair of overlapping boxes.
+    ///
+    /// Each pair is given once, with the smaller id first. The pairs
+    /// themselves are in no particular order.
+    pub fn pairs(&self) -> impl Iterator<Item = (Id, Id)> + '_ {
+        self.overlaps.iter().filter_map(|&(a, b)| {
+            let a = self.slots[a].as_ref().unwrap();
+            let b = self.slots[b].as_ref().unwrap();
+            let touching = a.aabb.intersects(&b.aabb);
+            if !(touching && is_valid(&a.aabb) && is_valid(&b.aabb)) {
+                return None;
+            }
+            Some(if a.id < b.id {
+                (a.id, b.id)
+            } else {
+                (b.id, a.id)
+            })
+        })
+    }
+
+    /// Add a box with an id, with both endpoints at the end of the sorted
+    /// list where they don't overlap anything.
+    fn insert(&mut self, id: Id) -> usize {
+        let at = self.endpoints.len();
+        let entry = Entry {
+            id,
+            aabb: Aabb::empty(),
+            min_at: at,
+            max_at: at + 1,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot] = Some(entry);
+                slot
+            }
+            None => {
+                self.slots.push(Some(entry));
+                self.slots.len() - 1
+            }
+        };
+        for is_max in [false, true] {
+            self.endpoints.push(Endpoint {
+                value: f32::INFINITY,
+                is_max,
+                slot,
+            });
+        }
+        self.ids.insert(id, slot);
+        // Other boxes may also end at infinity, so put the new endpoints in
+        // their proper place.
+        self.sift(at);
+        let max_at = self.slots[slot].as_ref().unwrap().max_at;
+        self.sift(max_at);
+        slot
+    }
+
+    /// Move the endpoint at a position left or right until the list is
+    /// sorted around it, updating the overlaps it passes.
+    fn sift(&mut self, mut at: usize) {
+        while at > 0 && self.endpoints[at - 1].cmp(&self.endpoints[at]).is_gt()
+        {
+            self.swap(at - 1);
+            at -= 1;
+        }
+        while at + 1 < self.endpoints.len()
+            && self.endpoints[at + 1].cmp(&self.endpoints[at]).is_lt()
+        {
+            self.swap(at);
+            at += 1;
+        }
+    }
+
+    /// Swap the endpoints at `at` and `at + 1`.
+    ///
+    /// When a minimum moves in front of another box's maximum, the boxes
+    /// have started to overlap along x. The box's own maximum must already
+    /// be after the other's minimum, since each box's minimum is always in
+    /// front of its maximum. When a maximum moves in front of another box's
+    /// minimum, the boxes have stopped overlapping.
+    fn swap(&mut self, at: usize) {
+        let (left, right) = (self.endpoints[at], self.endpoints[at + 1]);
+        if left.slot != right.slot && left.is_max != right.is_max {
+            let pair = (left.slot.min(right.slot), left.slot.max(right.slot));
+            if right.is_max {
+                self.overlaps.remove(&pair);
+            } else {
+                self.overlaps.insert(pair);
+            }
+        }
+        self.endpoints.swap(at, at + 1);
+        for (endpoint, at) in [(right, at), (left, at + 1)] {
+            let entry = self.slots[endpoint.slot].as_mut().unwrap();
+            if endpoint.is_max {
+                entry.max_at = at;
+            } else {
+                entry.min_at = at;
+            }
+        }
+    }
+}
+
+/// Returns true when a box is finite and not inverted, so it can overlap
+/// other boxes.
+fn is_valid(aabb: &Aabb) -> bool {
+    let finite = aabb
+        .min
+        .iter()
+        .chain(aabb.max.iter())
+        .all(|v| v.is_finite());
+    finite && aabb.min.x <= aabb.max.x && aabb.min.y <= aabb.max.y
+}
+
+/// The x extent of a box, with boxes which can't overlap anything sent to
+/// infinity.
+fn extent(aabb: &Aabb) -> (f32, f32) {
+    if is_valid(aabb) {
+        (aabb.min.x, aabb.max.x)
+    } else {
+        (f32::INFINITY, f32::INFINITY)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::SweepAndPrune,
+        crate::{
+            shape::Aabb,
+            test_support::{aabb, point2},
+        },
+        alloc::{collections::BTreeMap, vec::Vec},
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    fn brute_force(boxes: &BTreeMap<u8, Aabb>) -> Vec<(u8, u8)> {
+        let mut pairs = Vec::new();
+        for (&a, first) in boxes {
+            for (&b, second) in boxes.range(a + 1..) {
+                if first.intersects(second) {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs
+    }
+
+    fn sorted_pairs(boxes: &SweepAndPrune<u8>) -> Vec<(u8, u8)> {
+        let mut pairs: Vec<(u8, u8)> = boxes.pairs().collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    pub fn touching_boxes_should_overlap() {
+        let mut boxes = SweepAndPrune::new();
+        boxes.update(0, Aabb::new(point![0.0, 0.0], point![1.0, 1.0]));
+        boxes.update(1, Aabb::new(point![1.0, 1.0], point![2.0, 2.0]));
+        boxes.update(2, Aabb::new(point![1.0, 1.5], point![1.0, 3.0]));
+
+        assert_eq!(sorted_pairs(&boxes), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    pub fn invalid_boxes_should_never_overlap() {
+        let mut boxes = SweepAndPrune::new();
+        let everything = Aabb::new(point![-1e9, -1e9], point![1e9, 1e9]);
+        boxes.update(0, everything);
+        boxes.update(1, Aabb::empty());
+        boxes.update(
+            2,
+            Aabb {
+                min: point![f32::NAN, 0.0],
+                max: point![1.0, 1.0],
+            },
+        );
+        boxes.update(
+            3,
+            Aabb {
+                min: point![2.0, 0.0],
+                max: point![1.0, 1.0],
+            },
+        );
+
+        assert!(sorted_pairs(&boxes).is_empty());
+        boxes.update(1, Aabb::new(point![0.0, 0.0], point![1.0, 1.0]));
+        assert_eq!(sorted_pairs(&boxes), vec![(0, 1)]);
+        assert_eq!(boxes.len(), 4);
+    }
+
+    proptest! {
+        #[test]
+        fn pairs_should_match_brute_force(
+            initial in prop::collection::vec(aabb(-50.0..=50.0), ..24),
+            steps in prop::collection::vec(
+                (any::<u8>(), point2(-3.0..=3.0), 0..8u8),
+                ..80,
+            ),
+        ) {
+            let mut boxes = SweepAndPrune::new();
+            let mut reference = BTreeMap::new();
+            for (id, aabb) in initial.into_iter().enumerate() {
+                boxes.update(id as u8, aabb);
+                reference.insert(id as u8, aabb);
+            }
+            prop_assert_eq!(sorted_pairs(&boxes), brute_force(&reference));
+            let mut boxes = SweepAndPrune::from_boxes(
+                reference.iter().map(|(&id, &aabb)| (id, aabb)),
+            );
+            prop_assert_eq!(sorted_pairs(&boxes), brute_force(&reference));
+
+            for (id, nudge, action) in steps {
+                let id = id % 32;
+                match action {
+                    0 => {
+                        let removed = reference.remove(&id);
+                        prop_assert_eq!(boxes.remove(&id), removed);
+                    }
+                    1 => {
+                        // Jump somewhere new, passing many endpoints.
+                        let aabb = Aabb::new(nudge * 15.0, nudge * -12.0);
+                        boxes.update(id, aabb);
+                        reference.insert(id, aabb);
+                    }
+                    _ => {
+                        let old = reference.get(&id).copied().unwrap_or(
+                            Aabb::new(point![0.0, 0.0], point![5.0, 5.0]),
+                        );
+                        let grow = vector![nudge.y, nudge.y] * 0.5;
+                        let aabb = Aabb::new(
+                            old.min + nudge.coords - grow,
+                            old.max + nudge.coords + grow,
+                        );
+                        boxes.update(id, aabb);
+                        reference.insert(id, aabb);
+                    }
+                }
+                prop_assert_eq!(boxes.len(), reference.len());
+                prop_assert_eq!(boxes.get(&id), reference.get(&id));
+                prop_assert_eq!(sorted_pairs(&boxes), brute_force(&reference));
+            }
+        }
+    }
+}
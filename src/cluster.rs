@@ -0,0 +1,415 @@
+//! Grouping points into clusters.
+//!
+//! Every clustering function returns one cluster index per input point.
+//! Use [`hulls`] to turn the assignments into shapes for drawing.
+
+use {
+    crate::{
+        hull::convex_hull, line::DistanceToPoint, polygon::Polygon,
+        random::SplitMix64,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// The most Lloyd iterations [`kmeans`] runs before giving up on
+/// convergence.
+const MAX_ITERATIONS: usize = 100;
+
+/// Partition points into `k` clusters with k-means.
+///
+/// The initial centers are chosen with k-means++ seeding from a generator
+/// seeded with `seed`, so the same inputs and seed always produce the same
+/// assignments. Lloyd iterations run until the assignments stop changing or
+/// for at most 100 iterations.
+///
+/// When a cluster ends up empty, it is reseeded with the point which is
+/// farthest from its own cluster's center. This means every cluster has at
+/// least one point. `k` is clamped to the number of points, and to at least
+/// one cluster. Ties between equally near centers go to the lowest index.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::cluster::kmeans,
+///         nalgebra::point,
+///     };
+///
+///     let points = [
+///         point![0.0, 0.0],
+///         point![10.0, 10.0],
+///         point![0.5, 0.0],
+///         point![10.0, 10.5],
+///     ];
+///
+///     let assignments = kmeans(&points, 2, 7);
+///
+///     assert_eq!(assignments[0], assignments[2]);
+///     assert_eq!(assignments[1], assignments[3]);
+///     assert_ne!(assignments[0], assignments[1]);
+///
+pub fn kmeans(points: &[Point2<f32>], k: usize, seed: u64) -> Vec<usize> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let k = k.clamp(1, points.len());
+    let mut rng = SplitMix64(seed);
+    let mut centers = seed_centers(points, k, &mut rng);
+    let mut assignments = vec![usize::MAX; points.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut next: Vec<usize> =
+            points.iter().map(|p| nearest(&centers, p)).collect();
+        reseed_empty_clusters(points, &centers, &mut next);
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+        centers = means(points, &assignments, k);
+    }
+    assignments
+}
+
+/// The convex hull of every cluster, indexed by cluster.
+///
+/// There is one polygon for each index from 0 up to the largest assignment.
+/// Clusters whose points don't enclose any area get a degenerate polygon
+/// instead: the two extreme points of a collinear cluster, the point of a
+/// single-point cluster, or no vertices for an unused index.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::cluster::hulls,
+///         nalgebra::point,
+///     };
+///
+///     let points = [
+///         point![0.0, 0.0],
+///         point![1.0, 0.0],
+///         point![0.0, 1.0],
+///         point![5.0, 5.0],
+///     ];
+///
+///     let hulls = hulls(&points, &[0, 0, 0, 1]);
+///
+///     assert_eq!(hulls.len(), 2);
+///     assert_eq!(hulls[0].vertices.len(), 3);
+///     assert_eq!(hulls[1].vertices, vec![point![5.0, 5.0]]);
+///
+pub fn hulls(points: &[Point2<f32>], assignments: &[usize]) -> Vec<Polygon> {
+    let count = assignments.iter().max().map_or(0, |&max| max + 1);
+    let mut members = vec![Vec::new(); count];
+    for (point, &cluster) in points.iter().zip(assignments) {
+        members[cluster].push(*point);
+    }
+    members
+        .into_iter()
+        .map(|members| {
+            convex_hull(&members).unwrap_or_else(|| extremes(&members))
+        })
+        .collect()
+}
+
+/// The lexicographically smallest and largest points, which are the ends of
+/// a collinear set of points.
+fn extremes(points: &[Point2<f32>]) -> Polygon {
+    let lexicographic = |a: &&Point2<f32>, b: &&Point2<f32>| {
+        a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+    };
+    let (Some(min), Some(max)) = (
+        points.iter().min_by(lexicographic),
+        points.iter().max_by(lexicographic),
+    ) else {
+        return Polygon::new(Vec::new());
+    };
+    if min == max {
+        Polygon::new(vec![*min])
+    } else {
+        Polygon::new(vec![*min, *max])
+    }
+}
+
+/// Choose the initial centers with greedy k-means++.
+///
+/// Each new center is sampled with probability proportional to its squared
+/// distance from the nearest existing center. A few candidates are sampled
+/// for every center and the one which reduces the total squared distance
+/// the most is kept, which makes it much less likely for two centers to
+/// start in the same cluster.
+fn seed_centers(
+    points: &[Point2<f32>],
+    k: usize,
+    rng: &mut SplitMix64,
+) -> Vec<Point2<f32>> {
+    let trials = 2 + k.ilog2() as usize;
+    let mut centers = vec![points[rng.below(points.len())]];
+    let mut weights: Vec<f64> = points
+        .iter()
+        .map(|p| p.distance_to_point_squared(&centers[0]) as f64)
+        .collect();
+    while centers.len() < k {
+        let total: f64 = weights.iter().sum();
+        let mut best: Option<(f64, Vec<f64>, usize)> = None;
+        for _ in 0..trials {
+            let index = if total > 0.0 {
+                sample(&weights, rng.next_f64() * total)
+            } else {
+                // Every point is on top of a center.
+                rng.below(points.len())
+            };
+            let candidate: Vec<f64> = weights
+                .iter()
+                .zip(points)
+                .map(|(&weight, p)| {
+                    weight
+                        .min(p.distance_to_point_squared(&points[index]) as f64)
+                })
+                .collect();
+            let potential = candidate.iter().sum();
+            if best.as_ref().is_none_or(|(b, _, _)| potential < *b) {
+                best = Some((potential, candidate, index));
+            }
+        }
+        let (_, candidate, index) = best.unwrap();
+        weights = candidate;
+        centers.push(points[index]);
+    }
+    centers
+}
+
+/// The index where the running sum of weights first passes the target.
+fn sample(weights: &[f64], mut target: f64) -> usize {
+    weights
+        .iter()
+        .position(|&w| {
+            target -= w;
+            target < 0.0
+        })
+        .unwrap_or_else(|| weights.iter().rposition(|&w| w > 0.0).unwrap())
+}
+
+/// The index of the nearest center, preferring lower indices on ties.
+fn nearest(centers: &[Point2<f32>], point: &Point2<f32>) -> usize {
+    let mut best = (0, f32::INFINITY);
+    for (index, center) in centers.iter().enumerate() {
+        let distance = point.distance_to_point_squared(center);
+        if distance < best.1 {
+            best = (index, distance);
+        }
+    }
+    best.0
+}
+
+/// Move the farthest point from its center into each empty cluster.
+///
+/// Points are only taken from clusters with more than one point, so this
+/// never empties another cluster.
+fn reseed_empty_clusters(
+    points: &[Point2<f32>],
+    centers: &[Point2<f32>],
+    assignments: &mut [usize],
+) {
+    let mut counts = vec![0; centers.len()];
+    for &cluster in assignments.iter() {
+        counts[cluster] += 1;
+    }
+    for empty in 0..centers.len() {
+        if counts[empty] > 0 {
+            continue;
+        }
+        let mut farthest = None;
+        let mut distance = f32::NEG_INFINITY;
+        for (index, point) in points.iter().enumerate() {
+            let cluster = assignments[index];
+            if counts[cluster] < 2 {
+                continue;
+            }
+            let d = point.distance_to_point_squared(&centers[cluster]);
+            if d > distance {
+                (farthest, distance) = (Some(index), d);
+            }
+        }
+        let Some(index) = farthest else {
+            return;
+        };
+        counts[assignments[index]] -= 1;
+        counts[empty] += 1;
+        assignments[index] = empty;
+    }
+}
+
+/// The mean of every cluster's points.
+fn means(
+    points: &[Point2<f32>],
+    assignments: &[usize],
+    k: usize,
+) -> Vec<Point2<f32>> {
+    let mut sums = vec![(Vector2::<f64>::zeros(), 0usize); k];
+    for (point, &cluster) in points.iter().zip(assignments) {
+        sums[cluster].0 += point.coords.cast::<f64>();
+        sums[cluster].1 += 1;
+    }
+    sums.into_iter()
+        .map(|(sum, count)| Point2::from((sum / count as f64).cast::<f32>()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{hulls, kmeans},
+        crate::{line::DistanceToPoint, random::SplitMix64},
+        alloc::vec::Vec,
+        core::f64::consts::TAU,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    /// Three well-separated gaussian blobs of 50 points each.
+    fn blobs() -> (Vec<Point2<f32>>, Vec<usize>) {
+        let mut rng = SplitMix64(12);
+        let centers = [point![0.0, 0.0], point![10.0, 0.0], point![5.0, 8.0]];
+        let mut points = Vec::new();
+        let mut truth = Vec::new();
+        for (blob, center) in centers.iter().enumerate() {
+            for _ in 0..50 {
+                // Box-Muller transform
+                let r = (-2.0 * (1.0 - rng.next_f64()).ln()).sqrt() * 0.6;
+                let angle = TAU * rng.next_f64();
+                points.push(
+                    center
+                        + nalgebra::vector![angle.cos(), angle.sin()]
+                            .scale(r)
+                            .cast::<f32>(),
+                );
+                truth.push(blob);
+            }
+        }
+        (points, truth)
+    }
+
+    /// The largest distance within a cluster and the smallest distance
+    /// between clusters.
+    fn spreads(points: &[Point2<f32>], assignments: &[usize]) -> (f32, f32) {
+        let (mut intra, mut inter) = (0.0f32, f32::INFINITY);
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let d = points[i].distance_to_point(&points[j]);
+                if assignments[i] == assignments[j] {
+                    intra = intra.max(d);
+                } else {
+                    inter = inter.min(d);
+                }
+            }
+        }
+        (intra, inter)
+    }
+
+    fn same_partition(a: &[usize], b: &[usize]) -> bool {
+        (0..a.len())
+            .all(|i| (0..a.len()).all(|j| (a[i] == a[j]) == (b[i] == b[j])))
+    }
+
+    #[test]
+    pub fn kmeans_should_separate_gaussian_blobs() {
+        let (points, truth) = blobs();
+
+        for seed in 0..100 {
+            let assignments = kmeans(&points, 3, seed);
+
+            assert!(same_partition(&assignments, &truth));
+            let (intra, inter) = spreads(&points, &assignments);
+            assert!(intra < inter, "{intra} >= {inter}");
+        }
+    }
+
+    #[test]
+    pub fn kmeans_should_be_deterministic_for_a_seed() {
+        let (points, _) = blobs();
+
+        assert_eq!(kmeans(&points, 5, 42), kmeans(&points, 5, 42));
+    }
+
+    #[test]
+    pub fn kmeans_should_clamp_k_to_the_number_of_points() {
+        let points = [point![0.0, 0.0], point![1.0, 0.0], point![0.0, 1.0]];
+
+        let mut assignments = kmeans(&points, 10, 1);
+
+        assignments.sort();
+        assert_eq!(assignments, vec![0, 1, 2]);
+        assert_eq!(kmeans(&points, 0, 1), vec![0, 0, 0]);
+        assert!(kmeans(&[], 3, 1).is_empty());
+    }
+
+    #[test]
+    pub fn kmeans_should_not_leave_empty_clusters_for_duplicate_points() {
+        let points = [point![2.0, 2.0]; 6];
+
+        let assignments = kmeans(&points, 3, 9);
+
+        for cluster in 0..3 {
+            assert!(assignments.contains(&cluster));
+        }
+    }
+
+    #[test]
+    pub fn hulls_should_handle_degenerate_clusters() {
+        let points = [
+            point![0.0, 0.0],
+            point![2.0, 2.0],
+            point![1.0, 1.0],
+            point![5.0, 5.0],
+        ];
+
+        let hulls = hulls(&points, &[0, 0, 0, 2]);
+
+        assert_eq!(hulls.len(), 3);
+        assert_eq!(hulls[0].vertices, vec![point![0.0, 0.0], point![2.0, 2.0]]);
+        assert!(hulls[1].vertices.is_empty());
+        assert_eq!(hulls[2].vertices, vec![point![5.0, 5.0]]);
+    }
+
+    proptest! {
+        #[test]
+        fn kmeans_should_use_every_cluster(
+            points in prop::collection::vec(
+                crate::test_support::point2(-10.0..=10.0),
+                1..40,
+            ),
+            k in 1usize..8,
+            seed in any::<u64>(),
+        ) {
+            let assignments = kmeans(&points, k, seed);
+
+            prop_assert_eq!(assignments.len(), points.len());
+            for cluster in 0..k.min(points.len()) {
+                prop_assert!(assignments.contains(&cluster));
+            }
+        }
+
+        #[test]
+        fn hulls_should_contain_their_clusters(
+            points in prop::collection::vec(
+                crate::test_support::point2(-10.0..=10.0),
+                1..40,
+            ),
+            seed in any::<u64>(),
+        ) {
+            let assignments = kmeans(&points, 3, seed);
+            let hulls = hulls(&points, &assignments);
+
+            for (point, &cluster) in points.iter().zip(&assignments) {
+                let hull = &hulls[cluster];
+                if hull.vertices.len() >= 3 {
+                    prop_assert!(
+                        hull.contains_point(point)
+                            || hull
+                                .edges()
+                                .any(|e| e.distance_to_point(point) < 1e-4)
+                    );
+                }
+            }
+        }
+    }
+}
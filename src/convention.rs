@@ -0,0 +1,202 @@
+//! Which way the y axis points, and what that means for winding and
+//! normals.
+//!
+//! The crate's math is y-up: "counterclockwise" and "left" are what they
+//! look like on a graph where y grows upwards. Canvases and SVG are y-down,
+//! so the same coordinates look mirrored on screen and every
+//! counterclockwise polygon is drawn clockwise. The functions which name a
+//! rotation direction or a side have `*_with_convention` variants that take
+//! an [`AxisConvention`], so their answers match what's on screen.
+//!
+//! Results which don't depend on the axis direction, like
+//! [`Polygon::offset`](crate::polygon::Polygon::offset) growing outward or
+//! [`Polygon::area`](crate::polygon::Polygon::area), don't need a
+//! convention.
+
+use nalgebra::{Point2, Unit, Vector2};
+
+/// The direction of the y axis in the space where geometry is viewed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AxisConvention {
+    /// The y axis points up, like a graph. This is the crate's default.
+    #[default]
+    YUp,
+
+    /// The y axis points down, like a canvas or SVG.
+    YDown,
+}
+
+/// Which way a path turns, as seen in some [`AxisConvention`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// The path turns counterclockwise, or to the left.
+    Counterclockwise,
+
+    /// The path turns clockwise, or to the right.
+    Clockwise,
+
+    /// The path doesn't turn, like collinear points or a polygon with no
+    /// area.
+    Collinear,
+}
+
+impl Orientation {
+    /// The opposite orientation, with collinear staying collinear.
+    pub fn reversed(self) -> Self {
+        match self {
+            Orientation::Counterclockwise => Orientation::Clockwise,
+            Orientation::Clockwise => Orientation::Counterclockwise,
+            Orientation::Collinear => Orientation::Collinear,
+        }
+    }
+}
+
+impl AxisConvention {
+    /// The orientation of a signed area or cross product computed with the
+    /// crate's y-up math.
+    ///
+    /// Positive values are counterclockwise when y is up and clockwise
+    /// when y is down. Zero and NaN are collinear.
+    ///
+    /// # Example
+    ///
+    ///     use compgeo::convention::{AxisConvention, Orientation};
+    ///
+    ///     let up = AxisConvention::YUp.orientation_of(2.0);
+    ///     let down = AxisConvention::YDown.orientation_of(2.0);
+    ///
+    ///     assert_eq!(up, Orientation::Counterclockwise);
+    ///     assert_eq!(down, Orientation::Clockwise);
+    ///
+    pub fn orientation_of(self, signed: f32) -> Orientation {
+        let y_up = if signed > 0.0 {
+            Orientation::Counterclockwise
+        } else if signed < 0.0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::Collinear
+        };
+        match self {
+            AxisConvention::YUp => y_up,
+            AxisConvention::YDown => y_up.reversed(),
+        }
+    }
+
+    /// Which way the path from `a` through `b` to `c` turns.
+    pub fn orientation(
+        self,
+        a: &Point2<f32>,
+        b: &Point2<f32>,
+        c: &Point2<f32>,
+    ) -> Orientation {
+        self.orientation_of((b - a).perp(&(c - a)))
+    }
+
+    /// Rotate a vector 90 degrees counterclockwise as seen in this
+    /// convention, which points to the left of the vector's direction.
+    ///
+    /// When y is up this is
+    /// [`perp_vec2d`](crate::operations::perp_vec2d), and when y is down it
+    /// is the opposite vector.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::convention::AxisConvention,
+    ///         nalgebra::vector,
+    ///     };
+    ///
+    ///     let east = vector![1.0, 0.0];
+    ///
+    ///     // Left of east is up the screen, which is -y on a canvas.
+    ///     assert_eq!(AxisConvention::YUp.perp(&east), vector![0.0, 1.0]);
+    ///     assert_eq!(AxisConvention::YDown.perp(&east), vector![0.0, -1.0]);
+    ///
+    pub fn perp(self, vector: &Vector2<f32>) -> Vector2<f32> {
+        match self {
+            AxisConvention::YUp => Vector2::new(-vector.y, vector.x),
+            AxisConvention::YDown => Vector2::new(vector.y, -vector.x),
+        }
+    }
+
+    /// Rotate a unit vector 90 degrees counterclockwise as seen in this
+    /// convention, without renormalizing.
+    pub fn perp_unit(self, vector: &Unit<Vector2<f32>>) -> Unit<Vector2<f32>> {
+        Unit::new_unchecked(self.perp(vector))
+    }
+
+    /// Turn a normal to the left in the crate's y-up math into the normal
+    /// to the left in this convention.
+    pub(crate) fn left_of_y_up(
+        self,
+        normal: Unit<Vector2<f32>>,
+    ) -> Unit<Vector2<f32>> {
+        match self {
+            AxisConvention::YUp => normal,
+            AxisConvention::YDown => -normal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{AxisConvention, Orientation},
+        crate::{operations::perp_vec2d, test_support::point2},
+        nalgebra::{point, Vector2},
+        proptest::prelude::*,
+    };
+
+    const CONVENTIONS: [AxisConvention; 2] =
+        [AxisConvention::YUp, AxisConvention::YDown];
+
+    #[test]
+    pub fn y_up_should_be_the_default() {
+        assert_eq!(AxisConvention::default(), AxisConvention::YUp);
+    }
+
+    #[test]
+    pub fn collinear_points_should_be_collinear_either_way() {
+        let (a, b, c) = (point![0.0, 0.0], point![1.0, 1.0], point![3.0, 3.0]);
+
+        for convention in CONVENTIONS {
+            assert_eq!(
+                convention.orientation(&a, &b, &c),
+                Orientation::Collinear
+            );
+            assert_eq!(
+                convention.orientation_of(f32::NAN),
+                Orientation::Collinear
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn flipping_y_should_flip_turns_and_keep_normals_on_screen(
+            a in point2(-100.0..=100.0),
+            b in point2(-100.0..=100.0),
+            c in point2(-100.0..=100.0),
+        ) {
+            let flip = |p: nalgebra::Point2<f32>| point![p.x, -p.y];
+            let up = AxisConvention::YUp.orientation(&a, &b, &c);
+            let down = AxisConvention::YDown.orientation(&a, &b, &c);
+
+            prop_assert_eq!(down, up.reversed());
+            // Mirroring the points and the convention together draws the
+            // same picture, so the turn is the same.
+            let (a, b, c) = (flip(a), flip(b), flip(c));
+            let mirrored = AxisConvention::YDown.orientation(&a, &b, &c);
+            prop_assert_eq!(mirrored, up);
+
+            let direction = b - a;
+            let up_normal = AxisConvention::YUp.perp(&direction);
+            prop_assert_eq!(up_normal, perp_vec2d(&direction));
+            let mirror = |v: Vector2<f32>| Vector2::new(v.x, -v.y);
+            prop_assert_eq!(
+                AxisConvention::YDown.perp(&mirror(direction)),
+                mirror(up_normal)
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Convert between the crate's types and the plain containers that points
+//! arrive in from files, GPU buffers, and other libraries.
+//!
+//! Flat slices interleave coordinates as `[x0, y0, x1, y1, ...]`, and
+//! tuples are `(x, y)`. Each conversion has an inverse iterator, so data
+//! can be written back out without building an intermediate collection.
+
+use nalgebra::Point2;
+#[cfg(feature = "alloc")]
+use {
+    crate::{error::check_finite, Error},
+    alloc::vec::Vec,
+};
+
+/// Collect `(x, y)` pairs into points.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::convert::{points_from_iter, points_to_iter},
+///         nalgebra::point,
+///     };
+///
+///     let points = points_from_iter([(0.0, 1.0), (2.0, 3.0)]);
+///
+///     assert_eq!(points, vec![point![0.0, 1.0], point![2.0, 3.0]]);
+///     let pairs: Vec<_> = points_to_iter(&points).collect();
+///     assert_eq!(pairs, vec![(0.0, 1.0), (2.0, 3.0)]);
+///
+#[cfg(feature = "alloc")]
+pub fn points_from_iter(
+    pairs: impl IntoIterator<Item = (f32, f32)>,
+) -> Vec<Point2<f32>> {
+    pairs.into_iter().map(|(x, y)| Point2::new(x, y)).collect()
+}
+
+/// Iterate over points as `(x, y)` pairs, the inverse of
+/// [`points_from_iter`].
+pub fn points_to_iter(
+    points: &[Point2<f32>],
+) -> impl Iterator<Item = (f32, f32)> + '_ {
+    points.iter().map(|point| (point.x, point.y))
+}
+
+/// Read points from interleaved `[x0, y0, x1, y1, ...]` coordinates.
+///
+/// Returns [`Error::OddCoordinateCount`] when the slice has an odd length,
+/// rather than dropping the unpaired coordinate. When `strict` is true,
+/// coordinates which are infinite or NaN are rejected with
+/// [`Error::NotFinite`], and otherwise they're kept as they are.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             convert::{points_from_flat_slice, points_to_flat_iter},
+///             Error,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let points = points_from_flat_slice(&[0.0, 1.0, 2.0, 3.0], true)?;
+///
+///     assert_eq!(points, vec![point![0.0, 1.0], point![2.0, 3.0]]);
+///     let flat: Vec<f32> = points_to_flat_iter(&points).collect();
+///     assert_eq!(flat, vec![0.0, 1.0, 2.0, 3.0]);
+///     assert_eq!(
+///         points_from_flat_slice(&[0.0, 1.0, 2.0], false),
+///         Err(Error::OddCoordinateCount)
+///     );
+///     # Ok::<(), Error>(())
+///
+#[cfg(feature = "alloc")]
+pub fn points_from_flat_slice(
+    coordinates: &[f32],
+    strict: bool,
+) -> Result<Vec<Point2<f32>>, Error> {
+    if coordinates.len() % 2 != 0 {
+        return Err(Error::OddCoordinateCount);
+    }
+    if strict {
+        check_finite(coordinates)?;
+    }
+    Ok(coordinates
+        .chunks_exact(2)
+        .map(|pair| Point2::new(pair[0], pair[1]))
+        .collect())
+}
+
+/// Iterate over the interleaved coordinates of points, the inverse of
+/// [`points_from_flat_slice`].
+pub fn points_to_flat_iter(
+    points: &[Point2<f32>],
+) -> impl Iterator<Item = f32> + '_ {
+    points.iter().flat_map(|point| [point.x, point.y])
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            points_from_flat_slice, points_from_iter, points_to_flat_iter,
+            points_to_iter,
+        },
+        crate::{
+            line::Segment,
+            polygon::{Polygon, Polyline},
+            test_support::point2,
+            Error,
+        },
+        alloc::vec::Vec,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn odd_and_non_finite_coordinates_should_be_rejected() {
+        let odd = [0.0, 1.0, 2.0];
+        let nan = [0.0, f32::NAN, 2.0, 3.0];
+        let infinite = [f32::NEG_INFINITY, 1.0];
+
+        assert_eq!(
+            points_from_flat_slice(&odd, false),
+            Err(Error::OddCoordinateCount)
+        );
+        assert_eq!(
+            Polyline::from_flat_slice(&odd, true),
+            Err(Error::OddCoordinateCount)
+        );
+        assert_eq!(
+            Polygon::from_flat_slice(&odd, true),
+            Err(Error::OddCoordinateCount)
+        );
+        assert_eq!(points_from_flat_slice(&nan, true), Err(Error::NotFinite));
+        assert_eq!(
+            Polyline::from_flat_slice(&infinite, true),
+            Err(Error::NotFinite)
+        );
+        assert_eq!(Polygon::from_flat_slice(&nan, true), Err(Error::NotFinite));
+
+        let loose = points_from_flat_slice(&nan, false).unwrap();
+        assert!(loose[0].y.is_nan());
+        assert!(points_from_flat_slice(&[], true).unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn segments_should_round_trip_through_arrays() {
+        let segment = Segment::from_array([[1.0, 2.0], [3.0, -4.0]]);
+
+        assert_eq!(segment.start, point![1.0, 2.0]);
+        assert_eq!(segment.end, point![3.0, -4.0]);
+        assert_eq!(segment.to_array(), [[1.0, 2.0], [3.0, -4.0]]);
+    }
+
+    proptest! {
+        #[test]
+        fn points_should_round_trip_exactly(
+            points in prop::collection::vec(point2(-1e6..=1e6), 0..16),
+        ) {
+            let flat: Vec<f32> = points_to_flat_iter(&points).collect();
+            prop_assert_eq!(flat.len(), points.len() * 2);
+            prop_assert_eq!(&points_from_flat_slice(&flat, true)?, &points);
+            let pairs = points_to_iter(&points);
+            prop_assert_eq!(&points_from_iter(pairs), &points);
+
+            let polyline = Polyline::from_flat_slice(&flat, true)?;
+            prop_assert_eq!(&polyline.vertices, &points);
+            prop_assert_eq!(&polyline.to_flat_vec(), &flat);
+            let polygon = Polygon::from_flat_slice(&flat, false)?;
+            prop_assert_eq!(&polygon.vertices, &points);
+            prop_assert_eq!(&polygon.to_flat_vec(), &flat);
+        }
+    }
+}
@@ -0,0 +1,623 @@
+//! A doubly connected edge list (DCEL) for planar subdivisions.
+//!
+//! A DCEL represents the vertices, edges, and faces of a planar drawing. Every
+//! edge is split into a pair of directed "half-edges" which point in opposite
+//! directions. Each half-edge borders exactly one face (the face on its left)
+//! and knows the next half-edge around that face.
+//!
+//! Bounded faces are traversed counterclockwise and have a positive signed
+//! area. The unbounded region around each connected component is traversed
+//! clockwise and has a negative signed area.
+
+use {
+    crate::{line::Segment, polygon::Polygon, shape::Aabb, weld::Welder},
+    alloc::{collections::BTreeSet, vec::Vec},
+    core::cmp::Ordering,
+    nalgebra::{Point2, Vector2},
+};
+
+/// One half of an edge in a [`Dcel`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HalfEdge {
+    /// The index of the vertex where this half-edge starts.
+    pub origin: usize,
+
+    /// The index of the half-edge which points in the opposite direction.
+    pub twin: usize,
+
+    /// The index of the next half-edge around this half-edge's face.
+    pub next: usize,
+
+    /// The index of the previous half-edge around this half-edge's face.
+    pub prev: usize,
+
+    /// The index of the face on the left side of this half-edge.
+    pub face: usize,
+}
+
+/// A face in a [`Dcel`], defined by a cycle of half-edges.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Face {
+    /// The index of one of the half-edges on the face's boundary.
+    pub half_edge: usize,
+
+    /// The signed area enclosed by the face's boundary cycle. Bounded faces
+    /// have a positive area and the outer face of each connected component
+    /// has a negative area.
+    pub signed_area: f32,
+}
+
+/// A half-edge data structure describing a planar subdivision.
+#[derive(Debug, Clone)]
+pub struct Dcel {
+    vertices: Vec<Point2<f32>>,
+    half_edges: Vec<HalfEdge>,
+    faces: Vec<Face>,
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl Dcel {
+    /// Build a planar subdivision from a collection of segments.
+    ///
+    /// Segment endpoints within `tolerance` of each other are welded into a
+    /// single vertex. Segments which collapse to a single vertex, or which
+    /// duplicate another segment, are ignored.
+    ///
+    /// The segments must not cross each other except at shared endpoints.
+    /// Use [`snap_round`](crate::snap::snap_round) to planarize arbitrary
+    /// segments first.
+    ///
+    /// Each connected component gets its own outer face. Components nested
+    /// inside the face of another component are not linked to it, but
+    /// [`Dcel::locate`] still reports the innermost face for a point.
+    ///
+    /// # Example
+    ///
+    /// A square with a diagonal has three faces: two triangles and the outer
+    /// face.
+    ///
+    ///     use ::{
+    ///         compgeo::{dcel::Dcel, line::Segment},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let (a, b, c, d) = (
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![1.0, 1.0],
+    ///         point![0.0, 1.0],
+    ///     );
+    ///     let dcel = Dcel::from_segments(
+    ///         &[
+    ///             Segment::new(a, b),
+    ///             Segment::new(b, c),
+    ///             Segment::new(c, d),
+    ///             Segment::new(d, a),
+    ///             Segment::new(a, c),
+    ///         ],
+    ///         1e-6,
+    ///     );
+    ///
+    ///     assert_eq!(dcel.vertices().len(), 4);
+    ///     assert_eq!(dcel.faces().len(), 3);
+    ///     assert_eq!(dcel.bounded_faces().count(), 2);
+    ///
+    pub fn from_segments(segments: &[Segment], tolerance: f32) -> Self {
+        let mut welder = Welder::new(tolerance);
+        let mut seen = BTreeSet::new();
+        let mut half_edges = Vec::new();
+        for segment in segments {
+            let (u, v) =
+                (welder.weld(&segment.start), welder.weld(&segment.end));
+            if u == v || !seen.insert((u.min(v), u.max(v))) {
+                continue;
+            }
+            let index = half_edges.len();
+            for (origin, twin) in [(u, index + 1), (v, index)] {
+                half_edges.push(HalfEdge {
+                    origin,
+                    twin,
+                    next: usize::MAX,
+                    prev: usize::MAX,
+                    face: usize::MAX,
+                });
+            }
+        }
+
+        let vertices = welder.into_points();
+
+        // Sort the outgoing half-edges counterclockwise around each vertex.
+        let mut outgoing = vec![Vec::new(); vertices.len()];
+        for (index, half_edge) in half_edges.iter().enumerate() {
+            outgoing[half_edge.origin].push(index);
+        }
+        let direction = |e: usize| {
+            vertices[half_edges[half_edges[e].twin].origin]
+                - vertices[half_edges[e].origin]
+        };
+        let mut position = vec![0; half_edges.len()];
+        for edges in &mut outgoing {
+            edges.sort_by(|&a, &b| angular_order(&direction(a), &direction(b)));
+            for (i, &e) in edges.iter().enumerate() {
+                position[e] = i;
+            }
+        }
+
+        // The next half-edge after u->v is the outgoing half-edge at v which
+        // comes immediately clockwise from v->u. This keeps each face on the
+        // left.
+        for e in 0..half_edges.len() {
+            let twin = half_edges[e].twin;
+            let around = &outgoing[half_edges[twin].origin];
+            let next =
+                around[(position[twin] + around.len() - 1) % around.len()];
+            half_edges[e].next = next;
+            half_edges[next].prev = e;
+        }
+
+        // Every cycle of next pointers is a face.
+        let mut faces = Vec::new();
+        for start in 0..half_edges.len() {
+            if half_edges[start].face != usize::MAX {
+                continue;
+            }
+            let face = faces.len();
+            let mut twice_area = 0.0;
+            let origin = vertices[half_edges[start].origin];
+            let mut e = start;
+            loop {
+                half_edges[e].face = face;
+                let a = vertices[half_edges[e].origin] - origin;
+                let b =
+                    vertices[half_edges[half_edges[e].twin].origin] - origin;
+                twice_area += a.perp(&b);
+                e = half_edges[e].next;
+                if e == start {
+                    break;
+                }
+            }
+            faces.push(Face {
+                half_edge: start,
+                signed_area: twice_area * 0.5,
+            });
+        }
+
+        Self {
+            vertices,
+            half_edges,
+            faces,
+            outgoing,
+        }
+    }
+
+    /// All of the vertices in the subdivision.
+    pub fn vertices(&self) -> &[Point2<f32>] {
+        &self.vertices
+    }
+
+    /// All of the half-edges in the subdivision. Half-edges `2k` and `2k + 1`
+    /// are always twins.
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    /// All of the faces in the subdivision, including the outer faces.
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    /// The indices of the bounded faces (faces with a positive area).
+    pub fn bounded_faces(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.faces.len()).filter(|&face| self.is_bounded(face))
+    }
+
+    /// Returns true when the face encloses a positive area.
+    pub fn is_bounded(&self, face: usize) -> bool {
+        self.faces[face].signed_area > 0.0
+    }
+
+    /// The index of the vertex where a half-edge ends.
+    pub fn destination(&self, half_edge: usize) -> usize {
+        self.half_edges[self.half_edges[half_edge].twin].origin
+    }
+
+    /// Every edge in the subdivision as a segment, one per pair of twins.
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.half_edges.iter().step_by(2).map(|half_edge| {
+            Segment::new(
+                self.vertices[half_edge.origin],
+                self.vertices[self.half_edges[half_edge.twin].origin],
+            )
+        })
+    }
+
+    /// The vertices adjacent to a vertex, in counterclockwise order.
+    pub fn neighbors(&self, vertex: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outgoing[vertex].iter().map(|&e| self.destination(e))
+    }
+
+    /// The indices of the half-edges on a face's boundary, in order.
+    pub fn face_half_edges(&self, face: usize) -> Vec<usize> {
+        let start = self.faces[face].half_edge;
+        let mut cycle = vec![start];
+        let mut e = self.half_edges[start].next;
+        while e != start {
+            cycle.push(e);
+            e = self.half_edges[e].next;
+        }
+        cycle
+    }
+
+    /// The indices of the vertices on a face's boundary, in order.
+    ///
+    /// Vertices can appear more than once when the face has dangling edges.
+    pub fn face_vertices(&self, face: usize) -> Vec<usize> {
+        self.face_half_edges(face)
+            .into_iter()
+            .map(|e| self.half_edges[e].origin)
+            .collect()
+    }
+
+    /// The boundary of a face as a polygon.
+    ///
+    /// Bounded faces produce counterclockwise polygons and outer faces
+    /// produce clockwise polygons.
+    pub fn face_polygon(&self, face: usize) -> Polygon {
+        Polygon::new(
+            self.face_vertices(face)
+                .into_iter()
+                .map(|v| self.vertices[v])
+                .collect(),
+        )
+    }
+
+    /// Find the innermost bounded face which contains a point.
+    ///
+    /// Returns `None` when the point is outside every bounded face. This
+    /// checks every face, so it takes linear time.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{dcel::Dcel, line::Segment},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let (a, b, c) = (point![0.0, 0.0], point![4.0, 0.0], point![0.0, 4.0]);
+    ///     let dcel = Dcel::from_segments(
+    ///         &[Segment::new(a, b), Segment::new(b, c), Segment::new(c, a)],
+    ///         1e-6,
+    ///     );
+    ///
+    ///     let face = dcel.locate(&point![1.0, 1.0]).unwrap();
+    ///     assert!(dcel.is_bounded(face));
+    ///     assert_eq!(dcel.locate(&point![5.0, 5.0]), None);
+    ///
+    pub fn locate(&self, point: &Point2<f32>) -> Option<usize> {
+        self.bounded_faces()
+            .filter(|&face| self.face_polygon(face).contains_point(point))
+            .min_by(|&a, &b| {
+                self.faces[a]
+                    .signed_area
+                    .total_cmp(&self.faces[b].signed_area)
+            })
+    }
+
+    /// Build an index which answers [`Dcel::locate`] queries without
+    /// checking every face.
+    ///
+    /// The subdivision isn't borrowed, so the locator stays valid only as
+    /// long as the subdivision isn't rebuilt.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{dcel::Dcel, line::Segment},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let (a, b, c) = (point![0.0, 0.0], point![4.0, 0.0], point![0.0, 4.0]);
+    ///     let dcel = Dcel::from_segments(
+    ///         &[Segment::new(a, b), Segment::new(b, c), Segment::new(c, a)],
+    ///         1e-6,
+    ///     );
+    ///     let locator = dcel.face_locator();
+    ///
+    ///     let p = point![1.0, 1.0];
+    ///     assert_eq!(locator.locate(&p), dcel.locate(&p));
+    ///     assert_eq!(locator.locate(&point![5.0, 5.0]), None);
+    ///
+    pub fn face_locator(&self) -> FaceLocator {
+        FaceLocator::new(self)
+    }
+}
+
+/// A bounded face's boundary and bounding box, cached by [`FaceLocator`].
+#[derive(Debug, Clone)]
+struct LocatorFace {
+    face: usize,
+    polygon: Polygon,
+    bounds: Aabb,
+    signed_area: f32,
+}
+
+/// A grid of buckets which narrows down the faces that could contain a
+/// point.
+///
+/// Each bounded face is added to every grid cell which its bounding box
+/// overlaps. A query only checks the faces in the point's cell, and only runs
+/// the full point-in-polygon test when the face's bounding box contains the
+/// point. The grid has about as many cells as there are faces.
+///
+/// Results always match [`Dcel::locate`].
+#[derive(Debug, Clone)]
+pub struct FaceLocator {
+    faces: Vec<LocatorFace>,
+    bounds: Option<Aabb>,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl FaceLocator {
+    fn new(dcel: &Dcel) -> Self {
+        let faces: Vec<LocatorFace> = dcel
+            .bounded_faces()
+            .filter_map(|face| {
+                let polygon = dcel.face_polygon(face);
+                let bounds = Aabb::from_points(&polygon.vertices)?;
+                Some(LocatorFace {
+                    face,
+                    polygon,
+                    bounds,
+                    signed_area: dcel.faces[face].signed_area,
+                })
+            })
+            .collect();
+        let bounds = faces
+            .iter()
+            .map(|face| face.bounds)
+            .reduce(|a, b| Aabb::new(a.min.inf(&b.min), a.max.sup(&b.max)));
+
+        let size = (crate::math::sqrt(faces.len() as f32) as usize).max(1);
+        let mut locator = Self {
+            faces,
+            bounds,
+            columns: size,
+            rows: size,
+            cells: vec![vec![]; size * size],
+        };
+        for (index, face) in locator.faces.iter().enumerate() {
+            let (min_column, min_row) = locator.cell(&face.bounds.min);
+            let (max_column, max_row) = locator.cell(&face.bounds.max);
+            for row in min_row..=max_row {
+                for column in min_column..=max_column {
+                    locator.cells[row * locator.columns + column].push(index);
+                }
+            }
+        }
+        locator
+    }
+
+    /// The grid cell containing a point, clamped to the grid.
+    fn cell(&self, point: &Point2<f32>) -> (usize, usize) {
+        let Some(bounds) = self.bounds else {
+            return (0, 0);
+        };
+        let index = |value: f32, min: f32, extent: f32, count: usize| {
+            if extent > 0.0 {
+                let t = (value - min) / extent;
+                ((t * count as f32) as usize).min(count - 1)
+            } else {
+                0
+            }
+        };
+        (
+            index(point.x, bounds.min.x, bounds.width(), self.columns),
+            index(point.y, bounds.min.y, bounds.height(), self.rows),
+        )
+    }
+
+    /// Find the innermost bounded face which contains a point.
+    ///
+    /// Returns the face's index in the [`Dcel`] the locator was built from,
+    /// or `None` when the point is outside every bounded face.
+    pub fn locate(&self, point: &Point2<f32>) -> Option<usize> {
+        if !self.bounds?.contains_point(point) {
+            return None;
+        }
+        let (column, row) = self.cell(point);
+        self.cells[row * self.columns + column]
+            .iter()
+            .map(|&index| &self.faces[index])
+            .filter(|face| {
+                face.bounds.contains_point(point)
+                    && face.polygon.contains_point(point)
+            })
+            .min_by(|a, b| a.signed_area.total_cmp(&b.signed_area))
+            .map(|face| face.face)
+    }
+}
+
+/// Order two directions counterclockwise by angle, starting from the positive
+/// x axis. This avoids computing any trig functions.
+fn angular_order(a: &Vector2<f32>, b: &Vector2<f32>) -> Ordering {
+    let half = |v: &Vector2<f32>| !(v.y > 0.0 || (v.y == 0.0 && v.x > 0.0));
+    half(a)
+        .cmp(&half(b))
+        .then_with(|| 0.0.partial_cmp(&a.perp(b)).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{dcel::Dcel, line::Segment},
+        approx::assert_relative_eq,
+        nalgebra::{point, Point2},
+    };
+
+    fn square_segments() -> Vec<Segment> {
+        let (a, b, c, d) = (
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        );
+        vec![
+            Segment::new(a, b),
+            Segment::new(b, c),
+            Segment::new(c, d),
+            Segment::new(d, a),
+        ]
+    }
+
+    fn sorted_areas(dcel: &Dcel) -> Vec<f32> {
+        let mut areas: Vec<f32> =
+            dcel.faces().iter().map(|face| face.signed_area).collect();
+        areas.sort_by(f32::total_cmp);
+        areas
+    }
+
+    #[test]
+    pub fn a_square_with_a_diagonal_should_have_three_faces() {
+        let mut segments = square_segments();
+        segments.push(Segment::new(point![0.0, 0.0], point![1.0, 1.0]));
+
+        let dcel = Dcel::from_segments(&segments, 1e-6);
+
+        assert_eq!(dcel.vertices().len(), 4);
+        assert_eq!(dcel.half_edges().len(), 10);
+        assert_eq!(dcel.faces().len(), 3);
+        let areas = sorted_areas(&dcel);
+        assert_relative_eq!(areas.as_slice(), [-1.0, 0.5, 0.5].as_slice());
+
+        let lower = dcel.locate(&point![0.75, 0.25]).unwrap();
+        let upper = dcel.locate(&point![0.25, 0.75]).unwrap();
+        assert_ne!(lower, upper);
+        assert_eq!(dcel.face_vertices(lower).len(), 3);
+        assert!(dcel.face_polygon(lower).signed_area() > 0.0);
+    }
+
+    #[test]
+    pub fn a_dangling_edge_should_be_part_of_its_face() {
+        let mut segments = square_segments();
+        segments.push(Segment::new(point![0.0, 0.0], point![0.5, 0.5]));
+
+        let dcel = Dcel::from_segments(&segments, 1e-6);
+
+        assert_eq!(dcel.faces().len(), 2);
+        let inner = dcel.locate(&point![0.5, 0.25]).unwrap();
+        assert_relative_eq!(dcel.faces()[inner].signed_area, 1.0);
+
+        // The dangling edge is traversed once in each direction.
+        assert_eq!(dcel.face_vertices(inner).len(), 6);
+        assert_eq!(dcel.neighbors(4).collect::<Vec<usize>>(), vec![0]);
+    }
+
+    #[test]
+    pub fn nearby_endpoints_should_be_welded() {
+        let segments = vec![
+            Segment::new(point![0.0, 0.0], point![1.0, 0.0]),
+            Segment::new(point![1.0001, 0.0], point![0.0, 1.0]),
+            Segment::new(point![0.0, 1.0], point![0.0, 0.0002]),
+        ];
+        let dcel = Dcel::from_segments(&segments, 1e-3);
+        assert_eq!(dcel.vertices().len(), 3);
+        assert_eq!(dcel.bounded_faces().count(), 1);
+    }
+
+    #[test]
+    pub fn duplicate_and_degenerate_segments_should_be_ignored() {
+        let mut segments = square_segments();
+        segments.push(Segment::new(point![1.0, 0.0], point![0.0, 0.0]));
+        segments.push(Segment::new(point![0.5, 0.5], point![0.5, 0.5]));
+        let dcel = Dcel::from_segments(&segments, 1e-6);
+        assert_eq!(dcel.edges().count(), 4);
+        assert_eq!(dcel.faces().len(), 2);
+    }
+
+    #[test]
+    pub fn neighbors_should_be_in_counterclockwise_order() {
+        let center = point![0.0, 0.0];
+        let spokes: Vec<Point2<f32>> = vec![
+            point![0.0, -1.0],
+            point![1.0, 0.0],
+            point![-1.0, 0.0],
+            point![0.0, 1.0],
+        ];
+        let segments: Vec<Segment> =
+            spokes.iter().map(|&p| Segment::new(center, p)).collect();
+        let dcel = Dcel::from_segments(&segments, 1e-6);
+
+        let order: Vec<Point2<f32>> =
+            dcel.neighbors(0).map(|v| dcel.vertices()[v]).collect();
+        assert_eq!(
+            order,
+            vec![
+                point![1.0, 0.0],
+                point![0.0, 1.0],
+                point![-1.0, 0.0],
+                point![0.0, -1.0]
+            ]
+        );
+
+        // A star of spokes has no bounded faces.
+        assert_eq!(dcel.faces().len(), 1);
+        assert_eq!(dcel.bounded_faces().count(), 0);
+    }
+
+    #[test]
+    pub fn nested_components_should_locate_the_innermost_face() {
+        let mut segments = square_segments();
+        let scaled: Vec<Segment> = square_segments()
+            .iter()
+            .map(|s| {
+                Segment::new(
+                    point![0.25, 0.25] + s.start.coords * 0.5,
+                    point![0.25, 0.25] + s.end.coords * 0.5,
+                )
+            })
+            .collect();
+        segments.extend(scaled);
+        let dcel = Dcel::from_segments(&segments, 1e-6);
+
+        let inner = dcel.locate(&point![0.5, 0.5]).unwrap();
+        let outer = dcel.locate(&point![0.1, 0.1]).unwrap();
+        assert_relative_eq!(dcel.faces()[inner].signed_area, 0.25);
+        assert_relative_eq!(dcel.faces()[outer].signed_area, 1.0);
+
+        let locator = dcel.face_locator();
+        assert_eq!(locator.locate(&point![0.5, 0.5]), Some(inner));
+        assert_eq!(locator.locate(&point![0.1, 0.1]), Some(outer));
+    }
+
+    #[test]
+    pub fn face_locators_should_agree_with_a_scan() {
+        // A 6x6 grid of unit squares
+        let mut segments = vec![];
+        for i in 0..=6 {
+            let t = i as f32;
+            segments.push(Segment::new(point![t, 0.0], point![t, 6.0]));
+            segments.push(Segment::new(point![0.0, t], point![6.0, t]));
+        }
+        let planar = crate::snap::snap_round(&segments, 1e-3);
+        let dcel = Dcel::from_segments(&planar, 1e-6);
+        assert_eq!(dcel.bounded_faces().count(), 36);
+
+        let locator = dcel.face_locator();
+
+        for i in 0..=70 {
+            for j in 0..=70 {
+                let p = point![i as f32 * 0.1 - 0.5, j as f32 * 0.1 - 0.5];
+                assert_eq!(locator.locate(&p), dcel.locate(&p), "{p}");
+            }
+        }
+    }
+
+    #[test]
+    pub fn face_locators_should_handle_empty_subdivisions() {
+        let dcel = Dcel::from_segments(&[], 1e-6);
+
+        assert_eq!(dcel.face_locator().locate(&Point2::origin()), None);
+    }
+}
@@ -0,0 +1,181 @@
+//! Helpers for consistently formatting numbers and points.
+//!
+//! Every `Display` implementation in the crate writes numbers through these
+//! helpers so they all honor the formatter's precision (as in `{:.3}`) and
+//! never print negative zero.
+
+use {core::fmt, nalgebra::Point2};
+
+/// Write a number using the formatter's precision, if it has one.
+///
+/// Values which would be displayed as zero, including negative zero and
+/// small negative values which round to zero at the requested precision, are
+/// written as a positive zero.
+pub(crate) fn write_scalar(f: &mut fmt::Formatter<'_>, x: f32) -> fmt::Result {
+    let x = if rounds_to_zero(x, f.precision()) {
+        0.0
+    } else {
+        x
+    };
+    match f.precision() {
+        Some(precision) => write!(f, "{:.*}", precision, x),
+        None => write!(f, "{}", x),
+    }
+}
+
+/// Write a point as `(x, y)`.
+pub(crate) fn write_point(
+    f: &mut fmt::Formatter<'_>,
+    point: &Point2<f32>,
+) -> fmt::Result {
+    f.write_str("(")?;
+    write_scalar(f, point.x)?;
+    f.write_str(", ")?;
+    write_scalar(f, point.y)?;
+    f.write_str(")")
+}
+
+/// Write a sequence of points separated by `separator`.
+#[cfg(feature = "alloc")]
+pub(crate) fn write_points<'a>(
+    f: &mut fmt::Formatter<'_>,
+    points: impl IntoIterator<Item = &'a Point2<f32>>,
+    separator: &str,
+) -> fmt::Result {
+    for (i, point) in points.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        write_point(f, point)?;
+    }
+    Ok(())
+}
+
+/// Write a term in a sum, like ` + 3y` or ` - 3y`.
+///
+/// The first term is written without a leading operator.
+pub(crate) fn write_term(
+    f: &mut fmt::Formatter<'_>,
+    coefficient: f32,
+    variable: &str,
+    first: bool,
+) -> fmt::Result {
+    let negative =
+        coefficient < 0.0 && !rounds_to_zero(coefficient, f.precision());
+    match (first, negative) {
+        (true, true) => f.write_str("-")?,
+        (true, false) => {}
+        (false, true) => f.write_str(" - ")?,
+        (false, false) => f.write_str(" + ")?,
+    }
+    write_scalar(f, coefficient.abs())?;
+    f.write_str(variable)
+}
+
+/// Write a compact `Debug` representation like `Name(<display>)`.
+///
+/// This is used when the alternate flag isn't set. The formatter is passed
+/// along so flags like precision still apply.
+pub(crate) fn write_compact_debug(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    value: &impl fmt::Display,
+) -> fmt::Result {
+    f.write_str(name)?;
+    f.write_str("(")?;
+    value.fmt(f)?;
+    f.write_str(")")
+}
+
+/// Returns true when the value is zero at the given number of decimal places.
+fn rounds_to_zero(x: f32, precision: Option<usize>) -> bool {
+    let Some(precision) = precision else {
+        return x == 0.0;
+    };
+    let mut threshold = 0.5;
+    for _ in 0..precision {
+        threshold /= 10.0;
+    }
+    x.abs() < threshold
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{Line, Ray, Segment},
+            polygon::{Polygon, Polyline},
+            shape::{Aabb, Circle},
+        },
+        nalgebra::{point, vector, Unit},
+    };
+
+    #[test]
+    pub fn segments_should_display_as_arrows() {
+        let segment = Segment::new(point![1.0, 3.0], point![7.0, 3.0]);
+
+        assert_eq!(format!("{}", segment), "(1, 3) -> (7, 3)");
+        assert_eq!(format!("{:.2}", segment), "(1.00, 3.00) -> (7.00, 3.00)");
+        assert_eq!(format!("{:?}", segment), "Segment((1, 3) -> (7, 3))");
+    }
+
+    #[test]
+    pub fn rays_should_display_parametrically() {
+        let ray =
+            Ray::new(point![2.0, 2.0], Unit::new_normalize(vector![0.0, 1.0]));
+
+        assert_eq!(format!("{}", ray), "(2, 2) + t*(0, 1)");
+        assert_eq!(format!("{:?}", ray), "Ray((2, 2) + t*(0, 1))");
+    }
+
+    #[test]
+    pub fn lines_should_display_as_implicit_equations() {
+        let ray =
+            Ray::new(point![0.0, 3.0], Unit::new_normalize(vector![1.0, 0.0]));
+        let line = Line::from(ray);
+
+        // the normal is (-0, 1), which should not print a negative zero
+        assert_eq!(format!("{}", line), "0x + 1y - 3 = 0");
+        assert_eq!(format!("{:?}", line), "Line(0x + 1y - 3 = 0)");
+
+        let line = Line::new(Unit::new_normalize(vector![-3.0, 4.0]), 0.5);
+        assert_eq!(format!("{}", line), "-0.6x + 0.8y + 0.5 = 0");
+        assert_eq!(format!("{:.3}", line), "-0.600x + 0.800y + 0.500 = 0");
+    }
+
+    #[test]
+    pub fn precision_should_hide_tiny_negative_components() {
+        let segment = Segment::new(point![-1e-7, 0.25], point![-0.0, -2.5]);
+
+        assert_eq!(format!("{:.1}", segment), "(0.0, 0.2) -> (0.0, -2.5)");
+        assert_eq!(format!("{}", segment), "(-0.0000001, 0.25) -> (0, -2.5)");
+
+        let line = Line::new(Unit::new_normalize(vector![1.0, -1e-9]), -1e-9);
+        assert_eq!(format!("{:.2}", line), "1.00x + 0.00y + 0.00 = 0");
+    }
+
+    #[test]
+    pub fn shapes_should_display_their_defining_values() {
+        let circle = Circle::new(point![1.0, -2.0], 3.5);
+        assert_eq!(format!("{}", circle), "|p - (1, -2)| = 3.5");
+        assert_eq!(format!("{:?}", circle), "Circle(|p - (1, -2)| = 3.5)");
+
+        let aabb = Aabb::new(point![1.0, 2.0], point![3.0, 4.0]);
+        assert_eq!(format!("{:.1}", aabb), "[1.0, 3.0] x [2.0, 4.0]");
+        assert_eq!(format!("{:?}", aabb), "Aabb([1, 3] x [2, 4])");
+
+        let vertices =
+            vec![point![0.0, 0.0], point![1.0, 0.0], point![0.0, 1.0]];
+        let polygon = Polygon::new(vertices.clone());
+        assert_eq!(format!("{}", polygon), "[(0, 0), (1, 0), (0, 1)]");
+        let polyline = Polyline::new(vertices);
+        assert_eq!(format!("{}", polyline), "(0, 0) -> (1, 0) -> (0, 1)");
+    }
+
+    #[test]
+    pub fn alternate_debug_should_show_every_field() {
+        let segment = Segment::new(point![1.0, 3.0], point![7.0, 3.0]);
+
+        assert!(format!("{:#?}", segment).contains("start"));
+    }
+}
@@ -0,0 +1,244 @@
+//! The crate's error type.
+
+use core::fmt;
+
+/// The ways geometry can fail validation.
+///
+/// Most types have an unchecked `new` constructor which accepts any input,
+/// and a `try_new` constructor which validates its input and returns one of
+/// these errors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A segment's start and end are the same point, so it has no direction.
+    DegenerateSegment,
+
+    /// A triangle's corners are collinear, so it has no area.
+    DegenerateTriangle,
+
+    /// A direction or normal vector has zero length.
+    ZeroVector,
+
+    /// A shape has fewer vertices than it needs. The value is the minimum
+    /// number of vertices required.
+    TooFewVertices(usize),
+
+    /// Two shapes were expected to have the same number of vertices.
+    MismatchedVertexCounts,
+
+    /// A circle's radius is negative.
+    NegativeRadius,
+
+    /// A hole in a region isn't strictly inside the region's outer
+    /// boundary.
+    HoleOutsideBoundary,
+
+    /// Two holes in a region overlap or touch.
+    OverlappingHoles,
+
+    /// A polygon's boundary crosses or touches itself.
+    NotSimple,
+
+    /// A coordinate or parameter is infinite or NaN.
+    NotFinite,
+
+    /// A flat list of interleaved coordinates has an odd length, so its
+    /// last x coordinate has no y.
+    OddCoordinateCount,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DegenerateSegment => {
+                f.write_str("segment start and end are the same point")
+            }
+            Error::DegenerateTriangle => {
+                f.write_str("triangle corners are collinear")
+            }
+            Error::ZeroVector => f.write_str("vector has zero length"),
+            Error::TooFewVertices(minimum) => {
+                write!(f, "shape needs at least {} vertices", minimum)
+            }
+            Error::MismatchedVertexCounts => {
+                f.write_str("shapes have different numbers of vertices")
+            }
+            Error::NegativeRadius => f.write_str("radius is negative"),
+            Error::HoleOutsideBoundary => {
+                f.write_str("hole is not inside the outer boundary")
+            }
+            Error::OverlappingHoles => f.write_str("holes overlap"),
+            Error::NotSimple => {
+                f.write_str("polygon boundary crosses or touches itself")
+            }
+            Error::NotFinite => f.write_str("value is infinite or NaN"),
+            Error::OddCoordinateCount => {
+                f.write_str("flat coordinates have an odd length")
+            }
+        }
+    }
+}
+
+/// `core::error::Error` is the same trait as `std::error::Error`, so this
+/// works with and without the standard library.
+impl core::error::Error for Error {}
+
+/// Return [`Error::NotFinite`] unless every value is finite.
+pub(crate) fn check_finite(values: &[f32]) -> Result<(), Error> {
+    if values.iter().all(|value| value.is_finite()) {
+        Ok(())
+    } else {
+        Err(Error::NotFinite)
+    }
+}
+
+/// Panic in debug builds when any value is infinite or NaN.
+///
+/// This catches NaN leaking out of intersection math close to where it's
+/// produced, instead of wherever it eventually causes trouble.
+#[inline]
+#[track_caller]
+pub(crate) fn debug_assert_finite(values: &[f32]) {
+    debug_assert!(
+        values.iter().all(|value| value.is_finite()),
+        "expected finite values but found {:?}",
+        values
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            interval::Interval,
+            line::{Line, Ray, Segment},
+            polygon::{Polygon, Polyline},
+            shape::{Aabb, Circle, Wedge},
+            Error,
+        },
+        nalgebra::{point, vector},
+    };
+
+    const NAN: f32 = f32::NAN;
+    const INF: f32 = f32::INFINITY;
+
+    #[test]
+    pub fn segments_should_reject_bad_inputs() {
+        let p = point![1.0, 2.0];
+
+        assert_eq!(Segment::try_new(p, p), Err(Error::DegenerateSegment));
+        assert_eq!(
+            Segment::try_new(p, point![NAN, 0.0]),
+            Err(Error::NotFinite)
+        );
+        assert_eq!(
+            Segment::try_new(point![0.0, INF], p),
+            Err(Error::NotFinite)
+        );
+        assert!(Segment::try_new(p, point![0.0, 0.0]).is_ok());
+    }
+
+    #[test]
+    pub fn rays_should_reject_bad_inputs() {
+        let p = point![1.0, 2.0];
+
+        assert_eq!(Ray::try_new(p, vector![0.0, 0.0]), Err(Error::ZeroVector));
+        assert_eq!(Ray::try_new(p, vector![INF, 0.0]), Err(Error::NotFinite));
+        assert_eq!(
+            Ray::try_new(point![NAN, 0.0], vector![1.0, 0.0]),
+            Err(Error::NotFinite)
+        );
+        assert_eq!(
+            Ray::try_from_segment(&Segment::new(p, p)),
+            Err(Error::DegenerateSegment)
+        );
+        let ray = Ray::try_new(p, vector![0.0, 2.0]).unwrap();
+        assert_eq!(ray.direction.into_inner(), vector![0.0, 1.0]);
+    }
+
+    #[test]
+    pub fn lines_should_reject_bad_inputs() {
+        assert_eq!(
+            Line::try_new(vector![0.0, 0.0], 1.0),
+            Err(Error::ZeroVector)
+        );
+        assert_eq!(
+            Line::try_new(vector![1.0, 0.0], NAN),
+            Err(Error::NotFinite)
+        );
+        assert_eq!(
+            Line::try_new(vector![INF, 1.0], 0.0),
+            Err(Error::NotFinite)
+        );
+    }
+
+    #[test]
+    pub fn polygons_and_polylines_should_reject_bad_inputs() {
+        let (a, b, c) = (point![0.0, 0.0], point![1.0, 0.0], point![0.0, 1.0]);
+
+        assert_eq!(Polygon::try_new(vec![a, b]), Err(Error::TooFewVertices(3)));
+        assert_eq!(
+            Polygon::try_new(vec![a, b, point![NAN, 1.0]]),
+            Err(Error::NotFinite)
+        );
+        assert!(Polygon::try_new(vec![a, b, c]).is_ok());
+
+        assert_eq!(Polyline::try_new(vec![a]), Err(Error::TooFewVertices(2)));
+        assert_eq!(
+            Polyline::try_new(vec![a, point![1.0, INF]]),
+            Err(Error::NotFinite)
+        );
+        assert!(Polyline::try_new(vec![a, b]).is_ok());
+    }
+
+    #[test]
+    pub fn shapes_should_reject_bad_inputs() {
+        let p = point![1.0, 2.0];
+
+        assert_eq!(Circle::try_new(p, -0.5), Err(Error::NegativeRadius));
+        assert_eq!(Circle::try_new(p, NAN), Err(Error::NotFinite));
+        assert!(Circle::try_new(p, 0.0).is_ok());
+
+        assert_eq!(Aabb::try_new(p, point![INF, 0.0]), Err(Error::NotFinite));
+        assert!(Aabb::try_new(p, point![0.0, 0.0]).is_ok());
+
+        assert_eq!(
+            Wedge::try_new(p, vector![0.0, 0.0], vector![1.0, 0.0]),
+            Err(Error::ZeroVector)
+        );
+        assert_eq!(
+            Wedge::try_new(p, vector![1.0, 0.0], vector![NAN, 0.0]),
+            Err(Error::NotFinite)
+        );
+
+        assert_eq!(Interval::try_new(0.0, INF), Err(Error::NotFinite));
+        assert_eq!(Interval::try_new(2.0, 1.0), Ok(Interval::new(1.0, 2.0)));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "expected finite values")]
+    pub fn intersection_math_should_catch_nan_in_debug_builds() {
+        use crate::line::intersection::intersect_segments;
+
+        intersect_segments(
+            &Segment::new(point![0.0, 0.0], point![NAN, 1.0]),
+            &Segment::new(point![0.0, 1.0], point![1.0, 0.0]),
+        );
+    }
+
+    #[test]
+    pub fn errors_should_have_readable_messages() {
+        assert_eq!(
+            format!("{}", Error::TooFewVertices(3)),
+            "shape needs at least 3 vertices"
+        );
+        assert_eq!(format!("{}", Error::NotFinite), "value is infinite or NaN");
+    }
+
+    #[test]
+    pub fn errors_should_work_as_boxed_std_errors() {
+        let error: Box<dyn std::error::Error> = Error::ZeroVector.into();
+        assert_eq!(error.to_string(), "vector has zero length");
+    }
+}
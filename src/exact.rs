@@ -0,0 +1,506 @@
+//! Exact segment intersection using integer coordinates.
+//!
+//! Floating point intersection tests are unstable near degeneracies: nearly
+//! parallel segments can be misclassified as collinear, and touching
+//! segments can be reported as crossing or missing depending on rounding.
+//! The types in this module store coordinates as integers and classify
+//! intersections using exact 128-bit intermediate arithmetic, so the answers
+//! are always correct and always consistent.
+//!
+//! Floating point geometry is converted to integer coordinates by multiplying
+//! by a scale factor and rounding. For example, a scale of `1000.0` snaps
+//! every coordinate to a grid with a spacing of `0.001`.
+//!
+//! # Coordinate Limits
+//!
+//! Every coordinate must have a magnitude of at most [`MAX_COORDINATE`]
+//! (2^40). This guarantees that no intermediate computation overflows an
+//! `i128`.
+
+use {
+    crate::{line::Segment, math},
+    nalgebra::{point, Point2},
+};
+
+/// The largest magnitude allowed for any integer coordinate.
+pub const MAX_COORDINATE: i64 = 1 << 40;
+
+/// A line segment with integer coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ISegment {
+    pub start: Point2<i64>,
+    pub end: Point2<i64>,
+}
+
+impl ISegment {
+    /// Create a new integer segment defined by a start and end point.
+    ///
+    /// Coordinates must be within [`MAX_COORDINATE`] of zero.
+    pub fn new(start: Point2<i64>, end: Point2<i64>) -> Self {
+        debug_assert!(
+            [start.x, start.y, end.x, end.y]
+                .iter()
+                .all(|c| c.abs() <= MAX_COORDINATE),
+            "integer segment coordinates must not exceed MAX_COORDINATE"
+        );
+        Self { start, end }
+    }
+
+    /// Convert a floating point segment into integer coordinates.
+    ///
+    /// Each coordinate is multiplied by `scale` and then rounded to the
+    /// nearest integer, with half-way cases rounded away from zero.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{exact::ISegment, line::Segment},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let segment = Segment::new(point![0.25, -1.5], point![3.0, 0.1]);
+    ///     let isegment = ISegment::from_segment(&segment, 10.0);
+    ///
+    ///     assert_eq!(isegment.start, point![3, -15]);
+    ///     assert_eq!(isegment.end, point![30, 1]);
+    ///
+    pub fn from_segment(segment: &Segment, scale: f32) -> Self {
+        Self::new(
+            to_ipoint(&segment.start, scale),
+            to_ipoint(&segment.end, scale),
+        )
+    }
+
+    /// Convert this segment back to floating point coordinates by dividing
+    /// every coordinate by `scale`.
+    pub fn to_segment(&self, scale: f32) -> Segment {
+        Segment::new(to_point(&self.start, scale), to_point(&self.end, scale))
+    }
+
+    /// Returns true when the segment's start and end points are the same.
+    pub fn is_degenerate(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// A point with rational coordinates `(x / denominator, y / denominator)`.
+///
+/// Rational points are always stored in lowest terms with a positive
+/// denominator, so two rational points are equal if and only if they
+/// represent the same location.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RationalPoint {
+    pub x: i128,
+    pub y: i128,
+    pub denominator: i128,
+}
+
+impl RationalPoint {
+    /// Create a new rational point, reducing it to lowest terms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the denominator is zero.
+    pub fn new(x: i128, y: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "denominator must not be zero");
+        let sign = denominator.signum();
+        let divisor = gcd(gcd(x, y), denominator).max(1);
+        Self {
+            x: sign * x / divisor,
+            y: sign * y / divisor,
+            denominator: sign * denominator / divisor,
+        }
+    }
+
+    /// Returns true when both coordinates are integers.
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// Round to the nearest integer point.
+    ///
+    /// Each coordinate is rounded to the nearest integer. Coordinates exactly
+    /// half-way between two integers are rounded towards positive infinity.
+    ///
+    /// # Example
+    ///
+    ///     use ::{compgeo::exact::RationalPoint, nalgebra::point};
+    ///
+    ///     let p = RationalPoint::new(5, -5, 2);
+    ///     assert_eq!(p.round(), point![3, -2]);
+    ///
+    pub fn round(&self) -> Point2<i64> {
+        let round = |numerator: i128| {
+            let quotient = numerator.div_euclid(self.denominator);
+            let remainder = numerator.rem_euclid(self.denominator);
+            if 2 * remainder >= self.denominator {
+                (quotient + 1) as i64
+            } else {
+                quotient as i64
+            }
+        };
+        point![round(self.x), round(self.y)]
+    }
+
+    /// Convert to floating point coordinates by dividing by `scale`.
+    pub fn to_point(&self, scale: f32) -> Point2<f32> {
+        let denominator = self.denominator as f64 * scale as f64;
+        point![
+            (self.x as f64 / denominator) as f32,
+            (self.y as f64 / denominator) as f32
+        ]
+    }
+}
+
+impl From<Point2<i64>> for RationalPoint {
+    fn from(point: Point2<i64>) -> Self {
+        Self {
+            x: point.x as i128,
+            y: point.y as i128,
+            denominator: 1,
+        }
+    }
+}
+
+/// This type represents the exact intersection between two integer segments.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExactIntersection {
+    /// The segments do not intersect.
+    None,
+
+    /// The segments cross at a single point which is in the interior of both
+    /// segments.
+    Crossing(RationalPoint),
+
+    /// The segments touch at a single point which is an endpoint of at least
+    /// one of the segments. The point always has integer coordinates.
+    Touching(Point2<i64>),
+
+    /// The segments are collinear and overlap in a segment with nonzero
+    /// length. The overlap points in the same direction as the first segment
+    /// (or the second segment, when the first is just a point).
+    Overlap(ISegment),
+}
+
+/// Compute the exact intersection between two integer segments.
+///
+/// The classification is exact and symmetric: swapping the arguments always
+/// produces the same kind of intersection at the same location.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::exact::{
+///             intersect_isegments, ExactIntersection, ISegment,
+///             RationalPoint,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let a = ISegment::new(point![0, 0], point![3, 1]);
+///     let b = ISegment::new(point![0, 1], point![3, 0]);
+///
+///     assert_eq!(
+///         intersect_isegments(&a, &b),
+///         ExactIntersection::Crossing(RationalPoint::new(3, 1, 2))
+///     );
+///
+pub fn intersect_isegments(a: &ISegment, b: &ISegment) -> ExactIntersection {
+    let o1 = orientation(&a.start, &a.end, &b.start);
+    let o2 = orientation(&a.start, &a.end, &b.end);
+    let o3 = orientation(&b.start, &b.end, &a.start);
+    let o4 = orientation(&b.start, &b.end, &a.end);
+
+    if o1 == 0 && o2 == 0 && o3 == 0 && o4 == 0 {
+        return intersect_collinear(a, b);
+    }
+
+    if o1 * o2 > 0 || o3 * o4 > 0 {
+        // One segment lies entirely on one side of the other.
+        return ExactIntersection::None;
+    }
+
+    // The segments intersect at exactly one point. When an endpoint lies on
+    // the other segment's line, that endpoint is the intersection.
+    if o1 == 0 {
+        return ExactIntersection::Touching(b.start);
+    }
+    if o2 == 0 {
+        return ExactIntersection::Touching(b.end);
+    }
+    if o3 == 0 {
+        return ExactIntersection::Touching(a.start);
+    }
+    if o4 == 0 {
+        return ExactIntersection::Touching(a.end);
+    }
+
+    let dir_a = delta(&a.start, &a.end);
+    let dir_b = delta(&b.start, &b.end);
+    let w = delta(&a.start, &b.start);
+    let denominator = cross(dir_a, dir_b);
+    let s = cross(w, dir_b);
+    ExactIntersection::Crossing(RationalPoint::new(
+        a.start.x as i128 * denominator + dir_a.0 * s,
+        a.start.y as i128 * denominator + dir_a.1 * s,
+        denominator,
+    ))
+}
+
+/// Intersect two segments which lie on the same line.
+fn intersect_collinear(a: &ISegment, b: &ISegment) -> ExactIntersection {
+    let direction = if a.is_degenerate() {
+        delta(&b.start, &b.end)
+    } else {
+        delta(&a.start, &a.end)
+    };
+
+    if direction == (0, 0) {
+        // Both segments are just points.
+        return if a.start == b.start {
+            ExactIntersection::Touching(a.start)
+        } else {
+            ExactIntersection::None
+        };
+    }
+
+    // Order the endpoints by their exact position along the shared line.
+    let key = |p: &Point2<i64>| dot(delta(&a.start, p), direction);
+    let ordered = |s: &ISegment| {
+        if key(&s.start) <= key(&s.end) {
+            (s.start, s.end)
+        } else {
+            (s.end, s.start)
+        }
+    };
+    let (a_min, a_max) = ordered(a);
+    let (b_min, b_max) = ordered(b);
+
+    let lo = if key(&a_min) >= key(&b_min) {
+        a_min
+    } else {
+        b_min
+    };
+    let hi = if key(&a_max) <= key(&b_max) {
+        a_max
+    } else {
+        b_max
+    };
+
+    match key(&lo).cmp(&key(&hi)) {
+        core::cmp::Ordering::Greater => ExactIntersection::None,
+        core::cmp::Ordering::Equal => ExactIntersection::Touching(lo),
+        core::cmp::Ordering::Less => {
+            ExactIntersection::Overlap(ISegment::new(lo, hi))
+        }
+    }
+}
+
+/// The sign of the turn `a -> b -> c`: positive for counterclockwise,
+/// negative for clockwise, and zero when the points are collinear.
+fn orientation(a: &Point2<i64>, b: &Point2<i64>, c: &Point2<i64>) -> i128 {
+    cross(delta(a, b), delta(a, c)).signum()
+}
+
+/// The exact vector from `a` to `b`.
+fn delta(a: &Point2<i64>, b: &Point2<i64>) -> (i128, i128) {
+    (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128)
+}
+
+fn cross(a: (i128, i128), b: (i128, i128)) -> i128 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn dot(a: (i128, i128), b: (i128, i128)) -> i128 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+/// The greatest common divisor of the magnitudes of two numbers.
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn to_ipoint(point: &Point2<f32>, scale: f32) -> Point2<i64> {
+    let convert = |c: f32| math::round(c as f64 * scale as f64) as i64;
+    point![convert(point.x), convert(point.y)]
+}
+
+fn to_point(point: &Point2<i64>, scale: f32) -> Point2<f32> {
+    let scale = scale as f64;
+    point![
+        (point.x as f64 / scale) as f32,
+        (point.y as f64 / scale) as f32
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            exact::{
+                intersect_isegments, ExactIntersection, ISegment, RationalPoint,
+            },
+            line::{
+                intersection::{intersect_segments, SegmentIntersection},
+                Segment,
+            },
+        },
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    fn iseg(a: (i64, i64), b: (i64, i64)) -> ISegment {
+        ISegment::new(point![a.0, a.1], point![b.0, b.1])
+    }
+
+    #[test]
+    pub fn nearly_parallel_long_segments_should_be_classified_exactly() {
+        // These segments cross at (1e7, 0.5), but they are so long and so
+        // close to parallel that the f32 implementation thinks they're
+        // collinear.
+        let a = iseg((0, 0), (20_000_000, 1));
+        let b = iseg((0, 1), (20_000_000, 0));
+        assert!(matches!(
+            intersect_segments(&a.to_segment(1.0), &b.to_segment(1.0)),
+            SegmentIntersection::Overlap(_)
+        ));
+
+        let expected =
+            ExactIntersection::Crossing(RationalPoint::new(20_000_000, 1, 2));
+        assert_eq!(intersect_isegments(&a, &b), expected);
+        assert_eq!(intersect_isegments(&b, &a), expected);
+    }
+
+    #[test]
+    pub fn nearly_parallel_disjoint_segments_should_not_intersect() {
+        let a = iseg((0, 0), (20_000_000, 1));
+        let b = iseg((0, 1), (20_000_000, 2));
+        assert_eq!(intersect_isegments(&a, &b), ExactIntersection::None);
+    }
+
+    #[test]
+    pub fn segments_sharing_an_endpoint_should_touch() {
+        let a = iseg((0, 0), (5, 5));
+        let b = iseg((5, 5), (10, 0));
+        assert_eq!(
+            intersect_isegments(&a, &b),
+            ExactIntersection::Touching(point![5, 5])
+        );
+    }
+
+    #[test]
+    pub fn an_endpoint_in_the_interior_should_touch() {
+        let a = iseg((0, 0), (10, 0));
+        let b = iseg((4, 0), (4, 7));
+        assert_eq!(
+            intersect_isegments(&a, &b),
+            ExactIntersection::Touching(point![4, 0])
+        );
+        assert_eq!(
+            intersect_isegments(&b, &a),
+            ExactIntersection::Touching(point![4, 0])
+        );
+    }
+
+    #[test]
+    pub fn collinear_segments_should_overlap() {
+        let a = iseg((0, 0), (10, 10));
+        let b = iseg((15, 15), (5, 5));
+        assert_eq!(
+            intersect_isegments(&a, &b),
+            ExactIntersection::Overlap(iseg((5, 5), (10, 10)))
+        );
+    }
+
+    #[test]
+    pub fn collinear_segments_meeting_at_a_point_should_touch() {
+        let a = iseg((0, 0), (3, 0));
+        let b = iseg((3, 0), (7, 0));
+        assert_eq!(
+            intersect_isegments(&a, &b),
+            ExactIntersection::Touching(point![3, 0])
+        );
+    }
+
+    #[test]
+    pub fn collinear_separate_segments_should_not_intersect() {
+        let a = iseg((0, 0), (3, 0));
+        let b = iseg((4, 0), (7, 0));
+        assert_eq!(intersect_isegments(&a, &b), ExactIntersection::None);
+    }
+
+    #[test]
+    pub fn degenerate_segments_should_intersect_as_points() {
+        let p = iseg((2, 2), (2, 2));
+        assert_eq!(
+            intersect_isegments(&p, &iseg((0, 0), (4, 4))),
+            ExactIntersection::Touching(point![2, 2])
+        );
+        assert_eq!(
+            intersect_isegments(&p, &iseg((0, 1), (4, 4))),
+            ExactIntersection::None
+        );
+        assert_eq!(
+            intersect_isegments(&p, &p),
+            ExactIntersection::Touching(point![2, 2])
+        );
+    }
+
+    #[test]
+    pub fn rational_points_should_be_reduced_with_a_positive_denominator() {
+        let p = RationalPoint::new(4, -6, -2);
+        assert_eq!(p, RationalPoint::new(-2, 3, 1));
+        assert!(p.is_integer());
+        assert_eq!(p.to_point(2.0), point![-1.0, 1.5]);
+    }
+
+    #[test]
+    pub fn rational_points_should_round_half_towards_positive_infinity() {
+        assert_eq!(RationalPoint::new(1, -1, 2).round(), point![1, 0]);
+        assert_eq!(RationalPoint::new(7, -7, 3).round(), point![2, -2]);
+        assert_eq!(RationalPoint::new(8, -8, 3).round(), point![3, -3]);
+    }
+
+    #[test]
+    pub fn segments_should_round_trip_through_integer_coordinates() {
+        let segment = Segment::new(point![1.5, -2.25], point![100.0, 0.125]);
+        let isegment = ISegment::from_segment(&segment, 8.0);
+        assert_eq!(isegment.start, point![12, -18]);
+        assert_eq!(isegment.to_segment(8.0), segment);
+    }
+
+    fn ipoint() -> impl Strategy<Value = Point2<i64>> {
+        // A small range makes degenerate configurations likely.
+        (-8i64..=8, -8i64..=8).prop_map(|(x, y)| point![x, y])
+    }
+
+    fn isegment() -> impl Strategy<Value = ISegment> {
+        (ipoint(), ipoint()).prop_map(|(a, b)| ISegment::new(a, b))
+    }
+
+    proptest! {
+        #[test]
+        fn exact_intersection_should_be_symmetric(
+            a in isegment(),
+            b in isegment(),
+        ) {
+            let ab = intersect_isegments(&a, &b);
+            let ba = intersect_isegments(&b, &a);
+            match (ab, ba) {
+                (
+                    ExactIntersection::Overlap(s),
+                    ExactIntersection::Overlap(t),
+                ) => {
+                    prop_assert!(
+                        s == t || (s.start == t.end && s.end == t.start)
+                    );
+                }
+                _ => prop_assert_eq!(ab, ba),
+            }
+        }
+    }
+}
@@ -0,0 +1,1411 @@
+//! Scalar fields sampled on a grid, like the density of a cloud of points,
+//! and streamlines traced through vector fields.
+//!
+//! Scalar field values are samples at the centers of the cells of a
+//! [`Grid`](crate::raster::Grid), and are interpolated bilinearly between
+//! the centers. Outside of the centers, the field keeps the value of the
+//! nearest edge of the grid.
+//!
+//! Vector fields are any function from a point to a vector, like the
+//! gradient of a [`DensityGrid`] or the field along a drawing from
+//! [`field_from_segments`]. They can be followed one streamline at a time
+//! with [`trace_streamline`], or covered with evenly spaced streamlines by
+//! [`streamlines_even`].
+//!
+//! Curves where a function is zero, like the outline of a blend of signed
+//! distances, can be traced directly with [`trace_zero_set`], without
+//! sampling the function on a grid.
+
+use {
+    crate::{
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            DistanceToPoint, Segment,
+        },
+        math,
+        polygon::Polyline,
+        raster::Grid,
+        shape::Aabb,
+        spatial::{SegmentId, SegmentIndex},
+    },
+    alloc::{collections::BTreeMap, vec::Vec},
+    core::iter,
+    nalgebra::{Point2, Vector2},
+};
+
+/// A smoothed density of points, for driving art by where points are dense.
+///
+/// Each point is spread over the cells near it with a smooth bump which
+/// falls to zero at the kernel radius, so the density is the number of
+/// points per unit area.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityGrid {
+    /// The density at the center of each cell.
+    pub cells: Grid<f32>,
+}
+
+impl DensityGrid {
+    /// Accumulate the density of points over a grid of `(cols, rows)`
+    /// cells which divide up `bounds`.
+    ///
+    /// Each point is spread with the cubic kernel `(1 - d²/r²)³`, where `d`
+    /// is the distance from the point and `r` is the kernel radius. The
+    /// kernel is normalized over the cell centers it reaches, so every
+    /// point adds exactly one to the sum of the density times the cell area
+    /// across an unbounded grid. Kernels narrower than a cell could fall
+    /// between cell centers and vanish, so the radius is widened to at
+    /// least the width and height of a cell. Points outside the bounds only
+    /// add the part of their kernel which reaches inside, and points which
+    /// aren't finite are skipped. Takes O(n r²) time for n points.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{field::DensityGrid, shape::Aabb},
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+    ///     let points = [point![3.0, 3.0], point![7.0, 6.0]];
+    ///
+    ///     let density =
+    ///         DensityGrid::from_points(&points, &bounds, (50, 50), 1.5);
+    ///
+    ///     // Each cell is 0.2 by 0.2.
+    ///     let total: f32 = density.cells.values.iter().sum();
+    ///     assert_relative_eq!(total * 0.04, 2.0, epsilon = 1e-4);
+    ///     let (dense, sparse) = (point![3.0, 3.0], point![5.0, 5.0]);
+    ///     assert!(density.sample(&dense) > density.sample(&sparse));
+    ///
+    pub fn from_points(
+        points: &[Point2<f32>],
+        bounds: &Aabb,
+        resolution: (usize, usize),
+        kernel_radius: f32,
+    ) -> Self {
+        let (cols, rows) = resolution;
+        let mut cells = Grid {
+            bounds: *bounds,
+            cols,
+            rows,
+            values: vec![0.0; cols * rows],
+        };
+        let (width, height) = cell_size(&cells);
+        if !(width > 0.0 && height > 0.0) {
+            return Self { cells };
+        }
+        let radius = kernel_radius.max(width).max(height);
+        let cell_area = width * height;
+
+        // The cells whose centers are within the radius of a coordinate.
+        let reach = |value: f32, min: f32, size: f32| {
+            let first = math::ceil((value - radius - min) / size - 0.5) as i64;
+            let last = math::floor(((value + radius - min) / size - 0.5) as f64)
+                as i64;
+            first..=last
+        };
+        let mut weights = Vec::new();
+        for point in points {
+            if !(point.x.is_finite() && point.y.is_finite()) {
+                continue;
+            }
+            let (xs, ys) = (
+                reach(point.x, bounds.min.x, width),
+                reach(point.y, bounds.min.y, height),
+            );
+            let overlaps = |range: &core::ops::RangeInclusive<i64>, count| {
+                *range.end() >= 0 && *range.start() < count as i64
+            };
+            if !overlaps(&xs, cols) || !overlaps(&ys, rows) {
+                continue;
+            }
+            weights.clear();
+            let mut total = 0.0;
+            for row in ys {
+                for col in xs.clone() {
+                    let center = center(&cells, col as f32, row as f32);
+                    let d = (center - point).norm_squared() / (radius * radius);
+                    if d >= 1.0 {
+                        continue;
+                    }
+                    let weight = (1.0 - d) * (1.0 - d) * (1.0 - d);
+                    total += weight;
+                    let inside = (0..cols as i64).contains(&col)
+                        && (0..rows as i64).contains(&row);
+                    if inside {
+                        weights
+                            .push((row as usize * cols + col as usize, weight));
+                    }
+                }
+            }
+            for &(index, weight) in &weights {
+                cells.values[index] += weight / (total * cell_area);
+            }
+        }
+        Self { cells }
+    }
+
+    /// The density at a point, interpolated bilinearly between the cell
+    /// centers around it.
+    ///
+    /// Points beyond the outermost cell centers get the density at the
+    /// nearest point on the edge of the centers. An empty grid has zero
+    /// density everywhere.
+    pub fn sample(&self, p: &Point2<f32>) -> f32 {
+        let grid = &self.cells;
+        if grid.cols == 0 || grid.rows == 0 {
+            return 0.0;
+        }
+        let (width, height) = cell_size(grid);
+        let locate = |value: f32, min: f32, size: f32, count: usize| {
+            let f = ((value - min) / size - 0.5).clamp(0.0, (count - 1) as f32);
+            let f = if f.is_nan() { 0.0 } else { f };
+            let low = (math::floor(f as f64) as usize).min(count - 1);
+            (low, (low + 1).min(count - 1), f - low as f32)
+        };
+        let (c0, c1, tx) = locate(p.x, grid.bounds.min.x, width, grid.cols);
+        let (r0, r1, ty) = locate(p.y, grid.bounds.min.y, height, grid.rows);
+        let at = |col: usize, row: usize| grid.values[row * grid.cols + col];
+        let bottom = at(c0, r0) * (1.0 - tx) + at(c1, r0) * tx;
+        let top = at(c0, r1) * (1.0 - tx) + at(c1, r1) * tx;
+        bottom * (1.0 - ty) + top * ty
+    }
+
+    /// The direction the density increases fastest at a point, scaled by
+    /// how fast it increases.
+    ///
+    /// This is a central difference of [`DensityGrid::sample`] one cell to
+    /// either side, which smooths over the seams between cells where the
+    /// bilinear interpolation bends. It points toward dense areas, so
+    /// following it gathers strokes and following its negation spreads
+    /// them out.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{field::DensityGrid, shape::Aabb},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+    ///     let points = [point![5.0, 5.0]];
+    ///     let density =
+    ///         DensityGrid::from_points(&points, &bounds, (40, 40), 3.0);
+    ///
+    ///     let uphill = density.gradient(&point![6.0, 5.0]);
+    ///
+    ///     assert!(uphill.x < 0.0);
+    ///
+    pub fn gradient(&self, p: &Point2<f32>) -> Vector2<f32> {
+        let (width, height) = cell_size(&self.cells);
+        if !(width > 0.0 && height > 0.0) {
+            return Vector2::zeros();
+        }
+        let slope = |step: Vector2<f32>, size: f32| {
+            (self.sample(&(p + step)) - self.sample(&(p - step))) / (2.0 * size)
+        };
+        Vector2::new(
+            slope(Vector2::new(width, 0.0), width),
+            slope(Vector2::new(0.0, height), height),
+        )
+    }
+
+    /// The curves where the density equals `level`.
+    ///
+    /// See [`marching_squares`].
+    pub fn iso_contours(&self, level: f32) -> Vec<Polyline> {
+        marching_squares(&self.cells, level)
+    }
+}
+
+/// Trace the curves where a field sampled at the centers of a grid's cells
+/// equals `level`.
+///
+/// Each square between four neighboring cell centers is crossed where the
+/// bilinear field would be, and the crossings are joined across squares
+/// into polylines. Values at or above the level are inside, and every
+/// polyline runs with the inside on its left, so contours around peaks are
+/// counterclockwise. Closed contours end at the point where they start, and
+/// contours which leave the grid are open, with both ends on the boundary
+/// of the cell centers. Where a square has two inside corners opposite
+/// each other, the average of its corners decides whether the inside
+/// connects them. Grids with fewer than two rows or columns have no
+/// squares and no contours.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{field::marching_squares, raster::Grid, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     // A single peak in the middle of a 3 by 3 grid.
+///     let grid = Grid {
+///         bounds: Aabb::new(point![0.0, 0.0], point![3.0, 3.0]),
+///         cols: 3,
+///         rows: 3,
+///         values: vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+///     };
+///
+///     let contours = marching_squares(&grid, 0.5);
+///
+///     assert_eq!(contours.len(), 1);
+///     assert!(contours[0].is_closed());
+///     assert_eq!(
+///         contours[0].vertices,
+///         vec![
+///             point![1.0, 1.5],
+///             point![1.5, 1.0],
+///             point![2.0, 1.5],
+///             point![1.5, 2.0],
+///             point![1.0, 1.5],
+///         ]
+///     );
+///
+pub fn marching_squares(grid: &Grid<f32>, level: f32) -> Vec<Polyline> {
+    let (cols, rows) = (grid.cols, grid.rows);
+    if cols < 2 || rows < 2 {
+        return Vec::new();
+    }
+    let value = |(col, row): (usize, usize)| grid.values[row * cols + col];
+    let inside = |node: (usize, usize)| value(node) >= level;
+
+    // Edges between neighboring centers, horizontal ones first.
+    let horizontal = |col: usize, row: usize| row * (cols - 1) + col;
+    let vertical =
+        |col: usize, row: usize| rows * (cols - 1) + row * cols + col;
+    let edge_count = rows * (cols - 1) + (rows - 1) * cols;
+    let ends = |edge: usize| {
+        if edge < rows * (cols - 1) {
+            let (col, row) = (edge % (cols - 1), edge / (cols - 1));
+            ((col, row), (col + 1, row))
+        } else {
+            let edge = edge - rows * (cols - 1);
+            let (col, row) = (edge % cols, edge / cols);
+            ((col, row), (col, row + 1))
+        }
+    };
+    let crossing = |edge: usize| {
+        let (a, b) = ends(edge);
+        let t = (level - value(a)) / (value(b) - value(a));
+        let (pa, pb) = (
+            center(grid, a.0 as f32, a.1 as f32),
+            center(grid, b.0 as f32, b.1 as f32),
+        );
+        pa + (pb - pa) * t
+    };
+
+    // Within each square, a contour runs from an edge where the corners go
+    // from inside to outside, counterclockwise, to an edge where they go
+    // back in.
+    let mut next: Vec<Option<usize>> = vec![None; edge_count];
+    let mut has_previous = vec![false; edge_count];
+    let mut crossings = Vec::with_capacity(4);
+    for row in 0..rows - 1 {
+        for col in 0..cols - 1 {
+            let corners = [
+                (col, row),
+                (col + 1, row),
+                (col + 1, row + 1),
+                (col, row + 1),
+            ];
+            let edges = [
+                horizontal(col, row),
+                vertical(col + 1, row),
+                horizontal(col, row + 1),
+                vertical(col, row),
+            ];
+            crossings.clear();
+            for k in 0..4 {
+                let (a, b) = (inside(corners[k]), inside(corners[(k + 1) % 4]));
+                if a != b {
+                    crossings.push((edges[k], a));
+                }
+            }
+            let connected = || {
+                let sum: f32 = corners.iter().map(|&node| value(node)).sum();
+                sum / 4.0 >= level
+            };
+            // With two inside corners opposite each other, either the
+            // inside connects them or the outside does.
+            let skip = if crossings.len() == 4 && !connected() {
+                3
+            } else {
+                1
+            };
+            let count = crossings.len();
+            for (k, &(edge, leaving)) in crossings.iter().enumerate() {
+                if leaving {
+                    let end = crossings[(k + skip) % count].0;
+                    next[edge] = Some(end);
+                    has_previous[end] = true;
+                }
+            }
+        }
+    }
+
+    // Join the pieces, starting with the open contours.
+    let mut visited = vec![false; edge_count];
+    let mut contours = Vec::new();
+    let starts = (0..edge_count)
+        .filter(|&edge| !has_previous[edge])
+        .chain(0..edge_count);
+    for start in starts {
+        if visited[start] || next[start].is_none() {
+            continue;
+        }
+        let mut vertices = Vec::new();
+        let mut edge = start;
+        loop {
+            visited[edge] = true;
+            vertices.push(crossing(edge));
+            match next[edge] {
+                Some(following) if visited[following] => {
+                    vertices.push(crossing(following));
+                    break;
+                }
+                Some(following) => edge = following,
+                None => break,
+            }
+        }
+        contours.push(Polyline::new(vertices));
+    }
+    contours
+}
+
+/// Vector fields weaker than this are treated as zero, and streamlines
+/// stop where they reach it.
+const MIN_SPEED: f32 = 1e-6;
+
+/// Follow a vector field from a starting point, like a particle carried by
+/// a current.
+///
+/// The streamline moves `step` along the field's direction at a time,
+/// integrated with the fourth order Runge-Kutta method. Only the field's
+/// direction matters, so steps have the same length wherever the field is
+/// strong. Tracing stops when the streamline leaves `bounds`, with a last
+/// vertex where it crosses the boundary, when it reaches a length of
+/// `max_len`, with the last step shortened to land on it, or when
+/// the field's magnitude drops below `1e-6` or stops moving the streamline
+/// forward, like at a sink. Negate the field to trace backward.
+///
+/// The streamline starts with `start`, and is empty when `start` is outside
+/// the bounds. Steps which aren't positive and finite don't move.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{field::trace_streamline, shape::Aabb},
+///         nalgebra::{point, vector, Point2},
+///     };
+///
+///     // Swirl counterclockwise around the origin.
+///     let swirl = |p: &Point2<f32>| vector![-p.y, p.x];
+///     let bounds = Aabb::new(point![-5.0, -5.0], point![5.0, 5.0]);
+///
+///     let streamline =
+///         trace_streamline(point![2.0, 0.0], &swirl, 0.1, 6.0, &bounds);
+///
+///     assert_eq!(streamline.vertices.len(), 61);
+///     for vertex in &streamline.vertices {
+///         assert!((vertex.coords.norm() - 2.0).abs() < 1e-3);
+///     }
+///
+pub fn trace_streamline(
+    start: Point2<f32>,
+    field: &impl Fn(&Point2<f32>) -> Vector2<f32>,
+    step: f32,
+    max_len: f32,
+    bounds: &Aabb,
+) -> Polyline {
+    if !bounds.contains_point(&start) {
+        return Polyline::new(Vec::new());
+    }
+    let mut vertices = vec![start];
+    if !(step > 0.0 && step.is_finite()) {
+        return Polyline::new(vertices);
+    }
+    let mut length = 0.0;
+    let mut current = start;
+    // Lengths are measured along the integrated curve, and a sliver of
+    // rounding error at the end isn't worth another step.
+    while max_len - length > step * 1e-3 {
+        let h = step.min(max_len - length);
+        let Some(next) = rk4_step(field, &current, h) else {
+            break;
+        };
+        if !bounds.contains_point(&next) {
+            if let Some(inside) =
+                Segment::new(current, next).clip_to_aabb(bounds)
+            {
+                vertices.push(inside.end);
+            }
+            break;
+        }
+        length += h;
+        vertices.push(next);
+        current = next;
+    }
+    Polyline::new(vertices)
+}
+
+/// Follow the curve where a function is zero, like the outline of a shape
+/// given by its signed distance.
+///
+/// The seed is first moved onto the curve with Newton's method, then the
+/// curve is traced by predictor-corrector steps: each step moves about
+/// `step` along the tangent, perpendicular to the function's gradient, and
+/// Newton's method pulls the prediction back onto the curve. Gradients come
+/// from central differences, so the function only needs to be continuous
+/// and smooth near the curve. Steps shrink where the curve bends sharply or
+/// the correction fails, and grow back to `step` after.
+///
+/// The curve runs with the negative side on its left, so loops around
+/// negative regions, like the inside of a signed distance, run
+/// counterclockwise. A loop is closed when the tracing comes back around
+/// to the seed, with the last vertex equal to the first. A curve which
+/// leaves `bounds` is traced in both directions from the seed, with a
+/// vertex where each end crosses the boundary. Tracing also stops where
+/// the gradient vanishes or the curve can't be followed, like a cusp.
+///
+/// Shapes which only have an unsigned distance, like anything which
+/// implements [`DistanceToPoint`], can be outlined at an offset with a
+/// closure like `|p| shape.distance_to_point(p) - offset`, and blends like
+/// `min(d1, d2) - k` work the same way. Returns no vertices when the seed
+/// is outside the bounds, can't be moved onto the curve, or `step` isn't
+/// positive and finite.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{field::trace_zero_set, shape::Aabb},
+///         nalgebra::{point, Point2},
+///     };
+///
+///     // Two overlapping circles blended into one outline.
+///     let a = |p: &Point2<f32>| (p - point![-1.0, 0.0]).norm() - 1.5;
+///     let b = |p: &Point2<f32>| (p - point![1.0, 0.0]).norm() - 1.5;
+///     let blend = |p: &Point2<f32>| a(p).min(b(p));
+///     let bounds = Aabb::new(point![-5.0, -5.0], point![5.0, 5.0]);
+///
+///     let outline = trace_zero_set(&blend, point![0.0, 3.0], 0.05, &bounds);
+///
+///     assert!(outline.is_closed());
+///     for vertex in &outline.vertices {
+///         assert!(blend(vertex).abs() < 1e-3);
+///     }
+///
+pub fn trace_zero_set(
+    f: &impl Fn(&Point2<f32>) -> f32,
+    seed: Point2<f32>,
+    step: f32,
+    bounds: &Aabb,
+) -> Polyline {
+    let usable = step > 0.0 && step.is_finite();
+    if !usable || !bounds.contains_point(&seed) {
+        return Polyline::new(Vec::new());
+    }
+    let tracer = ZeroSetTracer { f, step };
+    let Some(start) = tracer
+        .correct(&seed, f32::INFINITY)
+        .filter(|start| bounds.contains_point(start))
+    else {
+        return Polyline::new(Vec::new());
+    };
+
+    let (forward, closed) = tracer.follow(&start, 1.0, bounds);
+    if closed {
+        return Polyline::new(forward);
+    }
+    let (mut vertices, _) = tracer.follow(&start, -1.0, bounds);
+    vertices.reverse();
+    vertices.extend_from_slice(&forward[1..]);
+    Polyline::new(vertices)
+}
+
+/// A vector field which flows along a drawing.
+///
+/// The field at a point runs along the nearest segment, from its start
+/// toward its end, and fades with the distance `d` to it as
+/// `exp(-d / falloff)`. Streamlines from [`trace_streamline`] follow the
+/// drawing near it, and stop once they're far enough away for the field to
+/// fade out, after about 14 times the falloff. The nearest segment is found
+/// with a [`SegmentIndex`], so each sample is fast even for large drawings.
+/// With no segments, or a falloff which isn't positive, the field is zero
+/// everywhere.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{field::field_from_segments, line::Segment},
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let wall = Segment::new(point![0.0, 0.0], point![10.0, 0.0]);
+///
+///     let field = field_from_segments(&[wall], 2.0);
+///
+///     assert_relative_eq!(field(&point![5.0, 0.0]), vector![1.0, 0.0]);
+///     assert_relative_eq!(
+///         field(&point![5.0, 2.0]),
+///         vector![(-1.0f32).exp(), 0.0],
+///     );
+///
+pub fn field_from_segments(
+    segments: &[Segment],
+    falloff: f32,
+) -> impl Fn(&Point2<f32>) -> Vector2<f32> {
+    let total: f32 = segments.iter().map(Segment::length).sum();
+    let typical = total / segments.len().max(1) as f32;
+    let cell_size = if typical > 0.0 && typical.is_finite() {
+        typical
+    } else {
+        1.0
+    };
+    let mut index = SegmentIndex::new(cell_size);
+    for segment in segments {
+        index.insert(*segment);
+    }
+    move |point: &Point2<f32>| {
+        let nearest = index.nearest_segment(point);
+        let Some((id, distance)) = nearest.filter(|_| falloff > 0.0) else {
+            return Vector2::zeros();
+        };
+        let segment = index.get(id).unwrap();
+        let along = segment.end - segment.start;
+        let length = along.norm();
+        if length == 0.0 {
+            return Vector2::zeros();
+        }
+        along * (math::exp(-distance / falloff) / length)
+    }
+}
+
+/// How close streamlines from [`streamlines_even`] come to each other, as
+/// a fraction of the separation.
+const TERMINATION_FACTOR: f32 = 0.5;
+
+/// Cover an area with evenly spaced streamlines, like hatching which
+/// follows a flow.
+///
+/// Streamlines are placed with the method of Jobard and Lefer. The first
+/// starts at the center of `bounds`, and each new one starts `separation`
+/// to the side of a vertex of an earlier one, trying the streamlines in
+/// the order they were placed and their vertices from start to end. Each
+/// streamline is traced forward and backward from its start, with steps
+/// like [`trace_streamline`], and stops at the bounds, where the field
+/// vanishes, or before it would come closer than half of `separation` to
+/// another streamline or to a distant part of itself. Areas which can't be
+/// reached from earlier streamlines, like the far side of a sink, are
+/// seeded from a grid of points `separation` apart.
+///
+/// No two streamlines come closer than half of the separation, and the
+/// result only depends on the arguments. Streamlines which can't take a
+/// single step are left out, and the result is empty when `separation` or
+/// `step` isn't positive and finite. A step of about a tenth of the
+/// separation gives smooth streamlines.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{field::streamlines_even, shape::Aabb},
+///         nalgebra::{point, vector, Point2},
+///     };
+///
+///     let rightward = |_: &Point2<f32>| vector![1.0, 0.0];
+///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 4.0]);
+///
+///     let streamlines = streamlines_even(&rightward, &bounds, 1.0, 0.1);
+///
+///     // Horizontal lines at y = 2, 3, 1, 4 and 0.
+///     assert_eq!(streamlines.len(), 5);
+///     assert_eq!(streamlines[1].vertices[0], point![0.0, 3.0]);
+///     for streamline in &streamlines {
+///         let last = streamline.vertices.last().unwrap();
+///         assert!((last.x - 10.0).abs() < 1e-4);
+///     }
+///
+pub fn streamlines_even(
+    field: &impl Fn(&Point2<f32>) -> Vector2<f32>,
+    bounds: &Aabb,
+    separation: f32,
+    step: f32,
+) -> Vec<Polyline> {
+    let usable = |x: f32| x > 0.0 && x.is_finite();
+    if !usable(separation) || !usable(step) {
+        return Vec::new();
+    }
+    let min_distance = separation * TERMINATION_FACTOR;
+    let area = (bounds.width() + separation) * (bounds.height() + separation);
+    let mut placement = EvenPlacement {
+        bounds,
+        separation,
+        step,
+        min_distance,
+        own_reach: 2.0 * (separation + step),
+        max_steps: (area / (min_distance * step)) as usize + 16,
+        index: SegmentIndex::new(separation),
+        owners: BTreeMap::new(),
+        lines: Vec::new(),
+    };
+
+    let cols = (bounds.width() / separation) as usize + 1;
+    let rows = (bounds.height() / separation) as usize + 1;
+    let grid = (0..rows).flat_map(|row| {
+        (0..cols).map(move |col| {
+            bounds.min + Vector2::new(col as f32, row as f32) * separation
+        })
+    });
+    let mut neighbors_seeded = 0;
+    for seed in iter::once(bounds.center()).chain(grid) {
+        placement.place(field, seed);
+        while neighbors_seeded < placement.lines.len() {
+            let len = placement.lines[neighbors_seeded].vertices.len();
+            for i in 0..len {
+                let vertices = &placement.lines[neighbors_seeded].vertices;
+                let along = vertices[(i + 1).min(len - 1)]
+                    - vertices[i.saturating_sub(1)];
+                let Some(tangent) = along.try_normalize(0.0) else {
+                    continue;
+                };
+                let side = Vector2::new(-tangent.y, tangent.x) * separation;
+                let vertex = vertices[i];
+                placement.place(field, vertex + side);
+                placement.place(field, vertex - side);
+            }
+            neighbors_seeded += 1;
+        }
+    }
+    placement.lines
+}
+
+/// The streamlines placed so far by [`streamlines_even`].
+struct EvenPlacement<'a> {
+    bounds: &'a Aabb,
+    separation: f32,
+    step: f32,
+
+    /// The closest two streamlines may come to each other.
+    min_distance: f32,
+
+    /// Parts of a streamline closer than this along it may be close
+    /// together.
+    own_reach: f32,
+
+    /// The most steps in either direction from a streamline's start.
+    max_steps: usize,
+
+    index: SegmentIndex,
+
+    /// The streamline which owns each segment and the arc length along it
+    /// to the segment's middle, measured from the start and negative when
+    /// traced backward.
+    owners: BTreeMap<SegmentId, (usize, f32)>,
+    lines: Vec<Polyline>,
+}
+
+impl EvenPlacement<'_> {
+    /// Add a streamline through a seed point if the seed is far enough from
+    /// the other streamlines.
+    fn place(
+        &mut self,
+        field: &impl Fn(&Point2<f32>) -> Vector2<f32>,
+        seed: Point2<f32>,
+    ) {
+        // Seeds at exactly the separation from a streamline are expected,
+        // so allow for rounding.
+        let too_close = self
+            .index
+            .nearest_segment(&seed)
+            .is_some_and(|(_, d)| d < self.separation * (1.0 - 1e-3));
+        if too_close || !self.bounds.contains_point(&seed) {
+            return;
+        }
+        let line = self.lines.len();
+        let forward = self.trace(field, seed, line, 1.0);
+        let backward =
+            self.trace(&|p: &Point2<f32>| -field(p), seed, line, -1.0);
+        if forward.is_empty() && backward.is_empty() {
+            return;
+        }
+        let vertices = backward
+            .into_iter()
+            .rev()
+            .chain([seed])
+            .chain(forward)
+            .collect();
+        self.lines.push(Polyline::new(vertices));
+    }
+
+    /// Follow the field from a seed until the streamline has to stop,
+    /// adding its segments to the index. Returns the vertices after the
+    /// seed.
+    fn trace(
+        &mut self,
+        field: &impl Fn(&Point2<f32>) -> Vector2<f32>,
+        seed: Point2<f32>,
+        line: usize,
+        sign: f32,
+    ) -> Vec<Point2<f32>> {
+        let mut vertices = Vec::new();
+        let mut current = seed;
+        let mut arc = 0.0;
+        for _ in 0..self.max_steps {
+            let Some(next) = rk4_step(field, &current, self.step) else {
+                break;
+            };
+            let inside = self.bounds.contains_point(&next);
+            let piece = Segment::new(current, next);
+            let piece = if inside {
+                piece
+            } else if let Some(clipped) = piece.clip_to_aabb(self.bounds) {
+                clipped
+            } else {
+                break;
+            };
+            let middle = sign * (arc + piece.length() / 2.0);
+            if !self.fits(&piece, line, middle) {
+                break;
+            }
+            let id = self.index.insert(piece);
+            self.owners.insert(id, (line, middle));
+            vertices.push(piece.end);
+            if !inside {
+                break;
+            }
+            arc += piece.length();
+            current = next;
+        }
+        vertices
+    }
+
+    /// Returns true when a new piece of a streamline keeps its distance
+    /// from the other streamlines, and from the distant parts of its own.
+    fn fits(&self, piece: &Segment, line: usize, middle: f32) -> bool {
+        let reach = Vector2::repeat(self.min_distance);
+        let nearby = Aabb::new(
+            piece.start.inf(&piece.end) - reach,
+            piece.start.sup(&piece.end) + reach,
+        );
+        self.index.query_aabb(&nearby).into_iter().all(|id| {
+            let (owner, owner_middle) = self.owners[&id];
+            let neighbor = (owner == line)
+                && (middle - owner_middle).abs() < self.own_reach;
+            neighbor
+                || segment_distance(self.index.get(id).unwrap(), piece)
+                    >= self.min_distance
+        })
+    }
+}
+
+/// The distance between the closest points of two segments.
+fn segment_distance(a: &Segment, b: &Segment) -> f32 {
+    if intersect_segments(a, b) != SegmentIntersection::None {
+        return 0.0;
+    }
+    let distance_squared = [
+        a.distance_to_point_squared(&b.start),
+        a.distance_to_point_squared(&b.end),
+        b.distance_to_point_squared(&a.start),
+        b.distance_to_point_squared(&a.end),
+    ]
+    .into_iter()
+    .fold(f32::INFINITY, f32::min);
+    math::sqrt(distance_squared)
+}
+
+/// The field's direction, or `None` where it's too weak to have one.
+fn direction(
+    field: &impl Fn(&Point2<f32>) -> Vector2<f32>,
+    p: &Point2<f32>,
+) -> Option<Vector2<f32>> {
+    let v = field(p);
+    let speed = v.norm();
+    (speed >= MIN_SPEED && speed.is_finite()).then(|| v / speed)
+}
+
+/// Move `h` along the field's direction with one fourth order Runge-Kutta
+/// step, or return `None` where the field vanishes or stops moving forward.
+fn rk4_step(
+    field: &impl Fn(&Point2<f32>) -> Vector2<f32>,
+    current: &Point2<f32>,
+    h: f32,
+) -> Option<Point2<f32>> {
+    let k1 = direction(field, current)?;
+    let k2 = direction(field, &(current + k1 * (h / 2.0)))?;
+    let k3 = direction(field, &(current + k2 * (h / 2.0)))?;
+    let k4 = direction(field, &(current + k3 * h))?;
+    let next = current + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (h / 6.0);
+    ((next - current).norm() > h * 1e-3).then_some(next)
+}
+
+/// The state for following the zero set of a function with
+/// [`trace_zero_set`].
+struct ZeroSetTracer<'a, F> {
+    f: &'a F,
+    step: f32,
+}
+
+impl<F: Fn(&Point2<f32>) -> f32> ZeroSetTracer<'_, F> {
+    /// The most Newton iterations used to pull a point onto the curve.
+    const NEWTON_ITERATIONS: usize = 12;
+
+    /// The steepest turn between neighboring tangents before the step is
+    /// shortened, in radians.
+    const MAX_TURN: f32 = 0.2;
+
+    /// The gradient from central differences, or `None` where it vanishes.
+    fn gradient(&self, p: &Point2<f32>) -> Option<Vector2<f32>> {
+        let h = self.step * 1e-2;
+        let (dx, dy) = (Vector2::new(h, 0.0), Vector2::new(0.0, h));
+        let f = self.f;
+        let gradient = Vector2::new(
+            f(&(p + dx)) - f(&(p - dx)),
+            f(&(p + dy)) - f(&(p - dy)),
+        ) / (2.0 * h);
+        let norm = gradient.norm();
+        (norm > MIN_SPEED && norm.is_finite()).then_some(gradient)
+    }
+
+    /// The unit tangent, with the negative side on the left.
+    fn tangent(&self, p: &Point2<f32>) -> Option<Vector2<f32>> {
+        let gradient = self.gradient(p)?;
+        Some(Vector2::new(-gradient.y, gradient.x).normalize())
+    }
+
+    /// Move a point onto the curve with Newton's method along the gradient,
+    /// giving up if it moves further than `max_move`.
+    fn correct(&self, p: &Point2<f32>, max_move: f32) -> Option<Point2<f32>> {
+        let mut current = *p;
+        for _ in 0..Self::NEWTON_ITERATIONS {
+            let value = (self.f)(&current);
+            let tolerance = self.step * 1e-4
+                + f32::EPSILON * 4.0 * (1.0 + current.coords.abs().max());
+            if value.abs() <= tolerance {
+                return Some(current);
+            }
+            let gradient = self.gradient(&current)?;
+            current -= gradient * (value / gradient.norm_squared());
+            if (current - p).norm() > max_move || !value.is_finite() {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Follow the curve from a point on it in one direction, returning the
+    /// vertices and whether they looped back around to the start.
+    fn follow(
+        &self,
+        start: &Point2<f32>,
+        direction: f32,
+        bounds: &Aabb,
+    ) -> (Vec<Point2<f32>>, bool) {
+        let mut vertices = vec![*start];
+        let Some(start_tangent) = self.tangent(start).map(|t| t * direction)
+        else {
+            return (vertices, false);
+        };
+        // A curve in the bounds with features at least a step apart is
+        // shorter than this many steps.
+        let size = bounds.width() + bounds.height();
+        let max_steps =
+            (size * size / (self.step * self.step) + 1e3).min(1e6) as usize;
+        let min_step = self.step * 1e-3;
+
+        let (mut current, mut tangent) = (*start, start_tangent);
+        let mut h = self.step;
+        let mut length = 0.0;
+        for _ in 0..max_steps {
+            let next = self
+                .correct(&(current + tangent * h), h)
+                .and_then(|next| Some((next, self.tangent(&next)? * direction)))
+                .filter(|(next, next_tangent)| {
+                    let moved = (next - current).norm();
+                    let turn = tangent.angle(next_tangent);
+                    moved > h * 0.5 && moved < h * 1.5 && turn <= Self::MAX_TURN
+                });
+            let Some((next, next_tangent)) = next else {
+                h /= 2.0;
+                if h < min_step {
+                    break;
+                }
+                continue;
+            };
+
+            if !bounds.contains_point(&next) {
+                if let Some(inside) =
+                    Segment::new(current, next).clip_to_aabb(bounds)
+                {
+                    vertices.push(inside.end);
+                }
+                break;
+            }
+            // Close the loop once the step passes the start going the same
+            // way, after getting well away from it.
+            length += (next - current).norm();
+            let passes_start = Segment::new(current, next)
+                .distance_to_point(start)
+                < self.step * 0.5;
+            if length > self.step * 3.0
+                && passes_start
+                && next_tangent.dot(&start_tangent) > 0.0
+            {
+                vertices.push(*start);
+                return (vertices, true);
+            }
+
+            vertices.push(next);
+            current = next;
+            tangent = next_tangent;
+            h = (h * 2.0).min(self.step);
+        }
+        (vertices, false)
+    }
+}
+
+/// The width and height of a grid's cells.
+fn cell_size(grid: &Grid<f32>) -> (f32, f32) {
+    (
+        grid.bounds.width() / grid.cols as f32,
+        grid.bounds.height() / grid.rows as f32,
+    )
+}
+
+/// The center of the cell in a column and row, which can be outside the
+/// grid.
+fn center(grid: &Grid<f32>, col: f32, row: f32) -> Point2<f32> {
+    let (width, height) = cell_size(grid);
+    grid.bounds.min + Vector2::new((col + 0.5) * width, (row + 0.5) * height)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            field_from_segments, marching_squares, segment_distance,
+            streamlines_even, trace_streamline, trace_zero_set, DensityGrid,
+        },
+        crate::{
+            line::{DistanceToPoint, Segment},
+            polygon::Polygon,
+            raster::Grid,
+            shape::Aabb,
+            test_support::{point2, unit_vector},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::TAU,
+        nalgebra::{point, vector, Point2},
+        proptest::prelude::*,
+    };
+
+    fn bounds() -> Aabb {
+        Aabb::new(point![0.0, 0.0], point![10.0, 10.0])
+    }
+
+    #[test]
+    pub fn single_points_should_make_symmetric_bumps_which_slope_toward_them() {
+        // The point is on a cell center, in a grid of 0.15625 wide cells.
+        let peak = point![5.078125, 5.078125];
+        let density =
+            DensityGrid::from_points(&[peak], &bounds(), (64, 64), 2.0);
+
+        let total: f32 = density.cells.values.iter().sum();
+        assert_relative_eq!(total * 0.15625 * 0.15625, 1.0, epsilon = 1e-4);
+        for radius in [0.5, 1.0, 1.5] {
+            let around: Vec<Point2<f32>> = (0..16)
+                .map(|i| {
+                    let angle = i as f32 * TAU / 16.0;
+                    peak + vector![angle.cos(), angle.sin()] * radius
+                })
+                .collect();
+            let first = density.sample(&around[0]);
+            for p in &around {
+                assert_relative_eq!(
+                    density.sample(p),
+                    first,
+                    max_relative = 0.05
+                );
+                let toward = (peak - p).normalize();
+                let gradient = density.gradient(p);
+                assert!(
+                    gradient.normalize().dot(&toward) > 0.99,
+                    "{} at {}",
+                    gradient,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn bumps_should_have_counterclockwise_contours() {
+        let peak = point![4.0, 6.0];
+        let density =
+            DensityGrid::from_points(&[peak], &bounds(), (50, 50), 2.0);
+        let half = density.sample(&peak) / 2.0;
+
+        let contours = density.iso_contours(half);
+
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].is_closed());
+        let loop_ = Polygon::new(contours[0].vertices.clone());
+        assert!(loop_.signed_area() > 0.0);
+        assert!(loop_.contains_point(&peak));
+        for vertex in &contours[0].vertices {
+            assert_relative_eq!(
+                density.sample(vertex),
+                half,
+                max_relative = 1e-3
+            );
+        }
+        assert!(density.iso_contours(density.sample(&peak) * 2.0).is_empty());
+    }
+
+    #[test]
+    pub fn contours_leaving_the_grid_should_be_open() {
+        // A ridge along the left side of the grid.
+        let grid = Grid {
+            bounds: Aabb::new(point![0.0, 0.0], point![3.0, 3.0]),
+            cols: 3,
+            rows: 3,
+            values: vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+        };
+
+        let contours = marching_squares(&grid, 0.5);
+
+        assert_eq!(contours.len(), 1);
+        // The ridge is on the left of the contour, so it runs up.
+        assert_eq!(
+            contours[0].vertices,
+            vec![point![1.0, 0.5], point![1.0, 1.5], point![1.0, 2.5]]
+        );
+    }
+
+    #[test]
+    pub fn narrow_kernels_and_outside_points_should_be_handled() {
+        let inside = [point![3.3, 7.1], point![6.05, 2.5]];
+        let outside = [point![-50.0, 5.0], point![f32::NAN, 1.0]];
+        let points: Vec<_> = inside.iter().chain(&outside).copied().collect();
+
+        let density =
+            DensityGrid::from_points(&points, &bounds(), (20, 20), 0.01);
+
+        let total: f32 = density.cells.values.iter().sum();
+        assert_relative_eq!(total * 0.25, 2.0, epsilon = 1e-4);
+        assert_eq!(
+            DensityGrid::from_points(&points, &bounds(), (0, 0), 1.0)
+                .sample(&inside[0]),
+            0.0
+        );
+    }
+
+    #[test]
+    pub fn streamlines_in_uniform_fields_should_be_straight() {
+        let uniform = |_: &Point2<f32>| vector![3.0, 1.0];
+        let start = point![1.0, 2.0];
+
+        let streamline =
+            trace_streamline(start, &uniform, 0.25, 100.0, &bounds());
+
+        let direction = vector![3.0, 1.0].normalize();
+        for vertex in &streamline.vertices {
+            assert!((vertex - start).perp(&direction).abs() < 1e-4);
+        }
+        // It stops where it leaves the bounds, on the right side.
+        let last = streamline.vertices.last().unwrap();
+        assert_relative_eq!(last.x, 10.0);
+        assert_relative_eq!(last.y, 5.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    pub fn streamlines_near_circles_should_follow_them() {
+        // A counterclockwise circle of segments around the middle.
+        let middle = point![5.0, 5.0];
+        let around = |i: usize| {
+            let angle = i as f32 * TAU / 64.0;
+            middle + vector![angle.cos(), angle.sin()] * 3.0
+        };
+        let circle: Vec<Segment> = (0..64)
+            .map(|i| Segment::new(around(i), around(i + 1)))
+            .collect();
+        let field = field_from_segments(&circle, 2.0);
+
+        let streamline =
+            trace_streamline(point![8.5, 5.0], &field, 0.05, 15.0, &bounds());
+
+        assert_relative_eq!(streamline.length(), 15.0, epsilon = 1e-2);
+        for piece in streamline.segments() {
+            let outward = (piece.start - middle).normalize();
+            let along = (piece.end - piece.start).normalize();
+            assert!((piece.start - middle).norm() - 3.5 < 0.3);
+            assert!(along.dot(&outward).abs() < 0.1);
+            assert!(outward.perp(&along) > 0.99);
+        }
+    }
+
+    #[test]
+    pub fn streamlines_should_stop_where_the_field_vanishes() {
+        let sink = |p: &Point2<f32>| point![5.0, 5.0] - p;
+        let fading = field_from_segments(
+            &[Segment::new(point![0.0, 0.0], point![1.0, 0.0])],
+            0.1,
+        );
+
+        let into_sink =
+            trace_streamline(point![2.0, 5.0], &sink, 0.1, 50.0, &bounds());
+        let faded =
+            trace_streamline(point![5.0, 9.0], &fading, 0.1, 50.0, &bounds());
+
+        assert!(into_sink.length() < 3.1);
+        assert!(
+            (into_sink.vertices.last().unwrap() - point![5.0, 5.0]).norm()
+                < 0.1
+        );
+        assert_eq!(faded.vertices, vec![point![5.0, 9.0]]);
+        assert!(trace_streamline(
+            point![11.0, 5.0],
+            &sink,
+            0.1,
+            5.0,
+            &bounds()
+        )
+        .vertices
+        .is_empty());
+        assert_eq!(
+            field_from_segments(&[], 1.0)(&point![0.0, 0.0]),
+            vector![0.0, 0.0]
+        );
+    }
+
+    #[test]
+    pub fn even_streamlines_should_be_deterministic() {
+        let swirl = |p: &Point2<f32>| vector![5.0 - p.y, p.x - 4.0];
+
+        let first = streamlines_even(&swirl, &bounds(), 0.8, 0.1);
+        let second = streamlines_even(&swirl, &bounds(), 0.8, 0.1);
+
+        assert!(first.len() > 5);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    pub fn even_streamlines_in_uniform_fields_should_be_parallel() {
+        let diagonal = |_: &Point2<f32>| vector![1.0, 1.0];
+
+        let streamlines = streamlines_even(&diagonal, &bounds(), 1.0, 0.1);
+
+        // Diagonals spaced by the separation, from corner to corner.
+        assert_eq!(streamlines.len(), 15);
+        let mut offsets: Vec<f32> = streamlines
+            .iter()
+            .map(|streamline| {
+                let first = streamline.vertices[0];
+                for vertex in &streamline.vertices {
+                    assert_relative_eq!(
+                        vertex.y - vertex.x,
+                        first.y - first.x,
+                        epsilon = 1e-3
+                    );
+                }
+                (first.y - first.x) / 2.0f32.sqrt()
+            })
+            .collect();
+        offsets.sort_by(f32::total_cmp);
+        for pair in offsets.windows(2) {
+            assert_relative_eq!(pair[1] - pair[0], 1.0, epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    pub fn even_streamlines_should_need_a_usable_separation_and_step() {
+        let rightward = |_: &Point2<f32>| vector![1.0, 0.0];
+
+        assert!(streamlines_even(&rightward, &bounds(), 0.0, 0.1).is_empty());
+        assert!(streamlines_even(&rightward, &bounds(), 1.0, -1.0).is_empty());
+        let still = |_: &Point2<f32>| vector![0.0, 0.0];
+        assert!(streamlines_even(&still, &bounds(), 1.0, 0.1).is_empty());
+    }
+
+    #[test]
+    pub fn zero_sets_of_circles_should_close_counterclockwise() {
+        let center = point![4.5, 5.5];
+        let circle = |p: &Point2<f32>| (p - center).norm() - 2.0;
+
+        let outline =
+            trace_zero_set(&circle, point![9.0, 5.0], 0.05, &bounds());
+
+        assert!(outline.is_closed());
+        let polygon = Polygon::new(outline.vertices[1..].to_vec());
+        assert!(polygon.signed_area() > 0.0);
+        assert_relative_eq!(outline.length(), TAU * 2.0, max_relative = 1e-2);
+        for vertex in &outline.vertices {
+            assert!(((vertex - center).norm() - 2.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    pub fn open_zero_sets_should_end_on_the_bounds() {
+        let line = |p: &Point2<f32>| p.y - 0.5 * p.x - 2.0;
+
+        let traced = trace_zero_set(&line, point![4.0, 4.5], 0.3, &bounds());
+
+        assert!(!traced.is_closed());
+        // The negative side, below the line, is on the left.
+        let first = traced.vertices[0];
+        let last = traced.vertices.last().unwrap();
+        assert_relative_eq!(first, point![10.0, 7.0], epsilon = 1e-4);
+        assert_relative_eq!(*last, point![0.0, 2.0], epsilon = 1e-4);
+        for vertex in &traced.vertices {
+            assert!(line(vertex).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    pub fn offsets_of_unsigned_distances_should_be_traced() {
+        let wall = Segment::new(point![3.0, 5.0], point![7.0, 5.0]);
+        let stadium = |p: &Point2<f32>| wall.distance_to_point(p) - 1.0;
+
+        let outline =
+            trace_zero_set(&stadium, point![5.0, 5.9], 0.02, &bounds());
+
+        assert!(outline.is_closed());
+        assert_relative_eq!(outline.length(), 8.0 + TAU, max_relative = 1e-3);
+    }
+
+    #[test]
+    pub fn zero_sets_should_need_a_seed_near_the_curve_and_a_usable_step() {
+        let circle = |p: &Point2<f32>| p.coords.norm() - 2.0;
+        let nowhere = |_: &Point2<f32>| 1.0;
+        let seed = point![2.0, 0.0];
+        let around = Aabb::new(point![-5.0, -5.0], point![5.0, 5.0]);
+
+        assert!(trace_zero_set(&nowhere, seed, 0.1, &around)
+            .vertices
+            .is_empty());
+        assert!(trace_zero_set(&circle, -seed, 0.1, &bounds())
+            .vertices
+            .is_empty());
+        assert!(trace_zero_set(&circle, seed, 0.0, &around)
+            .vertices
+            .is_empty());
+        assert!(trace_zero_set(&circle, seed, 0.1, &around).is_closed());
+    }
+
+    proptest! {
+        #[test]
+        fn traced_circles_should_keep_their_radius(
+            center in point2(3.0..=7.0),
+            radius in 0.5f32..=3.0,
+            direction in unit_vector(),
+            miss in -0.2f32..=0.2,
+        ) {
+            let circle = |p: &Point2<f32>| (p - center).norm() - radius;
+            let seed = center + direction.into_inner() * (radius + miss);
+            let step = radius / 20.0;
+
+            let outline = trace_zero_set(&circle, seed, step, &bounds());
+
+            prop_assert!(outline.vertices.len() > 20);
+            for vertex in &outline.vertices {
+                prop_assert!(((vertex - center).norm() - radius).abs() < 1e-3);
+                prop_assert!(bounds().contains_point(vertex));
+            }
+        }
+
+        #[test]
+        fn density_should_add_up_to_the_points_inside(
+            points in prop::collection::vec(point2(-5.0..=15.0), 0..20),
+            radius in 0.0f32..=3.0,
+        ) {
+            let density =
+                DensityGrid::from_points(&points, &bounds(), (25, 25), radius);
+
+            let total: f32 = density.cells.values.iter().sum::<f32>() * 0.16;
+            // Points whose kernel is entirely inside add one each, and points
+            // near the bounds add some fraction of one.
+            let reach = radius.max(0.4);
+            let deep = points
+                .iter()
+                .filter(|p| {
+                    p.x - reach > 0.0 && p.x + reach < 10.0
+                        && p.y - reach > 0.0 && p.y + reach < 10.0
+                })
+                .count();
+            let near = points
+                .iter()
+                .filter(|p| {
+                    p.x + reach > 0.0 && p.x - reach < 10.0
+                        && p.y + reach > 0.0 && p.y - reach < 10.0
+                })
+                .count();
+            prop_assert!(total >= deep as f32 - 1e-3);
+            prop_assert!(total <= near as f32 + 1e-3);
+            let values = &density.cells.values;
+            prop_assert!(values.iter().all(|&value| value >= 0.0));
+        }
+
+        #[test]
+        fn streamlines_should_stay_in_bounds_and_within_their_length(
+            start in point2(0.0..=10.0),
+            direction in unit_vector(),
+            step in 0.01f32..=1.0,
+            max_len in 0.0f32..=20.0,
+        ) {
+            let swirl = |p: &Point2<f32>| {
+                direction.into_inner() + vector![p.y - 5.0, 5.0 - p.x] * 0.3
+            };
+
+            let streamline =
+                trace_streamline(start, &swirl, step, max_len, &bounds());
+
+            prop_assert_eq!(streamline.vertices[0], start);
+            prop_assert!(streamline.length() <= max_len + 1e-3);
+            for vertex in &streamline.vertices {
+                prop_assert!(bounds().contains_point(vertex));
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn even_streamlines_should_keep_half_the_separation_apart(
+            center in point2(0.0..=10.0),
+            direction in unit_vector(),
+            separation in 0.7f32..=2.0,
+        ) {
+            let swirl = |p: &Point2<f32>| {
+                let around = vector![center.y - p.y, p.x - center.x];
+                direction.into_inner() + around
+            };
+            let step = separation / 8.0;
+
+            let streamlines =
+                streamlines_even(&swirl, &bounds(), separation, step);
+
+            prop_assert!(!streamlines.is_empty());
+            let segments: Vec<Vec<Segment>> = streamlines
+                .iter()
+                .map(|streamline| streamline.segments().collect())
+                .collect();
+            for (i, first) in segments.iter().enumerate() {
+                for second in &segments[i + 1..] {
+                    for a in first {
+                        for b in second {
+                            let distance = segment_distance(a, b);
+                            prop_assert!(distance >= separation / 2.0 - 1e-4);
+                        }
+                    }
+                }
+            }
+            for vertex in streamlines.iter().flat_map(|s| &s.vertices) {
+                prop_assert!(bounds().contains_point(vertex));
+            }
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! Fitting smooth curves to polylines.
+
+use {
+    crate::{
+        line::{DistanceToPoint, Segment},
+        math,
+        polygon::Polyline,
+        shape::Arc,
+    },
+    alloc::vec::Vec,
+    core::f32::consts::{FRAC_PI_4, TAU},
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+/// Vertices where the polyline turns by more than this many radians are
+/// corners, which the fitted curve passes through without smoothing.
+const CORNER_ANGLE: f32 = FRAC_PI_4;
+
+/// Arcs which stray less than this fraction of the tolerance from their
+/// chord are replaced by segments.
+const FLAT: f32 = 0.01;
+
+/// The most a biarc can be stretched compared to the path it replaces.
+/// Arcs through points which turn by up to [`CORNER_ANGLE`] are only a few
+/// percent longer than their chords.
+const MAX_STRETCH: f32 = 1.1;
+
+/// One piece of a curve made of circular arcs and straight segments.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ArcOrSegment {
+    /// A piece of a circle.
+    Arc(Arc),
+
+    /// A straight piece.
+    Segment(Segment),
+}
+
+impl ArcOrSegment {
+    /// The point where the piece starts.
+    pub fn start(&self) -> Point2<f32> {
+        match self {
+            ArcOrSegment::Arc(arc) => arc.point_at(0.0),
+            ArcOrSegment::Segment(segment) => segment.start,
+        }
+    }
+
+    /// The length of the piece.
+    pub fn length(&self) -> f32 {
+        match self {
+            ArcOrSegment::Arc(arc) => arc.length(),
+            ArcOrSegment::Segment(segment) => segment.length(),
+        }
+    }
+
+    /// The point where the piece ends.
+    pub fn end(&self) -> Point2<f32> {
+        match self {
+            ArcOrSegment::Arc(arc) => arc.point_at(1.0),
+            ArcOrSegment::Segment(segment) => segment.end,
+        }
+    }
+}
+
+impl DistanceToPoint for ArcOrSegment {
+    fn distance_to_point(&self, point: &Point2<f32>) -> f32 {
+        match self {
+            ArcOrSegment::Arc(arc) => arc.distance_to_point(point),
+            ArcOrSegment::Segment(segment) => segment.distance_to_point(point),
+        }
+    }
+
+    fn distance_to_point_squared(&self, point: &Point2<f32>) -> f32 {
+        match self {
+            ArcOrSegment::Arc(arc) => arc.distance_to_point_squared(point),
+            ArcOrSegment::Segment(segment) => {
+                segment.distance_to_point_squared(point)
+            }
+        }
+    }
+}
+
+/// Approximate a polyline with a chain of circular arcs and segments.
+///
+/// The polyline is split at its corners, where it turns by more than 45
+/// degrees, and the curve passes through every corner exactly. Between
+/// corners, the tangent at each vertex is estimated from the circle through
+/// it and its neighbors, and the vertices are covered greedily by biarcs:
+/// pairs of arcs which meet with a shared tangent and match the estimated
+/// tangents at both ends. Each biarc spans as many vertices as it can while
+/// every vertex, and the middle of every edge, stays within `tolerance` of
+/// it. Arcs which are too flat to matter become segments.
+///
+/// The pieces are in order along the polyline, and each one starts where the
+/// previous one ends. Smooth curves which are sampled densely need only a
+/// handful of pieces. Repeated vertices are ignored, and polylines with
+/// fewer than two distinct vertices give an empty result.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             fit::{biarcs, ArcOrSegment},
+///             line::DistanceToPoint,
+///             shape::Arc,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     // A whole circle sampled with 500 edges.
+///     let circle = Arc::new(point![2.0, 3.0], 10.0, 0.0, core::f32::consts::TAU);
+///     let path = circle.to_polyline_n(500);
+///
+///     let pieces = biarcs(&path, 0.01);
+///
+///     assert!(pieces.len() <= 8);
+///     for vertex in &path.vertices {
+///         let distance = pieces
+///             .iter()
+///             .map(|piece| piece.distance_to_point(vertex))
+///             .fold(f32::INFINITY, f32::min);
+///         assert!(distance <= 0.01);
+///     }
+///     for piece in &pieces {
+///         match piece {
+///             ArcOrSegment::Arc(arc) => assert!((arc.radius - 10.0).abs() < 0.1),
+///             ArcOrSegment::Segment(_) => panic!("circles have no segments"),
+///         }
+///     }
+///
+pub fn biarcs(path: &Polyline, tolerance: f32) -> Vec<ArcOrSegment> {
+    let mut points: Vec<Point2<f32>> = Vec::with_capacity(path.vertices.len());
+    for vertex in &path.vertices {
+        if points.last() != Some(vertex) {
+            points.push(*vertex);
+        }
+    }
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    // Fit each smooth run between corners separately.
+    let mut pieces = Vec::new();
+    let mut run_start = 0;
+    for i in 1..points.len() {
+        if i == points.len() - 1 || is_corner(&points[i - 1..=i + 1]) {
+            fit_run(&points[run_start..=i], tolerance, &mut pieces);
+            run_start = i;
+        }
+    }
+    pieces
+}
+
+/// Returns true when the path turns by more than [`CORNER_ANGLE`] at the
+/// middle of three points.
+fn is_corner(points: &[Point2<f32>]) -> bool {
+    let (incoming, outgoing) = (points[1] - points[0], points[2] - points[1]);
+    math::atan2(incoming.perp(&outgoing), incoming.dot(&outgoing)).abs()
+        > CORNER_ANGLE
+}
+
+/// Greedily cover a run of points without corners with biarcs.
+fn fit_run(
+    points: &[Point2<f32>],
+    tolerance: f32,
+    pieces: &mut Vec<ArcOrSegment>,
+) {
+    let tangents = tangents(points);
+    let fit = |start: usize, end: usize| {
+        let biarc = biarc(
+            &points[start],
+            &tangents[start],
+            &points[end],
+            &tangents[end],
+            tolerance,
+        )?;
+        fits(&biarc, &points[start..=end], tolerance).then_some(biarc)
+    };
+
+    let mut start = 0;
+    while start < points.len() - 1 {
+        // Gallop forward to find a span which doesn't fit, then binary
+        // search for the longest span which does.
+        let last = points.len() - 1;
+        let mut good = start + 1;
+        let mut step = 1;
+        let mut bad = loop {
+            let end = (good + step).min(last);
+            if end == good {
+                break last + 1;
+            }
+            if fit(start, end).is_none() {
+                break end;
+            }
+            good = end;
+            step *= 2;
+        };
+        while bad - good > 1 {
+            let middle = (good + bad) / 2;
+            if fit(start, middle).is_some() {
+                good = middle;
+            } else {
+                bad = middle;
+            }
+        }
+        match fit(start, good) {
+            Some(biarc) => pieces.extend(biarc.into_iter().flatten()),
+            None => pieces.push(ArcOrSegment::Segment(Segment::new(
+                points[start],
+                points[good],
+            ))),
+        }
+        start = good;
+    }
+}
+
+/// Estimate the tangent at each point from the circle through it and its
+/// neighbors. The ends use the same circle as their neighbor.
+fn tangents(points: &[Point2<f32>]) -> Vec<Unit<Vector2<f32>>> {
+    let n = points.len();
+    let chord = |i: usize| Unit::new_normalize(points[i + 1] - points[i]);
+    if n == 2 {
+        return vec![chord(0), chord(0)];
+    }
+    // This weighting of the edges on either side is exact for points on a
+    // circle.
+    let inner = |i: usize| {
+        let (before, after) =
+            (points[i] - points[i - 1], points[i + 1] - points[i]);
+        Unit::new_normalize(
+            before.normalize() * after.norm()
+                + after.normalize() * before.norm(),
+        )
+    };
+    // Mirror the neighbor's tangent across the chord to the end.
+    let mirror = |tangent: Unit<Vector2<f32>>, chord: Unit<Vector2<f32>>| {
+        Unit::new_normalize(
+            chord.into_inner() * 2.0 * chord.dot(&tangent)
+                - tangent.into_inner(),
+        )
+    };
+    let mut tangents = vec![mirror(inner(1), chord(0))];
+    tangents.extend((1..n - 1).map(inner));
+    tangents.push(mirror(inner(n - 2), chord(n - 2)));
+    tangents
+}
+
+/// Returns true when every point is within `tolerance` of the biarc, and
+/// the biarc isn't much longer than the points' path, which would mean it
+/// loops around between them.
+fn fits(
+    biarc: &[Option<ArcOrSegment>; 2],
+    points: &[Point2<f32>],
+    tolerance: f32,
+) -> bool {
+    let distance = |point: &Point2<f32>| {
+        biarc
+            .iter()
+            .flatten()
+            .map(|piece| piece.distance_to_point(point))
+            .fold(f32::INFINITY, f32::min)
+    };
+    let length: f32 = biarc.iter().flatten().map(ArcOrSegment::length).sum();
+    let path_length: f32 = points
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).norm())
+        .sum();
+    length <= path_length * MAX_STRETCH + tolerance
+        && points.iter().all(|point| distance(point) <= tolerance)
+}
+
+/// The pair of arcs from `start` to `end` which leaves along `start_tangent`
+/// and arrives along `end_tangent`, with the same distance from each end to
+/// the corner of its arc's tangent lines. Either piece can be missing when
+/// the biarc's joint lands on an end.
+fn biarc(
+    start: &Point2<f32>,
+    start_tangent: &Unit<Vector2<f32>>,
+    end: &Point2<f32>,
+    end_tangent: &Unit<Vector2<f32>>,
+    tolerance: f32,
+) -> Option<[Option<ArcOrSegment>; 2]> {
+    let chord = end - start;
+    let tangent_sum = start_tangent.into_inner() + end_tangent.into_inner();
+    let along = chord.dot(&tangent_sum);
+    let denominator = 2.0 * (1.0 - start_tangent.dot(end_tangent));
+    let distance = if denominator.abs() < 1e-6 {
+        // Parallel tangents, where the quadratic becomes linear.
+        let along_end = chord.dot(end_tangent);
+        if along_end.abs() < 1e-6 * chord.norm() {
+            return None;
+        }
+        chord.norm_squared() / (4.0 * along_end)
+    } else {
+        (-along
+            + math::sqrt(along * along + denominator * chord.norm_squared()))
+            / denominator
+    };
+    if !distance.is_finite() || distance <= 0.0 {
+        return None;
+    }
+    let joint = nalgebra::center(
+        &(start + start_tangent.scale(distance)),
+        &(end - end_tangent.scale(distance)),
+    );
+    let chord_line = Segment::new(*start, *end);
+    if chord_line.distance_to_point(&joint) <= tolerance * FLAT {
+        return Some([Some(ArcOrSegment::Segment(chord_line)), None]);
+    }
+    Some([
+        arc_or_segment(start, start_tangent, &joint, tolerance),
+        arc_or_segment(end, &-*end_tangent, &joint, tolerance).map(reverse),
+    ])
+}
+
+/// The arc which leaves `start` along `tangent` and passes through `end`,
+/// or a segment when the arc would be within a small fraction of the
+/// tolerance of its chord. Returns `None` when the points are the same.
+fn arc_or_segment(
+    start: &Point2<f32>,
+    tangent: &Unit<Vector2<f32>>,
+    end: &Point2<f32>,
+    tolerance: f32,
+) -> Option<ArcOrSegment> {
+    let chord = end - start;
+    let length_squared = chord.norm_squared();
+    if length_squared == 0.0 {
+        return None;
+    }
+    let segment = ArcOrSegment::Segment(Segment::new(*start, *end));
+
+    // The signed radius is positive when the arc turns left.
+    let normal = Vector2::new(-tangent.y, tangent.x);
+    let offset = normal.dot(&chord);
+    let radius = length_squared / (2.0 * offset);
+    if !radius.is_finite() {
+        return Some(segment);
+    }
+    let center = start + normal * radius;
+    let (from, to) = (start - center, end - center);
+    let mut sweep = math::atan2(from.perp(&to), from.dot(&to));
+    if radius > 0.0 && sweep < 0.0 {
+        sweep += TAU;
+    } else if radius < 0.0 && sweep > 0.0 {
+        sweep -= TAU;
+    }
+    let radius = radius.abs();
+    let sagitta = if sweep.abs() < core::f32::consts::PI {
+        radius * (1.0 - math::sin_cos(sweep * 0.5).1)
+    } else {
+        radius
+    };
+    // Very large arcs are also replaced, since f32 can't place their ends
+    // precisely relative to a faraway center.
+    if sagitta <= tolerance * FLAT || radius * f32::EPSILON > tolerance * FLAT {
+        return Some(segment);
+    }
+    Some(ArcOrSegment::Arc(Arc::new(
+        center,
+        radius,
+        math::atan2(from.y, from.x),
+        sweep,
+    )))
+}
+
+/// The same piece, traversed in the opposite direction.
+fn reverse(piece: ArcOrSegment) -> ArcOrSegment {
+    match piece {
+        ArcOrSegment::Arc(arc) => ArcOrSegment::Arc(Arc::new(
+            arc.center,
+            arc.radius,
+            arc.start_angle + arc.sweep,
+            -arc.sweep,
+        )),
+        ArcOrSegment::Segment(segment) => {
+            ArcOrSegment::Segment(Segment::new(segment.end, segment.start))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{biarcs, ArcOrSegment},
+        crate::{line::DistanceToPoint, polygon::Polyline, shape::Arc},
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::TAU,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    fn distance(pieces: &[ArcOrSegment], point: &Point2<f32>) -> f32 {
+        pieces
+            .iter()
+            .map(|piece| piece.distance_to_point(point))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn assert_fits(path: &Polyline, pieces: &[ArcOrSegment], tolerance: f32) {
+        for vertex in &path.vertices {
+            let distance = distance(pieces, vertex);
+            assert!(distance <= tolerance, "{} is {} away", vertex, distance);
+        }
+        for pair in pieces.windows(2) {
+            assert_relative_eq!(
+                pair[0].end(),
+                pair[1].start(),
+                epsilon = tolerance * 0.1
+            );
+        }
+        assert_relative_eq!(
+            pieces[0].start(),
+            path.vertices[0],
+            epsilon = tolerance * 0.1
+        );
+        assert_relative_eq!(
+            pieces.last().unwrap().end(),
+            *path.vertices.last().unwrap(),
+            epsilon = tolerance * 0.1
+        );
+    }
+
+    #[test]
+    pub fn sampled_circles_should_fit_a_few_arcs_with_the_same_radius() {
+        let circle = Arc::new(point![-3.0, 7.0], 25.0, 1.0, TAU);
+        let path = circle.to_polyline_n(720);
+
+        let pieces = biarcs(&path, 0.01);
+
+        assert!(pieces.len() <= 8, "{} pieces", pieces.len());
+        assert_fits(&path, &pieces, 0.01);
+        for piece in &pieces {
+            let ArcOrSegment::Arc(arc) = piece else {
+                panic!("{:?} isn't an arc", piece);
+            };
+            assert!((arc.radius - 25.0).abs() <= 0.25, "{}", arc.radius);
+        }
+    }
+
+    #[test]
+    pub fn corners_should_be_kept_as_breakpoints() {
+        // A dense "L" with a sharp corner at (10, 0).
+        let mut vertices: Vec<Point2<f32>> =
+            (0..=100).map(|i| point![i as f32 * 0.1, 0.0]).collect();
+        vertices.extend((1..=100).map(|i| point![10.0, i as f32 * 0.1]));
+        let path = Polyline::new(vertices);
+
+        let pieces = biarcs(&path, 0.01);
+
+        assert_eq!(pieces.len(), 2);
+        assert_fits(&path, &pieces, 0.01);
+        assert!(pieces
+            .iter()
+            .all(|piece| matches!(piece, ArcOrSegment::Segment(_))));
+        assert_eq!(pieces[0].end(), point![10.0, 0.0]);
+    }
+
+    #[test]
+    pub fn short_and_repeated_paths_should_be_segments() {
+        let repeated = Polyline::new(vec![point![1.0, 1.0]; 3]);
+        let pair = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![0.0, 0.0],
+            point![3.0, 4.0],
+        ]);
+
+        assert!(biarcs(&repeated, 0.1).is_empty());
+        assert!(biarcs(&Polyline::new(vec![]), 0.1).is_empty());
+        assert_eq!(biarcs(&pair, 0.1).len(), 1);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn smooth_curves_should_fit_within_the_tolerance(
+            amplitude in 0.0f32..=3.0,
+            frequency in 0.1f32..=1.0,
+            phase in 0.0f32..=6.0,
+            tolerance in 0.001f32..=0.1,
+        ) {
+            let path = Polyline::new(
+                (0..=2000)
+                    .map(|i| {
+                        let x = i as f32 * 0.01;
+                        point![x, amplitude * (x * frequency + phase).sin()]
+                    })
+                    .collect(),
+            );
+
+            let pieces = biarcs(&path, tolerance);
+
+            assert_fits(&path, &pieces, tolerance);
+            prop_assert!(pieces.len() * 10 < path.vertices.len());
+        }
+    }
+}
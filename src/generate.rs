@@ -0,0 +1,777 @@
+//! Generators for common shapes and layout scaffolding like grids and
+//! checkerboards.
+
+use {
+    crate::{
+        hull::convex_hull,
+        line::Segment,
+        math,
+        polygon::Polygon,
+        random::SplitMix64,
+        shape::{segments_for_sweep, Aabb},
+    },
+    alloc::vec::Vec,
+    core::{
+        cmp::Ordering,
+        f32::consts::{FRAC_PI_2, PI, TAU},
+    },
+    nalgebra::{Point2, Vector2},
+};
+
+/// The fewest segments used to approximate each ring in [`radial_grid`].
+const MIN_RING_SEGMENTS: usize = 64;
+
+/// The lines of a grid with `cols` columns and `rows` rows.
+///
+/// Following the fence-post rule, there are `cols + 1` vertical lines
+/// followed by `rows + 1` horizontal lines, including the lines on the
+/// box's boundary. Vertical lines are ordered left to right and point up,
+/// horizontal lines are ordered bottom to top and point right. Returns no
+/// lines when `cols` or `rows` is zero.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{generate::grid_lines, line::Segment, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+///     let lines = grid_lines(&bounds, 2, 1);
+///
+///     assert_eq!(lines.len(), 3 + 2);
+///     assert_eq!(lines[1], Segment::new(point![2.0, 0.0], point![2.0, 2.0]));
+///
+pub fn grid_lines(bounds: &Aabb, cols: usize, rows: usize) -> Vec<Segment> {
+    if cols == 0 || rows == 0 {
+        return Vec::new();
+    }
+    let xs = divide(bounds.min.x, bounds.max.x, cols);
+    let ys = divide(bounds.min.y, bounds.max.y, rows);
+    let (bottom, top) = (bounds.min.y, bounds.max.y);
+    let (left, right) = (bounds.min.x, bounds.max.x);
+    let vertical = xs
+        .iter()
+        .map(|&x| Segment::new(Point2::new(x, bottom), Point2::new(x, top)));
+    let horizontal = ys
+        .iter()
+        .map(|&y| Segment::new(Point2::new(left, y), Point2::new(right, y)));
+    vertical.chain(horizontal).collect()
+}
+
+/// The cells of a grid with `cols` columns and `rows` rows.
+///
+/// Cells are in row-major order starting from the bottom left, so the cell
+/// in column `c` and row `r` is at index `r * cols + c`. Neighboring cells
+/// share their boundary coordinates exactly. Returns no cells when `cols`
+/// or `rows` is zero.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{generate::grid_cells, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+///     let cells = grid_cells(&bounds, 2, 2);
+///
+///     assert_eq!(cells.len(), 4);
+///     assert_eq!(cells[1], Aabb::new(point![2.0, 0.0], point![4.0, 1.0]));
+///
+pub fn grid_cells(bounds: &Aabb, cols: usize, rows: usize) -> Vec<Aabb> {
+    if cols == 0 || rows == 0 {
+        return Vec::new();
+    }
+    let xs = divide(bounds.min.x, bounds.max.x, cols);
+    let ys = divide(bounds.min.y, bounds.max.y, rows);
+    ys.windows(2)
+        .flat_map(|y| {
+            xs.windows(2).map(move |x| {
+                Aabb::new(Point2::new(x[0], y[0]), Point2::new(x[1], y[1]))
+            })
+        })
+        .collect()
+}
+
+/// Every other cell of a grid, as counterclockwise polygons.
+///
+/// The bottom left cell is included, along with every cell whose column
+/// and row add up to an even number. The cells are in the same order as
+/// [`grid_cells`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{generate::checkerboard, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![3.0, 3.0]);
+///
+///     assert_eq!(checkerboard(&bounds, 3, 3).len(), 5);
+///
+pub fn checkerboard(bounds: &Aabb, cols: usize, rows: usize) -> Vec<Polygon> {
+    grid_cells(bounds, cols, rows)
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| (index / cols + index % cols) % 2 == 0)
+        .map(|(_, cell)| Polygon::new(cell.corners().to_vec()))
+        .collect()
+}
+
+/// Concentric rings crossed by evenly spaced spokes.
+///
+/// Each ring is approximated by at least 64 segments, rounded up to a
+/// multiple of `spokes` so that every spoke meets every ring at a vertex.
+/// The rings come first, in the order of `radii`, followed by the spokes.
+/// The first spoke points along the positive x axis and the rest follow
+/// counterclockwise, each running from the center out to the largest
+/// radius. Radii which aren't positive are skipped.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::generate::radial_grid,
+///         nalgebra::point,
+///     };
+///
+///     // Two rings of 64 segments and 8 spokes
+///     let segments = radial_grid(point![0.0, 0.0], &[1.0, 2.0], 8);
+///
+///     assert_eq!(segments.len(), 64 * 2 + 8);
+///     assert_eq!(segments[128].end, point![2.0, 0.0]);
+///
+pub fn radial_grid(
+    center: Point2<f32>,
+    radii: &[f32],
+    spokes: usize,
+) -> Vec<Segment> {
+    let radii: Vec<f32> = radii.iter().copied().filter(|&r| r > 0.0).collect();
+    let ring_segments = if spokes == 0 {
+        MIN_RING_SEGMENTS
+    } else {
+        MIN_RING_SEGMENTS.div_ceil(spokes) * spokes
+    };
+    let directions: Vec<Vector2<f32>> = (0..ring_segments)
+        .map(|i| polar(i as f32 * TAU / ring_segments as f32))
+        .collect();
+
+    let mut segments = Vec::with_capacity(radii.len() * ring_segments + spokes);
+    for &radius in &radii {
+        let ring: Vec<Point2<f32>> =
+            directions.iter().map(|d| center + d * radius).collect();
+        segments.extend(
+            (0..ring_segments)
+                .map(|i| Segment::new(ring[i], ring[(i + 1) % ring_segments])),
+        );
+    }
+    if let Some(outer) = radii.iter().copied().reduce(f32::max) {
+        let step = ring_segments / spokes.max(1);
+        segments.extend((0..spokes).map(|i| {
+            Segment::new(center, center + directions[i * step] * outer)
+        }));
+    }
+    segments
+}
+
+/// A rectangle with rounded corners, as a counterclockwise polygon.
+///
+/// The corners are quarter circles approximated with chords which stay
+/// within `max_error` of the circle, like [`Circle::to_polygon`]. The
+/// radius is clamped to half of the rectangle's shorter side. A radius of
+/// zero produces the rectangle's four corners, and a radius of exactly half
+/// the shorter side produces a stadium (or a circle, for squares) with no
+/// repeated vertices where the straight sides vanish.
+///
+/// The polygon starts at the bottom of the bottom right corner.
+///
+/// [`Circle::to_polygon`]: crate::shape::Circle::to_polygon
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{generate::rounded_rect, shape::Aabb},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+///
+///     assert_eq!(rounded_rect(&bounds, 0.0, 0.01).vertices, bounds.corners());
+///
+///     // Every corner removes a square of r² and adds a quarter circle
+///     let rounded = rounded_rect(&bounds, 0.5, 0.0001);
+///     let area = 8.0 - (4.0 - core::f32::consts::PI) * 0.25;
+///     assert_relative_eq!(rounded.area(), area, max_relative = 1e-3);
+///
+pub fn rounded_rect(
+    aabb: &Aabb,
+    corner_radius: f32,
+    max_error: f32,
+) -> Polygon {
+    let (width, height) = (aabb.width(), aabb.height());
+    let radius = corner_radius.min(width * 0.5).min(height * 0.5);
+    if radius.is_nan() || radius <= 0.0 {
+        return Polygon::new(aabb.corners().to_vec());
+    }
+
+    // The centers of the corner circles. When the straight sides vanish,
+    // the opposite corners share a center so the arcs meet exactly.
+    let center = aabb.center();
+    let inner_x = if radius * 2.0 >= width {
+        (center.x, center.x)
+    } else {
+        (aabb.min.x + radius, aabb.max.x - radius)
+    };
+    let inner_y = if radius * 2.0 >= height {
+        (center.y, center.y)
+    } else {
+        (aabb.min.y + radius, aabb.max.y - radius)
+    };
+    let corners = [
+        Point2::new(inner_x.1, inner_y.0),
+        Point2::new(inner_x.1, inner_y.1),
+        Point2::new(inner_x.0, inner_y.1),
+        Point2::new(inner_x.0, inner_y.0),
+    ];
+    // The direction from each corner's center to where its arc starts.
+    let axes = [
+        Vector2::new(0.0, -1.0),
+        Vector2::new(1.0, 0.0),
+        Vector2::new(0.0, 1.0),
+        Vector2::new(-1.0, 0.0),
+    ];
+
+    // Where a corner circle touches the side of the rectangle its axis
+    // points at, exactly on the side.
+    let touch = |circle: &Point2<f32>, axis: &Vector2<f32>| match axis {
+        a if a.x > 0.0 => Point2::new(aabb.max.x, circle.y),
+        a if a.x < 0.0 => Point2::new(aabb.min.x, circle.y),
+        a if a.y > 0.0 => Point2::new(circle.x, aabb.max.y),
+        _ => Point2::new(circle.x, aabb.min.y),
+    };
+
+    let n = segments_for_sweep(radius, FRAC_PI_2, max_error);
+    let mut vertices: Vec<Point2<f32>> = Vec::with_capacity(4 * (n + 1));
+    for (corner, (circle, start)) in corners.iter().zip(axes).enumerate() {
+        let end = axes[(corner + 1) % 4];
+        let angle = -FRAC_PI_2 + corner as f32 * FRAC_PI_2;
+        for i in 0..=n {
+            let vertex = if i == 0 {
+                touch(circle, &start)
+            } else if i == n {
+                touch(circle, &end)
+            } else {
+                circle + polar(angle + FRAC_PI_2 * i as f32 / n as f32) * radius
+            };
+            if vertices.last() != Some(&vertex) {
+                vertices.push(vertex);
+            }
+        }
+    }
+    if vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+    Polygon::new(vertices)
+}
+
+/// A regular polygon with `sides` sides, as a counterclockwise polygon.
+///
+/// The vertices are on a circle with radius `circumradius`. The first
+/// vertex is at the angle `rotation`, measured counterclockwise from the
+/// positive x axis. `sides` is clamped to at least three.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::generate::regular_polygon,
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     // A square standing on one of its corners
+///     let diamond = regular_polygon(point![0.0, 0.0], 1.0, 4, 0.0);
+///
+///     assert_eq!(diamond.vertices.len(), 4);
+///     assert_relative_eq!(diamond.area(), 2.0);
+///
+pub fn regular_polygon(
+    center: Point2<f32>,
+    circumradius: f32,
+    sides: usize,
+    rotation: f32,
+) -> Polygon {
+    let sides = sides.max(3);
+    Polygon::new(
+        (0..sides)
+            .map(|i| {
+                let angle = rotation + i as f32 * TAU / sides as f32;
+                center + polar(angle) * circumradius
+            })
+            .collect(),
+    )
+}
+
+/// A star with `points` points, as a counterclockwise polygon.
+///
+/// The tips of the star are on a circle with radius `outer_radius` and the
+/// notches between them are on a circle with radius `inner_radius`. The
+/// first tip is at the angle `rotation`, and each notch is halfway between
+/// its neighboring tips. `points` is clamped to at least two.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::generate::star,
+///         nalgebra::point,
+///     };
+///
+///     let star = star(point![0.0, 0.0], 2.0, 1.0, 5, 0.0);
+///
+///     assert_eq!(star.vertices.len(), 10);
+///     assert_eq!(star.vertices[0], point![2.0, 0.0]);
+///
+pub fn star(
+    center: Point2<f32>,
+    outer_radius: f32,
+    inner_radius: f32,
+    points: usize,
+    rotation: f32,
+) -> Polygon {
+    let points = points.max(2);
+    Polygon::new(
+        (0..points * 2)
+            .map(|i| {
+                let angle = rotation + i as f32 * PI / points as f32;
+                let radius = if i % 2 == 0 {
+                    outer_radius
+                } else {
+                    inner_radius
+                };
+                center + polar(angle) * radius
+            })
+            .collect(),
+    )
+}
+
+/// A random convex polygon with `n` vertices which fits inside `bounds`.
+///
+/// This is Valtr's algorithm: random steps in x and y are paired up and
+/// sorted by angle, so chaining them end to end makes a convex loop. The
+/// polygon is stretched to touch all four sides of the bounds. The same
+/// seed always makes the same polygon. `n` is clamped to at least three.
+///
+/// The vertices are counterclockwise. Rounding to `f32` can leave a vertex
+/// so close to the line through its neighbors that it's dropped, so very
+/// large polygons can have slightly fewer than `n` vertices.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{generate::random_convex_polygon, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+///     let polygon = random_convex_polygon(12, &bounds, 7);
+///
+///     assert_eq!(polygon.vertices.len(), 12);
+///     assert!(polygon.is_convex(0.0));
+///     assert_eq!(polygon, random_convex_polygon(12, &bounds, 7));
+///
+pub fn random_convex_polygon(n: usize, bounds: &Aabb, seed: u64) -> Polygon {
+    let n = n.max(3);
+    let mut rng = SplitMix64(seed);
+    let xs = valtr_steps(n, &mut rng);
+    let mut ys = valtr_steps(n, &mut rng);
+    rng.shuffle(&mut ys);
+
+    let mut steps: Vec<(f64, f64)> = xs.into_iter().zip(ys).collect();
+    steps.sort_by(|a, b| by_angle(*a, *b));
+
+    let mut corners = Vec::with_capacity(n);
+    let (mut x, mut y) = (0.0, 0.0);
+    for (dx, dy) in steps {
+        corners.push((x, y));
+        x += dx;
+        y += dy;
+    }
+
+    // Stretch the loop to fill the bounds.
+    let fold = |f: fn(f64, f64) -> f64, pick: fn(&(f64, f64)) -> f64| {
+        corners.iter().map(pick).fold(pick(&corners[0]), f)
+    };
+    let (left, right) = (fold(f64::min, |c| c.0), fold(f64::max, |c| c.0));
+    let (bottom, top) = (fold(f64::min, |c| c.1), fold(f64::max, |c| c.1));
+    let vertices: Vec<Point2<f32>> = corners
+        .iter()
+        .map(|&(x, y)| {
+            let u = (x - left) / (right - left);
+            let v = (y - bottom) / (top - bottom);
+            Point2::new(
+                bounds.min.x + (u * bounds.width() as f64) as f32,
+                bounds.min.y + (v * bounds.height() as f64) as f32,
+            )
+        })
+        .collect();
+
+    // The loop is convex, but rounding can leave nearly straight corners
+    // bent the wrong way, so let the hull straighten them out.
+    convex_hull(&vertices).unwrap_or_else(|| Polygon::new(vertices))
+}
+
+/// A random simple polygon with `n` vertices inside `bounds`.
+///
+/// Random points in the bounds are connected in order of their angle around
+/// their centroid, which makes a star-shaped polygon whose edges never
+/// cross. The same seed always makes the same polygon. `n` is clamped to at
+/// least three.
+///
+/// The vertices are counterclockwise. When the bounds have no area, neither
+/// does the polygon.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{generate::random_simple_polygon, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+///     let polygon = random_simple_polygon(30, &bounds, 7);
+///
+///     assert_eq!(polygon.vertices.len(), 30);
+///     assert!(polygon.is_simple());
+///     assert_eq!(polygon, random_simple_polygon(30, &bounds, 7));
+///
+pub fn random_simple_polygon(n: usize, bounds: &Aabb, seed: u64) -> Polygon {
+    let n = n.max(3);
+    let mut rng = SplitMix64(seed);
+    loop {
+        let points: Vec<Point2<f32>> = (0..n)
+            .map(|_| {
+                Point2::new(
+                    bounds.min.x + rng.next_f64() as f32 * bounds.width(),
+                    bounds.min.y + rng.next_f64() as f32 * bounds.height(),
+                )
+            })
+            .collect();
+        let center = points
+            .iter()
+            .fold(Vector2::zeros(), |sum, p| sum + p.coords)
+            / n as f32;
+        let mut offsets: Vec<Vector2<f32>> =
+            points.iter().map(|p| p.coords - center).collect();
+        offsets.sort_by(|a, b| {
+            by_angle((a.x as f64, a.y as f64), (b.x as f64, b.y as f64))
+                .then(a.norm_squared().total_cmp(&b.norm_squared()))
+        });
+
+        let polygon = Polygon::new(
+            offsets.iter().map(|v| Point2::from(center + v)).collect(),
+        );
+        // The centroid is never outside the points, so the polygon is
+        // simple unless rounding or a repeated point got in the way.
+        if bounds.width() * bounds.height() == 0.0
+            || (polygon.signed_area() > 0.0 && polygon.is_simple())
+        {
+            return polygon;
+        }
+    }
+}
+
+/// The steps along one axis for Valtr's algorithm.
+///
+/// Sorted random values are split between two chains which both run from
+/// the smallest value to the largest, one forward and one back, so the
+/// steps sum to zero.
+fn valtr_steps(n: usize, rng: &mut SplitMix64) -> Vec<f64> {
+    let mut values: Vec<f64> = (0..n).map(|_| rng.next_f64()).collect();
+    values.sort_by(f64::total_cmp);
+    let (min, max) = (values[0], values[n - 1]);
+
+    let mut steps = Vec::with_capacity(n);
+    let (mut forward, mut back) = (min, min);
+    for &value in &values[1..n - 1] {
+        if rng.next_u64() & 1 == 0 {
+            steps.push(value - forward);
+            forward = value;
+        } else {
+            steps.push(back - value);
+            back = value;
+        }
+    }
+    steps.push(max - forward);
+    steps.push(back - max);
+    steps
+}
+
+/// Order vectors counterclockwise by angle, starting from the positive x
+/// axis, without any trigonometry.
+fn by_angle(a: (f64, f64), b: (f64, f64)) -> Ordering {
+    let half = |(x, y): (f64, f64)| y < 0.0 || (y == 0.0 && x < 0.0);
+    half(a)
+        .cmp(&half(b))
+        .then_with(|| (b.0 * a.1).total_cmp(&(a.0 * b.1)))
+}
+
+/// The unit vector at an angle, measured counterclockwise from the positive
+/// x axis.
+fn polar(angle: f32) -> Vector2<f32> {
+    let (sin, cos) = math::sin_cos(angle);
+    Vector2::new(cos, sin)
+}
+
+/// Divide a range into equal parts, returning `parts + 1` values which
+/// start and end exactly on the ends of the range.
+fn divide(start: f32, end: f32, parts: usize) -> Vec<f32> {
+    (0..=parts)
+        .map(|i| {
+            let t = i as f32 / parts as f32;
+            start * (1.0 - t) + end * t
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            checkerboard, grid_cells, grid_lines, radial_grid,
+            random_convex_polygon, random_simple_polygon, regular_polygon,
+            rounded_rect, star,
+        },
+        crate::shape::Aabb,
+        approx::assert_relative_eq,
+        core::f32::consts::{PI, TAU},
+        nalgebra::{point, Rotation2},
+    };
+
+    fn bounds() -> Aabb {
+        Aabb::new(point![-1.0, 2.0], point![2.5, 9.0])
+    }
+
+    #[test]
+    pub fn grid_lines_should_include_the_fence_posts() {
+        for (cols, rows) in [(1, 1), (3, 2), (7, 5)] {
+            let lines = grid_lines(&bounds(), cols, rows);
+
+            assert_eq!(lines.len(), cols + 1 + rows + 1);
+            let (vertical, horizontal) = lines.split_at(cols + 1);
+            assert!(vertical.iter().all(|s| s.start.x == s.end.x));
+            assert!(horizontal.iter().all(|s| s.start.y == s.end.y));
+            assert_eq!(vertical[0].start, bounds().min);
+            assert_eq!(vertical[cols].end, bounds().max);
+            assert_eq!(horizontal[rows].end, bounds().max);
+        }
+        assert!(grid_lines(&bounds(), 0, 3).is_empty());
+    }
+
+    #[test]
+    pub fn grid_cells_should_tile_the_bounds_exactly() {
+        let (cols, rows) = (3, 4);
+
+        let cells = grid_cells(&bounds(), cols, rows);
+
+        assert_eq!(cells.len(), cols * rows);
+        let area: f32 = cells.iter().map(|c| c.width() * c.height()).sum();
+        assert_relative_eq!(area, 3.5 * 7.0, max_relative = 1e-6);
+        for row in 0..rows {
+            for col in 0..cols - 1 {
+                let (a, b) =
+                    (cells[row * cols + col], cells[row * cols + col + 1]);
+                assert_eq!(a.max.x, b.min.x);
+                assert_eq!(a.min.y, b.min.y);
+            }
+        }
+        assert_eq!(cells[0].min, bounds().min);
+        assert_eq!(cells[cols * rows - 1].max, bounds().max);
+        assert!(grid_cells(&bounds(), 2, 0).is_empty());
+    }
+
+    #[test]
+    pub fn checkerboards_should_alternate_cells() {
+        assert_eq!(checkerboard(&bounds(), 4, 4).len(), 8);
+        assert_eq!(checkerboard(&bounds(), 3, 3).len(), 5);
+        assert_eq!(checkerboard(&bounds(), 1, 1).len(), 1);
+        assert!(checkerboard(&bounds(), 0, 1).is_empty());
+
+        // In a 2 column grid, the second row starts with the second cell.
+        let squares = checkerboard(&bounds(), 2, 2);
+        let cells = grid_cells(&bounds(), 2, 2);
+        assert_eq!(squares[0].vertices, cells[0].corners().to_vec());
+        assert_eq!(squares[1].vertices, cells[3].corners().to_vec());
+        assert!(squares.iter().all(|s| s.signed_area() > 0.0));
+    }
+
+    #[test]
+    pub fn radial_grids_should_meet_spokes_at_ring_vertices() {
+        let center = point![1.0, 1.0];
+
+        let segments = radial_grid(center, &[1.0, 0.0, 3.0], 5);
+
+        // 64 rounded up to a multiple of 5
+        let ring_segments = 65;
+        assert_eq!(segments.len(), 2 * ring_segments + 5);
+        let (rings, spokes) = segments.split_at(2 * ring_segments);
+        for spoke in spokes {
+            assert_eq!(spoke.start, center);
+            assert_relative_eq!(spoke.length(), 3.0, epsilon = 1e-5);
+            assert!(rings.iter().any(|s| s.start == spoke.end));
+        }
+        for segment in rings {
+            let radius = (segment.start - center).norm();
+            assert!((radius - 1.0).abs() < 1e-5 || (radius - 3.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    pub fn radial_grids_without_spokes_or_rings_should_be_partial() {
+        assert_eq!(radial_grid(point![0.0, 0.0], &[1.0], 0).len(), 64);
+        assert!(radial_grid(point![0.0, 0.0], &[], 6).is_empty());
+    }
+
+    #[test]
+    pub fn zero_radius_corners_should_be_exact() {
+        let bounds = bounds();
+
+        assert_eq!(
+            rounded_rect(&bounds, 0.0, 0.01).vertices,
+            bounds.corners().to_vec()
+        );
+        assert_eq!(
+            rounded_rect(&bounds, -1.0, 0.01).vertices,
+            bounds.corners().to_vec()
+        );
+    }
+
+    #[test]
+    pub fn rounded_rects_should_touch_every_side() {
+        let bounds = bounds();
+
+        let rounded = rounded_rect(&bounds, 1.0, 1e-4);
+
+        let on = |f: &dyn Fn(&nalgebra::Point2<f32>) -> bool| {
+            rounded.vertices.iter().filter(|v| f(v)).count()
+        };
+        assert_eq!(on(&|v| v.y == bounds.min.y), 2);
+        assert_eq!(on(&|v| v.x == bounds.max.x), 2);
+        assert_eq!(on(&|v| v.y == bounds.max.y), 2);
+        assert_eq!(on(&|v| v.x == bounds.min.x), 2);
+        assert!(rounded.signed_area() > 0.0);
+        assert_relative_eq!(
+            rounded.area(),
+            3.5 * 7.0 - (4.0 - PI),
+            max_relative = 1e-3
+        );
+    }
+
+    #[test]
+    pub fn half_side_radii_should_not_repeat_tangent_points() {
+        let stadium_bounds = Aabb::new(point![0.0, 0.0], point![2.0, 6.0]);
+        let square = Aabb::new(point![0.0, 0.0], point![2.0, 2.0]);
+
+        let stadium = rounded_rect(&stadium_bounds, 5.0, 1e-4);
+        let circle = rounded_rect(&square, 1.0, 1e-4);
+
+        for polygon in [&stadium, &circle] {
+            let n = polygon.vertices.len();
+            for i in 0..n {
+                assert_ne!(polygon.vertices[i], polygon.vertices[(i + 1) % n]);
+            }
+            assert!(polygon.signed_area() > 0.0);
+        }
+        // A circle made of four quarters which share their end points
+        assert_eq!(circle.vertices.len() % 4, 0);
+        assert_relative_eq!(circle.area(), PI, max_relative = 1e-3);
+        assert_eq!(stadium.vertices.len(), circle.vertices.len() + 2);
+    }
+
+    #[test]
+    pub fn regular_polygons_should_have_equal_sides() {
+        let hexagon = regular_polygon(point![1.0, 2.0], 3.0, 6, 0.25);
+
+        assert_eq!(hexagon.vertices.len(), 6);
+        for edge in hexagon.edges() {
+            assert_relative_eq!(edge.length(), 3.0, max_relative = 1e-5);
+        }
+        assert!(hexagon.signed_area() > 0.0);
+        assert_eq!(
+            regular_polygon(point![0.0, 0.0], 1.0, 1, 0.0)
+                .vertices
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    pub fn stars_should_be_symmetric_under_rotation() {
+        let points = 7;
+        let star = star(point![0.0, 0.0], 3.0, 1.2, points, 0.4);
+        let rotation = Rotation2::new(TAU / points as f32);
+
+        assert_eq!(star.vertices.len(), 2 * points);
+        assert!(star.signed_area() > 0.0);
+        let n = star.vertices.len();
+        for i in 0..n {
+            let rotated = rotation * star.vertices[i];
+            assert_relative_eq!(
+                rotated,
+                star.vertices[(i + 2) % n],
+                epsilon = 1e-5
+            );
+        }
+    }
+
+    #[test]
+    pub fn random_convex_polygons_should_be_convex_for_every_seed() {
+        for seed in 0..2000 {
+            let n = 3 + seed as usize % 40;
+
+            let polygon = random_convex_polygon(n, &bounds(), seed);
+
+            assert!(polygon.is_convex(0.0), "seed {}: {}", seed, polygon);
+            assert!(polygon.signed_area() > 0.0);
+            assert!(polygon.vertices.len() <= n);
+            assert!(polygon
+                .vertices
+                .iter()
+                .all(|v| bounds().contains_point(v)));
+            assert_eq!(polygon, random_convex_polygon(n, &bounds(), seed));
+        }
+    }
+
+    #[test]
+    pub fn random_simple_polygons_should_be_simple_for_every_seed() {
+        for seed in 0..2000 {
+            let n = 3 + seed as usize % 40;
+
+            let polygon = random_simple_polygon(n, &bounds(), seed);
+
+            assert!(polygon.is_simple(), "seed {}: {}", seed, polygon);
+            assert!(polygon.signed_area() > 0.0);
+            assert_eq!(polygon.vertices.len(), n);
+            assert!(polygon
+                .vertices
+                .iter()
+                .all(|v| bounds().contains_point(v)));
+            assert_eq!(polygon, random_simple_polygon(n, &bounds(), seed));
+        }
+    }
+
+    #[test]
+    pub fn random_polygons_should_differ_between_seeds() {
+        assert_ne!(
+            random_convex_polygon(8, &bounds(), 1),
+            random_convex_polygon(8, &bounds(), 2)
+        );
+        assert_ne!(
+            random_simple_polygon(8, &bounds(), 1),
+            random_simple_polygon(8, &bounds(), 2)
+        );
+
+        let flat = Aabb::new(point![0.0, 1.0], point![5.0, 1.0]);
+        assert_eq!(random_simple_polygon(1, &flat, 3).vertices.len(), 3);
+    }
+}
@@ -0,0 +1,818 @@
+//! Graphs built from the points and segments in a drawing.
+
+use {
+    crate::{
+        line::{
+            intersection::{
+                intersect_all_pairs, intersect_segments, SegmentIntersection,
+            },
+            Segment,
+        },
+        polygon::Polyline,
+        shape::BoundingBox,
+        spatial::Bvh,
+        weld::Welder,
+    },
+    alloc::{collections::BTreeSet, vec::Vec},
+    nalgebra::Point2,
+};
+
+/// An edge in a [`SegmentGraph`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GraphEdge {
+    /// The indices of the nodes at either end of the edge.
+    pub nodes: (usize, usize),
+
+    /// The edge's geometry, from the first node to the second node.
+    pub segment: Segment,
+}
+
+/// A graph with nodes at segment endpoints and intersections.
+///
+/// Every edge is a piece of an input segment which doesn't cross any other
+/// edge. This makes the graph useful for traversal problems like finding
+/// closed loops or planning a pen plotter's path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentGraph {
+    nodes: Vec<Point2<f32>>,
+    edges: Vec<GraphEdge>,
+    incident: Vec<Vec<usize>>,
+}
+
+/// Build a graph from a collection of segments.
+///
+/// Segments are split wherever they intersect each other. Points within
+/// `tolerance` of each other are merged into a single node. Pieces which
+/// collapse to a single node are dropped, and pieces which duplicate an
+/// existing edge (for example, where collinear segments overlap) are only
+/// included once.
+///
+/// # Example
+///
+/// Two crossing segments become four edges which meet in the middle.
+///
+///     use ::{
+///         compgeo::{graph::segment_graph, line::Segment},
+///         nalgebra::point,
+///     };
+///
+///     let graph = segment_graph(
+///         &[
+///             Segment::new(point![0.0, 0.0], point![2.0, 2.0]),
+///             Segment::new(point![0.0, 2.0], point![2.0, 0.0]),
+///         ],
+///         1e-4,
+///     );
+///
+///     assert_eq!(graph.nodes().len(), 5);
+///     assert_eq!(graph.edges().len(), 4);
+///
+///     let center = graph
+///         .nodes()
+///         .iter()
+///         .position(|p| *p == point![1.0, 1.0])
+///         .unwrap();
+///     assert_eq!(graph.degree(center), 4);
+///
+pub fn segment_graph(segments: &[Segment], tolerance: f32) -> SegmentGraph {
+    // Collect the parameters where each segment needs to be split.
+    let mut splits: Vec<Vec<f32>> =
+        segments.iter().map(|_| vec![0.0, 1.0]).collect();
+    for (i, j, intersection) in intersect_all_pairs(segments) {
+        let points = match intersection {
+            SegmentIntersection::None => continue,
+            SegmentIntersection::Point(p) => [p, p],
+            SegmentIntersection::Overlap(s) => [s.start, s.end],
+        };
+        for index in [i, j] {
+            let segment = &segments[index];
+            for p in &points {
+                splits[index].push(parameter_of(segment, p));
+            }
+        }
+    }
+
+    let mut welder = Welder::new(tolerance);
+    let mut seen = BTreeSet::new();
+    let mut pieces = Vec::new();
+    for (segment, params) in segments.iter().zip(splits.iter_mut()) {
+        params.sort_by(f32::total_cmp);
+        let direction = segment.end - segment.start;
+        let mut previous = welder.weld(&segment.start);
+        for &t in params.iter() {
+            let node = welder.weld(&(segment.start + direction * t));
+            if node != previous
+                && seen.insert((previous.min(node), previous.max(node)))
+            {
+                pieces.push((previous, node));
+            }
+            previous = node;
+        }
+    }
+
+    let nodes = welder.into_points();
+    let mut incident = vec![Vec::new(); nodes.len()];
+    let edges = pieces
+        .into_iter()
+        .enumerate()
+        .map(|(index, (a, b))| {
+            incident[a].push(index);
+            incident[b].push(index);
+            GraphEdge {
+                nodes: (a, b),
+                segment: Segment::new(nodes[a], nodes[b]),
+            }
+        })
+        .collect();
+
+    SegmentGraph {
+        nodes,
+        edges,
+        incident,
+    }
+}
+
+impl SegmentGraph {
+    /// The location of every node in the graph.
+    pub fn nodes(&self) -> &[Point2<f32>] {
+        &self.nodes
+    }
+
+    /// Every edge in the graph.
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// The indices of the edges which touch a node.
+    pub fn incident_edges(&self, node: usize) -> &[usize] {
+        &self.incident[node]
+    }
+
+    /// The number of edges which touch a node.
+    pub fn degree(&self, node: usize) -> usize {
+        self.incident[node].len()
+    }
+
+    /// The nodes which share an edge with the given node.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.incident[node]
+            .iter()
+            .map(move |&edge| self.opposite(edge, node))
+    }
+
+    /// The node at the other end of an edge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the edge doesn't touch `node`.
+    pub fn opposite(&self, edge: usize, node: usize) -> usize {
+        match self.edges[edge].nodes {
+            (a, b) if a == node => b,
+            (a, b) if b == node => a,
+            _ => panic!("edge {} does not touch node {}", edge, node),
+        }
+    }
+
+    /// The total length of every edge in the graph.
+    pub fn total_length(&self) -> f32 {
+        self.edges.iter().map(|edge| edge.segment.length()).sum()
+    }
+
+    /// Group the nodes into connected components.
+    ///
+    /// Each component lists its nodes in ascending order, and components are
+    /// ordered by their smallest node. Isolated nodes form their own
+    /// single-node components.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut components = Vec::new();
+        for root in 0..self.nodes.len() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                for neighbor in self.neighbors(node) {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        component.push(neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+}
+
+/// Decompose a graph's edges into as few polylines as possible.
+///
+/// This is the drawing order for a pen plotter which lifts the pen as rarely
+/// as possible. Every edge appears in exactly one polyline. A connected
+/// component with no odd-degree nodes is drawn as a single closed polyline,
+/// and a component with `k > 0` odd-degree nodes is drawn as exactly `k / 2`
+/// open polylines, which is the fewest possible.
+///
+/// The polylines are ordered (and reversed where needed) by repeatedly
+/// picking whichever remaining polyline starts closest to where the previous
+/// one ended, which keeps the pen's travel between them short.
+///
+/// # Example
+///
+/// A plus sign has four odd-degree ends, so it takes two strokes.
+///
+///     use ::{
+///         compgeo::{
+///             graph::{plot_order, segment_graph},
+///             line::Segment,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let graph = segment_graph(
+///         &[
+///             Segment::new(point![-1.0, 0.0], point![1.0, 0.0]),
+///             Segment::new(point![0.0, -1.0], point![0.0, 1.0]),
+///         ],
+///         1e-4,
+///     );
+///     let strokes = plot_order(&graph);
+///
+///     assert_eq!(strokes.len(), 2);
+///     assert!(strokes.iter().all(|stroke| stroke.vertices.len() == 3));
+///
+pub fn plot_order(graph: &SegmentGraph) -> Vec<Polyline> {
+    // Pair up odd-degree nodes with extra "pen up" edges so that every node
+    // has even degree. An Eulerian circuit of the augmented graph, cut at
+    // every pen up edge, is an optimal decomposition.
+    let mut links: Vec<(usize, usize, bool)> = graph
+        .edges
+        .iter()
+        .map(|edge| (edge.nodes.0, edge.nodes.1, false))
+        .collect();
+    let mut incident = graph.incident.clone();
+    let mut circuit_starts = Vec::new();
+    for component in graph.components() {
+        let mut odd: Vec<usize> = component
+            .iter()
+            .copied()
+            .filter(|&node| graph.degree(node) % 2 == 1)
+            .collect();
+        match (odd.first(), component.first()) {
+            (Some(&node), _) => circuit_starts.push(node),
+            (None, Some(&node)) if graph.degree(node) > 0 => {
+                circuit_starts.push(node)
+            }
+            _ => continue,
+        }
+        // Greedily match each odd node with its nearest unmatched partner.
+        while let Some(a) = odd.pop() {
+            let (index, _) = odd
+                .iter()
+                .enumerate()
+                .map(|(index, &b)| {
+                    (index, (graph.nodes[a] - graph.nodes[b]).norm_squared())
+                })
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .expect("every component has an even number of odd nodes");
+            let b = odd.swap_remove(index);
+            incident[a].push(links.len());
+            incident[b].push(links.len());
+            links.push((a, b, true));
+        }
+    }
+
+    let mut used = vec![false; links.len()];
+    let mut cursor = vec![0; incident.len()];
+    let mut chains = Vec::new();
+    for start in circuit_starts {
+        // Hierholzer's algorithm. Nodes are popped off the stack in circuit
+        // order along with the link which connects them to the next node.
+        let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+        let mut circuit = Vec::new();
+        while let Some(&(node, _)) = stack.last() {
+            let next = incident[node][cursor[node]..]
+                .iter()
+                .position(|&link| !used[link]);
+            match next {
+                Some(offset) => {
+                    cursor[node] += offset + 1;
+                    let link = incident[node][cursor[node] - 1];
+                    used[link] = true;
+                    let (a, b, _) = links[link];
+                    stack.push((if a == node { b } else { a }, Some(link)));
+                }
+                None => circuit.push(stack.pop().unwrap()),
+            }
+        }
+
+        // Rotate the circuit so it begins just after a pen up link, then cut
+        // it into chains at every pen up link.
+        let steps: Vec<(usize, usize)> = circuit
+            .windows(2)
+            .map(|pair| (pair[0].0, pair[0].1.unwrap()))
+            .collect();
+        let first = steps
+            .iter()
+            .position(|&(_, link)| links[link].2)
+            .map_or(0, |index| index + 1);
+        let mut vertices = Vec::new();
+        for i in 0..steps.len() {
+            let (node, link) = steps[(first + i) % steps.len()];
+            let (a, b, pen_up) = links[link];
+            if pen_up {
+                chains.push(Polyline::new(core::mem::take(&mut vertices)));
+                continue;
+            }
+            if vertices.is_empty() {
+                vertices.push(graph.nodes[node]);
+            }
+            vertices.push(graph.nodes[if a == node { b } else { a }]);
+        }
+        if !vertices.is_empty() {
+            chains.push(Polyline::new(vertices));
+        }
+    }
+
+    order_by_travel(chains)
+}
+
+/// A place where two edges in a drawing of a graph cross.
+///
+/// See [`crossing_pairs`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EdgeCrossing {
+    /// The indices of the crossing edges, with the smaller index first.
+    pub edges: (usize, usize),
+
+    /// Where the edges cross. For edges which overlap, this is the start of
+    /// the overlap along the first edge.
+    pub point: Point2<f32>,
+}
+
+/// Count the crossings in a straight-line drawing of a graph.
+///
+/// This measures how tangled the drawing is, and it's zero when the drawing
+/// is a planar embedding. See [`crossing_pairs`] for what counts as a
+/// crossing.
+///
+/// # Example
+///
+/// A square with both diagonals drawn inside it crosses once, but moving
+/// one corner inside the triangle formed by the others untangles it.
+///
+///     use ::{
+///         compgeo::graph::crossing_count,
+///         nalgebra::point,
+///     };
+///
+///     let edges = [(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (1, 3)];
+///     let square = [
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///     ];
+///     let untangled = [
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![2.0, 1.0],
+///         point![2.0, 4.0],
+///     ];
+///
+///     assert_eq!(crossing_count(&square, &edges), 1);
+///     assert_eq!(crossing_count(&untangled, &edges), 0);
+///
+pub fn crossing_count(
+    nodes: &[Point2<f32>],
+    edges: &[(usize, usize)],
+) -> usize {
+    crossing_pairs(nodes, edges).len()
+}
+
+/// Find every pair of edges which cross in a straight-line drawing of a
+/// graph.
+///
+/// Each edge is a pair of indices into `nodes`, drawn as a segment between
+/// them. Edges which share a node never cross, even when they overlap, since
+/// meeting at a shared node is how a drawing connects them. Any other pair
+/// of edges which touch counts as crossing: edges which cross in their
+/// interiors, an edge which passes through another edge's node, and
+/// collinear edges which overlap. Crossings are sorted by their edge
+/// indices. Candidate pairs are found with a [`Bvh`], so this is fast
+/// unless the drawing is very tangled.
+///
+/// # Panics
+///
+/// Panics when an edge refers to a node which doesn't exist.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::graph::crossing_pairs,
+///         nalgebra::point,
+///     };
+///
+///     let nodes = [
+///         point![0.0, 0.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///         point![2.0, 0.0],
+///     ];
+///     let edges = [(0, 1), (1, 2), (2, 3)];
+///
+///     let crossings = crossing_pairs(&nodes, &edges);
+///
+///     assert_eq!(crossings.len(), 1);
+///     assert_eq!(crossings[0].edges, (0, 2));
+///     assert_eq!(crossings[0].point, point![1.0, 1.0]);
+///
+pub fn crossing_pairs(
+    nodes: &[Point2<f32>],
+    edges: &[(usize, usize)],
+) -> Vec<EdgeCrossing> {
+    let segments: Vec<Segment> = edges
+        .iter()
+        .map(|&(a, b)| Segment::new(nodes[a], nodes[b]))
+        .collect();
+    let bvh = Bvh::new(segments.clone());
+    let adjacent = |(a, b): (usize, usize), (c, d): (usize, usize)| {
+        a == c || a == d || b == c || b == d
+    };
+    let mut crossings = Vec::new();
+    for (first, segment) in segments.iter().enumerate() {
+        for second in bvh.query_aabb(&segment.bounding_box()) {
+            if second <= first || adjacent(edges[first], edges[second]) {
+                continue;
+            }
+            let point = match intersect_segments(segment, &segments[second]) {
+                SegmentIntersection::None => continue,
+                SegmentIntersection::Point(point) => point,
+                SegmentIntersection::Overlap(overlap) => overlap.start,
+            };
+            crossings.push(EdgeCrossing {
+                edges: (first, second),
+                point,
+            });
+        }
+    }
+    crossings
+}
+
+/// Order polylines so each one starts near where the previous one ended.
+fn order_by_travel(mut chains: Vec<Polyline>) -> Vec<Polyline> {
+    let mut ordered: Vec<Polyline> = Vec::with_capacity(chains.len());
+    while !chains.is_empty() {
+        let Some(pen) = ordered.last().and_then(|c| c.vertices.last()) else {
+            ordered.push(chains.remove(0));
+            continue;
+        };
+        let travel = |p: &Point2<f32>| (p - pen).norm_squared();
+        let (index, reverse, _) = chains
+            .iter()
+            .enumerate()
+            .flat_map(|(index, chain)| {
+                let start = travel(&chain.vertices[0]);
+                let end = travel(&chain.vertices[chain.vertices.len() - 1]);
+                [(index, false, start), (index, true, end)]
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .unwrap();
+        let mut chain = chains.remove(index);
+        if reverse {
+            chain.vertices.reverse();
+        }
+        ordered.push(chain);
+    }
+    ordered
+}
+
+/// The parameter for a point on a segment, where 0 is the start and 1 is the
+/// end.
+pub(crate) fn parameter_of(segment: &Segment, point: &Point2<f32>) -> f32 {
+    let direction = segment.end - segment.start;
+    let length_squared = direction.norm_squared();
+    if length_squared == 0.0 {
+        return 0.0;
+    }
+    ((point - segment.start).dot(&direction) / length_squared).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            graph::{
+                crossing_count, crossing_pairs, plot_order, segment_graph,
+                SegmentGraph,
+            },
+            line::{
+                intersection::{intersect_segments, SegmentIntersection},
+                Segment,
+            },
+            polygon::Polyline,
+            test_support::segment,
+        },
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    /// The sorted node pairs for every edge in the graph.
+    fn graph_edges(graph: &SegmentGraph) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = graph
+            .edges()
+            .iter()
+            .map(|edge| {
+                (
+                    edge.nodes.0.min(edge.nodes.1),
+                    edge.nodes.0.max(edge.nodes.1),
+                )
+            })
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// The sorted node pairs for every segment in the polylines.
+    fn polyline_edges(
+        graph: &SegmentGraph,
+        polylines: &[Polyline],
+    ) -> Vec<(usize, usize)> {
+        let node = |p| graph.nodes().iter().position(|n| *n == p).unwrap();
+        let mut edges: Vec<(usize, usize)> = polylines
+            .iter()
+            .flat_map(|polyline| polyline.segments())
+            .map(|s| {
+                let (a, b) = (node(s.start), node(s.end));
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    /// The fewest polylines which can cover every edge in the graph.
+    fn lower_bound(graph: &SegmentGraph) -> usize {
+        graph
+            .components()
+            .iter()
+            .filter(|component| graph.degree(component[0]) > 0)
+            .map(|component| {
+                let odd = component
+                    .iter()
+                    .filter(|&&node| graph.degree(node) % 2 == 1)
+                    .count();
+                (odd / 2).max(1)
+            })
+            .sum()
+    }
+
+    #[test]
+    pub fn a_grid_of_crossing_segments_should_split_at_every_crossing() {
+        // Three horizontal and three vertical lines which extend past each
+        // other like a hash sign.
+        let mut segments = Vec::new();
+        for i in 1..=3 {
+            let c = i as f32;
+            segments.push(Segment::new(point![0.0, c], point![4.0, c]));
+            segments.push(Segment::new(point![c, 0.0], point![c, 4.0]));
+        }
+
+        let graph = segment_graph(&segments, 1e-4);
+
+        // 9 crossings plus 12 free endpoints
+        assert_eq!(graph.nodes().len(), 21);
+        // every line is split into 4 pieces
+        assert_eq!(graph.edges().len(), 24);
+        assert_relative_eq!(graph.total_length(), 24.0);
+
+        let degrees: Vec<usize> =
+            (0..graph.nodes().len()).map(|n| graph.degree(n)).collect();
+        assert_eq!(degrees.iter().filter(|&&d| d == 4).count(), 9);
+        assert_eq!(degrees.iter().filter(|&&d| d == 1).count(), 12);
+    }
+
+    #[test]
+    pub fn a_closed_grid_should_share_nodes_at_corners_and_junctions() {
+        // A 2x2 grid of cells, like a window with four panes.
+        let mut segments = Vec::new();
+        for i in 0..=2 {
+            let c = i as f32;
+            segments.push(Segment::new(point![0.0, c], point![2.0, c]));
+            segments.push(Segment::new(point![c, 0.0], point![c, 2.0]));
+        }
+
+        let graph = segment_graph(&segments, 1e-4);
+
+        assert_eq!(graph.nodes().len(), 9);
+        assert_eq!(graph.edges().len(), 12);
+        assert_relative_eq!(graph.total_length(), 12.0);
+    }
+
+    #[test]
+    pub fn overlapping_collinear_segments_should_not_duplicate_edges() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![3.0, 0.0]),
+            Segment::new(point![2.0, 0.0], point![5.0, 0.0]),
+        ];
+
+        let graph = segment_graph(&segments, 1e-4);
+
+        assert_eq!(graph.nodes().len(), 4);
+        assert_eq!(graph.edges().len(), 3);
+        assert_relative_eq!(graph.total_length(), 5.0);
+    }
+
+    #[test]
+    pub fn nearly_coincident_endpoints_should_be_merged() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![1.0, 0.0]),
+            Segment::new(point![1.00001, 0.00001], point![1.0, 1.0]),
+            Segment::new(point![1.0, 1.0], point![0.0, 0.0]),
+        ];
+
+        let graph = segment_graph(&segments, 1e-3);
+
+        assert_eq!(graph.nodes().len(), 3);
+        assert!((0..3).all(|node| graph.degree(node) == 2));
+        let mut neighbors: Vec<usize> = graph.neighbors(0).collect();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![1, 2]);
+    }
+
+    #[test]
+    pub fn a_closed_loop_should_be_plotted_in_one_stroke() {
+        let graph = segment_graph(
+            &[
+                Segment::new(point![0.0, 0.0], point![1.0, 0.0]),
+                Segment::new(point![1.0, 0.0], point![1.0, 1.0]),
+                Segment::new(point![1.0, 1.0], point![0.0, 1.0]),
+                Segment::new(point![0.0, 1.0], point![0.0, 0.0]),
+            ],
+            1e-4,
+        );
+
+        let strokes = plot_order(&graph);
+
+        assert_eq!(strokes.len(), 1);
+        assert!(strokes[0].is_closed());
+        assert_eq!(polyline_edges(&graph, &strokes), graph_edges(&graph));
+    }
+
+    #[test]
+    pub fn grids_should_be_plotted_with_the_fewest_strokes() {
+        let mut segments = Vec::new();
+        for i in 0..=2 {
+            let c = i as f32;
+            segments.push(Segment::new(point![0.0, c], point![2.0, c]));
+            segments.push(Segment::new(point![c, 0.0], point![c, 2.0]));
+            segments
+                .push(Segment::new(point![5.0, c + 1.0], point![9.0, c + 1.0]));
+            segments
+                .push(Segment::new(point![c + 6.0, 0.0], point![c + 6.0, 4.0]));
+        }
+        let graph = segment_graph(&segments, 1e-4);
+        assert_eq!(graph.components().len(), 2);
+
+        let strokes = plot_order(&graph);
+
+        // the closed grid has 4 odd nodes and the open grid has 12
+        assert_eq!(strokes.len(), 2 + 6);
+        assert_eq!(strokes.len(), lower_bound(&graph));
+        assert_eq!(polyline_edges(&graph, &strokes), graph_edges(&graph));
+    }
+
+    #[test]
+    pub fn strokes_should_start_near_the_previous_stroke() {
+        let graph = segment_graph(
+            &[
+                Segment::new(point![0.0, 0.0], point![1.0, 0.0]),
+                Segment::new(point![10.0, 0.0], point![11.0, 0.0]),
+                Segment::new(point![2.0, 0.0], point![3.0, 0.0]),
+            ],
+            1e-4,
+        );
+
+        let strokes = plot_order(&graph);
+
+        assert_eq!(strokes.len(), 3);
+        assert_eq!(
+            strokes[1].vertices,
+            vec![point![2.0, 0.0], point![3.0, 0.0]]
+        );
+        assert_eq!(
+            strokes[2].vertices,
+            vec![point![10.0, 0.0], point![11.0, 0.0]]
+        );
+    }
+
+    #[test]
+    pub fn k4_should_cross_once_unless_drawn_planar() {
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+        let tangled = [
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![0.0, 1.0],
+            point![1.0, 1.0],
+        ];
+        let planar = [
+            point![0.0, 0.0],
+            point![3.0, 0.0],
+            point![0.0, 3.0],
+            point![1.0, 1.0],
+        ];
+
+        let crossings = crossing_pairs(&tangled, &edges);
+
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].edges, (2, 3));
+        assert_eq!(crossings[0].point, point![0.5, 0.5]);
+        assert_eq!(crossing_count(&tangled, &edges), 1);
+        assert_eq!(crossing_count(&planar, &edges), 0);
+    }
+
+    #[test]
+    pub fn only_edges_without_shared_nodes_should_cross() {
+        let nodes = [
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![4.0, 0.0],
+            point![1.0, 0.0],
+            point![3.0, 0.0],
+            point![2.0, 2.0],
+        ];
+
+        // Overlapping edges which share a node are connected, not crossed.
+        assert_eq!(crossing_count(&nodes, &[(0, 2), (0, 1)]), 0);
+        // Collinear overlaps and edges through another edge's node cross.
+        let overlap = crossing_pairs(&nodes, &[(0, 1), (3, 4)]);
+        assert_eq!(overlap.len(), 1);
+        assert_eq!(overlap[0].point, point![1.0, 0.0]);
+        assert_eq!(crossing_count(&nodes, &[(0, 2), (1, 5)]), 1);
+        assert_eq!(crossing_count(&nodes, &[]), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn crossings_should_match_every_nonadjacent_pair(
+            nodes in proptest::collection::vec(
+                (-10.0f32..=10.0, -10.0f32..=10.0)
+                    .prop_map(|(x, y)| point![x, y]),
+                2..10,
+            ),
+            picks in proptest::collection::vec((0usize..10, 0usize..10), 0..12),
+        ) {
+            let edges: Vec<(usize, usize)> = picks
+                .iter()
+                .map(|&(a, b)| (a % nodes.len(), b % nodes.len()))
+                .collect();
+
+            let found: Vec<(usize, usize)> = crossing_pairs(&nodes, &edges)
+                .iter()
+                .map(|crossing| crossing.edges)
+                .collect();
+
+            let segment = |(a, b): (usize, usize)| {
+                Segment::new(nodes[a], nodes[b])
+            };
+            let mut expected = Vec::new();
+            for i in 0..edges.len() {
+                for j in i + 1..edges.len() {
+                    let ((a, b), (c, d)) = (edges[i], edges[j]);
+                    if a == c || a == d || b == c || b == d {
+                        continue;
+                    }
+                    let meeting =
+                        intersect_segments(&segment(edges[i]), &segment(edges[j]));
+                    if meeting != SegmentIntersection::None {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            prop_assert_eq!(found, expected);
+        }
+
+        #[test]
+        fn plot_order_should_draw_every_edge_exactly_once(
+            segments in proptest::collection::vec(
+                segment(-10.0..=10.0, 0.5),
+                1..12,
+            )
+        ) {
+            let graph = segment_graph(&segments, 1e-3);
+
+            let strokes = plot_order(&graph);
+
+            prop_assert_eq!(
+                polyline_edges(&graph, &strokes),
+                graph_edges(&graph)
+            );
+            prop_assert_eq!(strokes.len(), lower_bound(&graph));
+        }
+    }
+}
@@ -0,0 +1,549 @@
+//! Fill regions with evenly spaced parallel lines.
+//!
+//! Hatching is how pen plotters shade an area. Each hatch line is a straight
+//! line across the region, cut into segments wherever it crosses a boundary.
+//! Crossings are paired with the even-odd rule, so hatch lines stop at the
+//! edges of holes and start again on the other side. Polygons can also be
+//! filled with rings which follow their outline inward.
+
+use {
+    crate::{
+        line::{DistanceToPoint, Segment},
+        math,
+        polygon::{cleanup_offset, Polygon, Region},
+    },
+    alloc::vec::Vec,
+    core::f32::consts::FRAC_PI_2,
+    nalgebra::{Point2, Vector2},
+};
+
+/// Fill a region with parallel segments.
+///
+/// The segments run at `angle` radians counterclockwise from the x axis.
+/// Hatch lines are `spacing` apart and are placed where the signed distance
+/// from the origin, measured perpendicular to the lines, is `offset` plus a
+/// multiple of `spacing`. Because the lines don't depend on the region's
+/// position, neighboring regions hatched with the same parameters line up.
+///
+/// Every segment points along the hatch direction. Returns no segments when
+/// `spacing` isn't positive.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{hatch::hatch_region, polygon::{Polygon, Region}},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = |min: f32, max: f32| {
+///         Polygon::new(vec![
+///             point![min, min],
+///             point![max, min],
+///             point![max, max],
+///             point![min, max],
+///         ])
+///     };
+///     let frame = Region::new(square(0.0, 4.0), vec![square(1.0, 3.0)]);
+///
+///     let hatches = hatch_region(&frame, 0.0, 1.0, 0.5);
+///
+///     // y = 0.5 and 3.5 cross the whole frame, y = 1.5 and 2.5 are split
+///     // in two by the hole.
+///     assert_eq!(hatches.len(), 6);
+///     let total: f32 = hatches.iter().map(|s| s.length()).sum();
+///     assert_relative_eq!(total, 12.0);
+///
+pub fn hatch_region(
+    region: &Region,
+    angle: f32,
+    spacing: f32,
+    offset: f32,
+) -> Vec<Segment> {
+    let hatcher = Hatcher::new(region, angle);
+    let mut segments = Vec::new();
+    if spacing.is_nan() || spacing <= 0.0 || hatcher.edges.is_empty() {
+        return segments;
+    }
+    // Count lines with an integer from the first one inside the region, so
+    // large offsets and positions can't stall the loop.
+    let offset = match offset % spacing {
+        remainder if remainder < 0.0 => remainder + spacing,
+        remainder => remainder,
+    };
+    let first = offset + math::ceil((hatcher.low - offset) / spacing) * spacing;
+    if first > hatcher.high {
+        return segments;
+    }
+    let count = ((hatcher.high - first) / spacing) as usize + 1;
+    for line in 0..count {
+        hatcher.scanline(first + line as f32 * spacing, &mut segments);
+    }
+    segments
+}
+
+/// Hatch a region twice, at `angle` and at a right angle to it.
+///
+/// This is the same as calling [`hatch_region`] for both angles and
+/// concatenating the results.
+pub fn cross_hatch(
+    region: &Region,
+    angle: f32,
+    spacing: f32,
+    offset: f32,
+) -> Vec<Segment> {
+    let mut segments = hatch_region(region, angle, spacing, offset);
+    segments.extend(hatch_region(region, angle + FRAC_PI_2, spacing, offset));
+    segments
+}
+
+/// Fill a region with parallel segments whose spacing depends on position.
+///
+/// This is useful for tonal shading, where darker areas get denser hatching.
+/// The first hatch line is half a spacing inside the region. After each
+/// line, `spacing` is called with the middle of that line's crossings with
+/// the region and the next line is placed that far away. Spacings smaller
+/// than a ten thousandth of the region's width across the hatch direction,
+/// including NaN, are raised to that width so the hatching always finishes.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{hatch::hatch_region_varying, polygon::{Polygon, Region}},
+///         nalgebra::point,
+///     };
+///
+///     let square = Region::new(
+///         Polygon::new(vec![
+///             point![0.0, 0.0],
+///             point![10.0, 0.0],
+///             point![10.0, 10.0],
+///             point![0.0, 10.0],
+///         ]),
+///         vec![],
+///     );
+///
+///     // Dense at the bottom and sparse at the top.
+///     let hatches = hatch_region_varying(&square, 0.0, |p| 0.1 + p.y * 0.1);
+///
+///     let bottom = hatches.iter().filter(|s| s.start.y < 5.0).count();
+///     assert!(bottom > 2 * (hatches.len() - bottom));
+///
+pub fn hatch_region_varying<F>(
+    region: &Region,
+    angle: f32,
+    mut spacing: F,
+) -> Vec<Segment>
+where
+    F: FnMut(&Point2<f32>) -> f32,
+{
+    let hatcher = Hatcher::new(region, angle);
+    let mut segments = Vec::new();
+    if hatcher.edges.is_empty() {
+        return segments;
+    }
+    let minimum = (hatcher.high - hatcher.low) * 1e-4;
+    let mut gap = |point: &Point2<f32>| spacing(point).max(minimum);
+
+    // Step the distance from the low side rather than the position itself,
+    // which could be too large for a small gap to change.
+    let extent = hatcher.high - hatcher.low;
+    let mut travelled = gap(&hatcher.midpoint(hatcher.low, &[])) / 2.0;
+    let mut crossings = Vec::new();
+    while travelled <= extent {
+        let position = hatcher.low + travelled;
+        crossings.clear();
+        hatcher.crossings(position, &mut crossings);
+        hatcher.pair(position, &crossings, &mut segments);
+        let next = travelled + gap(&hatcher.midpoint(position, &crossings));
+        if next <= travelled {
+            // A region with no width across the hatch direction.
+            break;
+        }
+        travelled = next;
+    }
+    segments
+}
+
+/// Fill a polygon with rings which step inward from its boundary, like the
+/// contour lines of a hill or the grain around a knot in wood.
+///
+/// Each ring is the previous one shrunk by `spacing` with
+/// [`Polygon::offset`] and untangled with [`cleanup_offset`], starting from
+/// the polygon itself, which isn't included. When a narrow part of the
+/// shape pinches off, a ring splits into several polygons, and each of
+/// them keeps shrinking on its own. Rings are returned from the outside in,
+/// with every polygon of one ring before any polygon of the next, and each
+/// is counterclockwise.
+///
+/// Rings stop when they vanish or after `max_rings` steps. Pieces with less
+/// area than a hundredth of a `spacing` by `spacing` square are dropped as
+/// slivers, as are pieces which come closer than `spacing` to the ring
+/// they were shrunk from, which is what's left when a shape too thin for
+/// the offset turns inside out. Returns no rings when `spacing` isn't
+/// positive.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{hatch::hatch_inward, polygon::Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![10.0, 0.0],
+///         point![10.0, 10.0],
+///         point![0.0, 10.0],
+///     ]);
+///
+///     let rings = hatch_inward(&square, 1.0, 100);
+///
+///     // Squares 8, 6, 4, and 2 units wide.
+///     assert_eq!(rings.len(), 4);
+///     assert_relative_eq!(rings[0].area(), 64.0, epsilon = 1e-3);
+///     assert_relative_eq!(rings[3].area(), 4.0, epsilon = 1e-3);
+///     assert_eq!(hatch_inward(&square, 1.0, 2).len(), 2);
+///
+pub fn hatch_inward(
+    polygon: &Polygon,
+    spacing: f32,
+    max_rings: usize,
+) -> Vec<Polygon> {
+    let mut rings = Vec::new();
+    if spacing.is_nan() || spacing <= 0.0 || polygon.vertices.len() < 3 {
+        return rings;
+    }
+    let min_area = spacing * spacing * 1e-2;
+    let mut previous = vec![polygon.clone()];
+    for _ in 0..max_rings {
+        let mut next = Vec::new();
+        for parent in &previous {
+            next.extend(
+                cleanup_offset(&parent.offset(-spacing)).into_iter().filter(
+                    |ring| {
+                        ring.area() >= min_area
+                            && stays_clear(ring, parent, spacing)
+                    },
+                ),
+            );
+        }
+        if next.is_empty() {
+            break;
+        }
+        rings.extend(next.iter().cloned());
+        previous = next;
+    }
+    rings
+}
+
+/// Returns true when every vertex of a ring is about `spacing` or more from
+/// the boundary of the polygon it was shrunk from.
+fn stays_clear(ring: &Polygon, parent: &Polygon, spacing: f32) -> bool {
+    let clearance = spacing * 0.99;
+    let clearance_squared = clearance * clearance;
+    ring.vertices.iter().all(|vertex| {
+        parent.edges().all(|edge| {
+            edge.distance_to_point_squared(vertex) >= clearance_squared
+        })
+    })
+}
+
+/// The boundary edges of a region seen from the hatch direction.
+struct Hatcher {
+    /// The direction the hatch lines run.
+    direction: Vector2<f32>,
+
+    /// The direction hatch lines are spaced along.
+    normal: Vector2<f32>,
+
+    /// The edges of every boundary in the region.
+    edges: Vec<Segment>,
+
+    /// The smallest distance along the normal to any vertex.
+    low: f32,
+
+    /// The largest distance along the normal to any vertex.
+    high: f32,
+
+    /// The smallest and largest distance along the direction to any vertex.
+    along: (f32, f32),
+}
+
+impl Hatcher {
+    fn new(region: &Region, angle: f32) -> Self {
+        let (sin, cos) = math::sin_cos(angle);
+        let direction = Vector2::new(cos, sin);
+        let normal = Vector2::new(-sin, cos);
+        let edges: Vec<Segment> = core::iter::once(&region.outer)
+            .chain(&region.holes)
+            .filter(|polygon| polygon.vertices.len() >= 3)
+            .flat_map(|polygon| polygon.edges())
+            .collect();
+        let (mut low, mut high) = (f32::INFINITY, f32::NEG_INFINITY);
+        let mut along = (f32::INFINITY, f32::NEG_INFINITY);
+        for edge in &edges {
+            let across = edge.start.coords.dot(&normal);
+            let on = edge.start.coords.dot(&direction);
+            (low, high) = (low.min(across), high.max(across));
+            along = (along.0.min(on), along.1.max(on));
+        }
+        Self {
+            direction,
+            normal,
+            edges,
+            low,
+            high,
+            along,
+        }
+    }
+
+    /// The point on a hatch line which is `along` units in the hatch
+    /// direction.
+    fn point(&self, position: f32, along: f32) -> Point2<f32> {
+        Point2::from(self.normal * position + self.direction * along)
+    }
+
+    /// Collect the hatch line's crossings with the boundary, sorted along
+    /// the hatch direction.
+    ///
+    /// Vertices exactly on the hatch line count as being below it, so a
+    /// line through a vertex crosses the boundary there once when the
+    /// boundary passes through and twice or not at all when it turns back.
+    fn crossings(&self, position: f32, crossings: &mut Vec<f32>) {
+        for edge in &self.edges {
+            let a = edge.start.coords.dot(&self.normal);
+            let b = edge.end.coords.dot(&self.normal);
+            if (a > position) == (b > position) {
+                continue;
+            }
+            let t = (position - a) / (b - a);
+            let crossing = edge.start + (edge.end - edge.start) * t;
+            crossings.push(crossing.coords.dot(&self.direction));
+        }
+        crossings.sort_by(f32::total_cmp);
+    }
+
+    /// Turn pairs of crossings into segments with the even-odd rule.
+    fn pair(&self, position: f32, crossings: &[f32], into: &mut Vec<Segment>) {
+        for pair in crossings.chunks_exact(2) {
+            if pair[0] < pair[1] {
+                into.push(Segment::new(
+                    self.point(position, pair[0]),
+                    self.point(position, pair[1]),
+                ));
+            }
+        }
+    }
+
+    /// Hatch a single line.
+    fn scanline(&self, position: f32, into: &mut Vec<Segment>) {
+        let mut crossings = Vec::new();
+        self.crossings(position, &mut crossings);
+        self.pair(position, &crossings, into);
+    }
+
+    /// The middle of a hatch line's crossings, or the middle of the region's
+    /// extent when the line doesn't cross it.
+    fn midpoint(&self, position: f32, crossings: &[f32]) -> Point2<f32> {
+        let (first, last) = match (crossings.first(), crossings.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => self.along,
+        };
+        self.point(position, (first + last) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            cross_hatch, hatch_inward, hatch_region, hatch_region_varying,
+        },
+        crate::{
+            math,
+            polygon::{Polygon, Region},
+            shape::Circle,
+            test_support::rectangle,
+        },
+        approx::assert_relative_eq,
+        core::f32::consts::{PI, TAU},
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    fn annulus() -> Region {
+        let circle = |radius| Circle::new(point![1.0, 2.0], radius);
+        Region::new(
+            circle(10.0).to_polygon_n(512),
+            vec![circle(5.0).to_polygon_n(512)],
+        )
+    }
+
+    #[test]
+    pub fn annulus_hatching_should_cover_its_area() {
+        let annulus = annulus();
+        let expected = PI * (100.0 - 25.0) / 0.1;
+
+        let hatches = hatch_region(&annulus, 0.3, 0.1, 0.0);
+        let total: f32 = hatches.iter().map(|s| s.length()).sum();
+
+        assert_relative_eq!(total, expected, max_relative = 0.02);
+        for hatch in &hatches {
+            let middle = nalgebra::center(&hatch.start, &hatch.end);
+            assert!(!annulus.holes[0].contains_point(&middle));
+        }
+    }
+
+    #[test]
+    pub fn cross_hatching_should_double_the_length() {
+        let square =
+            Region::new(rectangle(point![0.0, 0.0], point![4.0, 4.0]), vec![]);
+
+        let hatches = cross_hatch(&square, 0.0, 1.0, 0.5);
+        let total: f32 = hatches.iter().map(|s| s.length()).sum();
+
+        assert_eq!(hatches.len(), 8);
+        assert_relative_eq!(total, 32.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    pub fn hatch_lines_through_vertices_should_pair_correctly() {
+        // Every hatch line passes exactly through the hole's corners.
+        let frame = Region::new(
+            rectangle(point![0.0, 0.0], point![4.0, 4.0]),
+            vec![rectangle(point![1.0, 1.0], point![3.0, 3.0])],
+        );
+
+        let hatches = hatch_region(&frame, 0.0, 1.0, 0.0);
+
+        let total: f32 = hatches.iter().map(|s| s.length()).sum();
+        assert_relative_eq!(total, 4.0 + 2.0 + 2.0 + 4.0);
+        assert_eq!(hatch_region(&frame, 0.0, 0.0, 0.0), vec![]);
+    }
+
+    #[test]
+    pub fn large_offsets_and_positions_should_finish() {
+        let unit =
+            Region::new(rectangle(point![0.0, 0.0], point![1.0, 1.0]), vec![]);
+        let far = Region::new(
+            rectangle(point![1e6, 1e6], point![1e6 + 1.0, 1e6 + 1.0]),
+            vec![],
+        );
+
+        let offset = hatch_region(&unit, 0.0, 0.1, 1e8);
+        let shifted = hatch_region(&unit, 0.0, 0.1, 1e8 % 0.1);
+        let distant = hatch_region(&far, 0.0, 1e-3, 0.0);
+
+        assert_eq!(offset, shifted);
+        assert!((9..=11).contains(&offset.len()), "{}", offset.len());
+        assert!(!distant.is_empty());
+        assert!(distant.len() <= 1001, "{}", distant.len());
+    }
+
+    #[test]
+    pub fn varying_spacing_should_finish_far_from_the_origin() {
+        let far = Region::new(
+            rectangle(point![1e6, 1e6], point![1e6 + 1.0, 1e6 + 1.0]),
+            vec![],
+        );
+        let flat = Region::new(
+            Polygon::new(vec![
+                point![0.0, 1.0],
+                point![1.0, 1.0],
+                point![2.0, 1.0],
+            ]),
+            vec![],
+        );
+
+        let distant = hatch_region_varying(&far, 0.0, |_| 1e-6);
+        let degenerate = hatch_region_varying(&flat, 0.0, |_| 0.0);
+
+        assert!(!distant.is_empty());
+        assert!(distant.len() <= 10_001, "{}", distant.len());
+        assert!(degenerate.len() <= 1, "{}", degenerate.len());
+    }
+
+    #[test]
+    pub fn varying_spacing_should_skip_holes_and_finish() {
+        let annulus = annulus();
+
+        let hatches = hatch_region_varying(&annulus, 1.0, |_| f32::NAN);
+        let even = hatch_region_varying(&annulus, 1.0, |_| 0.1);
+        let total: f32 = even.iter().map(|s| s.length()).sum();
+
+        assert!(!hatches.is_empty());
+        assert_relative_eq!(total, PI * 75.0 / 0.1, max_relative = 0.02);
+        for hatch in &even {
+            let middle = nalgebra::center(&hatch.start, &hatch.end);
+            assert!(!annulus.holes[0].contains_point(&middle));
+        }
+    }
+
+    #[test]
+    pub fn circles_should_get_about_one_ring_per_spacing() {
+        let circle = Circle::new(point![3.0, -2.0], 10.0).to_polygon_n(256);
+
+        let rings = hatch_inward(&circle, 0.5, 1000);
+
+        // Rings at 0.5, 1.0, ..., 9.5 inside the circle.
+        assert!((18..=20).contains(&rings.len()), "{}", rings.len());
+        let mut outer = &circle;
+        for ring in &rings {
+            assert!(ring.vertices.iter().all(|p| outer.contains_point(p)));
+            assert!(ring.area() < outer.area());
+            outer = ring;
+        }
+        assert_eq!(hatch_inward(&circle, 0.5, 3).len(), 3);
+        assert!(hatch_inward(&circle, 0.0, 3).is_empty());
+        assert!(hatch_inward(&circle, f32::NAN, 3).is_empty());
+    }
+
+    #[test]
+    pub fn dumbbells_should_split_into_two_chains_of_rings() {
+        // Two circles with radius 5 joined by a neck 2 units tall.
+        let start = math::atan2(1.0, math::sqrt(24.0));
+        let arc = |center: f32, from: f32| {
+            (0..=32).map(move |i| {
+                let angle = from + (TAU - 2.0 * start) * i as f32 / 32.0;
+                let (sin, cos) = math::sin_cos(angle);
+                point![center + 5.0 * cos, 5.0 * sin]
+            })
+        };
+        let dumbbell = Polygon::new(
+            arc(0.0, start).chain(arc(16.0, PI + start)).collect(),
+        );
+
+        let rings = hatch_inward(&dumbbell, 0.75, 100);
+
+        // The first ring still passes through the neck, and the rest
+        // shrink toward the middle of each circle.
+        let left = |ring: &Polygon| ring.vertices.iter().all(|p| p.x < 8.0);
+        let right = |ring: &Polygon| ring.vertices.iter().all(|p| p.x > 8.0);
+        assert!(!left(&rings[0]) && !right(&rings[0]));
+        let lefts = rings[1..].iter().filter(|r| left(r)).count();
+        let rights = rings[1..].iter().filter(|r| right(r)).count();
+        assert_eq!(lefts + rights, rings.len() - 1);
+        assert_eq!(lefts, rights);
+        assert!(lefts >= 5, "{}", lefts);
+        for ring in &rings[1..] {
+            assert!(ring.vertices.iter().all(|p| rings[0].contains_point(p)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn hatches_should_stay_inside_the_region(
+            angle in 0.0f32..PI,
+            offset in 0.0f32..1.0,
+        ) {
+            let annulus = annulus();
+
+            for hatch in hatch_region(&annulus, angle, 0.5, offset) {
+                let middle = nalgebra::center(&hatch.start, &hatch.end);
+                prop_assert!(annulus.contains_point(&middle));
+            }
+        }
+    }
+}
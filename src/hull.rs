@@ -0,0 +1,1098 @@
+//! Hulls which enclose a set of points.
+
+use {
+    crate::{
+        fit::ArcOrSegment,
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            DistanceToPoint, Segment,
+        },
+        math,
+        operations::signed_angle,
+        polygon::Polygon,
+        shape::{Arc, Circle},
+    },
+    alloc::{collections::BTreeMap, vec::Vec},
+    core::{
+        cmp::Ordering,
+        f32::consts::{FRAC_PI_2, TAU},
+        ops::Bound,
+    },
+    nalgebra::{vector, Point2, Vector2},
+};
+
+/// Turns this close to a full turn are treated as no turn at all when
+/// wrapping around discs in [`convex_hull_of`].
+const WRAP_EPSILON: f32 = 1e-6;
+
+/// A shape which can be wrapped by [`convex_hull_of`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HullInput {
+    /// A single point.
+    Point(Point2<f32>),
+
+    /// A line segment.
+    Segment(Segment),
+
+    /// A filled circle.
+    Circle(Circle),
+
+    /// A polygon, which is wrapped by its vertices.
+    Polygon(Polygon),
+}
+
+/// The convex hull of a set of shapes, found by [`convex_hull_of`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HullShape {
+    /// The hull of shapes without any circles, which is a polygon.
+    Polygon(Polygon),
+
+    /// The hull of shapes including circles, as a closed counterclockwise
+    /// loop of arcs around the circles and the segments which are tangent
+    /// to them.
+    Boundary(Vec<ArcOrSegment>),
+}
+
+/// Compute the convex hull of a set of points.
+///
+/// The hull's vertices are in counterclockwise order. Points which lie on
+/// the hull's edges are not included as vertices. Returns `None` when there
+/// are fewer than three distinct points or every point is collinear, since
+/// then the hull has no area.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::hull::convex_hull,
+///         nalgebra::point,
+///     };
+///
+///     let hull = convex_hull(&[
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![1.0, 1.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///     ])
+///     .unwrap();
+///
+///     assert_eq!(
+///         hull.vertices,
+///         vec![
+///             point![0.0, 0.0],
+///             point![2.0, 0.0],
+///             point![2.0, 2.0],
+///             point![0.0, 2.0],
+///         ]
+///     );
+///
+pub fn convex_hull(points: &[Point2<f32>]) -> Option<Polygon> {
+    // Andrew's monotone chain
+    let sorted = sorted_unique(points);
+    let turns_left = |hull: &[Point2<f32>], p: &Point2<f32>| {
+        let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+        (b - a).perp(&(p - a)) > 0.0
+    };
+
+    let mut hull: Vec<Point2<f32>> = Vec::with_capacity(sorted.len() + 1);
+    for p in &sorted {
+        while hull.len() >= 2 && !turns_left(&hull, p) {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+    let lower_len = hull.len() + 1;
+    for p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len && !turns_left(&hull, p) {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+    // The last point is the same as the first.
+    hull.pop();
+
+    (hull.len() >= 3).then(|| Polygon::new(hull))
+}
+
+/// Compute a concave hull which follows the outline of a set of points.
+///
+/// This uses the k-nearest-neighbors algorithm by Moreira and Santos. The
+/// boundary is built by walking from point to point, choosing the next point
+/// from the `k` nearest neighbors by taking the sharpest right-hand turn
+/// which doesn't cross the boundary so far. Smaller values of `k` follow the
+/// points more closely, and larger values produce smoother hulls which
+/// approach the convex hull.
+///
+/// If the walk gets stuck or fails to enclose every point, `k` is increased
+/// and the walk is retried. If no value of `k` works, the convex hull is
+/// returned. Values of `k` below 3 are treated as 3.
+///
+/// The hull is a simple polygon with its vertices in counterclockwise order,
+/// and every vertex is one of the input points. Every input point is inside
+/// the hull or on its boundary. Like [`convex_hull`], returns `None` when
+/// there are fewer than three distinct points or every point is collinear.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::hull::{concave_hull, convex_hull},
+///         nalgebra::point,
+///     };
+///
+///     // Points spaced one unit apart around the outline of a "U" shape
+///     let outline = [
+///         point![0.0, 0.0],
+///         point![6.0, 0.0],
+///         point![6.0, 4.0],
+///         point![5.0, 4.0],
+///         point![5.0, 1.0],
+///         point![1.0, 1.0],
+///         point![1.0, 4.0],
+///         point![0.0, 4.0],
+///     ];
+///     let mut points = vec![];
+///     for (i, start) in outline.iter().enumerate() {
+///         let end = outline[(i + 1) % outline.len()];
+///         let steps = (end - start).norm() as usize;
+///         for step in 0..steps {
+///             points.push(start + (end - start) * step as f32 / steps as f32);
+///         }
+///     }
+///
+///     let concave = concave_hull(&points, 3).unwrap();
+///     let convex = convex_hull(&points).unwrap();
+///
+///     // the concave hull leaves out the gap between the arms of the "U"
+///     assert!(!concave.contains_point(&point![3.0, 3.0]));
+///     assert!(convex.contains_point(&point![3.0, 3.0]));
+///     assert!(concave.area() < convex.area());
+///
+pub fn concave_hull(points: &[Point2<f32>], k: usize) -> Option<Polygon> {
+    let convex = convex_hull(points)?;
+    let points = sorted_unique(points);
+    (k.max(3)..points.len())
+        .find_map(|k| knn_hull(&points, k))
+        .or(Some(convex))
+}
+
+/// Peel a set of points into nested convex hulls, like the layers of an
+/// onion.
+///
+/// The first layer is the convex hull of all the points. Each layer takes
+/// every remaining point which isn't strictly inside its hull, including
+/// points exactly on one of its edges, and the next layer is the hull of
+/// the points that are left. Peeling stops when the remaining points have
+/// no hull, because there are fewer than three or they're all on one line,
+/// and those points aren't part of any layer.
+///
+/// Layers are ordered from the outside in, each with its vertices
+/// counterclockwise like [`convex_hull`]. Each layer is strictly inside the
+/// one before it. This repeats the hull for every layer, which takes
+/// O(n² log n) time in the worst case.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::hull::onion_layers,
+///         nalgebra::point,
+///     };
+///
+///     // A 5 by 5 grid of points
+///     let points: Vec<_> = (0..25)
+///         .map(|i| point![(i % 5) as f32, (i / 5) as f32])
+///         .collect();
+///
+///     let layers = onion_layers(&points);
+///
+///     // The border of the grid, then the border of the 3 by 3 grid inside
+///     // it. The point in the middle is left over.
+///     assert_eq!(layers.len(), 2);
+///     assert_eq!(layers[0].area(), 16.0);
+///     assert_eq!(layers[1].area(), 4.0);
+///
+pub fn onion_layers(points: &[Point2<f32>]) -> Vec<Polygon> {
+    let mut remaining = sorted_unique(points);
+    let mut layers = Vec::new();
+    while let Some(hull) = convex_hull(&remaining) {
+        remaining.retain(|point| {
+            hull.edges().all(|edge| {
+                (edge.end - edge.start).perp(&(point - edge.start)) > 0.0
+            })
+        });
+        layers.push(hull);
+    }
+    layers
+}
+
+/// Compute the convex hull of points, segments, circles, and polygons.
+///
+/// When there are no circles with a positive radius, the hull is the
+/// [`convex_hull`] of the points, the segments' endpoints, and the
+/// polygons' vertices, and it's `None` when that has no area.
+///
+/// Otherwise every shape is treated as a disc, with points and vertices as
+/// discs of radius zero, and the hull is wrapped around the discs like a
+/// rubber band, starting with the piece at the bottom of the hull. Each
+/// disc on the hull contributes a counterclockwise arc, which is left out
+/// for points, and consecutive discs are joined by their common external
+/// tangent. A disc can contribute more than one arc. Discs inside other
+/// discs are ignored.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             fit::ArcOrSegment,
+///             hull::{convex_hull_of, HullInput, HullShape},
+///             shape::Circle,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let shapes = [
+///         HullInput::Circle(Circle::new(point![0.0, 0.0], 1.0)),
+///         HullInput::Point(point![3.0, 0.0]),
+///         HullInput::Point(point![3.0, 5.0]),
+///     ];
+///
+///     let Some(HullShape::Boundary(pieces)) = convex_hull_of(&shapes) else {
+///         panic!("the hull has a circle");
+///     };
+///
+///     // One arc around the circle, joined to both points
+///     let arcs = pieces
+///         .iter()
+///         .filter(|piece| matches!(piece, ArcOrSegment::Arc(_)))
+///         .count();
+///     assert_eq!(arcs, 1);
+///     assert_eq!(pieces.len(), 4);
+///
+pub fn convex_hull_of(shapes: &[HullInput]) -> Option<HullShape> {
+    let mut discs = Vec::new();
+    for shape in shapes {
+        match shape {
+            HullInput::Point(point) => discs.push((*point, 0.0)),
+            HullInput::Segment(segment) => {
+                discs.extend([(segment.start, 0.0), (segment.end, 0.0)])
+            }
+            HullInput::Circle(circle) => {
+                discs.push((circle.center, circle.radius))
+            }
+            HullInput::Polygon(polygon) => {
+                discs.extend(polygon.vertices.iter().map(|&v| (v, 0.0)))
+            }
+        }
+    }
+    if discs.iter().all(|&(_, radius)| radius <= 0.0) {
+        let points: Vec<_> = discs.into_iter().map(|(p, _)| p).collect();
+        return convex_hull(&points).map(HullShape::Polygon);
+    }
+    Some(HullShape::Boundary(disc_hull(&discs)))
+}
+
+/// Wrap a loop of arcs and tangent segments around discs, at least one of
+/// which has a positive radius.
+fn disc_hull(discs: &[(Point2<f32>, f32)]) -> Vec<ArcOrSegment> {
+    // Drop discs inside other discs, keeping the first of identical ones.
+    let discs: Vec<_> = discs
+        .iter()
+        .enumerate()
+        .filter(|&(i, &(center, radius))| {
+            !discs.iter().enumerate().any(|(j, &(other, other_radius))| {
+                let reach = (center - other).norm() + radius;
+                j != i
+                    && (reach < other_radius
+                        || (reach == other_radius
+                            && (radius < other_radius || j < i)))
+            })
+        })
+        .map(|(_, &disc)| disc)
+        .collect();
+    let point_at = |(center, radius): (Point2<f32>, f32), angle: f32| {
+        let (sin, cos) = math::sin_cos(angle);
+        center + vector![cos, sin] * radius
+    };
+
+    // Start at the bottom of the hull, on the disc which stays on the hull
+    // as the outward normal turns counterclockwise from straight down.
+    let first_angle = -FRAC_PI_2;
+    let start = (0..discs.len())
+        .max_by(|&a, &b| {
+            let ((a_center, a_radius), (b_center, b_radius)) =
+                (discs[a], discs[b]);
+            (a_radius - a_center.y)
+                .total_cmp(&(b_radius - b_center.y))
+                .then(a_center.x.total_cmp(&b_center.x))
+        })
+        .unwrap();
+
+    let mut pieces = Vec::new();
+    let (mut current, mut angle) = (start, first_angle);
+    // A hull of n discs has fewer than 2n arcs, and the limit guards
+    // against rounding sending the walk around again.
+    for _ in 0..2 * discs.len() {
+        // The outward normal of each disc's external tangent, as a turn
+        // counterclockwise from the current normal.
+        let (center, radius) = discs[current];
+        let turns: Vec<_> = (0..discs.len())
+            .filter(|&j| j != current)
+            .map(|j| {
+                let (other, other_radius) = discs[j];
+                let offset = other - center;
+                let distance = offset.norm();
+                let normal = math::atan2(offset.y, offset.x)
+                    - math::acos((radius - other_radius) / distance);
+                let mut turn = normal - angle;
+                while turn < -WRAP_EPSILON {
+                    turn += TAU;
+                }
+                while turn >= TAU - WRAP_EPSILON {
+                    turn -= TAU;
+                }
+                (turn.max(0.0), distance, j)
+            })
+            .collect();
+        // Of discs along the same tangent, skip to the furthest one.
+        let Some(smallest) = turns.iter().map(|t| t.0).reduce(f32::min) else {
+            break;
+        };
+        let (turn, _, next) = turns
+            .into_iter()
+            .filter(|t| t.0 <= smallest + WRAP_EPSILON)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        if angle + turn > first_angle + TAU + WRAP_EPSILON {
+            break;
+        }
+
+        push_arc(&mut pieces, discs[current], angle, turn);
+        angle += turn;
+        let (from, to) = (
+            point_at(discs[current], angle),
+            point_at(discs[next], angle),
+        );
+        if from != to {
+            pieces.push(ArcOrSegment::Segment(Segment::new(from, to)));
+        }
+        current = next;
+    }
+    push_arc(
+        &mut pieces,
+        discs[current],
+        angle,
+        first_angle + TAU - angle,
+    );
+
+    // The walk starts partway around the bottom disc, so join the arcs on
+    // either side of the start.
+    if let [ArcOrSegment::Arc(first), .., ArcOrSegment::Arc(last)] =
+        pieces.as_mut_slice()
+    {
+        if first.center == last.center && first.radius == last.radius {
+            first.start_angle = last.start_angle;
+            first.sweep += last.sweep;
+            pieces.pop();
+        }
+    }
+    pieces
+}
+
+/// Add the arc around a disc, unless it's a point or the arc is empty.
+fn push_arc(
+    pieces: &mut Vec<ArcOrSegment>,
+    (center, radius): (Point2<f32>, f32),
+    start_angle: f32,
+    sweep: f32,
+) {
+    if radius > 0.0 && sweep > 0.0 {
+        let arc = Arc::new(center, radius, start_angle, sweep);
+        pieces.push(ArcOrSegment::Arc(arc));
+    }
+}
+
+/// Try to build the concave hull for a single value of k.
+fn knn_hull(points: &[Point2<f32>], k: usize) -> Option<Polygon> {
+    let first = (0..points.len()).min_by(|&a, &b| {
+        let (a, b) = (points[a], points[b]);
+        a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+    })?;
+
+    let mut remaining: Vec<usize> =
+        (0..points.len()).filter(|&i| i != first).collect();
+    let mut hull = vec![first];
+    let mut current = first;
+    // The first point is the lowest, so start as if arriving from the left.
+    let mut back: Vector2<f32> = vector![-1.0, 0.0];
+    loop {
+        if hull.len() == 3 {
+            // Only allow the walk to close once the hull can have an area.
+            remaining.push(first);
+        }
+
+        let origin = points[current];
+        let mut nearest = remaining.clone();
+        nearest.sort_by(|&a, &b| {
+            let da = (points[a] - origin).norm_squared();
+            let db = (points[b] - origin).norm_squared();
+            da.total_cmp(&db)
+        });
+        nearest.truncate(k);
+
+        // Prefer the sharpest right-hand turn, which is the smallest
+        // counterclockwise angle from the direction back along the hull.
+        let turn = |i: usize| {
+            let angle = signed_angle(&back, &(points[i] - origin));
+            if angle <= 0.0 {
+                angle + TAU
+            } else {
+                angle
+            }
+        };
+        nearest.sort_by(|&a, &b| turn(a).total_cmp(&turn(b)));
+
+        let next = *nearest.iter().find(|&&candidate| {
+            !crosses_hull(points, &hull, candidate, candidate == first)
+        })?;
+        if next == first {
+            break;
+        }
+        hull.push(next);
+        remaining.retain(|&i| i != next);
+        back = origin - points[next];
+        current = next;
+    }
+
+    let polygon = Polygon::new(hull.iter().map(|&i| points[i]).collect());
+    encloses(&polygon, points).then_some(polygon)
+}
+
+/// Returns true when the edge from the last hull point to the candidate
+/// would touch any hull edge other than the ones it shares a vertex with.
+fn crosses_hull(
+    points: &[Point2<f32>],
+    hull: &[usize],
+    candidate: usize,
+    closing: bool,
+) -> bool {
+    let edge = Segment::new(points[hull[hull.len() - 1]], points[candidate]);
+    let edge_count = hull.len().saturating_sub(1);
+    (0..edge_count)
+        // the last hull edge always shares the current point
+        .filter(|&j| j + 1 < edge_count)
+        // the first hull edge shares the first point when closing the hull
+        .filter(|&j| !(closing && j == 0))
+        .any(|j| {
+            let hull_edge = Segment::new(points[hull[j]], points[hull[j + 1]]);
+            intersect_segments(&edge, &hull_edge) != SegmentIntersection::None
+        })
+}
+
+/// Returns true when every point is inside the polygon or on its boundary.
+pub(crate) fn encloses(polygon: &Polygon, points: &[Point2<f32>]) -> bool {
+    let scale = polygon
+        .vertices
+        .iter()
+        .map(|v| v.x.abs().max(v.y.abs()))
+        .fold(1.0, f32::max);
+    let tolerance = 1e-5 * scale;
+    points.iter().all(|p| {
+        polygon.contains_point(p)
+            || polygon
+                .edges()
+                .any(|edge| edge.distance_to_point(p) <= tolerance)
+    })
+}
+
+/// A convex hull which grows as points are added to it.
+///
+/// The hull is kept as its lower and upper chains, each ordered by x, so
+/// finding where a new point goes takes O(log n) time. Each point can only
+/// be removed from the hull once after it's added, so insertion takes
+/// O(log n) amortized time. Points inside the current hull don't change
+/// anything and are rejected in O(log n) time.
+///
+/// Like [`convex_hull`], the vertices are counterclockwise, starting from
+/// the vertex with the smallest x and then the smallest y, and points on
+/// the hull's edges are not vertices. While every point is on one line, the
+/// hull is just the two ends of the line, or the single point.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::hull::IncrementalHull,
+///         nalgebra::point,
+///     };
+///
+///     let mut hull = IncrementalHull::new();
+///     for point in [
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///     ] {
+///         assert!(hull.insert(point));
+///     }
+///
+///     assert!(hull.contains(&point![1.0, 1.0]));
+///     assert!(!hull.insert(point![1.0, 1.0]));
+///
+///     assert!(hull.insert(point![3.0, 1.0]));
+///     assert_eq!(hull.hull().count(), 5);
+///
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalHull {
+    lower: Chain,
+
+    /// The upper chain, stored as the lower chain of the points flipped
+    /// upside down.
+    upper: Chain,
+}
+
+impl IncrementalHull {
+    /// Create an empty hull.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point to the hull.
+    ///
+    /// Returns true when the hull changed. Points inside the hull or on its
+    /// boundary are ignored.
+    pub fn insert(&mut self, point: Point2<f32>) -> bool {
+        if self.contains(&point) {
+            return false;
+        }
+        let lower = self.lower.insert(point);
+        let upper = self.upper.insert(flip(&point));
+        lower || upper
+    }
+
+    /// The hull's vertices, in counterclockwise order.
+    ///
+    /// The vertices are read straight from the two chains, so nothing has
+    /// to be rebuilt when points are inserted. Collect them when they're
+    /// needed more than once.
+    pub fn hull(&self) -> impl Iterator<Item = Point2<f32>> + '_ {
+        // Both chains run between the leftmost and rightmost vertices, so
+        // the upper chain skips any ends the lower chain already has.
+        let ends = (
+            self.lower.points.values().next().copied(),
+            self.lower.points.values().next_back().copied(),
+        );
+        let upper = self.upper.points.values().rev().map(flip);
+        let upper = upper.filter(move |vertex| {
+            Some(*vertex) != ends.0 && Some(*vertex) != ends.1
+        });
+        self.lower.points.values().copied().chain(upper)
+    }
+
+    /// Returns true when the point is inside the hull or on its boundary.
+    pub fn contains(&self, point: &Point2<f32>) -> bool {
+        self.lower.below(point) && self.upper.below(&flip(point))
+    }
+}
+
+/// One monotone chain of a convex hull, with the hull above it.
+#[derive(Debug, Clone, Default)]
+struct Chain {
+    /// The chain's vertices by x. Each x has at most one vertex.
+    points: BTreeMap<Key, Point2<f32>>,
+}
+
+impl Chain {
+    /// Add a point which is outside the hull, returning true when the
+    /// chain changed.
+    fn insert(&mut self, point: Point2<f32>) -> bool {
+        let key = Key::new(point.x);
+        if let Some(existing) = self.points.get(&key) {
+            if existing.y <= point.y {
+                return false;
+            }
+        } else if let (Some(before), Some(after)) =
+            (self.before(key, 0), self.after(key, 0))
+        {
+            if turn(&before, &after, &point) >= 0.0 {
+                // The point is above this chain.
+                return false;
+            }
+        }
+        self.points.insert(key, point);
+
+        // Remove the vertices which no longer turn left.
+        while let (Some(a), Some(b)) =
+            (self.before(key, 1), self.before(key, 0))
+        {
+            if turn(&a, &b, &point) > 0.0 {
+                break;
+            }
+            self.points.remove(&Key::new(b.x));
+        }
+        while let (Some(b), Some(c)) = (self.after(key, 0), self.after(key, 1))
+        {
+            if turn(&point, &b, &c) > 0.0 {
+                break;
+            }
+            self.points.remove(&Key::new(b.x));
+        }
+        true
+    }
+
+    /// Returns true when the point is on or above the chain, and within
+    /// its range of x.
+    fn below(&self, point: &Point2<f32>) -> bool {
+        let key = Key::new(point.x);
+        if let Some(vertex) = self.points.get(&key) {
+            return vertex.y <= point.y;
+        }
+        match (self.before(key, 0), self.after(key, 0)) {
+            (Some(before), Some(after)) => turn(&before, &after, point) >= 0.0,
+            _ => false,
+        }
+    }
+
+    /// The vertex `skip + 1` places before `key`.
+    fn before(&self, key: Key, skip: usize) -> Option<Point2<f32>> {
+        self.points.range(..key).rev().nth(skip).map(|(_, p)| *p)
+    }
+
+    /// The vertex `skip + 1` places after `key`.
+    fn after(&self, key: Key, skip: usize) -> Option<Point2<f32>> {
+        self.points
+            .range((Bound::Excluded(key), Bound::Unbounded))
+            .nth(skip)
+            .map(|(_, p)| *p)
+    }
+}
+
+/// An x coordinate which can be used as a key in a map.
+#[derive(Debug, Copy, Clone)]
+struct Key(f32);
+
+impl Key {
+    fn new(x: f32) -> Self {
+        // Adding zero turns -0 into 0, so they're the same key.
+        Self(x + 0.0)
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Positive when `a`, `b`, `c` turn left, negative when they turn right,
+/// and zero when they're on one line.
+fn turn(a: &Point2<f32>, b: &Point2<f32>, c: &Point2<f32>) -> f32 {
+    (b - a).perp(&(c - a))
+}
+
+/// Mirror a point across the x axis.
+fn flip(point: &Point2<f32>) -> Point2<f32> {
+    Point2::new(point.x, -point.y)
+}
+
+/// The distinct points sorted by x and then by y.
+fn sorted_unique(points: &[Point2<f32>]) -> Vec<Point2<f32>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    sorted
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            fit::ArcOrSegment,
+            hull::{
+                concave_hull, convex_hull, convex_hull_of, encloses,
+                onion_layers, HullInput, HullShape, IncrementalHull,
+            },
+            line::{
+                intersection::{intersect_segments, SegmentIntersection},
+                Segment,
+            },
+            polygon::Polygon,
+            shape::Circle,
+            test_support::{circle, point2},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::{PI, TAU},
+        nalgebra::{point, vector, Point2},
+        proptest::prelude::*,
+    };
+
+    /// Returns true when no two non-adjacent edges touch.
+    fn is_simple(polygon: &Polygon) -> bool {
+        let edges: Vec<_> = polygon.edges().collect();
+        let n = edges.len();
+        (0..n).all(|i| {
+            ((i + 2)..n).filter(|&j| !(i == 0 && j == n - 1)).all(|j| {
+                intersect_segments(&edges[i], &edges[j])
+                    == SegmentIntersection::None
+            })
+        })
+    }
+
+    #[test]
+    pub fn convex_hulls_should_skip_interior_and_collinear_points() {
+        let hull = convex_hull(&[
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+            point![1.0, 1.0],
+            point![2.0, 2.0],
+            point![2.0, 2.0],
+            point![0.0, 2.0],
+        ])
+        .unwrap();
+
+        assert_eq!(hull.vertices.len(), 4);
+        assert!(hull.signed_area() > 0.0);
+    }
+
+    #[test]
+    pub fn degenerate_clouds_should_have_no_hull() {
+        let a = point![1.0, 1.0];
+        let b = point![2.0, 3.0];
+
+        assert_eq!(convex_hull(&[]), None);
+        assert_eq!(concave_hull(&[a, b, a], 3), None);
+        let collinear: Vec<Point2<f32>> =
+            (0..10).map(|i| point![i as f32, 2.0 * i as f32]).collect();
+        assert_eq!(convex_hull(&collinear), None);
+        assert_eq!(concave_hull(&collinear, 3), None);
+    }
+
+    #[test]
+    pub fn concave_hulls_should_follow_a_ring_of_points() {
+        // Points on the outline of a "C" shape
+        let mut points = Vec::new();
+        for i in 0..=10 {
+            let t = i as f32;
+            points.push(point![t, 0.0]);
+            points.push(point![t, 10.0]);
+            points.push(point![0.0, t]);
+            if i <= 3 || i >= 7 {
+                points.push(point![10.0, t]);
+            }
+            if (1..=9).contains(&i) {
+                points.push(point![t.max(3.0), 3.0]);
+                points.push(point![t.max(3.0), 7.0]);
+            }
+        }
+        for i in 3..=7 {
+            points.push(point![3.0, i as f32]);
+        }
+
+        let hull = concave_hull(&points, 3).unwrap();
+
+        assert!(encloses(&hull, &points));
+        assert!(is_simple(&hull));
+        assert!(hull.signed_area() > 0.0);
+        // the convex hull has area 100, but the "C" only covers 72
+        assert!(hull.area() < 80.0);
+    }
+
+    #[test]
+    pub fn triangles_should_be_their_own_hull() {
+        let points = [point![0.0, 0.0], point![4.0, 0.0], point![0.0, 3.0]];
+
+        let hull = concave_hull(&points, 3).unwrap();
+
+        assert_eq!(hull.vertices.len(), 3);
+        assert!(hull.signed_area() > 0.0);
+    }
+
+    /// Points on a small grid, so there are lots of duplicates and
+    /// collinear points.
+    fn grid_point() -> impl Strategy<Value = Point2<f32>> {
+        (-6i32..=6, -6i32..=6).prop_map(|(x, y)| point![x as f32, y as f32])
+    }
+
+    /// Returns true when the point is inside or on a counterclockwise
+    /// convex polygon.
+    fn inside_convex(polygon: &Polygon, point: &Point2<f32>) -> bool {
+        polygon.edges().all(|edge| {
+            (edge.end - edge.start).perp(&(point - edge.start)) >= 0.0
+        })
+    }
+
+    fn vertices(hull: &IncrementalHull) -> Vec<Point2<f32>> {
+        hull.hull().collect()
+    }
+
+    #[test]
+    pub fn incremental_hulls_should_grow_from_a_point_to_a_polygon() {
+        let mut hull = IncrementalHull::new();
+        assert_eq!(hull.hull().count(), 0);
+        assert!(!hull.contains(&point![0.0, 0.0]));
+
+        assert!(hull.insert(point![0.0, 0.0]));
+        assert!(!hull.insert(point![0.0, 0.0]));
+        assert_eq!(vertices(&hull), [point![0.0, 0.0]]);
+
+        assert!(hull.insert(point![0.0, 2.0]));
+        assert!(!hull.insert(point![0.0, 1.0]));
+        assert_eq!(vertices(&hull), [point![0.0, 0.0], point![0.0, 2.0]]);
+        assert!(hull.contains(&point![0.0, 1.5]));
+
+        assert!(hull.insert(point![-2.0, 1.0]));
+        assert_eq!(
+            vertices(&hull),
+            [point![-2.0, 1.0], point![0.0, 0.0], point![0.0, 2.0]]
+        );
+        assert!(!hull.contains(&point![0.5, 1.0]));
+    }
+
+    #[test]
+    pub fn onion_layers_should_peel_concentric_rings_in_order() {
+        let mut points = vec![point![0.1, -0.2]];
+        for ring in 1..=5 {
+            // Rings with different numbers of points and rotations.
+            let count = 4 + ring * 2;
+            points.extend((0..count).map(|i| {
+                let angle = ring as f32 + i as f32 * TAU / count as f32;
+                point![angle.cos(), angle.sin()] * ring as f32
+            }));
+        }
+
+        let layers = onion_layers(&points);
+
+        assert_eq!(layers.len(), 5);
+        for (layer, ring) in layers.iter().zip((1..=5).rev()) {
+            assert_eq!(layer.vertices.len(), 4 + ring * 2);
+            for vertex in &layer.vertices {
+                assert!((vertex.coords.norm() - ring as f32).abs() < 1e-5);
+            }
+        }
+        assert!(onion_layers(&[]).is_empty());
+    }
+
+    fn boundary(shapes: &[HullInput]) -> Vec<ArcOrSegment> {
+        match convex_hull_of(shapes) {
+            Some(HullShape::Boundary(pieces)) => pieces,
+            hull => panic!("expected a boundary, got {:?}", hull),
+        }
+    }
+
+    #[test]
+    pub fn two_equal_circles_should_make_a_stadium() {
+        let pieces = boundary(&[
+            HullInput::Circle(Circle::new(point![1.0, 2.0], 0.5)),
+            HullInput::Circle(Circle::new(point![4.0, 6.0], 0.5)),
+        ]);
+
+        assert_eq!(pieces.len(), 4);
+        for piece in &pieces {
+            match piece {
+                ArcOrSegment::Arc(arc) => {
+                    assert_relative_eq!(arc.sweep, PI, epsilon = 1e-5)
+                }
+                ArcOrSegment::Segment(segment) => {
+                    assert_relative_eq!(segment.length(), 5.0, epsilon = 1e-5);
+                    let middle = point![2.5, 4.0];
+                    let offset = segment.start - middle;
+                    let along = vector![0.6, 0.8];
+                    let across = offset - along * offset.dot(&along);
+                    assert_relative_eq!(across.norm(), 0.5, epsilon = 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn hulls_without_circles_should_be_point_hulls() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![3.0, 1.0]),
+            Segment::new(point![1.0, 2.0], point![2.5, -1.0]),
+            Segment::new(point![0.5, 0.5], point![1.0, 1.0]),
+        ];
+        let inputs: Vec<_> =
+            segments.iter().copied().map(HullInput::Segment).collect();
+        let endpoints: Vec<_> = segments
+            .iter()
+            .flat_map(|segment| [segment.start, segment.end])
+            .collect();
+
+        assert_eq!(
+            convex_hull_of(&inputs),
+            convex_hull(&endpoints).map(HullShape::Polygon)
+        );
+        // Circles with no radius are points too.
+        let dot = HullInput::Circle(Circle::new(point![0.0, 0.0], 0.0));
+        assert_eq!(convex_hull_of(&[dot.clone(), inputs[0].clone()]), None);
+        assert_eq!(convex_hull_of(&[]), None);
+    }
+
+    #[test]
+    pub fn shapes_inside_a_circle_should_leave_the_whole_circle() {
+        let square = Polygon::new(vec![
+            point![-1.0, -1.0],
+            point![1.0, -1.0],
+            point![1.0, 1.0],
+            point![-1.0, 1.0],
+        ]);
+
+        let pieces = boundary(&[
+            HullInput::Polygon(square),
+            HullInput::Circle(Circle::new(point![0.0, 0.0], 2.0)),
+            HullInput::Circle(Circle::new(point![0.0, 0.0], 2.0)),
+            HullInput::Circle(Circle::new(point![1.0, 0.0], 1.0)),
+            HullInput::Point(point![0.0, 2.0]),
+        ]);
+
+        assert_eq!(pieces.len(), 1);
+        let ArcOrSegment::Arc(arc) = pieces[0] else {
+            panic!("expected an arc");
+        };
+        assert_eq!(arc.radius, 2.0);
+        assert_relative_eq!(arc.sweep, TAU);
+    }
+
+    proptest! {
+        #[test]
+        fn disc_hulls_should_touch_the_furthest_shape_in_every_direction(
+            circles in proptest::collection::vec(
+                circle(-10.0..=10.0, 0.1..=3.0),
+                1..=6,
+            ),
+            points in proptest::collection::vec(point2(-10.0..=10.0), 0..=6),
+        ) {
+            let inputs: Vec<_> = circles
+                .iter()
+                .copied()
+                .map(HullInput::Circle)
+                .chain(points.iter().copied().map(HullInput::Point))
+                .collect();
+
+            let pieces = boundary(&inputs);
+
+            let wrapped = pieces.iter().cycle().skip(1);
+            for (piece, next) in pieces.iter().zip(wrapped) {
+                let gap = (piece.end() - next.start()).norm();
+                prop_assert!(gap < 1e-3);
+            }
+            for i in 0..32 {
+                let (sin, cos) = (i as f32 * TAU / 32.0).sin_cos();
+                let direction = vector![cos, sin];
+                let furthest = circles
+                    .iter()
+                    .map(|c| c.center.coords.dot(&direction) + c.radius)
+                    .chain(points.iter().map(|p| p.coords.dot(&direction)))
+                    .fold(f32::MIN, f32::max);
+                let reached = pieces
+                    .iter()
+                    .flat_map(|piece| match piece {
+                        ArcOrSegment::Arc(arc) => {
+                            (0..=64).map(|t| arc.point_at(t as f32 / 64.0))
+                                .collect::<Vec<_>>()
+                        }
+                        ArcOrSegment::Segment(s) => vec![s.start, s.end],
+                    })
+                    .map(|p| p.coords.dot(&direction))
+                    .fold(f32::MIN, f32::max);
+                prop_assert!((furthest - reached).abs() < 1e-2);
+            }
+        }
+
+        #[test]
+        fn concave_hulls_should_be_simple_and_enclose_every_point(
+            points in proptest::collection::vec(point2(-10.0..=10.0), 3..40),
+            k in 3usize..8,
+        ) {
+            let Some(hull) = concave_hull(&points, k) else {
+                prop_assume!(false);
+                unreachable!();
+            };
+
+            prop_assert!(encloses(&hull, &points));
+            prop_assert!(is_simple(&hull));
+            prop_assert!(hull.signed_area() > 0.0);
+        }
+
+        #[test]
+        fn convex_hulls_should_enclose_every_point(
+            points in proptest::collection::vec(point2(-10.0..=10.0), 3..40),
+        ) {
+            if let Some(hull) = convex_hull(&points) {
+                prop_assert!(encloses(&hull, &points));
+                prop_assert!(is_simple(&hull));
+                prop_assert!(hull.signed_area() > 0.0);
+            }
+        }
+
+        #[test]
+        fn incremental_hulls_should_match_batch_hulls(
+            points in prop::collection::vec(grid_point(), 1..80),
+            queries in prop::collection::vec(grid_point(), 10),
+        ) {
+            let mut hull = IncrementalHull::new();
+            for (i, point) in points.iter().enumerate() {
+                hull.insert(*point);
+                if i % 7 != 6 && i != points.len() - 1 {
+                    continue;
+                }
+                let inserted = &points[..=i];
+                prop_assert!(inserted.iter().all(|p| hull.contains(p)));
+                match convex_hull(inserted) {
+                    Some(batch) => {
+                        prop_assert_eq!(vertices(&hull), batch.vertices.clone());
+                        for query in &queries {
+                            prop_assert_eq!(
+                                hull.contains(query),
+                                inside_convex(&batch, query)
+                            );
+                        }
+                    }
+                    None => prop_assert!(hull.hull().count() <= 2),
+                }
+            }
+        }
+
+        #[test]
+        fn incremental_hulls_should_match_batch_hulls_for_scattered_points(
+            points in prop::collection::vec(point2(-10.0..=10.0), 3..60),
+        ) {
+            let mut hull = IncrementalHull::new();
+            for point in &points {
+                hull.insert(*point);
+            }
+
+            let batch = convex_hull(&points).unwrap();
+            prop_assert_eq!(vertices(&hull), batch.vertices);
+        }
+
+        #[test]
+        fn onion_layers_should_nest_and_use_each_point_once(
+            points in prop::collection::vec(point2(-10.0..=10.0), 0..80),
+        ) {
+            let layers = onion_layers(&points);
+
+            let used: usize = layers.iter().map(|l| l.vertices.len()).sum();
+            prop_assert!(used <= points.len());
+            for pair in layers.windows(2) {
+                for vertex in &pair[1].vertices {
+                    prop_assert!(inside_convex(&pair[0], vertex));
+                    prop_assert!(!pair[0].vertices.contains(vertex));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Closed intervals on the real number line.
+
+#[cfg(feature = "alloc")]
+use {
+    crate::line::{DistanceToPoint, Line, Segment},
+    alloc::vec::Vec,
+    nalgebra::{vector, Point2},
+};
+
+use crate::{error::check_finite, Error};
+
+/// A closed interval `[start, end]` on the real number line.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Interval {
+    /// The smallest value in the interval.
+    pub start: f32,
+
+    /// The largest value in the interval.
+    pub end: f32,
+}
+
+impl Interval {
+    /// Create a new interval between two values.
+    ///
+    /// The values can be provided in either order. They aren't validated,
+    /// use [`Interval::try_new`] to reject non-finite values.
+    ///
+    /// # Example
+    ///
+    ///     use compgeo::interval::Interval;
+    ///
+    ///     let interval = Interval::new(3.0, 1.0);
+    ///
+    ///     assert_eq!(interval.start, 1.0);
+    ///     assert_eq!(interval.end, 3.0);
+    ///
+    pub fn new(a: f32, b: f32) -> Self {
+        Self {
+            start: a.min(b),
+            end: a.max(b),
+        }
+    }
+
+    /// Create a new interval, checking that both values are finite.
+    pub fn try_new(a: f32, b: f32) -> Result<Self, Error> {
+        check_finite(&[a, b])?;
+        Ok(Self::new(a, b))
+    }
+
+    /// The distance between the start and end of the interval.
+    pub fn length(&self) -> f32 {
+        self.end - self.start
+    }
+
+    /// Returns true when the value is inside the interval, including its
+    /// endpoints.
+    pub fn contains(&self, value: f32) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Returns true when the intervals share at least one value.
+    ///
+    /// Intervals which only share an endpoint overlap.
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Merge overlapping intervals in place.
+    ///
+    /// Afterwards the intervals are sorted by their start and none of them
+    /// overlap. Intervals which exactly abut each other, like `[0, 1]` and
+    /// `[1, 2]`, are merged into one.
+    ///
+    /// # Example
+    ///
+    ///     use compgeo::interval::Interval;
+    ///
+    ///     let mut intervals = vec![
+    ///         Interval::new(5.0, 6.0),
+    ///         Interval::new(0.0, 2.0),
+    ///         Interval::new(1.0, 3.0),
+    ///     ];
+    ///     Interval::merge_all(&mut intervals);
+    ///
+    ///     assert_eq!(
+    ///         intervals,
+    ///         vec![Interval::new(0.0, 3.0), Interval::new(5.0, 6.0)]
+    ///     );
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn merge_all(intervals: &mut Vec<Interval>) {
+        intervals.sort_by(|a, b| a.start.total_cmp(&b.start));
+        let mut merged = 0;
+        for i in 0..intervals.len() {
+            let next = intervals[i];
+            if i > 0 && next.start <= intervals[merged - 1].end {
+                let last = &mut intervals[merged - 1];
+                last.end = last.end.max(next.end);
+            } else {
+                intervals[merged] = next;
+                merged += 1;
+            }
+        }
+        intervals.truncate(merged);
+    }
+}
+
+/// The total length of a line covered by the projections of some segments.
+///
+/// Every segment is projected onto the axis, and the length of the union of
+/// the projections is returned. Overlapping parts are only counted once.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             interval::union_length,
+///             line::{Line, Segment},
+///         },
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let x_axis = Line::new(Unit::new_normalize(vector![0.0, 1.0]), 0.0);
+///     let segments = [
+///         Segment::new(point![0.0, 0.0], point![2.0, 1.0]),
+///         Segment::new(point![1.0, 5.0], point![3.0, 5.0]),
+///     ];
+///
+///     assert_relative_eq!(union_length(&segments, &x_axis), 3.0);
+///
+#[cfg(feature = "alloc")]
+pub fn union_length(segments: &[Segment], axis: &Line) -> f32 {
+    covered_length(segments.iter(), axis)
+}
+
+/// The total length of a line covered by segments which lie along it.
+///
+/// This is like [`union_length`] except that only segments whose endpoints
+/// are both within `tolerance` of the axis are included.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             interval::collinear_union_length,
+///             line::{Line, Segment},
+///         },
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let x_axis = Line::new(Unit::new_normalize(vector![0.0, 1.0]), 0.0);
+///     let segments = [
+///         Segment::new(point![0.0, 0.0], point![2.0, 0.0]),
+///         Segment::new(point![1.0, 5.0], point![3.0, 5.0]),
+///     ];
+///
+///     assert_relative_eq!(
+///         collinear_union_length(&segments, &x_axis, 1e-4),
+///         2.0
+///     );
+///
+#[cfg(feature = "alloc")]
+pub fn collinear_union_length(
+    segments: &[Segment],
+    axis: &Line,
+    tolerance: f32,
+) -> f32 {
+    covered_length(
+        segments.iter().filter(|segment| {
+            axis.distance_to_point(&segment.start).abs() <= tolerance
+                && axis.distance_to_point(&segment.end).abs() <= tolerance
+        }),
+        axis,
+    )
+}
+
+#[cfg(feature = "alloc")]
+fn covered_length<'a>(
+    segments: impl Iterator<Item = &'a Segment>,
+    axis: &Line,
+) -> f32 {
+    // The line's direction is its normal rotated clockwise.
+    let direction = vector![axis.normal.y, -axis.normal.x];
+    let project = |p: &Point2<f32>| p.coords.dot(&direction);
+    let mut intervals: Vec<Interval> = segments
+        .map(|segment| {
+            Interval::new(project(&segment.start), project(&segment.end))
+        })
+        .collect();
+    Interval::merge_all(&mut intervals);
+    intervals.iter().map(Interval::length).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            interval::{collinear_union_length, union_length, Interval},
+            line::{Line, Segment},
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+    };
+
+    fn x_axis() -> Line {
+        Line::new(Unit::new_normalize(vector![0.0, 1.0]), 0.0)
+    }
+
+    #[test]
+    pub fn merge_all_should_handle_nested_and_abutting_intervals() {
+        let mut intervals = vec![
+            Interval::new(4.0, 5.0),
+            Interval::new(0.0, 10.0),
+            Interval::new(10.0, 12.0),
+            Interval::new(20.0, 21.0),
+            Interval::new(15.0, 14.0),
+        ];
+
+        Interval::merge_all(&mut intervals);
+
+        assert_eq!(
+            intervals,
+            vec![
+                Interval::new(0.0, 12.0),
+                Interval::new(14.0, 15.0),
+                Interval::new(20.0, 21.0),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn merge_all_should_accept_an_empty_list() {
+        let mut intervals = vec![];
+        Interval::merge_all(&mut intervals);
+        assert!(intervals.is_empty());
+    }
+
+    #[test]
+    pub fn union_length_should_not_count_gaps_or_overlaps() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![3.0, 0.0]),
+            Segment::new(point![4.0, 0.0], point![2.0, 0.0]),
+            Segment::new(point![5.0, 0.0], point![10.0, 0.0]),
+        ];
+
+        assert_relative_eq!(union_length(&segments, &x_axis()), 9.0);
+    }
+
+    #[test]
+    pub fn union_length_should_project_onto_diagonal_axes() {
+        let diagonal = Line::new(Unit::new_normalize(vector![-1.0, 1.0]), 0.0);
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![1.0, 1.0]),
+            Segment::new(point![1.0, 1.0], point![2.0, 2.0]),
+            // perpendicular to the axis, so it has no length when projected
+            Segment::new(point![5.0, 0.0], point![0.0, 5.0]),
+        ];
+
+        assert_relative_eq!(
+            union_length(&segments, &diagonal),
+            2.0 * 2.0f32.sqrt(),
+            epsilon = 1e-5
+        );
+    }
+
+    #[test]
+    pub fn collinear_union_length_should_ignore_distant_segments() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![3.0, 0.00001]),
+            Segment::new(point![2.0, 1.0], point![8.0, 1.0]),
+            Segment::new(point![5.0, 0.0], point![6.0, 3.0]),
+        ];
+
+        assert_relative_eq!(
+            collinear_union_length(&segments, &x_axis(), 1e-4),
+            3.0
+        );
+    }
+}
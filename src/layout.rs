@@ -0,0 +1,650 @@
+//! Spread points out inside shapes, for placing labels, nodes, or stipples.
+//!
+//! [`relax_points_in_polygon`] pushes points apart until they're a minimum
+//! distance from each other and from the boundary, similar to Lloyd
+//! relaxation but without computing any Voronoi cells. [`lloyd_relax`] is
+//! the real thing, built on the clipped cells from [`voronoi_cells`]. Either
+//! way, points which leave the shape are projected back inside along the
+//! boundary's gradient, which works for concave shapes where moving toward
+//! the centroid would leave the shape.
+
+use {
+    crate::{
+        line::Segment,
+        math,
+        polygon::{clip_to_half_plane, Polygon},
+        shape::{Aabb, BoundingBox},
+        spatial::Bvh,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// Spread points out inside a polygon until no two are closer than
+/// `min_separation`.
+///
+/// Each iteration moves every pair of points which are too close apart by
+/// half of the overlap each, and moves points which are closer than half
+/// of `min_separation` to the boundary directly away from their closest
+/// boundary point. Every point which ends up outside the polygon (or on its
+/// boundary) is then projected back inside, just past its closest boundary
+/// point. Escaping points are also projected inside before the first
+/// iteration, so every point is strictly inside the polygon afterward even
+/// when `iterations` is zero.
+///
+/// Returns true when every pair of points ends up at least `min_separation`
+/// apart. Returns false when the points couldn't be separated, either
+/// because there weren't enough iterations or because the polygon is too
+/// small to hold them all, in which case the points are as spread out as
+/// the iterations could make them. The polygon should be simple, in either
+/// winding, and polygons without any interior leave the points unchanged.
+/// Every pair of points is compared, so this is meant for the modest
+/// numbers of points used for labels.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{layout::relax_points_in_polygon, polygon::Polygon},
+///         nalgebra::point,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///         point![0.0, 4.0],
+///     ]);
+///     let mut points = vec![point![2.0, 2.0]; 4];
+///
+///     assert!(relax_points_in_polygon(&mut points, &square, 50, 1.0));
+///     for (i, a) in points.iter().enumerate() {
+///         assert!(square.contains_point(a));
+///         for b in &points[i + 1..] {
+///             assert!((a - b).norm() >= 1.0);
+///         }
+///     }
+///
+pub fn relax_points_in_polygon(
+    points: &mut [Point2<f32>],
+    boundary: &Polygon,
+    iterations: usize,
+    min_separation: f32,
+) -> bool {
+    let Some(inside) = Interior::new(boundary) else {
+        return false;
+    };
+    let separation = min_separation.max(0.0);
+    // Pushing points exactly to the separation only approaches it, so aim
+    // a little further.
+    let target = separation * 1.001;
+    for point in points.iter_mut() {
+        *point = inside.project(point, separation);
+    }
+
+    for _ in 0..iterations {
+        if is_separated(points, separation) {
+            break;
+        }
+        let mut moves = vec![Vector2::zeros(); points.len()];
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let offset = points[i] - points[j];
+                let distance = offset.norm();
+                if distance >= target {
+                    continue;
+                }
+                let direction = if distance > 0.0 {
+                    offset / distance
+                } else {
+                    spread_direction(i, j)
+                };
+                let push = direction * (target - distance) * 0.5;
+                moves[i] += push;
+                moves[j] -= push;
+            }
+        }
+        for (point, push) in points.iter_mut().zip(&moves) {
+            let moved = *point + push;
+            let (closest, _, depth) = inside.closest(&moved);
+            let margin = target * 0.5;
+            let moved = if depth > 0.0 && depth < margin {
+                closest + (moved - closest) * (margin / depth)
+            } else {
+                moved
+            };
+            *point = inside.project(&moved, separation);
+        }
+    }
+    is_separated(points, separation)
+}
+
+/// Returns true when no two points are closer than `separation`.
+fn is_separated(points: &[Point2<f32>], separation: f32) -> bool {
+    let limit = separation * separation;
+    points.iter().enumerate().all(|(i, a)| {
+        points[i + 1..]
+            .iter()
+            .all(|b| (a - b).norm_squared() >= limit)
+    })
+}
+
+/// A direction for pushing apart two points at the same position, which is
+/// different for every pair so coincident points fan out.
+fn spread_direction(i: usize, j: usize) -> Vector2<f32> {
+    // Golden angle steps never line up with each other.
+    let angle = (i * 31 + j) as f32 * 2.399_963;
+    let (sin, cos) = math::sin_cos(angle);
+    Vector2::new(cos, sin)
+}
+
+/// A polygon with a point which is known to be strictly inside it, for
+/// projecting points back inside.
+struct Interior<'a> {
+    polygon: &'a Polygon,
+
+    /// 1 for counterclockwise polygons and -1 for clockwise ones.
+    winding: f32,
+
+    /// The size of the polygon's bounding box, for the smallest step inside.
+    scale: f32,
+
+    /// The centroid of the polygon's largest triangle.
+    fallback: Point2<f32>,
+}
+
+impl<'a> Interior<'a> {
+    /// Returns `None` when the polygon has no interior.
+    fn new(polygon: &'a Polygon) -> Option<Self> {
+        let area = polygon.signed_area();
+        if area == 0.0 || !area.is_finite() {
+            return None;
+        }
+        let vertices = &polygon.vertices;
+        let fallback = polygon
+            .triangulate()
+            .into_iter()
+            .map(|[a, b, c]| {
+                Polygon::new(vec![vertices[a], vertices[b], vertices[c]])
+            })
+            .max_by(|a, b| a.area().total_cmp(&b.area()))?
+            .centroid();
+        let bounds = polygon.bounding_box();
+        Some(Self {
+            polygon,
+            winding: area.signum(),
+            scale: bounds.width().max(bounds.height()),
+            fallback,
+        })
+    }
+
+    /// The closest point on the boundary, the edge it's on, and the signed
+    /// distance to it, which is positive strictly inside the polygon.
+    fn closest(&self, point: &Point2<f32>) -> (Point2<f32>, Segment, f32) {
+        let (closest, edge, distance_squared) = self
+            .polygon
+            .edges()
+            .map(|edge| {
+                let closest = edge.closest_point(point);
+                (closest, edge, (point - closest).norm_squared())
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .unwrap();
+        let distance = math::sqrt(distance_squared);
+        if distance > 0.0 && self.polygon.contains_point(point) {
+            (closest, edge, distance)
+        } else {
+            (closest, edge, -distance)
+        }
+    }
+
+    /// Move a point which isn't strictly inside the polygon to just inside
+    /// its closest boundary point, aiming for `separation / 2` inside but
+    /// staying closer to the boundary where the polygon is thin.
+    fn project(&self, point: &Point2<f32>, separation: f32) -> Point2<f32> {
+        let (closest, edge, depth) = self.closest(point);
+        if depth > 0.0 {
+            return *point;
+        }
+
+        // Outside points move across the boundary toward their closest
+        // point, and points on the boundary move along the edge's normal.
+        let toward = closest - point;
+        let inward = if toward.norm_squared() > 0.0 {
+            toward.normalize()
+        } else {
+            let along = edge.end - edge.start;
+            Vector2::new(-along.y, along.x).normalize() * self.winding
+        };
+        if inward.iter().all(|x| x.is_finite()) {
+            let mut step = (separation * 0.5).max(self.scale * 1e-4);
+            for _ in 0..32 {
+                let candidate = closest + inward * step;
+                if self.closest(&candidate).2 > 0.0 {
+                    return candidate;
+                }
+                step *= 0.5;
+            }
+        }
+        self.fallback
+    }
+}
+
+/// Move each point to the centroid of its Voronoi cell within a region,
+/// over and over, which spreads the points out evenly.
+///
+/// This is Lloyd relaxation. After a few iterations the points look like
+/// blue noise, with no clumps or gaps, which is what stippling needs. Each
+/// iteration computes every cell with [`voronoi_cells`] and then moves all
+/// of the points at once. A point whose cell is empty stays where it is.
+/// The centroid of a cell in a concave region can be outside the region,
+/// and is projected back inside just past the closest boundary point, so
+/// points which start inside the region stay strictly inside it.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{layout::lloyd_relax, polygon::Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///         point![0.0, 4.0],
+///     ]);
+///     let mut points = vec![point![1.0, 1.0], point![1.5, 1.0]];
+///
+///     lloyd_relax(&mut points, &square, 50);
+///
+///     // The points split the square into two equal rectangles.
+///     assert_relative_eq!(points[0], point![1.0, 2.0], epsilon = 1e-3);
+///     assert_relative_eq!(points[1], point![3.0, 2.0], epsilon = 1e-3);
+///
+pub fn lloyd_relax(
+    points: &mut [Point2<f32>],
+    region: &Polygon,
+    iterations: usize,
+) {
+    let Some(inside) = Interior::new(region) else {
+        return;
+    };
+    for _ in 0..iterations {
+        let cells = voronoi_cells(points, region);
+        for (point, cell) in points.iter_mut().zip(cells) {
+            if let Some(cell) = cell {
+                *point = inside.project(&cell.centroid(), 0.0);
+            }
+        }
+    }
+}
+
+/// The Voronoi cell of each point, clipped to a region.
+///
+/// A point's cell is the part of the region which is closer to it than to
+/// any other point. Cells are built by clipping the region against the
+/// perpendicular bisector between the point and each of its neighbors,
+/// nearest first, stopping once the rest are too far away to reach the
+/// cell. Neighbors are found with a [`Bvh`] over the points, so evenly
+/// spread points take about O(n log n) time. A cell which covers most of
+/// the region still has to be checked against every point, so the worst
+/// case is O(n² log n).
+///
+/// The cell for a point is `None` when nothing is left after clipping,
+/// which can happen for points outside the region. Of several points at
+/// the same position, only the first has a cell. The region should be
+/// simple, in either winding, and the cells have the same winding. In a
+/// concave region a cell can be split into pieces, which are joined by
+/// zero-width bridges along the cell's edges. The bridges don't change the
+/// cell's area or centroid.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{layout::voronoi_cells, polygon::Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///         point![0.0, 4.0],
+///     ]);
+///     let points = [point![1.0, 1.0], point![3.0, 1.0], point![1.0, 1.0]];
+///
+///     let cells = voronoi_cells(&points, &square);
+///
+///     assert_relative_eq!(cells[0].as_ref().unwrap().area(), 8.0);
+///     assert_relative_eq!(cells[1].as_ref().unwrap().area(), 8.0);
+///     assert!(cells[2].is_none());
+///
+pub fn voronoi_cells(
+    points: &[Point2<f32>],
+    region: &Polygon,
+) -> Vec<Option<Polygon>> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let bvh = Bvh::new(points.to_vec());
+    let bounds = region.bounding_box();
+    // About twice the spacing of evenly spread points, which is enough to
+    // reach every neighbor of most cells on the first try.
+    let initial = 2.0
+        * math::sqrt(bounds.width() * bounds.height() / points.len() as f32);
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let distance = |j: usize| (points[j] - point).norm_squared();
+            let reach = |cell: &[Point2<f32>]| {
+                cell.iter()
+                    .map(|vertex| (vertex - point).norm_squared())
+                    .fold(0.0, f32::max)
+            };
+            let mut cell = region.vertices.clone();
+            let mut searched = -1.0;
+            let mut radius = initial;
+            loop {
+                let corner = Vector2::new(radius, radius);
+                let mut nearby: Vec<usize> = bvh
+                    .query_aabb(&Aabb::new(point - corner, point + corner))
+                    .into_iter()
+                    .filter(|&j| {
+                        let distance = distance(j);
+                        distance > searched && distance <= radius * radius
+                    })
+                    .collect();
+                nearby.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+                for j in nearby {
+                    if j == i {
+                        continue;
+                    }
+                    if points[j] == *point {
+                        if j < i {
+                            return None;
+                        }
+                        continue;
+                    }
+                    // Neighbors more than twice as far away as the cell's
+                    // furthest vertex can't cut it.
+                    if distance(j) > 4.0 * reach(&cell) {
+                        break;
+                    }
+                    let normal = points[j] - point;
+                    let middle = nalgebra::center(point, &points[j]);
+                    cell = clip_to_half_plane(&cell, |vertex| {
+                        (middle - vertex).dot(&normal)
+                    });
+                }
+                // Cells only shrink, so once every point within twice the
+                // cell's reach has been used there's nothing left to cut it.
+                let needed = 2.0 * math::sqrt(reach(&cell));
+                if needed <= radius || cell.is_empty() {
+                    break;
+                }
+                searched = radius * radius;
+                radius = needed;
+            }
+            let cell = Polygon::new(cell);
+            (cell.vertices.len() >= 3 && cell.area() > 0.0).then_some(cell)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            layout::{lloyd_relax, relax_points_in_polygon, voronoi_cells},
+            line::DistanceToPoint,
+            polygon::Polygon,
+            random::SplitMix64,
+            test_support::polygon,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    /// A "C" whose centroid is in the gap between its arms.
+    fn hook() -> Polygon {
+        Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 2.0],
+            point![2.0, 2.0],
+            point![2.0, 8.0],
+            point![10.0, 8.0],
+            point![10.0, 10.0],
+            point![0.0, 10.0],
+        ])
+    }
+
+    fn strictly_inside(polygon: &Polygon, point: &Point2<f32>) -> bool {
+        polygon.contains_point(point)
+            && polygon
+                .edges()
+                .all(|edge| edge.distance_to_point(point) > 0.0)
+    }
+
+    fn closest_pair(points: &[Point2<f32>]) -> f32 {
+        let mut closest = f32::INFINITY;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                closest = closest.min((a - b).norm());
+            }
+        }
+        closest
+    }
+
+    #[test]
+    pub fn points_should_stay_inside_concave_polygons() {
+        let hook = hook();
+        assert!(!hook.contains_point(&hook.centroid()));
+        // Points in the gap and past the ends of the arms.
+        let mut points: Vec<Point2<f32>> = (0..12)
+            .map(|i| point![3.0 + i as f32 * 0.5, 5.0])
+            .chain([point![12.0, 1.0], point![12.0, 9.0], point![5.0, 5.0]])
+            .collect();
+
+        let separated = relax_points_in_polygon(&mut points, &hook, 200, 1.0);
+
+        assert!(separated);
+        assert!(points.iter().all(|p| strictly_inside(&hook, p)));
+        assert!(closest_pair(&points) >= 1.0);
+    }
+
+    #[test]
+    pub fn crowded_polygons_should_report_a_best_effort() {
+        let mut clockwise = hook();
+        clockwise.vertices.reverse();
+        let mut points = vec![point![1.0, 5.0]; 40];
+
+        let separated =
+            relax_points_in_polygon(&mut points, &clockwise, 100, 3.0);
+
+        assert!(!separated);
+        assert!(points.iter().all(|p| strictly_inside(&clockwise, p)));
+        assert!(closest_pair(&points) > 0.0);
+    }
+
+    #[test]
+    pub fn escaped_points_should_be_projected_without_iterating() {
+        let hook = hook();
+        let mut points = vec![point![6.0, 5.0], point![1.0, 12.0]];
+
+        assert!(relax_points_in_polygon(&mut points, &hook, 0, 0.0));
+        assert!(points.iter().all(|p| strictly_inside(&hook, p)));
+        assert!(points[0].x > 2.0 - 1e-3);
+        assert!(points[1].y < 10.0);
+
+        let line = Polygon::new(vec![point![0.0, 0.0], point![1.0, 0.0]]);
+        let mut points = vec![point![5.0, 5.0]];
+        assert!(!relax_points_in_polygon(&mut points, &line, 10, 1.0));
+        assert_eq!(points, vec![point![5.0, 5.0]]);
+    }
+
+    /// The variance of the areas of the points' cells.
+    fn cell_area_variance(points: &[Point2<f32>], region: &Polygon) -> f32 {
+        let areas: Vec<f32> = voronoi_cells(points, region)
+            .iter()
+            .map(|cell| cell.as_ref().map_or(0.0, Polygon::area))
+            .collect();
+        let mean = areas.iter().sum::<f32>() / areas.len() as f32;
+        areas.iter().map(|area| (area - mean).powi(2)).sum::<f32>()
+            / areas.len() as f32
+    }
+
+    #[test]
+    pub fn lloyd_relaxation_should_even_out_cell_areas() {
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 10.0],
+            point![0.0, 10.0],
+        ]);
+        let mut random = SplitMix64(7);
+        let mut points: Vec<Point2<f32>> = (0..60)
+            .map(|_| {
+                let x = random.next_f64() as f32 * 10.0;
+                let y = random.next_f64() as f32 * 10.0;
+                point![x, y]
+            })
+            .collect();
+
+        let mut variances = vec![cell_area_variance(&points, &square)];
+        for _ in 0..20 {
+            lloyd_relax(&mut points, &square, 1);
+            variances.push(cell_area_variance(&points, &square));
+            assert!(points.iter().all(|p| strictly_inside(&square, p)));
+        }
+
+        for pair in variances.windows(2) {
+            assert!(pair[1] <= pair[0] * 1.05, "{variances:?}");
+        }
+        assert!(variances[20] < variances[0] * 0.1, "{variances:?}");
+    }
+
+    #[test]
+    pub fn lloyd_relaxation_should_stay_inside_concave_regions() {
+        let mut hook = hook();
+        hook.vertices.reverse();
+        let mut points: Vec<Point2<f32>> = (0..10)
+            .map(|i| point![1.0, 0.5 + i as f32])
+            .chain([point![1.0, 1.0], point![9.0, 9.0]])
+            .collect();
+
+        lloyd_relax(&mut points, &hook, 30);
+
+        assert!(points.iter().all(|p| strictly_inside(&hook, p)));
+        let cells = voronoi_cells(&points, &hook);
+        assert!(cells.iter().all(Option::is_some));
+        let total: f32 = cells.iter().flatten().map(Polygon::area).sum();
+        assert_relative_eq!(total, hook.area(), max_relative = 1e-4);
+        assert!(cells.iter().flatten().all(|c| c.signed_area() < 0.0));
+    }
+
+    #[test]
+    pub fn clustered_points_should_still_reach_distant_neighbors() {
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 10.0],
+            point![0.0, 10.0],
+        ]);
+        // A tight cluster makes the first search too small for the cells
+        // of the two lone points, which then have to look further out.
+        let points: Vec<Point2<f32>> = (0..100)
+            .map(|i| {
+                let (col, row) = ((i % 10) as f32, (i / 10) as f32);
+                point![0.5 + col * 0.1, 4.55 + row * 0.1]
+            })
+            .chain([point![9.0, 9.0], point![9.0, 1.0]])
+            .collect();
+
+        let cells = voronoi_cells(&points, &square);
+
+        let total: f32 = cells.iter().flatten().map(Polygon::area).sum();
+        assert_relative_eq!(total, 100.0, max_relative = 1e-4);
+        let lone = [&cells[100], &cells[101]].map(|c| c.as_ref().unwrap());
+        assert_relative_eq!(lone[0].area(), lone[1].area(), epsilon = 1e-3);
+        assert!(lone[0].contains_point(&point![5.0, 9.5]));
+        assert!(!lone[0].contains_point(&point![9.0, 2.0]));
+    }
+
+    #[test]
+    pub fn cells_outside_the_region_should_be_empty() {
+        let hook = hook();
+        let points = [point![5.0, 1.0], point![20.0, 1.0], point![6.0, 5.0]];
+
+        let cells = voronoi_cells(&points, &hook);
+
+        assert!(cells[1].is_none());
+        let total: f32 = cells.iter().flatten().map(Polygon::area).sum();
+        assert_relative_eq!(total, hook.area(), max_relative = 1e-5);
+        assert!(voronoi_cells(&[], &hook).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn cells_should_partition_the_region(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            points in proptest::collection::vec(
+                crate::test_support::point2(-10.0..=10.0),
+                1..20,
+            ),
+        ) {
+            prop_assume!(polygon.area() > 1.0);
+
+            let cells = voronoi_cells(&points, &polygon);
+
+            let total: f32 = cells.iter().flatten().map(Polygon::area).sum();
+            assert_relative_eq!(total, polygon.area(), max_relative = 1e-3);
+            for (point, cell) in points.iter().zip(&cells) {
+                if let Some(cell) = cell {
+                    for vertex in &cell.vertices {
+                        let own = (vertex - point).norm();
+                        let nearest = points
+                            .iter()
+                            .map(|other| (vertex - other).norm())
+                            .fold(f32::INFINITY, f32::min);
+                        prop_assert!(own <= nearest + 1e-3);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn relaxed_points_should_be_strictly_inside(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            mut points in proptest::collection::vec(
+                crate::test_support::point2(-12.0..=12.0),
+                0..12,
+            ),
+            min_separation in 0.0f32..=2.0,
+        ) {
+            prop_assume!(polygon.area() > 1.0);
+
+            let separated = relax_points_in_polygon(
+                &mut points,
+                &polygon,
+                20,
+                min_separation,
+            );
+
+            for point in &points {
+                prop_assert!(strictly_inside(&polygon, point), "{point}");
+            }
+            let closest = closest_pair(&points);
+            if separated {
+                prop_assert!(closest >= min_separation * (1.0 - 1e-6));
+            } else {
+                prop_assert!(closest < min_separation * (1.0 + 1e-6));
+            }
+        }
+    }
+}
@@ -1,6 +1,133 @@
 //! This crate provides data structures and algorithms for dealing with common
 //! computational geometry problems.
 //!
+//! # Features
+//!
+//! - `std` *(default)*: use the standard library. Disabling this feature
+//!   makes the crate `#![no_std]`, in which case floating point math is
+//!   provided by libm. The core types ([`line::Segment`], [`line::Ray`],
+//!   [`line::Line`], [`line::DistanceToPoint`]), the [`operations`], and the
+//!   intersection functions are all available without `std`.
+//! - `alloc`: functions which return or fill collections. This is implied by
+//!   `std` but can be enabled by itself for targets which have an allocator.
+//! - `parallel`: rayon-powered versions of the expensive batch operations.
+//! - `proptest-support`: proptest strategies and `Arbitrary` implementations
+//!   in the `test_support` module.
+//!
+//! # Example
+//!
+//! The core types can be used from a `#![no_std]` crate:
+//!
+//!     #![no_std]
+//!     # extern crate std;
+//!
+//!     use {
+//!         compgeo::line::{DistanceToPoint, Segment},
+//!         nalgebra::point,
+//!     };
+//!
+//!     fn distance_to_origin(segment: &Segment) -> f32 {
+//!         segment.distance_to_point(&point![0.0, 0.0])
+//!     }
+//!
+//!     # fn main() {
+//!     let segment = Segment::new(point![1.0, -1.0], point![1.0, 1.0]);
+//!     assert_eq!(distance_to_origin(&segment), 1.0);
+//!     # }
+//!
 
+#![cfg_attr(not(feature = "std"), no_std)]
+// `is_multiple_of` needs Rust 1.87, so keep using `%` for parity checks.
+#![allow(clippy::manual_is_multiple_of)]
+
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+
+mod display;
+mod error;
+mod math;
+
+pub mod convention;
+pub mod convert;
+pub mod exact;
+pub mod interval;
 pub mod line;
 pub mod operations;
+pub mod path;
+pub mod projection;
+pub mod query;
+pub mod shape;
+pub mod transform;
+
+#[cfg(feature = "alloc")]
+pub mod arrangement;
+#[cfg(feature = "alloc")]
+pub mod broadphase;
+#[cfg(feature = "alloc")]
+pub mod cluster;
+#[cfg(feature = "alloc")]
+pub mod dcel;
+#[cfg(feature = "alloc")]
+pub mod field;
+#[cfg(feature = "alloc")]
+pub mod fit;
+#[cfg(feature = "alloc")]
+pub mod generate;
+#[cfg(feature = "alloc")]
+pub mod graph;
+#[cfg(feature = "alloc")]
+pub mod hatch;
+#[cfg(feature = "alloc")]
+pub mod hull;
+#[cfg(feature = "alloc")]
+pub mod layout;
+#[cfg(feature = "alloc")]
+pub mod lp;
+#[cfg(feature = "alloc")]
+pub mod lsystem;
+#[cfg(feature = "alloc")]
+pub mod maze;
+#[cfg(feature = "alloc")]
+pub mod morph;
+#[cfg(feature = "alloc")]
+pub mod mosaic;
+#[cfg(feature = "alloc")]
+pub mod order;
+#[cfg(feature = "alloc")]
+pub mod polygon;
+#[cfg(feature = "alloc")]
+pub mod raster;
+#[cfg(feature = "alloc")]
+pub mod scan;
+#[cfg(feature = "alloc")]
+pub mod scene;
+#[cfg(feature = "alloc")]
+pub mod shadow;
+#[cfg(feature = "alloc")]
+pub mod sim;
+#[cfg(feature = "alloc")]
+pub mod snap;
+#[cfg(feature = "alloc")]
+pub mod spatial;
+#[cfg(feature = "alloc")]
+pub mod tessellate;
+#[cfg(feature = "alloc")]
+pub mod triangulation;
+#[cfg(feature = "alloc")]
+pub mod turtle;
+#[cfg(feature = "alloc")]
+pub mod visibility;
+
+#[cfg(feature = "alloc")]
+mod random;
+#[cfg(feature = "alloc")]
+mod weld;
+
+#[cfg(feature = "parallel")]
+pub mod parallel;
+
+#[cfg(any(test, feature = "proptest-support"))]
+pub mod test_support;
+
+pub use self::error::Error;
@@ -0,0 +1,356 @@
+#[cfg(feature = "alloc")]
+use {
+    crate::line::intersection::{
+        intersect_segments_detailed, DetailedIntersection,
+    },
+    alloc::vec::Vec,
+};
+use {
+    crate::{line::Segment, shape::Aabb},
+    nalgebra::{Point2, Vector2},
+};
+
+/// Values which can be linearly interpolated, like a stroke's width or
+/// color.
+pub trait Lerp: Sized {
+    /// Interpolate from `self` at `t = 0` to `other` at `t = 1`.
+    ///
+    /// Values of `t` outside of `[0, 1]` extrapolate.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    /// Returns exactly `self` at `t = 0` and exactly `other` at `t = 1`.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
+impl Lerp for Vector2<f32> {
+    /// Each component is interpolated like an `f32`.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self * (1.0 - t) + other * t
+    }
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    /// Each element is interpolated independently.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        core::array::from_fn(|i| self[i].lerp(&other[i], t))
+    }
+}
+
+/// A line segment with attributes at its endpoints which vary linearly
+/// along it.
+///
+/// Splitting or clipping the segment interpolates the attributes at the new
+/// endpoints, using the same parameters which place the new endpoints.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AttributedSegment<A> {
+    pub segment: Segment,
+
+    /// The attributes at the segment's start.
+    pub start: A,
+
+    /// The attributes at the segment's end.
+    pub end: A,
+}
+
+impl<A: Lerp> AttributedSegment<A> {
+    /// Create a segment with attributes at its start and end.
+    pub fn new(segment: Segment, start: A, end: A) -> Self {
+        Self {
+            segment,
+            start,
+            end,
+        }
+    }
+
+    /// The attributes at a parameter along the segment, from 0 at the start
+    /// to 1 at the end.
+    pub fn attribute_at(&self, t: f32) -> A {
+        self.start.lerp(&self.end, t)
+    }
+
+    /// Split the segment in two at a parameter along it.
+    ///
+    /// Both halves share the point and the attributes at the split. Values
+    /// of `t` outside of `[0, 1]` extrapolate, so one of the halves
+    /// overlaps the other.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::{AttributedSegment, Segment},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let stroke = AttributedSegment::new(
+    ///         Segment::new(point![0.0, 0.0], point![4.0, 0.0]),
+    ///         1.0,
+    ///         3.0,
+    ///     );
+    ///
+    ///     let (before, after) = stroke.split_at(0.25);
+    ///
+    ///     assert_eq!(before.segment.end, point![1.0, 0.0]);
+    ///     assert_eq!((before.start, before.end), (1.0, 1.5));
+    ///     assert_eq!((after.start, after.end), (1.5, 3.0));
+    ///
+    pub fn split_at(&self, t: f32) -> (Self, Self) {
+        let point = self.point_at(t);
+        let attributes = || self.attribute_at(t);
+        (
+            Self::new(
+                Segment::new(self.segment.start, point),
+                self.attribute_at(0.0),
+                attributes(),
+            ),
+            Self::new(
+                Segment::new(point, self.segment.end),
+                attributes(),
+                self.attribute_at(1.0),
+            ),
+        )
+    }
+
+    /// The part of the segment which is inside a box.
+    ///
+    /// The segment is clipped like [`Segment::clip_to_aabb`], and the
+    /// attributes are interpolated at the parameters where it enters and
+    /// leaves the box. Returns `None` when the segment misses the box or
+    /// only touches it at a single point.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             line::{AttributedSegment, Segment},
+    ///             shape::Aabb,
+    ///         },
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+    ///     let stroke = AttributedSegment::new(
+    ///         Segment::new(point![2.0, 1.0], point![6.0, 1.0]),
+    ///         [0.0, 1.0],
+    ///         [1.0, 0.0],
+    ///     );
+    ///
+    ///     let clipped = stroke.clip_to_aabb(&bounds).unwrap();
+    ///
+    ///     assert_eq!(clipped.segment.end, point![4.0, 1.0]);
+    ///     assert_eq!(clipped.end, [0.5, 0.5]);
+    ///
+    pub fn clip_to_aabb(&self, aabb: &Aabb) -> Option<Self> {
+        let segment = self.segment.clip_to_aabb(aabb)?;
+        let (enter, exit) = self.segment.clip_parameters(aabb)?;
+        Some(Self::new(
+            segment,
+            self.attribute_at(enter),
+            self.attribute_at(exit),
+        ))
+    }
+
+    /// Split the segment where another segment meets it.
+    ///
+    /// A crossing splits the segment in two, and an overlap splits it at
+    /// both ends of the overlap. Splits use the parameters and points from
+    /// [`intersect_segments_detailed`], and pieces with no length, from
+    /// splits at the segment's endpoints, are left out. The pieces are in
+    /// order from the segment's start to its end, and a segment which
+    /// doesn't meet the other is returned whole.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::{AttributedSegment, Segment},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let stroke = AttributedSegment::new(
+    ///         Segment::new(point![0.0, 0.0], point![4.0, 0.0]),
+    ///         0.0,
+    ///         8.0,
+    ///     );
+    ///     let cut = Segment::new(point![3.0, -1.0], point![3.0, 1.0]);
+    ///
+    ///     let pieces = stroke.split_by(&cut);
+    ///
+    ///     assert_eq!(pieces.len(), 2);
+    ///     assert_eq!(pieces[0].segment.end, point![3.0, 0.0]);
+    ///     assert_eq!(pieces[0].end, 6.0);
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn split_by(&self, other: &Segment) -> Vec<Self> {
+        let Segment { start, end } = self.segment;
+        let mut cuts = Vec::with_capacity(2);
+        let mut cut = |t: f32, point| {
+            // Keep the endpoints exact.
+            if t <= 0.0 {
+                cuts.push((0.0, start));
+            } else if t >= 1.0 {
+                cuts.push((1.0, end));
+            } else {
+                cuts.push((t, point));
+            }
+        };
+        match intersect_segments_detailed(&self.segment, other) {
+            DetailedIntersection::None => (),
+            DetailedIntersection::Point { point, t_a, .. } => cut(t_a, point),
+            DetailedIntersection::Overlap { segment, t_a, .. } => {
+                cut(t_a.0, segment.start);
+                cut(t_a.1, segment.end);
+            }
+        }
+
+        let mut pieces = Vec::with_capacity(cuts.len() + 1);
+        let mut from = (0.0, start);
+        for to in cuts.into_iter().chain([(1.0, end)]) {
+            if to.0 > from.0 {
+                pieces.push(Self::new(
+                    Segment::new(from.1, to.1),
+                    self.attribute_at(from.0),
+                    self.attribute_at(to.0),
+                ));
+                from = to;
+            }
+        }
+        pieces
+    }
+
+    fn point_at(&self, t: f32) -> Point2<f32> {
+        let Segment { start, end } = self.segment;
+        start + (end - start) * t
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{AttributedSegment, Lerp},
+        crate::{
+            line::Segment,
+            shape::Aabb,
+            test_support::{aabb, segment},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Point2},
+        proptest::prelude::*,
+    };
+
+    /// A stroke with a width and a color.
+    fn stroke(segment: Segment) -> AttributedSegment<[f32; 4]> {
+        AttributedSegment::new(
+            segment,
+            [0.5, 1.0, 0.0, 0.2],
+            [2.5, 0.0, 1.0, 0.6],
+        )
+    }
+
+    #[test]
+    pub fn splitting_should_lerp_attributes_at_the_split() {
+        let stroke = AttributedSegment::new(
+            Segment::new(point![1.0, 1.0], point![5.0, 3.0]),
+            [vector![0.0, 1.0], vector![0.3, 0.7]],
+            [vector![2.0, -1.0], vector![0.9, 0.1]],
+        );
+
+        let (before, after) = stroke.split_at(0.25);
+
+        let expected = [
+            vector![0.0, 1.0].lerp(&vector![2.0, -1.0], 0.25),
+            vector![0.3.lerp(&0.9, 0.25), 0.7.lerp(&0.1, 0.25)],
+        ];
+        assert_eq!(before.end, expected);
+        assert_eq!(after.start, expected);
+        assert_eq!(before.start, stroke.start);
+        assert_eq!(after.end, stroke.end);
+        assert_eq!(before.segment.end, point![2.0, 1.5]);
+        assert_eq!(after.segment.start, point![2.0, 1.5]);
+    }
+
+    #[test]
+    pub fn overlaps_should_split_at_both_ends() {
+        let stroke = AttributedSegment::new(
+            Segment::new(point![0.0, 0.0], point![4.0, 0.0]),
+            0.0,
+            4.0,
+        );
+        let along = Segment::new(point![3.0, 0.0], point![1.0, 0.0]);
+        let past_the_end = Segment::new(point![3.0, 0.0], point![9.0, 0.0]);
+        let beside = Segment::new(point![0.0, 1.0], point![4.0, 1.0]);
+
+        let ends: Vec<(f32, f32)> = stroke
+            .split_by(&along)
+            .iter()
+            .map(|piece| (piece.start, piece.end))
+            .collect();
+
+        assert_eq!(ends, [(0.0, 1.0), (1.0, 3.0), (3.0, 4.0)]);
+        assert_eq!(stroke.split_by(&past_the_end).len(), 2);
+        assert_eq!(stroke.split_by(&beside), [stroke]);
+    }
+
+    #[test]
+    pub fn clipping_outside_the_box_should_return_none() {
+        let bounds = Aabb::new(point![0.0, 0.0], point![1.0, 1.0]);
+        let stroke = stroke(Segment::new(point![2.0, 0.0], point![3.0, 1.0]));
+
+        assert_eq!(stroke.clip_to_aabb(&bounds), None);
+    }
+
+    proptest! {
+        #[test]
+        fn clipped_attributes_should_match_direct_evaluation(
+            segment in segment(-10.0..=10.0, 0.1),
+            bounds in aabb(-10.0..=10.0),
+        ) {
+            let stroke = stroke(segment);
+
+            let clipped = stroke.clip_to_aabb(&bounds);
+
+            prop_assert_eq!(
+                clipped.map(|clipped| clipped.segment),
+                segment.clip_to_aabb(&bounds)
+            );
+            let direction = segment.end - segment.start;
+            let along = |p: &Point2<f32>| {
+                (p - segment.start).dot(&direction) / direction.norm_squared()
+            };
+            if let Some(clipped) = clipped {
+                for (point, attributes) in [
+                    (clipped.segment.start, clipped.start),
+                    (clipped.segment.end, clipped.end),
+                ] {
+                    let direct = stroke.attribute_at(along(&point));
+                    for (a, b) in attributes.iter().zip(direct) {
+                        assert_relative_eq!(*a, b, epsilon = 1e-3);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn split_pieces_should_chain_from_start_to_end(
+            a in segment(-10.0..=10.0, 0.1),
+            b in segment(-10.0..=10.0, 0.0),
+        ) {
+            let stroke = AttributedSegment::new(a, 0.0, 1.0);
+
+            let pieces = stroke.split_by(&b);
+
+            let last = pieces[pieces.len() - 1];
+            prop_assert!(pieces.len() <= 3);
+            prop_assert_eq!((pieces[0].segment.start, pieces[0].start), (a.start, 0.0));
+            prop_assert_eq!((last.segment.end, last.end), (a.end, 1.0));
+            for pair in pieces.windows(2) {
+                prop_assert_eq!(pair[0].segment.end, pair[1].segment.start);
+                prop_assert_eq!(pair[0].end, pair[1].start);
+                prop_assert!(pair[0].end < pair[1].end);
+            }
+        }
+    }
+}
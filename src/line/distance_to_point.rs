@@ -29,3 +29,63 @@ impl DistanceToPoint for nalgebra::Point2<f32> {
         (point - self).norm_squared()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{DistanceToPoint, Line, Ray, Segment},
+            test_support::point2,
+        },
+        approx::assert_relative_eq,
+        nalgebra::Point2,
+        proptest::prelude::*,
+    };
+
+    /// The squared distance should always have the same magnitude as the
+    /// distance squared, regardless of how an implementation signs its
+    /// results.
+    fn assert_consistent(item: &impl DistanceToPoint, point: &Point2<f32>) {
+        let distance = item.distance_to_point(point);
+        assert_relative_eq!(
+            item.distance_to_point_squared(point).abs(),
+            distance * distance,
+            max_relative = 1e-4,
+            epsilon = 1e-3,
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn segment_distances_should_be_consistent(
+            segment in any::<Segment>(),
+            point in point2(-100.0..=100.0),
+        ) {
+            assert_consistent(&segment, &point);
+        }
+
+        #[test]
+        fn ray_distances_should_be_consistent(
+            ray in any::<Ray>(),
+            point in point2(-100.0..=100.0),
+        ) {
+            assert_consistent(&ray, &point);
+        }
+
+        #[test]
+        fn line_distances_should_be_consistent(
+            line in any::<Line>(),
+            point in point2(-100.0..=100.0),
+        ) {
+            assert_consistent(&line, &point);
+        }
+
+        #[test]
+        fn point_distances_should_be_consistent(
+            a in point2(-100.0..=100.0),
+            b in point2(-100.0..=100.0),
+        ) {
+            assert_consistent(&a, &b);
+        }
+    }
+}
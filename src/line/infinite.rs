@@ -1,8 +1,13 @@
 use {
     crate::{
-        line::{DistanceToPoint, Ray},
+        display::{write_compact_debug, write_term},
+        error::check_finite,
+        line::{DistanceToPoint, Ray, Segment},
         operations::perp_unit2d,
+        shape::Aabb,
+        Error,
     },
+    core::fmt,
     nalgebra::{Point2, Unit, Vector2},
 };
 
@@ -16,9 +21,10 @@ use {
 /// ```
 ///
 /// Where the vector `(a, b)` is the normal vector to the line and `c` is the
-/// offset along the normal.
+/// offset along the normal. Lines are displayed in this form, like
+/// `0x + 1y - 3 = 0`.
 ///
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Line {
     /// The normal vector for the line - the vector which is perpendicular to
     /// the line.
@@ -37,7 +43,9 @@ impl Line {
     /// f(x, y) = normal.x * x + normal.y * y + c == 0
     /// ```
     ///
-    /// Where `normal` and `c` are the arguments to this constructor.
+    /// Where `normal` and `c` are the arguments to this constructor. The
+    /// offset isn't validated. Use [`Line::try_new`] to reject non-finite
+    /// values.
     ///
     /// # Example
     ///
@@ -51,6 +59,97 @@ impl Line {
     pub fn new(normal: Unit<Vector2<f32>>, c: f32) -> Self {
         Self { normal, c }
     }
+
+    /// Create a new line, normalizing the normal vector and checking that
+    /// every value is finite.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Line, Error},
+    ///         nalgebra::vector,
+    ///     };
+    ///
+    ///     assert_eq!(
+    ///         Line::try_new(vector![0.0, 1.0], f32::NAN),
+    ///         Err(Error::NotFinite)
+    ///     );
+    ///     assert!(Line::try_new(vector![0.0, 2.0], 1.0).is_ok());
+    ///
+    pub fn try_new(normal: Vector2<f32>, c: f32) -> Result<Self, Error> {
+        check_finite(&[normal.x, normal.y, c])?;
+        let normal = Unit::try_new(normal, 0.0).ok_or(Error::ZeroVector)?;
+        Ok(Self::new(normal, c))
+    }
+
+    /// The part of the line which is inside a box.
+    ///
+    /// The segment points along the line's direction, `(normal.y,
+    /// -normal.x)`, which is the direction of the ray the line was built
+    /// from. Its endpoints are clamped onto the box's boundary. Returns
+    /// `None` when the line misses the box or only touches one corner.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::{Line, Segment}, shape::Aabb},
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+    ///     let line = Line::try_new(vector![0.0, 1.0], -1.0).unwrap();
+    ///
+    ///     assert_eq!(
+    ///         line.clip_to_aabb(&bounds),
+    ///         Some(Segment::new(point![0.0, 1.0], point![4.0, 1.0]))
+    ///     );
+    ///
+    pub fn clip_to_aabb(&self, aabb: &Aabb) -> Option<Segment> {
+        let direction = Vector2::new(self.normal.y, -self.normal.x);
+        let origin = Point2::from(self.normal.into_inner() * -self.c);
+        let (enter, exit) = aabb.slab_parameters(&origin, &direction)?;
+        let clamp =
+            |t: f32| (origin + direction * t).sup(&aabb.min).inf(&aabb.max);
+        (enter < exit).then(|| Segment::new(clamp(enter), clamp(exit)))
+    }
+
+    /// The point on the line which is closest to the given point.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::Line,
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let line = Line::try_new(vector![0.0, 1.0], -1.0).unwrap();
+    ///
+    ///     assert_eq!(line.closest_point(&point![3.0, 5.0]), point![3.0, 1.0]);
+    ///
+    pub fn closest_point(&self, point: &Point2<f32>) -> Point2<f32> {
+        point - self.normal.scale(self.distance_to_point(point))
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_term(f, self.normal.x, "x", true)?;
+        write_term(f, self.normal.y, "y", false)?;
+        write_term(f, self.c, "", false)?;
+        f.write_str(" = 0")
+    }
+}
+
+impl fmt::Debug for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write_compact_debug(f, "Line", self);
+        }
+        f.debug_struct("Line")
+            .field("normal", &self.normal)
+            .field("c", &self.c)
+            .finish()
+    }
 }
 
 impl DistanceToPoint for Line {
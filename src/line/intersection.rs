@@ -1,6 +1,17 @@
 //! Functions and types for calculating the intersections between lines.
 
-use crate::{line::Segment, operations::perp_vec2d};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use {
+    crate::{
+        error::debug_assert_finite,
+        line::{DistanceToPoint, Ray, Segment},
+        math,
+        operations::{perp_vec2d, solve_2x2},
+    },
+    nalgebra::{Point2, Vector2},
+};
 
 /// This type represents the intersection between two line segments.
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -15,14 +26,136 @@ pub enum SegmentIntersection {
     Overlap(Segment),
 }
 
+/// The 2d cross product (also called the perp-dot product) of two vectors.
+fn cross(a: &Vector2<f32>, b: &Vector2<f32>) -> f32 {
+    perp_vec2d(a).dot(b)
+}
+
+/// The intersection between two line segments, along with where it is
+/// along each of them.
+///
+/// Parameters go from 0 at a segment's start to 1 at its end, so the point
+/// `t` along a segment is `start + (end - start) * t`. Segments with zero
+/// length only have the parameter 0.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DetailedIntersection {
+    /// The segments don't intersect.
+    None,
+
+    /// The segments intersect at a single point.
+    Point {
+        point: Point2<f32>,
+
+        /// The parameter of the point along the first segment.
+        t_a: f32,
+
+        /// The parameter of the point along the second segment.
+        t_b: f32,
+    },
+
+    /// The segments overlap along a segment which points in the same
+    /// direction as the first segment.
+    Overlap {
+        segment: Segment,
+
+        /// The parameters of the overlap's start and end along the first
+        /// segment, in increasing order.
+        t_a: (f32, f32),
+
+        /// The parameters of the overlap's start and end along the second
+        /// segment. They decrease when the segments point in opposite
+        /// directions.
+        t_b: (f32, f32),
+    },
+}
+
+impl From<DetailedIntersection> for SegmentIntersection {
+    fn from(intersection: DetailedIntersection) -> Self {
+        match intersection {
+            DetailedIntersection::None => SegmentIntersection::None,
+            DetailedIntersection::Point { point, .. } => {
+                SegmentIntersection::Point(point)
+            }
+            DetailedIntersection::Overlap { segment, .. } => {
+                SegmentIntersection::Overlap(segment)
+            }
+        }
+    }
+}
+
 /// Compute the intersection between two line segments.
+///
+/// When the segments are collinear and overlap, the overlapping region is
+/// returned as a segment which points in the same direction as `a`. Use
+/// [`intersect_segments_detailed`] to also get where the intersection is
+/// along each segment.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{
+///             intersection::{intersect_segments, SegmentIntersection},
+///             Segment,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let a = Segment::new(point![0.0, 0.0], point![2.0, 2.0]);
+///     let b = Segment::new(point![0.0, 2.0], point![2.0, 0.0]);
+///
+///     assert_eq!(
+///         intersect_segments(&a, &b),
+///         SegmentIntersection::Point(point![1.0, 1.0])
+///     );
+///
 pub fn intersect_segments(a: &Segment, b: &Segment) -> SegmentIntersection {
+    intersect_segments_detailed(a, b).into()
+}
+
+/// Compute the intersection between two line segments, and the parameters
+/// of the intersection along each of them.
+///
+/// The intersection is exactly the one found by [`intersect_segments`].
+/// Crossing points are computed from the parameter along `a`, so
+/// interpolating along `a` with `t_a` lands exactly on the point.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{
+///             intersection::{
+///                 intersect_segments_detailed, DetailedIntersection,
+///             },
+///             Segment,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let a = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+///     let b = Segment::new(point![1.0, -1.0], point![1.0, 3.0]);
+///
+///     assert_eq!(
+///         intersect_segments_detailed(&a, &b),
+///         DetailedIntersection::Point {
+///             point: point![1.0, 0.0],
+///             t_a: 0.25,
+///             t_b: 0.25,
+///         }
+///     );
+///
+pub fn intersect_segments_detailed(
+    a: &Segment,
+    b: &Segment,
+) -> DetailedIntersection {
     let dir_a = a.end - a.start;
     let dir_b = b.end - b.start;
+    let w = b.start - a.start;
+    debug_assert_finite(&[dir_a.x, dir_a.y, dir_b.x, dir_b.y, w.x, w.y]);
 
-    // check for degenerate cases: parallel lines, segments which have zero
-    // length
-    if dir_a.dot(&perp_vec2d(&dir_b)) <= f32::EPSILON {
+    // Solve a.start + dir_a * s = b.start + dir_b * t. There's no unique
+    // solution in the degenerate cases: parallel lines, segments which have
+    // zero length.
+    let Some((s, t)) = solve_2x2(&dir_a, &(-dir_b), &w) else {
         // First, check if the segments are degenerate
         let sqr_len_a = a.length_squared();
         let sqr_len_b = b.length_squared();
@@ -30,39 +163,912 @@ pub fn intersect_segments(a: &Segment, b: &Segment) -> SegmentIntersection {
         if sqr_len_a == 0.0 && sqr_len_b == 0.0 {
             // both segments are just points
             if a.start == b.start {
-                return SegmentIntersection::Point(a.start);
+                return DetailedIntersection::Point {
+                    point: a.start,
+                    t_a: 0.0,
+                    t_b: 0.0,
+                };
             } else {
-                return SegmentIntersection::None;
+                return DetailedIntersection::None;
             }
         }
 
         if sqr_len_a == 0.0 {
             // just segment a is a point
-            // TODO: IMPLEMENT THIS
+            return point_on_segment(&a.start, b).map_or(
+                DetailedIntersection::None,
+                |t| DetailedIntersection::Point {
+                    point: a.start,
+                    t_a: 0.0,
+                    t_b: t,
+                },
+            );
+        }
+
+        if sqr_len_b == 0.0 {
+            // just segment b is a point
+            return point_on_segment(&b.start, a).map_or(
+                DetailedIntersection::None,
+                |t| DetailedIntersection::Point {
+                    point: b.start,
+                    t_a: t,
+                    t_b: 0.0,
+                },
+            );
         }
 
         // The lines are parallel, or so close to it as to be unable to
         // tell.
-        return SegmentIntersection::None;
+        return intersect_parallel_segments(a, b);
+    };
+
+    if !(0.0..=1.0).contains(&s) || !(0.0..=1.0).contains(&t) {
+        return DetailedIntersection::None;
+    }
+
+    DetailedIntersection::Point {
+        point: a.start + dir_a * s,
+        t_a: s,
+        t_b: t,
+    }
+}
+
+/// The parameter of a point along a segment with nonzero length, or `None`
+/// when the point isn't on the segment.
+fn point_on_segment(point: &Point2<f32>, segment: &Segment) -> Option<f32> {
+    let tolerance = f32::EPSILON * segment.length_squared();
+    if segment.distance_to_point_squared(point) <= tolerance {
+        Some(parameter(segment, point).clamp(0.0, 1.0))
+    } else {
+        None
+    }
+}
+
+/// The intersection between two parallel segments with nonzero length.
+fn intersect_parallel_segments(
+    a: &Segment,
+    b: &Segment,
+) -> DetailedIntersection {
+    let dir_a = a.end - a.start;
+    let len_sqr_a = dir_a.norm_squared();
+
+    // Parallel segments only intersect when they are also collinear.
+    let offset = cross(&(b.start - a.start), &dir_a);
+    if offset.abs() > f32::EPSILON * len_sqr_a.max(1.0) {
+        return DetailedIntersection::None;
     }
 
-    // let du = u.norm_squared();
-    // let dv = v.norm_squared();
-    // if du == 0 && dv == 0
+    // Project b onto a's parametric representation and intersect the
+    // resulting intervals.
+    let t0 = (b.start - a.start).dot(&dir_a) / len_sqr_a;
+    let t1 = (b.end - a.start).dot(&dir_a) / len_sqr_a;
+    let lo = t0.min(t1).max(0.0);
+    let hi = t0.max(t1).min(1.0);
 
-    SegmentIntersection::None
+    // Map parameters along a back onto b.
+    let along_b = |t: f32| ((t - t0) / (t1 - t0)).clamp(0.0, 1.0);
+    if lo > hi {
+        DetailedIntersection::None
+    } else if lo == hi {
+        DetailedIntersection::Point {
+            point: a.start + dir_a * lo,
+            t_a: lo,
+            t_b: along_b(lo),
+        }
+    } else {
+        DetailedIntersection::Overlap {
+            segment: Segment::new(a.start + dir_a * lo, a.start + dir_a * hi),
+            t_a: (lo, hi),
+            t_b: (along_b(lo), along_b(hi)),
+        }
+    }
+}
+
+/// How far along a segment with nonzero length a point is, from 0 at the
+/// start to 1 at the end.
+fn parameter(segment: &Segment, point: &Point2<f32>) -> f32 {
+    let direction = segment.end - segment.start;
+    (point - segment.start).dot(&direction) / direction.norm_squared()
+}
+
+/// How two line segments meet.
+///
+/// See [`classify_intersection`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IntersectionKind {
+    /// The segments don't touch.
+    Disjoint,
+
+    /// The segments cross at a single point which is strictly inside both
+    /// of them.
+    CrossingInterior,
+
+    /// An endpoint of one segment touches the other segment away from its
+    /// endpoints, like the stem of a "T".
+    TouchingEndpointToInterior,
+
+    /// The segments share an endpoint and don't overlap.
+    TouchingEndpointToEndpoint,
+
+    /// The segments lie on the same line and share more than a single
+    /// point.
+    CollinearOverlapping,
+
+    /// The segments lie on the same line and only share an endpoint.
+    CollinearTouching,
+}
+
+/// Classify how two line segments meet.
+///
+/// Points which are within `tolerance` of each other count as the same
+/// point, so an endpoint which is within `tolerance` of the other segment
+/// touches it and segments whose endpoints are all within `tolerance` of the
+/// other segment's line are collinear. Use a tolerance of zero for exact
+/// comparisons. Segments shorter than the tolerance are treated as points,
+/// so they're never collinear or crossing, and a point which touches a
+/// segment touches it with an endpoint. The result doesn't depend on the
+/// order of the segments.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{
+///             intersection::{classify_intersection, IntersectionKind},
+///             Segment,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let floor = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+///     let post = Segment::new(point![2.0, 0.001], point![2.0, 3.0]);
+///     let wall = Segment::new(point![4.0, 0.0], point![4.0, 3.0]);
+///
+///     assert_eq!(
+///         classify_intersection(&floor, &post, 0.01),
+///         IntersectionKind::TouchingEndpointToInterior
+///     );
+///     assert_eq!(
+///         classify_intersection(&floor, &post, 0.0),
+///         IntersectionKind::Disjoint
+///     );
+///     assert_eq!(
+///         classify_intersection(&floor, &wall, 0.0),
+///         IntersectionKind::TouchingEndpointToEndpoint
+///     );
+///
+pub fn classify_intersection(
+    a: &Segment,
+    b: &Segment,
+    tolerance: f32,
+) -> IntersectionKind {
+    let tolerance = tolerance.max(0.0);
+    let a_is_point = a.length() <= tolerance;
+    let b_is_point = b.length() <= tolerance;
+
+    if !a_is_point && !b_is_point {
+        if let Some(kind) = classify_collinear(a, b, tolerance) {
+            return kind;
+        }
+    }
+
+    let ends = |s: &Segment| [s.start, s.end];
+    let shares_endpoint = ends(a)
+        .iter()
+        .any(|p| ends(b).iter().any(|q| (p - q).norm() <= tolerance));
+    if shares_endpoint {
+        return IntersectionKind::TouchingEndpointToEndpoint;
+    }
+    let touches = |s: &Segment, other: &Segment| {
+        ends(s)
+            .iter()
+            .any(|p| other.distance_to_point(p) <= tolerance)
+    };
+    if touches(a, b) || touches(b, a) {
+        // A point touching a segment touches it with its "endpoint".
+        return IntersectionKind::TouchingEndpointToInterior;
+    }
+    if a_is_point || b_is_point {
+        return IntersectionKind::Disjoint;
+    }
+    match intersect_segments(a, b) {
+        SegmentIntersection::None => IntersectionKind::Disjoint,
+        SegmentIntersection::Point(_) => IntersectionKind::CrossingInterior,
+        // Nearly parallel segments can overlap within the rounding error
+        // even when they aren't collinear within the tolerance.
+        SegmentIntersection::Overlap(_) => {
+            IntersectionKind::CollinearOverlapping
+        }
+    }
+}
+
+/// Classify two segments which are longer than the tolerance, or return
+/// `None` when they aren't collinear.
+fn classify_collinear(
+    a: &Segment,
+    b: &Segment,
+    tolerance: f32,
+) -> Option<IntersectionKind> {
+    let on_line = |s: &Segment, other: &Segment| {
+        let direction = (other.end - other.start).normalize();
+        [s.start, s.end]
+            .iter()
+            .all(|p| cross(&direction, &(p - other.start)).abs() <= tolerance)
+    };
+    if !on_line(a, b) || !on_line(b, a) {
+        return None;
+    }
+
+    // Measure both segments as distances along a.
+    let direction = (a.end - a.start).normalize();
+    let along = |p: &Point2<f32>| (p - a.start).dot(&direction);
+    let (b0, b1) = (along(&b.start), along(&b.end));
+    let low = b0.min(b1).max(0.0);
+    let high = b0.max(b1).min(a.length());
+    let kind = if high - low > tolerance {
+        IntersectionKind::CollinearOverlapping
+    } else if high - low >= -tolerance {
+        IntersectionKind::CollinearTouching
+    } else {
+        IntersectionKind::Disjoint
+    };
+    Some(kind)
+}
+
+/// Compute the intersection between every pair of segments in a slice.
+///
+/// The result contains a `(i, j, intersection)` entry for every pair of
+/// segments with `i < j` which intersect. Entries are sorted by `i` and then
+/// by `j`.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{
+///             intersection::{intersect_all_pairs, SegmentIntersection},
+///             Segment,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let segments = [
+///         Segment::new(point![0.0, 0.0], point![2.0, 2.0]),
+///         Segment::new(point![5.0, 5.0], point![6.0, 6.0]),
+///         Segment::new(point![0.0, 2.0], point![2.0, 0.0]),
+///     ];
+///
+///     assert_eq!(
+///         intersect_all_pairs(&segments),
+///         vec![(0, 2, SegmentIntersection::Point(point![1.0, 1.0]))]
+///     );
+///
+#[cfg(feature = "alloc")]
+pub fn intersect_all_pairs(
+    segments: &[Segment],
+) -> Vec<(usize, usize, SegmentIntersection)> {
+    (0..segments.len())
+        .flat_map(|i| intersect_with_later_segments(segments, i))
+        .collect()
+}
+
+/// Intersect the segment at index `i` with every segment which comes after it.
+#[cfg(feature = "alloc")]
+pub(crate) fn intersect_with_later_segments(
+    segments: &[Segment],
+    i: usize,
+) -> impl Iterator<Item = (usize, usize, SegmentIntersection)> + '_ {
+    let a = &segments[i];
+    segments[i + 1..]
+        .iter()
+        .enumerate()
+        .filter_map(move |(offset, b)| match intersect_segments(a, b) {
+            SegmentIntersection::None => None,
+            intersection => Some((i, i + 1 + offset, intersection)),
+        })
+}
+
+/// The result of casting a ray against a collection of segments.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayHit {
+    /// The index of the segment which was hit.
+    pub index: usize,
+
+    /// The ray parameter for the hit. Ray directions are unit vectors, so
+    /// this is also the distance from the ray's origin to the hit.
+    pub t: f32,
+
+    /// The point where the ray hit the segment.
+    pub point: Point2<f32>,
+}
+
+/// Compute the ray parameter `t` where a ray first touches a segment.
+///
+/// Returns `None` when the ray misses the segment. When the segment is
+/// collinear with the ray, the parameter for the nearest point of overlap is
+/// returned.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{intersection::intersect_ray_segment, Ray, Segment},
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let ray = Ray::new(
+///         point![0.0, 0.0],
+///         Unit::new_normalize(vector![1.0, 0.0])
+///     );
+///     let segment = Segment::new(point![3.0, -1.0], point![3.0, 1.0]);
+///
+///     assert_relative_eq!(intersect_ray_segment(&ray, &segment).unwrap(), 3.0);
+///
+pub fn intersect_ray_segment(ray: &Ray, segment: &Segment) -> Option<f32> {
+    intersect_ray_segment_within(ray, segment, f32::INFINITY)
+}
+
+/// Compute the ray parameter `t` where a ray first touches a segment, as
+/// long as it's no further than `max_t`.
+///
+/// This is [`intersect_ray_segment`] for rays with a limited range, like a
+/// sensor or a weapon. Hits at exactly `max_t` are included, and an
+/// infinite `max_t` gives the same results as [`intersect_ray_segment`].
+/// When the segment is collinear with the ray, it's only hit when the
+/// nearest point of overlap is in range.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{
+///             intersection::intersect_ray_segment_within,
+///             Ray,
+///             Segment,
+///         },
+///         nalgebra::{point, vector, Unit},
+///     };
+///
+///     let ray = Ray::new(
+///         point![0.0, 0.0],
+///         Unit::new_normalize(vector![1.0, 0.0])
+///     );
+///     let segment = Segment::new(point![3.0, -1.0], point![3.0, 1.0]);
+///
+///     let within = |max_t| intersect_ray_segment_within(&ray, &segment, max_t);
+///
+///     assert_eq!(within(3.0), Some(3.0));
+///     assert_eq!(within(2.5), None);
+///
+pub fn intersect_ray_segment_within(
+    ray: &Ray,
+    segment: &Segment,
+    max_t: f32,
+) -> Option<f32> {
+    let dir_s = segment.end - segment.start;
+    let w = segment.start - ray.origin;
+    debug_assert_finite(&[dir_s.x, dir_s.y, w.x, w.y]);
+
+    // Solve ray.origin + direction * t = segment.start + dir_s * s.
+    let Some((t, s)) = solve_2x2(&ray.direction, &(-dir_s), &w) else {
+        // The segment is parallel to the ray (or is just a point), so there
+        // is only a hit when the segment lies on the ray.
+        let tolerance = f32::EPSILON * dir_s.norm_squared().max(1.0);
+        if cross(&w, &ray.direction).abs() > tolerance {
+            return None;
+        }
+        let t0 = w.dot(&ray.direction);
+        let t1 = (segment.end - ray.origin).dot(&ray.direction);
+        let (near, far) = (t0.min(t1), t0.max(t1));
+        let near = near.max(0.0);
+        return if far < 0.0 || near > max_t {
+            None
+        } else {
+            Some(near)
+        };
+    };
+
+    if t < 0.0 || t > max_t || !(0.0..=1.0).contains(&s) {
+        return None;
+    }
+    Some(t)
+}
+
+/// Find the first segment hit by a ray.
+///
+/// Returns `None` when the ray doesn't hit any segments. When multiple
+/// segments are hit at the same distance, the segment with the lowest index
+/// is returned.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{intersection::raycast, Ray, Segment},
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let ray = Ray::new(
+///         point![0.0, 0.0],
+///         Unit::new_normalize(vector![1.0, 0.0])
+///     );
+///     let segments = [
+///         Segment::new(point![5.0, -1.0], point![5.0, 1.0]),
+///         Segment::new(point![2.0, -1.0], point![2.0, 1.0]),
+///     ];
+///
+///     let hit = raycast(&ray, &segments).unwrap();
+///     assert_eq!(hit.index, 1);
+///     assert_relative_eq!(hit.point, point![2.0, 0.0]);
+///
+pub fn raycast(ray: &Ray, segments: &[Segment]) -> Option<RayHit> {
+    raycast_within(ray, segments, f32::INFINITY)
+}
+
+/// Find the first segment hit by a ray within `max_t` of its origin.
+///
+/// This is [`raycast`] for rays with a limited range. Hits further than
+/// `max_t` are rejected as each segment is tested, rather than after the
+/// nearest hit is found, so segments beyond the range never replace a
+/// closer candidate. Hits at exactly `max_t` are included, and an infinite
+/// `max_t` gives the same results as [`raycast`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{intersection::raycast_within, Ray, Segment},
+///         nalgebra::{point, vector, Unit},
+///     };
+///
+///     let ray = Ray::new(
+///         point![0.0, 0.0],
+///         Unit::new_normalize(vector![1.0, 0.0])
+///     );
+///     let segments = [
+///         Segment::new(point![5.0, -1.0], point![5.0, 1.0]),
+///         Segment::new(point![2.0, -1.0], point![2.0, 1.0]),
+///     ];
+///
+///     assert_eq!(raycast_within(&ray, &segments, 4.0).unwrap().index, 1);
+///     assert!(raycast_within(&ray, &segments, 1.5).is_none());
+///
+pub fn raycast_within(
+    ray: &Ray,
+    segments: &[Segment],
+    max_t: f32,
+) -> Option<RayHit> {
+    let mut nearest: Option<(usize, f32)> = None;
+    for (index, segment) in segments.iter().enumerate() {
+        let limit = nearest.map_or(max_t, |(_, best)| best);
+        let t = match intersect_ray_segment_within(ray, segment, limit) {
+            Some(t) => t,
+            None => continue,
+        };
+        match nearest {
+            Some((_, best)) if best <= t => (),
+            _ => nearest = Some((index, t)),
+        }
+    }
+    nearest.map(|(index, t)| RayHit {
+        index,
+        t,
+        point: ray.origin + ray.direction.scale(t),
+    })
+}
+
+/// The result of casting a beam against a collection of segments.
+///
+/// See [`beamcast`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BeamHit {
+    /// The index of the segment which was hit.
+    pub index: usize,
+
+    /// The ray parameter where the beam first touches the segment. This is
+    /// never after the point where the ray itself would hit the segment.
+    pub t: f32,
+
+    /// The point on the segment which comes closest to the ray.
+    pub point: Point2<f32>,
+
+    /// How far the segment passes from the ray, which is zero when the ray
+    /// hits it.
+    pub distance: f32,
+}
+
+/// Find the first segment touched by a beam `2 * half_width` wide, centered
+/// on a ray.
+///
+/// Thin rays slip past small targets, so this is the better choice for
+/// picking under a cursor. A segment is touched when it passes within
+/// `half_width` of the ray, ignoring the part of the line behind the
+/// origin, which is the same as sweeping a circle with a radius of
+/// `half_width` along the ray, or casting a capsule. Segments are ordered
+/// by where the beam first touches them rather than by how close they come
+/// to the ray, so a segment which the beam grazes early wins over one which
+/// the ray hits head on further away. Segments touched at the same `t` go
+/// to the lowest index, and segments within `half_width` of the origin are
+/// touched at `t = 0`. A beam with a `half_width` of zero or less is a
+/// plain ray, like [`raycast`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::line::{
+///             intersection::{beamcast, raycast},
+///             Ray, Segment,
+///         },
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let ray = Ray::new(
+///         point![0.0, 0.0],
+///         Unit::new_normalize(vector![1.0, 0.0])
+///     );
+///     // A short tick which stops just below the ray.
+///     let segments = [Segment::new(point![4.0, -1.0], point![4.0, -0.1])];
+///
+///     assert!(raycast(&ray, &segments).is_none());
+///     let hit = beamcast(&ray, 0.25, &segments).unwrap();
+///     assert_eq!(hit.index, 0);
+///     assert_relative_eq!(hit.point, point![4.0, -0.1]);
+///     assert_relative_eq!(hit.distance, 0.1);
+///     assert!(hit.t < 4.0);
+///
+pub fn beamcast(
+    ray: &Ray,
+    half_width: f32,
+    segments: &[Segment],
+) -> Option<BeamHit> {
+    let half_width = half_width.max(0.0);
+    let mut nearest: Option<BeamHit> = None;
+    for (index, segment) in segments.iter().enumerate() {
+        let Some(hit) = beam_segment(ray, half_width, segment, index) else {
+            continue;
+        };
+        match nearest {
+            Some(best) if best.t <= hit.t => (),
+            _ => nearest = Some(hit),
+        }
+    }
+    nearest
+}
+
+/// Cast a beam against a single segment.
+fn beam_segment(
+    ray: &Ray,
+    half_width: f32,
+    segment: &Segment,
+    index: usize,
+) -> Option<BeamHit> {
+    // The closest approach is where the ray crosses the segment, or else
+    // at one of the segment's ends or the ray's origin.
+    let along =
+        |point: &Point2<f32>| (point - ray.origin).dot(&ray.direction).max(0.0);
+    let (approach_t, point, distance) =
+        match intersect_ray_segment(ray, segment) {
+            Some(t) => (t, ray.origin + ray.direction.scale(t), 0.0),
+            None => {
+                let from_origin = segment.closest_point(&ray.origin);
+                [segment.start, segment.end]
+                    .iter()
+                    .map(|end| {
+                        let t = along(end);
+                        (
+                            t,
+                            *end,
+                            (ray.origin + ray.direction.scale(t) - end).norm(),
+                        )
+                    })
+                    .chain([(
+                        0.0,
+                        from_origin,
+                        (from_origin - ray.origin).norm(),
+                    )])
+                    .min_by(|a, b| a.2.total_cmp(&b.2))
+                    .unwrap()
+            }
+        };
+    if distance > half_width {
+        return None;
+    }
+
+    // The beam first touches the segment where the ray enters the capsule
+    // around it: one of the circles at its ends, or one of its sides.
+    let t = if segment.distance_to_point(&ray.origin) <= half_width {
+        0.0
+    } else {
+        let direction = segment.end - segment.start;
+        let offset = Vector2::new(-direction.y, direction.x)
+            .try_normalize(0.0)
+            .unwrap_or_else(Vector2::zeros)
+            * half_width;
+        let sides = [offset, -offset].map(|offset| {
+            let side =
+                Segment::new(segment.start + offset, segment.end + offset);
+            intersect_ray_segment(ray, &side)
+        });
+        [segment.start, segment.end]
+            .iter()
+            .map(|end| ray_circle_entry(ray, end, half_width))
+            .chain(sides)
+            .flatten()
+            .fold(approach_t, f32::min)
+    };
+    Some(BeamHit {
+        index,
+        t,
+        point,
+        distance,
+    })
+}
+
+/// The ray parameter where a ray enters a circle, for rays which start
+/// outside of it.
+fn ray_circle_entry(
+    ray: &Ray,
+    center: &Point2<f32>,
+    radius: f32,
+) -> Option<f32> {
+    let offset = ray.origin - center;
+    let b = offset.dot(&ray.direction);
+    let discriminant = b * b - (offset.norm_squared() - radius * radius);
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = -b - math::sqrt(discriminant);
+    (t >= 0.0).then_some(t)
+}
+
+/// Cast many rays against the same collection of segments.
+///
+/// The result has one entry per ray, in the same order as `rays`.
+#[cfg(feature = "alloc")]
+pub fn raycast_many(rays: &[Ray], segments: &[Segment]) -> Vec<Option<RayHit>> {
+    rays.iter().map(|ray| raycast(ray, segments)).collect()
 }
 
 #[cfg(test)]
 mod test {
     use {
-        crate::line::{
-            intersection::{intersect_segments, SegmentIntersection},
-            Segment,
+        crate::{
+            line::{
+                intersection::{
+                    beamcast, classify_intersection, intersect_ray_segment,
+                    intersect_ray_segment_within, intersect_segments,
+                    intersect_segments_detailed, raycast, raycast_within,
+                    DetailedIntersection, IntersectionKind,
+                    SegmentIntersection,
+                },
+                DistanceToPoint, Ray, Segment,
+            },
+            test_support::{ray, segment},
         },
-        nalgebra::point,
+        approx::{assert_relative_eq, relative_eq},
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
     };
 
+    /// Two intersections are equivalent when they have the same kind and
+    /// their geometry matches up to floating point error. The direction of
+    /// an overlapping segment is ignored.
+    fn equivalent(a: &SegmentIntersection, b: &SegmentIntersection) -> bool {
+        let close = |p: &nalgebra::Point2<f32>, q: &nalgebra::Point2<f32>| {
+            relative_eq!(p, q, epsilon = 1e-3, max_relative = 1e-4)
+        };
+        match (a, b) {
+            (SegmentIntersection::None, SegmentIntersection::None) => true,
+            (SegmentIntersection::Point(p), SegmentIntersection::Point(q)) => {
+                close(p, q)
+            }
+            (
+                SegmentIntersection::Overlap(s),
+                SegmentIntersection::Overlap(t),
+            ) => {
+                (close(&s.start, &t.start) && close(&s.end, &t.end))
+                    || (close(&s.start, &t.end) && close(&s.end, &t.start))
+            }
+            _ => false,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn classification_should_agree_with_intersect_segments(
+            a in segment(-10.0..=10.0, 0.1),
+            b in segment(-10.0..=10.0, 0.1),
+        ) {
+            let kind = classify_intersection(&a, &b, 1e-3);
+            prop_assert_eq!(kind, classify_intersection(&b, &a, 1e-3));
+
+            let intersection = intersect_segments(&a, &b);
+            match kind {
+                IntersectionKind::CrossingInterior => {
+                    prop_assert!(
+                        matches!(intersection, SegmentIntersection::Point(_))
+                    );
+                }
+                IntersectionKind::CollinearOverlapping => {
+                    prop_assert!(
+                        !matches!(intersection, SegmentIntersection::None)
+                    );
+                }
+                IntersectionKind::Disjoint => {
+                    prop_assert_eq!(intersection, SegmentIntersection::None);
+                }
+                _ => {}
+            }
+            if let SegmentIntersection::Point(p) = intersection {
+                let far_from_ends = [a.start, a.end, b.start, b.end]
+                    .iter()
+                    .all(|end| (end - p).norm() > 0.1);
+                if far_from_ends {
+                    prop_assert_eq!(kind, IntersectionKind::CrossingInterior);
+                }
+            }
+        }
+
+        #[test]
+        fn classification_should_find_constructed_touches(
+            a in segment(-10.0..=10.0, 0.5),
+            t in 0.1f32..0.9,
+            offset in -10.0f32..10.0,
+        ) {
+            prop_assume!(offset.abs() > 0.5);
+            let normal = nalgebra::Vector2::new(
+                a.start.y - a.end.y,
+                a.end.x - a.start.x,
+            )
+            .normalize();
+            let on_a = a.start + (a.end - a.start) * t;
+            let stem = Segment::new(on_a, on_a + normal * offset);
+            let corner = Segment::new(a.end, a.end + normal * offset);
+
+            prop_assert_eq!(
+                classify_intersection(&a, &stem, 1e-3),
+                IntersectionKind::TouchingEndpointToInterior
+            );
+            prop_assert_eq!(
+                classify_intersection(&a, &corner, 1e-3),
+                IntersectionKind::TouchingEndpointToEndpoint
+            );
+        }
+
+        #[test]
+        fn segment_intersection_should_be_symmetric(
+            a in any::<Segment>(),
+            b in any::<Segment>(),
+        ) {
+            let ab = intersect_segments(&a, &b);
+            let ba = intersect_segments(&b, &a);
+            prop_assert!(equivalent(&ab, &ba), "{:?} != {:?}", ab, ba);
+        }
+
+        #[test]
+        fn segment_intersections_should_lie_on_both_segments(
+            a in any::<Segment>(),
+            b in any::<Segment>(),
+        ) {
+            if let SegmentIntersection::Point(p) = intersect_segments(&a, &b) {
+                use crate::line::DistanceToPoint;
+                prop_assert!(a.distance_to_point(&p) < 1e-3);
+                prop_assert!(b.distance_to_point(&p) < 1e-3);
+            }
+        }
+
+        #[test]
+        fn detailed_parameters_should_locate_the_intersection(
+            a in segment(-10.0..=10.0, 0.1),
+            b in segment(-10.0..=10.0, 0.1),
+        ) {
+            let at = |s: &Segment, t: f32| s.start + (s.end - s.start) * t;
+            let close = |p: nalgebra::Point2<f32>, q: nalgebra::Point2<f32>| {
+                (p - q).norm() < 1e-3
+            };
+            match intersect_segments_detailed(&a, &b) {
+                DetailedIntersection::None => (),
+                DetailedIntersection::Point { point, t_a, t_b } => {
+                    prop_assert!((0.0..=1.0).contains(&t_a));
+                    prop_assert!((0.0..=1.0).contains(&t_b));
+                    prop_assert!(close(at(&a, t_a), point));
+                    prop_assert!(close(at(&b, t_b), point));
+                }
+                DetailedIntersection::Overlap { segment, t_a, t_b } => {
+                    prop_assert!(t_a.0 < t_a.1);
+                    prop_assert!(close(at(&a, t_a.0), segment.start));
+                    prop_assert!(close(at(&a, t_a.1), segment.end));
+                    prop_assert!(close(at(&b, t_b.0), segment.start));
+                    prop_assert!(close(at(&b, t_b.1), segment.end));
+                }
+            }
+        }
+    }
+
+    fn classify(a: [f32; 4], b: [f32; 4], tolerance: f32) -> IntersectionKind {
+        let segment = |[x0, y0, x1, y1]: [f32; 4]| {
+            Segment::new(point![x0, y0], point![x1, y1])
+        };
+        let kind = classify_intersection(&segment(a), &segment(b), tolerance);
+        assert_eq!(
+            kind,
+            classify_intersection(&segment(b), &segment(a), tolerance)
+        );
+        kind
+    }
+
+    #[test]
+    pub fn segments_with_large_coordinates_should_intersect() {
+        let a = Segment::new(point![-1e10, 0.0], point![1e10, 0.0]);
+        let b = Segment::new(point![0.0, -1e10], point![0.0, 1e10]);
+
+        let SegmentIntersection::Point(p) = intersect_segments(&a, &b) else {
+            panic!("the segments cross at the origin");
+        };
+        assert_relative_eq!(p, point![0.0, 0.0]);
+    }
+
+    #[test]
+    pub fn classification_should_find_disjoint_segments() {
+        let kind = classify([0.0, 0.0, 1.0, 0.0], [0.0, 1.0, 1.0, 2.0], 0.0);
+        assert_eq!(kind, IntersectionKind::Disjoint);
+
+        let kind = classify([0.0, 0.0, 1.0, 0.0], [2.0, 0.0, 3.0, 0.0], 0.5);
+        assert_eq!(kind, IntersectionKind::Disjoint);
+    }
+
+    #[test]
+    pub fn classification_should_find_proper_crossings() {
+        let kind = classify([0.0, 0.0, 2.0, 2.0], [0.0, 2.0, 2.0, 0.0], 0.1);
+        assert_eq!(kind, IntersectionKind::CrossingInterior);
+    }
+
+    #[test]
+    pub fn classification_should_find_endpoints_touching_interiors() {
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [2.0, 0.0, 2.0, 3.0], 0.0);
+        assert_eq!(kind, IntersectionKind::TouchingEndpointToInterior);
+
+        // Close enough to count, even though the stem stops short.
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [2.0, 0.05, 2.0, 3.0], 0.1);
+        assert_eq!(kind, IntersectionKind::TouchingEndpointToInterior);
+    }
+
+    #[test]
+    pub fn classification_should_find_shared_endpoints() {
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [4.0, 0.0, 4.0, 3.0], 0.0);
+        assert_eq!(kind, IntersectionKind::TouchingEndpointToEndpoint);
+
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [4.0, 0.01, 5.0, 3.0], 0.1);
+        assert_eq!(kind, IntersectionKind::TouchingEndpointToEndpoint);
+    }
+
+    #[test]
+    pub fn classification_should_find_collinear_overlaps() {
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [3.0, 0.0, 1.0, 0.0], 0.0);
+        assert_eq!(kind, IntersectionKind::CollinearOverlapping);
+
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [2.0, 0.01, 6.0, -0.01], 0.1);
+        assert_eq!(kind, IntersectionKind::CollinearOverlapping);
+    }
+
+    #[test]
+    pub fn classification_should_find_collinear_segments_touching_ends() {
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [4.0, 0.0, 6.0, 0.0], 0.0);
+        assert_eq!(kind, IntersectionKind::CollinearTouching);
+
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [6.0, 0.0, 4.05, 0.0], 0.1);
+        assert_eq!(kind, IntersectionKind::CollinearTouching);
+    }
+
+    #[test]
+    pub fn classification_should_treat_short_segments_as_points() {
+        let kind = classify([0.0, 0.0, 4.0, 0.0], [2.0, 0.0, 2.0, 0.01], 0.1);
+        assert_eq!(kind, IntersectionKind::TouchingEndpointToInterior);
+
+        let kind = classify([0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], 0.0);
+        assert_eq!(kind, IntersectionKind::TouchingEndpointToEndpoint);
+
+        let kind = classify([0.0, 0.0, 0.0, 0.0], [1.0, 0.0, 1.0, 0.0], 0.0);
+        assert_eq!(kind, IntersectionKind::Disjoint);
+    }
+
     #[test]
     pub fn segments_should_not_intersect_when_parallel_and_non_overlapping() {
         let s1 = Segment::new(point![0.0, 0.0], point![1.0, 0.0]);
@@ -86,4 +1092,243 @@ mod test {
                 == SegmentIntersection::Point(point![1.0, 0.0])
         );
     }
+
+    #[test]
+    pub fn segments_should_intersect_when_they_cross() {
+        let s1 = Segment::new(point![0.0, 0.0], point![4.0, 2.0]);
+        let s2 = Segment::new(point![0.0, 2.0], point![4.0, 0.0]);
+        assert_eq!(
+            intersect_segments(&s1, &s2),
+            SegmentIntersection::Point(point![2.0, 1.0])
+        );
+    }
+
+    #[test]
+    pub fn segments_should_intersect_when_they_share_an_endpoint() {
+        let s1 = Segment::new(point![0.0, 0.0], point![1.0, 1.0]);
+        let s2 = Segment::new(point![1.0, 1.0], point![2.0, 0.0]);
+        assert_eq!(
+            intersect_segments(&s1, &s2),
+            SegmentIntersection::Point(point![1.0, 1.0])
+        );
+    }
+
+    #[test]
+    pub fn segments_should_not_intersect_when_lines_cross_outside_them() {
+        let s1 = Segment::new(point![0.0, 0.0], point![1.0, 0.0]);
+        let s2 = Segment::new(point![3.0, -1.0], point![3.0, 1.0]);
+        assert_eq!(intersect_segments(&s1, &s2), SegmentIntersection::None);
+    }
+
+    #[test]
+    pub fn segments_should_overlap_when_collinear() {
+        let s1 = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+        let s2 = Segment::new(point![6.0, 0.0], point![2.0, 0.0]);
+        assert_eq!(
+            intersect_segments(&s1, &s2),
+            SegmentIntersection::Overlap(Segment::new(
+                point![2.0, 0.0],
+                point![4.0, 0.0]
+            ))
+        );
+    }
+
+    #[test]
+    pub fn segments_should_intersect_at_a_point_when_collinear_and_touching() {
+        let s1 = Segment::new(point![0.0, 0.0], point![2.0, 0.0]);
+        let s2 = Segment::new(point![2.0, 0.0], point![3.0, 0.0]);
+        assert_eq!(
+            intersect_segments(&s1, &s2),
+            SegmentIntersection::Point(point![2.0, 0.0])
+        );
+    }
+
+    #[test]
+    pub fn segments_should_intersect_when_a_point_lies_on_a_segment() {
+        let s1 = Segment::new(point![1.0, 1.0], point![1.0, 1.0]);
+        let s2 = Segment::new(point![0.0, 0.0], point![2.0, 2.0]);
+        assert_eq!(
+            intersect_segments(&s1, &s2),
+            SegmentIntersection::Point(point![1.0, 1.0])
+        );
+        assert_eq!(
+            intersect_segments(&s2, &s1),
+            SegmentIntersection::Point(point![1.0, 1.0])
+        );
+    }
+
+    #[test]
+    pub fn rays_should_not_hit_segments_behind_the_origin() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![1.0, 0.0]));
+        let segment = Segment::new(point![-1.0, -1.0], point![-1.0, 1.0]);
+        assert_eq!(intersect_ray_segment(&ray, &segment), None);
+    }
+
+    #[test]
+    pub fn rays_should_hit_the_near_end_of_collinear_segments() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![1.0, 0.0]));
+        let segment = Segment::new(point![5.0, 0.0], point![3.0, 0.0]);
+        assert_relative_eq!(
+            intersect_ray_segment(&ray, &segment).unwrap(),
+            3.0
+        );
+    }
+
+    #[test]
+    pub fn raycast_should_return_the_nearest_hit() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![0.0, 1.0]));
+        let segments = [
+            Segment::new(point![-1.0, 4.0], point![1.0, 4.0]),
+            Segment::new(point![2.0, 1.0], point![3.0, 1.0]),
+            Segment::new(point![-1.0, 2.0], point![1.0, 3.0]),
+        ];
+        let hit = raycast(&ray, &segments).unwrap();
+        assert_eq!(hit.index, 2);
+        assert_relative_eq!(hit.t, 2.5);
+        assert_relative_eq!(hit.point, point![0.0, 2.5]);
+    }
+
+    #[test]
+    pub fn bounded_rays_should_include_hits_at_their_maximum() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![1.0, 0.0]));
+        let crossing = Segment::new(point![2.0, -1.0], point![2.0, 1.0]);
+        let collinear = Segment::new(point![2.0, 0.0], point![5.0, 0.0]);
+
+        for segment in [crossing, collinear] {
+            assert_eq!(
+                intersect_ray_segment_within(&ray, &segment, 2.0),
+                Some(2.0)
+            );
+            assert_eq!(intersect_ray_segment_within(&ray, &segment, 1.9), None);
+        }
+        // A collinear segment which starts behind the origin is hit at zero.
+        let behind = Segment::new(point![-2.0, 0.0], point![1.0, 0.0]);
+        assert_eq!(intersect_ray_segment_within(&ray, &behind, 0.0), Some(0.0));
+    }
+
+    #[test]
+    pub fn bounded_raycasts_should_ignore_hits_past_the_maximum() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![0.0, 1.0]));
+        let segments = [
+            Segment::new(point![-1.0, 4.0], point![1.0, 4.0]),
+            Segment::new(point![-1.0, 2.0], point![1.0, 2.0]),
+        ];
+
+        assert_eq!(raycast_within(&ray, &segments, 1.5), None);
+        let hit = raycast_within(&ray, &segments, 2.0).unwrap();
+        assert_eq!(hit.index, 1);
+        assert_relative_eq!(hit.t, 2.0);
+    }
+
+    #[test]
+    pub fn beams_should_pick_segments_which_rays_narrowly_miss() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![1.0, 0.0]));
+        let half_width = 0.2;
+        // Ends half a beam width above the ray.
+        let tick = Segment::new(point![3.0, 0.1], point![3.0, 2.0]);
+
+        assert!(raycast(&ray, &[tick]).is_none());
+        let hit = beamcast(&ray, half_width, &[tick]).unwrap();
+        assert_relative_eq!(hit.point, point![3.0, 0.1]);
+        assert_relative_eq!(hit.distance, 0.1);
+        // Where the edge of the beam meets the end of the tick.
+        assert_relative_eq!(hit.t, 3.0 - 0.03f32.sqrt(), epsilon = 1e-5);
+        assert!(beamcast(&ray, 0.05, &[tick]).is_none());
+
+        // Within reach of the origin, or behind it, is hit immediately.
+        let behind = Segment::new(point![-1.0, 0.1], point![-1.0, -0.1]);
+        let around = Segment::new(point![-1.0, 0.1], point![1.0, 0.1]);
+        assert!(beamcast(&ray, half_width, &[behind]).is_none());
+        assert_eq!(beamcast(&ray, half_width, &[around]).unwrap().t, 0.0);
+    }
+
+    #[test]
+    pub fn beams_should_order_candidates_by_where_they_touch() {
+        let ray =
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![0.0, 1.0]));
+        let segments = [
+            // Crossed head on.
+            Segment::new(point![-1.0, 5.0], point![1.0, 5.0]),
+            // Passes close to the ray, but only far away.
+            Segment::new(point![0.05, 8.0], point![0.05, 9.0]),
+            // Grazed by the edge of the beam before the first crossing.
+            Segment::new(point![0.3, 4.5], point![1.0, 4.5]),
+            // The same, but later in the list.
+            Segment::new(point![-0.3, 4.5], point![-1.0, 4.5]),
+        ];
+
+        let hit = beamcast(&ray, 0.5, &segments).unwrap();
+        assert_eq!(hit.index, 2);
+        assert_relative_eq!(hit.t, 4.1, epsilon = 1e-5);
+        assert_relative_eq!(hit.distance, 0.3);
+
+        let hit = beamcast(&ray, 0.5, &segments[..2]).unwrap();
+        assert_eq!(hit.index, 0);
+        assert_relative_eq!(hit.t, 4.5);
+        assert_eq!(hit.distance, 0.0);
+        assert_eq!(beamcast(&ray, 0.1, &segments[1..2]).unwrap().index, 0);
+        assert!(beamcast(&ray, 0.5, &[]).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn beams_should_touch_no_later_than_rays_hit(
+            ray in ray(-10.0..=10.0),
+            segments in proptest::collection::vec(
+                segment(-10.0..=10.0, 0.1),
+                0..16,
+            ),
+            half_width in 0.0f32..=2.0,
+        ) {
+            let thin = raycast(&ray, &segments);
+            let beam = beamcast(&ray, half_width, &segments);
+
+            if let Some(thin) = thin {
+                let beam = beam.unwrap();
+                prop_assert!(beam.t <= thin.t + 1e-4);
+            }
+            if let Some(hit) = beam {
+                prop_assert!(hit.distance <= half_width);
+                // The edge of the beam touches the segment at `t`.
+                let center = ray.origin + ray.direction.scale(hit.t);
+                let reach = segments[hit.index].distance_to_point(&center);
+                prop_assert!(reach <= half_width + 1e-3, "{reach}");
+                if hit.t > 0.0 {
+                    prop_assert!(reach >= half_width - 1e-3, "{reach}");
+                }
+            }
+            let zero = beamcast(&ray, 0.0, &segments);
+            prop_assert_eq!(zero.map(|hit| hit.index), thin.map(|hit| hit.index));
+        }
+
+        #[test]
+        fn bounded_raycasts_should_match_filtered_raycasts(
+            ray in ray(-10.0..=10.0),
+            segments in proptest::collection::vec(
+                segment(-10.0..=10.0, 0.1),
+                0..16,
+            ),
+            max_t in 0.0f32..=30.0,
+        ) {
+            let unbounded = raycast(&ray, &segments);
+            prop_assert_eq!(
+                raycast_within(&ray, &segments, f32::INFINITY),
+                unbounded
+            );
+            for segment in &segments {
+                prop_assert_eq!(
+                    intersect_ray_segment_within(&ray, segment, f32::INFINITY),
+                    intersect_ray_segment(&ray, segment)
+                );
+            }
+            let expected = unbounded.filter(|hit| hit.t <= max_t);
+            prop_assert_eq!(raycast_within(&ray, &segments, max_t), expected);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 //! Structs and algorithms for Lines in two dimensions.
 
+mod attributed;
 mod distance_to_point;
 mod infinite;
 mod ray;
@@ -8,6 +9,9 @@ mod segment;
 pub mod intersection;
 
 pub use self::{
-    distance_to_point::DistanceToPoint, infinite::Line, ray::Ray,
+    attributed::{AttributedSegment, Lerp},
+    distance_to_point::DistanceToPoint,
+    infinite::Line,
+    ray::Ray,
     segment::Segment,
 };
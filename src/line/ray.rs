@@ -1,6 +1,14 @@
 use {
-    crate::line::{DistanceToPoint, Segment},
-    nalgebra::{Point2, Unit, Vector2},
+    crate::{
+        display::{write_compact_debug, write_point},
+        error::check_finite,
+        line::{intersection::intersect_ray_segment, DistanceToPoint, Segment},
+        math::atan2,
+        shape::Aabb,
+        Error,
+    },
+    core::{f32::consts::PI, fmt},
+    nalgebra::{Point2, Rotation2, Unit, Vector2},
 };
 
 /// A Ray is a half-line which begins at an origin point.
@@ -12,9 +20,9 @@ use {
 /// ```
 ///
 /// Where `P` is the origin point for the Ray and `V` is the direction the
-/// Ray points.
+/// Ray points. Rays are displayed in this form, like `(2, 2) + t*(0, 1)`.
 ///
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Ray {
     /// The Ray's origin in 2d space.
     pub origin: Point2<f32>,
@@ -26,6 +34,9 @@ pub struct Ray {
 impl Ray {
     /// Create a new Ray with the given origin and direction.
     ///
+    /// The origin isn't validated. Use [`Ray::try_new`] to reject non-finite
+    /// values.
+    ///
     /// A Ray is defined by the parametric equation:
     /// ```math
     /// Ray(t) = origin + t*direction
@@ -71,10 +82,183 @@ impl Ray {
         Self { origin, direction }
     }
 
+    /// Create a new Ray, normalizing the direction and checking that every
+    /// value is finite.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Ray, Error},
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let origin = point![1.0, 1.0];
+    ///
+    ///     assert_eq!(
+    ///         Ray::try_new(origin, vector![0.0, 0.0]),
+    ///         Err(Error::ZeroVector)
+    ///     );
+    ///     assert!(Ray::try_new(origin, vector![3.0, 4.0]).is_ok());
+    ///
+    pub fn try_new(
+        origin: Point2<f32>,
+        direction: Vector2<f32>,
+    ) -> Result<Self, Error> {
+        check_finite(&[origin.x, origin.y, direction.x, direction.y])?;
+        let direction =
+            Unit::try_new(direction, 0.0).ok_or(Error::ZeroVector)?;
+        Ok(Self::new(origin, direction))
+    }
+
+    /// Create a Ray which starts at the segment's `start` point and points
+    /// towards the `end` point, checking that the segment is valid.
+    ///
+    /// This is the checked version of `Ray::from(segment)`.
+    pub fn try_from_segment(segment: &Segment) -> Result<Self, Error> {
+        let segment = Segment::try_new(segment.start, segment.end)?;
+        Ok(Self::from(segment))
+    }
+
     /// Create a line segment from this Ray with a given length.
     pub fn as_segment(&self, length: f32) -> Segment {
         Segment::new(self.origin, self.origin + self.direction.scale(length))
     }
+
+    /// The part of the Ray which is inside a box.
+    ///
+    /// When the origin is inside the box the segment runs from the origin to
+    /// the point where the Ray leaves. When the origin is outside the segment
+    /// runs from where the Ray enters to where it leaves. Returns `None` when
+    /// the Ray misses the box.
+    ///
+    /// An origin on the boundary counts as inside, so a Ray pointing into the
+    /// box from its boundary starts at the origin. A Ray which only touches
+    /// the box at a single point, including one pointing away from the box
+    /// from its boundary, returns `None`.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::{Ray, Segment}, shape::Aabb},
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+    ///     let ray = Ray::new(
+    ///         point![-1.0, 1.0],
+    ///         Unit::new_normalize(vector![1.0, 0.0])
+    ///     );
+    ///
+    ///     assert_eq!(
+    ///         ray.clip_to_aabb(&bounds),
+    ///         Some(Segment::new(point![0.0, 1.0], point![4.0, 1.0]))
+    ///     );
+    ///
+    pub fn clip_to_aabb(&self, aabb: &Aabb) -> Option<Segment> {
+        let direction = self.direction.into_inner();
+        let (enter, exit) = aabb.slab_parameters(&self.origin, &direction)?;
+        let enter = enter.max(0.0);
+        let clamp = |t: f32| {
+            (self.origin + direction * t).sup(&aabb.min).inf(&aabb.max)
+        };
+        (enter < exit).then(|| Segment::new(clamp(enter), clamp(exit)))
+    }
+
+    /// The segment from the Ray's origin to the first point where it hits
+    /// another segment.
+    ///
+    /// Returns `None` when the Ray misses the segment. When the segment lies
+    /// on the Ray, the result stops at the nearest point of overlap. The
+    /// result has zero length when the origin is on the segment.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::{Ray, Segment},
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let ray = Ray::new(
+    ///         point![0.0, 0.0],
+    ///         Unit::new_normalize(vector![0.0, 1.0])
+    ///     );
+    ///     let wall = Segment::new(point![-1.0, 3.0], point![1.0, 3.0]);
+    ///
+    ///     assert_eq!(
+    ///         ray.as_segment_until(&wall),
+    ///         Some(Segment::new(point![0.0, 0.0], point![0.0, 3.0]))
+    ///     );
+    ///
+    pub fn as_segment_until(&self, other: &Segment) -> Option<Segment> {
+        intersect_ray_segment(self, other).map(|t| self.as_segment(t))
+    }
+
+    /// Interpolate between two rays.
+    ///
+    /// The origin is linearly interpolated and the direction rotates at a
+    /// constant angular speed along the shorter arc between the two
+    /// directions. When the directions are exactly opposite, the direction
+    /// rotates counterclockwise.
+    ///
+    /// Values of `t` outside of `[0, 1]` extrapolate: the origin continues
+    /// along the same line and the direction keeps rotating at the same rate.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::Ray,
+    ///         nalgebra::{point, vector, Unit},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let right = Unit::new_normalize(vector![1.0, 0.0]);
+    ///     let up = Unit::new_normalize(vector![0.0, 1.0]);
+    ///     let a = Ray::new(point![0.0, 0.0], right);
+    ///     let b = Ray::new(point![2.0, 0.0], up);
+    ///     let halfway = a.slerp(&b, 0.5);
+    ///
+    ///     assert_relative_eq!(halfway.origin, point![1.0, 0.0]);
+    ///     assert_relative_eq!(
+    ///         halfway.direction,
+    ///         Unit::new_normalize(vector![1.0, 1.0])
+    ///     );
+    ///
+    pub fn slerp(&self, other: &Ray, t: f32) -> Ray {
+        let cross = self.direction.perp(&other.direction);
+        let dot = self.direction.dot(&other.direction);
+        let angle = if cross == 0.0 && dot < 0.0 {
+            // atan2 can return either -pi or pi here depending on the sign of
+            // zero, so pick the counterclockwise rotation explicitly.
+            PI
+        } else {
+            atan2(cross, dot)
+        };
+        let direction = Rotation2::new(angle * t) * self.direction.as_ref();
+        Ray::new(
+            self.origin + (other.origin - self.origin) * t,
+            Unit::new_normalize(direction),
+        )
+    }
+}
+
+impl fmt::Display for Ray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_point(f, &self.origin)?;
+        f.write_str(" + t*")?;
+        write_point(f, &Point2::from(self.direction.into_inner()))
+    }
+}
+
+impl fmt::Debug for Ray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write_compact_debug(f, "Ray", self);
+        }
+        f.debug_struct("Ray")
+            .field("origin", &self.origin)
+            .field("direction", &self.direction)
+            .finish()
+    }
 }
 
 impl DistanceToPoint for Ray {
@@ -201,6 +385,10 @@ impl From<Segment> for Ray {
     /// Create a Ray which starts at the segment's `start` point and points
     /// towards the `end` point.
     ///
+    /// The segment isn't validated, so a zero-length segment produces a ray
+    /// with a NaN direction. Use [`Ray::try_from_segment`] to reject
+    /// degenerate segments.
+    ///
     /// # Example
     ///
     /// Turn a segment like:
@@ -273,3 +461,166 @@ impl From<Segment> for Ray {
         Ray::new(origin, direction)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{Ray, Segment},
+            shape::Aabb,
+            test_support::{aabb, ray as any_ray},
+        },
+        approx::assert_relative_eq,
+        core::f32::consts::FRAC_PI_2,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    fn ray(x: f32, y: f32) -> Ray {
+        Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![x, y]))
+    }
+
+    #[test]
+    pub fn slerp_should_take_the_shorter_arc() {
+        let a = ray(1.0, 0.0);
+        let b = ray(0.0, -1.0);
+
+        assert_relative_eq!(
+            a.slerp(&b, 0.5).direction,
+            Unit::new_normalize(vector![1.0, -1.0])
+        );
+    }
+
+    #[test]
+    pub fn slerp_should_rotate_counterclockwise_between_opposite_rays() {
+        let a = ray(1.0, 0.0);
+        let b = ray(-1.0, 0.0);
+
+        assert_relative_eq!(
+            a.slerp(&b, 0.5).direction,
+            Unit::new_normalize(vector![0.0, 1.0]),
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            b.slerp(&a, 0.5).direction,
+            Unit::new_normalize(vector![0.0, -1.0]),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    pub fn slerp_should_extrapolate_outside_the_unit_interval() {
+        let a = ray(1.0, 0.0);
+        let b =
+            Ray::new(point![1.0, 1.0], Unit::new_normalize(vector![1.0, 1.0]));
+
+        let extrapolated = a.slerp(&b, 2.0);
+
+        assert_relative_eq!(extrapolated.origin, point![2.0, 2.0]);
+        assert_relative_eq!(
+            extrapolated.direction,
+            Unit::new_normalize(vector![0.0, 1.0]),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    pub fn repeated_slerp_should_stay_unit_length() {
+        let target = ray(-0.3, -1.0);
+        let mut current = ray(1.0, 0.2);
+        for step in 0..10_000 {
+            current = current.slerp(&target, 0.001 * (step % 7) as f32);
+            assert_relative_eq!(current.direction.norm(), 1.0, epsilon = 1e-6);
+        }
+
+        let a = ray(1.0, 0.0);
+        let b = ray(0.0, 1.0);
+        for step in 0..=1000 {
+            let t = step as f32 / 1000.0;
+            let direction = a.slerp(&b, t).direction;
+            assert_relative_eq!(direction.norm(), 1.0, epsilon = 1e-6);
+            assert_relative_eq!(
+                direction.y.atan2(direction.x),
+                t * FRAC_PI_2,
+                epsilon = 1e-5
+            );
+        }
+    }
+
+    fn aimed(x: f32, y: f32, dx: f32, dy: f32) -> Ray {
+        Ray::new(point![x, y], Unit::new_normalize(vector![dx, dy]))
+    }
+
+    fn bounds() -> Aabb {
+        Aabb::new(point![0.0, 0.0], point![4.0, 2.0])
+    }
+
+    #[test]
+    pub fn clip_to_aabb_should_start_at_an_origin_inside_the_box() {
+        let clipped = aimed(1.0, 1.0, 1.0, 0.0).clip_to_aabb(&bounds());
+
+        assert_eq!(
+            clipped,
+            Some(Segment::new(point![1.0, 1.0], point![4.0, 1.0]))
+        );
+    }
+
+    #[test]
+    pub fn clip_to_aabb_should_miss_boxes_behind_the_origin() {
+        assert_eq!(aimed(5.0, 1.0, 1.0, 0.0).clip_to_aabb(&bounds()), None);
+        assert_eq!(aimed(-1.0, 3.0, 1.0, 0.0).clip_to_aabb(&bounds()), None);
+    }
+
+    #[test]
+    pub fn clip_to_aabb_should_treat_boundary_origins_as_inside() {
+        let inward = aimed(0.0, 1.0, 1.0, 0.0).clip_to_aabb(&bounds());
+        let outward = aimed(0.0, 1.0, -1.0, 0.0).clip_to_aabb(&bounds());
+        let along = aimed(1.0, 0.0, 1.0, 0.0).clip_to_aabb(&bounds());
+        let corner = aimed(4.0, 2.0, 1.0, 1.0).clip_to_aabb(&bounds());
+
+        assert_eq!(
+            inward,
+            Some(Segment::new(point![0.0, 1.0], point![4.0, 1.0]))
+        );
+        assert_eq!(outward, None);
+        assert_eq!(
+            along,
+            Some(Segment::new(point![1.0, 0.0], point![4.0, 0.0]))
+        );
+        assert_eq!(corner, None);
+    }
+
+    #[test]
+    pub fn as_segment_until_should_stop_at_the_nearest_overlap() {
+        let ray = aimed(0.0, 0.0, 1.0, 0.0);
+        let collinear = Segment::new(point![5.0, 0.0], point![2.0, 0.0]);
+        let behind = Segment::new(point![-1.0, -1.0], point![-1.0, 1.0]);
+
+        assert_eq!(
+            ray.as_segment_until(&collinear),
+            Some(Segment::new(point![0.0, 0.0], point![2.0, 0.0]))
+        );
+        assert_eq!(ray.as_segment_until(&behind), None);
+    }
+
+    proptest! {
+        #[test]
+        fn clipped_rays_should_stay_on_the_ray_and_in_the_box(
+            ray in any_ray(-10.0..=10.0),
+            bounds in aabb(-5.0..=5.0),
+        ) {
+            if let Some(clipped) = ray.clip_to_aabb(&bounds) {
+                let tolerance = 1e-4;
+                for p in [clipped.start, clipped.end] {
+                    prop_assert!(p.x >= bounds.min.x - tolerance);
+                    prop_assert!(p.x <= bounds.max.x + tolerance);
+                    prop_assert!(p.y >= bounds.min.y - tolerance);
+                    prop_assert!(p.y <= bounds.max.y + tolerance);
+                    let offset = p - ray.origin;
+                    prop_assert!(offset.perp(&ray.direction).abs() < 1e-3);
+                    prop_assert!(offset.dot(&ray.direction) >= -tolerance);
+                }
+            }
+        }
+    }
+}
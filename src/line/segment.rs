@@ -1,7 +1,23 @@
-use {crate::line::DistanceToPoint, nalgebra::Point2};
+use {
+    crate::{
+        display::{write_compact_debug, write_point},
+        error::check_finite,
+        line::{DistanceToPoint, Line},
+        path::{Frame, PathFrame},
+        shape::{
+            Aabb, BoundingBox, BoundingCircle, BoundingObb, Circle, Obb,
+            SupportMap,
+        },
+        Error,
+    },
+    core::fmt,
+    nalgebra::{Matrix2, Point2, Unit, Vector2},
+};
 
 /// A line-segment defined by a start point and an end point.
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// Segments are displayed as `(1, 3) -> (7, 3)`.
+#[derive(Copy, Clone, PartialEq)]
 pub struct Segment {
     pub start: Point2<f32>,
     pub end: Point2<f32>,
@@ -10,6 +26,9 @@ pub struct Segment {
 impl Segment {
     /// Create a new line segment defined by a start and end point.
     ///
+    /// The points aren't validated. Use [`Segment::try_new`] to reject
+    /// non-finite and zero-length segments.
+    ///
     /// # Example
     ///
     /// ```none
@@ -45,6 +64,57 @@ impl Segment {
         Self { start, end }
     }
 
+    /// Create a new line segment, checking that it has finite coordinates
+    /// and nonzero length.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, Error},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let p = point![1.0, 3.0];
+    ///
+    ///     assert_eq!(Segment::try_new(p, p), Err(Error::DegenerateSegment));
+    ///     assert!(Segment::try_new(p, point![7.0, 3.0]).is_ok());
+    ///
+    pub fn try_new(
+        start: Point2<f32>,
+        end: Point2<f32>,
+    ) -> Result<Self, Error> {
+        check_finite(&[start.x, start.y, end.x, end.y])?;
+        if start == end {
+            return Err(Error::DegenerateSegment);
+        }
+        Ok(Self::new(start, end))
+    }
+
+    /// Create a segment from `[start, end]` coordinate pairs, like the
+    /// vertices of a line in a GPU buffer.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::Segment,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let segment = Segment::from_array([[0.0, 1.0], [2.0, 3.0]]);
+    ///
+    ///     assert_eq!(segment.end, point![2.0, 3.0]);
+    ///     assert_eq!(segment.to_array(), [[0.0, 1.0], [2.0, 3.0]]);
+    ///
+    pub fn from_array([start, end]: [[f32; 2]; 2]) -> Self {
+        Self::new(start.into(), end.into())
+    }
+
+    /// The segment's `[start, end]` coordinate pairs, the inverse of
+    /// [`Segment::from_array`].
+    pub fn to_array(&self) -> [[f32; 2]; 2] {
+        [self.start.into(), self.end.into()]
+    }
+
     /// Get the closest point on this segment to an arbitrarily provided point.
     ///
     /// # Example 1 - Between Endpoints
@@ -190,6 +260,278 @@ impl Segment {
     pub fn length_squared(&self) -> f32 {
         (self.start - self.end).norm_squared()
     }
+
+    /// The segment's center of mass as a uniform rod, which is its midpoint.
+    pub fn centroid(&self) -> Point2<f32> {
+        nalgebra::center(&self.start, &self.end)
+    }
+
+    /// The segment's second moment of length about its centroid, the
+    /// matrix `∫ (p - c)(p - c)ᵀ ds` along it.
+    ///
+    /// This is the rod's counterpart to a polygon's second moment of area:
+    /// `L³ / 12` along the segment's direction and zero across it.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::Segment,
+    ///         nalgebra::{point, Matrix2},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let segment = Segment::new(point![1.0, 2.0], point![1.0, 8.0]);
+    ///
+    ///     assert_eq!(segment.centroid(), point![1.0, 5.0]);
+    ///     assert_relative_eq!(
+    ///         segment.second_moment_of_length(),
+    ///         Matrix2::new(0.0, 0.0, 0.0, 216.0 / 12.0),
+    ///     );
+    ///
+    pub fn second_moment_of_length(&self) -> Matrix2<f32> {
+        let direction = self.end - self.start;
+        direction * direction.transpose() * (direction.norm() / 12.0)
+    }
+
+    /// Linearly interpolate between two segments.
+    ///
+    /// Each endpoint is interpolated independently. Values of `t` outside of
+    /// `[0, 1]` extrapolate.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::Segment,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let a = Segment::new(point![0.0, 0.0], point![2.0, 0.0]);
+    ///     let b = Segment::new(point![0.0, 2.0], point![4.0, 2.0]);
+    ///
+    ///     assert_eq!(
+    ///         a.lerp(&b, 0.5),
+    ///         Segment::new(point![0.0, 1.0], point![3.0, 1.0])
+    ///     );
+    ///
+    pub fn lerp(&self, other: &Segment, t: f32) -> Segment {
+        Segment::new(
+            self.start + (other.start - self.start) * t,
+            self.end + (other.end - self.end) * t,
+        )
+    }
+
+    /// Grow or shrink the segment by absolute lengths at each end.
+    ///
+    /// Positive values lengthen the segment past its start or end, and
+    /// negative values shorten it. When the ends are shortened past each
+    /// other, the segment collapses to the zero length segment halfway
+    /// between where they would be. A segment with zero length has no
+    /// direction and is returned unchanged.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::Segment,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let segment = Segment::new(point![1.0, 0.0], point![3.0, 0.0]);
+    ///
+    ///     assert_eq!(
+    ///         segment.extended(1.0, -0.5),
+    ///         Segment::new(point![0.0, 0.0], point![2.5, 0.0])
+    ///     );
+    ///     assert_eq!(
+    ///         segment.extended(-2.0, -2.0),
+    ///         Segment::new(point![2.0, 0.0], point![2.0, 0.0])
+    ///     );
+    ///
+    pub fn extended(&self, at_start: f32, at_end: f32) -> Segment {
+        let length = self.length();
+        if length == 0.0 {
+            return *self;
+        }
+        let direction = (self.end - self.start) / length;
+        let (mut s0, mut s1) = (-at_start, length + at_end);
+        if s1 < s0 {
+            let middle = (s0 + s1) * 0.5;
+            (s0, s1) = (middle, middle);
+        }
+        Segment::new(self.start + direction * s0, self.start + direction * s1)
+    }
+
+    /// Cut off the part of the segment on the positive side of a line, the
+    /// side the line's normal points towards.
+    ///
+    /// The segment keeps its direction, and points exactly on the line are
+    /// kept. Returns `None` when the whole segment is on the positive side.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::{Line, Segment},
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let segment = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+    ///     // The line x = 3, with everything to its right on the positive side
+    ///     let line = Line::try_new(vector![1.0, 0.0], -3.0).unwrap();
+    ///
+    ///     assert_eq!(
+    ///         segment.trim_to_line(&line),
+    ///         Some(Segment::new(point![0.0, 0.0], point![3.0, 0.0]))
+    ///     );
+    ///
+    pub fn trim_to_line(&self, line: &Line) -> Option<Segment> {
+        let (d0, d1) = (
+            line.distance_to_point(&self.start),
+            line.distance_to_point(&self.end),
+        );
+        match (d0 > 0.0, d1 > 0.0) {
+            (false, false) => Some(*self),
+            (true, true) => None,
+            (true, false) => {
+                Some(Segment::new(self.crossing(d0, d1), self.end))
+            }
+            (false, true) => {
+                Some(Segment::new(self.start, self.crossing(d0, d1)))
+            }
+        }
+    }
+
+    /// Lengthen the segment past its end until it reaches a line.
+    ///
+    /// Segments which already touch or cross the line are returned
+    /// unchanged. Returns `None` when the segment is parallel to the line
+    /// or points away from it, since then moving the end forward never
+    /// reaches the line. Reverse the segment to extend its start instead.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::line::{Line, Segment},
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let segment = Segment::new(point![0.0, 0.0], point![1.0, 1.0]);
+    ///     let line = Line::try_new(vector![0.0, 1.0], -3.0).unwrap();
+    ///
+    ///     assert_eq!(
+    ///         segment.extend_to_line(&line),
+    ///         Some(Segment::new(point![0.0, 0.0], point![3.0, 3.0]))
+    ///     );
+    ///     assert_eq!(
+    ///         Segment::new(point![1.0, 1.0], point![0.0, 0.0])
+    ///             .extend_to_line(&line),
+    ///         None
+    ///     );
+    ///
+    pub fn extend_to_line(&self, line: &Line) -> Option<Segment> {
+        let (d0, d1) = (
+            line.distance_to_point(&self.start),
+            line.distance_to_point(&self.end),
+        );
+        if d0 == 0.0 || d1 == 0.0 || (d0 > 0.0) != (d1 > 0.0) {
+            return Some(*self);
+        }
+        // The end only approaches the line when it's closer than the start.
+        if d1.abs() >= d0.abs() {
+            return None;
+        }
+        let end = self.crossing(d0, d1);
+        (end.x.is_finite() && end.y.is_finite())
+            .then(|| Segment::new(self.start, end))
+    }
+
+    /// The part of the segment which is inside a box.
+    ///
+    /// The segment keeps its direction, and endpoints which are already
+    /// inside the box are kept exactly. Returns `None` when the segment
+    /// misses the box or only touches it at a single point.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, shape::Aabb},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+    ///     let segment = Segment::new(point![2.0, 1.0], point![6.0, 1.0]);
+    ///
+    ///     assert_eq!(
+    ///         segment.clip_to_aabb(&bounds),
+    ///         Some(Segment::new(point![2.0, 1.0], point![4.0, 1.0]))
+    ///     );
+    ///
+    pub fn clip_to_aabb(&self, aabb: &Aabb) -> Option<Segment> {
+        let (enter, exit) = self.clip_parameters(aabb)?;
+        let direction = self.end - self.start;
+        let clamp =
+            |t: f32| (self.start + direction * t).sup(&aabb.min).inf(&aabb.max);
+        let start = if enter == 0.0 {
+            self.start
+        } else {
+            clamp(enter)
+        };
+        let end = if exit == 1.0 { self.end } else { clamp(exit) };
+        Some(Segment::new(start, end))
+    }
+
+    /// The parameters where the segment enters and exits a box, clamped to
+    /// the segment, or `None` when [`Segment::clip_to_aabb`] misses the box.
+    pub(crate) fn clip_parameters(&self, aabb: &Aabb) -> Option<(f32, f32)> {
+        let direction = self.end - self.start;
+        let (enter, exit) = aabb.slab_parameters(&self.start, &direction)?;
+        let (enter, exit) = (enter.max(0.0), exit.min(1.0));
+        (enter < exit).then_some((enter, exit))
+    }
+
+    /// The point on the segment's supporting line where the signed distance
+    /// to a line is zero, given the distances at the start and end.
+    fn crossing(&self, d0: f32, d1: f32) -> Point2<f32> {
+        let t = d0 / (d0 - d1);
+        self.start + (self.end - self.start) * t
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_point(f, &self.start)?;
+        f.write_str(" -> ")?;
+        write_point(f, &self.end)
+    }
+}
+
+impl fmt::Debug for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write_compact_debug(f, "Segment", self);
+        }
+        f.debug_struct("Segment")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl PathFrame for Segment {
+    fn path_length(&self) -> f32 {
+        self.length()
+    }
+
+    /// The frame at a distance along the segment, clamped to its endpoints.
+    ///
+    /// Every frame on a segment has the same tangent and normal.
+    fn frame_at_length(&self, s: f32) -> Option<Frame> {
+        let length = self.length();
+        if length == 0.0 {
+            return None;
+        }
+        let tangent = Unit::new_unchecked((self.end - self.start) / length);
+        let s = s.clamp(0.0, length);
+        Some(Frame::new(self.start + tangent.as_ref() * s, tangent))
+    }
 }
 
 impl DistanceToPoint for Segment {
@@ -205,3 +547,142 @@ impl DistanceToPoint for Segment {
         (point - self.closest_point(point)).norm_squared()
     }
 }
+
+impl BoundingBox for Segment {
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(self.start, self.end)
+    }
+}
+
+impl BoundingCircle for Segment {
+    /// The circle with the segment as its diameter.
+    fn bounding_circle(&self) -> Circle {
+        Circle::new(self.centroid(), self.length() / 2.0)
+    }
+}
+
+impl BoundingObb for Segment {
+    /// The box along the segment with no width. Segments with no length
+    /// get a box along the x axis.
+    fn bounding_obb(&self) -> Obb {
+        let axis = Unit::try_new(self.end - self.start, 0.0)
+            .unwrap_or(Vector2::x_axis());
+        let half_extents = Vector2::new(self.length() / 2.0, 0.0);
+        Obb::new(self.centroid(), axis, half_extents)
+    }
+}
+
+impl SupportMap for Segment {
+    /// The endpoint furthest along the direction, or the start when they're
+    /// equally far.
+    fn support_point(&self, direction: &Unit<Vector2<f32>>) -> Point2<f32> {
+        if (self.end - self.start).dot(direction) > 0.0 {
+            self.end
+        } else {
+            self.start
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{DistanceToPoint, Line, Segment},
+            shape::Aabb,
+            test_support::segment,
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn clipping_to_a_box_should_keep_inside_endpoints_exactly() {
+        let bounds = Aabb::new(point![0.0, 0.0], point![1.0, 1.0]);
+        let inside = Segment::new(point![0.1, 0.3], point![0.7, 0.9]);
+        let crossing = Segment::new(point![0.5, -0.5], point![0.5, 0.25]);
+        let corner = Segment::new(point![2.0, 0.0], point![0.0, 2.0]);
+
+        assert_eq!(inside.clip_to_aabb(&bounds), Some(inside));
+        assert_eq!(
+            crossing.clip_to_aabb(&bounds),
+            Some(Segment::new(point![0.5, 0.0], point![0.5, 0.25]))
+        );
+        assert_eq!(corner.clip_to_aabb(&bounds), None);
+        assert_eq!(
+            Segment::new(point![3.0, 3.0], point![4.0, 4.0])
+                .clip_to_aabb(&bounds),
+            None
+        );
+    }
+
+    #[test]
+    pub fn extending_zero_length_segments_should_do_nothing() {
+        let p = point![1.0, 2.0];
+
+        assert_eq!(Segment::new(p, p).extended(1.0, 1.0), Segment::new(p, p));
+    }
+
+    #[test]
+    pub fn trimming_should_keep_the_negative_side() {
+        let segment = Segment::new(point![4.0, 1.0], point![0.0, 1.0]);
+        let line = Line::try_new(vector![-1.0, 0.0], 1.0).unwrap();
+
+        assert_eq!(
+            segment.trim_to_line(&line),
+            Some(Segment::new(point![4.0, 1.0], point![1.0, 1.0]))
+        );
+
+        let behind = Line::try_new(vector![1.0, 0.0], -5.0).unwrap();
+        assert_eq!(segment.trim_to_line(&behind), Some(segment));
+
+        let ahead = Line::try_new(vector![-1.0, 0.0], 5.0).unwrap();
+        assert_eq!(segment.trim_to_line(&ahead), None);
+    }
+
+    #[test]
+    pub fn segments_touching_the_line_should_not_be_extended() {
+        let line = Line::try_new(vector![1.0, 0.0], -2.0).unwrap();
+        let touching = Segment::new(point![0.0, 0.0], point![2.0, 5.0]);
+        let crossing = Segment::new(point![0.0, 0.0], point![3.0, 1.0]);
+        let starting = Segment::new(point![2.0, 0.0], point![1.0, 1.0]);
+
+        for segment in [touching, crossing, starting] {
+            assert_eq!(segment.extend_to_line(&line), Some(segment));
+        }
+    }
+
+    #[test]
+    pub fn parallel_segments_should_not_be_extended() {
+        let line = Line::try_new(vector![0.0, 1.0], -2.0).unwrap();
+        let segment = Segment::new(point![0.0, 0.0], point![3.0, 0.0]);
+
+        assert_eq!(segment.extend_to_line(&line), None);
+    }
+
+    proptest! {
+        #[test]
+        fn extending_and_trimming_should_round_trip(
+            segment in segment(-10.0..=10.0, 0.1),
+            amount in 0.0f32..10.0,
+        ) {
+            let direction = Unit::new_normalize(segment.end - segment.start);
+            // The line through the original end, facing forward
+            let line = Line::new(direction, -direction.dot(&segment.end.coords));
+
+            let extended = segment.extended(0.0, amount);
+            let trimmed = extended.trim_to_line(&line).unwrap();
+
+            assert_relative_eq!(trimmed.start, segment.start);
+            assert_relative_eq!(trimmed.end, segment.end, epsilon = 1e-4);
+
+            // Extending back to the line undoes trimming in the other
+            // direction.
+            let shortened = segment.extended(0.0, -segment.length() * 0.5);
+            let restored = shortened.extend_to_line(&line).unwrap();
+            assert_relative_eq!(restored.end, segment.end, epsilon = 1e-3);
+            prop_assert!(line.distance_to_point(&restored.end).abs() < 1e-3);
+        }
+    }
+}
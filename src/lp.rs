@@ -0,0 +1,483 @@
+//! Linear programming in two dimensions.
+//!
+//! A linear program looks for the point which minimizes a linear objective
+//! while satisfying a set of linear constraints. In two dimensions each
+//! constraint is a half-plane, the points which satisfy all of them are a
+//! convex region, and the best point is usually one of the region's
+//! corners.
+
+use {
+    crate::{line::Line, random::SplitMix64},
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// How far a point can be outside a constraint and still satisfy it,
+/// relative to the size of the constraints.
+const TOLERANCE: f64 = 1e-5;
+
+/// Directions whose dot product with a constraint's normal is smaller than
+/// this are parallel to the constraint.
+const PARALLEL: f64 = 1e-9;
+
+/// The size of the box which the search starts in, relative to the size of
+/// the constraints. It grows when the answer is outside of it.
+const START_BOUND: f64 = 1e4;
+
+/// The outcome of a two dimensional linear program.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LpResult {
+    /// A point which satisfies every constraint and minimizes the
+    /// objective.
+    Optimal(Point2<f32>),
+
+    /// No point satisfies every constraint.
+    Infeasible,
+
+    /// The objective can be made as small as you like.
+    Unbounded,
+}
+
+/// Minimize `objective · p` over the points `p` which are on or below
+/// every constraint line.
+///
+/// A point is below a line when its signed distance to the line is
+/// negative, so each constraint keeps the half-plane on the opposite side
+/// from the line's normal. This is Seidel's randomized incremental
+/// algorithm, which takes O(n) expected time for n constraints. The
+/// constraints are added in a random order chosen by `seed`, so the same
+/// seed always gives the same answer.
+///
+/// When a whole edge of the feasible region is optimal, like when the
+/// objective is perpendicular to a constraint, any optimal point can be
+/// returned. With a zero objective every feasible point is optimal, so this
+/// just checks that there is one. Constraints may be violated by a small
+/// tolerance, relative to the size of their offsets, so regions which
+/// shrink to a single point are still feasible.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::Line,
+///             lp::{lp2d, LpResult},
+///         },
+///         nalgebra::{point, vector},
+///     };
+///
+///     // x >= 0, y >= 0, and x + y >= 2
+///     let constraints = [
+///         Line::try_new(vector![-1.0, 0.0], 0.0).unwrap(),
+///         Line::try_new(vector![0.0, -1.0], 0.0).unwrap(),
+///         Line::try_new(vector![-1.0, -1.0], 2.0 / 2.0f32.sqrt()).unwrap(),
+///     ];
+///
+///     // Find the lowest point with the smallest 2x + y.
+///     let LpResult::Optimal(best) = lp2d(&constraints, vector![2.0, 1.0], 7)
+///     else {
+///         panic!("expected an optimal point");
+///     };
+///     assert!((best - point![0.0, 2.0]).norm() < 1e-5);
+///
+///     assert_eq!(
+///         lp2d(&constraints, vector![-1.0, 0.0], 7),
+///         LpResult::Unbounded
+///     );
+///
+pub fn lp2d(
+    constraints: &[Line],
+    objective: Vector2<f32>,
+    seed: u64,
+) -> LpResult {
+    let planes: Vec<HalfPlane> = constraints
+        .iter()
+        .map(|line| HalfPlane {
+            normal: line.normal.into_inner().cast(),
+            c: line.c as f64,
+        })
+        .collect();
+    let objective: Vector2<f64> = objective.cast();
+    if planes.is_empty() {
+        return if objective == Vector2::zeros() {
+            LpResult::Optimal(Point2::origin())
+        } else {
+            LpResult::Unbounded
+        };
+    }
+
+    let mut order: Vec<usize> = (0..planes.len()).collect();
+    SplitMix64(seed).shuffle(&mut order);
+    let scale = planes
+        .iter()
+        .fold(1.0, |scale: f64, p| scale.max(p.c.abs()));
+    let problem = Problem {
+        planes: &planes,
+        order: &order,
+        tolerance: TOLERANCE * scale,
+        scale,
+    };
+
+    // Minimizing against the sum of the normals can't be unbounded, since
+    // every direction the region extends in points away from the normals.
+    let sum: Vector2<f64> = planes.iter().map(|p| p.normal).sum();
+    let towards_constraints = if sum.norm() > PARALLEL {
+        -sum.normalize()
+    } else {
+        planes[0].normal
+    };
+    if objective == Vector2::zeros() {
+        return problem.solve(towards_constraints);
+    }
+
+    let direction = objective.normalize();
+    if problem.recedes_below(direction) {
+        return match problem.solve(towards_constraints) {
+            LpResult::Optimal(_) => LpResult::Unbounded,
+            result => result,
+        };
+    }
+    problem.solve(direction)
+}
+
+/// A constraint `normal · p + c <= 0`, in double precision.
+#[derive(Debug, Copy, Clone)]
+struct HalfPlane {
+    normal: Vector2<f64>,
+    c: f64,
+}
+
+impl HalfPlane {
+    /// How far the point is outside the half-plane.
+    fn excess(&self, point: &Vector2<f64>) -> f64 {
+        self.normal.dot(point) + self.c
+    }
+}
+
+struct Problem<'a> {
+    planes: &'a [HalfPlane],
+
+    /// The order to add the planes in.
+    order: &'a [usize],
+    tolerance: f64,
+    scale: f64,
+}
+
+impl Problem<'_> {
+    /// Returns true when the feasible region, if there is one, extends
+    /// forever in some direction where the objective decreases.
+    fn recedes_below(&self, objective: Vector2<f64>) -> bool {
+        // Every such direction can be scaled to -objective + t * side.
+        let side = Vector2::new(-objective.y, objective.x);
+        interval(
+            self.planes
+                .iter()
+                .map(|p| (-p.normal.dot(&objective), p.normal.dot(&side))),
+            PARALLEL,
+        )
+        .is_some()
+    }
+
+    /// Solve the program for an objective which is bounded below.
+    fn solve(&self, objective: Vector2<f64>) -> LpResult {
+        let side = Vector2::new(-objective.y, objective.x);
+        let recedes = |direction: Vector2<f64>| {
+            self.planes
+                .iter()
+                .all(|p| p.normal.dot(&direction) <= PARALLEL)
+        };
+        let (forward, backward) = (recedes(side), recedes(-side));
+        if forward && backward {
+            // Every constraint is perpendicular to the objective, so the
+            // optimal points fill a whole line. Take the one closest to the
+            // origin.
+            let bounds =
+                self.planes.iter().map(|p| (p.c, p.normal.dot(&objective)));
+            return match interval(bounds, self.tolerance) {
+                Some((low, _)) => optimal(objective * low),
+                None => LpResult::Infeasible,
+            };
+        }
+
+        // Break ties between optimal points by moving along the side, away
+        // from any direction where the optimal points go on forever.
+        let tie_break = if forward { side } else { -side };
+        let mut bound = START_BOUND * self.scale;
+        loop {
+            let Some(point) = self.seidel(objective, tie_break, bound) else {
+                return LpResult::Infeasible;
+            };
+            if point.amax() < bound * 0.5 || bound > 1e30 {
+                return optimal(point);
+            }
+            bound *= START_BOUND;
+        }
+    }
+
+    /// Seidel's algorithm inside a box which reaches `bound` from the
+    /// origin along each axis. Returns `None` when the constraints can't
+    /// all be satisfied.
+    fn seidel(
+        &self,
+        objective: Vector2<f64>,
+        tie_break: Vector2<f64>,
+        bound: f64,
+    ) -> Option<Vector2<f64>> {
+        let walls = [
+            Vector2::new(1.0, 0.0),
+            Vector2::new(-1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(0.0, -1.0),
+        ]
+        .map(|normal| HalfPlane { normal, c: -bound });
+        let key = |p: &Vector2<f64>| (objective.dot(p), tie_break.dot(p));
+        let mut best = [
+            Vector2::new(-bound, -bound),
+            Vector2::new(bound, -bound),
+            Vector2::new(bound, bound),
+            Vector2::new(-bound, bound),
+        ]
+        .into_iter()
+        .min_by(|a, b| key(a).partial_cmp(&key(b)).unwrap())?;
+
+        for (added, &index) in self.order.iter().enumerate() {
+            let plane = &self.planes[index];
+            if plane.excess(&best) <= self.tolerance {
+                continue;
+            }
+
+            // The new best point is on this plane's line, so solve the one
+            // dimensional problem along it.
+            let base = plane.normal * -plane.c;
+            let along = Vector2::new(-plane.normal.y, plane.normal.x);
+            let earlier = self.order[..added].iter().map(|&i| &self.planes[i]);
+            let (low, high) = interval(
+                walls
+                    .iter()
+                    .chain(earlier)
+                    .map(|p| (p.excess(&base), p.normal.dot(&along))),
+                self.tolerance,
+            )?;
+            let slope = match objective.dot(&along) {
+                slope if slope.abs() > PARALLEL => slope,
+                _ => tie_break.dot(&along),
+            };
+            best = base + along * if slope > 0.0 { low } else { high };
+        }
+        Some(best)
+    }
+}
+
+/// The range of `t` where `a + t * b <= 0` for every `(a, b)`, or `None`
+/// when it's empty even after allowing each to be off by `slack`.
+///
+/// Ranges which are only non-empty thanks to the slack shrink to a single
+/// value in the middle of where they cross.
+fn interval(
+    bounds: impl Iterator<Item = (f64, f64)>,
+    slack: f64,
+) -> Option<(f64, f64)> {
+    let (mut low, mut high) = (f64::NEG_INFINITY, f64::INFINITY);
+    let (mut loose_low, mut loose_high) = (low, high);
+    for (a, b) in bounds {
+        if b.abs() <= PARALLEL {
+            if a > slack {
+                return None;
+            }
+        } else if b > 0.0 {
+            high = high.min(-a / b);
+            loose_high = loose_high.min((slack - a) / b);
+        } else {
+            low = low.max(-a / b);
+            loose_low = loose_low.max((slack - a) / b);
+        }
+    }
+    if loose_low > loose_high {
+        return None;
+    }
+    if low > high {
+        let middle = (low + high) / 2.0;
+        return Some((middle, middle));
+    }
+    Some((low, high))
+}
+
+fn optimal(point: Vector2<f64>) -> LpResult {
+    LpResult::Optimal(Point2::new(point.x as f32, point.y as f32))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{lp2d, LpResult},
+        crate::{
+            line::{DistanceToPoint, Line},
+            test_support::unit_vector,
+        },
+        alloc::vec::Vec,
+        nalgebra::{point, vector, Point2, Unit, Vector2},
+        proptest::prelude::*,
+    };
+
+    fn line(normal: Vector2<f32>, c: f32) -> Line {
+        Line::try_new(normal, c).unwrap()
+    }
+
+    /// The constraints for the box from `min` to `max`.
+    fn square(min: f32, max: f32) -> Vec<Line> {
+        vec![
+            line(vector![1.0, 0.0], -max),
+            line(vector![-1.0, 0.0], min),
+            line(vector![0.0, 1.0], -max),
+            line(vector![0.0, -1.0], min),
+        ]
+    }
+
+    fn feasible(constraints: &[Line], point: &Point2<f32>) -> bool {
+        constraints
+            .iter()
+            .all(|c| c.distance_to_point(point) <= 1e-3)
+    }
+
+    /// The answer found by checking every corner, every foot of a line,
+    /// and every direction the feasible region could go on forever in.
+    fn brute_force(constraints: &[Line], objective: Vector2<f32>) -> LpResult {
+        let mut candidates: Vec<Point2<f32>> = constraints
+            .iter()
+            .map(|c| Point2::from(c.normal.into_inner() * -c.c))
+            .collect();
+        for (i, a) in constraints.iter().enumerate() {
+            for b in &constraints[i + 1..] {
+                let det = a.normal.perp(&b.normal);
+                if det.abs() > 1e-4 {
+                    candidates.push(Point2::new(
+                        (a.normal.y * b.c - b.normal.y * a.c) / det,
+                        (b.normal.x * a.c - a.normal.x * b.c) / det,
+                    ));
+                }
+            }
+        }
+        let best = candidates
+            .into_iter()
+            .filter(|p| feasible(constraints, p))
+            .min_by(|a, b| {
+                objective
+                    .dot(&a.coords)
+                    .total_cmp(&objective.dot(&b.coords))
+            });
+        let Some(best) = best else {
+            return LpResult::Infeasible;
+        };
+
+        let directions = constraints
+            .iter()
+            .flat_map(|c| {
+                let side = vector![-c.normal.y, c.normal.x];
+                [side, -side]
+            })
+            .chain([-objective]);
+        for direction in directions {
+            let recedes =
+                constraints.iter().all(|c| c.normal.dot(&direction) <= 1e-6);
+            if recedes && objective.dot(&direction) < -1e-6 {
+                return LpResult::Unbounded;
+            }
+        }
+        LpResult::Optimal(best)
+    }
+
+    #[test]
+    pub fn objectives_perpendicular_to_an_edge_should_pick_a_point_on_it() {
+        let constraints = square(0.0, 1.0);
+
+        let result = lp2d(&constraints, vector![0.0, 3.0], 1);
+
+        let LpResult::Optimal(best) = result else {
+            panic!("{:?}", result);
+        };
+        assert!(best.y.abs() < 1e-6);
+        assert!(best.x >= 0.0 && best.x <= 1.0);
+    }
+
+    #[test]
+    pub fn redundant_constraints_should_not_change_the_answer() {
+        let mut constraints = square(-1.0, 2.0);
+        constraints.extend(square(-1.0, 2.0));
+        constraints.extend(square(-5.0, 5.0));
+        constraints.push(line(vector![1.0, 1.0], -10.0));
+
+        for seed in 0..10 {
+            assert_eq!(
+                lp2d(&constraints, vector![1.0, 2.0], seed),
+                LpResult::Optimal(point![-1.0, -1.0])
+            );
+        }
+    }
+
+    #[test]
+    pub fn single_feasible_points_should_be_found() {
+        let mut constraints = square(3.0, 3.0);
+        constraints.push(line(vector![1.0, -1.0], 0.0));
+
+        for objective in
+            [vector![1.0, 0.0], vector![-1.0, 2.0], vector![0.0, 0.0]]
+        {
+            let LpResult::Optimal(best) = lp2d(&constraints, objective, 5)
+            else {
+                panic!("expected an optimal point");
+            };
+            assert!((best - point![3.0, 3.0]).norm() < 1e-4);
+        }
+    }
+
+    #[test]
+    pub fn degenerate_programs_should_report_why() {
+        let disjoint =
+            [line(vector![1.0, 0.0], 0.0), line(vector![-1.0, 0.0], 1.0)];
+        let strip = [
+            line(vector![0.0, 1.0], -1.0),
+            line(vector![0.0, -1.0], -1.0),
+        ];
+
+        assert_eq!(lp2d(&disjoint, vector![1.0, 1.0], 0), LpResult::Infeasible);
+        assert_eq!(lp2d(&disjoint, vector![0.0, 0.0], 0), LpResult::Infeasible);
+        assert_eq!(lp2d(&strip, vector![1.0, 0.0], 0), LpResult::Unbounded);
+        assert_eq!(
+            lp2d(&strip, vector![0.0, 1.0], 0),
+            LpResult::Optimal(point![0.0, -1.0])
+        );
+        assert_eq!(lp2d(&[], vector![0.0, 1.0], 0), LpResult::Unbounded);
+        assert_eq!(
+            lp2d(&[], vector![0.0, 0.0], 0),
+            LpResult::Optimal(point![0.0, 0.0])
+        );
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(512))]
+
+        #[test]
+        fn lp2d_should_agree_with_brute_force(
+            constraints in prop::collection::vec(
+                (unit_vector(), -10.0f32..=10.0)
+                    .prop_map(|(normal, c)| Line::new(normal, c)),
+                1..10,
+            ),
+            objective in unit_vector(),
+            seed in any::<u64>(),
+        ) {
+            let objective: Vector2<f32> = Unit::into_inner(objective);
+
+            let result = lp2d(&constraints, objective, seed);
+
+            match (result, brute_force(&constraints, objective)) {
+                (LpResult::Optimal(found), LpResult::Optimal(best)) => {
+                    prop_assert!(feasible(&constraints, &found));
+                    let (found, best) =
+                        (objective.dot(&found.coords), objective.dot(&best.coords));
+                    prop_assert!((found - best).abs() < 1e-3 + best.abs() * 1e-4, "{} {}", found, best);
+                }
+                (result, expected) => prop_assert_eq!(result, expected),
+            }
+        }
+    }
+}
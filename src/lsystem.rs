@@ -0,0 +1,205 @@
+//! Lindenmayer systems, which grow strings by rewriting symbols and draw
+//! them with a [turtle](crate::turtle::Turtle).
+//!
+//! An L-system starts from an axiom and replaces every symbol which has a
+//! rule with the rule's replacement, all at once, for a number of
+//! iterations. Interpreting the result maps each symbol to a turtle
+//! command, which draws fractals like the Koch curve and branching plants.
+
+use {
+    crate::{polygon::Polyline, turtle::Turtle},
+    alloc::{string::String, vec::Vec},
+    nalgebra::Point2,
+};
+
+/// What the turtle does for a symbol in [`interpret`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Command {
+    /// Move forward by a distance, drawing a line.
+    Forward(f32),
+
+    /// Move forward by a distance without drawing.
+    Move(f32),
+
+    /// Turn counterclockwise by an angle in radians.
+    Turn(f32),
+
+    /// Save the turtle's state, to start a branch.
+    Push,
+
+    /// Return to the most recently saved state, to end a branch.
+    Pop,
+}
+
+/// Rewrite the axiom with the rules for a number of iterations.
+///
+/// Each rule maps a symbol to its replacement. Every iteration replaces all
+/// symbols at once, and symbols without a rule are kept as they are. When
+/// several rules have the same symbol, the first one is used.
+///
+/// # Example
+///
+///     use compgeo::lsystem::expand;
+///
+///     let algae = expand("A", &[('A', "AB"), ('B', "A")], 4);
+///
+///     assert_eq!(algae, "ABAABABA");
+///
+pub fn expand(
+    axiom: &str,
+    rules: &[(char, &str)],
+    iterations: usize,
+) -> String {
+    let mut current = String::from(axiom);
+    for _ in 0..iterations {
+        let mut next = String::with_capacity(current.len());
+        for symbol in current.chars() {
+            match rules.iter().find(|(from, _)| *from == symbol) {
+                Some((_, to)) => next.push_str(to),
+                None => next.push(symbol),
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// Draw a string with a turtle, mapping each symbol to a command.
+///
+/// The turtle starts at the origin heading along the x axis with its pen
+/// down. Symbols without a binding are skipped, so they can be used to
+/// steer the rewriting without drawing anything. When several bindings
+/// have the same symbol, the first one is used. Returns the turtle's
+/// strokes, as described by [`Turtle`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::lsystem::{expand, interpret, Command},
+///         approx::assert_relative_eq,
+///         core::f32::consts::FRAC_PI_3,
+///         nalgebra::point,
+///     };
+///
+///     let koch = expand("F", &[('F', "F+F--F+F")], 1);
+///     let strokes = interpret(
+///         &koch,
+///         &[
+///             ('F', Command::Forward(1.0)),
+///             ('+', Command::Turn(FRAC_PI_3)),
+///             ('-', Command::Turn(-FRAC_PI_3)),
+///         ],
+///     );
+///
+///     assert_eq!(strokes.len(), 1);
+///     assert_eq!(strokes[0].vertices.len(), 5);
+///     assert_relative_eq!(strokes[0].vertices[4], point![3.0, 0.0]);
+///
+pub fn interpret(string: &str, bindings: &[(char, Command)]) -> Vec<Polyline> {
+    let mut turtle = Turtle::new(Point2::origin(), 0.0);
+    for symbol in string.chars() {
+        let Some(&(_, command)) =
+            bindings.iter().find(|(bound, _)| *bound == symbol)
+        else {
+            continue;
+        };
+        match command {
+            Command::Forward(distance) => {
+                turtle.forward(distance);
+            }
+            Command::Move(distance) => {
+                let pen_down = turtle.is_pen_down();
+                turtle.pen_up().forward(distance);
+                if pen_down {
+                    turtle.pen_down();
+                }
+            }
+            Command::Turn(angle) => {
+                turtle.turn(angle);
+            }
+            Command::Push => {
+                turtle.push();
+            }
+            Command::Pop => {
+                turtle.pop();
+            }
+        }
+    }
+    turtle.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{expand, interpret, Command},
+        approx::assert_relative_eq,
+        core::f32::consts::{FRAC_PI_2, FRAC_PI_3, FRAC_PI_4},
+        nalgebra::point,
+    };
+
+    #[test]
+    pub fn koch_curves_should_grow_by_a_third_each_iteration() {
+        let curve = expand("F", &[('F', "F+F--F+F")], 3);
+        let step = 1.0 / 27.0;
+
+        let strokes = interpret(
+            &curve,
+            &[
+                ('F', Command::Forward(step)),
+                ('+', Command::Turn(FRAC_PI_3)),
+                ('-', Command::Turn(-FRAC_PI_3)),
+            ],
+        );
+
+        assert_eq!(strokes.len(), 1);
+        assert_eq!(strokes[0].vertices.len(), 4 * 4 * 4 + 1);
+        let growth = 4.0f32 / 3.0;
+        assert_relative_eq!(
+            strokes[0].length(),
+            growth * growth * growth,
+            max_relative = 1e-5
+        );
+        let end = strokes[0].vertices[64];
+        assert_relative_eq!(end, point![1.0, 0.0], epsilon = 1e-5);
+    }
+
+    #[test]
+    pub fn branches_should_start_where_they_were_pushed() {
+        let plant = expand("X", &[('X', "F[+X][-X]"), ('F', "FF")], 2);
+        assert_eq!(plant, "FF[+F[+X][-X]][-F[+X][-X]]");
+
+        let strokes = interpret(
+            &plant,
+            &[
+                ('F', Command::Forward(1.0)),
+                ('+', Command::Turn(FRAC_PI_4)),
+                ('-', Command::Turn(-FRAC_PI_4)),
+                ('[', Command::Push),
+                (']', Command::Pop),
+            ],
+        );
+
+        // The trunk and first branch are one stroke, then every branch
+        // after a pop is its own stroke.
+        assert_eq!(strokes.len(), 2);
+        assert_eq!(strokes[0].vertices.len(), 4);
+        assert_eq!(strokes[1].vertices[0], strokes[0].vertices[2]);
+    }
+
+    #[test]
+    pub fn moves_should_lift_the_pen() {
+        let strokes = interpret(
+            "F+fF",
+            &[
+                ('F', Command::Forward(1.0)),
+                ('f', Command::Move(1.0)),
+                ('+', Command::Turn(FRAC_PI_2)),
+            ],
+        );
+
+        assert_eq!(strokes.len(), 2);
+        assert_relative_eq!(strokes[1].vertices[0], point![1.0, 1.0]);
+        assert_relative_eq!(strokes[1].vertices[1], point![1.0, 2.0]);
+        assert!(interpret("F", &[]).is_empty());
+    }
+}
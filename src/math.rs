@@ -0,0 +1,78 @@
+//! Floating point functions which work with and without the standard library.
+//!
+//! Without `std`, methods like `f32::sqrt` don't exist. These functions defer
+//! to nalgebra's scalar traits which use the standard library when it's
+//! available and libm when it isn't.
+
+// Not every function is used by every combination of features.
+#![allow(dead_code)]
+
+use nalgebra::{ComplexField, RealField};
+
+/// The square root of a number.
+#[inline]
+pub(crate) fn sqrt(x: f32) -> f32 {
+    ComplexField::sqrt(x)
+}
+
+/// The square root of a number, in double precision.
+#[inline]
+pub(crate) fn sqrt_f64(x: f64) -> f64 {
+    ComplexField::sqrt(x)
+}
+
+/// Round a number to the nearest integer, rounding half-way cases away from
+/// zero.
+#[inline]
+pub(crate) fn round(x: f64) -> f64 {
+    ComplexField::round(x)
+}
+
+/// The largest integer less than or equal to a number.
+#[inline]
+pub(crate) fn floor(x: f64) -> f64 {
+    ComplexField::floor(x)
+}
+
+/// The angle in radians, in `[-pi, pi]`, between the positive x axis and the
+/// point `(x, y)`.
+#[inline]
+pub(crate) fn atan2(y: f32, x: f32) -> f32 {
+    RealField::atan2(y, x)
+}
+
+/// The sine and cosine of an angle in radians.
+#[inline]
+pub(crate) fn sin_cos(x: f32) -> (f32, f32) {
+    ComplexField::sin_cos(x)
+}
+
+/// The sine and cosine of an angle in radians, in double precision.
+#[inline]
+pub(crate) fn sin_cos_f64(x: f64) -> (f64, f64) {
+    ComplexField::sin_cos(x)
+}
+
+/// The smallest integer greater than or equal to a number.
+#[inline]
+pub(crate) fn ceil(x: f32) -> f32 {
+    ComplexField::ceil(x)
+}
+
+/// The exponential function, `e` raised to a power.
+#[inline]
+pub(crate) fn exp(x: f32) -> f32 {
+    ComplexField::exp(x)
+}
+
+/// The arccosine of a number, in radians.
+#[inline]
+pub(crate) fn acos(x: f32) -> f32 {
+    ComplexField::acos(x)
+}
+
+/// The arccosine of a number, in radians, in double precision.
+#[inline]
+pub(crate) fn acos_f64(x: f64) -> f64 {
+    ComplexField::acos(x)
+}
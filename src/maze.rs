@@ -0,0 +1,316 @@
+//! Carve mazes out of a tessellation.
+//!
+//! A maze starts with a wall along every edge of every cell and knocks down
+//! the walls along a spanning tree of the cells, so there's exactly one path
+//! between any two cells. The remaining walls are returned as segments,
+//! ready to draw.
+
+use {
+    crate::{line::Segment, polygon::Polygon, random::SplitMix64},
+    alloc::{collections::BTreeMap, vec::Vec},
+};
+
+/// How [`generate`] picks which walls to knock down.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    /// A randomized depth-first search, which makes long, winding corridors
+    /// with few dead ends.
+    RecursiveBacktracker,
+
+    /// Randomized Kruskal's algorithm, which knocks down walls in a random
+    /// order whenever they separate unconnected cells, making many short
+    /// dead ends.
+    Kruskal,
+}
+
+/// The walls left after carving a maze through the cells.
+///
+/// Two cells are neighbors when they share an edge, meaning one has an edge
+/// with exactly the same endpoints as an edge of the other in either
+/// direction, like the tiles from [`crate::tessellate`]. Cells which only
+/// share part of an edge, or whose vertices differ by rounding, aren't
+/// neighbors. The walls between neighbors along a spanning tree are
+/// removed, so every cell can be reached from every other one, as long as
+/// the cells are connected to begin with. Otherwise each connected group of
+/// cells becomes its own maze.
+///
+/// Every wall is returned once, with the direction of the first cell which
+/// has it. Walls come in the order of the cells and their edges, and the
+/// same cells, seed, and algorithm always give the same walls. Edges with
+/// zero length are skipped.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             maze::{generate, MazeAlgorithm},
+///             shape::Aabb,
+///             tessellate::squares,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 4.0]);
+///     let cells = squares(&bounds, 1.0, None);
+///
+///     let walls = generate(&cells, 7, MazeAlgorithm::Kruskal);
+///
+///     // A 4x4 grid has 40 edges, and 15 are knocked down to join 16 cells.
+///     assert_eq!(walls.len(), 40 - 15);
+///
+pub fn generate(
+    cells: &[Polygon],
+    seed: u64,
+    algorithm: MazeAlgorithm,
+) -> Vec<Segment> {
+    let (edges, neighbors) = shared_edges(cells);
+    let mut rng = SplitMix64(seed);
+    let mut open = vec![false; edges.len()];
+    match algorithm {
+        MazeAlgorithm::RecursiveBacktracker => {
+            backtrack(&neighbors, &mut rng, &mut open)
+        }
+        MazeAlgorithm::Kruskal => {
+            kruskal(cells.len(), &edges, &mut rng, &mut open)
+        }
+    }
+    edges
+        .into_iter()
+        .zip(open)
+        .filter(|(_, open)| !open)
+        .map(|(edge, _)| edge.segment)
+        .collect()
+}
+
+/// An edge of at least one cell.
+struct Edge {
+    segment: Segment,
+
+    /// The two cells on either side, when the edge is between neighbors.
+    cells: Option<(usize, usize)>,
+}
+
+/// Every distinct edge, in the order they're first seen, along with each
+/// cell's neighbors and the edges between them.
+fn shared_edges(cells: &[Polygon]) -> (Vec<Edge>, Vec<Vec<(usize, usize)>>) {
+    // Endpoints are compared by their bits, with negative zero folded into
+    // zero, and in sorted order so either direction finds the same edge.
+    let key = |segment: &Segment| {
+        let bits = |x: f32| (x + 0.0).to_bits();
+        let a = (bits(segment.start.x), bits(segment.start.y));
+        let b = (bits(segment.end.x), bits(segment.end.y));
+        (a.min(b), a.max(b))
+    };
+
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut owners = BTreeMap::new();
+    let mut neighbors = vec![Vec::new(); cells.len()];
+    for (cell, polygon) in cells.iter().enumerate() {
+        for segment in polygon.edges().filter(|edge| edge.start != edge.end) {
+            let &mut (id, first) =
+                owners.entry(key(&segment)).or_insert_with(|| {
+                    edges.push(Edge {
+                        segment,
+                        cells: None,
+                    });
+                    (edges.len() - 1, cell)
+                });
+            if first != cell && edges[id].cells.is_none() {
+                edges[id].cells = Some((first, cell));
+                neighbors[first].push((id, cell));
+                neighbors[cell].push((id, first));
+            }
+        }
+    }
+    (edges, neighbors)
+}
+
+/// Open the walls along a randomized depth-first search from each cell
+/// which hasn't been reached yet.
+fn backtrack(
+    neighbors: &[Vec<(usize, usize)>],
+    rng: &mut SplitMix64,
+    open: &mut [bool],
+) {
+    let mut visited = vec![false; neighbors.len()];
+    let mut stack = Vec::new();
+    let mut choices = Vec::new();
+    for start in 0..neighbors.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        stack.push(start);
+        while let Some(&cell) = stack.last() {
+            choices.clear();
+            choices.extend(
+                neighbors[cell]
+                    .iter()
+                    .filter(|&&(_, neighbor)| !visited[neighbor]),
+            );
+            if choices.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let (edge, next) = choices[rng.below(choices.len())];
+            open[edge] = true;
+            visited[next] = true;
+            stack.push(next);
+        }
+    }
+}
+
+/// Open the walls in a random order whenever they join cells which aren't
+/// connected yet.
+fn kruskal(
+    cell_count: usize,
+    edges: &[Edge],
+    rng: &mut SplitMix64,
+    open: &mut [bool],
+) {
+    let mut order: Vec<_> = edges
+        .iter()
+        .enumerate()
+        .filter_map(|(id, edge)| edge.cells.map(|cells| (id, cells)))
+        .collect();
+    rng.shuffle(&mut order);
+
+    let mut roots: Vec<usize> = (0..cell_count).collect();
+    fn find(roots: &mut [usize], mut i: usize) -> usize {
+        while roots[i] != i {
+            roots[i] = roots[roots[i]];
+            i = roots[i];
+        }
+        i
+    }
+    for (id, (a, b)) in order {
+        let (a, b) = (find(&mut roots, a), find(&mut roots, b));
+        if a != b {
+            roots[a.max(b)] = a.min(b);
+            open[id] = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{generate, shared_edges, MazeAlgorithm},
+        crate::{
+            polygon::Polygon,
+            shape::Aabb,
+            tessellate::{hexagons, squares, HexOrientation},
+        },
+        alloc::{collections::BTreeSet, vec::Vec},
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    const ALGORITHMS: [MazeAlgorithm; 2] =
+        [MazeAlgorithm::RecursiveBacktracker, MazeAlgorithm::Kruskal];
+
+    fn bounds() -> Aabb {
+        Aabb::new(point![0.0, 0.0], point![6.0, 5.0])
+    }
+
+    fn tessellations() -> Vec<Vec<Polygon>> {
+        vec![
+            squares(&bounds(), 1.0, None),
+            hexagons(&bounds(), 0.6, HexOrientation::PointyTop, None),
+            hexagons(&bounds(), 0.6, HexOrientation::FlatTop, None),
+        ]
+    }
+
+    /// Check that every cell is reachable through the removed walls, and
+    /// that no wall is returned twice.
+    fn check_maze(cells: &[Polygon], seed: u64, algorithm: MazeAlgorithm) {
+        let walls = generate(cells, seed, algorithm);
+        let wall_keys: BTreeSet<_> = walls
+            .iter()
+            .map(|wall| {
+                let a = (wall.start.x.to_bits(), wall.start.y.to_bits());
+                let b = (wall.end.x.to_bits(), wall.end.y.to_bits());
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        assert_eq!(wall_keys.len(), walls.len());
+
+        let (edges, _) = shared_edges(cells);
+        let mut roots: Vec<usize> = (0..cells.len()).collect();
+        fn find(roots: &mut [usize], mut i: usize) -> usize {
+            while roots[i] != i {
+                i = roots[i];
+            }
+            i
+        }
+        let mut removed = 0;
+        for edge in &edges {
+            let is_wall = walls.contains(&edge.segment);
+            if let (false, Some((a, b))) = (is_wall, edge.cells) {
+                let (a, b) = (find(&mut roots, a), find(&mut roots, b));
+                assert_ne!(a, b, "removing a wall shouldn't make a loop");
+                roots[a] = b;
+                removed += 1;
+            }
+        }
+        assert_eq!(removed, cells.len() - 1);
+        assert_eq!(walls.len(), edges.len() - removed);
+    }
+
+    #[test]
+    pub fn mazes_should_connect_every_cell() {
+        for cells in tessellations() {
+            for algorithm in ALGORITHMS {
+                check_maze(&cells, 3, algorithm);
+            }
+        }
+    }
+
+    #[test]
+    pub fn mazes_should_be_the_same_for_a_seed() {
+        let cells = squares(&bounds(), 0.5, None);
+
+        for algorithm in ALGORITHMS {
+            let maze = generate(&cells, 11, algorithm);
+
+            assert_eq!(generate(&cells, 11, algorithm), maze);
+            assert_ne!(generate(&cells, 12, algorithm), maze);
+        }
+    }
+
+    #[test]
+    pub fn separate_groups_of_cells_should_be_separate_mazes() {
+        let square = |x: f32| {
+            Polygon::new(vec![
+                point![x, 0.0],
+                point![x + 1.0, 0.0],
+                point![x + 1.0, 1.0],
+                point![x, 1.0],
+            ])
+        };
+        let cells = [square(0.0), square(1.0), square(3.0)];
+
+        for algorithm in ALGORITHMS {
+            let walls = generate(&cells, 0, algorithm);
+
+            // The pair loses the wall between them, and the lone square
+            // keeps all four of its walls.
+            assert_eq!(walls.len(), 10);
+            assert!(!walls
+                .iter()
+                .any(|wall| wall.start.x == 1.0 && wall.end.x == 1.0));
+        }
+        assert!(generate(&[], 0, MazeAlgorithm::Kruskal).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn every_seed_should_give_a_connected_maze(seed in any::<u64>()) {
+            let cells = hexagons(&bounds(), 0.8, HexOrientation::FlatTop, None);
+
+            for algorithm in ALGORITHMS {
+                check_maze(&cells, seed, algorithm);
+            }
+        }
+    }
+}
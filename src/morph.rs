@@ -0,0 +1,251 @@
+//! Smooth transitions between polygons with different shapes.
+//!
+//! Two polygons can only be interpolated vertex by vertex once they have the
+//! same number of vertices and each vertex has a sensible partner. The
+//! functions here resample both boundaries evenly by arc length and then
+//! rotate one of them to line up with the other.
+
+use {
+    crate::{
+        path::ArcLengthTable,
+        polygon::{Polygon, Polyline},
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// Interpolate between two polygons.
+///
+/// The polygons are matched up with [`align_polygons`], using as many
+/// vertices as both polygons have together so the corners of either shape
+/// aren't cut off too badly. Then each vertex moves in a straight line
+/// towards its partner. The result is exactly the first aligned polygon at
+/// `t = 0` and exactly the second at `t = 1`. Identical polygons are
+/// returned unchanged for every `t`.
+///
+/// Call [`align_polygons`] once and interpolate its output directly when
+/// animating many frames between the same polygons.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{morph::morph, polygon::Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///     ]);
+///     let diamond = Polygon::new(vec![
+///         point![1.0, -1.0],
+///         point![3.0, 1.0],
+///         point![1.0, 3.0],
+///         point![-1.0, 1.0],
+///     ]);
+///
+///     let halfway = morph(&square, &diamond, 0.5);
+///
+///     assert_eq!(halfway.vertices.len(), 8);
+///     assert_relative_eq!(halfway.area(), 6.0, epsilon = 1e-5);
+///
+pub fn morph(a: &Polygon, b: &Polygon, t: f32) -> Polygon {
+    if a == b {
+        return a.clone();
+    }
+    let (a, b) = align_polygons(a, b, a.vertices.len() + b.vertices.len());
+    Polygon::new(
+        a.vertices
+            .iter()
+            .zip(&b.vertices)
+            .map(|(a, b)| Point2::from(a.coords * (1.0 - t) + b.coords * t))
+            .collect(),
+    )
+}
+
+/// Resample two polygons to `n` vertices each, with matching vertices at
+/// the same index.
+///
+/// Both polygons are wound counterclockwise and then resampled at evenly
+/// spaced distances along their boundaries, starting from their first
+/// vertices. The second polygon's vertices are then rotated to the offset
+/// which minimizes the total squared distance between partners, which
+/// takes O(n²) time.
+///
+/// Returns two empty polygons when `n` is zero or either polygon has no
+/// vertices.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{morph::align_polygons, polygon::Polygon},
+///         nalgebra::point,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![1.0, 0.0],
+///         point![1.0, 1.0],
+///         point![0.0, 1.0],
+///     ]);
+///     let mut shifted = square.clone();
+///     shifted.vertices.rotate_left(2);
+///     shifted.vertices.reverse();
+///
+///     let (a, b) = align_polygons(&square, &shifted, 4);
+///
+///     assert_eq!(a, square);
+///     assert_eq!(b, square);
+///
+pub fn align_polygons(
+    a: &Polygon,
+    b: &Polygon,
+    n: usize,
+) -> (Polygon, Polygon) {
+    if n == 0 || a.vertices.is_empty() || b.vertices.is_empty() {
+        return (Polygon::new(Vec::new()), Polygon::new(Vec::new()));
+    }
+    let a = resample(a, n);
+    let b = resample(b, n);
+
+    let cost = |offset: usize| -> f32 {
+        a.iter()
+            .enumerate()
+            .map(|(i, p)| (p - b[(i + offset) % n]).norm_squared())
+            .sum()
+    };
+    let offset = (0..n)
+        .min_by(|&x, &y| cost(x).total_cmp(&cost(y)))
+        .unwrap_or(0);
+
+    let mut b = b;
+    b.rotate_left(offset);
+    (Polygon::new(a), Polygon::new(b))
+}
+
+/// Sample `n` points evenly spaced by distance around a polygon's boundary,
+/// counterclockwise from its first vertex.
+fn resample(polygon: &Polygon, n: usize) -> Vec<Point2<f32>> {
+    let mut vertices = polygon.vertices.clone();
+    if polygon.signed_area() < 0.0 {
+        // Reverse everything after the first vertex so the samples still
+        // start there.
+        vertices[1..].reverse();
+    }
+    vertices.push(vertices[0]);
+    let table = ArcLengthTable::new(&Polyline::new(vertices));
+    let step = table.length() / n as f32;
+    (0..n)
+        .map(|i| {
+            table
+                .point_at_length(i as f32 * step)
+                .unwrap_or(polygon.vertices[0])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{align_polygons, morph},
+        crate::{
+            polygon::Polygon,
+            shape::Circle,
+            test_support::{polygon, rectangle},
+        },
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    fn triangle() -> Polygon {
+        Polygon::new(vec![point![0.0, 0.0], point![4.0, 0.0], point![0.0, 3.0]])
+    }
+
+    #[test]
+    pub fn morphs_should_start_and_end_at_the_aligned_polygons() {
+        let (a, b) = align_polygons(
+            &rectangle(point![0.0, 0.0], point![3.0, 3.0]),
+            &triangle(),
+            7,
+        );
+
+        let start = morph(
+            &rectangle(point![0.0, 0.0], point![3.0, 3.0]),
+            &triangle(),
+            0.0,
+        );
+        let end = morph(
+            &rectangle(point![0.0, 0.0], point![3.0, 3.0]),
+            &triangle(),
+            1.0,
+        );
+
+        assert_eq!(start, a);
+        assert_eq!(end, b);
+        assert_eq!(start.vertices.len(), 7);
+        assert_relative_eq!(start.vertices[0], point![0.0, 0.0]);
+    }
+
+    #[test]
+    pub fn opposite_windings_should_be_normalized() {
+        let mut clockwise = triangle();
+        clockwise.vertices.reverse();
+
+        let (a, b) = align_polygons(&triangle(), &clockwise, 12);
+
+        assert!(b.signed_area() > 0.0);
+        for (a, b) in a.vertices.iter().zip(&b.vertices) {
+            assert_relative_eq!(a, b, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    pub fn identical_and_empty_polygons_should_be_handled() {
+        assert_eq!(morph(&triangle(), &triangle(), 0.3), triangle());
+
+        let (a, b) = align_polygons(
+            &Polygon::new(vec![]),
+            &rectangle(point![0.0, 0.0], point![3.0, 3.0]),
+            4,
+        );
+        assert!(a.vertices.is_empty() && b.vertices.is_empty());
+        let (a, _) = align_polygons(
+            &rectangle(point![0.0, 0.0], point![3.0, 3.0]),
+            &triangle(),
+            0,
+        );
+        assert!(a.vertices.is_empty());
+    }
+
+    #[test]
+    pub fn very_different_vertex_counts_should_resample_both() {
+        let circle = Circle::new(point![1.5, 1.5], 2.0);
+
+        let blob = morph(
+            &rectangle(point![0.0, 0.0], point![3.0, 3.0]),
+            &circle.to_polygon_n(200),
+            0.5,
+        );
+
+        assert_eq!(blob.vertices.len(), 204);
+        assert!(blob.signed_area() > 0.0);
+    }
+
+    proptest! {
+        #[test]
+        fn aligned_polygons_should_have_n_vertices(
+            a in polygon(-10.0..=10.0, 3..=8, false),
+            b in polygon(-10.0..=10.0, 3..=8, false),
+            n in 1usize..32,
+        ) {
+            let (a, b) = align_polygons(&a, &b, n);
+
+            prop_assert_eq!(a.vertices.len(), n);
+            prop_assert_eq!(b.vertices.len(), n);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Divide the plane between weighted sites, for mosaic effects.
+//!
+//! Every point belongs to the site with the smallest additively weighted
+//! distance, which is the distance to the site minus the site's weight, so
+//! heavier sites claim more of the plane. The exact regions are bounded by
+//! hyperbolic arcs (an Apollonius diagram), so instead of constructing them
+//! the regions are sampled on a [`Grid`] of site labels and outlined with
+//! [`marching_squares`].
+
+use {
+    crate::{
+        field::marching_squares, polygon::Polyline, raster::Grid, shape::Aabb,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// The index of the site which is nearest to a point, by additively
+/// weighted distance.
+///
+/// Each site is a position and a weight, and its distance to the query is
+/// the Euclidean distance minus the weight. With every weight equal, this
+/// is the plain nearest site. Ties go to the site with the lowest index.
+///
+/// # Panics
+///
+/// Panics when there are no sites.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::mosaic::nearest_site,
+///         nalgebra::point,
+///     };
+///
+///     let sites = [(point![0.0, 0.0], 0.0), (point![4.0, 0.0], 2.0)];
+///
+///     // 3 away from the first site, but only 1 - 2 from the second.
+///     assert_eq!(nearest_site(&point![3.0, 0.0], &sites), 1);
+///     // 1 away from the first site, but 3 - 2 from the second.
+///     assert_eq!(nearest_site(&point![1.0, 0.0], &sites), 0);
+///
+pub fn nearest_site(
+    query: &Point2<f32>,
+    sites: &[(Point2<f32>, f32)],
+) -> usize {
+    assert!(!sites.is_empty(), "there are no sites to choose from");
+    let distance =
+        |(site, weight): &(Point2<f32>, f32)| (site - query).norm() - weight;
+    let mut nearest = (0, distance(&sites[0]));
+    for (index, site) in sites.iter().enumerate().skip(1) {
+        let distance = distance(site);
+        if distance < nearest.1 {
+            nearest = (index, distance);
+        }
+    }
+    nearest.0
+}
+
+/// Label every cell of a grid with its [`nearest_site`].
+///
+/// The grid divides `bounds` into `(cols, rows)` cells, and each cell is
+/// labeled with the index of the site nearest to its center. Values are in
+/// the same row-major order as every other [`Grid`].
+///
+/// # Panics
+///
+/// Panics when there are no sites.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{mosaic::assign_grid, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 2.0]);
+///     let sites = [(point![1.0, 1.0], 0.0), (point![3.0, 1.0], 0.0)];
+///
+///     let labels = assign_grid(&bounds, (4, 2), &sites);
+///
+///     assert_eq!(labels.values, vec![0, 0, 1, 1, 0, 0, 1, 1]);
+///
+pub fn assign_grid(
+    bounds: &Aabb,
+    (cols, rows): (usize, usize),
+    sites: &[(Point2<f32>, f32)],
+) -> Grid<u32> {
+    assert!(!sites.is_empty(), "there are no sites to choose from");
+    let width = bounds.width() / cols as f32;
+    let height = bounds.height() / rows as f32;
+    let values = (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (col, row)))
+        .map(|(col, row)| {
+            let center = bounds.min
+                + Vector2::new(
+                    (col as f32 + 0.5) * width,
+                    (row as f32 + 0.5) * height,
+                );
+            nearest_site(&center, sites) as u32
+        })
+        .collect();
+    Grid {
+        bounds: *bounds,
+        cols,
+        rows,
+        values,
+    }
+}
+
+/// Outline the cells with each label in a grid, like the regions from
+/// [`assign_grid`].
+///
+/// `outlines[k]` holds the contours around the cells labeled `k`, traced by
+/// [`marching_squares`] halfway between the centers of cells with the label
+/// and cells without it. Contours run counterclockwise around their region.
+/// Regions which reach the edge of the grid have open contours ending at
+/// the boundary of the cell centers, and the border between two regions is
+/// traced twice, once in each direction. There's one entry for every label
+/// up to the largest one in the grid, and labels which don't appear have no
+/// contours.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             mosaic::{assign_grid, region_outlines},
+///             shape::Aabb,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+///     let sites = [
+///         (point![5.0, 5.0], 1.0),
+///         (point![5.0, 0.5], 0.0),
+///         (point![9.5, 5.0], 0.0),
+///         (point![5.0, 9.5], 0.0),
+///         (point![0.5, 5.0], 0.0),
+///     ];
+///     let labels = assign_grid(&bounds, (20, 20), &sites);
+///
+///     let outlines = region_outlines(&labels);
+///
+///     // The middle site is surrounded by the others.
+///     assert_eq!(outlines[0].len(), 1);
+///     assert!(outlines[0][0].is_closed());
+///     assert!(outlines[1..].iter().all(|o| !o[0].is_closed()));
+///
+pub fn region_outlines(labels: &Grid<u32>) -> Vec<Vec<Polyline>> {
+    let count = labels
+        .values
+        .iter()
+        .max()
+        .map_or(0, |&max| max as usize + 1);
+    // The range of columns and rows covered by each label.
+    let mut extents = vec![None::<[usize; 4]>; count];
+    for (index, &label) in labels.values.iter().enumerate() {
+        let (col, row) = (index % labels.cols, index / labels.cols);
+        let extent =
+            extents[label as usize].get_or_insert([col, row, col, row]);
+        *extent = [
+            extent[0].min(col),
+            extent[1].min(row),
+            extent[2].max(col),
+            extent[3].max(row),
+        ];
+    }
+
+    let width = labels.bounds.width() / labels.cols as f32;
+    let height = labels.bounds.height() / labels.rows as f32;
+    extents
+        .iter()
+        .enumerate()
+        .map(|(label, extent)| {
+            let Some([min_col, min_row, max_col, max_row]) = *extent else {
+                return Vec::new();
+            };
+            // Only the window around the label, plus a cell on each side
+            // where there's one, can have contours.
+            let (min_col, min_row) =
+                (min_col.saturating_sub(1), min_row.saturating_sub(1));
+            let max_col = (max_col + 1).min(labels.cols - 1);
+            let max_row = (max_row + 1).min(labels.rows - 1);
+            let corner = |col: usize, row: usize| {
+                labels.bounds.min
+                    + Vector2::new(col as f32 * width, row as f32 * height)
+            };
+            let window = Grid {
+                bounds: Aabb::new(
+                    corner(min_col, min_row),
+                    corner(max_col + 1, max_row + 1),
+                ),
+                cols: max_col - min_col + 1,
+                rows: max_row - min_row + 1,
+                values: (min_row..=max_row)
+                    .flat_map(|row| {
+                        (min_col..=max_col).map(move |col| (col, row))
+                    })
+                    .map(|(col, row)| {
+                        let value = labels.values[row * labels.cols + col];
+                        if value as usize == label {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect(),
+            };
+            marching_squares(&window, 0.5)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            mosaic::{assign_grid, nearest_site, region_outlines},
+            query::nearest_in,
+            shape::Aabb,
+            test_support::point2,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    fn count(labels: &[u32], label: u32) -> usize {
+        labels.iter().filter(|&&value| value == label).count()
+    }
+
+    #[test]
+    pub fn ties_should_go_to_the_lowest_index() {
+        let sites = [
+            (point![1.0, 0.0], 0.0),
+            (point![-1.0, 0.0], 0.0),
+            (point![0.0, 5.0], 4.0),
+        ];
+
+        assert_eq!(nearest_site(&point![0.0, 0.0], &sites), 0);
+        assert_eq!(nearest_site(&point![-0.5, 0.0], &sites), 1);
+        assert_eq!(nearest_site(&point![0.0, 1.0], &sites), 2);
+    }
+
+    #[test]
+    pub fn heavier_sites_should_claim_more_cells() {
+        let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+        let mut sites = vec![
+            (point![2.0, 2.0], 0.0),
+            (point![8.0, 3.0], 0.0),
+            (point![5.0, 8.0], 0.0),
+        ];
+
+        let mut counts = Vec::new();
+        for weight in [0.0, 0.5, 1.0, 2.0, 4.0] {
+            sites[2].1 = weight;
+            let labels = assign_grid(&bounds, (40, 40), &sites);
+            counts.push(count(&labels.values, 2));
+        }
+
+        for pair in counts.windows(2) {
+            assert!(pair[1] > pair[0], "{counts:?}");
+        }
+    }
+
+    #[test]
+    pub fn outlines_should_follow_the_border_between_regions() {
+        let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 4.0]);
+        let sites = [(point![1.0, 2.0], 0.0), (point![3.0, 2.0], 0.0)];
+        let labels = assign_grid(&bounds, (8, 8), &sites);
+
+        let outlines = region_outlines(&labels);
+
+        assert_eq!(outlines.len(), 2);
+        for outline in &outlines {
+            assert_eq!(outline.len(), 1);
+            for vertex in &outline[0].vertices {
+                assert_relative_eq!(vertex.x, 2.0);
+            }
+        }
+        // Counterclockwise around each region.
+        let (left, right) = (&outlines[0][0], &outlines[1][0]);
+        assert!(left.vertices[0].y < left.vertices[left.vertices.len() - 1].y);
+        assert!(
+            right.vertices[0].y > right.vertices[right.vertices.len() - 1].y
+        );
+
+        let missing = assign_grid(
+            &bounds,
+            (8, 8),
+            &[sites[0], (point![9.0, 9.0], 0.0), sites[1]],
+        );
+        let outlines = region_outlines(&missing);
+        assert_eq!(outlines.len(), 3);
+        assert!(outlines[1].is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn unweighted_sites_should_match_the_nearest_point(
+            sites in proptest::collection::vec(point2(-10.0..=10.0), 1..20),
+            query in point2(-12.0..=12.0),
+        ) {
+            let weighted: Vec<(Point2<f32>, f32)> =
+                sites.iter().map(|&site| (site, 0.0)).collect();
+
+            let (nearest, _) = nearest_in(&sites, &query).unwrap();
+
+            prop_assert_eq!(nearest_site(&query, &weighted), nearest);
+        }
+    }
+}
@@ -1,6 +1,18 @@
 //! Misc. operations on points and vectors in 2d.
 
-use nalgebra::{vector, Unit, Vector2};
+use {
+    crate::{
+        math::{atan2, floor, round, sin_cos},
+        Error,
+    },
+    core::{
+        cmp::Ordering,
+        f32::consts::{PI, TAU},
+        hash::{Hash, Hasher},
+        ops::{Add, Mul, Neg, Sub},
+    },
+    nalgebra::{vector, Point2, Unit, Vector2, Vector3},
+};
 
 /// Compute a perpendicular vector by rotating the given vector 90 degrees
 /// counterclockwise.
@@ -45,3 +57,961 @@ pub fn perp_vec2d(vector: &Vector2<f32>) -> Vector2<f32> {
 pub fn perp_unit2d(vector: &Unit<Vector2<f32>>) -> Unit<Vector2<f32>> {
     Unit::new_unchecked(vector![-vector.y, vector.x])
 }
+
+/// Compute the signed angle, in radians, to rotate from `a` to `b`.
+///
+/// The angle is positive for counterclockwise rotations and is always in
+/// `[-pi, pi]`. When either vector has zero length the angle is 0.
+///
+/// # Example
+///
+///     use {
+///         compgeo::operations::signed_angle,
+///         nalgebra::vector,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let a = vector![1.0, 0.0];
+///
+///     assert_relative_eq!(
+///         signed_angle(&a, &vector![0.0, 2.0]),
+///         core::f32::consts::FRAC_PI_2
+///     );
+///     assert_relative_eq!(
+///         signed_angle(&a, &vector![1.0, -1.0]),
+///         -core::f32::consts::FRAC_PI_4
+///     );
+///     assert_eq!(signed_angle(&a, &vector![0.0, 0.0]), 0.0);
+///
+pub fn signed_angle(a: &Vector2<f32>, b: &Vector2<f32>) -> f32 {
+    atan2(a.perp(b), a.dot(b))
+}
+
+/// Divide `num` by `den`, unless `den` is too close to zero.
+///
+/// Returns `None` when `|den| <= tol`, when `den` is NaN, or when the
+/// quotient isn't finite. Pass a tolerance scaled to the magnitudes which
+/// produced `den`, so the check means the same thing at every scale.
+///
+/// # Example
+///
+///     use compgeo::operations::checked_div;
+///
+///     assert_eq!(checked_div(3.0, 2.0, 1e-6), Some(1.5));
+///     assert_eq!(checked_div(3.0, 1e-8, 1e-6), None);
+///     assert_eq!(checked_div(3.0, f32::NAN, 1e-6), None);
+///
+pub fn checked_div(num: f32, den: f32, tol: f32) -> Option<f32> {
+    // Written this way around so a NaN denominator is rejected too.
+    let large_enough = den.abs() > tol;
+    if !large_enough {
+        return None;
+    }
+    let quotient = num / den;
+    quotient.is_finite().then_some(quotient)
+}
+
+/// Solve `a * s + b * t = rhs` for `s` and `t`.
+///
+/// This is the 2x2 linear system with `a` and `b` as its columns. Returns
+/// `None` when the system is singular or nearly so: when the determinant
+/// `a.perp(b)` is no larger than `f32::EPSILON * |a| * |b|`, which is when
+/// `a` and `b` are parallel to within rounding error. This is the
+/// tolerance used by every line intersection in the crate.
+///
+/// # Example
+///
+///     use {
+///         compgeo::operations::solve_2x2,
+///         nalgebra::vector,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let (s, t) = solve_2x2(
+///         &vector![2.0, 0.0],
+///         &vector![1.0, 1.0],
+///         &vector![3.0, 2.0],
+///     )
+///     .unwrap();
+///     assert_relative_eq!(s, 0.5);
+///     assert_relative_eq!(t, 2.0);
+///
+///     // Parallel columns have no unique solution.
+///     let (a, b) = (vector![1.0, 2.0], vector![2.0, 4.0]);
+///     assert_eq!(solve_2x2(&a, &b, &vector![1.0, 0.0]), None);
+///
+pub fn solve_2x2(
+    a: &Vector2<f32>,
+    b: &Vector2<f32>,
+    rhs: &Vector2<f32>,
+) -> Option<(f32, f32)> {
+    let determinant = a.perp(b);
+    let tolerance = f32::EPSILON * a.norm() * b.norm();
+    let s = checked_div(rhs.perp(b), determinant, tolerance)?;
+    let t = checked_div(a.perp(rhs), determinant, tolerance)?;
+    Some((s, t))
+}
+
+/// Compute the barycentric coordinates of a point with respect to a
+/// triangle.
+///
+/// The coordinates `(u, v, w)` are the weights of the corners `a`, `b`, and
+/// `c` and always sum to 1. They are all in `[0, 1]` when the point is
+/// inside the triangle, and one of them is negative when the point is
+/// outside. Either winding order works.
+///
+/// Returns [`Error::DegenerateTriangle`] when the corners are collinear, or
+/// so close to collinear that the coordinates overflow.
+///
+/// # Example
+///
+///     use {
+///         compgeo::operations::barycentric,
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let (a, b, c) = (point![0.0, 0.0], point![4.0, 0.0], point![0.0, 4.0]);
+///
+///     assert_relative_eq!(
+///         barycentric(&point![1.0, 2.0], &a, &b, &c).unwrap(),
+///         vector![0.25, 0.25, 0.5]
+///     );
+///     assert!(barycentric(&a, &a, &b, &point![8.0, 0.0]).is_err());
+///
+pub fn barycentric(
+    p: &Point2<f32>,
+    a: &Point2<f32>,
+    b: &Point2<f32>,
+    c: &Point2<f32>,
+) -> Result<Vector3<f32>, Error> {
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let area = ab.perp(&ac);
+    let v = ap.perp(&ac) / area;
+    let w = ab.perp(&ap) / area;
+    let coordinates = vector![1.0 - v - w, v, w];
+    if area == 0.0 || !coordinates.iter().all(|x| x.is_finite()) {
+        return Err(Error::DegenerateTriangle);
+    }
+    Ok(coordinates)
+}
+
+/// Compute the point with the given barycentric coordinates in a triangle.
+///
+/// This is the weighted sum of the corners, so it's the inverse of
+/// [`barycentric`] when the coordinates sum to 1.
+///
+/// # Example
+///
+///     use {
+///         compgeo::operations::from_barycentric,
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let (a, b, c) = (point![0.0, 0.0], point![4.0, 0.0], point![0.0, 4.0]);
+///
+///     assert_relative_eq!(
+///         from_barycentric(&vector![0.25, 0.25, 0.5], &a, &b, &c),
+///         point![1.0, 2.0]
+///     );
+///
+pub fn from_barycentric(
+    coordinates: &Vector3<f32>,
+    a: &Point2<f32>,
+    b: &Point2<f32>,
+    c: &Point2<f32>,
+) -> Point2<f32> {
+    Point2::from(
+        a.coords * coordinates.x
+            + b.coords * coordinates.y
+            + c.coords * coordinates.z,
+    )
+}
+
+/// Bilinearly interpolate between the corners of a quad.
+///
+/// The corners are named by their `(u, v)` coordinates, so `p00` is at
+/// `u = 0, v = 0` and `p10` is at `u = 1, v = 0`. Values of `u` and `v`
+/// outside of `[0, 1]` extrapolate.
+///
+/// # Example
+///
+///     use {
+///         compgeo::operations::bilerp,
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let p = bilerp(
+///         &point![0.0, 0.0],
+///         &point![4.0, 0.0],
+///         &point![0.0, 2.0],
+///         &point![6.0, 2.0],
+///         0.5,
+///         0.5,
+///     );
+///
+///     assert_relative_eq!(p, point![2.5, 1.0]);
+///
+pub fn bilerp(
+    p00: &Point2<f32>,
+    p10: &Point2<f32>,
+    p01: &Point2<f32>,
+    p11: &Point2<f32>,
+    u: f32,
+    v: f32,
+) -> Point2<f32> {
+    let bottom = p00.coords.lerp(&p10.coords, u);
+    let top = p01.coords.lerp(&p11.coords, u);
+    Point2::from(bottom.lerp(&top, v))
+}
+
+/// A point which has been rounded to the nearest point on an integer
+/// lattice.
+///
+/// Floating point coordinates can't be hashed or totally ordered in a
+/// useful way, but lattice coordinates can. Snapping is done in f64 with
+/// IEEE operations, so a point snaps to the same lattice point on every
+/// platform. Use [`snap`] to create snapped points.
+///
+/// Two snapped points are equal when they have the same lattice coordinates
+/// and the same resolution. Points are ordered by `x`, then `y`, then
+/// resolution.
+#[derive(Debug, Copy, Clone)]
+pub struct SnappedPoint {
+    /// The number of lattice steps from the origin along the x axis.
+    pub x: i64,
+
+    /// The number of lattice steps from the origin along the y axis.
+    pub y: i64,
+
+    resolution: f32,
+}
+
+impl SnappedPoint {
+    /// The distance between neighboring lattice points.
+    pub fn resolution(&self) -> f32 {
+        self.resolution
+    }
+
+    /// The lattice point `dx` steps right and `dy` steps up from this one.
+    pub fn offset(&self, dx: i64, dy: i64) -> SnappedPoint {
+        SnappedPoint {
+            x: self.x.saturating_add(dx),
+            y: self.y.saturating_add(dy),
+            resolution: self.resolution,
+        }
+    }
+
+    /// The location of the lattice point.
+    ///
+    /// This is the input point moved by at most half the resolution along
+    /// each axis.
+    pub fn to_point(&self) -> Point2<f32> {
+        let resolution = self.resolution as f64;
+        Point2::new(
+            (self.x as f64 * resolution) as f32,
+            (self.y as f64 * resolution) as f32,
+        )
+    }
+}
+
+impl PartialEq for SnappedPoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SnappedPoint {}
+
+impl PartialOrd for SnappedPoint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SnappedPoint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.x, self.y)
+            .cmp(&(other.x, other.y))
+            .then(self.resolution.total_cmp(&other.resolution))
+    }
+}
+
+impl Hash for SnappedPoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.x.hash(state);
+        self.y.hash(state);
+        // total_cmp treats values as equal exactly when their bits match.
+        self.resolution.to_bits().hash(state);
+    }
+}
+
+/// Round a point to the nearest point on a lattice whose points are
+/// `resolution` apart.
+///
+/// Halfway cases round away from zero, so points which are slightly on
+/// either side of zero snap to the same lattice point. Coordinates which
+/// are too large for the lattice saturate and NaN coordinates snap to zero.
+/// The resolution should be positive and finite.
+///
+/// # Example
+///
+///     use {
+///         compgeo::operations::snap,
+///         nalgebra::point,
+///     };
+///
+///     let a = snap(&point![0.9999, -0.0001], 0.5);
+///     let b = snap(&point![1.1, 0.2], 0.5);
+///
+///     assert_eq!(a, b);
+///     assert_eq!((a.x, a.y), (2, 0));
+///     assert_eq!(a.to_point(), point![1.0, 0.0]);
+///
+pub fn snap(point: &Point2<f32>, resolution: f32) -> SnappedPoint {
+    let step = |c: f32| round(c as f64 / resolution as f64) as i64;
+    SnappedPoint {
+        x: step(point.x),
+        y: step(point.y),
+        resolution,
+    }
+}
+
+/// An angle in radians, measured counterclockwise from the positive x axis.
+///
+/// Raw `f32` radians are ambiguous about which turn they're on, so code
+/// which compares them has to remember to wrap them first. An `Angle`
+/// keeps whatever value it was given, and offers the wrapped forms
+/// explicitly: [`Angle::positive`] in `[0, 2pi)` and [`Angle::signed`] in
+/// `(-pi, pi]`. Differences between angles should come from
+/// [`Angle::shortest_delta`], which never goes the long way around.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::operations::Angle,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let a = Angle::from_degrees(350.0);
+///     let b = Angle::from_degrees(10.0);
+///
+///     assert_relative_eq!(a.signed().degrees(), -10.0, epsilon = 1e-4);
+///     let delta = a.shortest_delta(b);
+///     assert_relative_eq!(delta.degrees(), 20.0, epsilon = 1e-4);
+///     assert_relative_eq!(
+///         a.lerp_shortest(b, 0.5).positive().radians(),
+///         0.0,
+///         epsilon = 1e-6
+///     );
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Angle(f32);
+
+impl Angle {
+    /// The angle of the positive x axis.
+    pub const ZERO: Angle = Angle(0.0);
+
+    /// Create an angle from radians.
+    pub const fn from_radians(radians: f32) -> Self {
+        Self(radians)
+    }
+
+    /// Create an angle from degrees.
+    pub fn from_degrees(degrees: f32) -> Self {
+        Self(degrees.to_radians())
+    }
+
+    /// The angle of a direction.
+    ///
+    /// The result is in `[-pi, pi]`, as from `atan2`.
+    pub fn from_unit(direction: &Unit<Vector2<f32>>) -> Self {
+        Self(atan2(direction.y, direction.x))
+    }
+
+    /// The angle in radians, exactly as it was given.
+    pub fn radians(self) -> f32 {
+        self.0
+    }
+
+    /// The angle in degrees, exactly as it was given.
+    pub fn degrees(self) -> f32 {
+        self.0.to_degrees()
+    }
+
+    /// The same direction with radians wrapped into `[0, 2pi)`.
+    pub fn positive(self) -> Self {
+        let radians = self.0 as f64;
+        let turns = floor(radians / TAU as f64);
+        let wrapped = (radians - turns * TAU as f64) as f32;
+        // A full turn in disguise, like a tiny negative angle or 450
+        // degrees less 90 degrees, is the positive x axis.
+        if wrapped >= TAU * (1.0 - f32::EPSILON) {
+            return Self::ZERO;
+        }
+        Self(wrapped.max(0.0))
+    }
+
+    /// The same direction with radians wrapped into `(-pi, pi]`.
+    pub fn signed(self) -> Self {
+        let positive = self.positive().0;
+        if positive > PI {
+            Self(positive - TAU)
+        } else {
+            Self(positive)
+        }
+    }
+
+    /// The smallest rotation which turns this angle into `to`.
+    ///
+    /// The result is in `(-pi, pi]`, positive for counterclockwise turns.
+    /// Angles which are exactly opposite turn counterclockwise.
+    pub fn shortest_delta(self, to: Angle) -> Angle {
+        (to - self).signed()
+    }
+
+    /// Interpolate from this angle toward `to` the short way around.
+    ///
+    /// `t = 0` gives this angle and `t = 1` gives the same direction as
+    /// `to`, but the result isn't wrapped, so it can be a turn away from
+    /// `to` itself.
+    pub fn lerp_shortest(self, to: Angle, t: f32) -> Angle {
+        self + self.shortest_delta(to) * t
+    }
+
+    /// The unit vector pointing in this direction.
+    pub fn to_unit(self) -> Unit<Vector2<f32>> {
+        let (sin, cos) = sin_cos(self.0);
+        Unit::new_normalize(vector![cos, sin])
+    }
+}
+
+impl From<Unit<Vector2<f32>>> for Angle {
+    fn from(direction: Unit<Vector2<f32>>) -> Self {
+        Self::from_unit(&direction)
+    }
+}
+
+impl From<Angle> for Unit<Vector2<f32>> {
+    fn from(angle: Angle) -> Self {
+        angle.to_unit()
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, rhs: Angle) -> Angle {
+        Angle(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+
+    fn sub(self, rhs: Angle) -> Angle {
+        Angle(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        Angle(-self.0)
+    }
+}
+
+impl Mul<f32> for Angle {
+    type Output = Angle;
+
+    fn mul(self, rhs: f32) -> Angle {
+        Angle(self.0 * rhs)
+    }
+}
+
+/// The directions swept counterclockwise from one angle to another.
+///
+/// An interval is a start angle and a sweep in `[0, 2pi]`, so intervals
+/// which cross the positive x axis, like 350 to 10 degrees, work like any
+/// other. Both ends are included.
+///
+/// # Example
+///
+///     use compgeo::operations::{Angle, AngleInterval};
+///
+///     let ahead = AngleInterval::new(
+///         Angle::from_degrees(350.0),
+///         Angle::from_degrees(10.0),
+///     );
+///
+///     assert!(ahead.contains(Angle::from_degrees(5.0)));
+///     assert!(ahead.contains(Angle::from_degrees(-5.0)));
+///     assert!(!ahead.contains(Angle::from_degrees(180.0)));
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AngleInterval {
+    start: Angle,
+    sweep: f32,
+}
+
+impl AngleInterval {
+    /// The interval swept counterclockwise from `start` to `end`.
+    ///
+    /// When the two are the same direction the interval is that single
+    /// direction. Use [`AngleInterval::full`] for every direction.
+    pub fn new(start: Angle, end: Angle) -> Self {
+        Self {
+            start: start.positive(),
+            sweep: (end - start).positive().radians(),
+        }
+    }
+
+    /// The interval swept from `start` through `sweep` radians.
+    ///
+    /// Negative sweeps run clockwise, so the interval starts at
+    /// `start + sweep` instead. Sweeps of a full turn or more give
+    /// [`AngleInterval::full`].
+    pub fn from_sweep(start: Angle, sweep: f32) -> Self {
+        if sweep.abs() >= TAU {
+            return Self::full();
+        }
+        let (start, sweep) = if sweep < 0.0 {
+            (start + Angle(sweep), -sweep)
+        } else {
+            (start, sweep)
+        };
+        Self {
+            start: start.positive(),
+            sweep,
+        }
+    }
+
+    /// The interval which extends `half_width` radians to either side of
+    /// `center`, which is clamped to `[0, pi]`.
+    pub fn from_center(center: Angle, half_width: f32) -> Self {
+        let half_width = half_width.clamp(0.0, PI);
+        Self::from_sweep(center - Angle(half_width), 2.0 * half_width)
+    }
+
+    /// The interval which covers every direction.
+    pub fn full() -> Self {
+        Self {
+            start: Angle::ZERO,
+            sweep: TAU,
+        }
+    }
+
+    /// The clockwise end of the interval, in `[0, 2pi)`.
+    pub fn start(&self) -> Angle {
+        self.start
+    }
+
+    /// The counterclockwise end of the interval, in `[0, 2pi)`.
+    pub fn end(&self) -> Angle {
+        (self.start + Angle(self.sweep)).positive()
+    }
+
+    /// The direction halfway through the interval, in `[0, 2pi)`.
+    pub fn center(&self) -> Angle {
+        (self.start + Angle(self.sweep / 2.0)).positive()
+    }
+
+    /// How far the interval sweeps, in radians in `[0, 2pi]`.
+    pub fn sweep(&self) -> f32 {
+        self.sweep
+    }
+
+    /// Returns true when the interval covers every direction.
+    pub fn is_full(&self) -> bool {
+        self.sweep >= TAU
+    }
+
+    /// Returns true when the direction is inside the interval, including
+    /// its ends.
+    pub fn contains(&self, angle: Angle) -> bool {
+        self.is_full() || self.offset(angle) <= self.sweep
+    }
+
+    /// Returns true when every direction in `other` is inside this
+    /// interval.
+    pub fn contains_interval(&self, other: &AngleInterval) -> bool {
+        if self.is_full() {
+            return true;
+        }
+        !other.is_full() && self.offset(other.start) + other.sweep <= self.sweep
+    }
+
+    /// Returns true when the intervals share at least one direction.
+    pub fn overlaps(&self, other: &AngleInterval) -> bool {
+        self.contains(other.start) || other.contains(self.start)
+    }
+
+    /// How far counterclockwise from the start an angle is, in `[0, 2pi)`.
+    fn offset(&self, angle: Angle) -> f32 {
+        (angle - self.start).positive().radians()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            barycentric, bilerp, checked_div, from_barycentric, snap,
+            solve_2x2, Angle, AngleInterval,
+        },
+        crate::{
+            test_support::{point2, unit_vector},
+            Error,
+        },
+        approx::assert_relative_eq,
+        core::f32::consts::{PI, TAU},
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn barycentric_should_reject_collinear_corners() {
+        let (a, b) = (point![0.0, 0.0], point![1.0, 1.0]);
+
+        assert_eq!(
+            barycentric(&point![3.0, 1.0], &a, &b, &point![2.0, 2.0]),
+            Err(Error::DegenerateTriangle)
+        );
+        assert_eq!(
+            barycentric(&point![3.0, 1.0], &a, &a, &a),
+            Err(Error::DegenerateTriangle)
+        );
+    }
+
+    #[test]
+    pub fn barycentric_should_weight_the_corners() {
+        let (a, b, c) = (point![1.0, 1.0], point![3.0, 1.0], point![1.0, 5.0]);
+
+        for (corner, expected) in [
+            (a, vector![1.0, 0.0, 0.0]),
+            (b, vector![0.0, 1.0, 0.0]),
+            (c, vector![0.0, 0.0, 1.0]),
+        ] {
+            assert_relative_eq!(
+                barycentric(&corner, &a, &b, &c).unwrap(),
+                expected
+            );
+        }
+        let outside = barycentric(&point![4.0, 4.0], &a, &b, &c).unwrap();
+        assert!(outside.x < 0.0);
+    }
+
+    #[test]
+    pub fn bilerp_should_hit_the_corners() {
+        let corners = [
+            point![0.0, 0.0],
+            point![4.0, 1.0],
+            point![-1.0, 3.0],
+            point![5.0, 6.0],
+        ];
+        let [p00, p10, p01, p11] = corners;
+
+        assert_eq!(bilerp(&p00, &p10, &p01, &p11, 0.0, 0.0), p00);
+        assert_eq!(bilerp(&p00, &p10, &p01, &p11, 1.0, 0.0), p10);
+        assert_eq!(bilerp(&p00, &p10, &p01, &p11, 0.0, 1.0), p01);
+        assert_eq!(bilerp(&p00, &p10, &p01, &p11, 1.0, 1.0), p11);
+    }
+
+    #[test]
+    pub fn near_singular_systems_should_have_no_solution() {
+        let a = vector![1.0, 1.0];
+        let nearly_a = vector![1.0, 1.0 + f32::EPSILON];
+        let rhs = vector![0.0, 1.0];
+
+        // The exact solution is enormous and meaningless.
+        assert_eq!(solve_2x2(&a, &nearly_a, &rhs), None);
+        assert_eq!(solve_2x2(&(a * 1e20), &(nearly_a * 1e20), &rhs), None);
+        assert_eq!(solve_2x2(&vector![0.0, 0.0], &a, &rhs), None);
+        assert_eq!(solve_2x2(&vector![f32::NAN, 0.0], &a, &rhs), None);
+
+        // The tolerance scales with the columns, so tiny systems still solve.
+        let (s, t) =
+            solve_2x2(&(a * 1e-20), &vector![1e-20, 0.0], &(rhs * 1e-20))
+                .unwrap();
+        assert_relative_eq!(s, 1.0);
+        assert_relative_eq!(t, -1.0);
+
+        // Squaring the norms of huge columns would overflow the tolerance.
+        let (s, t) = solve_2x2(
+            &vector![1e10, 0.0],
+            &vector![0.0, 1e10],
+            &vector![1e10, 1e10],
+        )
+        .unwrap();
+        assert_relative_eq!(s, 1.0);
+        assert_relative_eq!(t, 1.0);
+
+        assert_eq!(checked_div(1.0, 0.0, 0.0), None);
+        assert_eq!(checked_div(f32::MAX, 0.5, 0.0), None);
+        assert_eq!(checked_div(-1.0, 4.0, 0.0), Some(-0.25));
+    }
+
+    #[cfg(feature = "std")]
+    fn hash_of(value: &impl core::hash::Hash) -> u64 {
+        use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher};
+        BuildHasherDefault::<DefaultHasher>::default().hash_one(value)
+    }
+
+    #[test]
+    pub fn snapping_should_merge_points_straddling_zero() {
+        let below = snap(&point![-0.0001, 0.0001], 1.0);
+        let above = snap(&point![0.0001, -0.0001], 1.0);
+        let negative_zero = snap(&point![-0.0, -0.0], 1.0);
+
+        assert_eq!(below, above);
+        assert_eq!(below, negative_zero);
+        assert_eq!((below.x, below.y), (0, 0));
+        assert_eq!(below.to_point(), point![0.0, 0.0]);
+        assert!(below.to_point().x.is_sign_positive());
+    }
+
+    #[test]
+    pub fn snapping_should_round_halfway_cases_away_from_zero() {
+        let resolution = 0.1;
+        // 0.25 isn't exactly representable, so compute the boundary from
+        // the resolution that's actually stored.
+        let halfway = (2.5 * resolution as f64) as f32;
+        let just_below = f32::from_bits(halfway.to_bits() - 1);
+
+        assert_eq!(snap(&point![0.5, -0.5], 1.0).x, 1);
+        assert_eq!(snap(&point![0.5, -0.5], 1.0).y, -1);
+        assert_eq!(snap(&point![0.49999997, -0.49999997], 1.0).x, 0);
+        assert_eq!(snap(&point![0.49999997, -0.49999997], 1.0).y, 0);
+        assert_eq!(snap(&point![just_below, 0.0], resolution).x, 2);
+        assert_eq!(snap(&point![1e30, f32::NAN], 1e-6).x, i64::MAX);
+        assert_eq!(snap(&point![1e30, f32::NAN], 1e-6).y, 0);
+    }
+
+    #[test]
+    pub fn snapped_points_should_order_by_x_then_y_then_resolution() {
+        let a = snap(&point![1.0, 5.0], 1.0);
+        let b = snap(&point![2.0, -5.0], 1.0);
+        let c = snap(&point![2.0, 0.0], 1.0);
+        let d = snap(&point![4.0, 0.0], 2.0);
+
+        assert!(a < b && b < c && c < d);
+        assert_eq!((d.x, d.y), (c.x, c.y));
+        assert_ne!(c, d);
+        assert_eq!(c.offset(1, -1).to_point(), point![3.0, -1.0]);
+    }
+
+    fn degrees(degrees: f32) -> Angle {
+        Angle::from_degrees(degrees)
+    }
+
+    #[test]
+    pub fn wrapping_intervals_should_contain_angles_across_zero() {
+        let ahead = AngleInterval::new(degrees(350.0), degrees(10.0));
+
+        assert_relative_eq!(
+            ahead.sweep(),
+            20.0f32.to_radians(),
+            epsilon = 1e-5
+        );
+        for inside in [5.0, 0.0, -5.0, 355.0, 365.0, -355.0, 725.0, 350.0, 10.0]
+        {
+            assert!(ahead.contains(degrees(inside)), "{inside}");
+        }
+        for outside in [180.0, -180.0, 11.0, 349.0, 90.0, 270.0, -11.0, 190.0] {
+            assert!(!ahead.contains(degrees(outside)), "{outside}");
+        }
+        // The same interval from its ends given in other turns.
+        let again = AngleInterval::new(degrees(-10.0), degrees(370.0));
+        assert!(again.contains(degrees(5.0)));
+        assert!(!again.contains(degrees(180.0)));
+        assert_relative_eq!(again.sweep(), ahead.sweep(), epsilon = 1e-5);
+        assert_relative_eq!(ahead.center().radians(), 0.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    pub fn wrapping_intervals_should_compare_with_other_intervals() {
+        let ahead = AngleInterval::new(degrees(350.0), degrees(10.0));
+        let narrow = AngleInterval::new(degrees(355.0), degrees(5.0));
+        let behind = AngleInterval::new(degrees(170.0), degrees(190.0));
+        let left = AngleInterval::new(degrees(5.0), degrees(90.0));
+
+        assert!(ahead.contains_interval(&narrow));
+        assert!(!narrow.contains_interval(&ahead));
+        assert!(!ahead.contains_interval(&left));
+        assert!(ahead.overlaps(&left) && left.overlaps(&ahead));
+        assert!(!ahead.overlaps(&behind) && !behind.overlaps(&ahead));
+        // The complement of the interval runs the long way around.
+        let rest = AngleInterval::new(degrees(10.0), degrees(350.0));
+        assert!(rest.contains(degrees(180.0)));
+        assert!(!rest.contains(degrees(5.0)));
+        assert!(rest.contains_interval(&behind));
+        assert!(AngleInterval::full().contains_interval(&ahead));
+        assert!(!ahead.contains_interval(&AngleInterval::full()));
+    }
+
+    #[test]
+    pub fn interval_constructors_should_agree() {
+        let ahead = AngleInterval::new(degrees(350.0), degrees(10.0));
+        let clockwise =
+            AngleInterval::from_sweep(degrees(10.0), -20.0f32.to_radians());
+        let centered = AngleInterval::from_center(degrees(0.0), 0.1745329);
+
+        for interval in [clockwise, centered] {
+            assert_relative_eq!(
+                interval.start().radians(),
+                ahead.start().radians(),
+                epsilon = 1e-5
+            );
+            assert_relative_eq!(
+                interval.sweep(),
+                ahead.sweep(),
+                epsilon = 1e-5
+            );
+            assert!(interval.contains(degrees(5.0)));
+            assert!(!interval.contains(degrees(180.0)));
+        }
+        // Equal ends are a single direction, not a full turn.
+        let single = AngleInterval::new(degrees(90.0), degrees(450.0));
+        assert_eq!(single.sweep(), 0.0);
+        assert!(single.contains(degrees(90.0)));
+        assert!(!single.contains(degrees(91.0)));
+        assert!(AngleInterval::from_sweep(Angle::ZERO, 7.0).is_full());
+        assert!(AngleInterval::from_center(Angle::ZERO, 4.0).is_full());
+    }
+
+    #[test]
+    pub fn angles_should_wrap_into_their_ranges() {
+        assert_eq!(Angle::from_radians(PI).signed().radians(), PI);
+        assert_eq!(Angle::from_radians(-PI).signed().radians(), PI);
+        assert_eq!(Angle::from_radians(TAU).positive().radians(), 0.0);
+        assert_eq!(Angle::from_radians(-1e-9).positive().radians(), 0.0);
+        assert_relative_eq!(degrees(-90.0).positive().degrees(), 270.0);
+        assert_relative_eq!(degrees(270.0).signed().degrees(), -90.0);
+        assert_relative_eq!(
+            degrees(10.0).shortest_delta(degrees(350.0)).degrees(),
+            -20.0,
+            epsilon = 1e-4
+        );
+        // Opposite angles turn counterclockwise.
+        assert_relative_eq!(
+            degrees(0.0).shortest_delta(degrees(180.0)).radians(),
+            PI
+        );
+        let halfway = degrees(350.0).lerp_shortest(degrees(30.0), 0.25);
+        assert_relative_eq!(halfway.degrees(), 360.0, epsilon = 1e-4);
+    }
+
+    proptest! {
+        #[cfg(feature = "std")]
+        #[test]
+        fn equal_snapped_points_should_hash_equally(
+            a in point2(-2.0..=2.0),
+            b in point2(-2.0..=2.0),
+            resolution in prop::sample::select(vec![0.5f32, 1.0, 3.0]),
+        ) {
+            let (a, b) = (snap(&a, resolution), snap(&b, resolution));
+
+            prop_assert_eq!(a == b, a.cmp(&b).is_eq());
+            if a == b {
+                prop_assert_eq!(hash_of(&a), hash_of(&b));
+            }
+        }
+
+        #[test]
+        fn snapping_should_move_points_by_at_most_half_a_step(
+            p in point2(-1000.0..=1000.0),
+            resolution in 0.001f32..10.0,
+        ) {
+            let snapped = snap(&p, resolution);
+
+            let error = snapped.to_point() - p;
+            let limit = resolution * 0.5 * (1.0 + 1e-4) + p.coords.amax() * 1e-6;
+            prop_assert!(error.x.abs() <= limit);
+            prop_assert!(error.y.abs() <= limit);
+            prop_assert_eq!(snap(&snapped.to_point(), resolution), snapped);
+        }
+
+        #[test]
+        fn solutions_should_satisfy_the_system(
+            a in point2(-10.0..=10.0),
+            b in point2(-10.0..=10.0),
+            rhs in point2(-10.0..=10.0),
+        ) {
+            let (a, b, rhs) = (a.coords, b.coords, rhs.coords);
+            let Some((s, t)) = solve_2x2(&a, &b, &rhs) else {
+                prop_assert!(a.perp(&b).abs() <= 1e-5 * a.norm() * b.norm());
+                return Ok(());
+            };
+
+            prop_assert!(s.is_finite() && t.is_finite());
+            prop_assume!(a.perp(&b).abs() > 1e-2 * a.norm() * b.norm());
+            assert_relative_eq!(a * s + b * t, rhs, epsilon = 1e-3);
+        }
+
+        #[test]
+        fn barycentric_coordinates_should_round_trip(
+            p in point2(-10.0..=10.0),
+            a in point2(-10.0..=10.0),
+            b in point2(-10.0..=10.0),
+            c in point2(-10.0..=10.0),
+        ) {
+            prop_assume!((b - a).perp(&(c - a)).abs() > 1.0);
+
+            let coordinates = barycentric(&p, &a, &b, &c).unwrap();
+
+            assert_relative_eq!(coordinates.sum(), 1.0, epsilon = 1e-4);
+            assert_relative_eq!(
+                from_barycentric(&coordinates, &a, &b, &c),
+                p,
+                epsilon = 1e-3
+            );
+        }
+
+        #[test]
+        fn intervals_should_contain_angles_by_their_offset(
+            start in -1000.0f32..=1000.0,
+            sweep in 0.0f32..=360.0,
+            offset in -720.0f32..=720.0,
+        ) {
+            let interval = AngleInterval::from_sweep(
+                degrees(start),
+                sweep.to_radians(),
+            );
+            let around = (offset as f64).rem_euclid(360.0) as f32;
+            prop_assume!((around - sweep).abs() > 1e-2);
+            prop_assume!(around > 1e-2 && around < 360.0 - 1e-2);
+
+            let angle = degrees(start + offset);
+
+            prop_assert_eq!(interval.contains(angle), around <= sweep);
+            for turns in [-2.0, -1.0, 1.0, 2.0] {
+                let turned = angle + Angle::from_radians(TAU * turns);
+                prop_assert_eq!(interval.contains(turned), around <= sweep);
+            }
+        }
+
+        #[test]
+        fn angles_should_round_trip_through_unit_vectors(
+            direction in unit_vector(),
+            turns in -3i32..=3,
+        ) {
+            let angle = Angle::from_unit(&direction);
+            let turned = angle + Angle::from_radians(TAU * turns as f32);
+
+            prop_assert!((-PI..=PI).contains(&angle.radians()));
+            prop_assert!((0.0..TAU).contains(&turned.positive().radians()));
+            prop_assert!(turned.signed().radians() > -PI);
+            prop_assert!(turned.signed().radians() <= PI);
+            assert_relative_eq!(
+                turned.to_unit().into_inner(),
+                direction.into_inner(),
+                epsilon = 1e-5
+            );
+            let delta = angle.shortest_delta(turned).radians();
+            prop_assert!(delta.abs() < 1e-4 || (TAU - delta.abs()) < 1e-4);
+        }
+    }
+}
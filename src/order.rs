@@ -0,0 +1,332 @@
+//! Choose the order to draw things in, to keep a pen plotter's travel
+//! short.
+//!
+//! A plotter lifts its pen to move from the end of one stroke to the start
+//! of the next, and with thousands of small strokes this pen-up travel can
+//! take most of a job's time. Orders here are lists of `(index, reversed)`
+//! pairs: each element is drawn once, from its end to its start when
+//! `reversed` is true. The pen begins at a starting point and doesn't need
+//! to return there.
+
+use {
+    crate::polygon::Polyline,
+    alloc::vec::Vec,
+    nalgebra::{distance, Point2},
+};
+
+/// A visit order for elements with the given start and end points, built
+/// by always travelling to the nearest unvisited endpoint.
+///
+/// The pen starts at `start`. At each step it travels to whichever
+/// unvisited element has the nearest start or end point and draws it from
+/// that end, so closed elements, whose start and end are the same, are
+/// always drawn forward. Ties go to the lowest index. Takes O(n²) time.
+///
+/// Greedy orders usually have a few long jumps back to elements which were
+/// skipped, which [`two_opt_improve`] can remove.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::order::{greedy_nearest, travel_distance},
+///         nalgebra::point,
+///     };
+///
+///     let strokes = [
+///         (point![5.0, 0.0], point![6.0, 0.0]),
+///         (point![2.0, 0.0], point![1.0, 0.0]),
+///         (point![3.0, 0.0], point![4.0, 0.0]),
+///     ];
+///
+///     let order = greedy_nearest(&strokes, point![0.0, 0.0]);
+///
+///     assert_eq!(order, vec![(1, true), (2, false), (0, false)]);
+///     assert_eq!(travel_distance(&strokes, point![0.0, 0.0], &order), 3.0);
+///
+pub fn greedy_nearest(
+    starts_ends: &[(Point2<f32>, Point2<f32>)],
+    start: Point2<f32>,
+) -> Vec<(usize, bool)> {
+    let mut visited = vec![false; starts_ends.len()];
+    let mut order = Vec::with_capacity(starts_ends.len());
+    let mut pen = start;
+    for _ in 0..starts_ends.len() {
+        let mut best: Option<(f32, usize, bool)> = None;
+        for (index, (first, last)) in starts_ends.iter().enumerate() {
+            if visited[index] {
+                continue;
+            }
+            for (reversed, end) in [(false, first), (true, last)] {
+                let squared = (end - pen).norm_squared();
+                match best {
+                    Some((nearest, ..)) if nearest <= squared => (),
+                    _ => best = Some((squared, index, reversed)),
+                }
+            }
+        }
+        let Some((_, index, reversed)) = best else {
+            break;
+        };
+        visited[index] = true;
+        order.push((index, reversed));
+        pen = exit(starts_ends, (index, reversed));
+    }
+    order
+}
+
+/// Shorten the pen-up travel of an order by reversing runs of it.
+///
+/// This is the 2-opt heuristic: reversing the run of elements from `i` to
+/// `j` also flips the direction each of them is drawn in, which replaces
+/// the jump into `i` and the jump out of `j` with two others. Each pass
+/// tries every run and keeps each reversal which makes the travel shorter.
+/// Passes repeat until one doesn't find anything to improve, or until
+/// `max_iters` passes have been made. Each pass takes O(n²) time.
+///
+/// The travel never gets longer, and every element stays in the order
+/// exactly once.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::order::{travel_distance, two_opt_improve},
+///         nalgebra::point,
+///     };
+///
+///     let strokes = [
+///         (point![1.0, 0.0], point![2.0, 0.0]),
+///         (point![3.0, 0.0], point![4.0, 0.0]),
+///         (point![5.0, 0.0], point![6.0, 0.0]),
+///     ];
+///     let start = point![0.0, 0.0];
+///     let mut order = vec![(2, true), (1, true), (0, true)];
+///
+///     two_opt_improve(&strokes, start, &mut order, 10);
+///
+///     assert_eq!(order, vec![(0, false), (1, false), (2, false)]);
+///     assert_eq!(travel_distance(&strokes, start, &order), 3.0);
+///
+pub fn two_opt_improve(
+    starts_ends: &[(Point2<f32>, Point2<f32>)],
+    start: Point2<f32>,
+    order: &mut [(usize, bool)],
+    max_iters: usize,
+) {
+    let n = order.len();
+    for _ in 0..max_iters {
+        let mut improved = false;
+        for i in 0..n {
+            let before = match i {
+                0 => start,
+                _ => exit(starts_ends, order[i - 1]),
+            };
+            for j in i..n {
+                let (first, last) = (order[i], order[j]);
+                let after = order.get(j + 1).map(|&e| entry(starts_ends, e));
+                let leave = |point: Point2<f32>| {
+                    after.map_or(0.0, |after| distance(&point, &after))
+                };
+                let current = distance(&before, &entry(starts_ends, first))
+                    + leave(exit(starts_ends, last));
+                // Reversed, the run is entered where it used to leave and
+                // leaves where it used to be entered.
+                let swapped = distance(&before, &exit(starts_ends, last))
+                    + leave(entry(starts_ends, first));
+                if swapped < current - f32::EPSILON * current {
+                    order[i..=j].reverse();
+                    for element in &mut order[i..=j] {
+                        element.1 = !element.1;
+                    }
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+/// The total pen-up travel for an order, starting from `start`.
+///
+/// This is the distance from `start` to the first element, plus the
+/// distance from the end of each element to the start of the next. The
+/// length of the elements themselves isn't included, since it's the same
+/// for every order.
+pub fn travel_distance(
+    starts_ends: &[(Point2<f32>, Point2<f32>)],
+    start: Point2<f32>,
+    order: &[(usize, bool)],
+) -> f32 {
+    let mut pen = start;
+    let mut travel = 0.0;
+    for &element in order {
+        travel += distance(&pen, &entry(starts_ends, element));
+        pen = exit(starts_ends, element);
+    }
+    travel
+}
+
+/// An order for drawing polylines with little pen-up travel.
+///
+/// This is [`greedy_nearest`] followed by up to `max_iters` passes of
+/// [`two_opt_improve`]. Empty polylines have nothing to draw and are
+/// treated as a point at `start`.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{order::order_polylines, polygon::Polyline},
+///         nalgebra::point,
+///     };
+///
+///     let paths = [
+///         Polyline::new(vec![point![9.0, 0.0], point![9.0, 5.0]]),
+///         Polyline::new(vec![point![0.0, 5.0], point![0.0, 1.0]]),
+///     ];
+///
+///     let order = order_polylines(&paths, point![0.0, 0.0], 10);
+///
+///     assert_eq!(order, vec![(1, true), (0, true)]);
+///
+pub fn order_polylines(
+    paths: &[Polyline],
+    start: Point2<f32>,
+    max_iters: usize,
+) -> Vec<(usize, bool)> {
+    let starts_ends = polyline_ends(paths, start);
+    let mut order = greedy_nearest(&starts_ends, start);
+    two_opt_improve(&starts_ends, start, &mut order, max_iters);
+    order
+}
+
+/// The total pen-up travel for drawing polylines in an order, starting
+/// from `start`.
+///
+/// See [`travel_distance`].
+pub fn polyline_travel_distance(
+    paths: &[Polyline],
+    start: Point2<f32>,
+    order: &[(usize, bool)],
+) -> f32 {
+    travel_distance(&polyline_ends(paths, start), start, order)
+}
+
+fn polyline_ends(
+    paths: &[Polyline],
+    start: Point2<f32>,
+) -> Vec<(Point2<f32>, Point2<f32>)> {
+    paths
+        .iter()
+        .map(|path| match (path.vertices.first(), path.vertices.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => (start, start),
+        })
+        .collect()
+}
+
+/// Where the pen touches down to draw an element.
+fn entry(
+    starts_ends: &[(Point2<f32>, Point2<f32>)],
+    (index, reversed): (usize, bool),
+) -> Point2<f32> {
+    let (first, last) = starts_ends[index];
+    if reversed {
+        last
+    } else {
+        first
+    }
+}
+
+/// Where the pen lifts after drawing an element.
+fn exit(
+    starts_ends: &[(Point2<f32>, Point2<f32>)],
+    (index, reversed): (usize, bool),
+) -> Point2<f32> {
+    entry(starts_ends, (index, !reversed))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            greedy_nearest, order_polylines, polyline_travel_distance,
+            travel_distance, two_opt_improve,
+        },
+        crate::{polygon::Polyline, test_support::segment},
+        alloc::vec::Vec,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    fn is_permutation(order: &[(usize, bool)], n: usize) -> bool {
+        let mut seen = vec![false; n];
+        order.len() == n
+            && order
+                .iter()
+                .all(|&(index, _)| !core::mem::replace(&mut seen[index], true))
+    }
+
+    #[test]
+    pub fn grids_of_strokes_should_halve_their_travel() {
+        // Short strokes in each cell of a 12 by 12 grid, listed in a
+        // scattered order with every other stroke backwards.
+        let count = 144;
+        let paths: Vec<Polyline> = (0..count)
+            .map(|i| {
+                let cell = (i * 37) % count;
+                let corner = point![(cell % 12) as f32, (cell / 12) as f32];
+                let stroke = [corner, corner + nalgebra::vector![0.5, 0.25]];
+                if i % 2 == 0 {
+                    Polyline::new(stroke.to_vec())
+                } else {
+                    Polyline::new(stroke.iter().rev().copied().collect())
+                }
+            })
+            .collect();
+        let start = point![0.0, 0.0];
+        let identity: Vec<(usize, bool)> =
+            (0..count).map(|i| (i, false)).collect();
+
+        let order = order_polylines(&paths, start, 50);
+
+        assert!(is_permutation(&order, count));
+        let before = polyline_travel_distance(&paths, start, &identity);
+        let after = polyline_travel_distance(&paths, start, &order);
+        assert!(after * 2.0 <= before, "{} vs {}", after, before);
+    }
+
+    #[test]
+    pub fn empty_inputs_should_have_empty_orders() {
+        let start = point![1.0, 1.0];
+        let mut order = greedy_nearest(&[], start);
+        two_opt_improve(&[], start, &mut order, 10);
+
+        assert!(order.is_empty());
+        assert_eq!(travel_distance(&[], start, &order), 0.0);
+
+        let paths = [Polyline::new(vec![]), Polyline::new(vec![start])];
+        let order = order_polylines(&paths, start, 10);
+        assert!(is_permutation(&order, 2));
+        assert_eq!(polyline_travel_distance(&paths, start, &order), 0.0);
+    }
+
+    proptest! {
+        #[test]
+        fn improved_orders_should_visit_everything_once_and_not_get_worse(
+            segments in prop::collection::vec(segment(-10.0..=10.0, 0.0), 0..40),
+        ) {
+            let starts_ends: Vec<(Point2<f32>, Point2<f32>)> =
+                segments.iter().map(|s| (s.start, s.end)).collect();
+            let start = point![0.0, 0.0];
+
+            let mut order = greedy_nearest(&starts_ends, start);
+            let greedy = travel_distance(&starts_ends, start, &order);
+            two_opt_improve(&starts_ends, start, &mut order, 20);
+            let improved = travel_distance(&starts_ends, start, &order);
+
+            prop_assert!(is_permutation(&order, segments.len()));
+            prop_assert!(improved <= greedy * (1.0 + 1e-5) + 1e-5);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Parallel versions of the expensive batch operations.
+//!
+//! Every function in this module produces exactly the same results, in exactly
+//! the same order, as its sequential counterpart. The work is distributed
+//! using rayon's global thread pool.
+//!
+//! This module is only available when the `parallel` feature is enabled.
+
+use {
+    crate::{
+        line::{
+            intersection::{
+                intersect_with_later_segments, raycast, RayHit,
+                SegmentIntersection,
+            },
+            DistanceToPoint, Ray, Segment,
+        },
+        query::nearest_in,
+    },
+    nalgebra::Point2,
+    rayon::prelude::*,
+};
+
+/// Compute the intersection between every pair of segments in parallel.
+///
+/// See [`intersect_all_pairs`](crate::line::intersection::intersect_all_pairs)
+/// for details about the output.
+pub fn par_intersect_all_pairs(
+    segments: &[Segment],
+) -> Vec<(usize, usize, SegmentIntersection)> {
+    (0..segments.len())
+        .into_par_iter()
+        .flat_map_iter(|i| intersect_with_later_segments(segments, i))
+        .collect()
+}
+
+/// Cast many rays against the same collection of segments in parallel.
+///
+/// See [`raycast_many`](crate::line::intersection::raycast_many) for details
+/// about the output.
+pub fn par_raycast_many(
+    rays: &[Ray],
+    segments: &[Segment],
+) -> Vec<Option<RayHit>> {
+    rays.par_iter().map(|ray| raycast(ray, segments)).collect()
+}
+
+/// Find the nearest item for every point in a slice in parallel.
+///
+/// See [`nearest_for_points`](crate::query::nearest_for_points) for details
+/// about the output.
+pub fn par_nearest_for_points<T: DistanceToPoint + Sync>(
+    points: &[Point2<f32>],
+    items: &[T],
+) -> Vec<Option<(usize, f32)>> {
+    points
+        .par_iter()
+        .map(|point| nearest_in(items, point))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{
+                intersection::{intersect_all_pairs, raycast_many},
+                Ray, Segment,
+            },
+            parallel::{
+                par_intersect_all_pairs, par_nearest_for_points,
+                par_raycast_many,
+            },
+            query::nearest_for_points,
+            random::SplitMix64,
+        },
+        nalgebra::{point, vector, Point2, Unit},
+    };
+
+    fn random_point(rng: &mut SplitMix64) -> Point2<f32> {
+        let x = rng.next_f64() as f32 * 100.0;
+        let y = rng.next_f64() as f32 * 100.0;
+        point![x, y]
+    }
+
+    fn random_segments(rng: &mut SplitMix64, count: usize) -> Vec<Segment> {
+        (0..count)
+            .map(|_| Segment::new(random_point(rng), random_point(rng)))
+            .collect()
+    }
+
+    #[test]
+    pub fn par_intersect_all_pairs_should_match_sequential() {
+        let segments = random_segments(&mut SplitMix64(1), 300);
+        let sequential = intersect_all_pairs(&segments);
+        assert!(!sequential.is_empty());
+        assert_eq!(par_intersect_all_pairs(&segments), sequential);
+    }
+
+    #[test]
+    pub fn par_raycast_many_should_match_sequential() {
+        let mut rng = SplitMix64(2);
+        let segments = random_segments(&mut rng, 200);
+        let rays: Vec<Ray> = (0..500)
+            .map(|_| {
+                let x = rng.next_f64() as f32 - 0.5;
+                let direction = vector![x, rng.next_f64() as f32];
+                Ray::new(random_point(&mut rng), Unit::new_normalize(direction))
+            })
+            .collect();
+
+        let sequential = raycast_many(&rays, &segments);
+        assert!(sequential.iter().any(Option::is_some));
+        assert_eq!(par_raycast_many(&rays, &segments), sequential);
+    }
+
+    #[test]
+    pub fn par_nearest_for_points_should_match_sequential() {
+        let mut rng = SplitMix64(3);
+        let segments = random_segments(&mut rng, 200);
+        let points: Vec<Point2<f32>> =
+            (0..500).map(|_| random_point(&mut rng)).collect();
+
+        assert_eq!(
+            par_nearest_for_points(&points, &segments),
+            nearest_for_points(&points, &segments)
+        );
+    }
+}
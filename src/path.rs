@@ -0,0 +1,613 @@
+//! Positions and directions at a distance along a path.
+
+use {
+    crate::{convention::AxisConvention, operations::perp_unit2d},
+    nalgebra::{Point2, Unit, Vector2},
+};
+#[cfg(feature = "alloc")]
+use {
+    crate::{math::floor, polygon::Polyline},
+    alloc::vec::Vec,
+};
+
+/// A point on a path along with the path's direction at that point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Frame {
+    /// The point on the path.
+    pub position: Point2<f32>,
+
+    /// The direction of travel along the path.
+    pub tangent: Unit<Vector2<f32>>,
+
+    /// The tangent rotated 90 degrees counterclockwise, so it points to the
+    /// left of the direction of travel. For counterclockwise polygons this
+    /// points into the polygon.
+    pub normal: Unit<Vector2<f32>>,
+}
+
+impl Frame {
+    pub(crate) fn new(
+        position: Point2<f32>,
+        tangent: Unit<Vector2<f32>>,
+    ) -> Self {
+        Self {
+            position,
+            tangent,
+            normal: perp_unit2d(&tangent),
+        }
+    }
+
+    /// The unit normal to the left of the direction of travel as seen in a
+    /// convention.
+    ///
+    /// This is [`Frame::normal`] when y is up, and its opposite when y is
+    /// down, since left on a canvas is the crate's right.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             convention::AxisConvention,
+    ///             line::Segment,
+    ///             path::PathFrame,
+    ///         },
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let east = Segment::new(point![0.0, 0.0], point![2.0, 0.0]);
+    ///     let frame = east.frame_at_length(1.0).unwrap();
+    ///
+    ///     let left = frame.normal_with_convention(AxisConvention::YDown);
+    ///
+    ///     assert_eq!(left.into_inner(), vector![0.0, -1.0]);
+    ///
+    pub fn normal_with_convention(
+        &self,
+        convention: AxisConvention,
+    ) -> Unit<Vector2<f32>> {
+        convention.left_of_y_up(self.normal)
+    }
+}
+
+/// Types which can be traversed by arc length, like segments and polylines.
+pub trait PathFrame {
+    /// The total length of the path.
+    fn path_length(&self) -> f32;
+
+    /// The frame at a distance `s` along the path.
+    ///
+    /// Open paths clamp `s` to the ends of the path, and closed paths wrap
+    /// `s` around. Exactly at an interior vertex, the tangent is the
+    /// normalized average of the tangents of the edges on either side. Edges
+    /// with zero length are skipped, and if the adjacent edges point in
+    /// exactly opposite directions the outgoing edge's tangent is used.
+    ///
+    /// Returns `None` when the path has zero length, since it has no
+    /// direction.
+    fn frame_at_length(&self, s: f32) -> Option<Frame>;
+}
+
+/// A polyline with its cumulative arc lengths precomputed, for fast
+/// repeated lookups by distance.
+///
+/// [`PathFrame::frame_at_length`] on a [`Polyline`] walks the whole path for
+/// every query. The table measures the path once and then answers each
+/// query with a binary search, so it pays off as soon as a path is queried
+/// more than a handful of times. The table owns a copy of the polyline's
+/// vertices, so editing the original polyline afterwards can't leave the
+/// table with stale lengths.
+///
+/// Lookups agree with the polyline's own [`PathFrame`] implementation:
+/// distances are clamped to the ends of the path, zero length edges are
+/// skipped, and the tangent exactly at an interior vertex is the average of
+/// the edges on either side.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{path::ArcLengthTable, polygon::Polyline},
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let table = ArcLengthTable::new(&Polyline::new(vec![
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![2.0, 2.0],
+///     ]));
+///
+///     assert_eq!(table.length(), 4.0);
+///     assert_eq!(table.point_at_length(3.0), Some(point![2.0, 1.0]));
+///     assert_relative_eq!(
+///         table.tangent_at_length(3.0).unwrap().into_inner(),
+///         vector![0.0, 1.0]
+///     );
+///     assert_eq!(table.length_at_point_index(1), Some(2.0));
+///
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLengthTable {
+    vertices: Vec<Point2<f32>>,
+
+    /// The distance along the path to each vertex.
+    lengths: Vec<f32>,
+
+    /// The index of the starting vertex of every edge with nonzero length.
+    edges: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl ArcLengthTable {
+    /// Measure a polyline.
+    pub fn new(polyline: &Polyline) -> Self {
+        let vertices = polyline.vertices.clone();
+        let mut lengths = Vec::with_capacity(vertices.len());
+        let mut edges = Vec::new();
+        let mut traveled = 0.0;
+        for (index, vertex) in vertices.iter().enumerate() {
+            if index > 0 {
+                let previous = vertices[index - 1];
+                if previous != *vertex {
+                    traveled += (vertex - previous).norm();
+                    edges.push(index - 1);
+                }
+            }
+            lengths.push(traveled);
+        }
+        Self {
+            vertices,
+            lengths,
+            edges,
+        }
+    }
+
+    /// The vertices of the measured polyline.
+    pub fn vertices(&self) -> &[Point2<f32>] {
+        &self.vertices
+    }
+
+    /// The total length of the path.
+    pub fn length(&self) -> f32 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    /// The distance along the path to a vertex, or `None` when the index is
+    /// out of bounds.
+    pub fn length_at_point_index(&self, index: usize) -> Option<f32> {
+        self.lengths.get(index).copied()
+    }
+
+    /// The point at a distance along the path, clamped to its ends.
+    ///
+    /// Paths with zero length return their first vertex. Returns `None` when
+    /// the path has no vertices.
+    pub fn point_at_length(&self, s: f32) -> Option<Point2<f32>> {
+        match self.frame_at_length(s) {
+            Some(frame) => Some(frame.position),
+            None => self.vertices.first().copied(),
+        }
+    }
+
+    /// The direction of travel at a distance along the path.
+    ///
+    /// Returns `None` when the path has zero length.
+    pub fn tangent_at_length(&self, s: f32) -> Option<Unit<Vector2<f32>>> {
+        self.frame_at_length(s).map(|frame| frame.tangent)
+    }
+
+    /// The part of the path between two distances.
+    ///
+    /// The distances can be in either order and are clamped to the ends of
+    /// the path. The slice always runs in the path's direction, starting and
+    /// ending at the points at those distances and passing through every
+    /// vertex between them. Returns an empty polyline when the path has no
+    /// vertices.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{path::ArcLengthTable, polygon::Polyline},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let table = ArcLengthTable::new(&Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///     ]));
+    ///
+    ///     assert_eq!(
+    ///         table.slice(3.0, 1.0).vertices,
+    ///         vec![point![1.0, 0.0], point![2.0, 0.0], point![2.0, 1.0]]
+    ///     );
+    ///
+    pub fn slice(&self, s0: f32, s1: f32) -> Polyline {
+        let (Some(start), Some(end)) =
+            (self.point_at_length(s0), self.point_at_length(s1))
+        else {
+            return Polyline::new(Vec::new());
+        };
+        let (start, end, s0, s1) = if s0 <= s1 {
+            (start, end, s0, s1)
+        } else {
+            (end, start, s1, s0)
+        };
+        let first = self.lengths.partition_point(|&l| l <= s0);
+        let last = self.lengths.partition_point(|&l| l < s1);
+        let mut vertices = vec![start];
+        for vertex in &self.vertices[first..last.max(first)] {
+            if vertices.last() != Some(vertex) {
+                vertices.push(*vertex);
+            }
+        }
+        if vertices.len() == 1 || vertices.last() != Some(&end) {
+            vertices.push(end);
+        }
+        Polyline::new(vertices)
+    }
+
+    /// The direction of an edge, by its index in `self.edges`.
+    fn direction(&self, edge: usize) -> Vector2<f32> {
+        let start = self.edges[edge];
+        (self.vertices[start + 1] - self.vertices[start]).normalize()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PathFrame for ArcLengthTable {
+    fn path_length(&self) -> f32 {
+        self.length()
+    }
+
+    /// The frame at a distance along the path, clamped to its ends.
+    ///
+    /// This finds the edge with a binary search, so it takes logarithmic
+    /// time.
+    fn frame_at_length(&self, s: f32) -> Option<Frame> {
+        if self.edges.is_empty() {
+            return None;
+        }
+        let s = s.clamp(0.0, self.length());
+        // The first edge which ends at or after `s`.
+        let edge = self
+            .edges
+            .partition_point(|&start| self.lengths[start + 1] < s)
+            .min(self.edges.len() - 1);
+        let start = self.edges[edge];
+        let (a, b) = (self.vertices[start], self.vertices[start + 1]);
+        let tangent = self.direction(edge);
+        let t = s - self.lengths[start];
+        let frame = if t >= (b - a).norm() {
+            match self.edges.get(edge + 1) {
+                Some(_) => {
+                    Frame::new(b, average(tangent, self.direction(edge + 1)))
+                }
+                None => Frame::new(b, Unit::new_normalize(tangent)),
+            }
+        } else {
+            Frame::new(a + tangent * t, Unit::new_normalize(tangent))
+        };
+        Some(frame)
+    }
+}
+
+/// What to do with placements which run past the end of a path.
+///
+/// See [`Polyline::place_along`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Overrun {
+    /// Stop at the first placement which doesn't fit on the path.
+    Stop,
+
+    /// Keep going in a straight line along the path's final direction.
+    Extend,
+}
+
+/// Where one of a sequence of items, like the glyphs of a label, sits along
+/// a path.
+///
+/// See [`Polyline::place_along`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Placement {
+    /// The item's origin on the path, with a tangent along the chord to
+    /// where the item ends, which is where the item's baseline sits.
+    pub frame: Frame,
+
+    /// The distance along the path to the item's origin.
+    pub distance: f32,
+
+    /// The straight line distance across the item divided by its advance.
+    ///
+    /// This is 1 where the path is straight and drops where the item spans
+    /// a corner, which is where neighboring items overlap or spread apart.
+    /// Items with no advance have a ratio of 1.
+    pub chord_ratio: f32,
+}
+
+/// Compute the frame at a distance along a sequence of vertices.
+///
+/// When `closed` is true, there is an implicit edge from the last vertex back
+/// to the first.
+#[cfg(feature = "alloc")]
+pub(crate) fn frame_along_vertices(
+    vertices: &[Point2<f32>],
+    closed: bool,
+    s: f32,
+) -> Option<Frame> {
+    let n = vertices.len();
+    let edge_count = if closed { n } else { n.saturating_sub(1) };
+    let edges = || {
+        (0..edge_count)
+            .map(move |i| (vertices[i], vertices[(i + 1) % n]))
+            .filter(|(a, b)| a != b)
+    };
+
+    let total: f32 = edges().map(|(a, b)| (b - a).norm()).sum();
+    if total == 0.0 {
+        return None;
+    }
+    let s = if closed {
+        s - floor((s / total) as f64) as f32 * total
+    } else {
+        s.clamp(0.0, total)
+    };
+
+    let direction = |(a, b): (Point2<f32>, Point2<f32>)| (b - a).normalize();
+    let mut traveled = 0.0;
+    let mut last = None;
+    for (index, (a, b)) in edges().enumerate() {
+        let length = (b - a).norm();
+        let tangent = direction((a, b));
+        last = Some((b, tangent));
+        if s > traveled + length {
+            traveled += length;
+            continue;
+        }
+
+        let t = s - traveled;
+        let frame = if t >= length {
+            let next = edges()
+                .nth(index + 1)
+                .or_else(|| closed.then(|| edges().next()).flatten());
+            match next {
+                Some(next) => Frame::new(b, average(tangent, direction(next))),
+                None => Frame::new(b, Unit::new_normalize(tangent)),
+            }
+        } else if t <= 0.0 && index == 0 && closed {
+            let previous = edges().next_back().unwrap();
+            Frame::new(a, average(direction(previous), tangent))
+        } else {
+            Frame::new(a + tangent * t, Unit::new_normalize(tangent))
+        };
+        return Some(frame);
+    }
+
+    // Rounding can leave `s` just beyond the accumulated length.
+    last.map(|(end, tangent)| Frame::new(end, Unit::new_normalize(tangent)))
+}
+
+/// The normalized average of an incoming and outgoing direction.
+#[cfg(feature = "alloc")]
+pub(crate) fn average(
+    incoming: Vector2<f32>,
+    outgoing: Vector2<f32>,
+) -> Unit<Vector2<f32>> {
+    Unit::try_new(incoming + outgoing, f32::EPSILON)
+        .unwrap_or_else(|| Unit::new_normalize(outgoing))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            path::{ArcLengthTable, PathFrame},
+            polygon::{Polygon, Polyline},
+            test_support::point2,
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    fn counterclockwise_square() -> Polygon {
+        Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 2.0],
+            point![0.0, 2.0],
+        ])
+    }
+
+    #[test]
+    pub fn square_edge_midpoints_should_have_outward_normals() {
+        let mut square = counterclockwise_square();
+        // Normals point left of travel, so a clockwise square's normals point
+        // out of the square.
+        square.vertices.reverse();
+
+        let expected = [
+            (point![1.0, 2.0], vector![0.0, 1.0]),
+            (point![2.0, 1.0], vector![1.0, 0.0]),
+            (point![1.0, 0.0], vector![0.0, -1.0]),
+            (point![0.0, 1.0], vector![-1.0, 0.0]),
+        ];
+        for (i, (position, normal)) in expected.iter().enumerate() {
+            let frame = square.frame_at_length(1.0 + 2.0 * i as f32).unwrap();
+            assert_relative_eq!(frame.position, position);
+            assert_relative_eq!(frame.normal.into_inner(), normal);
+        }
+    }
+
+    #[test]
+    pub fn corners_should_average_the_adjacent_tangents() {
+        let square = counterclockwise_square();
+
+        let corner = square.frame_at_length(2.0).unwrap();
+        assert_relative_eq!(corner.position, point![2.0, 0.0]);
+        assert_relative_eq!(
+            corner.tangent,
+            Unit::new_normalize(vector![1.0, 1.0])
+        );
+
+        // the first vertex sits between the last edge and the first edge
+        let start = square.frame_at_length(0.0).unwrap();
+        assert_relative_eq!(
+            start.tangent,
+            Unit::new_normalize(vector![1.0, -1.0])
+        );
+    }
+
+    #[test]
+    pub fn closed_paths_should_wrap() {
+        let square = counterclockwise_square();
+
+        assert_relative_eq!(
+            square.frame_at_length(9.0).unwrap().position,
+            point![1.0, 0.0]
+        );
+        assert_relative_eq!(
+            square.frame_at_length(-1.0).unwrap().position,
+            point![0.0, 1.0]
+        );
+    }
+
+    #[test]
+    pub fn open_paths_should_clamp() {
+        let polyline = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 3.0],
+        ]);
+
+        let end = polyline.frame_at_length(10.0).unwrap();
+        assert_relative_eq!(end.position, point![1.0, 3.0]);
+        assert_relative_eq!(end.tangent.into_inner(), vector![0.0, 1.0]);
+
+        let start = polyline.frame_at_length(-10.0).unwrap();
+        assert_relative_eq!(start.position, point![0.0, 0.0]);
+        assert_relative_eq!(start.tangent.into_inner(), vector![1.0, 0.0]);
+
+        // the zero length edge is skipped when averaging at the corner
+        let corner = polyline.frame_at_length(1.0).unwrap();
+        assert_relative_eq!(
+            corner.tangent,
+            Unit::new_normalize(vector![1.0, 1.0])
+        );
+    }
+
+    #[test]
+    pub fn segments_should_have_a_constant_frame() {
+        let segment = Segment::new(point![1.0, 1.0], point![1.0, 5.0]);
+
+        let frame = segment.frame_at_length(2.5).unwrap();
+
+        assert_relative_eq!(segment.path_length(), 4.0);
+        assert_relative_eq!(frame.position, point![1.0, 3.5]);
+        assert_relative_eq!(frame.normal.into_inner(), vector![-1.0, 0.0]);
+    }
+
+    #[test]
+    pub fn zero_length_paths_should_have_no_frame() {
+        let p = point![1.0, 1.0];
+
+        assert_eq!(Segment::new(p, p).frame_at_length(0.0), None);
+        assert_eq!(Polyline::new(vec![p, p, p]).frame_at_length(0.0), None);
+        assert_eq!(Polygon::new(vec![p]).frame_at_length(0.0), None);
+    }
+
+    #[test]
+    pub fn tables_should_handle_empty_and_zero_length_paths() {
+        let empty = ArcLengthTable::new(&Polyline::new(vec![]));
+        assert_eq!(empty.length(), 0.0);
+        assert_eq!(empty.point_at_length(1.0), None);
+        assert!(empty.slice(0.0, 1.0).vertices.is_empty());
+
+        let p = point![1.0, 2.0];
+        let point = ArcLengthTable::new(&Polyline::new(vec![p, p]));
+        assert_eq!(point.point_at_length(1.0), Some(p));
+        assert_eq!(point.tangent_at_length(1.0), None);
+        assert_eq!(point.length_at_point_index(1), Some(0.0));
+        assert_eq!(point.length_at_point_index(2), None);
+    }
+
+    #[test]
+    pub fn slices_should_skip_repeated_vertices() {
+        let table = ArcLengthTable::new(&Polyline::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 3.0],
+        ]));
+
+        assert_eq!(
+            table.slice(-1.0, 2.0).vertices,
+            vec![point![0.0, 0.0], point![1.0, 0.0], point![1.0, 1.0]]
+        );
+        assert_eq!(
+            table.slice(1.0, 10.0).vertices,
+            vec![point![1.0, 0.0], point![1.0, 3.0]]
+        );
+        assert_eq!(
+            table.slice(0.5, 0.5).vertices,
+            vec![point![0.5, 0.0], point![0.5, 0.0]]
+        );
+    }
+
+    fn polyline() -> impl Strategy<Value = Polyline> {
+        // Repeating some vertices makes zero length edges likely.
+        prop::collection::vec((point2(-10.0..=10.0), 0..3usize), 1..20)
+            .prop_map(|vertices| {
+                Polyline::new(
+                    vertices
+                        .into_iter()
+                        .flat_map(|(p, repeat)| {
+                            core::iter::repeat_n(p, 1 + repeat / 2)
+                        })
+                        .collect(),
+                )
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn tables_should_agree_with_the_linear_scan(
+            polyline in polyline(),
+            fractions in prop::collection::vec(-0.1f32..1.1, 1..10),
+        ) {
+            let table = ArcLengthTable::new(&polyline);
+            assert_relative_eq!(table.length(), polyline.length());
+
+            let length = polyline.length();
+            let vertices = (0..polyline.vertices.len())
+                .map(|i| table.length_at_point_index(i).unwrap());
+            for s in fractions.iter().map(|f| f * length).chain(vertices) {
+                let expected = polyline.frame_at_length(s);
+                let actual = table.frame_at_length(s);
+                prop_assert_eq!(expected, actual);
+            }
+        }
+
+        #[test]
+        fn slices_should_have_the_requested_length(
+            polyline in polyline(),
+            a in -0.1f32..1.1,
+            b in -0.1f32..1.1,
+        ) {
+            let table = ArcLengthTable::new(&polyline);
+            let length = table.length();
+
+            let slice = table.slice(a * length, b * length);
+
+            let expected = (b.clamp(0.0, 1.0) - a.clamp(0.0, 1.0)).abs() * length;
+            assert_relative_eq!(
+                slice.length(),
+                expected,
+                epsilon = 1e-4 * length.max(1.0)
+            );
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use {
+    crate::{
+        convention::AxisConvention,
+        math,
+        operations::perp_unit2d,
+        path::average,
+        polygon::{Polygon, Polyline},
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+impl Polygon {
+    /// Sample points along the boundary, each paired with the outward unit
+    /// normal there, like the boundary particles of a fluid simulation.
+    ///
+    /// Every vertex is sampled, and each edge is split into equal parts at
+    /// most `spacing` long. Samples inside an edge use the edge's normal,
+    /// and samples at a corner use the normal along the angle's bisector.
+    /// The winding is normalized first, so the normals point out of the
+    /// polygon either way and a polygon gives the same samples as its
+    /// reverse. Samples start at the first vertex of the counterclockwise
+    /// winding and run counterclockwise.
+    ///
+    /// Edges with zero length are skipped. Returns no samples when the
+    /// spacing isn't positive or the polygon has no edges with length.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::{point, vector},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![0.0, 2.0],
+    ///     ]);
+    ///
+    ///     let samples = square.boundary_samples(1.0);
+    ///
+    ///     assert_eq!(samples.len(), 8);
+    ///     assert_eq!(samples[1].0, point![1.0, 0.0]);
+    ///     assert_relative_eq!(samples[1].1.into_inner(), vector![0.0, -1.0]);
+    ///     let diagonal = -vector![1.0, 1.0].normalize();
+    ///     assert_relative_eq!(samples[0].1.into_inner(), diagonal);
+    ///
+    pub fn boundary_samples(
+        &self,
+        spacing: f32,
+    ) -> Vec<(Point2<f32>, Unit<Vector2<f32>>)> {
+        let mut vertices = self.vertices.clone();
+        if self.signed_area() < 0.0 {
+            vertices.reverse();
+        }
+        // The left of a counterclockwise boundary is inside.
+        samples_with_left_normals(&vertices, true, spacing)
+            .into_iter()
+            .map(|(point, normal)| (point, -normal))
+            .collect()
+    }
+}
+
+impl Polyline {
+    /// Sample points along the polyline, each paired with the unit normal
+    /// to the left of the direction of travel.
+    ///
+    /// Samples are placed like [`Polygon::boundary_samples`]: at every
+    /// vertex and evenly along each segment at most `spacing` apart, with
+    /// interior vertices using the normal along the angle's bisector. The
+    /// ends of an open polyline use the normal of their segment, and a
+    /// closed polyline is sampled like a polygon without repeating the
+    /// shared vertex or changing its direction.
+    ///
+    /// Segments with zero length are skipped. Returns no samples when the
+    /// spacing isn't positive or the polyline has no length.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polyline,
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let line = Polyline::new(vec![point![0.0, 0.0], point![3.0, 0.0]]);
+    ///
+    ///     let samples = line.boundary_samples(1.5);
+    ///
+    ///     assert_eq!(samples.len(), 3);
+    ///     assert_eq!(samples[2].0, point![3.0, 0.0]);
+    ///     for (_, normal) in samples {
+    ///         assert_eq!(normal.into_inner(), vector![0.0, 1.0]);
+    ///     }
+    ///
+    pub fn boundary_samples(
+        &self,
+        spacing: f32,
+    ) -> Vec<(Point2<f32>, Unit<Vector2<f32>>)> {
+        if self.is_closed() {
+            let loop_vertices = &self.vertices[..self.vertices.len() - 1];
+            samples_with_left_normals(loop_vertices, true, spacing)
+        } else {
+            samples_with_left_normals(&self.vertices, false, spacing)
+        }
+    }
+
+    /// Sample points along the polyline like
+    /// [`Polyline::boundary_samples`], with normals to the left of the
+    /// direction of travel as seen in a convention.
+    ///
+    /// Moving samples along their normals offsets the polyline to the left
+    /// on screen, which is the crate's right when y points down.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{convention::AxisConvention, polygon::Polyline},
+    ///         nalgebra::{point, vector},
+    ///     };
+    ///
+    ///     let line = Polyline::new(vec![point![0.0, 0.0], point![3.0, 0.0]]);
+    ///
+    ///     let canvas = AxisConvention::YDown;
+    ///     let samples = line.boundary_samples_with_convention(1.5, canvas);
+    ///
+    ///     for (_, normal) in samples {
+    ///         assert_eq!(normal.into_inner(), vector![0.0, -1.0]);
+    ///     }
+    ///
+    pub fn boundary_samples_with_convention(
+        &self,
+        spacing: f32,
+        convention: AxisConvention,
+    ) -> Vec<(Point2<f32>, Unit<Vector2<f32>>)> {
+        self.boundary_samples(spacing)
+            .into_iter()
+            .map(|(point, normal)| (point, convention.left_of_y_up(normal)))
+            .collect()
+    }
+}
+
+/// Sample every vertex and evenly along every edge, with normals to the
+/// left of the direction of travel.
+fn samples_with_left_normals(
+    vertices: &[Point2<f32>],
+    closed: bool,
+    spacing: f32,
+) -> Vec<(Point2<f32>, Unit<Vector2<f32>>)> {
+    if spacing.is_nan() || spacing <= 0.0 {
+        return Vec::new();
+    }
+    let n = vertices.len();
+    let edge_count = if closed { n } else { n.saturating_sub(1) };
+    let edges: Vec<_> = (0..edge_count)
+        .map(|i| (vertices[i], vertices[(i + 1) % n]))
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| (a, b, Unit::new_normalize(b - a)))
+        .collect();
+    let Some(&(_, last_end, last_tangent)) = edges.last() else {
+        return Vec::new();
+    };
+
+    let mut samples = Vec::new();
+    for (index, &(start, end, tangent)) in edges.iter().enumerate() {
+        let corner = match index.checked_sub(1) {
+            Some(previous) => average(*edges[previous].2, *tangent),
+            None if closed => average(*last_tangent, *tangent),
+            None => tangent,
+        };
+        samples.push((start, perp_unit2d(&corner)));
+
+        let normal = perp_unit2d(&tangent);
+        let parts = math::ceil((end - start).norm() / spacing).max(1.0);
+        let parts = parts as usize;
+        samples.extend((1..parts).map(|i| {
+            (start + (end - start) * (i as f32 / parts as f32), normal)
+        }));
+    }
+    if !closed {
+        samples.push((last_end, perp_unit2d(&last_tangent)));
+    }
+    samples
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            convention::{AxisConvention, Orientation},
+            line::DistanceToPoint,
+            polygon::Polyline,
+            test_support::{polygon, rectangle},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn square_normals_should_point_away_from_the_centroid() {
+        let samples =
+            rectangle(point![0.0, 0.0], point![4.0, 4.0]).boundary_samples(0.5);
+
+        assert_eq!(samples.len(), 32);
+        for (point, normal) in &samples {
+            assert!((point - point![2.0, 2.0]).dot(normal) > 0.0);
+        }
+        let corners: Vec<_> = samples
+            .iter()
+            .step_by(8)
+            .map(|(_, normal)| *normal)
+            .collect();
+        let diagonal = 0.5f32.sqrt();
+        assert_relative_eq!(
+            corners[0].into_inner(),
+            vector![-1.0, -1.0] * diagonal
+        );
+        assert_relative_eq!(
+            corners[2].into_inner(),
+            vector![1.0, 1.0] * diagonal
+        );
+    }
+
+    #[test]
+    pub fn reversed_squares_should_have_the_same_samples() {
+        let mut reversed = rectangle(point![0.0, 0.0], point![4.0, 4.0]);
+        reversed.vertices.reverse();
+
+        assert_eq!(
+            reversed.boundary_samples(0.7),
+            rectangle(point![0.0, 0.0], point![4.0, 4.0]).boundary_samples(0.7)
+        );
+    }
+
+    #[test]
+    pub fn polyline_normals_should_be_on_the_left() {
+        let turn = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 2.0],
+        ]);
+        let closed = Polyline::new(
+            rectangle(point![0.0, 0.0], point![4.0, 4.0])
+                .vertices
+                .into_iter()
+                .chain([point![0.0, 0.0]])
+                .collect(),
+        );
+
+        let samples = turn.boundary_samples(1.0);
+
+        let normals: Vec<_> = samples
+            .iter()
+            .map(|(_, normal)| normal.into_inner())
+            .collect();
+        let bisector = vector![-1.0, 1.0].normalize();
+        assert_eq!(normals.len(), 5);
+        assert_relative_eq!(normals[0], vector![0.0, 1.0]);
+        assert_relative_eq!(normals[2], bisector);
+        assert_relative_eq!(normals[4], vector![-1.0, 0.0]);
+        // Closed polylines point in and aren't sampled twice at the start.
+        let inward = closed.boundary_samples(4.0);
+        assert_eq!(inward.len(), 4);
+        assert_relative_eq!(
+            inward[0].1.into_inner(),
+            vector![1.0, 1.0].normalize()
+        );
+        assert!(Polyline::new(vec![point![1.0, 1.0]])
+            .boundary_samples(1.0)
+            .is_empty());
+        assert!(turn.boundary_samples(0.0).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn samples_should_be_on_the_boundary_and_close_together(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            spacing in 0.1f32..=5.0,
+        ) {
+            let samples = polygon.boundary_samples(spacing);
+
+            for (point, normal) in &samples {
+                let on_edge = polygon.edges().any(|edge| {
+                    edge.distance_to_point(point) < 1e-3
+                });
+                prop_assert!(on_edge);
+                assert_relative_eq!(normal.norm(), 1.0, epsilon = 1e-4);
+            }
+            let wrapped = samples.iter().chain(samples.first());
+            for (a, b) in samples.iter().zip(wrapped.skip(1)) {
+                prop_assert!((b.0 - a.0).norm() <= spacing + 1e-3);
+            }
+        }
+
+        #[test]
+        fn left_normals_should_point_into_polygons_wound_counterclockwise(
+            polygon in polygon(-10.0..=10.0, 3..=8, true),
+        ) {
+            prop_assume!(polygon.area() > 1.0);
+            let mut reversed = polygon.clone();
+            reversed.vertices.reverse();
+
+            for convention in [AxisConvention::YUp, AxisConvention::YDown] {
+                // Whichever winding looks counterclockwise in the convention
+                // has the polygon on the left of its boundary.
+                let (counterclockwise, clockwise) =
+                    match polygon.orientation_with_convention(convention) {
+                        Orientation::Counterclockwise => (&polygon, &reversed),
+                        _ => (&reversed, &polygon),
+                    };
+                prop_assert_eq!(
+                    clockwise.orientation_with_convention(convention),
+                    Orientation::Clockwise
+                );
+                let mut closed = counterclockwise.vertices.clone();
+                closed.push(closed[0]);
+                let samples = Polyline::new(closed)
+                    .boundary_samples_with_convention(1.0, convention);
+                for (point, normal) in samples {
+                    let inside = point + normal.into_inner() * 1e-3;
+                    prop_assert!(polygon.contains_point(&inside));
+                }
+            }
+
+            // Offsets grow outward whatever the winding.
+            for winding in [&polygon, &reversed] {
+                prop_assert!(winding.offset(0.5).area() > polygon.area());
+            }
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use {
+    crate::{
+        line::{DistanceToPoint, Segment},
+        math,
+        polygon::{
+            offset::{inside_point, untangle, winding_number},
+            Polygon, Polyline, Region,
+        },
+        shape::{segments_for_sweep, Circle},
+    },
+    alloc::vec::Vec,
+    core::f32::consts::PI,
+    nalgebra::{Point2, Vector2},
+};
+
+/// Geometry which can be grown into the regions within a distance of it,
+/// like the buffers of a GIS library.
+///
+/// Buffers are the Minkowski sum of the geometry with a disc: every point
+/// within `distance` of a point, a segment, or a polyline, or within
+/// `distance` of a polygon's inside. Round parts are approximated by chords
+/// whose points are on the exact circle, and which stray from it by at
+/// most `max_error`, like [`Circle::to_polygon`]. The regions have
+/// counterclockwise outer boundaries and clockwise holes, and come from
+/// untangling offset loops like [`cleanup_offset`](super::cleanup_offset).
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::Segment,
+///             polygon::{Buffer, Polyline},
+///         },
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let segment = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+///     let capsule = segment.buffer(1.0, 0.001);
+///
+///     assert_eq!(capsule.len(), 1);
+///     assert_relative_eq!(
+///         capsule[0].area(),
+///         8.0 + core::f32::consts::PI,
+///         epsilon = 0.01
+///     );
+///
+///     // Strokes around a closed loop have a hole in the middle.
+///     let ring = Polyline::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///         point![0.0, 4.0],
+///         point![0.0, 0.0],
+///     ]);
+///     let stroke = ring.buffer(1.0, 0.001);
+///
+///     assert_eq!(stroke[0].holes.len(), 1);
+///     assert_relative_eq!(stroke[0].holes[0].area(), 4.0, epsilon = 1e-4);
+///
+pub trait Buffer {
+    /// The regions within `distance` of the geometry.
+    ///
+    /// Distances which aren't positive leave nothing, except for polygons,
+    /// which shrink by negative distances.
+    fn buffer(&self, distance: f32, max_error: f32) -> Vec<Region>;
+}
+
+impl Buffer for Point2<f32> {
+    /// A circle around the point, exactly [`Circle::to_polygon`].
+    fn buffer(&self, distance: f32, max_error: f32) -> Vec<Region> {
+        if distance.is_nan() || distance <= 0.0 {
+            return Vec::new();
+        }
+        let circle = Circle::new(*self, distance).to_polygon(max_error);
+        vec![Region::new(circle, Vec::new())]
+    }
+}
+
+impl Buffer for Segment {
+    /// A capsule around the segment, with round caps at both ends.
+    fn buffer(&self, distance: f32, max_error: f32) -> Vec<Region> {
+        Polyline::new(vec![self.start, self.end]).buffer(distance, max_error)
+    }
+}
+
+impl Buffer for Polyline {
+    /// A stroke along the polyline with round joins and caps.
+    ///
+    /// Closed polylines are stroked all the way around without caps, which
+    /// leaves a hole in the middle when the loop is wide enough. Where an
+    /// open polyline crosses itself the stroke is merged, and areas it
+    /// surrounds without covering become holes.
+    fn buffer(&self, distance: f32, max_error: f32) -> Vec<Region> {
+        let vertices = distinct(&self.vertices, self.is_closed());
+        if distance.is_nan() || distance <= 0.0 || vertices.is_empty() {
+            return Vec::new();
+        }
+        if vertices.len() == 1 {
+            return vertices[0].buffer(distance, max_error);
+        }
+        if !self.is_closed() || vertices.len() < 3 {
+            // Walking out and back again strokes both sides, and turning
+            // around at the ends makes the caps.
+            let mut path = vertices.clone();
+            path.extend(vertices[1..vertices.len() - 1].iter().rev());
+            return buffer_loop(&path, &vertices, false, distance, max_error);
+        }
+
+        let ring = Polygon::new(vertices);
+        let mut regions = ring.buffer(distance, max_error);
+        for inset in ring.buffer(-distance, max_error) {
+            let mut hole = inset.outer;
+            let point = inside_point(&hole);
+            hole.vertices.reverse();
+            if let Some(region) = regions
+                .iter_mut()
+                .find(|region| region.outer.contains_point(&point))
+            {
+                region.holes.push(hole);
+            }
+        }
+        regions
+    }
+}
+
+impl Buffer for Polygon {
+    /// The polygon grown by positive distances, with round corners where it
+    /// grows past its convex vertices, or shrunk by negative distances,
+    /// with round corners inside its concave vertices. A distance of zero
+    /// leaves the polygon as it is.
+    ///
+    /// The polygon should be simple, and its winding doesn't matter.
+    fn buffer(&self, distance: f32, max_error: f32) -> Vec<Region> {
+        let mut vertices = distinct(&self.vertices, true);
+        if distance.is_nan() || vertices.len() < 3 {
+            return Vec::new();
+        }
+        if self.signed_area() < 0.0 {
+            vertices.reverse();
+        }
+        if distance == 0.0 {
+            return vec![Region::new(Polygon::new(vertices), Vec::new())];
+        }
+        buffer_loop(&vertices, &vertices, true, distance, max_error)
+    }
+}
+
+/// The vertices without consecutive repeats, or repeats of the first
+/// vertex at the end of a closed path.
+fn distinct(vertices: &[Point2<f32>], closed: bool) -> Vec<Point2<f32>> {
+    let mut distinct: Vec<Point2<f32>> = Vec::with_capacity(vertices.len());
+    for vertex in vertices {
+        if distinct.last() != Some(vertex) {
+            distinct.push(*vertex);
+        }
+    }
+    while closed && distinct.len() > 1 && distinct.first() == distinct.last() {
+        distinct.pop();
+    }
+    distinct
+}
+
+/// Offset a loop to the right of its direction of travel, with round joins
+/// where the offset pulls away from a vertex, then untangle it into
+/// regions.
+///
+/// `original` is the geometry being buffered, which is closed when
+/// `closed` is true, and loops which are nowhere `distance` away from it
+/// are where the offset turned inside out.
+fn buffer_loop(
+    path: &[Point2<f32>],
+    original: &[Point2<f32>],
+    closed: bool,
+    distance: f32,
+    max_error: f32,
+) -> Vec<Region> {
+    let n = path.len();
+    let right = |i: usize| {
+        let edge = path[(i + 1) % n] - path[i];
+        Vector2::new(edge.y, -edge.x).normalize()
+    };
+    let radius = distance.abs();
+    let mut raw = Vec::new();
+    for (i, vertex) in path.iter().enumerate() {
+        let (before, after) = (right((i + n - 1) % n), right(i));
+        let (from, to) = (before * distance, after * distance);
+        let cross = before.perp(&after);
+        let sweep = if cross == 0.0 && before.dot(&after) < 0.0 {
+            // Turning around, like at the end of a stroke.
+            if distance > 0.0 {
+                PI
+            } else {
+                -PI
+            }
+        } else {
+            math::atan2(cross, before.dot(&after))
+        };
+        raw.push(vertex + from);
+        if sweep * distance > 0.0 {
+            let chords = segments_for_sweep(radius, sweep.abs(), max_error);
+            raw.extend((1..chords).map(|k| {
+                let (sin, cos) =
+                    math::sin_cos(sweep * k as f32 / chords as f32);
+                let rotated = Vector2::new(
+                    from.x * cos - from.y * sin,
+                    from.x * sin + from.y * cos,
+                );
+                vertex + rotated
+            }));
+        }
+        if to != from {
+            raw.push(vertex + to);
+        }
+    }
+    let raw = Polygon::new(distinct(&raw, true));
+    if raw.vertices.len() < 3 {
+        return Vec::new();
+    }
+
+    let edges: Vec<Segment> = if closed {
+        Polygon::new(original.to_vec()).edges().collect()
+    } else {
+        Polyline::new(original.to_vec()).segments().collect()
+    };
+    // Vertices of the real boundary are exactly the distance away, on the
+    // offset edges and the arcs.
+    let clear = |loop_: &Polygon| {
+        loop_.vertices.iter().any(|vertex| {
+            edges.iter().all(|edge| {
+                edge.distance_to_point(vertex) >= radius * (1.0 - 1e-3)
+            })
+        })
+    };
+    let mut outers = Vec::new();
+    let mut holes = Vec::new();
+    for mut candidate in untangle(&raw) {
+        if candidate.signed_area() > 0.0 {
+            let inside = inside_point(&candidate);
+            if winding_number(&raw, &inside) == 1 && clear(&candidate) {
+                outers.push(candidate);
+            }
+        } else if candidate.signed_area() < 0.0 {
+            // Holes are uncovered areas which the offset goes around.
+            candidate.vertices.reverse();
+            let inside = inside_point(&candidate);
+            candidate.vertices.reverse();
+            if winding_number(&raw, &inside) == 0 && clear(&candidate) {
+                holes.push((inside, candidate));
+            }
+        }
+    }
+
+    let mut regions: Vec<Region> = outers
+        .into_iter()
+        .map(|outer| Region::new(outer, Vec::new()))
+        .collect();
+    for (inside, hole) in holes {
+        let around = regions
+            .iter_mut()
+            .filter(|region| region.outer.contains_point(&inside))
+            .min_by(|a, b| a.outer.area().total_cmp(&b.outer.area()));
+        if let Some(region) = around {
+            region.holes.push(hole);
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::Buffer,
+        crate::{
+            line::DistanceToPoint,
+            polygon::{Polygon, Polyline, Region},
+            shape::Circle,
+            test_support::{point2, polygon, rectangle, segment},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::PI,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn points_should_buffer_into_circles() {
+        let center = point![2.0, -1.0];
+
+        let buffered = center.buffer(1.5, 0.01);
+
+        let circle = Circle::new(center, 1.5).to_polygon(0.01);
+        assert_eq!(buffered, [Region::new(circle, Vec::new())]);
+        assert!(center.buffer(0.0, 0.01).is_empty());
+    }
+
+    #[test]
+    pub fn polygons_should_grow_round_corners_and_shrink_sharp_ones() {
+        let mut clockwise = rectangle(point![0.0, 0.0], point![4.0, 4.0]);
+        clockwise.vertices.reverse();
+
+        let grown = clockwise.buffer(1.0, 1e-4);
+        let shrunk =
+            rectangle(point![0.0, 0.0], point![4.0, 4.0]).buffer(-1.0, 1e-4);
+
+        assert_eq!(grown.len(), 1);
+        assert_relative_eq!(grown[0].area(), 16.0 + 16.0 + PI, epsilon = 1e-2);
+        assert_eq!(shrunk.len(), 1);
+        assert_relative_eq!(shrunk[0].area(), 4.0, epsilon = 1e-4);
+        // Shrinking past the middle leaves nothing.
+        assert!(rectangle(point![0.0, 0.0], point![4.0, 4.0])
+            .buffer(-2.5, 1e-4)
+            .is_empty());
+    }
+
+    #[test]
+    pub fn concave_corners_should_round_when_shrinking() {
+        let l_shape = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![8.0, 0.0],
+            point![8.0, 4.0],
+            point![4.0, 4.0],
+            point![4.0, 8.0],
+            point![0.0, 8.0],
+        ]);
+
+        let shrunk = l_shape.buffer(-1.0, 1e-4);
+
+        // Two 2x6 arms overlapping in a 2x2 square, which reach on toward
+        // the concave corner until they're a unit away from it.
+        let expected = 12.0 + 12.0 - 4.0 + (1.0 - PI / 4.0);
+        assert_eq!(shrunk.len(), 1);
+        assert_relative_eq!(shrunk[0].area(), expected, epsilon = 1e-3);
+    }
+
+    #[test]
+    pub fn crossing_strokes_should_merge_and_surround_holes() {
+        // A loop drawn as an open path which crosses itself at its start.
+        let path = Polyline::new(vec![
+            point![2.0, -2.0],
+            point![2.0, 8.0],
+            point![8.0, 8.0],
+            point![8.0, 2.0],
+            point![-2.0, 2.0],
+        ]);
+
+        let stroke = path.buffer(1.0, 1e-3);
+
+        assert_eq!(stroke.len(), 1);
+        assert_eq!(stroke[0].holes.len(), 1);
+        assert_relative_eq!(stroke[0].holes[0].area(), 16.0, epsilon = 1e-3);
+        assert!(stroke[0].contains_point(&point![2.0, 2.0]));
+        assert!(!stroke[0].contains_point(&point![5.0, 5.0]));
+    }
+
+    proptest! {
+        #[test]
+        fn segment_buffers_should_be_capsules(
+            segment in segment(-10.0..=10.0, 0.0),
+            distance in 0.1f32..=3.0,
+            max_error in 0.001f32..=0.1,
+        ) {
+            let capsule = segment.buffer(distance, max_error);
+
+            let exact = 2.0 * distance * segment.length()
+                + PI * distance * distance;
+            // Chords cut off at most the error along the round caps.
+            let bound = 2.0 * PI * distance * max_error;
+            prop_assert_eq!(capsule.len(), 1);
+            prop_assert!(capsule[0].holes.is_empty());
+            let area = capsule[0].area();
+            prop_assert!(area <= exact * (1.0 + 1e-4) + 1e-4);
+            prop_assert!(area >= exact - bound - exact * 1e-4);
+        }
+
+        #[test]
+        fn polygon_buffers_should_keep_their_distance(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            distance in -2.0f32..=2.0,
+            probe in point2(-12.0..=12.0),
+        ) {
+            let regions = polygon.buffer(distance, 1e-3);
+
+            let boundary = |p: &Point2<f32>| {
+                polygon
+                    .edges()
+                    .map(|edge| edge.distance_to_point(p))
+                    .fold(f32::INFINITY, f32::min)
+            };
+            let signed = if polygon.contains_point(&probe) {
+                -boundary(&probe)
+            } else {
+                boundary(&probe)
+            };
+            let inside = regions.iter().any(|r| r.contains_point(&probe));
+            // Leave room for the chords and for rounding near the boundary.
+            if signed < distance - 0.01 {
+                prop_assert!(inside);
+            } else if signed > distance + 0.01 {
+                prop_assert!(!inside);
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use {
+    crate::{
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            Segment,
+        },
+        polygon::Polygon,
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// Cut segments into the pieces which are inside a polygon.
+///
+/// This is the same as [`clip_segments_to_polygon_indexed`] without the
+/// source indices.
+pub fn clip_segments_to_polygon(
+    segments: &[Segment],
+    polygon: &Polygon,
+) -> Vec<Segment> {
+    clip_segments_to_polygon_indexed(segments, polygon)
+        .into_iter()
+        .map(|(_, piece)| piece)
+        .collect()
+}
+
+/// Cut segments into the pieces which are inside a polygon, tagging each
+/// piece with the index of the segment it came from.
+///
+/// Each segment is split wherever it crosses the polygon's boundary and the
+/// parts whose middles are inside the polygon are kept, using the same
+/// even-odd rule as [`Polygon::contains_point`]. Pieces keep the direction
+/// of their source segment and come out in the same order as the segments.
+/// A segment which leaves and re-enters a concave polygon gives several
+/// pieces with the same index. Parts which run exactly along an edge may
+/// or may not be kept.
+///
+/// The index makes it easy to carry any other data along with the pieces,
+/// like a stroke's color, by looking it up in a list next to the segments.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::Segment,
+///             polygon::{clip_segments_to_polygon_indexed, Polygon},
+///         },
+///         nalgebra::point,
+///     };
+///
+///     // A "U" shape, open at the top.
+///     let cup = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![3.0, 0.0],
+///         point![3.0, 3.0],
+///         point![2.0, 3.0],
+///         point![2.0, 1.0],
+///         point![1.0, 1.0],
+///         point![1.0, 3.0],
+///         point![0.0, 3.0],
+///     ]);
+///     let strokes = [
+///         Segment::new(point![-1.0, 2.0], point![4.0, 2.0]),
+///         Segment::new(point![5.0, 5.0], point![6.0, 6.0]),
+///         Segment::new(point![0.5, 0.5], point![2.5, 0.5]),
+///     ];
+///     let colors = ["red", "green", "blue"];
+///
+///     let pieces = clip_segments_to_polygon_indexed(&strokes, &cup);
+///
+///     let tags: Vec<&str> = pieces.iter().map(|&(i, _)| colors[i]).collect();
+///     assert_eq!(tags, ["red", "red", "blue"]);
+///     assert_eq!(
+///         pieces[1].1,
+///         Segment::new(point![2.0, 2.0], point![3.0, 2.0])
+///     );
+///
+pub fn clip_segments_to_polygon_indexed(
+    segments: &[Segment],
+    polygon: &Polygon,
+) -> Vec<(usize, Segment)> {
+    let mut pieces = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        clip_segment(segment, polygon, |piece| pieces.push((index, piece)));
+    }
+    pieces
+}
+
+/// Call `keep` with every piece of a segment which is inside a polygon, in
+/// order along the segment.
+fn clip_segment(
+    segment: &Segment,
+    polygon: &Polygon,
+    mut keep: impl FnMut(Segment),
+) {
+    let direction = segment.end - segment.start;
+    let length_squared = direction.norm_squared();
+    if length_squared == 0.0 {
+        if polygon.contains_point(&segment.start) {
+            keep(*segment);
+        }
+        return;
+    }
+
+    let parameter = |point: &Point2<f32>| {
+        (point - segment.start).dot(&direction) / length_squared
+    };
+    let mut cuts = vec![0.0, 1.0];
+    for edge in polygon.edges() {
+        match intersect_segments(segment, &edge) {
+            SegmentIntersection::None => {}
+            SegmentIntersection::Point(point) => cuts.push(parameter(&point)),
+            SegmentIntersection::Overlap(overlap) => {
+                cuts.push(parameter(&overlap.start));
+                cuts.push(parameter(&overlap.end));
+            }
+        }
+    }
+    cuts.retain(|t| (0.0..=1.0).contains(t));
+    cuts.sort_by(f32::total_cmp);
+    cuts.dedup();
+
+    let point_at = |t: f32| {
+        if t == 0.0 {
+            segment.start
+        } else if t == 1.0 {
+            segment.end
+        } else {
+            segment.start + direction * t
+        }
+    };
+    let mut inside: Option<f32> = None;
+    for pair in cuts.windows(2) {
+        let middle = point_at((pair[0] + pair[1]) * 0.5);
+        match (inside, polygon.contains_point(&middle)) {
+            (None, true) => inside = Some(pair[0]),
+            (Some(start), false) => {
+                keep(Segment::new(point_at(start), point_at(pair[0])));
+                inside = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = inside {
+        keep(Segment::new(point_at(start), segment.end));
+    }
+}
+
+/// Clip a polygon's vertices to a half plane with the Sutherland-Hodgman
+/// algorithm.
+///
+/// `inside` is a signed distance, or anything proportional to one, which
+/// isn't negative in the half plane being kept. The result has the kept
+/// vertices in order, with a new vertex wherever an edge crosses the
+/// boundary, and is empty when nothing is kept. Vertices exactly on the
+/// boundary are kept without adding a crossing next to them.
+pub(crate) fn clip_to_half_plane(
+    vertices: &[Point2<f32>],
+    inside: impl Fn(&Point2<f32>) -> f32,
+) -> Vec<Point2<f32>> {
+    let mut clipped = Vec::with_capacity(vertices.len() + 1);
+    for (i, current) in vertices.iter().enumerate() {
+        let previous = vertices[(i + vertices.len() - 1) % vertices.len()];
+        let (a, b) = (inside(&previous), inside(current));
+        if (a < 0.0 && b > 0.0) || (a > 0.0 && b < 0.0) {
+            clipped.push(previous + (current - previous) * (a / (a - b)));
+        }
+        if b >= 0.0 {
+            clipped.push(*current);
+        }
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            clip_segments_to_polygon, clip_segments_to_polygon_indexed,
+            clip_to_half_plane,
+        },
+        crate::{
+            line::{DistanceToPoint, Segment},
+            polygon::Polygon,
+            test_support::{polygon, rectangle, segment},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn pieces_should_be_attributed_to_their_source() {
+        let strokes = [
+            (Segment::new(point![-2.0, 1.0], point![6.0, 1.0]), 'a'),
+            (Segment::new(point![6.0, 6.0], point![9.0, 9.0]), 'b'),
+            (Segment::new(point![1.0, 2.0], point![3.0, 3.0]), 'c'),
+        ];
+        let segments: Vec<Segment> = strokes.iter().map(|(s, _)| *s).collect();
+
+        let pieces = clip_segments_to_polygon_indexed(
+            &segments,
+            &rectangle(point![0.0, 0.0], point![4.0, 4.0]),
+        );
+
+        let tags: Vec<char> =
+            pieces.iter().map(|&(i, _)| strokes[i].1).collect();
+        assert_eq!(tags, ['a', 'c']);
+        assert_eq!(
+            pieces[0].1,
+            Segment::new(point![0.0, 1.0], point![4.0, 1.0])
+        );
+        assert_eq!(pieces[1].1, segments[2]);
+    }
+
+    #[test]
+    pub fn half_plane_clipping_should_cut_across_the_polygon() {
+        let vertices = rectangle(point![0.0, 0.0], point![4.0, 4.0]).vertices;
+
+        let left = clip_to_half_plane(&vertices, |p| 1.0 - p.x);
+        let touching = clip_to_half_plane(&vertices, |p| p.x);
+        let outside = clip_to_half_plane(&vertices, |p| -1.0 - p.x);
+
+        assert_relative_eq!(Polygon::new(left.clone()).area(), 4.0);
+        assert_eq!(left.len(), 4);
+        assert_eq!(touching, vertices);
+        assert!(outside.is_empty());
+    }
+
+    #[test]
+    pub fn segments_leaving_a_concave_polygon_should_split() {
+        let comb = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![5.0, 0.0],
+            point![5.0, 3.0],
+            point![4.0, 3.0],
+            point![4.0, 1.0],
+            point![3.0, 1.0],
+            point![3.0, 3.0],
+            point![2.0, 3.0],
+            point![2.0, 1.0],
+            point![1.0, 1.0],
+            point![1.0, 3.0],
+            point![0.0, 3.0],
+        ]);
+        let stroke = Segment::new(point![6.0, 2.0], point![-1.0, 2.0]);
+
+        let pieces = clip_segments_to_polygon(&[stroke], &comb);
+
+        assert_eq!(
+            pieces,
+            [
+                Segment::new(point![5.0, 2.0], point![4.0, 2.0]),
+                Segment::new(point![3.0, 2.0], point![2.0, 2.0]),
+                Segment::new(point![1.0, 2.0], point![0.0, 2.0]),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn segments_through_vertices_should_not_toggle_twice() {
+        let diamond = Polygon::new(vec![
+            point![2.0, 0.0],
+            point![4.0, 2.0],
+            point![2.0, 4.0],
+            point![0.0, 2.0],
+        ]);
+        let diagonal = Segment::new(point![-1.0, 2.0], point![5.0, 2.0]);
+
+        let pieces = clip_segments_to_polygon(&[diagonal], &diamond);
+
+        assert_eq!(pieces, [Segment::new(point![0.0, 2.0], point![4.0, 2.0])]);
+    }
+
+    proptest! {
+        #[test]
+        fn pieces_should_lie_inside_on_their_source(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            segments in prop::collection::vec(segment(-12.0..=12.0, 0.1), 1..8),
+        ) {
+            let pieces = clip_segments_to_polygon_indexed(&segments, &polygon);
+
+            let mut previous = 0;
+            for (index, piece) in pieces {
+                let source = segments[index];
+                prop_assert!(index >= previous);
+                previous = index;
+                assert_relative_eq!(
+                    source.distance_to_point(&piece.start),
+                    0.0,
+                    epsilon = 1e-4
+                );
+                assert_relative_eq!(
+                    source.distance_to_point(&piece.end),
+                    0.0,
+                    epsilon = 1e-4
+                );
+                let middle = nalgebra::center(&piece.start, &piece.end);
+                prop_assert!(polygon.contains_point(&middle));
+                prop_assert!(
+                    (piece.end - piece.start).dot(&(source.end - source.start))
+                        >= 0.0
+                );
+            }
+        }
+    }
+}
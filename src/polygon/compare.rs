@@ -0,0 +1,236 @@
+use crate::{
+    polygon::{covered_area, Polygon},
+    raster::coverage_grid,
+    shape::BoundingBox,
+};
+
+/// The intersection over union of two polygons: the area they share
+/// divided by the area either of them covers.
+///
+/// This scores how well one shape matches another, from 1 for identical
+/// shapes to 0 for shapes which don't overlap at all. The areas are exact,
+/// up to rounding, since the union comes from [`covered_area`] and the
+/// shared area is what's left when the union is taken out of the sum of the
+/// two areas. Returns zero when neither polygon has any area.
+///
+/// The polygons should be simple. They can be wound either way. See
+/// [`iou_approx`] for a faster estimate.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{iou, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = |x: f32| {
+///         Polygon::new(vec![
+///             point![x, 0.0],
+///             point![x + 2.0, 0.0],
+///             point![x + 2.0, 2.0],
+///             point![x, 2.0],
+///         ])
+///     };
+///
+///     // They share a 1x2 rectangle and cover 3x2 together.
+///     assert_relative_eq!(iou(&square(0.0), &square(1.0)), 1.0 / 3.0);
+///
+pub fn iou(a: &Polygon, b: &Polygon) -> f32 {
+    let (shared, union) = shared_and_union_areas(a, b);
+    if union <= 0.0 {
+        return 0.0;
+    }
+    (shared / union).clamp(0.0, 1.0)
+}
+
+/// The area covered by exactly one of two polygons.
+///
+/// This is the union's area minus the area they share, so it's zero for
+/// identical shapes and the sum of their areas for shapes which don't
+/// overlap. Unlike [`iou`] it's measured in units of area, so it says how
+/// much of a shape is wrong rather than what fraction is right.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{symmetric_difference_area, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = |x: f32| {
+///         Polygon::new(vec![
+///             point![x, 0.0],
+///             point![x + 2.0, 0.0],
+///             point![x + 2.0, 2.0],
+///             point![x, 2.0],
+///         ])
+///     };
+///
+///     let area = symmetric_difference_area(&square(0.0), &square(1.0));
+///
+///     assert_relative_eq!(area, 4.0, epsilon = 1e-4);
+///
+pub fn symmetric_difference_area(a: &Polygon, b: &Polygon) -> f32 {
+    let (shared, union) = shared_and_union_areas(a, b);
+    (union - shared).max(0.0)
+}
+
+/// Estimate the intersection over union of two polygons by rasterizing
+/// them onto a shared grid.
+///
+/// The box around both polygons is divided into `resolution.0` columns and
+/// `resolution.1` rows, and the [`coverage`](crate::raster::coverage) of
+/// each cell is found for each polygon. A cell contributes the smaller of
+/// its two coverages to the shared area and the larger to the union. Cells
+/// which are entirely inside or outside both polygons are counted exactly,
+/// so the only error is in cells crossed by both boundaries, and the
+/// estimate converges on [`iou`] as the resolution grows. Returns zero
+/// when neither polygon covers any of the grid.
+///
+/// This is cheaper than [`iou`] for polygons with many vertices, since the
+/// boundaries never need to be crossed with each other.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{iou, iou_approx, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let triangle = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![0.0, 4.0],
+///     ]);
+///     let flipped = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///     ]);
+///
+///     let exact = iou(&triangle, &flipped);
+///     let approx = iou_approx(&triangle, &flipped, (64, 64));
+///
+///     assert_relative_eq!(exact, 1.0 / 3.0, epsilon = 1e-4);
+///     assert_relative_eq!(approx, exact, epsilon = 0.02);
+///
+pub fn iou_approx(a: &Polygon, b: &Polygon, resolution: (usize, usize)) -> f32 {
+    let bounds = a.bounding_box().merged(&b.bounding_box());
+    if bounds.width() < 0.0 {
+        // Neither polygon has any vertices.
+        return 0.0;
+    }
+    let grid_a = coverage_grid(a, &bounds, resolution);
+    let grid_b = coverage_grid(b, &bounds, resolution);
+    let cells = || grid_a.values.iter().zip(&grid_b.values);
+    let shared: f32 = cells().map(|(a, b)| a.min(*b)).sum();
+    let union: f32 = cells().map(|(a, b)| a.max(*b)).sum();
+    if union <= 0.0 {
+        return 0.0;
+    }
+    (shared / union).clamp(0.0, 1.0)
+}
+
+/// The area two polygons share and the area they cover together.
+fn shared_and_union_areas(a: &Polygon, b: &Polygon) -> (f32, f32) {
+    let union = covered_area(&[a.clone(), b.clone()]);
+    let shared =
+        (a.area() + b.area() - union).clamp(0.0, a.area().min(b.area()));
+    (shared, union)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{iou, iou_approx, symmetric_difference_area},
+        crate::{
+            polygon::Polygon,
+            test_support::{polygon, rectangle},
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn identical_polygons_should_match_exactly() {
+        let square = rectangle(point![1.0, 1.0], point![5.0, 5.0]);
+        let mut reversed = square.clone();
+        reversed.vertices.reverse();
+
+        assert_relative_eq!(iou(&square, &reversed), 1.0, epsilon = 1e-5);
+        assert_relative_eq!(
+            symmetric_difference_area(&square, &reversed),
+            0.0,
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(iou_approx(&square, &square, (8, 8)), 1.0);
+    }
+
+    #[test]
+    pub fn disjoint_polygons_should_not_match_at_all() {
+        let a = rectangle(point![0.0, 0.0], point![2.0, 2.0]);
+        let b = rectangle(point![5.0, 0.0], point![8.0, 1.0]);
+
+        assert_eq!(iou(&a, &b), 0.0);
+        assert_eq!(iou_approx(&a, &b, (16, 16)), 0.0);
+        assert_relative_eq!(symmetric_difference_area(&a, &b), 7.0);
+    }
+
+    #[test]
+    pub fn squares_shifted_by_half_their_width_should_share_a_third() {
+        // A 4x4 square moved 2 to the right shares a 2x4 rectangle and
+        // covers 6x4 together: 8 / 24 = 1/3, with 16 covered by only one.
+        let a = rectangle(point![0.0, 0.0], point![4.0, 4.0]);
+        let b = rectangle(point![2.0, 0.0], point![6.0, 4.0]);
+
+        assert_relative_eq!(iou(&a, &b), 1.0 / 3.0, epsilon = 1e-5);
+        assert_relative_eq!(
+            symmetric_difference_area(&a, &b),
+            16.0,
+            epsilon = 1e-4
+        );
+        // The boundaries line up with the grid, so the estimate is exact.
+        assert_relative_eq!(iou_approx(&a, &b, (6, 4)), 1.0 / 3.0);
+    }
+
+    #[test]
+    pub fn empty_polygons_should_score_zero() {
+        let empty = Polygon::new(vec![]);
+        let square = rectangle(point![0.0, 0.0], point![1.0, 1.0]);
+
+        assert_eq!(iou(&empty, &empty), 0.0);
+        assert_eq!(iou(&empty, &square), 0.0);
+        assert_eq!(iou_approx(&empty, &empty, (4, 4)), 0.0);
+        assert_eq!(iou_approx(&empty, &square, (4, 4)), 0.0);
+        assert_relative_eq!(symmetric_difference_area(&empty, &square), 1.0);
+    }
+
+    proptest! {
+        #[test]
+        fn estimates_should_approach_the_exact_iou(
+            a in polygon(-5.0..=5.0, 3..=8, true),
+            b in polygon(-5.0..=5.0, 3..=8, true),
+            shift in -3.0f32..=3.0,
+        ) {
+            let mut b = b;
+            for vertex in &mut b.vertices {
+                *vertex += vector![shift, 0.0];
+            }
+
+            let exact = iou(&a, &b);
+            let approx = iou_approx(&a, &b, (128, 128));
+
+            prop_assert!((0.0..=1.0).contains(&exact));
+            prop_assert!((exact - approx).abs() < 0.02);
+            prop_assert!((iou(&b, &a) - exact).abs() < 1e-4);
+            // The areas add up to the union plus the shared area.
+            let union = (a.area() + b.area()) / (1.0 + exact);
+            let difference = symmetric_difference_area(&a, &b);
+            prop_assert!((difference - union * (1.0 - exact)).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,614 @@
+use {
+    crate::polygon::Polygon,
+    alloc::vec::Vec,
+    core::cmp::Ordering,
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+/// Intersect two convex polygons in O(n + m) time.
+///
+/// This is O'Rourke's edge-advance algorithm: it walks around both
+/// boundaries at once, always advancing along whichever edge is "aiming" at
+/// the other polygon's current edge, and records the crossings along with
+/// the vertices which are inside the other polygon.
+///
+/// The polygons can be wound either way, and the result is a convex,
+/// counterclockwise polygon. When one polygon contains the other, the inner
+/// polygon is returned. Returns `None` when the intersection has no area,
+/// including when the polygons are disjoint, touch at a vertex, or share an
+/// edge without overlapping. Non-convex inputs produce meaningless results.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{intersect_convex, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///     ]);
+///     let diamond = Polygon::new(vec![
+///         point![2.0, 0.0],
+///         point![4.0, 2.0],
+///         point![2.0, 4.0],
+///         point![0.0, 2.0],
+///     ]);
+///
+///     let overlap = intersect_convex(&square, &diamond).unwrap();
+///
+///     assert_relative_eq!(overlap.area(), 2.0);
+///
+pub fn intersect_convex(a: &Polygon, b: &Polygon) -> Option<Polygon> {
+    let p = counterclockwise(a)?;
+    let q = counterclockwise(b)?;
+    let (n, m) = (p.len(), q.len());
+
+    let mut output: Vec<Point2<f64>> = vec![];
+    let mut inside = Inside::Unknown;
+    let (mut i, mut j) = (0, 0);
+    let (mut p_steps, mut q_steps) = (0, 0);
+    let mut first_crossing = true;
+    loop {
+        let (p0, p1) = (p[(i + n - 1) % n], p[i]);
+        let (q0, q1) = (q[(j + m - 1) % m], q[j]);
+        let (edge_p, edge_q) = (p1 - p0, q1 - q0);
+        let turn = sign(edge_p.perp(&edge_q));
+        // Which side of the other polygon's current edge each head is on.
+        // Positive is the inside.
+        let p_side = sign(orientation(&q0, &q1, &p1));
+        let q_side = sign(orientation(&p0, &p1, &q1));
+
+        match crossing(&p0, &p1, &q0, &q1) {
+            Crossing::Point(x) => {
+                if first_crossing {
+                    // Count steps from the first crossing so the walk makes
+                    // a full loop of the intersection.
+                    first_crossing = false;
+                    (p_steps, q_steps) = (0, 0);
+                }
+                output.push(x);
+                if p_side == Ordering::Greater {
+                    inside = Inside::P;
+                } else if q_side == Ordering::Greater {
+                    inside = Inside::Q;
+                }
+            }
+            Crossing::Overlap if edge_p.dot(&edge_q) < 0.0 => {
+                // The edges overlap but point in opposite directions, so the
+                // polygons are on opposite sides of the shared line.
+                return None;
+            }
+            Crossing::Overlap | Crossing::None => {}
+        }
+
+        let advance_p = match (turn, p_side, q_side) {
+            (Ordering::Equal, Ordering::Less, Ordering::Less) => {
+                // Parallel edges facing away from each other
+                return None;
+            }
+            (Ordering::Equal, Ordering::Equal, Ordering::Equal) => {
+                // Collinear edges: advance whichever is outside.
+                inside != Inside::P
+            }
+            (Ordering::Greater | Ordering::Equal, _, q_side) => {
+                q_side == Ordering::Greater
+            }
+            (Ordering::Less, p_side, _) => p_side != Ordering::Greater,
+        };
+        if advance_p {
+            if inside == Inside::P {
+                output.push(p1);
+            }
+            i = (i + 1) % n;
+            p_steps += 1;
+        } else {
+            if inside == Inside::Q {
+                output.push(q1);
+            }
+            j = (j + 1) % m;
+            q_steps += 1;
+        }
+
+        let finished = p_steps >= n && q_steps >= m;
+        if finished || p_steps >= 2 * n || q_steps >= 2 * m {
+            break;
+        }
+    }
+
+    if inside == Inside::Unknown {
+        // The boundaries never crossed, so either one polygon is inside the
+        // other or they're disjoint. Only the smaller one can be inside.
+        let (inner, outer) = if a.area() <= b.area() {
+            (&p, &q)
+        } else {
+            (&q, &p)
+        };
+        return if contains_closed(outer, &average(inner)) {
+            to_polygon(inner)
+        } else {
+            None
+        };
+    }
+    to_polygon(&output)
+}
+
+impl Polygon {
+    /// Find the vertex of a convex polygon which is furthest along a
+    /// direction in O(log n) time.
+    ///
+    /// The projections of a convex polygon's vertices onto a direction rise
+    /// to a single peak and fall again as you walk around the boundary, so
+    /// a binary search can climb to the top. Ties are resolved in favor of
+    /// the lowest index, and the polygon can be wound either way. Returns
+    /// `None` when the polygon has no vertices. The result is meaningless
+    /// for polygons which aren't convex, use
+    /// [`extreme_point`](crate::query::extreme_point) for those.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let hexagon = Polygon::new(vec![
+    ///         point![2.0, 0.0],
+    ///         point![1.0, 2.0],
+    ///         point![-1.0, 2.0],
+    ///         point![-2.0, 0.0],
+    ///         point![-1.0, -2.0],
+    ///         point![1.0, -2.0],
+    ///     ]);
+    ///     let up = Unit::new_normalize(vector![0.0, 1.0]);
+    ///     let left = Unit::new_normalize(vector![-1.0, 0.1]);
+    ///
+    ///     // The top edge is level, so the first of its vertices wins.
+    ///     assert_eq!(hexagon.extreme_vertex_convex(&up), Some(1));
+    ///     assert_eq!(hexagon.extreme_vertex_convex(&left), Some(3));
+    ///
+    pub fn extreme_vertex_convex(
+        &self,
+        direction: &Unit<Vector2<f32>>,
+    ) -> Option<usize> {
+        let vertices = &self.vertices;
+        let n = vertices.len();
+        if n == 0 {
+            return None;
+        }
+        let height = |i: usize| vertices[i % n].coords.dot(direction);
+        // Whether the edge leaving vertex `i` climbs.
+        let rises = |i: usize| height(i + 1) > height(i);
+
+        // Binary search on the edges in [a, b), keeping vertex `a` on the
+        // rising side of the peak whenever its edge rises.
+        let mut top = 0;
+        let (mut a, mut b) = (0, n);
+        let mut a_rises = rises(0);
+        if a_rises || height(n - 1) > height(0) {
+            while b > a + 1 {
+                let c = (a + b) / 2;
+                let c_rises = rises(c);
+                if !c_rises && height(c - 1) <= height(c) {
+                    a = c;
+                    break;
+                }
+                let go_left = if a_rises {
+                    !c_rises || height(a) > height(c)
+                } else {
+                    !c_rises && height(a) >= height(c)
+                };
+                if go_left {
+                    b = c;
+                } else {
+                    a = c;
+                    a_rises = c_rises;
+                }
+            }
+            top = a;
+        }
+
+        // Finish with a short local climb, which fixes up plateaus and
+        // rounding, and then walk back over ties to the lowest index.
+        let (previous, next) = (|i| (i + n - 1) % n, |i| (i + 1) % n);
+        for _ in 0..n {
+            if height(previous(top)) > height(top) {
+                top = previous(top);
+            } else if height(next(top)) > height(top) {
+                top = next(top);
+            } else {
+                break;
+            }
+        }
+        let peak = height(top);
+        let mut lowest = top;
+        for offset in [n - 1, 1] {
+            let mut i = (top + offset) % n;
+            while i != top && height(i) == peak {
+                lowest = lowest.min(i);
+                i = (i + offset) % n;
+            }
+        }
+        Some(lowest)
+    }
+}
+
+/// Which polygon's boundary is currently inside the other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Inside {
+    P,
+    Q,
+    Unknown,
+}
+
+/// How two edges meet.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Crossing {
+    /// The edges meet at a single point, possibly an endpoint.
+    Point(Point2<f64>),
+
+    /// The edges are collinear and share at least one point.
+    Overlap,
+
+    None,
+}
+
+fn crossing(
+    p0: &Point2<f64>,
+    p1: &Point2<f64>,
+    q0: &Point2<f64>,
+    q1: &Point2<f64>,
+) -> Crossing {
+    let (edge_p, edge_q) = (p1 - p0, q1 - q0);
+    let denominator = edge_p.perp(&edge_q);
+    let offset = q0 - p0;
+    if denominator == 0.0 {
+        if orientation(p0, p1, q0) != 0.0 {
+            return Crossing::None;
+        }
+        // Project the second edge onto the first.
+        let length = edge_p.norm_squared();
+        let s0 = offset.dot(&edge_p) / length;
+        let s1 = (q1 - p0).dot(&edge_p) / length;
+        return if s0.min(s1) <= 1.0 && s0.max(s1) >= 0.0 {
+            Crossing::Overlap
+        } else {
+            Crossing::None
+        };
+    }
+    let s = offset.perp(&edge_q) / denominator;
+    let t = offset.perp(&edge_p) / denominator;
+    if (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t) {
+        Crossing::Point(p0 + edge_p * s)
+    } else {
+        Crossing::None
+    }
+}
+
+/// Twice the signed area of the triangle a, b, c.
+fn orientation(a: &Point2<f64>, b: &Point2<f64>, c: &Point2<f64>) -> f64 {
+    (b - a).perp(&(c - a))
+}
+
+fn sign(value: f64) -> Ordering {
+    value.partial_cmp(&0.0).unwrap_or(Ordering::Equal)
+}
+
+/// The polygon's vertices in counterclockwise order, at double precision.
+fn counterclockwise(polygon: &Polygon) -> Option<Vec<Point2<f64>>> {
+    if polygon.vertices.len() < 3 {
+        return None;
+    }
+    let mut vertices: Vec<Point2<f64>> =
+        polygon.vertices.iter().map(|v| v.cast()).collect();
+    if polygon.signed_area() < 0.0 {
+        vertices.reverse();
+    }
+    Some(vertices)
+}
+
+/// Returns true when the point is inside the convex, counterclockwise
+/// polygon or on its boundary.
+fn contains_closed(polygon: &[Point2<f64>], point: &Point2<f64>) -> bool {
+    let n = polygon.len();
+    (0..n)
+        .all(|i| orientation(&polygon[i], &polygon[(i + 1) % n], point) >= 0.0)
+}
+
+fn average(points: &[Point2<f64>]) -> Point2<f64> {
+    let sum: Vector2<f64> = points.iter().map(|p| p.coords).sum();
+    Point2::from(sum / points.len() as f64)
+}
+
+/// Convert the walk's output to a polygon, dropping repeated points. Returns
+/// `None` when what's left has no area.
+fn to_polygon(points: &[Point2<f64>]) -> Option<Polygon> {
+    let mut vertices: Vec<Point2<f32>> = Vec::with_capacity(points.len());
+    for point in points {
+        let point: Point2<f32> = point.cast();
+        if vertices.last() != Some(&point) {
+            vertices.push(point);
+        }
+    }
+    while vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+    let polygon = Polygon::new(vertices);
+    (polygon.vertices.len() >= 3 && polygon.signed_area() > 0.0)
+        .then_some(polygon)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            hull::convex_hull,
+            polygon::{intersect_convex, Polygon},
+            query::extreme_point,
+            shape::Circle,
+            test_support::{point2, polygon, rectangle, unit_vector},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Point2, Unit, Vector2},
+        proptest::prelude::*,
+    };
+
+    /// Clip a polygon against each edge of a counterclockwise convex polygon
+    /// with the Sutherland-Hodgman algorithm, as a simple reference.
+    fn sutherland_hodgman(
+        subject: &Polygon,
+        clip: &Polygon,
+    ) -> Vec<Point2<f32>> {
+        let mut output = subject.vertices.clone();
+        for edge in clip.edges() {
+            let side = |p: &Point2<f32>| {
+                (edge.end - edge.start).perp(&(p - edge.start))
+            };
+            let input = core::mem::take(&mut output);
+            for (k, current) in input.iter().enumerate() {
+                let previous = input[(k + input.len() - 1) % input.len()];
+                let (a, b) = (side(&previous), side(current));
+                if (a >= 0.0) != (b >= 0.0) {
+                    output
+                        .push(previous + (current - previous) * (a / (a - b)));
+                }
+                if b >= 0.0 {
+                    output.push(*current);
+                }
+            }
+            if output.is_empty() {
+                break;
+            }
+        }
+        output
+    }
+
+    #[test]
+    pub fn overlapping_squares_should_intersect_in_a_square() {
+        let a = rectangle(point![0.0, 0.0], point![2.0, 2.0]);
+        let b = rectangle(point![1.0, 1.0], point![3.0, 3.0]);
+
+        let overlap = intersect_convex(&a, &b).unwrap();
+
+        assert_relative_eq!(overlap.area(), 1.0);
+        assert!(overlap.signed_area() > 0.0);
+        assert_eq!(overlap.vertices.len(), 4);
+    }
+
+    #[test]
+    pub fn contained_polygons_should_be_returned() {
+        let outer = rectangle(point![0.0, 0.0], point![10.0, 10.0]);
+        let mut inner = rectangle(point![2.0, 3.0], point![3.0, 4.0]);
+        inner.vertices.reverse();
+
+        let expected = rectangle(point![2.0, 3.0], point![3.0, 4.0]).vertices;
+        let mut overlap = intersect_convex(&outer, &inner).unwrap();
+        overlap
+            .vertices
+            .sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        let mut sorted = expected.clone();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        assert_eq!(overlap.vertices, sorted);
+
+        let overlap = intersect_convex(&inner, &outer).unwrap();
+        assert_relative_eq!(overlap.area(), 1.0);
+    }
+
+    #[test]
+    pub fn disjoint_and_touching_polygons_should_not_intersect() {
+        let a = rectangle(point![0.0, 0.0], point![1.0, 1.0]);
+
+        assert_eq!(
+            intersect_convex(
+                &a,
+                &rectangle(point![5.0, 0.0], point![6.0, 1.0])
+            ),
+            None
+        );
+        // Sharing a corner
+        assert_eq!(
+            intersect_convex(
+                &a,
+                &rectangle(point![1.0, 1.0], point![2.0, 2.0])
+            ),
+            None
+        );
+        // Sharing an edge exactly, like neighboring tiles
+        assert_eq!(
+            intersect_convex(
+                &a,
+                &rectangle(point![1.0, 0.0], point![2.0, 1.0])
+            ),
+            None
+        );
+        // Sharing part of an edge
+        assert_eq!(
+            intersect_convex(
+                &a,
+                &rectangle(point![1.0, 0.5], point![2.0, 1.5])
+            ),
+            None
+        );
+    }
+
+    #[test]
+    pub fn polygons_sharing_an_edge_on_the_same_side_should_overlap() {
+        let a = rectangle(point![0.0, 0.0], point![2.0, 2.0]);
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![1.0, 3.0],
+        ]);
+
+        let overlap = intersect_convex(&a, &triangle).unwrap();
+        let expected = sutherland_hodgman(&triangle, &a);
+
+        assert_relative_eq!(
+            overlap.area(),
+            Polygon::new(expected).area(),
+            max_relative = 1e-5
+        );
+    }
+
+    #[test]
+    pub fn identical_polygons_should_intersect_in_themselves() {
+        let a = rectangle(point![0.0, 0.0], point![2.0, 2.0]);
+
+        let overlap = intersect_convex(&a, &a).unwrap();
+
+        assert_relative_eq!(overlap.area(), 4.0);
+    }
+
+    #[test]
+    pub fn degenerate_polygons_should_not_intersect() {
+        let a = rectangle(point![0.0, 0.0], point![2.0, 2.0]);
+        let line = Polygon::new(vec![point![0.0, 0.0], point![1.0, 1.0]]);
+
+        assert_eq!(intersect_convex(&a, &line), None);
+    }
+
+    fn assert_extreme(polygon: &Polygon, direction: &Unit<Vector2<f32>>) {
+        let height = |i: usize| polygon.vertices[i].coords.dot(direction);
+        let expected = extreme_point(&polygon.vertices, direction).unwrap();
+
+        let found = polygon.extreme_vertex_convex(direction).unwrap();
+
+        assert_relative_eq!(height(found), height(expected), epsilon = 1e-4);
+    }
+
+    #[test]
+    pub fn extreme_vertices_should_prefer_the_lowest_index() {
+        let square = Polygon::new(vec![
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+        ]);
+        let up = Unit::new_normalize(vector![0.0, 1.0]);
+        let down = Unit::new_normalize(vector![0.0, -1.0]);
+        let right = Unit::new_normalize(vector![1.0, 0.0]);
+
+        assert_eq!(square.extreme_vertex_convex(&up), Some(0));
+        assert_eq!(square.extreme_vertex_convex(&down), Some(2));
+        assert_eq!(square.extreme_vertex_convex(&right), Some(0));
+        assert_eq!(Polygon::new(vec![]).extreme_vertex_convex(&up), None);
+    }
+
+    #[test]
+    pub fn extreme_vertices_should_match_a_scan_on_large_polygons() {
+        let circle = Circle::new(point![3.0, -2.0], 10.0);
+        for n in [3, 4, 5, 100, 1001, 4096] {
+            let mut polygon = circle.to_polygon_n(n);
+            for i in 0..64 {
+                let angle = i as f32 * 0.1;
+                let direction =
+                    Unit::new_normalize(vector![angle.cos(), angle.sin()]);
+                assert_extreme(&polygon, &direction);
+                polygon.vertices.reverse();
+                assert_extreme(&polygon, &direction);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn extreme_vertices_should_match_a_scan_on_hulls(
+            points in prop::collection::vec(point2(-10.0..=10.0), 3..100),
+            direction in unit_vector(),
+        ) {
+            let hull = convex_hull(&points);
+            prop_assume!(hull.is_some());
+
+            assert_extreme(&hull.unwrap(), &direction);
+        }
+
+        #[test]
+        fn extreme_vertices_should_match_a_scan_on_convex_polygons(
+            polygon in polygon(-10.0..=10.0, 3..=8, true),
+            direction in unit_vector(),
+        ) {
+            assert_extreme(&polygon, &direction);
+        }
+
+        #[test]
+        fn intersections_should_match_sutherland_hodgman(
+            a in polygon(-10.0..=10.0, 3..=12, true),
+            b in polygon(-10.0..=10.0, 3..=12, true),
+            dx in -12.0f32..12.0,
+            dy in -12.0f32..12.0,
+            scale in 0.1f32..1.5,
+        ) {
+            let offset = vector![dx, dy];
+            let b = Polygon::new(
+                b.vertices.iter().map(|v| v * scale + offset).collect(),
+            );
+            let expected = Polygon::new(sutherland_hodgman(&a, &b)).area();
+
+            match intersect_convex(&a, &b) {
+                Some(overlap) => {
+                    prop_assert!(overlap.signed_area() > 0.0);
+                    assert_relative_eq!(
+                        overlap.area(),
+                        expected,
+                        max_relative = 1e-3,
+                        epsilon = 1e-3
+                    );
+                }
+                None => prop_assert!(expected < 1e-3, "{}", expected),
+            }
+        }
+
+        #[test]
+        fn lattice_intersections_should_match_sutherland_hodgman(
+            a in proptest::collection::vec((0..5i32, 0..5i32), 3..8),
+            b in proptest::collection::vec((0..5i32, 0..5i32), 3..8),
+        ) {
+            // Hulls of points on a small lattice share edges, vertices, and
+            // collinear overlaps far more often than random polygons.
+            let hull = |points: &[(i32, i32)]| {
+                let points: Vec<Point2<f32>> = points
+                    .iter()
+                    .map(|&(x, y)| point![x as f32, y as f32])
+                    .collect();
+                convex_hull(&points)
+            };
+            let (Some(a), Some(b)) = (hull(&a), hull(&b)) else {
+                return Ok(());
+            };
+            let expected = Polygon::new(sutherland_hodgman(&a, &b)).area();
+
+            match intersect_convex(&a, &b) {
+                Some(overlap) => {
+                    prop_assert!(overlap.signed_area() > 0.0);
+                    assert_relative_eq!(overlap.area(), expected, epsilon = 1e-4);
+                }
+                None => prop_assert!(expected < 1e-4, "{}", expected),
+            }
+        }
+    }
+}
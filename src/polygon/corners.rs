@@ -0,0 +1,416 @@
+use {
+    crate::{
+        operations::{perp_unit2d, signed_angle},
+        polygon::Polygon,
+        shape::Arc,
+        Error,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+impl Polygon {
+    /// Cut every corner off with a straight edge, joining the points
+    /// `distance` back along each of the corner's edges.
+    ///
+    /// Distances are clamped to half the length of the shorter edge at the
+    /// corner, so the cuts at either end of an edge never cross and the
+    /// result stays simple when the polygon is convex. Cuts which meet in
+    /// the middle of an edge share a vertex. Negative and NaN distances
+    /// leave the corner alone. See [`Polygon::chamfer_vertices`] for the
+    /// details.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![3.0, 0.0],
+    ///         point![3.0, 3.0],
+    ///         point![0.0, 3.0],
+    ///     ]);
+    ///
+    ///     let octagon = square.chamfer(1.0);
+    ///
+    ///     assert_eq!(octagon.vertices.len(), 8);
+    ///     assert_eq!(octagon.vertices[0], point![0.0, 1.0]);
+    ///     assert_relative_eq!(octagon.area(), 9.0 - 2.0);
+    ///
+    pub fn chamfer(&self, distance: f32) -> Polygon {
+        self.cut_corners(|_, corner, out| {
+            out.extend(corner.cut(distance));
+        })
+    }
+
+    /// Cut each corner off with a straight edge, using a distance for every
+    /// vertex.
+    ///
+    /// Corner `i` is replaced by the points `distances[i]` back along its
+    /// incoming and outgoing edges, in that order, so the result winds the
+    /// same way as the polygon. Distances are clamped to half the length of
+    /// the shorter edge at the corner, and corners with a distance of zero
+    /// or less keep their vertex. Repeated vertices are merged first and
+    /// use the first one's distance.
+    ///
+    /// Cutting a convex corner removes a triangle from inside the polygon
+    /// and cutting a reflex corner adds one outside it, so a concave
+    /// polygon stays simple as long as no other edge passes within the cut
+    /// triangles. Polygons with fewer than three distinct vertices are
+    /// returned without their repeated vertices.
+    ///
+    /// Returns [`Error::MismatchedVertexCounts`] when there isn't exactly
+    /// one distance for each vertex.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let triangle = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     let cut = triangle.chamfer_vertices(&[1.0, 0.0, 0.0]).unwrap();
+    ///
+    ///     assert_eq!(
+    ///         cut.vertices,
+    ///         vec![
+    ///             point![0.0, 1.0],
+    ///             point![1.0, 0.0],
+    ///             point![4.0, 0.0],
+    ///             point![0.0, 4.0],
+    ///         ]
+    ///     );
+    ///     assert!(triangle.chamfer_vertices(&[1.0]).is_err());
+    ///
+    pub fn chamfer_vertices(
+        &self,
+        distances: &[f32],
+    ) -> Result<Polygon, Error> {
+        if distances.len() != self.vertices.len() {
+            return Err(Error::MismatchedVertexCounts);
+        }
+        Ok(self.cut_corners(|index, corner, out| {
+            out.extend(corner.cut(distances[index]));
+        }))
+    }
+
+    /// Round every corner with a circular arc of a radius, approximated by
+    /// chords which stay within `max_error` of the arc.
+    ///
+    /// Each arc is tangent to both edges at the corner, so it bulges into
+    /// convex corners and out of reflex ones. When the arc would reach
+    /// past half of the shorter edge at a corner, that corner gets the
+    /// largest arc which fits instead, so neighboring arcs never overlap.
+    /// The arcs' ends are exactly on the edges, and arcs which meet in the
+    /// middle of an edge share a vertex. Corners where the boundary goes
+    /// straight on or doubles back, and every corner when the radius isn't
+    /// positive, keep their vertex. Repeated vertices are merged first, and
+    /// the result winds the same way as the polygon.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///         core::f32::consts::PI,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 4.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     let rounded = square.fillet_all(1.0, 1e-4);
+    ///
+    ///     // Each corner loses a square with a quarter circle taken out.
+    ///     let expected = 16.0 - (4.0 - PI);
+    ///     assert_relative_eq!(rounded.area(), expected, max_relative = 1e-3);
+    ///
+    pub fn fillet_all(&self, radius: f32, max_error: f32) -> Polygon {
+        self.cut_corners(|_, corner, out| corner.fillet(radius, max_error, out))
+    }
+
+    /// Replace each distinct vertex with the points chosen for its corner.
+    fn cut_corners(
+        &self,
+        mut cut: impl FnMut(usize, &Corner, &mut Vec<Point2<f32>>),
+    ) -> Polygon {
+        let mut distinct: Vec<(usize, Point2<f32>)> = Vec::new();
+        for (index, vertex) in self.vertices.iter().enumerate() {
+            if distinct.last().map(|(_, last)| last) != Some(vertex) {
+                distinct.push((index, *vertex));
+            }
+        }
+        while distinct.len() > 1
+            && distinct.first().map(|(_, p)| p)
+                == distinct.last().map(|(_, p)| p)
+        {
+            distinct.pop();
+        }
+        let n = distinct.len();
+        if n < 3 {
+            return Polygon::new(
+                distinct.into_iter().map(|(_, p)| p).collect(),
+            );
+        }
+
+        let mut vertices: Vec<Point2<f32>> = Vec::new();
+        for i in 0..n {
+            let corner = Corner::new(
+                distinct[(i + n - 1) % n].1,
+                distinct[i].1,
+                distinct[(i + 1) % n].1,
+            );
+            cut(distinct[i].0, &corner, &mut vertices);
+        }
+        // Cuts which meet in the middle of an edge give the same point.
+        vertices.dedup();
+        while vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Polygon::new(vertices)
+    }
+}
+
+/// A vertex along with its neighbors.
+struct Corner {
+    before: Point2<f32>,
+    vertex: Point2<f32>,
+    after: Point2<f32>,
+    incoming: Unit<Vector2<f32>>,
+    outgoing: Unit<Vector2<f32>>,
+    before_length: f32,
+    after_length: f32,
+
+    /// The furthest a cut can reach along either edge, half the length of
+    /// the shorter one.
+    reach: f32,
+}
+
+impl Corner {
+    fn new(
+        before: Point2<f32>,
+        vertex: Point2<f32>,
+        after: Point2<f32>,
+    ) -> Self {
+        let (incoming, before_length) = Unit::new_and_get(vertex - before);
+        let (outgoing, after_length) = Unit::new_and_get(after - vertex);
+        Self {
+            before,
+            vertex,
+            after,
+            incoming,
+            outgoing,
+            before_length,
+            after_length,
+            reach: before_length.min(after_length) / 2.0,
+        }
+    }
+
+    /// The points a distance back along the incoming edge and forward along
+    /// the outgoing edge, or just the vertex when the distance isn't
+    /// positive. Points which reach halfway along an edge use its midpoint
+    /// exactly, so they match the cut from the edge's other end.
+    fn cut(&self, distance: f32) -> impl Iterator<Item = Point2<f32>> {
+        let distance = distance.max(0.0).min(self.reach);
+        let points = if distance > 0.0 {
+            let start = if distance * 2.0 >= self.before_length {
+                nalgebra::center(&self.before, &self.vertex)
+            } else {
+                self.vertex - self.incoming.into_inner() * distance
+            };
+            let end = if distance * 2.0 >= self.after_length {
+                nalgebra::center(&self.vertex, &self.after)
+            } else {
+                self.vertex + self.outgoing.into_inner() * distance
+            };
+            [Some(start), Some(end)]
+        } else {
+            [Some(self.vertex), None]
+        };
+        points.into_iter().flatten()
+    }
+
+    /// Push the vertices of an arc tangent to both edges.
+    fn fillet(&self, radius: f32, max_error: f32, out: &mut Vec<Point2<f32>>) {
+        let cross = self.incoming.perp(&self.outgoing);
+        let dot = self.incoming.dot(&self.outgoing);
+        // The tangent points are `radius * tan(turn / 2)` from the vertex.
+        let tangent_length = radius * cross.abs() / (1.0 + dot);
+        let turns = radius > 0.0 && cross != 0.0 && tangent_length > 0.0;
+        if !turns {
+            out.push(self.vertex);
+            return;
+        }
+        let distance = tangent_length.min(self.reach);
+        let radius = radius * distance / tangent_length;
+        let mut tangent_points = self.cut(distance);
+        let (Some(start), Some(end)) =
+            (tangent_points.next(), tangent_points.next())
+        else {
+            out.push(self.vertex);
+            return;
+        };
+
+        // The center is on the side the boundary turns towards.
+        let normal = perp_unit2d(&self.incoming).into_inner() * cross.signum();
+        let center = start + normal * radius;
+        let offset = start - center;
+        let arc = Arc::new(
+            center,
+            radius,
+            crate::math::atan2(offset.y, offset.x),
+            signed_angle(&self.incoming, &self.outgoing),
+        );
+        let polyline = arc.to_polyline(max_error);
+        let inner = &polyline.vertices[1..polyline.vertices.len() - 1];
+        out.push(start);
+        out.extend_from_slice(inner);
+        out.push(end);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            polygon::Polygon,
+            test_support::{polygon, rectangle},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::PI,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn chamfered_squares_should_be_octagons() {
+        let octagon =
+            rectangle(point![0.0, 0.0], point![4.0, 4.0]).chamfer(1.0);
+
+        assert_eq!(octagon.vertices.len(), 8);
+        let lengths: Vec<f32> =
+            octagon.edges().map(|edge| edge.length()).collect();
+        for pair in lengths.chunks(2) {
+            assert_relative_eq!(pair[0], 2f32.sqrt());
+            assert_relative_eq!(pair[1], 2.0);
+        }
+        assert!(octagon.is_simple());
+        assert!(octagon.signed_area() > 0.0);
+    }
+
+    #[test]
+    pub fn long_chamfers_should_meet_in_the_middle() {
+        let diamond =
+            rectangle(point![0.0, 0.0], point![2.0, 2.0]).chamfer(5.0);
+
+        assert_eq!(
+            diamond.vertices,
+            vec![
+                point![0.0, 1.0],
+                point![1.0, 0.0],
+                point![2.0, 1.0],
+                point![1.0, 2.0],
+            ]
+        );
+        assert_eq!(
+            rectangle(point![0.0, 0.0], point![2.0, 2.0]).chamfer(-1.0),
+            rectangle(point![0.0, 0.0], point![2.0, 2.0])
+        );
+        assert_eq!(
+            rectangle(point![0.0, 0.0], point![2.0, 2.0]).chamfer(f32::NAN),
+            rectangle(point![0.0, 0.0], point![2.0, 2.0])
+        );
+    }
+
+    #[test]
+    pub fn concave_polygons_should_stay_simple() {
+        // An "L" whose inside corner is cut outwards.
+        let l_shape = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![4.0, 0.0],
+            point![4.0, 1.0],
+            point![1.0, 1.0],
+            point![1.0, 4.0],
+            point![0.0, 4.0],
+        ]);
+
+        for distance in [0.1, 0.5, 2.0] {
+            let chamfered = l_shape.chamfer(distance);
+            let filleted = l_shape.fillet_all(distance, 1e-3);
+
+            assert!(chamfered.is_simple());
+            assert!(filleted.is_simple());
+            assert!(filleted.signed_area() > 0.0);
+        }
+        let cut = l_shape.chamfer(0.5);
+        assert_relative_eq!(cut.area(), l_shape.area() - 5.0 * 0.125 + 0.125);
+    }
+
+    #[test]
+    pub fn repeated_and_straight_vertices_should_be_handled() {
+        let mut doubled = rectangle(point![0.0, 0.0], point![2.0, 2.0]);
+        doubled.vertices.insert(1, point![0.0, 0.0]);
+        doubled.vertices.insert(3, point![2.0, 1.0]);
+
+        let rounded = doubled.fillet_all(0.5, 0.01);
+
+        // The vertex in the middle of a side stays where it is.
+        assert!(rounded.vertices.contains(&point![2.0, 1.0]));
+        assert_eq!(
+            doubled.chamfer_vertices(&[0.0; 6]).unwrap().vertices.len(),
+            5
+        );
+        assert!(Polygon::new(vec![])
+            .fillet_all(1.0, 0.1)
+            .vertices
+            .is_empty());
+    }
+
+    #[test]
+    pub fn fillets_should_clamp_to_fit_the_edges() {
+        let rounded = rectangle(point![0.0, 0.0], point![2.0, 2.0])
+            .fillet_all(10.0, 1e-4);
+
+        // The largest arcs which fit make a circle.
+        assert_relative_eq!(rounded.area(), PI, max_relative = 1e-3);
+        assert!(rounded.is_simple());
+    }
+
+    proptest! {
+        #[test]
+        fn convex_polygons_should_stay_simple_and_inside(
+            polygon in polygon(-10.0..=10.0, 3..=8, true),
+            distance in 0.0f32..=5.0,
+        ) {
+            prop_assume!(polygon.is_simple() && polygon.area() > 1.0);
+
+            for cut in [
+                polygon.chamfer(distance),
+                polygon.fillet_all(distance, 0.01),
+            ] {
+                prop_assert!(cut.is_simple());
+                prop_assert!(cut.area() <= polygon.area() * (1.0 + 1e-5));
+                prop_assert!(cut.signed_area() > 0.0);
+                for vertex in &cut.vertices {
+                    let grown = polygon.offset(1e-3);
+                    prop_assert!(grown.contains_point(vertex));
+                }
+            }
+        }
+    }
+}
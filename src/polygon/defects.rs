@@ -0,0 +1,336 @@
+use {
+    crate::{
+        hull::convex_hull,
+        line::{DistanceToPoint, Segment},
+        polygon::Polygon,
+    },
+    alloc::vec::Vec,
+    core::f32::consts::PI,
+};
+
+/// A dent in a polygon, where the boundary leaves its convex hull.
+///
+/// The vertices from `start` to `end` are a maximal run of the boundary
+/// between two neighboring hull vertices. Indices refer to the polygon's
+/// vertices, and the run goes forward through the vertices from `start`,
+/// wrapping around at the end, whichever way the polygon is wound.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Defect {
+    /// The hull vertex where the dent begins.
+    pub start: usize,
+
+    /// The hull vertex where the dent ends.
+    pub end: usize,
+
+    /// The vertex in the dent which is furthest from the hull edge between
+    /// `start` and `end`.
+    pub deepest: usize,
+
+    /// The distance from the deepest vertex to the hull edge.
+    pub depth: f32,
+}
+
+impl Polygon {
+    /// Returns true when every corner of the polygon turns the same way.
+    ///
+    /// Corners which are within `tolerance` of the line through their
+    /// neighbors count as straight and are ignored, so runs of collinear or
+    /// nearly collinear vertices are allowed. The polygon must also turn
+    /// exactly once, which rules out stars and other polygons that loop
+    /// around more than once. Polygons with fewer than three vertices or no
+    /// area aren't convex.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let mut square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![0.0, 2.0],
+    ///     ]);
+    ///     assert!(square.is_convex(0.0));
+    ///
+    ///     // Push the middle of the bottom edge up a little.
+    ///     square.vertices[1].y = 0.01;
+    ///     assert!(!square.is_convex(0.0));
+    ///     assert!(square.is_convex(0.1));
+    ///
+    pub fn is_convex(&self, tolerance: f32) -> bool {
+        let n = self.vertices.len();
+        if n < 3 || self.signed_area() == 0.0 {
+            return false;
+        }
+        let (mut left, mut right) = (false, false);
+        for i in 0..n {
+            let a = self.vertices[(i + n - 1) % n];
+            let b = self.vertices[i];
+            let c = self.vertices[(i + 1) % n];
+            let base = (c - a).norm();
+            if base == 0.0 {
+                // The boundary doubles back on itself.
+                return false;
+            }
+            let deviation = (b - a).perp(&(c - b)) / base;
+            if deviation > tolerance {
+                left = true;
+            } else if deviation < -tolerance {
+                right = true;
+            }
+        }
+        !(left && right) && self.total_turning().abs() < 3.0 * PI
+    }
+
+    /// Find the dents where the polygon's boundary leaves its convex hull.
+    ///
+    /// There is one defect for every hull edge which the boundary doesn't
+    /// follow, with the deepest vertex in the dent and its distance to the
+    /// hull edge. Vertices which lie exactly on a hull edge don't make a
+    /// defect. Defects are sorted by `start`. Convex polygons have no
+    /// defects, and neither do polygons with fewer than three vertices or
+    /// no area.
+    ///
+    /// The polygon should be simple. This is the same idea as OpenCV's
+    /// `convexityDefects`, which is often used to count the fingers of a
+    /// hand.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // A "V" shape with a notch cut into its top.
+    ///     let notched = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 4.0],
+    ///         point![2.0, 1.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     let defects = notched.convexity_defects();
+    ///
+    ///     assert_eq!(defects.len(), 1);
+    ///     assert_eq!((defects[0].start, defects[0].end), (2, 4));
+    ///     assert_eq!(defects[0].deepest, 3);
+    ///     assert_relative_eq!(defects[0].depth, 3.0);
+    ///
+    pub fn convexity_defects(&self) -> Vec<Defect> {
+        let n = self.vertices.len();
+        let Some(hull) = convex_hull(&self.vertices).filter(|_| n >= 3) else {
+            return Vec::new();
+        };
+
+        // Walk the boundary counterclockwise, like the hull.
+        let mut order: Vec<usize> = (0..n).collect();
+        if self.signed_area() < 0.0 {
+            order.reverse();
+        }
+
+        // Mark the first copy of each hull vertex along the boundary. For a
+        // simple polygon these come in the same order as the hull.
+        let mut found = vec![false; hull.vertices.len()];
+        let on_hull: Vec<bool> = order
+            .iter()
+            .map(|&i| {
+                let position = hull
+                    .vertices
+                    .iter()
+                    .zip(&found)
+                    .position(|(h, &f)| !f && *h == self.vertices[i]);
+                position.map(|p| found[p] = true).is_some()
+            })
+            .collect();
+        let first = on_hull.iter().position(|&h| h).unwrap_or(0);
+
+        let mut defects = Vec::new();
+        let mut start = order[first];
+        let mut run = Vec::new();
+        for k in 1..=n {
+            let position = (first + k) % n;
+            let i = order[position];
+            if !on_hull[position] {
+                run.push(i);
+                continue;
+            }
+            defects.extend(self.defect(start, i, &run));
+            run.clear();
+            start = i;
+        }
+        if self.signed_area() < 0.0 {
+            // The runs were walked backwards.
+            for defect in &mut defects {
+                core::mem::swap(&mut defect.start, &mut defect.end);
+            }
+        }
+        defects.sort_by_key(|defect| defect.start);
+        defects
+    }
+
+    /// The defect for the boundary vertices between two hull vertices, or
+    /// `None` when they're all on the hull edge.
+    fn defect(
+        &self,
+        start: usize,
+        end: usize,
+        run: &[usize],
+    ) -> Option<Defect> {
+        let edge = Segment::new(self.vertices[start], self.vertices[end]);
+        let (deepest, depth) = run
+            .iter()
+            .map(|&i| (i, edge.distance_to_point(&self.vertices[i])))
+            .fold(
+                None,
+                |best: Option<(usize, f32)>, (i, depth)| match best {
+                    Some((_, best_depth)) if best_depth >= depth => best,
+                    _ => Some((i, depth)),
+                },
+            )?;
+        (depth > 0.0).then_some(Defect {
+            start,
+            end,
+            deepest,
+            depth,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::Defect,
+        crate::{polygon::Polygon, test_support::polygon},
+        approx::assert_relative_eq,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    /// A palm with five pointed fingers of different lengths, like a
+    /// traced hand.
+    fn hand() -> Polygon {
+        let mut vertices = vec![point![0.0, 0.0], point![10.0, 0.0]];
+        vertices.push(point![10.0, 4.0]);
+        for (finger, height) in [6.0, 9.0, 10.0, 9.0, 7.0].iter().enumerate() {
+            let tip = 9.0 - 2.0 * finger as f32;
+            vertices.push(point![tip, *height]);
+            vertices.push(point![tip - 1.0, 4.0]);
+        }
+        Polygon::new(vertices)
+    }
+
+    fn corners(polygon: &Polygon, defect: &Defect) -> [Point2<f32>; 3] {
+        [defect.start, defect.deepest, defect.end].map(|i| polygon.vertices[i])
+    }
+
+    #[test]
+    pub fn hands_should_have_a_defect_between_each_finger() {
+        let hand = hand();
+
+        let defects = hand.convexity_defects();
+
+        assert_eq!(defects.len(), 4);
+        for defect in &defects {
+            let [start, deepest, end] = corners(&hand, defect);
+            assert_eq!(deepest.y, 4.0);
+            assert_eq!(deepest.x, start.x - 1.0);
+            assert_eq!(end.x, start.x - 2.0);
+            assert!(defect.depth > 2.0 && defect.depth < 6.0);
+        }
+        // The gaps next to the longest finger are the deepest.
+        assert_relative_eq!(defects[1].depth, 26.0f32.sqrt());
+        assert_relative_eq!(defects[2].depth, 26.0f32.sqrt());
+        assert!(defects[0].depth < defects[1].depth);
+        assert!(defects[3].depth < defects[2].depth);
+        assert!(!hand.is_convex(0.1));
+    }
+
+    #[test]
+    pub fn clockwise_polygons_should_report_runs_in_their_own_order() {
+        let mut hand = hand();
+        hand.vertices.reverse();
+
+        let defects = hand.convexity_defects();
+
+        assert_eq!(defects.len(), 4);
+        for defect in &defects {
+            let [start, deepest, end] = corners(&hand, defect);
+            assert!(
+                defect.start < defect.deepest && defect.deepest < defect.end
+            );
+            assert_eq!(deepest.y, 4.0);
+            assert_eq!(end.x, start.x + 2.0);
+        }
+    }
+
+    #[test]
+    pub fn collinear_and_clockwise_polygons_can_be_convex() {
+        let mut square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 2.0],
+            point![0.0, 2.0],
+        ]);
+        assert!(square.is_convex(0.0));
+        assert!(square.convexity_defects().is_empty());
+
+        square.vertices.reverse();
+        assert!(square.is_convex(0.0));
+        assert!(square.convexity_defects().is_empty());
+    }
+
+    #[test]
+    pub fn stars_and_degenerate_polygons_should_not_be_convex() {
+        let star = Polygon::new(
+            (0..5)
+                .map(|i| {
+                    let angle = i as f32 * 4.0 * core::f32::consts::PI / 5.0;
+                    point![angle.cos(), angle.sin()]
+                })
+                .collect(),
+        );
+        let sliver = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 1.0],
+            point![2.0, 2.0],
+        ]);
+
+        assert!(!star.is_convex(0.0));
+        assert!(!sliver.is_convex(0.0));
+        assert!(!Polygon::new(vec![]).is_convex(0.0));
+        assert!(sliver.convexity_defects().is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn convex_polygons_should_have_no_defects(
+            polygon in polygon(-10.0..=10.0, 3..=8, true),
+        ) {
+            prop_assert!(polygon.is_convex(1e-4));
+            prop_assert!(polygon.convexity_defects().is_empty());
+        }
+
+        #[test]
+        fn defects_should_be_inside_the_hull(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+        ) {
+            for defect in polygon.convexity_defects() {
+                let [start, deepest, end] = corners(&polygon, &defect);
+                // Hull edges run counterclockwise, so the dent is to
+                // their left.
+                let winding = polygon.signed_area().signum();
+                let side = (end - start).perp(&(deepest - start)) * winding;
+                prop_assert!(defect.depth > 0.0);
+                prop_assert!(side >= 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use {
+    crate::{
+        line::{Line, Ray, Segment},
+        polygon::Polygon,
+    },
+    alloc::vec::Vec,
+    nalgebra::{center, Unit, Vector2},
+};
+
+impl Polygon {
+    /// The infinite line parallel to each edge at a signed distance from
+    /// it, for dimension lines and labels.
+    ///
+    /// Positive distances are outside the polygon and negative distances
+    /// are inside it, whichever way the vertices wind. Each line's normal
+    /// points back toward its edge, so the line's
+    /// [`distance_to_point`](crate::line::DistanceToPoint::distance_to_point)
+    /// is `distance` on the edge and grows toward the polygon's interior.
+    ///
+    /// There is one line for each edge, in the same order as
+    /// [`Polygon::edges`], except that zero-length edges are skipped since
+    /// they have no direction.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::DistanceToPoint, polygon::Polygon},
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 4.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     let guides = square.edge_guides(1.0);
+    ///
+    ///     // The guide for the bottom edge is the line y = -1.
+    ///     let bottom = guides[0];
+    ///     let on_guide = bottom.distance_to_point(&point![7.0, -1.0]);
+    ///     let on_edge = bottom.distance_to_point(&point![2.0, 0.0]);
+    ///     assert_relative_eq!(on_guide, 0.0);
+    ///     assert_relative_eq!(on_edge, 1.0);
+    ///
+    pub fn edge_guides(&self, distance: f32) -> Vec<Line> {
+        self.outward_edges()
+            .map(|(edge, outward)| {
+                let c = outward.dot(&edge.start.coords) + distance;
+                Line::new(-outward, c)
+            })
+            .collect()
+    }
+
+    /// A ray from the midpoint of each edge along the edge's outward
+    /// normal.
+    ///
+    /// Outward is away from the polygon's interior whichever way the
+    /// vertices wind. There is one ray for each edge, in the same order as
+    /// [`Polygon::edges`], except that zero-length edges are skipped since
+    /// they have no normal.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::{point, vector},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // Clockwise, but the normals still point out.
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![0.0, 4.0],
+    ///         point![4.0, 4.0],
+    ///         point![4.0, 0.0],
+    ///     ]);
+    ///
+    ///     let normals = square.edge_midpoint_normals();
+    ///
+    ///     assert_eq!(normals[0].origin, point![0.0, 2.0]);
+    ///     let direction = normals[0].direction.into_inner();
+    ///     assert_relative_eq!(direction, vector![-1.0, 0.0]);
+    ///
+    pub fn edge_midpoint_normals(&self) -> Vec<Ray> {
+        self.outward_edges()
+            .map(|(edge, outward)| {
+                Ray::new(center(&edge.start, &edge.end), outward)
+            })
+            .collect()
+    }
+
+    /// Each edge with nonzero length along with its outward unit normal.
+    fn outward_edges(
+        &self,
+    ) -> impl Iterator<Item = (Segment, Unit<Vector2<f32>>)> + '_ {
+        // Outward is to the right of counterclockwise edges and to the left
+        // of clockwise ones. Polygons with no area count as
+        // counterclockwise.
+        let sign = if self.signed_area() < 0.0 { -1.0 } else { 1.0 };
+        self.edges().filter_map(move |edge| {
+            let direction = edge.end - edge.start;
+            let outward = Vector2::new(direction.y, -direction.x) * sign;
+            Unit::try_new(outward, 0.0).map(|outward| (edge, outward))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::DistanceToPoint, polygon::Polygon, test_support::polygon,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{center, point},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn square_guides_should_be_one_side_plus_distance_from_the_far_edge() {
+        let side = 3.0;
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![side, 0.0],
+            point![side, side],
+            point![0.0, side],
+        ]);
+        let mut clockwise = square.clone();
+        clockwise.vertices.reverse();
+
+        for polygon in [square, clockwise] {
+            let edges: Vec<_> = polygon.edges().collect();
+            let guides = polygon.edge_guides(1.0);
+            assert_eq!(guides.len(), 4);
+            for (i, guide) in guides.iter().enumerate() {
+                let opposite = edges[(i + 2) % 4];
+                let midpoint = center(&opposite.start, &opposite.end);
+                assert_relative_eq!(
+                    guide.distance_to_point(&midpoint),
+                    side + 1.0
+                );
+                // The guide is parallel to its edge.
+                assert_relative_eq!(
+                    guide.distance_to_point(&edges[i].start),
+                    1.0
+                );
+                assert_relative_eq!(
+                    guide.distance_to_point(&edges[i].end),
+                    1.0
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn zero_length_edges_should_be_skipped() {
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 0.0],
+            point![0.0, 2.0],
+        ]);
+
+        assert_eq!(triangle.edge_guides(0.5).len(), 3);
+        assert_eq!(triangle.edge_midpoint_normals().len(), 3);
+        assert!(Polygon::new(vec![]).edge_guides(1.0).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn convex_polygons_should_be_behind_every_guide(
+            polygon in polygon(-10.0..=10.0, 3..=8, true),
+            clockwise in any::<bool>(),
+            distance in -1.0f32..=4.0,
+        ) {
+            let mut polygon = polygon;
+            if clockwise {
+                polygon.vertices.reverse();
+            }
+
+            for guide in polygon.edge_guides(distance) {
+                for vertex in &polygon.vertices {
+                    prop_assert!(
+                        guide.distance_to_point(vertex) >= distance - 1e-4
+                    );
+                }
+            }
+            for normal in polygon.edge_midpoint_normals() {
+                let outside = normal.origin + normal.direction.scale(0.01);
+                let inside = normal.origin - normal.direction.scale(0.01);
+                prop_assert!(!polygon.contains_point(&outside));
+                prop_assert!(polygon.contains_point(&inside));
+            }
+        }
+    }
+}
@@ -0,0 +1,579 @@
+use {
+    crate::{
+        line::Segment,
+        math::{atan2, sin_cos, sqrt},
+        polygon::Polygon,
+    },
+    nalgebra::{Matrix2, Point2, Unit, Vector2},
+};
+
+/// The area, centroid, and second moment of area of a region.
+///
+/// These are the mass properties of a flat plate with uniform unit density,
+/// which is what simple rigid body physics needs. Multiply the area and
+/// inertia by the density for other materials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    /// The unsigned area of the region.
+    pub area: f32,
+
+    /// The center of mass of the region.
+    pub centroid: Point2<f32>,
+
+    /// The second moment of area about the centroid.
+    ///
+    /// This is the matrix `∫ (p - c)(p - c)ᵀ dA` over the region, so the
+    /// diagonal entries are `∫ x² dA` and `∫ y² dA` and the off-diagonal
+    /// entries are the product moment `∫ xy dA`, all relative to the
+    /// centroid. The trace is the polar moment of inertia, which resists
+    /// rotation in the plane.
+    pub inertia: Matrix2<f32>,
+}
+
+impl MassProperties {
+    /// Compute the mass properties of the region bounded by closed loops of
+    /// segments.
+    ///
+    /// The loops are integrated with the signed trapezoid formulas from
+    /// Green's theorem, so the segments can be in any order as long as they
+    /// form closed loops. Holes are subtracted when they wind the opposite
+    /// way from the outer boundary. The overall winding doesn't matter.
+    ///
+    /// When the loops enclose no area, the area and inertia are zero and the
+    /// centroid is the average of the segments' start points, or the origin
+    /// when there are no segments.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, polygon::MassProperties},
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // A 4x4 square with a 2x2 hole wound the other way
+    ///     let square = [
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 4.0],
+    ///         point![0.0, 4.0],
+    ///     ];
+    ///     let hole = [
+    ///         point![1.0, 1.0],
+    ///         point![1.0, 3.0],
+    ///         point![3.0, 3.0],
+    ///         point![3.0, 1.0],
+    ///     ];
+    ///     let edges = [square, hole].into_iter().flat_map(|vertices| {
+    ///         (0..4).map(move |i| {
+    ///             Segment::new(vertices[i], vertices[(i + 1) % 4])
+    ///         })
+    ///     });
+    ///
+    ///     let properties = MassProperties::from_boundary(edges);
+    ///
+    ///     assert_relative_eq!(properties.area, 12.0);
+    ///     assert_relative_eq!(properties.centroid, point![2.0, 2.0]);
+    ///
+    pub fn from_boundary(edges: impl IntoIterator<Item = Segment>) -> Self {
+        let mut edges = edges.into_iter().peekable();
+        let Some(origin) = edges.peek().map(|edge| edge.start) else {
+            return Self {
+                area: 0.0,
+                centroid: Point2::origin(),
+                inertia: Matrix2::zeros(),
+            };
+        };
+
+        // Integrate relative to the first point to reduce cancellation error
+        // for regions far from the origin.
+        let mut twice_area = 0.0;
+        let mut first_moment = Vector2::zeros();
+        let mut second_moment = Matrix2::zeros();
+        let mut start_sum = Vector2::zeros();
+        let mut count = 0;
+        for edge in edges {
+            let (a, b) = (edge.start - origin, edge.end - origin);
+            let cross = a.perp(&b);
+            twice_area += cross;
+            first_moment += (a + b) * cross;
+            let xx = a.x * a.x + a.x * b.x + b.x * b.x;
+            let yy = a.y * a.y + a.y * b.y + b.y * b.y;
+            let xy = 2.0 * a.x * a.y + a.x * b.y + b.x * a.y + 2.0 * b.x * b.y;
+            second_moment += Matrix2::new(
+                2.0 * xx * cross,
+                xy * cross,
+                xy * cross,
+                2.0 * yy * cross,
+            );
+            start_sum += a;
+            count += 1;
+        }
+
+        let area = twice_area * 0.5;
+        if area == 0.0 {
+            return Self {
+                area: 0.0,
+                centroid: origin + start_sum / count as f32,
+                inertia: Matrix2::zeros(),
+            };
+        }
+        let centroid = first_moment / (6.0 * area);
+        let about_origin = second_moment / 24.0;
+        // The parallel axis theorem moves the second moment to the centroid.
+        let about_centroid =
+            about_origin - centroid * centroid.transpose() * area;
+        // Clockwise boundaries flip the sign of every integral, but not the
+        // centroid because it's a ratio.
+        let sign = area.signum();
+        Self {
+            area: area * sign,
+            centroid: origin + centroid,
+            inertia: about_centroid * sign,
+        }
+    }
+
+    /// The polar radius of gyration: the distance from the centroid at which
+    /// all of the area could be concentrated without changing the polar
+    /// moment of inertia.
+    ///
+    /// Returns 0 when the area is 0.
+    pub fn radius_of_gyration(&self) -> f32 {
+        if self.area > 0.0 {
+            sqrt(self.inertia.trace().max(0.0) / self.area)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Polygon {
+    /// Compute the polygon's area, centroid, and second moment of area in
+    /// one pass.
+    ///
+    /// See [`MassProperties::from_boundary`] for details. Clockwise and
+    /// counterclockwise polygons have the same mass properties.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::{point, Matrix2},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // A 4x2 rectangle centered on (3, 2)
+    ///     let rectangle = Polygon::new(vec![
+    ///         point![1.0, 1.0],
+    ///         point![5.0, 1.0],
+    ///         point![5.0, 3.0],
+    ///         point![1.0, 3.0],
+    ///     ]);
+    ///
+    ///     let properties = rectangle.mass_properties();
+    ///
+    ///     assert_relative_eq!(properties.area, 8.0);
+    ///     assert_relative_eq!(properties.centroid, point![3.0, 2.0]);
+    ///     // w³h / 12 and wh³ / 12
+    ///     assert_relative_eq!(
+    ///         properties.inertia,
+    ///         Matrix2::new(128.0 / 12.0, 0.0, 0.0, 32.0 / 12.0),
+    ///         epsilon = 1e-5,
+    ///     );
+    ///
+    pub fn mass_properties(&self) -> MassProperties {
+        MassProperties::from_boundary(self.edges())
+    }
+
+    /// The polygon's center of mass.
+    ///
+    /// Polygons with no area use the average of their vertices.
+    pub fn centroid(&self) -> Point2<f32> {
+        self.mass_properties().centroid
+    }
+
+    /// The polygon's second moment of area about its centroid.
+    ///
+    /// See [`MassProperties::inertia`] for the layout of the matrix.
+    pub fn second_moment_of_area(&self) -> Matrix2<f32> {
+        self.mass_properties().inertia
+    }
+
+    /// The polygon's polar radius of gyration about its centroid.
+    ///
+    /// See [`MassProperties::radius_of_gyration`].
+    pub fn radius_of_gyration(&self) -> f32 {
+        self.mass_properties().radius_of_gyration()
+    }
+}
+
+/// The combined center of mass and second moment of a mix of weighted
+/// points, segments, and polygons, for balancing a composition or lining it
+/// up with the canvas.
+///
+/// Points carry their own weight, segments weigh their length times a
+/// density, and polygons weigh their area times a density. Segments count
+/// as uniform rods and polygons as uniform plates, so their own second
+/// moments about their centroids are included. Every primitive that's added
+/// counts once and only once: adding a polygon and then its edges adds a
+/// plate and a frame of rods, since the plate's weight comes only from its
+/// area and the rods' weight only from their length.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, polygon::MassAggregate},
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let rod = Segment::new(point![2.0, 0.0], point![4.0, 0.0]);
+///
+///     let mut aggregate = MassAggregate::new();
+///     aggregate
+///         .add_point(point![0.0, 0.0], 1.0)
+///         .add_point(point![6.0, 0.0], 1.0)
+///         .add_segment(&rod, 1.0);
+///
+///     assert_eq!(aggregate.total_weight(), 4.0);
+///     assert_eq!(aggregate.centroid(), Some(point![3.0, 0.0]));
+///     let axes = aggregate.principal_axes().unwrap();
+///     assert_relative_eq!(axes.major_axis.into_inner(), vector![1.0, 0.0]);
+///     assert_eq!(axes.minor_moment, 0.0);
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MassAggregate {
+    /// The point every moment is measured from, which is the centroid of
+    /// the first primitive, to reduce cancellation for compositions far
+    /// from the origin.
+    origin: Option<Point2<f32>>,
+    weight: f32,
+    first_moment: Vector2<f32>,
+    second_moment: Matrix2<f32>,
+}
+
+/// The directions along which a mass is most and least spread out, found
+/// from the eigenvectors of its second moment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrincipalAxes {
+    /// The direction of the largest second moment, along which the mass is
+    /// elongated.
+    pub major_axis: Unit<Vector2<f32>>,
+
+    /// The direction of the smallest second moment, perpendicular to the
+    /// major axis.
+    pub minor_axis: Unit<Vector2<f32>>,
+
+    /// The second moment along the major axis.
+    pub major_moment: f32,
+
+    /// The second moment along the minor axis.
+    pub minor_moment: f32,
+}
+
+impl MassAggregate {
+    /// Create an aggregate with nothing in it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a point with a weight.
+    pub fn add_point(&mut self, point: Point2<f32>, weight: f32) -> &mut Self {
+        self.add(point, weight, Matrix2::zeros())
+    }
+
+    /// Add a segment as a uniform rod, weighing its length times the
+    /// density.
+    pub fn add_segment(
+        &mut self,
+        segment: &Segment,
+        density: f32,
+    ) -> &mut Self {
+        self.add(
+            segment.centroid(),
+            segment.length() * density,
+            segment.second_moment_of_length() * density,
+        )
+    }
+
+    /// Add a polygon as a uniform plate, weighing its area times the
+    /// density. The polygon's winding doesn't matter.
+    pub fn add_polygon(
+        &mut self,
+        polygon: &Polygon,
+        density: f32,
+    ) -> &mut Self {
+        let properties = polygon.mass_properties();
+        self.add(
+            properties.centroid,
+            properties.area * density,
+            properties.inertia * density,
+        )
+    }
+
+    /// The total weight of everything added.
+    pub fn total_weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// The combined center of mass, or `None` when the total weight is
+    /// zero.
+    pub fn centroid(&self) -> Option<Point2<f32>> {
+        let origin = self.origin?;
+        (self.weight != 0.0).then(|| origin + self.first_moment / self.weight)
+    }
+
+    /// The combined second moment about the centroid, in the layout of
+    /// [`MassProperties::inertia`].
+    ///
+    /// The second moment is zero when the total weight is zero.
+    pub fn second_moment(&self) -> Matrix2<f32> {
+        if self.weight == 0.0 {
+            return Matrix2::zeros();
+        }
+        // The parallel axis theorem moves the second moment from the
+        // origin to the centroid.
+        let offset = self.first_moment / self.weight;
+        self.second_moment - offset * offset.transpose() * self.weight
+    }
+
+    /// The principal axes of the combined second moment, or `None` when the
+    /// total weight is zero.
+    ///
+    /// When the mass is spread out equally in every direction, like a
+    /// circle or a square, any direction is a principal axis and the major
+    /// axis is the x axis.
+    pub fn principal_axes(&self) -> Option<PrincipalAxes> {
+        self.centroid()?;
+        let moment = self.second_moment();
+        let (xx, xy, yy) = (moment[(0, 0)], moment[(0, 1)], moment[(1, 1)]);
+        let mean = (xx + yy) / 2.0;
+        let half_difference = (xx - yy) / 2.0;
+        let spread = sqrt(half_difference * half_difference + xy * xy);
+        let (sin, cos) = sin_cos(atan2(xy, half_difference) / 2.0);
+        Some(PrincipalAxes {
+            major_axis: Unit::new_unchecked(Vector2::new(cos, sin)),
+            minor_axis: Unit::new_unchecked(Vector2::new(-sin, cos)),
+            major_moment: mean + spread,
+            minor_moment: mean - spread,
+        })
+    }
+
+    fn add(
+        &mut self,
+        centroid: Point2<f32>,
+        weight: f32,
+        own_moment: Matrix2<f32>,
+    ) -> &mut Self {
+        let origin = *self.origin.get_or_insert(centroid);
+        let offset = centroid - origin;
+        self.weight += weight;
+        self.first_moment += offset * weight;
+        self.second_moment += own_moment + offset * offset.transpose() * weight;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            polygon::{MassAggregate, MassProperties, Polygon},
+            test_support::polygon,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::{PI, TAU},
+        nalgebra::{point, vector, Matrix2, Point2},
+        proptest::prelude::*,
+    };
+
+    fn regular_polygon(radius: f32, sides: usize) -> Polygon {
+        Polygon::new(
+            (0..sides)
+                .map(|i| {
+                    let angle = i as f32 * TAU / sides as f32;
+                    point![radius * angle.cos(), radius * angle.sin()]
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    pub fn circles_should_match_the_closed_form_values() {
+        let r = 3.0;
+        let circle = regular_polygon(r, 1024);
+
+        let properties = circle.mass_properties();
+
+        assert_relative_eq!(properties.area, PI * r * r, max_relative = 1e-4);
+        assert_relative_eq!(
+            properties.centroid,
+            Point2::origin(),
+            epsilon = 1e-4
+        );
+        let quarter = PI * r.powi(4) / 4.0;
+        assert_relative_eq!(
+            properties.inertia,
+            Matrix2::new(quarter, 0.0, 0.0, quarter),
+            max_relative = 1e-3,
+            epsilon = 1e-3,
+        );
+        assert_relative_eq!(
+            circle.radius_of_gyration(),
+            r / 2.0f32.sqrt(),
+            max_relative = 1e-4
+        );
+    }
+
+    #[test]
+    pub fn winding_should_not_change_mass_properties() {
+        let mut triangle = Polygon::new(vec![
+            point![1.0, 1.0],
+            point![5.0, 2.0],
+            point![2.0, 4.0],
+        ]);
+        let counterclockwise = triangle.mass_properties();
+
+        triangle.vertices.reverse();
+        let clockwise = triangle.mass_properties();
+
+        assert_relative_eq!(clockwise.area, counterclockwise.area);
+        assert_relative_eq!(clockwise.centroid, counterclockwise.centroid);
+        assert_relative_eq!(
+            clockwise.inertia,
+            counterclockwise.inertia,
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(clockwise.centroid, point![8.0 / 3.0, 7.0 / 3.0]);
+    }
+
+    #[test]
+    pub fn degenerate_boundaries_should_have_no_area() {
+        let empty = MassProperties::from_boundary([]);
+        assert_eq!(empty.area, 0.0);
+        assert_eq!(empty.centroid, Point2::origin());
+
+        let flat = Polygon::new(vec![
+            point![0.0, 1.0],
+            point![2.0, 1.0],
+            point![4.0, 1.0],
+        ]);
+        assert_eq!(flat.mass_properties().area, 0.0);
+        assert_eq!(flat.centroid(), point![2.0, 1.0]);
+        assert_eq!(flat.second_moment_of_area(), Matrix2::zeros());
+        assert_eq!(flat.radius_of_gyration(), 0.0);
+    }
+
+    #[test]
+    pub fn equal_squares_should_balance_at_the_midpoint() {
+        let square = |x: f32, y: f32| {
+            Polygon::new(vec![
+                point![x, y],
+                point![x + 2.0, y],
+                point![x + 2.0, y + 2.0],
+                point![x, y + 2.0],
+            ])
+        };
+
+        let mut aggregate = MassAggregate::new();
+        aggregate
+            .add_polygon(&square(0.0, 0.0), 1.0)
+            .add_polygon(&square(10.0, 4.0), 1.0);
+
+        assert_eq!(aggregate.total_weight(), 8.0);
+        assert_relative_eq!(aggregate.centroid().unwrap(), point![6.0, 3.0]);
+    }
+
+    #[test]
+    pub fn elongated_layouts_should_have_a_major_axis_along_them() {
+        let direction = vector![2.0, 1.0].normalize();
+        let mut aggregate = MassAggregate::new();
+        for i in 0..5 {
+            let center = point![3.0, -1.0] + direction * (i as f32 * 4.0);
+            let hexagon = Polygon::new(
+                regular_polygon(1.0, 6)
+                    .vertices
+                    .iter()
+                    .map(|v| v + center.coords)
+                    .collect(),
+            );
+            aggregate.add_polygon(&hexagon, 1.0);
+            aggregate.add_point(center + vector![0.0, 0.5], 0.5);
+        }
+
+        let axes = aggregate.principal_axes().unwrap();
+
+        let alignment = axes.major_axis.dot(&direction).abs();
+        assert_relative_eq!(alignment, 1.0, epsilon = 1e-3);
+        assert_relative_eq!(axes.minor_axis.dot(&axes.major_axis), 0.0);
+        assert!(axes.major_moment > 10.0 * axes.minor_moment);
+        let moment = aggregate.second_moment();
+        assert_relative_eq!(
+            moment * axes.major_axis.into_inner(),
+            axes.major_axis.into_inner() * axes.major_moment,
+            max_relative = 1e-3,
+        );
+    }
+
+    #[test]
+    pub fn boundaries_should_add_their_length_to_the_plate() {
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![3.0, 0.0],
+            point![0.0, 4.0],
+        ]);
+        let mut aggregate = MassAggregate::new();
+        aggregate.add_polygon(&triangle, 1.0);
+        for edge in triangle.edges() {
+            aggregate.add_segment(&edge, 1.0);
+        }
+
+        assert_relative_eq!(aggregate.total_weight(), 6.0 + 12.0);
+        assert_eq!(MassAggregate::new().centroid(), None);
+        assert_eq!(MassAggregate::new().principal_axes(), None);
+        let rod = Segment::new(point![1.0, 1.0], point![1.0, 1.0]);
+        assert_eq!(
+            MassAggregate::new().add_segment(&rod, 2.0).centroid(),
+            None
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn translation_should_not_change_centroid_relative_inertia(
+            polygon in polygon(-10.0..=10.0, 3..=12, false),
+            x in -100.0f32..100.0,
+            y in -100.0f32..100.0,
+        ) {
+            let offset = vector![x, y];
+            let moved = Polygon::new(
+                polygon.vertices.iter().map(|v| v + offset).collect::<Vec<_>>(),
+            );
+
+            let before = polygon.mass_properties();
+            let after = moved.mass_properties();
+
+            assert_relative_eq!(after.area, before.area, max_relative = 1e-4);
+            assert_relative_eq!(
+                after.centroid,
+                before.centroid + offset,
+                epsilon = 1e-3,
+            );
+            assert_relative_eq!(
+                after.inertia,
+                before.inertia,
+                max_relative = 1e-3,
+                epsilon = 1e-2,
+            );
+        }
+
+        #[test]
+        fn centroids_should_be_inside_convex_polygons(
+            polygon in polygon(-10.0..=10.0, 3..=12, true),
+        ) {
+            prop_assert!(polygon.contains_point(&polygon.centroid()));
+        }
+    }
+}
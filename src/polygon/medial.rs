@@ -0,0 +1,188 @@
+use {
+    crate::{
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            Segment,
+        },
+        math,
+        polygon::Polygon,
+        shape::Circle,
+        triangulation::Triangulation,
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+impl Polygon {
+    /// Approximate the polygon's medial axis, the skeleton of points which
+    /// are equally close to two or more parts of the boundary.
+    ///
+    /// The boundary is sampled at most `sample_spacing` apart and the
+    /// Voronoi diagram of the samples is built from their Delaunay
+    /// triangulation. Voronoi edges which are entirely inside the polygon
+    /// approximate the medial axis. Smaller spacings follow the true axis
+    /// more closely, but take O(n²) time in the number of samples.
+    ///
+    /// The skeleton is returned as many short pieces in no particular order.
+    /// Besides the main spine, every convex corner grows a short branch
+    /// towards its tip. Returns no segments when the spacing isn't positive
+    /// or the polygon has fewer than three vertices.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let stroke = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![10.0, 0.0],
+    ///         point![10.0, 1.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///
+    ///     let skeleton = stroke.medial_axis_approx(0.1);
+    ///
+    ///     for piece in &skeleton {
+    ///         assert!(stroke.contains_point(&piece.start));
+    ///         assert!(stroke.contains_point(&piece.end));
+    ///     }
+    ///
+    pub fn medial_axis_approx(&self, sample_spacing: f32) -> Vec<Segment> {
+        if sample_spacing.is_nan()
+            || sample_spacing <= 0.0
+            || self.vertices.len() < 3
+        {
+            return Vec::new();
+        }
+
+        let mut samples = Vec::new();
+        for edge in self.edges() {
+            let parts = math::ceil(edge.length() / sample_spacing).max(1.0);
+            let parts = parts as usize;
+            samples.extend((0..parts).map(|i| {
+                edge.start + (edge.end - edge.start) * (i as f32 / parts as f32)
+            }));
+        }
+
+        let triangulation = Triangulation::delaunay(samples);
+        let centers: Vec<Option<Point2<f32>>> = triangulation
+            .triangles()
+            .iter()
+            .map(|&[a, b, c]| {
+                let points = triangulation.points();
+                Circle::circumscribe(&points[a], &points[b], &points[c])
+                    .map(|circle| circle.center)
+                    .filter(|center| self.contains_point(center))
+            })
+            .collect();
+
+        let mut skeleton = Vec::new();
+        for (triangle, center) in centers.iter().enumerate() {
+            let Some(start) = center else {
+                continue;
+            };
+            for neighbor in triangulation.neighbors(triangle).into_iter() {
+                let Some(neighbor) = neighbor.filter(|&n| n > triangle) else {
+                    continue;
+                };
+                let Some(end) = centers[neighbor] else {
+                    continue;
+                };
+                let piece = Segment::new(*start, end);
+                if piece.start != piece.end && !self.crosses_boundary(&piece) {
+                    skeleton.push(piece);
+                }
+            }
+        }
+        skeleton
+    }
+
+    /// Returns true when the segment touches any of the polygon's edges.
+    fn crosses_boundary(&self, segment: &Segment) -> bool {
+        self.edges().any(|edge| {
+            intersect_segments(&edge, segment) != SegmentIntersection::None
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            interval::union_length,
+            line::{Line, Segment},
+            polygon::Polygon,
+            test_support::polygon,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn long_rectangles_should_have_a_central_spine() {
+        let stroke = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 1.0],
+            point![0.0, 1.0],
+        ]);
+
+        let skeleton = stroke.medial_axis_approx(0.05);
+
+        let spine: Vec<Segment> = skeleton
+            .iter()
+            .filter(|piece| {
+                (piece.start.y - 0.5).abs() < 1e-3
+                    && (piece.end.y - 0.5).abs() < 1e-3
+            })
+            .copied()
+            .collect();
+        let x_axis = Line::new(Unit::new_normalize(vector![0.0, 1.0]), 0.0);
+        assert_relative_eq!(
+            union_length(&spine, &x_axis),
+            9.0,
+            max_relative = 0.02
+        );
+        let branches: f32 = skeleton
+            .iter()
+            .filter(|piece| {
+                let middle = nalgebra::center(&piece.start, &piece.end);
+                (middle.y - 0.5).abs() >= 1e-3
+            })
+            .map(|piece| piece.length())
+            .sum();
+        // The four corner branches are each about sqrt(0.5) long.
+        assert!(branches < 4.0);
+    }
+
+    #[test]
+    pub fn degenerate_inputs_should_have_no_skeleton() {
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![0.0, 1.0],
+        ]);
+
+        assert!(triangle.medial_axis_approx(0.0).is_empty());
+        assert!(triangle.medial_axis_approx(f32::NAN).is_empty());
+        assert!(Polygon::new(vec![]).medial_axis_approx(1.0).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn skeletons_should_stay_inside_the_polygon(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+        ) {
+            for piece in polygon.medial_axis_approx(1.0) {
+                let middle = nalgebra::center(&piece.start, &piece.end);
+                prop_assert!(polygon.contains_point(&piece.start));
+                prop_assert!(polygon.contains_point(&piece.end));
+                prop_assert!(polygon.contains_point(&middle));
+            }
+        }
+    }
+}
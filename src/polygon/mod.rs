@@ -0,0 +1,43 @@
+//! Structs and algorithms for Polygons in two dimensions.
+
+mod boundary;
+mod buffer;
+mod clip;
+mod compare;
+mod convex;
+mod corners;
+mod defects;
+mod guides;
+mod mass;
+mod medial;
+mod nesting;
+mod normalize;
+mod offset;
+mod polyline;
+mod region;
+mod relax;
+mod shape;
+mod similarity;
+mod symmetry;
+mod triangulate;
+mod turning;
+mod union;
+
+pub(crate) use self::clip::clip_to_half_plane;
+pub use self::{
+    buffer::Buffer,
+    clip::{clip_segments_to_polygon, clip_segments_to_polygon_indexed},
+    compare::{iou, iou_approx, symmetric_difference_area},
+    convex::intersect_convex,
+    defects::Defect,
+    mass::{MassAggregate, MassProperties, PrincipalAxes},
+    nesting::{nesting_tree, NestingTree},
+    offset::cleanup_offset,
+    polyline::{Polyline, SelfIntersection},
+    region::Region,
+    shape::Polygon,
+    similarity::{dedup_polylines, frechet_distance, hausdorff_distance},
+    symmetry::Symmetries,
+    triangulate::triangulate_monotone,
+    union::{covered_area, union_all},
+};
@@ -0,0 +1,213 @@
+use {
+    crate::{polygon::Polygon, shape::Aabb},
+    alloc::vec::Vec,
+};
+
+/// Which loops in a collection are inside which.
+///
+/// The tree is a forest over the indices of the polygons it was built from.
+/// Each polygon's parent is the smallest polygon which contains it, and
+/// polygons which aren't inside any other are roots.
+///
+/// A polygon's depth is the number of polygons around it. With the even-odd
+/// fill rule, polygons at even depths are filled outlines and polygons at
+/// odd depths are holes in their parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestingTree {
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    depths: Vec<usize>,
+}
+
+/// Find which polygons are nested inside which.
+///
+/// The polygons are expected to be closed loops which don't cross each
+/// other, like the contours traced from an image. Containment is decided by
+/// testing the first vertex of one polygon against the other with
+/// [`Polygon::contains_point`], after checking that its bounding box fits.
+/// A polygon can only contain polygons with a strictly smaller area, so
+/// identical loops are siblings rather than nested.
+///
+/// Loops which share part of their boundary are nested only if the first
+/// vertex of the smaller one is strictly inside the larger one. When that
+/// vertex is on the shared boundary the result can go either way. Polygons
+/// with fewer than three vertices never contain anything.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{nesting_tree, Polygon},
+///         nalgebra::point,
+///     };
+///
+///     let square = |min: f32, max: f32| {
+///         Polygon::new(vec![
+///             point![min, min],
+///             point![max, min],
+///             point![max, max],
+///             point![min, max],
+///         ])
+///     };
+///     let loops = [square(1.0, 2.0), square(0.0, 3.0), square(5.0, 6.0)];
+///
+///     let tree = nesting_tree(&loops);
+///
+///     assert_eq!(tree.parent(0), Some(1));
+///     assert_eq!(tree.children(1), &[0]);
+///     assert_eq!(tree.roots().collect::<Vec<_>>(), vec![1, 2]);
+///     assert!(tree.is_hole(0));
+///
+pub fn nesting_tree(polygons: &[Polygon]) -> NestingTree {
+    let bounds: Vec<Option<Aabb>> = polygons
+        .iter()
+        .map(|polygon| Aabb::from_points(&polygon.vertices))
+        .collect();
+    let areas: Vec<f32> = polygons.iter().map(Polygon::area).collect();
+
+    let contains = |outer: usize, inner: usize| {
+        let (Some(outer_bounds), Some(inner_bounds)) =
+            (&bounds[outer], &bounds[inner])
+        else {
+            return false;
+        };
+        areas[outer] > areas[inner]
+            && polygons[outer].vertices.len() >= 3
+            && outer_bounds.contains_point(&inner_bounds.min)
+            && outer_bounds.contains_point(&inner_bounds.max)
+            && polygons[outer].contains_point(&polygons[inner].vertices[0])
+    };
+
+    let parents: Vec<Option<usize>> = (0..polygons.len())
+        .map(|inner| {
+            (0..polygons.len())
+                .filter(|&outer| contains(outer, inner))
+                .min_by(|&a, &b| areas[a].total_cmp(&areas[b]))
+        })
+        .collect();
+
+    // Parents always have a larger area, so visiting the largest polygons
+    // first means every parent's depth is known before its children.
+    let mut order: Vec<usize> = (0..polygons.len()).collect();
+    order.sort_by(|&a, &b| areas[b].total_cmp(&areas[a]));
+    let mut depths = vec![0; polygons.len()];
+    let mut children = vec![Vec::new(); polygons.len()];
+    for &index in &order {
+        if let Some(parent) = parents[index] {
+            depths[index] = depths[parent] + 1;
+        }
+    }
+    for (index, parent) in parents.iter().enumerate() {
+        if let Some(parent) = parent {
+            children[*parent].push(index);
+        }
+    }
+
+    NestingTree {
+        parents,
+        children,
+        depths,
+    }
+}
+
+impl NestingTree {
+    /// The number of polygons in the tree.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns true when the tree was built from no polygons.
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The smallest polygon which contains a polygon, or `None` for roots.
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        self.parents[index]
+    }
+
+    /// The polygons whose parent is the given polygon, in index order.
+    pub fn children(&self, index: usize) -> &[usize] {
+        &self.children[index]
+    }
+
+    /// The number of polygons which contain a polygon. Roots have a depth
+    /// of zero.
+    pub fn depth(&self, index: usize) -> usize {
+        self.depths[index]
+    }
+
+    /// Returns true when a polygon is at an odd depth, making it a hole
+    /// under the even-odd fill rule.
+    pub fn is_hole(&self, index: usize) -> bool {
+        self.depths[index] % 2 == 1
+    }
+
+    /// The polygons which aren't inside any other, in index order.
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(|(_, parent)| parent.is_none())
+            .map(|(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            polygon::{nesting_tree, Polygon},
+            test_support::rectangle,
+        },
+        alloc::vec::Vec,
+        nalgebra::point,
+    };
+
+    #[test]
+    pub fn concentric_squares_should_form_a_chain() {
+        let loops = [
+            rectangle(point![2.0, 2.0], point![3.0, 3.0]),
+            rectangle(point![0.0, 0.0], point![5.0, 5.0]),
+            rectangle(point![1.0, 1.0], point![4.0, 4.0]),
+        ];
+
+        let tree = nesting_tree(&loops);
+
+        assert_eq!(tree.roots().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(tree.children(1), &[2]);
+        assert_eq!(tree.children(2), &[0]);
+        assert!(tree.children(0).is_empty());
+        assert_eq!(
+            (0..3).map(|i| tree.depth(i)).collect::<Vec<_>>(),
+            vec![2, 0, 1]
+        );
+        assert!(!tree.is_hole(0));
+        assert!(tree.is_hole(2));
+    }
+
+    #[test]
+    pub fn disjoint_squares_should_both_be_roots() {
+        let tree = nesting_tree(&[
+            rectangle(point![0.0, 0.0], point![1.0, 1.0]),
+            rectangle(point![2.0, 2.0], point![3.0, 3.0]),
+        ]);
+
+        assert_eq!(tree.roots().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(tree.depth(0), 0);
+        assert_eq!(tree.depth(1), 0);
+    }
+
+    #[test]
+    pub fn identical_loops_should_be_siblings() {
+        let tree = nesting_tree(&[
+            rectangle(point![0.0, 0.0], point![4.0, 4.0]),
+            rectangle(point![1.0, 1.0], point![2.0, 2.0]),
+            rectangle(point![1.0, 1.0], point![2.0, 2.0]),
+            Polygon::new(vec![]),
+        ]);
+
+        assert_eq!(tree.children(0), &[1, 2]);
+        assert_eq!(tree.parent(3), None);
+        assert_eq!(tree.len(), 4);
+    }
+}
@@ -0,0 +1,388 @@
+use {
+    crate::{
+        line::{DistanceToPoint, Segment},
+        math,
+        polygon::Polygon,
+        Error,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+impl Polygon {
+    /// Remove vertices which lie on the straight path between their
+    /// neighbors.
+    ///
+    /// A vertex is removed when it's within `tolerance` of the segment
+    /// between the vertices before and after it. After each removal the
+    /// vertex before it is checked again, since its neighbors changed.
+    /// Repeated vertices are always removed, and a tolerance of zero only
+    /// removes exactly collinear vertices. Takes O(n²) time in the worst
+    /// case.
+    ///
+    /// Returns [`Error::TooFewVertices`] when fewer than three vertices are
+    /// left, and [`Error::NotSimple`] when the result crosses or touches
+    /// itself, which can happen when the tolerance is large compared to
+    /// the polygon.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.001],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![0.0, 2.0],
+    ///     ]);
+    ///
+    ///     let cleaned = square.remove_collinear_vertices(0.01).unwrap();
+    ///
+    ///     assert_eq!(cleaned.vertices.len(), 4);
+    ///     assert_eq!(cleaned.vertices[1], point![2.0, 0.0]);
+    ///
+    pub fn remove_collinear_vertices(
+        &self,
+        tolerance: f32,
+    ) -> Result<Polygon, Error> {
+        let mut vertices = self.vertices.clone();
+        let (mut i, mut unchanged) = (0, 0);
+        while vertices.len() >= 3 && unchanged < vertices.len() {
+            let n = vertices.len();
+            let between =
+                Segment::new(vertices[(i + n - 1) % n], vertices[(i + 1) % n]);
+            if between.distance_to_point(&vertices[i]) <= tolerance {
+                vertices.remove(i);
+                i = (i + n - 2) % (n - 1);
+                unchanged = 0;
+            } else {
+                i = (i + 1) % n;
+                unchanged += 1;
+            }
+        }
+        validated(vertices)
+    }
+
+    /// Collapse edges shorter than `min_length` to their midpoints.
+    ///
+    /// The shortest edge is collapsed first, and collapsing an edge moves
+    /// its neighbors' ends, so they're measured again before the next
+    /// shortest edge is picked. This repeats until every edge is at least
+    /// `min_length` long. Small features like notches and zigzags shrink
+    /// to a single vertex, which
+    /// [`Polygon::remove_collinear_vertices`] can then remove. Takes O(n²)
+    /// time in the worst case.
+    ///
+    /// Returns [`Error::TooFewVertices`] when fewer than three vertices are
+    /// left, and [`Error::NotSimple`] when the result crosses or touches
+    /// itself.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let triangle = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 0.01],
+    ///         point![0.0, 3.0],
+    ///     ]);
+    ///
+    ///     let cleaned = triangle.remove_short_edges(0.1).unwrap();
+    ///
+    ///     assert_eq!(cleaned.vertices.len(), 3);
+    ///     assert_eq!(cleaned.vertices[1], point![4.0, 0.005]);
+    ///
+    pub fn remove_short_edges(
+        &self,
+        min_length: f32,
+    ) -> Result<Polygon, Error> {
+        let mut vertices = self.vertices.clone();
+        while vertices.len() >= 3 {
+            let n = vertices.len();
+            let shortest = (0..n)
+                .map(|i| (i, (vertices[(i + 1) % n] - vertices[i]).norm()))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            let Some((i, length)) = shortest else {
+                break;
+            };
+            if length >= min_length {
+                break;
+            }
+            let next = (i + 1) % n;
+            vertices[i] = nalgebra::center(&vertices[i], &vertices[next]);
+            vertices.remove(next);
+        }
+        validated(vertices)
+    }
+
+    /// Rotate every edge to the nearest multiple of an angle, like the
+    /// "snap to 45°" tool in drawing programs.
+    ///
+    /// Each edge is rotated about its midpoint so that its direction is a
+    /// multiple of `increment` radians counterclockwise from the positive x
+    /// axis. Neighboring edges which snap to the same direction are merged
+    /// into one edge through their length weighted middle. The vertices
+    /// are then where the lines through neighboring edges intersect, so
+    /// the result has one vertex for each run of edges with the same
+    /// direction. Directions which are within `f32` rounding error of an
+    /// axis are snapped exactly onto it, so edges snapped to 90° with
+    /// `FRAC_PI_2` are exactly horizontal and vertical.
+    ///
+    /// Returns [`Error::TooFewVertices`] when fewer than three directions
+    /// are left, and [`Error::NotSimple`] when neighboring edges snap to
+    /// opposite directions or the result crosses or touches itself.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         core::f32::consts::FRAC_PI_2,
+    ///     };
+    ///
+    ///     let sketch = Polygon::new(vec![
+    ///         point![0.1, 0.1],
+    ///         point![4.0, -0.1],
+    ///         point![4.5, 2.0],
+    ///         point![-0.1, 2.0],
+    ///     ]);
+    ///
+    ///     let snapped = sketch.snap_angles(FRAC_PI_2).unwrap();
+    ///
+    ///     assert_eq!(
+    ///         snapped.vertices,
+    ///         vec![
+    ///             point![0.0, 0.0],
+    ///             point![4.25, 0.0],
+    ///             point![4.25, 2.0],
+    ///             point![0.0, 2.0],
+    ///         ]
+    ///     );
+    ///
+    pub fn snap_angles(&self, increment: f32) -> Result<Polygon, Error> {
+        // Each run of edges with the same snapped direction becomes one
+        // line, as a point on it and its direction.
+        let mut lines: Vec<(Point2<f32>, Vector2<f64>, f32)> = Vec::new();
+        for edge in self.edges() {
+            let along = edge.end - edge.start;
+            let length = along.norm();
+            if length == 0.0 {
+                continue;
+            }
+            let angle = math::atan2(along.y, along.x) as f64;
+            let steps = math::round(angle / increment as f64);
+            let (sin, cos) = math::sin_cos_f64(steps * increment as f64);
+            // Increments like `FRAC_PI_2` aren't exact, so directions within
+            // its rounding error of an axis are put exactly on the axis.
+            let axis = |value: f64| {
+                if value.abs() < 1e-6 {
+                    0.0
+                } else if value.abs() > 1.0 - 1e-12 {
+                    value.signum()
+                } else {
+                    value
+                }
+            };
+            let direction = Vector2::new(axis(cos), axis(sin));
+            let middle = nalgebra::center(&edge.start, &edge.end);
+            match lines.last_mut() {
+                Some((anchor, last, weight)) if same(last, &direction) => {
+                    let total = *weight + length;
+                    *anchor += (middle - *anchor) * (length / total);
+                    *weight = total;
+                }
+                _ => lines.push((middle, direction, length)),
+            }
+        }
+        if lines.len() > 1 && same(&lines[0].1, &lines[lines.len() - 1].1) {
+            let (anchor, _, length) = lines.pop().unwrap();
+            let (first, _, weight) = &mut lines[0];
+            let total = *weight + length;
+            *first += (anchor - *first) * (length / total);
+            *weight = total;
+        }
+        if lines.len() < 3 {
+            return Err(Error::TooFewVertices(3));
+        }
+
+        let n = lines.len();
+        let mut vertices = Vec::with_capacity(n);
+        for i in 0..n {
+            let (a, da, _) = lines[(i + n - 1) % n];
+            let (b, db, _) = lines[i];
+            let denominator = da.perp(&db);
+            if denominator.abs() <= 1e-9 {
+                // The boundary doubles back on itself.
+                return Err(Error::NotSimple);
+            }
+            let (a, b) = (a.cast::<f64>(), b.cast::<f64>());
+            let t = (b - a).perp(&db) / denominator;
+            vertices.push((a + da * t).cast::<f32>());
+        }
+        validated(vertices)
+    }
+}
+
+/// Returns true when two unit vectors point the same way.
+fn same(a: &Vector2<f64>, b: &Vector2<f64>) -> bool {
+    a.perp(b).abs() <= 1e-9 && a.dot(b) > 0.0
+}
+
+/// Wrap up the vertices of an edited polygon, checking that it's still a
+/// simple polygon.
+fn validated(vertices: Vec<Point2<f32>>) -> Result<Polygon, Error> {
+    let polygon = Polygon::try_new(vertices)?;
+    if polygon.is_simple() {
+        Ok(polygon)
+    } else {
+        Err(Error::NotSimple)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            generate::random_simple_polygon, polygon::Polygon, shape::Aabb,
+            Error,
+        },
+        core::f32::consts::{FRAC_PI_2, FRAC_PI_4},
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn noisy_rectangles_should_snap_to_exact_rectangles() {
+        let noisy = Polygon::new(vec![
+            point![0.01, -0.02],
+            point![10.03, 0.01],
+            point![9.98, 5.02],
+            point![-0.01, 4.97],
+        ]);
+
+        let snapped = noisy.snap_angles(FRAC_PI_2).unwrap();
+
+        let v = &snapped.vertices;
+        assert_eq!(v.len(), 4);
+        assert_eq!(
+            (v[0].y, v[1].x, v[2].y, v[3].x),
+            (v[1].y, v[2].x, v[3].y, v[0].x)
+        );
+        assert!((snapped.area() - noisy.area()).abs() < 0.1);
+    }
+
+    #[test]
+    pub fn snapping_should_merge_edges_with_the_same_direction() {
+        // The bottom bends slightly in the middle, and the top right corner
+        // is cut at roughly 45 degrees.
+        let shape = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![3.0, 0.05],
+            point![6.0, 0.0],
+            point![6.0, 3.0],
+            point![4.9, 4.05],
+            point![0.0, 4.0],
+        ]);
+
+        let snapped = shape.snap_angles(FRAC_PI_4).unwrap();
+
+        assert_eq!(snapped.vertices.len(), 5);
+        assert!(snapped.is_simple());
+        // Half turns leave only two directions.
+        assert_eq!(
+            shape.snap_angles(core::f32::consts::PI),
+            Err(Error::TooFewVertices(3))
+        );
+    }
+
+    #[test]
+    pub fn removing_short_edges_should_remove_micro_notches() {
+        // A rectangle with a tiny notch in the middle of its top edge.
+        let notched = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 5.0],
+            point![5.02, 5.0],
+            point![5.02, 4.98],
+            point![4.98, 4.98],
+            point![4.98, 5.0],
+            point![0.0, 5.0],
+        ]);
+
+        let collapsed = notched.remove_short_edges(0.1).unwrap();
+        let cleaned = collapsed.remove_collinear_vertices(0.05).unwrap();
+
+        assert_eq!(collapsed.vertices.len(), 5);
+        assert!(collapsed.edges().all(|edge| edge.length() >= 0.1));
+        assert_eq!(
+            cleaned.vertices,
+            vec![
+                point![0.0, 0.0],
+                point![10.0, 0.0],
+                point![10.0, 5.0],
+                point![0.0, 5.0],
+            ]
+        );
+        assert!((cleaned.area() - 50.0).abs() < 1e-4);
+    }
+
+    #[test]
+    pub fn edits_which_break_the_polygon_should_fail() {
+        let thin = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![4.0, 0.0],
+            point![2.0, 0.05],
+        ]);
+        let bowtie = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 2.0],
+            point![2.0, 0.0],
+            point![0.0, 2.0],
+        ]);
+
+        assert_eq!(
+            thin.remove_collinear_vertices(0.1),
+            Err(Error::TooFewVertices(3))
+        );
+        assert_eq!(thin.remove_short_edges(5.0), Err(Error::TooFewVertices(3)));
+        assert_eq!(
+            bowtie.remove_collinear_vertices(0.0),
+            Err(Error::NotSimple)
+        );
+        assert_eq!(
+            Polygon::new(vec![]).remove_short_edges(1.0),
+            Err(Error::TooFewVertices(3))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn edited_polygons_should_be_simple_or_fail(
+            seed in any::<u64>(),
+            tolerance in 0.0f32..=0.5,
+        ) {
+            let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+            let polygon = random_simple_polygon(10, &bounds, seed);
+
+            let edits = [
+                polygon.remove_collinear_vertices(tolerance),
+                polygon.remove_short_edges(tolerance * 4.0),
+                polygon.snap_angles(FRAC_PI_4),
+            ];
+
+            for edited in edits.into_iter().flatten() {
+                prop_assert!(edited.is_simple());
+            }
+            prop_assert_eq!(polygon.remove_collinear_vertices(0.0), Ok(polygon.clone()));
+            prop_assert_eq!(polygon.remove_short_edges(0.0), Ok(polygon));
+        }
+    }
+}
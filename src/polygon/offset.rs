@@ -0,0 +1,380 @@
+use {
+    crate::{
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            Segment,
+        },
+        polygon::Polygon,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+impl Polygon {
+    /// Move every edge of the polygon a distance along its outward normal.
+    ///
+    /// Positive distances grow the polygon and negative distances shrink
+    /// it. Neighboring edges are extended or trimmed until they meet, so
+    /// corners stay sharp. The result is always counterclockwise, with one
+    /// vertex for each distinct vertex of the polygon.
+    ///
+    /// This is the raw offset: when the distance is large compared to the
+    /// polygon's features, short edges turn inside out and the boundary
+    /// crosses itself. Use [`cleanup_offset`] to untangle it.
+    ///
+    /// There's no variant taking an
+    /// [`AxisConvention`](crate::convention::AxisConvention) because outward
+    /// only depends on which side of its boundary the polygon is. Flipping
+    /// the y axis mirrors the polygon and its offset together, so the offset
+    /// grows the same way in either convention. Only the winding of the
+    /// result, which is counterclockwise with y pointing up, looks clockwise
+    /// with y pointing down.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 4.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     let grown = square.offset(1.0);
+    ///     let shrunk = square.offset(-1.0);
+    ///
+    ///     assert_relative_eq!(grown.vertices[0], point![-1.0, -1.0]);
+    ///     assert_relative_eq!(grown.area(), 36.0, epsilon = 1e-4);
+    ///     assert_relative_eq!(shrunk.area(), 4.0, epsilon = 1e-4);
+    ///
+    pub fn offset(&self, distance: f32) -> Polygon {
+        let mut vertices: Vec<Point2<f32>> = Vec::new();
+        for vertex in &self.vertices {
+            if vertices.last() != Some(vertex) {
+                vertices.push(*vertex);
+            }
+        }
+        while vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        if self.signed_area() < 0.0 {
+            vertices.reverse();
+        }
+
+        let n = vertices.len();
+        let outward = |i: usize| {
+            let edge = vertices[(i + 1) % n] - vertices[i];
+            Vector2::new(edge.y, -edge.x).normalize()
+        };
+        Polygon::new(
+            (0..n)
+                .map(|i| {
+                    let (before, after) =
+                        (outward((i + n - 1) % n), outward(i));
+                    // The miter point is `distance` from both edge lines.
+                    let scale = 1.0 + before.dot(&after);
+                    if scale <= f32::EPSILON {
+                        // The boundary doubles back on itself.
+                        return vertices[i] + after * distance;
+                    }
+                    vertices[i] + (before + after) * (distance / scale)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Untangle an offset polygon whose boundary crosses itself.
+///
+/// The loop is cut at every point where two of its edges cross and the
+/// pieces are reconnected into loops which don't cross. A loop is kept when
+/// it winds counterclockwise, like the offset of any polygon from
+/// [`Polygon::offset`], and the original loop winds around its inside
+/// exactly once. The rest are where the offset turned inside out, like the
+/// small loops which appear where an edge was shorter than the offset
+/// distance, and they are dropped.
+///
+/// Only the loop itself is used, so parts of the offset which don't cross
+/// anything but are still too close to the original polygon are kept. For
+/// example, a square shrunk by more than half its width turns into a
+/// smaller square rotated half a turn, which still winds counterclockwise.
+/// Crossings are found by comparing every pair of edges, which takes O(n²)
+/// time.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{cleanup_offset, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     // An "L" with a thin arm along the bottom and a thick arm on the left.
+///     let l_shape = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![8.0, 0.0],
+///         point![8.0, 1.0],
+///         point![3.0, 1.0],
+///         point![3.0, 6.0],
+///         point![0.0, 6.0],
+///     ]);
+///
+///     // Shrinking by more than half of the thin arm's width turns the arm
+///     // inside out.
+///     let raw = l_shape.offset(-0.75);
+///     assert!(!raw.is_simple());
+///
+///     let cleaned = cleanup_offset(&raw);
+///
+///     assert_eq!(cleaned.len(), 1);
+///     assert!(cleaned[0].is_simple());
+///     assert_relative_eq!(cleaned[0].area(), 1.5 * 4.5, epsilon = 1e-4);
+///
+pub fn cleanup_offset(loop_: &Polygon) -> Vec<Polygon> {
+    untangle(loop_)
+        .into_iter()
+        .filter(|candidate| {
+            candidate.signed_area() > 0.0
+                && winding_number(loop_, &inside_point(candidate)) == 1
+        })
+        .collect()
+}
+
+/// Cut a loop at every point where two of its edges cross, and reconnect
+/// the pieces into loops which don't cross, in either winding. Loops with
+/// fewer than three vertices are dropped.
+pub(crate) fn untangle(loop_: &Polygon) -> Vec<Polygon> {
+    let edges: Vec<Segment> = loop_.edges().collect();
+    let n = edges.len();
+
+    // Every crossing between edges which aren't neighbors becomes a pair of
+    // nodes, one on each edge.
+    let mut crossings: Vec<Vec<(f32, Point2<f32>, usize)>> =
+        vec![Vec::new(); n];
+    let mut count = 0;
+    for i in 0..n {
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            if let SegmentIntersection::Point(point) =
+                intersect_segments(&edges[i], &edges[j])
+            {
+                crossings[i].push((parameter(&edges[i], &point), point, count));
+                crossings[j].push((parameter(&edges[j], &point), point, count));
+                count += 1;
+            }
+        }
+    }
+
+    // Lay out the nodes in order around the loop: each vertex followed by
+    // the crossings along its edge.
+    let mut nodes: Vec<(Point2<f32>, Option<usize>)> = Vec::new();
+    for (edge, along) in edges.iter().zip(&mut crossings) {
+        along.sort_by(|a, b| a.0.total_cmp(&b.0));
+        nodes.push((edge.start, None));
+        nodes.extend(
+            along
+                .iter()
+                .map(|&(_, point, crossing)| (point, Some(crossing))),
+        );
+    }
+    let mut partner = vec![usize::MAX; nodes.len()];
+    let mut first = vec![usize::MAX; count];
+    for (index, &(_, crossing)) in nodes.iter().enumerate() {
+        if let Some(crossing) = crossing {
+            if first[crossing] == usize::MAX {
+                first[crossing] = index;
+            } else {
+                partner[index] = first[crossing];
+                partner[first[crossing]] = index;
+            }
+        }
+    }
+
+    // Walk the pieces between nodes. At a crossing, switch over to the
+    // other edge, which splits the loop into loops that don't cross.
+    let mut visited = vec![false; nodes.len()];
+    let mut loops = Vec::new();
+    for start in 0..nodes.len() {
+        if visited[start] {
+            continue;
+        }
+        let mut vertices = Vec::new();
+        let mut piece = start;
+        while !visited[piece] {
+            visited[piece] = true;
+            if vertices.last() != Some(&nodes[piece].0) {
+                vertices.push(nodes[piece].0);
+            }
+            let end = (piece + 1) % nodes.len();
+            piece = match nodes[end].1 {
+                Some(_) => partner[end],
+                None => end,
+            };
+        }
+        while vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        if vertices.len() >= 3 {
+            loops.push(Polygon::new(vertices));
+        }
+    }
+    loops
+}
+
+/// A point just to the left of the middle of a counterclockwise polygon's
+/// longest edge, which is inside the polygon.
+pub(crate) fn inside_point(polygon: &Polygon) -> Point2<f32> {
+    let longest = polygon
+        .edges()
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .expect("the polygon has at least three vertices");
+    let direction = longest.end - longest.start;
+    let left = Vector2::new(-direction.y, direction.x);
+    nalgebra::center(&longest.start, &longest.end) + left * 1e-3
+}
+
+/// The number of times a polygon winds counterclockwise around a point.
+pub(crate) fn winding_number(polygon: &Polygon, point: &Point2<f32>) -> i32 {
+    let mut winding = 0;
+    for edge in polygon.edges() {
+        let (a, b) = (edge.start - point, edge.end - point);
+        if (a.y <= 0.0) != (b.y <= 0.0) {
+            // The edge crosses the ray to the right of the point when the
+            // point is on the edge's left for upward edges, or its right
+            // for downward edges.
+            let side = a.perp(&b);
+            if b.y > a.y && side > 0.0 {
+                winding += 1;
+            } else if b.y < a.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
+
+/// How far along a segment a point on it is, from 0 at the start to 1 at
+/// the end.
+fn parameter(segment: &Segment, point: &Point2<f32>) -> f32 {
+    let direction = segment.end - segment.start;
+    (point - segment.start).dot(&direction) / direction.norm_squared()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::cleanup_offset,
+        crate::{
+            line::{
+                intersection::{intersect_segments, SegmentIntersection},
+                DistanceToPoint, Segment,
+            },
+            polygon::Polygon,
+            test_support::polygon,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    /// Returns true when two edges which aren't neighbors cross. Pieces of
+    /// the same offset edge are nearly collinear, so reported crossings are
+    /// double checked against the distance to each edge.
+    fn crosses_itself(polygon: &Polygon) -> bool {
+        let edges: Vec<Segment> = polygon.edges().collect();
+        let n = edges.len();
+        (0..n).any(|i| {
+            (i + 2..n).filter(|&j| !(i == 0 && j == n - 1)).any(|j| {
+                match intersect_segments(&edges[i], &edges[j]) {
+                    SegmentIntersection::Point(point) => [i, j]
+                        .iter()
+                        .all(|&k| edges[k].distance_to_point(&point) < 1e-4),
+                    _ => false,
+                }
+            })
+        })
+    }
+
+    #[test]
+    pub fn growing_a_notch_should_remove_the_inverted_loop() {
+        // A square with a narrow notch cut into its top edge.
+        let notched = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 10.0],
+            point![6.0, 10.0],
+            point![5.5, 6.0],
+            point![4.5, 6.0],
+            point![4.0, 10.0],
+            point![0.0, 10.0],
+        ]);
+
+        // Growing it pushes the walls of the notch past each other.
+        let raw = notched.offset(1.0);
+        let cleaned = cleanup_offset(&raw);
+
+        assert!(!raw.is_simple());
+        assert_eq!(cleaned.len(), 1);
+        assert!(cleaned[0].is_simple());
+        // The inverted loop at the bottom of the notch winds the same way
+        // as the rest, but it's inside the offset a second time.
+        assert!(cleaned[0].area() < raw.signed_area());
+        assert!(cleaned[0].area() > 143.0 && cleaned[0].area() < 144.0);
+    }
+
+    #[test]
+    pub fn clockwise_polygons_should_offset_outward() {
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![0.0, 2.0],
+            point![2.0, 2.0],
+            point![2.0, 0.0],
+        ]);
+
+        let grown = square.offset(0.5);
+
+        assert!(grown.signed_area() > 0.0);
+        assert_relative_eq!(grown.area(), 9.0, epsilon = 1e-4);
+        assert_eq!(cleanup_offset(&grown), [grown]);
+    }
+
+    #[test]
+    pub fn empty_loops_should_have_nothing_to_keep() {
+        assert!(cleanup_offset(&Polygon::new(vec![])).is_empty());
+        assert!(Polygon::new(vec![]).offset(1.0).vertices.is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn convex_offsets_should_not_need_cleaning(
+            polygon in polygon(-10.0..=10.0, 3..=8, true),
+            distance in 0.0f32..=5.0,
+        ) {
+            let grown = polygon.offset(distance);
+
+            let cleaned = cleanup_offset(&grown);
+
+            prop_assert_eq!(cleaned.len(), 1);
+            prop_assert!(cleaned[0].area() >= polygon.area());
+        }
+
+        #[test]
+        fn cleaned_offsets_should_not_cross_themselves(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            distance in -1.0f32..=1.0,
+        ) {
+            for cleaned in cleanup_offset(&polygon.offset(distance)) {
+                prop_assert!(cleaned.signed_area() > 0.0);
+                prop_assert!(!crosses_itself(&cleaned), "{}", cleaned);
+            }
+        }
+    }
+}
@@ -0,0 +1,717 @@
+use {
+    crate::{
+        convert::{points_from_flat_slice, points_to_flat_iter},
+        display::write_points,
+        error::check_finite,
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            Segment,
+        },
+        path::{
+            frame_along_vertices, ArcLengthTable, Frame, Overrun, PathFrame,
+            Placement,
+        },
+        shape::{Aabb, BoundingBox, BoundingCircle, BoundingObb, Circle, Obb},
+        spatial::Bvh,
+        Error,
+    },
+    alloc::vec::Vec,
+    core::fmt,
+    nalgebra::{Point2, Unit},
+};
+
+/// A place where a polyline crosses or touches itself.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SelfIntersection {
+    /// The index of the earlier segment. Segment `i` runs from vertex `i` to
+    /// vertex `i + 1`.
+    pub first: usize,
+
+    /// The index of the later segment.
+    pub second: usize,
+
+    /// Where the segments meet.
+    pub point: Point2<f32>,
+}
+
+/// An open chain of segments defined by a sequence of vertices.
+///
+/// Unlike a [`Polygon`](super::Polygon), there is no implicit edge from the
+/// last vertex back to the first. A polyline whose first and last vertices
+/// are the same point traces a closed loop.
+///
+/// Polylines are displayed like segments, as `(0, 0) -> (1, 1) -> (2, 0)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    /// The polyline's vertices, in order from start to end.
+    pub vertices: Vec<Point2<f32>>,
+}
+
+impl Polyline {
+    /// Create a new polyline from a sequence of vertices.
+    ///
+    /// The vertices aren't validated. Use [`Polyline::try_new`] to reject
+    /// polylines with too few vertices or non-finite coordinates.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polyline,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let zigzag = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 1.0],
+    ///         point![2.0, 0.0],
+    ///     ]);
+    ///
+    pub fn new(vertices: Vec<Point2<f32>>) -> Self {
+        Self { vertices }
+    }
+
+    /// Create a new polyline, checking that it has at least two vertices and
+    /// that every coordinate is finite.
+    pub fn try_new(vertices: Vec<Point2<f32>>) -> Result<Self, Error> {
+        if vertices.len() < 2 {
+            return Err(Error::TooFewVertices(2));
+        }
+        for vertex in &vertices {
+            check_finite(&[vertex.x, vertex.y])?;
+        }
+        Ok(Self::new(vertices))
+    }
+
+    /// Create a polyline from interleaved `[x0, y0, x1, y1, ...]`
+    /// coordinates.
+    ///
+    /// Returns [`Error::OddCoordinateCount`] when the slice has an odd
+    /// length. When `strict` is true, coordinates which are infinite or NaN
+    /// are rejected with [`Error::NotFinite`]. The number of vertices isn't
+    /// checked. See [`points_from_flat_slice`] for details.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{polygon::Polyline, Error},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let flat = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+    ///     let path = Polyline::from_flat_slice(&flat, true)?;
+    ///
+    ///     assert_eq!(path.vertices[1], point![1.0, 0.0]);
+    ///     assert_eq!(path.to_flat_vec(), flat);
+    ///     assert!(Polyline::from_flat_slice(&[0.0, f32::NAN], true).is_err());
+    ///     # Ok::<(), Error>(())
+    ///
+    pub fn from_flat_slice(
+        coordinates: &[f32],
+        strict: bool,
+    ) -> Result<Self, Error> {
+        Ok(Self::new(points_from_flat_slice(coordinates, strict)?))
+    }
+
+    /// The vertices' interleaved coordinates, the inverse of
+    /// [`Polyline::from_flat_slice`].
+    pub fn to_flat_vec(&self) -> Vec<f32> {
+        points_to_flat_iter(&self.vertices).collect()
+    }
+
+    /// Iterate over the segments between consecutive vertices.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, polygon::Polyline},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let zigzag = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 1.0],
+    ///         point![2.0, 0.0],
+    ///     ]);
+    ///     let segments: Vec<Segment> = zigzag.segments().collect();
+    ///
+    ///     assert_eq!(segments.len(), 2);
+    ///     assert_eq!(
+    ///         segments[1],
+    ///         Segment::new(point![1.0, 1.0], point![2.0, 0.0])
+    ///     );
+    ///
+    pub fn segments(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.vertices
+            .windows(2)
+            .map(|pair| Segment::new(pair[0], pair[1]))
+    }
+
+    /// The total length of every segment in the polyline.
+    pub fn length(&self) -> f32 {
+        self.segments().map(|segment| segment.length()).sum()
+    }
+
+    /// Returns true when the first and last vertices are the same point.
+    pub fn is_closed(&self) -> bool {
+        self.vertices.len() > 2 && self.vertices.first() == self.vertices.last()
+    }
+
+    /// Find every place where the polyline crosses or touches itself.
+    ///
+    /// Neighboring segments always share a vertex, so that isn't an
+    /// intersection, and neither is the first vertex of a closed polyline
+    /// meeting the last. The exception is a spike, where the path turns
+    /// exactly around and runs back over the segment it just drew: the tip
+    /// of the spike is reported as an intersection between the two
+    /// segments. Other segments which overlap along a stretch are reported
+    /// once, at the start of the overlap along the earlier segment.
+    ///
+    /// Intersections are sorted by `first` and then by `second`. Candidate
+    /// pairs are found with a [`Bvh`], so this is fast unless the polyline
+    /// crosses itself many times.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polyline,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let figure_eight = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![2.0, 0.0],
+    ///         point![0.0, 2.0],
+    ///         point![0.0, 0.0],
+    ///     ]);
+    ///
+    ///     let crossings = figure_eight.self_intersections();
+    ///
+    ///     assert_eq!(crossings.len(), 1);
+    ///     assert_eq!((crossings[0].first, crossings[0].second), (0, 2));
+    ///     assert_eq!(crossings[0].point, point![1.0, 1.0]);
+    ///
+    pub fn self_intersections(&self) -> Vec<SelfIntersection> {
+        self.meetings()
+            .into_iter()
+            .map(|(first, second, meeting)| SelfIntersection {
+                first,
+                second,
+                point: match meeting {
+                    SegmentIntersection::Overlap(overlap) => overlap.start,
+                    SegmentIntersection::Point(point) => point,
+                    SegmentIntersection::None => unreachable!(),
+                },
+            })
+            .collect()
+    }
+
+    /// Cut the polyline into pieces which don't cross themselves.
+    ///
+    /// The polyline is cut at every point found by
+    /// [`Polyline::self_intersections`], and at both ends of every
+    /// overlap, so spikes are cut at their tips. The pieces are in order
+    /// along the polyline and each one starts where the last one ended.
+    /// Pieces can still touch each other at the cuts, and a piece which
+    /// leaves a crossing and comes back to it is a closed loop. Repeated
+    /// vertices are kept, but pieces with no length are dropped.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polyline,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     // An open figure eight, which crosses itself at (1, 1).
+    ///     let figure_eight = Polyline::new(vec![
+    ///         point![1.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![0.0, 2.0],
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![1.0, 2.0],
+    ///     ]);
+    ///
+    ///     let pieces = figure_eight.split_at_self_intersections();
+    ///
+    ///     assert_eq!(pieces.len(), 3);
+    ///     assert!(pieces[1].is_closed());
+    ///     assert!(pieces.iter().all(|p| p.self_intersections().is_empty()));
+    ///
+    pub fn split_at_self_intersections(&self) -> Vec<Polyline> {
+        let segments: Vec<Segment> = self.segments().collect();
+
+        // Cuts are positions along the polyline, as a segment index plus
+        // how far along the segment. Cuts at a vertex always use the
+        // segment starting there.
+        let mut cuts: Vec<(usize, f32, Point2<f32>)> = Vec::new();
+        let mut cut = |index: usize, point: Point2<f32>| {
+            let segment = &segments[index];
+            if point == segment.end {
+                cuts.push((index + 1, 0.0, point));
+            } else if point == segment.start {
+                cuts.push((index, 0.0, point));
+            } else {
+                let along = segment.end - segment.start;
+                let t =
+                    (point - segment.start).dot(&along) / along.norm_squared();
+                cuts.push((index, t, point));
+            }
+        };
+        for (first, second, meeting) in self.meetings() {
+            let points = match meeting {
+                SegmentIntersection::Overlap(overlap) => {
+                    [overlap.start, overlap.end]
+                }
+                SegmentIntersection::Point(point) => [point, point],
+                SegmentIntersection::None => continue,
+            };
+            for point in points {
+                cut(first, point);
+                cut(second, point);
+            }
+        }
+        cuts.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        cuts.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+
+        let mut pieces = Vec::new();
+        let mut piece: Vec<Point2<f32>> = Vec::new();
+        let mut cuts = cuts.into_iter().peekable();
+        for (index, vertex) in self.vertices.iter().enumerate() {
+            // Cuts partway along the segment which ends here.
+            while let Some((_, _, point)) =
+                cuts.next_if(|&(segment, ..)| segment < index)
+            {
+                piece.push(point);
+                pieces.push(Polyline::new(core::mem::take(&mut piece)));
+                piece.push(point);
+            }
+            piece.push(*vertex);
+            if cuts
+                .next_if(|&(segment, t, _)| segment == index && t == 0.0)
+                .is_some()
+            {
+                pieces.push(Polyline::new(core::mem::take(&mut piece)));
+                piece.push(*vertex);
+            }
+        }
+        // A cut at the last vertex leaves an empty piece, which is dropped
+        // along with pieces that have no length.
+        pieces.push(Polyline::new(piece));
+        pieces.retain(|piece| {
+            piece
+                .vertices
+                .iter()
+                .any(|v| Some(v) != piece.vertices.first())
+        });
+        pieces
+    }
+
+    /// Place a sequence of items, like the glyphs of a label, one after
+    /// another along the polyline.
+    ///
+    /// Each item takes up its advance along the path, starting
+    /// `start_offset` from the start of the path, so item `i` starts at the
+    /// offset plus the sum of the advances before it. Its frame is at its
+    /// start, with the tangent pointing straight at the point on the path
+    /// where it ends, so the item's baseline is a chord of the path. A
+    /// negative offset starts before the path, along its first direction.
+    /// With [`Overrun::Stop`], placement stops at the first item which runs
+    /// past the end of the path, and with [`Overrun::Extend`] items past the
+    /// end continue along the path's last direction.
+    ///
+    /// Only the geometry is placed, so choosing advances, kerning, and
+    /// glyphs is up to the caller. Where the path turns sharply, items
+    /// which follow each other overlap on the inside of the turn and spread
+    /// apart on the outside. [`Placement::chord_ratio`] drops below 1 for
+    /// those items, so they can be nudged or the path smoothed. Returns no
+    /// placements when the polyline has zero length, since it has no
+    /// direction.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             path::Overrun,
+    ///             polygon::Polyline,
+    ///         },
+    ///         nalgebra::{point, vector},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let corner = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///     ]);
+    ///     let advances = [1.0, 1.0, 1.0, 1.0];
+    ///
+    ///     let stopped = corner.place_along(&advances, 0.5, Overrun::Stop);
+    ///     let extended = corner.place_along(&advances, 0.5, Overrun::Extend);
+    ///
+    ///     assert_eq!(stopped.len(), 3);
+    ///     assert_eq!(extended.len(), 4);
+    ///     assert_relative_eq!(extended[3].frame.position, point![2.0, 1.5]);
+    ///     // The second item spans the corner.
+    ///     assert_relative_eq!(stopped[0].chord_ratio, 1.0);
+    ///     assert_relative_eq!(stopped[1].chord_ratio, 0.5f32.sqrt());
+    ///     assert_relative_eq!(
+    ///         stopped[1].frame.tangent.into_inner(),
+    ///         vector![1.0, 1.0].normalize()
+    ///     );
+    ///
+    pub fn place_along(
+        &self,
+        advances: &[f32],
+        start_offset: f32,
+        overrun: Overrun,
+    ) -> Vec<Placement> {
+        let table = ArcLengthTable::new(self);
+        let length = table.length();
+        let (Some(first), Some(last)) =
+            (table.frame_at_length(0.0), table.frame_at_length(length))
+        else {
+            return Vec::new();
+        };
+        // Beyond either end, the path continues in a straight line.
+        let point_at = |s: f32| {
+            if s < 0.0 {
+                first.position + first.tangent.into_inner() * s
+            } else if s > length {
+                last.position + last.tangent.into_inner() * (s - length)
+            } else {
+                table.point_at_length(s).unwrap()
+            }
+        };
+        let tangent_at = |s: f32| {
+            if s < 0.0 {
+                first.tangent
+            } else if s > length {
+                last.tangent
+            } else {
+                table.tangent_at_length(s).unwrap()
+            }
+        };
+
+        let mut placements = Vec::with_capacity(advances.len());
+        let mut distance = start_offset;
+        for &advance in advances {
+            let end = distance + advance;
+            if overrun == Overrun::Stop && end > length {
+                break;
+            }
+            let (start_point, end_point) = (point_at(distance), point_at(end));
+            let chord = end_point - start_point;
+            let tangent = Unit::try_new(chord, f32::EPSILON)
+                .unwrap_or_else(|| tangent_at(distance));
+            let chord_ratio = if advance > 0.0 {
+                chord.norm() / advance
+            } else {
+                1.0
+            };
+            placements.push(Placement {
+                frame: Frame::new(start_point, tangent),
+                distance,
+                chord_ratio,
+            });
+            distance = end;
+        }
+        placements
+    }
+
+    /// Every pair of segments which meet, besides neighbors meeting at
+    /// their shared vertex.
+    fn meetings(&self) -> Vec<(usize, usize, SegmentIntersection)> {
+        let segments: Vec<Segment> = self.segments().collect();
+        let n = segments.len();
+        let bvh = Bvh::new(segments.clone());
+        let mut meetings = Vec::new();
+        for (first, segment) in segments.iter().enumerate() {
+            if let Some(next) = segments.get(first + 1) {
+                let (a, b) =
+                    (segment.end - segment.start, next.end - next.start);
+                if a.perp(&b) == 0.0 && a.dot(&b) < 0.0 {
+                    // A spike which turns exactly around.
+                    let tip = SegmentIntersection::Point(segment.end);
+                    meetings.push((first, first + 1, tip));
+                }
+            }
+            for second in bvh.query_aabb(&segment.bounding_box()) {
+                if second <= first + 1 {
+                    continue;
+                }
+                let meeting = intersect_segments(segment, &segments[second]);
+                let closing = first == 0
+                    && second == n - 1
+                    && self.is_closed()
+                    && meeting == SegmentIntersection::Point(segment.start);
+                if meeting != SegmentIntersection::None && !closing {
+                    meetings.push((first, second, meeting));
+                }
+            }
+        }
+        meetings
+    }
+}
+
+impl PathFrame for Polyline {
+    fn path_length(&self) -> f32 {
+        self.length()
+    }
+
+    /// The frame at a distance along the polyline, clamped to its ends.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{path::PathFrame, polygon::Polyline},
+    ///         nalgebra::{point, vector},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let path = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///     ]);
+    ///     let frame = path.frame_at_length(3.0).unwrap();
+    ///
+    ///     assert_relative_eq!(frame.position, point![2.0, 1.0]);
+    ///     assert_relative_eq!(frame.tangent.into_inner(), vector![0.0, 1.0]);
+    ///     assert_relative_eq!(frame.normal.into_inner(), vector![-1.0, 0.0]);
+    ///
+    fn frame_at_length(&self, s: f32) -> Option<Frame> {
+        frame_along_vertices(&self.vertices, false, s)
+    }
+}
+
+impl BoundingBox for Polyline {
+    /// The box around every vertex. A polyline without vertices has an
+    /// inverted box which contains and touches nothing.
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(&self.vertices).unwrap_or_else(Aabb::empty)
+    }
+}
+
+impl BoundingCircle for Polyline {
+    /// The smallest circle around every vertex, from
+    /// [`Circle::enclosing`]. A polyline without vertices has a circle with
+    /// a NaN radius, which contains nothing.
+    fn bounding_circle(&self) -> Circle {
+        Circle::enclosing(&self.vertices).unwrap_or_else(Circle::empty)
+    }
+}
+
+impl BoundingObb for Polyline {
+    /// The smallest oriented box around every vertex, from
+    /// [`Obb::enclosing`]. A polyline without vertices has a box with
+    /// negative extents, which contains nothing.
+    fn bounding_obb(&self) -> Obb {
+        Obb::enclosing(&self.vertices).unwrap_or_else(Obb::empty)
+    }
+}
+
+impl fmt::Display for Polyline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_points(f, &self.vertices, " -> ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::Polyline,
+        crate::{
+            generate::random_simple_polygon,
+            path::{Overrun, PathFrame},
+            shape::Aabb,
+            test_support::point2,
+        },
+        alloc::vec::Vec,
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn figure_eights_should_cross_once() {
+        let figure_eight = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 2.0],
+            point![2.0, 0.0],
+            point![0.0, 2.0],
+            point![0.0, 0.0],
+        ]);
+
+        let crossings = figure_eight.self_intersections();
+        let pieces = figure_eight.split_at_self_intersections();
+
+        assert_eq!(crossings.len(), 1);
+        // Start to the crossing, the loop on the right, and back to the
+        // start from the crossing.
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[0].vertices, [point![0.0, 0.0], point![1.0, 1.0]]);
+        assert_eq!(pieces[1].vertices.len(), 4);
+        assert!(pieces[1].is_closed());
+        assert_eq!(pieces[2].vertices.len(), 3);
+        for piece in &pieces {
+            assert!(piece.self_intersections().is_empty());
+        }
+    }
+
+    #[test]
+    pub fn spikes_should_be_cut_at_their_tips() {
+        let spike = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 3.0],
+            point![2.0, 1.0],
+            point![4.0, 1.0],
+        ]);
+
+        let crossings = spike.self_intersections();
+        let pieces = spike.split_at_self_intersections();
+
+        // The tip, and where the path leaves the spike partway along it.
+        assert_eq!(crossings.len(), 2);
+        assert_eq!(crossings[0].point, point![2.0, 3.0]);
+        assert_eq!(crossings[1].point, point![2.0, 1.0]);
+        assert_eq!(pieces.len(), 4);
+        assert_eq!(pieces[1].vertices, [point![2.0, 1.0], point![2.0, 3.0]]);
+        assert_eq!(pieces[2].vertices, [point![2.0, 3.0], point![2.0, 1.0]]);
+        for piece in &pieces {
+            assert!(piece.self_intersections().is_empty());
+        }
+    }
+
+    #[test]
+    pub fn simple_and_closed_paths_should_stay_whole() {
+        let square = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+            point![0.0, 0.0],
+        ]);
+
+        assert!(square.self_intersections().is_empty());
+        assert_eq!(square.split_at_self_intersections()[0], square);
+        assert!(Polyline::new(vec![])
+            .split_at_self_intersections()
+            .is_empty());
+    }
+
+    #[test]
+    pub fn placements_on_a_segment_should_be_spaced_by_the_advances() {
+        let line = Polyline::new(vec![point![0.0, 1.0], point![10.0, 1.0]]);
+        let advances = [1.0, 2.5, 0.0, 1.5];
+
+        let placements = line.place_along(&advances, 2.0, Overrun::Stop);
+
+        let starts: Vec<f32> =
+            placements.iter().map(|p| p.frame.position.x).collect();
+        assert_eq!(starts, vec![2.0, 3.0, 5.5, 5.5]);
+        for placement in &placements {
+            assert_eq!(placement.frame.position.y, 1.0);
+            assert_eq!(placement.frame.tangent.into_inner(), vector![1.0, 0.0]);
+            assert_eq!(placement.frame.normal.into_inner(), vector![0.0, 1.0]);
+            assert_eq!(placement.chord_ratio, 1.0);
+        }
+    }
+
+    #[test]
+    pub fn placements_should_extend_past_either_end() {
+        let line = Polyline::new(vec![point![0.0, 0.0], point![0.0, 2.0]]);
+
+        let before = line.place_along(&[1.0, 1.0], -1.5, Overrun::Stop);
+        let after = line.place_along(&[1.0, 1.0, 1.0], 0.5, Overrun::Extend);
+
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[0].frame.position, point![0.0, -1.5]);
+        assert_eq!(before[0].distance, -1.5);
+        assert_eq!(after[2].frame.position, point![0.0, 2.5]);
+        assert_eq!(after[2].frame.tangent.into_inner(), vector![0.0, 1.0]);
+        assert_eq!(line.place_along(&[1.0; 3], 0.5, Overrun::Stop).len(), 1);
+
+        let point = Polyline::new(vec![point![1.0, 1.0], point![1.0, 1.0]]);
+        assert!(point.place_along(&[1.0], 0.0, Overrun::Extend).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn placements_should_consume_the_advances(
+            vertices in prop::collection::vec(point2(-10.0..=10.0), 2..8),
+            advances in prop::collection::vec(0.0f32..=2.0, 1..10),
+            start_offset in 0.0f32..=5.0,
+        ) {
+            let path = Polyline::new(vertices);
+            prop_assume!(path.length() > 0.0);
+
+            let placements =
+                path.place_along(&advances, start_offset, Overrun::Extend);
+
+            prop_assert_eq!(placements.len(), advances.len());
+            let mut origin = start_offset;
+            for (placement, &advance) in placements.iter().zip(&advances) {
+                // Chords can't be longer than the arcs they cut across.
+                prop_assert!(placement.chord_ratio * advance <= advance + 1e-4);
+                // Each item starts where the advances so far end on the
+                // path, and its chord ends where its own advance does.
+                let end = placement.frame.position
+                    + placement.frame.tangent.into_inner()
+                        * (placement.chord_ratio * advance);
+                let start = placement.frame.position;
+                for (distance, point) in
+                    [(origin, start), (origin + advance, end)]
+                {
+                    if let Some(frame) = path
+                        .frame_at_length(distance)
+                        .filter(|_| distance <= path.length())
+                    {
+                        prop_assert!(
+                            (point - frame.position).norm() <= 1e-3,
+                            "{} is not {}", point, frame.position
+                        );
+                    }
+                }
+                origin += advance;
+            }
+        }
+
+        #[test]
+        fn split_pieces_should_not_cross_themselves(
+            vertices in prop::collection::vec(point2(-10.0..=10.0), 2..12),
+        ) {
+            let path = Polyline::new(vertices);
+
+            let pieces = path.split_at_self_intersections();
+
+            let total: f32 = pieces.iter().map(Polyline::length).sum();
+            prop_assert!((total - path.length()).abs() < 1e-3);
+            for pair in pieces.windows(2) {
+                prop_assert_eq!(pair[0].vertices.last(), pair[1].vertices.first());
+            }
+            for piece in &pieces {
+                // Rounding at the cuts can leave crossings right next to
+                // the ends, so only look for ones well inside the piece.
+                for crossing in piece.self_intersections() {
+                    let ends = [piece.vertices[0], *piece.vertices.last().unwrap()];
+                    prop_assert!(
+                        ends.iter().any(|end| (end - crossing.point).norm() < 1e-3),
+                        "{:?} in {}", crossing, piece
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn simple_polygon_outlines_should_not_intersect(seed in any::<u64>()) {
+            let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+            let polygon = random_simple_polygon(12, &bounds, seed);
+            let mut outline: Vec<_> = polygon.vertices.clone();
+            outline.push(outline[0]);
+
+            prop_assert!(Polyline::new(outline).self_intersections().is_empty());
+        }
+    }
+}
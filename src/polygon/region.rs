@@ -0,0 +1,557 @@
+use {
+    crate::{
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            DistanceToPoint,
+        },
+        math::sqrt,
+        polygon::{triangulate::ear_clip, Polygon},
+        Error,
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// A polygon with holes.
+///
+/// The region is everything inside the outer boundary which isn't inside
+/// one of the holes. Regions built with [`Region::new`] or
+/// [`Region::try_new`] have a counterclockwise outer boundary and clockwise
+/// holes, so every boundary has the region on its left. The fields are
+/// public, but nothing here depends on the windings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    /// The boundary around the whole region.
+    pub outer: Polygon,
+
+    /// The polygons cut out of the region.
+    pub holes: Vec<Polygon>,
+}
+
+impl Region {
+    /// Create a new region, winding the outer boundary counterclockwise and
+    /// the holes clockwise.
+    ///
+    /// The holes aren't validated. Use [`Region::try_new`] to check that they
+    /// are inside the outer boundary and don't overlap.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::{Polygon, Region},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = |min: f32, max: f32| {
+    ///         Polygon::new(vec![
+    ///             point![min, min],
+    ///             point![max, min],
+    ///             point![max, max],
+    ///             point![min, max],
+    ///         ])
+    ///     };
+    ///
+    ///     let frame = Region::new(square(0.0, 4.0), vec![square(1.0, 3.0)]);
+    ///
+    ///     assert_eq!(frame.area(), 12.0);
+    ///     assert!(frame.holes[0].signed_area() < 0.0);
+    ///
+    pub fn new(mut outer: Polygon, mut holes: Vec<Polygon>) -> Self {
+        if outer.signed_area() < 0.0 {
+            outer.vertices.reverse();
+        }
+        for hole in &mut holes {
+            if hole.signed_area() > 0.0 {
+                hole.vertices.reverse();
+            }
+        }
+        Self { outer, holes }
+    }
+
+    /// Create a new region, checking that every boundary is a valid polygon,
+    /// that the holes are strictly inside the outer boundary, and that the
+    /// holes don't overlap or touch each other.
+    ///
+    /// Holes are compared edge by edge, so this takes O(n²) time in the
+    /// total number of vertices.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{polygon::{Polygon, Region}, Error},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = |min: f32, max: f32| {
+    ///         Polygon::new(vec![
+    ///             point![min, min],
+    ///             point![max, min],
+    ///             point![max, max],
+    ///             point![min, max],
+    ///         ])
+    ///     };
+    ///
+    ///     assert_eq!(
+    ///         Region::try_new(square(0.0, 4.0), vec![square(3.0, 5.0)]),
+    ///         Err(Error::HoleOutsideBoundary)
+    ///     );
+    ///     assert_eq!(
+    ///         Region::try_new(
+    ///             square(0.0, 4.0),
+    ///             vec![square(1.0, 2.0), square(1.5, 3.0)]
+    ///         ),
+    ///         Err(Error::OverlappingHoles)
+    ///     );
+    ///
+    pub fn try_new(outer: Polygon, holes: Vec<Polygon>) -> Result<Self, Error> {
+        let outer = Polygon::try_new(outer.vertices)?;
+        let holes = holes
+            .into_iter()
+            .map(|hole| Polygon::try_new(hole.vertices))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (i, hole) in holes.iter().enumerate() {
+            if boundaries_touch(&outer, hole)
+                || !outer.contains_point(&hole.vertices[0])
+            {
+                return Err(Error::HoleOutsideBoundary);
+            }
+            for other in &holes[..i] {
+                if boundaries_touch(hole, other)
+                    || hole.contains_point(&other.vertices[0])
+                    || other.contains_point(&hole.vertices[0])
+                {
+                    return Err(Error::OverlappingHoles);
+                }
+            }
+        }
+        Ok(Self::new(outer, holes))
+    }
+
+    /// The area inside the outer boundary minus the area of the holes.
+    pub fn area(&self) -> f32 {
+        self.outer.area() - self.holes.iter().map(Polygon::area).sum::<f32>()
+    }
+
+    /// Iterate over every vertex in the region: the outer boundary's
+    /// vertices followed by each hole's vertices, in order.
+    ///
+    /// The triangles from [`Region::triangulate`] index into this sequence.
+    pub fn vertices(&self) -> impl Iterator<Item = &Point2<f32>> + '_ {
+        self.outer
+            .vertices
+            .iter()
+            .chain(self.holes.iter().flat_map(|hole| hole.vertices.iter()))
+    }
+
+    /// Returns true when the point is inside the outer boundary and outside
+    /// every hole.
+    ///
+    /// Points exactly on a boundary may be reported as either inside or
+    /// outside, like [`Polygon::contains_point`].
+    pub fn contains_point(&self, point: &Point2<f32>) -> bool {
+        self.outer.contains_point(point)
+            && !self.holes.iter().any(|hole| hole.contains_point(point))
+    }
+
+    /// Split the region into triangles.
+    ///
+    /// Each hole is joined to the outer boundary by a bridge, a pair of
+    /// edges running to and from a vertex which can see the hole's rightmost
+    /// vertex. This turns the region into a single boundary which touches
+    /// itself along the bridges, which is then ear clipped.
+    ///
+    /// Each triangle is a triple of indices into [`Region::vertices`] and is
+    /// wound counterclockwise. A region with `n` vertices and `h` holes
+    /// produces `n + 2h - 2` triangles whose areas sum to the region's area.
+    /// The region should be valid, as checked by [`Region::try_new`].
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::{Polygon, Region},
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let square = |min: f32, max: f32| {
+    ///         Polygon::new(vec![
+    ///             point![min, min],
+    ///             point![max, min],
+    ///             point![max, max],
+    ///             point![min, max],
+    ///         ])
+    ///     };
+    ///     let frame = Region::new(square(0.0, 4.0), vec![square(1.0, 3.0)]);
+    ///
+    ///     let vertices: Vec<_> = frame.vertices().collect();
+    ///     let triangles = frame.triangulate();
+    ///
+    ///     assert_eq!(triangles.len(), 8);
+    ///     let area: f32 = triangles
+    ///         .iter()
+    ///         .map(|&[a, b, c]| {
+    ///             (vertices[b] - vertices[a]).perp(&(vertices[c] - vertices[a]))
+    ///                 / 2.0
+    ///         })
+    ///         .sum();
+    ///     assert_relative_eq!(area, 12.0);
+    ///
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let vertices: Vec<Point2<f32>> = self.vertices().copied().collect();
+        if self.outer.vertices.len() < 3 {
+            return Vec::new();
+        }
+
+        let mut ring = ring(&self.outer, 0, true);
+        let mut holes = Vec::with_capacity(self.holes.len());
+        let mut offset = self.outer.vertices.len();
+        for hole in &self.holes {
+            if hole.vertices.len() >= 3 {
+                holes.push(self::ring(hole, offset, false));
+            }
+            offset += hole.vertices.len();
+        }
+
+        // Bridging the rightmost holes first means a bridge never has to
+        // pass through a hole which hasn't been joined to the ring yet.
+        let rightmost = |hole: &[usize]| {
+            (0..hole.len())
+                .max_by(|&a, &b| {
+                    let (a, b) = (vertices[hole[a]], vertices[hole[b]]);
+                    a.x.total_cmp(&b.x).then(b.y.total_cmp(&a.y))
+                })
+                .unwrap()
+        };
+        holes.sort_by(|a, b| {
+            let (a, b) = (vertices[a[rightmost(a)]], vertices[b[rightmost(b)]]);
+            b.x.total_cmp(&a.x)
+        });
+        for hole in &holes {
+            let start = rightmost(hole);
+            let Some(position) = bridge(&vertices, &ring, hole[start]) else {
+                continue;
+            };
+            let mut spliced = Vec::with_capacity(hole.len() + 2);
+            spliced.extend_from_slice(&hole[start..]);
+            spliced.extend_from_slice(&hole[..start]);
+            spliced.push(hole[start]);
+            spliced.push(ring[position]);
+            ring.splice(position + 1..position + 1, spliced);
+        }
+
+        ear_clip(&vertices, ring)
+    }
+}
+
+impl DistanceToPoint for Region {
+    /// Compute the signed distance from the region's boundary to a point.
+    ///
+    /// The distance is negative for points inside the region and positive
+    /// for points outside it, including points inside a hole.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             line::DistanceToPoint,
+    ///             polygon::{Polygon, Region},
+    ///         },
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let square = |min: f32, max: f32| {
+    ///         Polygon::new(vec![
+    ///             point![min, min],
+    ///             point![max, min],
+    ///             point![max, max],
+    ///             point![min, max],
+    ///         ])
+    ///     };
+    ///     let frame = Region::new(square(0.0, 6.0), vec![square(2.0, 4.0)]);
+    ///
+    ///     assert_relative_eq!(frame.distance_to_point(&point![1.0, 3.0]), -1.0);
+    ///     assert_relative_eq!(frame.distance_to_point(&point![3.0, 3.0]), 1.0);
+    ///     assert_relative_eq!(frame.distance_to_point(&point![8.0, 3.0]), 2.0);
+    ///
+    fn distance_to_point(&self, point: &Point2<f32>) -> f32 {
+        let distance = sqrt(self.distance_to_point_squared(point));
+        if self.contains_point(point) {
+            -distance
+        } else {
+            distance
+        }
+    }
+
+    /// The squared distance from the region's boundary to a point.
+    ///
+    /// The value is always positive, so compare against
+    /// [`Region::contains_point`] to tell inside from outside.
+    fn distance_to_point_squared(&self, point: &Point2<f32>) -> f32 {
+        core::iter::once(&self.outer)
+            .chain(&self.holes)
+            .flat_map(Polygon::edges)
+            .map(|edge| edge.distance_to_point_squared(point))
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+/// The indices of a polygon's vertices, offset into the region's vertices
+/// and ordered counterclockwise or clockwise.
+fn ring(
+    polygon: &Polygon,
+    offset: usize,
+    counterclockwise: bool,
+) -> Vec<usize> {
+    let mut ring: Vec<usize> =
+        (offset..offset + polygon.vertices.len()).collect();
+    if (polygon.signed_area() < 0.0) == counterclockwise {
+        ring.reverse();
+    }
+    ring
+}
+
+/// Returns true when any edge of one polygon touches any edge of the other.
+fn boundaries_touch(a: &Polygon, b: &Polygon) -> bool {
+    a.edges().any(|edge| {
+        b.edges().any(|other| {
+            intersect_segments(&edge, &other) != SegmentIntersection::None
+        })
+    })
+}
+
+/// Positive when a, b, c turn counterclockwise and negative when they turn
+/// clockwise.
+fn cross(a: &Point2<f32>, b: &Point2<f32>, c: &Point2<f32>) -> f32 {
+    (b - a).perp(&(c - a))
+}
+
+/// Find the position of a ring vertex which can see the hole vertex `m`.
+///
+/// This is the bridge search from David Eberly's "Triangulation by Ear
+/// Clipping". A ray from `m` towards +x hits the nearest edge of the ring at
+/// `i`. The edge's rightmost endpoint `p` is visible unless other ring
+/// vertices are inside the triangle `m, i, p`, in which case the one with
+/// the smallest angle to the ray is visible instead.
+///
+/// Vertices appear more than once in a ring after earlier bridges, so only
+/// positions whose interior wedge opens towards `m` are considered.
+fn bridge(vertices: &[Point2<f32>], ring: &[usize], m: usize) -> Option<usize> {
+    let n = ring.len();
+    let point = |position: usize| vertices[ring[position % n]];
+    let m = vertices[m];
+
+    let mut hit: Option<(f32, usize)> = None;
+    for position in 0..n {
+        let (a, b) = (point(position), point(position + 1));
+        if a.y == b.y || m.y < a.y.min(b.y) || m.y > a.y.max(b.y) {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x >= m.x && hit.is_none_or(|(best, _)| x < best) {
+            let end = if b.x > a.x { position + 1 } else { position };
+            hit = Some((x, end % n));
+        }
+    }
+    let (x, candidate) = hit?;
+    let (i, p) = (Point2::new(x, m.y), point(candidate));
+
+    let opens_towards_m = |position: usize| {
+        let prev = point(position + n - 1);
+        let (here, next) = (point(position), point(position + 1));
+        let (before, after) = (
+            cross(&prev, &here, &m) >= 0.0,
+            cross(&here, &next, &m) >= 0.0,
+        );
+        if cross(&prev, &here, &next) >= 0.0 {
+            before && after
+        } else {
+            before || after
+        }
+    };
+    let in_triangle = |r: &Point2<f32>| {
+        let sides = [cross(&m, &i, r), cross(&i, &p, r), cross(&p, &m, r)];
+        !(sides.iter().any(|&s| s < 0.0) && sides.iter().any(|&s| s > 0.0))
+    };
+    let slope = |r: &Point2<f32>| (r.y - m.y).abs() / (r.x - m.x);
+
+    (0..n)
+        .filter(|&position| {
+            let r = point(position);
+            r.x > m.x && in_triangle(&r) && opens_towards_m(position)
+        })
+        .min_by(|&a, &b| {
+            let (ra, rb) = (point(a), point(b));
+            slope(&ra).total_cmp(&slope(&rb)).then(
+                (ra - m).norm_squared().total_cmp(&(rb - m).norm_squared()),
+            )
+        })
+        .or(Some(candidate))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::DistanceToPoint,
+            polygon::{Polygon, Region},
+            Error,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Polygon {
+        Polygon::new(vec![
+            point![x0, y0],
+            point![x1, y0],
+            point![x1, y1],
+            point![x0, y1],
+        ])
+    }
+
+    fn triangle_areas(region: &Region) -> Vec<f32> {
+        let vertices: Vec<Point2<f32>> = region.vertices().copied().collect();
+        region
+            .triangulate()
+            .iter()
+            .map(|&[a, b, c]| {
+                (vertices[b] - vertices[a]).perp(&(vertices[c] - vertices[a]))
+                    / 2.0
+            })
+            .collect()
+    }
+
+    fn assert_covers_exactly(region: &Region) {
+        let vertex_count = region.vertices().count();
+        let areas = triangle_areas(region);
+
+        assert_eq!(areas.len(), vertex_count + 2 * region.holes.len() - 2);
+        assert!(areas.iter().all(|&area| area > 0.0));
+        assert_relative_eq!(
+            areas.iter().sum::<f32>(),
+            region.area(),
+            max_relative = 1e-5
+        );
+    }
+
+    #[test]
+    pub fn square_with_a_square_hole_should_subtract_the_hole() {
+        let region = Region::try_new(
+            rect(0.0, 0.0, 10.0, 10.0),
+            vec![rect(4.0, 4.0, 6.0, 6.0)],
+        )
+        .unwrap();
+
+        assert_relative_eq!(region.area(), 96.0);
+        assert!(region.contains_point(&point![1.0, 1.0]));
+        assert!(!region.contains_point(&point![5.0, 5.0]));
+        assert!(region.distance_to_point(&point![5.0, 5.0]) > 0.0);
+        assert!(!region.contains_point(&point![11.0, 5.0]));
+        assert_covers_exactly(&region);
+    }
+
+    #[test]
+    pub fn windings_should_be_normalized() {
+        let mut outer = rect(0.0, 0.0, 10.0, 10.0);
+        outer.vertices.reverse();
+        let mut hole = rect(1.0, 1.0, 2.0, 2.0);
+        hole.vertices.reverse();
+
+        let region = Region::new(outer, vec![hole, rect(4.0, 4.0, 5.0, 5.0)]);
+
+        assert!(region.outer.signed_area() > 0.0);
+        assert!(region.holes.iter().all(|hole| hole.signed_area() < 0.0));
+        assert_covers_exactly(&region);
+    }
+
+    #[test]
+    pub fn try_new_should_reject_invalid_holes() {
+        let outer = rect(0.0, 0.0, 10.0, 10.0);
+
+        assert_eq!(
+            Region::try_new(outer.clone(), vec![rect(20.0, 0.0, 21.0, 1.0)]),
+            Err(Error::HoleOutsideBoundary)
+        );
+        assert_eq!(
+            Region::try_new(outer.clone(), vec![rect(0.0, 2.0, 1.0, 3.0)]),
+            Err(Error::HoleOutsideBoundary)
+        );
+        assert_eq!(
+            Region::try_new(
+                outer.clone(),
+                vec![rect(1.0, 1.0, 8.0, 8.0), rect(2.0, 2.0, 3.0, 3.0),]
+            ),
+            Err(Error::OverlappingHoles)
+        );
+        assert_eq!(
+            Region::try_new(
+                outer.clone(),
+                vec![rect(1.0, 1.0, 2.0, 2.0), rect(2.0, 1.0, 3.0, 2.0),]
+            ),
+            Err(Error::OverlappingHoles)
+        );
+        assert_eq!(
+            Region::try_new(outer, vec![Polygon::new(vec![])]),
+            Err(Error::TooFewVertices(3))
+        );
+    }
+
+    #[test]
+    pub fn bridges_should_pass_between_holes() {
+        // The holes to the left can only see the outer boundary through the
+        // gaps between the holes to their right.
+        let holes = (0..3)
+            .flat_map(|row| {
+                (0..3).map(move |col| {
+                    let (x, y) =
+                        (1.0 + 3.0 * col as f32, 1.0 + 3.0 * row as f32);
+                    rect(x, y, x + 2.0, y + 2.0)
+                })
+            })
+            .collect();
+
+        let region =
+            Region::try_new(rect(0.0, 0.0, 10.0, 10.0), holes).unwrap();
+
+        assert_relative_eq!(region.area(), 64.0);
+        assert_covers_exactly(&region);
+    }
+
+    proptest! {
+        #[test]
+        fn triangulations_should_cover_the_region(
+            cells in prop::collection::vec(any::<bool>(), 16),
+            jitter in prop::collection::vec(0.0f32..0.4, 16),
+        ) {
+            let holes: Vec<Polygon> = cells
+                .iter()
+                .zip(&jitter)
+                .enumerate()
+                .filter(|(_, (&cell, _))| cell)
+                .map(|(index, (_, &jitter))| {
+                    let (x, y) = ((index % 4) as f32, (index / 4) as f32);
+                    rect(
+                        1.0 + 2.0 * x + jitter,
+                        1.0 + 2.0 * y,
+                        2.0 + 2.0 * x,
+                        2.0 + 2.0 * y - jitter,
+                    )
+                })
+                .collect();
+            let outer = Polygon::new(vec![
+                point![0.0, 0.0],
+                point![9.0, 0.0],
+                point![9.0, 9.0],
+                point![4.5, 8.5],
+                point![0.0, 9.0],
+            ]);
+
+            let region = Region::try_new(outer, holes).unwrap();
+
+            assert_covers_exactly(&region);
+        }
+    }
+}
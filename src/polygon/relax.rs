@@ -0,0 +1,318 @@
+use {
+    crate::{math, polygon::Polygon},
+    alloc::vec::Vec,
+    nalgebra::{center, Point2},
+};
+
+impl Polygon {
+    /// Smooth the polygon by moving each vertex toward the midpoint of its
+    /// neighbors, for organic blobs.
+    ///
+    /// Each iteration moves every vertex `strength` of the way to the
+    /// midpoint of the vertices before and after it, using the positions
+    /// from the previous iteration. A strength of 0 does nothing and 1
+    /// jumps straight to the midpoints. Plain smoothing like this shrinks
+    /// the polygon, so when `preserve_area` is set the polygon is scaled
+    /// about its centroid after each iteration to restore its original
+    /// signed area. Sharp features round off first, and given enough
+    /// iterations the polygon becomes convex.
+    ///
+    /// When the polygon starts out simple, an iteration which would make
+    /// it cross itself is rolled back and the smoothing stops there, so
+    /// the result is always simple too. This mostly happens with strengths
+    /// of 1 or more, which can fold thin features over their neighbors.
+    /// Polygons with fewer than three vertices are returned unchanged.
+    ///
+    /// See [`Polygon::relax_pinned`] to hold some vertices in place.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::generate::star,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let star = star(point![0.0, 0.0], 4.0, 1.0, 5, 0.0);
+    ///
+    ///     let blob = star.relax(50, 0.5, true);
+    ///     let shrunk = star.relax(50, 0.5, false);
+    ///
+    ///     assert!(blob.is_convex(0.0));
+    ///     assert_relative_eq!(blob.area(), star.area(), max_relative = 1e-4);
+    ///     assert!(shrunk.area() < 0.5 * star.area());
+    ///
+    pub fn relax(
+        &self,
+        iterations: usize,
+        strength: f32,
+        preserve_area: bool,
+    ) -> Polygon {
+        self.relax_pinned(iterations, strength, preserve_area, &[])
+    }
+
+    /// Smooth the polygon like [`Polygon::relax`], but leave the vertices
+    /// at the `pinned` indices where they are.
+    ///
+    /// Pinned vertices still pull on their neighbors. When `preserve_area`
+    /// is set only the free vertices are scaled about the centroid, by
+    /// whatever factor restores the original area, so the pins don't move.
+    /// When no factor can restore the area, such as when every vertex is
+    /// pinned, the iteration isn't scaled at all. Indices which aren't
+    /// vertices are ignored.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let notched = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 4.0],
+    ///         point![2.0, 1.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     let relaxed = notched.relax_pinned(20, 0.5, true, &[0, 1]);
+    ///
+    ///     assert_eq!(relaxed.vertices[0], point![0.0, 0.0]);
+    ///     assert_eq!(relaxed.vertices[1], point![4.0, 0.0]);
+    ///     assert_relative_eq!(
+    ///         relaxed.area(),
+    ///         notched.area(),
+    ///         max_relative = 1e-4
+    ///     );
+    ///
+    pub fn relax_pinned(
+        &self,
+        iterations: usize,
+        strength: f32,
+        preserve_area: bool,
+        pinned: &[usize],
+    ) -> Polygon {
+        let n = self.vertices.len();
+        if n < 3 {
+            return self.clone();
+        }
+        let mut is_pinned = vec![false; n];
+        for &index in pinned.iter().filter(|&&index| index < n) {
+            is_pinned[index] = true;
+        }
+        let target = self.signed_area();
+        let check_simple = self.is_simple();
+
+        let mut current = self.clone();
+        for _ in 0..iterations {
+            let vertices = &current.vertices;
+            let mut next = Polygon::new(
+                (0..n)
+                    .map(|i| {
+                        let vertex = vertices[i];
+                        if is_pinned[i] {
+                            return vertex;
+                        }
+                        let midpoint = center(
+                            &vertices[(i + n - 1) % n],
+                            &vertices[(i + 1) % n],
+                        );
+                        vertex + (midpoint - vertex) * strength
+                    })
+                    .collect(),
+            );
+            if preserve_area {
+                restore_area(&mut next, &is_pinned, target);
+            }
+            if check_simple && !next.is_simple() {
+                break;
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+/// Scale the unpinned vertices about the centroid so the polygon's signed
+/// area is `target`.
+///
+/// Measured from the centroid, scaling the free vertices by `s` makes the
+/// area a quadratic `a s² + b s + c`: pairs of free vertices contribute to
+/// `a`, mixed pairs to `b`, and pairs of pinned vertices to `c`. The root
+/// nearest 1 is used, since it changes the polygon the least.
+fn restore_area(polygon: &mut Polygon, is_pinned: &[bool], target: f32) {
+    let origin = polygon.centroid();
+    let offsets: Vec<_> = polygon
+        .vertices
+        .iter()
+        .map(|vertex| vertex - origin)
+        .collect();
+    let n = offsets.len();
+    let (mut a, mut b, mut c) = (0.0, 0.0, 0.0);
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let cross = offsets[i].perp(&offsets[j]) / 2.0;
+        match (is_pinned[i], is_pinned[j]) {
+            (false, false) => a += cross,
+            (true, true) => c += cross,
+            _ => b += cross,
+        }
+    }
+
+    let Some(scale) = nearest_root(a, b, c - target) else {
+        return;
+    };
+    for (i, vertex) in polygon.vertices.iter_mut().enumerate() {
+        if !is_pinned[i] {
+            *vertex = Point2::from(origin.coords + offsets[i] * scale);
+        }
+    }
+}
+
+/// The positive root of `a x² + b x + c` which is nearest to 1, if there
+/// is one.
+fn nearest_root(a: f32, b: f32, c: f32) -> Option<f32> {
+    let roots = if a.abs() <= f32::EPSILON * b.abs().max(c.abs()) {
+        // Effectively linear.
+        [-c / b, f32::NAN]
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = math::sqrt(discriminant);
+        [(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+    };
+    roots
+        .into_iter()
+        .filter(|root| root.is_finite() && *root > 0.0)
+        .min_by(|x, y| (x - 1.0).abs().total_cmp(&(y - 1.0).abs()))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{generate::star, polygon::Polygon, test_support::polygon},
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    /// A bar with two thin teeth sticking up from it.
+    fn comb() -> Polygon {
+        Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 1.0],
+            point![9.0, 1.0],
+            point![8.0, 3.0],
+            point![7.0, 1.0],
+            point![3.0, 1.0],
+            point![2.0, 3.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ])
+    }
+
+    fn deepest_defect(polygon: &Polygon) -> f32 {
+        polygon
+            .convexity_defects()
+            .iter()
+            .map(|defect| defect.depth)
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    pub fn area_should_be_preserved_over_many_iterations() {
+        let mut clockwise = comb();
+        clockwise.vertices.reverse();
+
+        for shape in
+            [star(point![1.0, 2.0], 4.0, 1.0, 5, 0.3), comb(), clockwise]
+        {
+            let relaxed = shape.relax(100, 0.5, true);
+
+            assert_relative_eq!(
+                relaxed.signed_area(),
+                shape.signed_area(),
+                max_relative = 1e-4
+            );
+            assert!(relaxed.is_simple());
+            assert!(shape.relax(100, 0.5, false).area() < shape.area());
+        }
+    }
+
+    #[test]
+    pub fn stars_should_relax_into_convex_blobs() {
+        let mut blob = star(point![0.0, 0.0], 4.0, 1.0, 5, 0.0);
+        let mut depths = vec![deepest_defect(&blob)];
+        while !blob.is_convex(0.0) && depths.len() < 100 {
+            blob = blob.relax(1, 0.5, true);
+            depths.push(deepest_defect(&blob));
+        }
+
+        assert!(blob.is_convex(0.0));
+        for pair in depths.windows(2) {
+            assert!(pair[1] < pair[0], "{depths:?}");
+        }
+        assert_eq!(*depths.last().unwrap(), 0.0);
+    }
+
+    #[test]
+    pub fn pinned_vertices_should_stay_put() {
+        let shape = star(point![0.0, 0.0], 4.0, 1.0, 5, 0.0);
+        let pinned = [0, 2, 5, 99];
+
+        let relaxed = shape.relax_pinned(100, 0.5, true, &pinned);
+
+        for i in [0, 2, 5] {
+            assert_eq!(relaxed.vertices[i], shape.vertices[i]);
+        }
+        assert_ne!(relaxed.vertices[1], shape.vertices[1]);
+        assert_relative_eq!(
+            relaxed.signed_area(),
+            shape.signed_area(),
+            max_relative = 1e-4
+        );
+        let all: Vec<usize> = (0..10).collect();
+        assert_eq!(shape.relax_pinned(10, 0.5, true, &all), shape);
+    }
+
+    #[test]
+    pub fn crossing_iterations_should_be_rolled_back() {
+        // Jumping straight to the midpoints folds the teeth over the bar.
+        let comb = comb();
+
+        assert_eq!(comb.relax(10, 1.0, true), comb);
+        let shape = star(point![0.0, 0.0], 4.0, 1.0, 5, 0.0);
+        for strength in [1.5, 2.0] {
+            assert!(shape.relax(10, strength, true).is_simple());
+        }
+        let line = Polygon::new(vec![point![0.0, 0.0], point![1.0, 0.0]]);
+        assert_eq!(line.relax(10, 0.5, true), line);
+    }
+
+    proptest! {
+        #[test]
+        fn relaxed_polygons_should_keep_their_area(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            iterations in 0usize..=20,
+            strength in 0.0f32..=0.5,
+        ) {
+            prop_assume!(polygon.area() > 1.0);
+
+            let relaxed = polygon.relax(iterations, strength, true);
+
+            prop_assert_eq!(relaxed.vertices.len(), polygon.vertices.len());
+            prop_assert!(relaxed.is_simple());
+            assert_relative_eq!(
+                relaxed.signed_area(),
+                polygon.signed_area(),
+                max_relative = 1e-3
+            );
+        }
+    }
+}
@@ -0,0 +1,501 @@
+use {
+    crate::{
+        convention::{AxisConvention, Orientation},
+        convert::{points_from_flat_slice, points_to_flat_iter},
+        display::write_points,
+        error::check_finite,
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            Segment,
+        },
+        path::{frame_along_vertices, Frame, PathFrame},
+        query::extreme_point,
+        shape::{
+            Aabb, BoundingBox, BoundingCircle, BoundingObb, Circle, Obb,
+            SupportMap,
+        },
+        Error,
+    },
+    alloc::vec::Vec,
+    core::fmt,
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+/// A closed polygon defined by a sequence of vertices.
+///
+/// There is an implicit edge from the last vertex back to the first, so the
+/// first vertex should not be repeated at the end. Counterclockwise polygons
+/// have a positive signed area and clockwise polygons have a negative signed
+/// area.
+///
+/// Polygons are displayed as a list of vertices, like
+/// `[(0, 0), (1, 0), (0, 1)]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    /// The polygon's vertices, in order around the boundary.
+    pub vertices: Vec<Point2<f32>>,
+}
+
+impl Polygon {
+    /// Create a new polygon from a sequence of vertices.
+    ///
+    /// The vertices aren't validated. Use [`Polygon::try_new`] to reject
+    /// polygons with too few vertices or non-finite coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```none
+    /// +----------------------------------------------------+
+    /// |    ^                                               |
+    /// |  4 +                                               |
+    /// |    |                                               |
+    /// |  3 +     (1, 3)+---------+(4, 3)                   |
+    /// |    |           |         |                         |
+    /// |  2 +           |         |                         |
+    /// |    |           |         |                         |
+    /// |  1 +     (1, 1)+---------+(4, 1)                   |
+    /// |    |                                               |
+    /// |x---+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+> |
+    /// |    |  1  2  3  4  5  6  7  8  9  10 11 12 13 14    |
+    /// |  y |                                               |
+    /// +----------------------------------------------------+
+    /// ```
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let rectangle = Polygon::new(vec![
+    ///         point![1.0, 1.0],
+    ///         point![4.0, 1.0],
+    ///         point![4.0, 3.0],
+    ///         point![1.0, 3.0],
+    ///     ]);
+    ///
+    pub fn new(vertices: Vec<Point2<f32>>) -> Self {
+        Self { vertices }
+    }
+
+    /// Create a new polygon, checking that it has at least three vertices
+    /// and that every coordinate is finite.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{polygon::Polygon, Error},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     assert_eq!(
+    ///         Polygon::try_new(vec![point![0.0, 0.0], point![1.0, 0.0]]),
+    ///         Err(Error::TooFewVertices(3))
+    ///     );
+    ///
+    pub fn try_new(vertices: Vec<Point2<f32>>) -> Result<Self, Error> {
+        if vertices.len() < 3 {
+            return Err(Error::TooFewVertices(3));
+        }
+        for vertex in &vertices {
+            check_finite(&[vertex.x, vertex.y])?;
+        }
+        Ok(Self::new(vertices))
+    }
+
+    /// Create a polygon from interleaved `[x0, y0, x1, y1, ...]`
+    /// coordinates.
+    ///
+    /// Returns [`Error::OddCoordinateCount`] when the slice has an odd
+    /// length. When `strict` is true, coordinates which are infinite or NaN
+    /// are rejected with [`Error::NotFinite`]. The number of vertices isn't
+    /// checked. See [`points_from_flat_slice`] for details.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{polygon::Polygon, Error},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let flat = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+    ///     let triangle = Polygon::from_flat_slice(&flat, true)?;
+    ///
+    ///     assert_eq!(triangle.vertices[1], point![1.0, 0.0]);
+    ///     assert_eq!(triangle.to_flat_vec(), flat);
+    ///     assert!(Polygon::from_flat_slice(&[0.0, f32::NAN], true).is_err());
+    ///     # Ok::<(), Error>(())
+    ///
+    pub fn from_flat_slice(
+        coordinates: &[f32],
+        strict: bool,
+    ) -> Result<Self, Error> {
+        Ok(Self::new(points_from_flat_slice(coordinates, strict)?))
+    }
+
+    /// The vertices' interleaved coordinates, the inverse of
+    /// [`Polygon::from_flat_slice`].
+    pub fn to_flat_vec(&self) -> Vec<f32> {
+        points_to_flat_iter(&self.vertices).collect()
+    }
+
+    /// Iterate over the polygon's edges, including the closing edge from the
+    /// last vertex back to the first.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, polygon::Polygon},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let triangle = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///     let edges: Vec<Segment> = triangle.edges().collect();
+    ///
+    ///     assert_eq!(edges.len(), 3);
+    ///     assert_eq!(
+    ///         edges[2],
+    ///         Segment::new(point![0.0, 1.0], point![0.0, 0.0])
+    ///     );
+    ///
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| {
+            Segment::new(self.vertices[i], self.vertices[(i + 1) % n])
+        })
+    }
+
+    /// The signed area enclosed by the polygon.
+    ///
+    /// The area is positive when the vertices are in counterclockwise order
+    /// and negative when they're in clockwise order.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let mut square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![0.0, 2.0],
+    ///     ]);
+    ///     assert_relative_eq!(square.signed_area(), 4.0);
+    ///
+    ///     square.vertices.reverse();
+    ///     assert_relative_eq!(square.signed_area(), -4.0);
+    ///
+    pub fn signed_area(&self) -> f32 {
+        let Some(origin) = self.vertices.first() else {
+            return 0.0;
+        };
+        // Measure relative to the first vertex to reduce cancellation error
+        // for polygons far from the origin.
+        let twice_area: f32 = self
+            .edges()
+            .map(|edge| (edge.start - origin).perp(&(edge.end - origin)))
+            .sum();
+        twice_area * 0.5
+    }
+
+    /// The winding of the vertices in the crate's y-up convention, from the
+    /// sign of [`Polygon::signed_area`].
+    pub fn orientation(&self) -> Orientation {
+        self.orientation_with_convention(AxisConvention::YUp)
+    }
+
+    /// The winding of the vertices as seen in a convention.
+    ///
+    /// The same vertices wind the other way when y points down, so a
+    /// polygon which is counterclockwise in the crate's math is drawn
+    /// clockwise on a canvas.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             convention::{AxisConvention, Orientation},
+    ///             polygon::Polygon,
+    ///         },
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let triangle = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///
+    ///     assert_eq!(triangle.orientation(), Orientation::Counterclockwise);
+    ///     assert_eq!(
+    ///         triangle.orientation_with_convention(AxisConvention::YDown),
+    ///         Orientation::Clockwise
+    ///     );
+    ///
+    pub fn orientation_with_convention(
+        &self,
+        convention: AxisConvention,
+    ) -> Orientation {
+        convention.orientation_of(self.signed_area())
+    }
+
+    /// Linearly interpolate between two polygons.
+    ///
+    /// Each vertex is interpolated toward the vertex with the same index in
+    /// `other`. Returns [`Error::MismatchedVertexCounts`] when the polygons
+    /// have different numbers of vertices. Values of `t` outside of `[0, 1]`
+    /// extrapolate.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let small = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///     let large = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![3.0, 0.0],
+    ///         point![0.0, 3.0],
+    ///     ]);
+    ///
+    ///     let middle = small.lerp(&large, 0.5).unwrap();
+    ///     assert_eq!(middle.vertices[1], point![2.0, 0.0]);
+    ///
+    pub fn lerp(&self, other: &Polygon, t: f32) -> Result<Polygon, Error> {
+        if self.vertices.len() != other.vertices.len() {
+            return Err(Error::MismatchedVertexCounts);
+        }
+        Ok(Polygon::new(
+            self.vertices
+                .iter()
+                .zip(&other.vertices)
+                .map(|(a, b)| a + (b - a) * t)
+                .collect(),
+        ))
+    }
+
+    /// The total length of the polygon's edges.
+    pub fn perimeter(&self) -> f32 {
+        self.edges().map(|edge| edge.length()).sum()
+    }
+
+    /// The unsigned area enclosed by the polygon.
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    /// Returns true when the point is inside the polygon.
+    ///
+    /// Containment uses the even-odd rule so self-intersecting polygons are
+    /// handled consistently. Points exactly on the boundary may be reported
+    /// as either inside or outside.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let triangle = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![0.0, 4.0],
+    ///     ]);
+    ///
+    ///     assert!(triangle.contains_point(&point![1.0, 1.0]));
+    ///     assert!(!triangle.contains_point(&point![3.0, 3.0]));
+    ///
+    pub fn contains_point(&self, point: &Point2<f32>) -> bool {
+        let mut inside = false;
+        for edge in self.edges() {
+            let (a, b) = (edge.start, edge.end);
+            if (a.y > point.y) != (b.y > point.y) {
+                let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Returns true when the polygon's boundary doesn't touch itself.
+    ///
+    /// Neighboring edges may only meet at their shared vertex, and every
+    /// other pair of edges must be disjoint. Every pair of edges is checked,
+    /// so this takes O(n²) time. Polygons with fewer than three vertices
+    /// aren't simple.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![1.0, 1.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///     let bowtie = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 1.0],
+    ///         point![1.0, 0.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///
+    ///     assert!(square.is_simple());
+    ///     assert!(!bowtie.is_simple());
+    ///
+    pub fn is_simple(&self) -> bool {
+        let edges: Vec<Segment> = self.edges().collect();
+        let n = edges.len();
+        if n < 3 {
+            return false;
+        }
+        (0..n).all(|i| {
+            (i + 1..n).all(|j| {
+                let neighbors = j == i + 1 || (i == 0 && j == n - 1);
+                match intersect_segments(&edges[i], &edges[j]) {
+                    SegmentIntersection::None => true,
+                    SegmentIntersection::Point(_) => {
+                        neighbors && edges[i].length() > 0.0
+                    }
+                    SegmentIntersection::Overlap(_) => false,
+                }
+            })
+        })
+    }
+}
+
+impl PathFrame for Polygon {
+    fn path_length(&self) -> f32 {
+        self.perimeter()
+    }
+
+    /// The frame at a distance along the polygon's boundary, starting from
+    /// the first vertex. Distances wrap around the perimeter.
+    fn frame_at_length(&self, s: f32) -> Option<Frame> {
+        frame_along_vertices(&self.vertices, true, s)
+    }
+}
+
+impl BoundingBox for Polygon {
+    /// The box around every vertex. A polygon without vertices has an
+    /// inverted box which contains and touches nothing.
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(&self.vertices).unwrap_or_else(Aabb::empty)
+    }
+}
+
+impl BoundingCircle for Polygon {
+    /// The smallest circle around every vertex, from
+    /// [`Circle::enclosing`]. A polygon without vertices has a circle with
+    /// a NaN radius, which contains nothing.
+    fn bounding_circle(&self) -> Circle {
+        Circle::enclosing(&self.vertices).unwrap_or_else(Circle::empty)
+    }
+}
+
+impl BoundingObb for Polygon {
+    /// The smallest oriented box around every vertex, from
+    /// [`Obb::enclosing`]. A polygon without vertices has a box with
+    /// negative extents, which contains nothing.
+    fn bounding_obb(&self) -> Obb {
+        Obb::enclosing(&self.vertices).unwrap_or_else(Obb::empty)
+    }
+}
+
+impl SupportMap for Polygon {
+    /// The vertex furthest along the direction, found with a linear scan.
+    /// Ties go to the lowest index. Polygons without vertices have no
+    /// support point and return a point with NaN coordinates.
+    ///
+    /// Use [`Polygon::extreme_vertex_convex`] for a faster search when the
+    /// polygon is known to be convex.
+    fn support_point(&self, direction: &Unit<Vector2<f32>>) -> Point2<f32> {
+        extreme_point(&self.vertices, direction)
+            .map_or(Point2::new(f32::NAN, f32::NAN), |i| self.vertices[i])
+    }
+}
+
+impl fmt::Display for Polygon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        write_points(f, &self.vertices, ", ")?;
+        f.write_str("]")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{polygon::Polygon, Error},
+        nalgebra::point,
+    };
+
+    #[test]
+    pub fn touching_and_doubled_back_polygons_should_not_be_simple() {
+        let touching = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![4.0, 0.0],
+            point![2.0, 2.0],
+            point![4.0, 4.0],
+            point![0.0, 4.0],
+            point![2.0, 2.0],
+        ]);
+        let doubled_back = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+        ]);
+        let repeated = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 0.0],
+            point![0.0, 1.0],
+        ]);
+
+        assert!(!touching.is_simple());
+        assert!(!doubled_back.is_simple());
+        assert!(!repeated.is_simple());
+        assert!(!Polygon::new(vec![point![0.0, 0.0]; 2]).is_simple());
+    }
+
+    #[test]
+    pub fn lerp_should_fail_when_vertex_counts_differ() {
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![0.0, 1.0],
+        ]);
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ]);
+
+        assert_eq!(
+            triangle.lerp(&square, 0.5),
+            Err(Error::MismatchedVertexCounts)
+        );
+        assert_eq!(triangle.lerp(&triangle, 0.5), Ok(triangle));
+    }
+}
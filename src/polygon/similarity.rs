@@ -0,0 +1,462 @@
+use {
+    crate::{
+        line::{DistanceToPoint, Segment},
+        math,
+        polygon::Polyline,
+        shape::{Aabb, BoundingBox},
+        spatial::Bvh,
+    },
+    alloc::vec::Vec,
+    nalgebra::{center, Point2, Vector2},
+};
+
+/// The Hausdorff distance between two polylines, which is the furthest
+/// any point on one path is from the other path.
+///
+/// Both directions are measured, so the distance is symmetric, and every
+/// point along the segments counts, not just the vertices. The furthest
+/// point on each segment is found by splitting the segment only where the
+/// other path could still be further away than the best distance so far,
+/// which gives the exact distance up to rounding. The direction of travel
+/// doesn't matter, so a path and its reverse are zero apart.
+///
+/// A polyline with one vertex is a point. Returns infinity when either
+/// polyline has no vertices.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{hausdorff_distance, Polyline},
+///         nalgebra::point,
+///     };
+///
+///     let a = Polyline::new(vec![point![0.0, 0.0], point![4.0, 0.0]]);
+///     let b = Polyline::new(vec![point![1.0, 1.0], point![3.0, 1.0]]);
+///
+///     // The ends of `a` are furthest from `b`.
+///     assert_eq!(hausdorff_distance(&a, &b), 2.0f32.sqrt());
+///
+pub fn hausdorff_distance(a: &Polyline, b: &Polyline) -> f32 {
+    let Some(bounds) = Aabb::from_points(&a.vertices)
+        .zip(Aabb::from_points(&b.vertices))
+        .map(|(a, b)| a.merged(&b))
+    else {
+        return f32::INFINITY;
+    };
+    let tolerance = (bounds.width() + bounds.height()) * 1e-6;
+    let (a, b) = (pieces(a), pieces(b));
+    directed_hausdorff(&a, &b, tolerance)
+        .max(directed_hausdorff(&b, &a, tolerance))
+}
+
+/// The discrete Fréchet distance between two polylines, which is the
+/// shortest leash that lets two walkers hop along the vertices of each
+/// path from start to end without either of them going backwards.
+///
+/// Unlike [`hausdorff_distance`], the order of the vertices matters, so a
+/// path and its reverse are usually far apart. Only the vertices are
+/// matched, so the distance is never less than the continuous Fréchet
+/// distance and can be much more when the segments are long. Pass
+/// `max_spacing` to add vertices along each segment at most that far apart
+/// first, which brings the distance within `max_spacing` of the continuous
+/// one. Spacings which aren't positive are ignored.
+///
+/// Uses O(n·m) time but only O(m) memory for paths with n and m vertices
+/// after adding vertices. Returns infinity when either polyline has no
+/// vertices.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{frechet_distance, Polyline},
+///         nalgebra::point,
+///     };
+///
+///     let a = Polyline::new(vec![point![0.0, 0.0], point![4.0, 0.0]]);
+///     let b = Polyline::new(vec![point![0.0, 1.0], point![4.0, 1.0]]);
+///
+///     assert_eq!(frechet_distance(&a, &b, None), 1.0);
+///
+///     // Walking one path backwards means the walkers start 4 apart.
+///     let reversed = Polyline::new(vec![point![4.0, 1.0], point![0.0, 1.0]]);
+///     assert!(frechet_distance(&a, &reversed, None) > 4.0);
+///
+pub fn frechet_distance(
+    a: &Polyline,
+    b: &Polyline,
+    max_spacing: Option<f32>,
+) -> f32 {
+    let a = densify(&a.vertices, max_spacing);
+    let b = densify(&b.vertices, max_spacing);
+    if a.is_empty() || b.is_empty() {
+        return f32::INFINITY;
+    }
+
+    // Each entry of the row is the shortest leash which gets the walkers
+    // to a pair of vertices, and the row is updated in place for each
+    // vertex of `a`.
+    let mut row = vec![f32::INFINITY; b.len()];
+    for (i, p) in a.iter().enumerate() {
+        let mut diagonal = f32::INFINITY;
+        let mut left = f32::INFINITY;
+        for (j, q) in b.iter().enumerate() {
+            let distance = (p - q).norm();
+            let above = row[j];
+            row[j] = if i == 0 && j == 0 {
+                distance
+            } else {
+                distance.max(above.min(left).min(diagonal))
+            };
+            diagonal = above;
+            left = row[j];
+        }
+    }
+    row[b.len() - 1]
+}
+
+/// Pick out paths which aren't near-duplicates of each other, like the
+/// strokes drawn over and over by iterative generators.
+///
+/// Two paths are duplicates when their [`hausdorff_distance`] is at most
+/// `tolerance`, whichever way they're drawn. Paths are considered from the
+/// longest to the shortest, with ties in their original order, and each is
+/// kept unless it duplicates a path which was already kept. So every
+/// dropped path duplicates a kept one, and of a group of duplicates the
+/// longest is kept. Bounding boxes are compared before any distances, so
+/// paths which are far apart are skipped quickly.
+///
+/// Returns the indices of the kept paths in increasing order. Paths without
+/// vertices are never duplicates.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{dedup_polylines, Polyline},
+///         nalgebra::point,
+///     };
+///
+///     let paths = [
+///         Polyline::new(vec![point![0.0, 0.0], point![4.0, 0.0]]),
+///         Polyline::new(vec![point![0.0, 3.0], point![4.0, 3.0]]),
+///         // The first path drawn backwards, slightly off
+///         Polyline::new(vec![point![3.99, 0.01], point![0.0, -0.01]]),
+///     ];
+///
+///     assert_eq!(dedup_polylines(&paths, 0.1), vec![0, 1]);
+///
+pub fn dedup_polylines(paths: &[Polyline], tolerance: f32) -> Vec<usize> {
+    let boxes: Vec<Aabb> =
+        paths.iter().map(BoundingBox::bounding_box).collect();
+    // Paths without vertices have inverted boxes, which are left out.
+    let indexed: Vec<usize> = (0..paths.len())
+        .filter(|&i| !paths[i].vertices.is_empty())
+        .collect();
+    let index = Bvh::new(indexed.iter().map(|&i| boxes[i]).collect());
+    let grown = |aabb: &Aabb| {
+        let margin = Vector2::repeat(tolerance);
+        Aabb::new(aabb.min - margin, aabb.max + margin)
+    };
+    // Every point of a duplicate is within the tolerance of the other
+    // path, so each box fits inside the other one grown by the tolerance.
+    let within = |inner: &Aabb, outer: &Aabb| {
+        let outer = grown(outer);
+        outer.contains_point(&inner.min) && outer.contains_point(&inner.max)
+    };
+
+    let lengths: Vec<f32> = paths.iter().map(Polyline::length).collect();
+    let mut order: Vec<usize> = (0..paths.len()).collect();
+    order.sort_by(|&a, &b| lengths[b].total_cmp(&lengths[a]));
+    let mut kept = vec![false; paths.len()];
+    for i in order {
+        let duplicate = !paths[i].vertices.is_empty()
+            && index.query_aabb(&grown(&boxes[i])).into_iter().any(|hit| {
+                let j = indexed[hit];
+                kept[j]
+                    && within(&boxes[i], &boxes[j])
+                    && within(&boxes[j], &boxes[i])
+                    && hausdorff_distance(&paths[i], &paths[j]) <= tolerance
+            });
+        kept[i] = !duplicate;
+    }
+    (0..paths.len()).filter(|&i| kept[i]).collect()
+}
+
+/// The segments of a polyline, with a single vertex as a segment with no
+/// length.
+fn pieces(path: &Polyline) -> Vec<Segment> {
+    match path.vertices.as_slice() {
+        [point] => vec![Segment::new(*point, *point)],
+        _ => path.segments().collect(),
+    }
+}
+
+/// The furthest any point on the `from` segments is from the `to`
+/// segments.
+fn directed_hausdorff(from: &[Segment], to: &[Segment], tolerance: f32) -> f32 {
+    // A point with its distance to the nearest segment and which segment
+    // that is.
+    let sample = |point: Point2<f32>| {
+        let (distance_squared, nearest) = to
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| (segment.distance_to_point_squared(&point), i))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .unwrap();
+        (point, math::sqrt(distance_squared), nearest)
+    };
+
+    let mut furthest = 0.0f32;
+    let mut pending = Vec::new();
+    for segment in from {
+        pending.push((sample(segment.start), sample(segment.end)));
+        while let Some((p, q)) = pending.pop() {
+            furthest = furthest.max(p.1).max(q.1);
+            // Distances change no faster than the point moves, and the
+            // distance to one segment is convex along another, so none of
+            // these bounds can be beaten between `p` and `q`.
+            let lipschitz = (p.1 + q.1 + (q.0 - p.0).norm()) / 2.0;
+            let from_p = p.1.max(to[p.2].distance_to_point(&q.0));
+            let from_q = q.1.max(to[q.2].distance_to_point(&p.0));
+            if lipschitz.min(from_p).min(from_q) <= furthest + tolerance {
+                continue;
+            }
+            let middle = sample(center(&p.0, &q.0));
+            pending.push((p, middle));
+            pending.push((middle, q));
+        }
+    }
+    furthest
+}
+
+/// The vertices with extra ones added along each segment so they're at
+/// most `max_spacing` apart.
+fn densify(
+    vertices: &[Point2<f32>],
+    max_spacing: Option<f32>,
+) -> Vec<Point2<f32>> {
+    let Some(spacing) = max_spacing.filter(|&spacing| spacing > 0.0) else {
+        return vertices.to_vec();
+    };
+    let mut dense: Vec<_> = vertices.first().into_iter().copied().collect();
+    for pair in vertices.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let parts = math::ceil((b - a).norm() / spacing).max(1.0) as usize;
+        dense.extend(
+            (1..=parts).map(|i| a + (b - a) * (i as f32 / parts as f32)),
+        );
+    }
+    dense
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{dedup_polylines, frechet_distance, hausdorff_distance},
+        crate::{
+            line::DistanceToPoint, polygon::Polyline, test_support::polygon,
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    fn zigzag() -> Polyline {
+        Polyline::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 1.5],
+            point![3.0, -0.5],
+            point![5.5, 0.25],
+        ])
+    }
+
+    #[test]
+    pub fn identical_paths_should_have_no_distance() {
+        let path = zigzag();
+
+        assert_eq!(hausdorff_distance(&path, &path), 0.0);
+        assert_eq!(frechet_distance(&path, &path, None), 0.0);
+        assert_eq!(frechet_distance(&path, &path, Some(0.1)), 0.0);
+    }
+
+    #[test]
+    pub fn reversed_paths_should_only_match_without_direction() {
+        let path = zigzag();
+        let mut reversed = path.clone();
+        reversed.vertices.reverse();
+
+        assert_eq!(hausdorff_distance(&path, &reversed), 0.0);
+        // The walkers start at opposite ends.
+        assert!(frechet_distance(&path, &reversed, None) >= 5.5);
+    }
+
+    #[test]
+    pub fn parallel_segments_should_be_their_separation_apart() {
+        let d = 0.37;
+        let a = Polyline::new(vec![point![1.5, 2.0], point![4.25, 2.0]]);
+        let b =
+            Polyline::new(vec![point![1.5, 2.0 + d], point![4.25, 2.0 + d]]);
+
+        assert_eq!(hausdorff_distance(&a, &b), (2.0f32 + d) - 2.0);
+        assert_eq!(frechet_distance(&a, &b, None), (2.0f32 + d) - 2.0);
+    }
+
+    #[test]
+    pub fn the_furthest_point_can_be_inside_a_segment() {
+        // Both ends of `a` are on `b`, but its middle isn't.
+        let a = Polyline::new(vec![point![0.0, 0.0], point![4.0, 0.0]]);
+        let b = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![0.0, 2.0],
+            point![4.0, 2.0],
+            point![4.0, 0.0],
+        ]);
+
+        assert_relative_eq!(hausdorff_distance(&a, &b), 2.0, epsilon = 1e-4);
+        let point = Polyline::new(vec![point![0.0, 1.0]]);
+        assert_relative_eq!(hausdorff_distance(&a, &point), 17.0f32.sqrt());
+        assert_eq!(
+            hausdorff_distance(&a, &Polyline::new(vec![])),
+            f32::INFINITY
+        );
+        assert_eq!(
+            frechet_distance(&Polyline::new(vec![]), &a, None),
+            f32::INFINITY
+        );
+    }
+
+    #[test]
+    pub fn adding_vertices_should_bring_frechet_distances_down() {
+        let a = Polyline::new(vec![point![0.0, 0.0], point![10.0, 0.0]]);
+        let b = Polyline::new(vec![
+            point![0.0, 1.0],
+            point![5.0, 1.0],
+            point![10.0, 1.0],
+        ]);
+
+        // One walker on `a` has to wait at an end while the other reaches
+        // the middle of `b`.
+        assert!(frechet_distance(&a, &b, None) > 5.0);
+        let dense = frechet_distance(&a, &b, Some(0.01));
+        assert_relative_eq!(dense, 1.0, epsilon = 1e-2);
+    }
+
+    #[test]
+    pub fn jittered_copies_should_collapse_to_one() {
+        let curve = zigzag();
+        let jittered = |offsets: [f32; 4]| {
+            let vertices = curve
+                .vertices
+                .iter()
+                .zip(offsets)
+                .map(|(p, offset)| p + vector![offset, -offset])
+                .collect();
+            Polyline::new(vertices)
+        };
+        let mut reversed = jittered([0.02, -0.01, 0.0, 0.03]);
+        reversed.vertices.reverse();
+        let mut longer = jittered([0.01, 0.02, -0.02, 0.0]);
+        longer.vertices.push(point![5.55, 0.3]);
+        let paths = [
+            jittered([0.0, 0.03, -0.01, 0.02]),
+            reversed,
+            longer,
+            curve.clone(),
+        ];
+
+        // The extra vertex makes the third path the longest.
+        assert_eq!(dedup_polylines(&paths, 0.1), vec![2]);
+        assert_eq!(dedup_polylines(&paths, 0.0).len(), 4);
+    }
+
+    #[test]
+    pub fn different_curves_should_all_survive() {
+        let curve = zigzag();
+        let shifted = |dy: f32| {
+            Polyline::new(
+                curve
+                    .vertices
+                    .iter()
+                    .map(|p| p + vector![0.0, dy])
+                    .collect(),
+            )
+        };
+        let paths = [
+            curve.clone(),
+            shifted(0.5),
+            shifted(-0.5),
+            Polyline::new(curve.vertices[..3].to_vec()),
+            Polyline::new(vec![]),
+            Polyline::new(vec![]),
+        ];
+
+        assert_eq!(dedup_polylines(&paths, 0.2), vec![0, 1, 2, 3, 4, 5]);
+        // Equal lengths keep the earlier path.
+        assert_eq!(dedup_polylines(&[curve.clone(), curve], 0.2), vec![0]);
+    }
+
+    proptest! {
+        #[test]
+        fn hausdorff_distances_should_match_dense_sampling(
+            a in polygon(-10.0..=10.0, 3..=6, false),
+            b in polygon(-10.0..=10.0, 3..=6, false),
+        ) {
+            let a = Polyline::new(a.vertices);
+            let b = Polyline::new(b.vertices);
+            let spacing = 0.01;
+
+            let distance = hausdorff_distance(&a, &b);
+
+            let directed = |from: &Polyline, to: &Polyline| {
+                from.segments()
+                    .flat_map(|segment| {
+                        let parts = (segment.length() / spacing) as usize + 1;
+                        (0..=parts).map(move |i| {
+                            let t = i as f32 / parts as f32;
+                            segment.start + (segment.end - segment.start) * t
+                        })
+                    })
+                    .map(|p| {
+                        to.segments()
+                            .map(|s| s.distance_to_point(&p))
+                            .fold(f32::INFINITY, f32::min)
+                    })
+                    .fold(0.0f32, f32::max)
+            };
+            let sampled = directed(&a, &b).max(directed(&b, &a));
+            prop_assert!(sampled <= distance + 1e-3);
+            prop_assert!(distance <= sampled + spacing / 2.0 + 1e-3);
+            let frechet = frechet_distance(&a, &b, None);
+            prop_assert!(frechet >= distance - 1e-3);
+        }
+
+        #[test]
+        fn kept_paths_should_be_apart_and_cover_the_rest(
+            paths in proptest::collection::vec(
+                polygon(-3.0..=3.0, 3..=4, false),
+                1..=8,
+            ),
+            tolerance in 0.0f32..=4.0,
+        ) {
+            let paths: Vec<_> = paths
+                .into_iter()
+                .map(|p| Polyline::new(p.vertices))
+                .collect();
+
+            let kept = dedup_polylines(&paths, tolerance);
+
+            for (i, &a) in kept.iter().enumerate() {
+                for &b in &kept[i + 1..] {
+                    let d = hausdorff_distance(&paths[a], &paths[b]);
+                    prop_assert!(d > tolerance);
+                }
+            }
+            for dropped in (0..paths.len()).filter(|i| !kept.contains(i)) {
+                let covered = kept.iter().any(|&k| {
+                    hausdorff_distance(&paths[dropped], &paths[k]) <= tolerance
+                });
+                prop_assert!(covered);
+            }
+        }
+    }
+}
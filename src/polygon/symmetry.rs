@@ -0,0 +1,346 @@
+use {
+    crate::{line::Line, math, polygon::Polygon, transform::mirror_across},
+    alloc::vec::Vec,
+    nalgebra::{Point2, Rotation2, Unit, Vector2},
+};
+
+/// The symmetries of a polygon, found by
+/// [`Polygon::detect_symmetries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symmetries {
+    /// The number of rotations about the centroid, including the identity,
+    /// which map the polygon onto itself. Polygons with no rotational
+    /// symmetry have order 1.
+    pub rotation_order: usize,
+
+    /// The lines through the centroid which the polygon can be mirrored
+    /// across without changing it.
+    pub mirrors: Vec<Line>,
+}
+
+impl Polygon {
+    /// Find the rotations and reflections which map the polygon onto
+    /// itself.
+    ///
+    /// Each vertex gets a signature made of the length of the edge after it
+    /// and the angle the boundary turns there. A rotation shifts the
+    /// signatures around the polygon, and a reflection reverses them, so
+    /// candidate symmetries are found by matching the signatures against
+    /// shifted and reversed copies of themselves. Every candidate is then
+    /// checked by moving the vertices, and kept only when each vertex lands
+    /// within `tolerance` of the vertex it should map to.
+    ///
+    /// Symmetries map vertices to vertices, so extra vertices along an
+    /// edge break the symmetry just like moving a corner would. Polygons
+    /// with fewer than three vertices have rotation order 1 and no mirrors.
+    /// Matching takes O(n²) time.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{polygon::Polygon, transform::mirror_across},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 2.0],
+    ///         point![0.0, 2.0],
+    ///     ]);
+    ///
+    ///     let symmetries = square.detect_symmetries(1e-4);
+    ///
+    ///     assert_eq!(symmetries.rotation_order, 4);
+    ///     assert_eq!(symmetries.mirrors.len(), 4);
+    ///     for mirror in &symmetries.mirrors {
+    ///         let mirrored = mirror_across(&point![0.0, 0.0], mirror);
+    ///         let nearest = square
+    ///             .vertices
+    ///             .iter()
+    ///             .map(|v| (v - mirrored).norm())
+    ///             .fold(f32::INFINITY, f32::min);
+    ///         assert!(nearest < 1e-4);
+    ///     }
+    ///
+    pub fn detect_symmetries(&self, tolerance: f32) -> Symmetries {
+        let n = self.vertices.len();
+        let mut symmetries = Symmetries {
+            rotation_order: 1,
+            mirrors: Vec::new(),
+        };
+        if n < 3 {
+            return symmetries;
+        }
+        let center = self.centroid();
+        let signatures = self.signatures(tolerance);
+        let at = |i: usize| self.vertices[i % n];
+
+        // The smallest shift which matches is the rotation with the
+        // smallest angle, and every other rotation is a multiple of it.
+        for shift in (1..n).filter(|&shift| n % shift == 0) {
+            let matches = (0..n).all(|i| {
+                signatures.edges[i].matches(&signatures.edges[(i + shift) % n])
+            });
+            if !matches {
+                continue;
+            }
+            let (from, to) = (at(0) - center, at(shift) - center);
+            let rotation = Rotation2::rotation_between(&from, &to);
+            if (0..n).all(|i| {
+                let moved = center + rotation * (at(i) - center);
+                (moved - at(i + shift)).norm() <= tolerance
+            }) {
+                symmetries.rotation_order = n / shift;
+                break;
+            }
+        }
+
+        // A mirror which sends vertex 0 to vertex `pair` sends vertex i to
+        // vertex `pair - i`, and the edge after i to the edge before
+        // `pair - i`.
+        for pair in 0..n {
+            let matches = (0..n).all(|i| {
+                let j = (pair + n - i) % n;
+                signatures.turns[i].matches(&signatures.turns[j])
+                    && signatures.edges[i]
+                        .matches(&signatures.edges[(j + n - 1) % n])
+            });
+            if !matches {
+                continue;
+            }
+            let Some(mirror) = mirror_line(&center, &at(0), &at(pair)) else {
+                continue;
+            };
+            if (0..n).all(|i| {
+                let mirrored = mirror_across(&at(i), &mirror);
+                (mirrored - at(pair + n - i)).norm() <= tolerance
+            }) {
+                symmetries.mirrors.push(mirror);
+            }
+        }
+        symmetries
+    }
+
+    /// The length of the edge after each vertex, and the angle the
+    /// boundary turns at each vertex.
+    fn signatures(&self, tolerance: f32) -> Signatures {
+        let n = self.vertices.len();
+        let edges: Vec<f32> = self.edges().map(|edge| edge.length()).collect();
+        let shortest = edges.iter().copied().fold(f32::INFINITY, f32::min);
+        // Moving each corner by the tolerance turns the boundary by up to
+        // about this much at the shortest edge.
+        let angle_tolerance = 4.0 * tolerance / shortest.max(f32::EPSILON);
+        Signatures {
+            edges: edges
+                .iter()
+                .map(|&value| Signature {
+                    value,
+                    tolerance: 2.0 * tolerance,
+                })
+                .collect(),
+            turns: (0..n)
+                .map(|i| {
+                    let before =
+                        self.vertices[i] - self.vertices[(i + n - 1) % n];
+                    let after = self.vertices[(i + 1) % n] - self.vertices[i];
+                    Signature {
+                        value: math::atan2(
+                            before.perp(&after),
+                            before.dot(&after),
+                        ),
+                        tolerance: angle_tolerance,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The line through the centroid which mirrors `a` onto `b`.
+fn mirror_line(
+    center: &Point2<f32>,
+    a: &Point2<f32>,
+    b: &Point2<f32>,
+) -> Option<Line> {
+    let normal = if a == b {
+        // The vertex is on the mirror.
+        let along = a - center;
+        Vector2::new(-along.y, along.x)
+    } else {
+        b - a
+    };
+    let normal = Unit::try_new(normal, 0.0)?;
+    Some(Line::new(normal, -normal.dot(&center.coords)))
+}
+
+struct Signatures {
+    edges: Vec<Signature>,
+    turns: Vec<Signature>,
+}
+
+/// A value which matches any other value within its tolerance.
+struct Signature {
+    value: f32,
+    tolerance: f32,
+}
+
+impl Signature {
+    fn matches(&self, other: &Signature) -> bool {
+        (self.value - other.value).abs() <= self.tolerance
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            generate::{regular_polygon, star},
+            polygon::Polygon,
+            test_support::point2,
+            transform::mirror_across,
+        },
+        core::f32::consts::TAU,
+        nalgebra::{point, vector, Point2, Rotation2},
+        proptest::prelude::*,
+    };
+
+    /// Returns true when every moved vertex lands on a vertex.
+    fn maps_onto_itself(
+        polygon: &Polygon,
+        map: impl Fn(&Point2<f32>) -> Point2<f32>,
+    ) -> bool {
+        polygon.vertices.iter().all(|v| {
+            let moved = map(v);
+            polygon.vertices.iter().any(|w| (w - moved).norm() < 1e-3)
+        })
+    }
+
+    #[test]
+    pub fn squares_should_have_four_rotations_and_four_mirrors() {
+        let square = regular_polygon(point![3.0, -1.0], 2.0, 4, 0.3);
+
+        let symmetries = square.detect_symmetries(1e-4);
+
+        assert_eq!(symmetries.rotation_order, 4);
+        assert_eq!(symmetries.mirrors.len(), 4);
+        for mirror in &symmetries.mirrors {
+            assert!(maps_onto_itself(&square, |v| mirror_across(v, mirror)));
+        }
+    }
+
+    #[test]
+    pub fn scalene_triangles_should_have_no_symmetry() {
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![4.0, 0.0],
+            point![1.0, 2.0],
+        ]);
+
+        let symmetries = triangle.detect_symmetries(1e-4);
+
+        assert_eq!(symmetries.rotation_order, 1);
+        assert!(symmetries.mirrors.is_empty());
+    }
+
+    #[test]
+    pub fn stars_should_match_their_points() {
+        let star = star(point![1.0, 1.0], 3.0, 1.2, 5, 0.7);
+
+        let symmetries = star.detect_symmetries(1e-4);
+
+        assert_eq!(symmetries.rotation_order, 5);
+        assert_eq!(symmetries.mirrors.len(), 5);
+        for mirror in &symmetries.mirrors {
+            assert!(maps_onto_itself(&star, |v| mirror_across(v, mirror)));
+        }
+
+        // Clockwise polygons have the same symmetries.
+        let mut clockwise = star.clone();
+        clockwise.vertices.reverse();
+        let reversed = clockwise.detect_symmetries(1e-4);
+        assert_eq!(reversed.rotation_order, 5);
+        assert_eq!(reversed.mirrors.len(), 5);
+    }
+
+    #[test]
+    pub fn noise_within_the_tolerance_should_be_ignored() {
+        let mut hexagon = regular_polygon(point![0.0, 0.0], 5.0, 6, 0.0);
+        hexagon.vertices[2].x += 1e-3;
+
+        assert_eq!(hexagon.detect_symmetries(1e-2).rotation_order, 6);
+        assert_eq!(hexagon.detect_symmetries(1e-2).mirrors.len(), 6);
+        assert_eq!(hexagon.detect_symmetries(1e-5).rotation_order, 1);
+
+        // A rectangle only has two mirrors, and a rhombus has the other two.
+        let rectangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![4.0, 0.0],
+            point![4.0, 2.0],
+            point![0.0, 2.0],
+        ]);
+        let rhombus = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, -1.0],
+            point![4.0, 0.0],
+            point![2.0, 1.0],
+        ]);
+        for shape in [rectangle, rhombus] {
+            let symmetries = shape.detect_symmetries(1e-4);
+            assert_eq!(symmetries.rotation_order, 2);
+            assert_eq!(symmetries.mirrors.len(), 2);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn regular_polygons_should_be_fully_symmetric(
+            center in point2(-10.0..=10.0),
+            sides in 3usize..=12,
+            rotation in 0.0f32..=6.0,
+        ) {
+            let polygon = regular_polygon(center, 4.0, sides, rotation);
+
+            let symmetries = polygon.detect_symmetries(1e-3);
+
+            prop_assert_eq!(symmetries.rotation_order, sides);
+            prop_assert_eq!(symmetries.mirrors.len(), sides);
+            for mirror in &symmetries.mirrors {
+                prop_assert!(
+                    maps_onto_itself(&polygon, |v| mirror_across(v, mirror))
+                );
+            }
+        }
+
+        #[test]
+        fn pinwheels_should_rotate_but_not_mirror(
+            center in point2(-10.0..=10.0),
+            blades in 2usize..=8,
+            rotation in 0.0f32..=6.0,
+            lean in 0.1f32..=0.4,
+        ) {
+            // Each blade is a tip and a notch which leans toward it.
+            let step = TAU / blades as f32;
+            let polygon = Polygon::new(
+                (0..blades)
+                    .flat_map(|blade| {
+                        let angle = rotation + blade as f32 * step;
+                        [(angle, 4.0), (angle + lean * step, 1.5)]
+                    })
+                    .map(|(angle, radius)| {
+                        center + vector![angle.cos(), angle.sin()] * radius
+                    })
+                    .collect(),
+            );
+
+            let symmetries = polygon.detect_symmetries(1e-3);
+
+            prop_assert_eq!(symmetries.rotation_order, blades);
+            prop_assert!(symmetries.mirrors.is_empty());
+            let turn = Rotation2::new(step);
+            let centroid = polygon.centroid();
+            let rotate = |v: &Point2<f32>| centroid + turn * (v - centroid);
+            prop_assert!(maps_onto_itself(&polygon, rotate));
+        }
+    }
+}
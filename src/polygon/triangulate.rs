@@ -0,0 +1,665 @@
+use {
+    crate::{operations::signed_angle, polygon::Polygon},
+    alloc::vec::Vec,
+    core::{cmp::Ordering, f32::consts::TAU},
+    nalgebra::Point2,
+};
+
+/// Polygons with fewer vertices than this are ear clipped by
+/// [`Polygon::triangulate_fast`] because the sweep's bookkeeping costs more
+/// than it saves.
+const EAR_CLIPPING_LIMIT: usize = 16;
+
+impl Polygon {
+    /// Split the polygon into triangles by ear clipping.
+    ///
+    /// Each triangle is a triple of indices into the polygon's vertices and is
+    /// wound counterclockwise, regardless of the polygon's own winding. A
+    /// simple polygon with `n` vertices always produces `n - 2` triangles
+    /// whose areas sum to the polygon's area. Polygons with fewer than three
+    /// vertices produce no triangles.
+    ///
+    /// Ear clipping takes O(n²) time. Prefer [`Polygon::triangulate_fast`]
+    /// for polygons with many vertices.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![1.0, 1.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///
+    ///     assert_eq!(square.triangulate().len(), 2);
+    ///
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        ear_clip(&self.vertices, self.counterclockwise_order())
+    }
+
+    /// Split the polygon into y-monotone pieces.
+    ///
+    /// A polygon is y-monotone when every horizontal line crosses it at most
+    /// once, so it can be triangulated in linear time. The pieces are found
+    /// by sweeping a horizontal line from top to bottom and adding diagonals
+    /// at split and merge vertices, the vertices where the polygon forks or
+    /// joins from the sweep's point of view.
+    ///
+    /// Vertices with the same y coordinate are ordered from left to right, as
+    /// if the polygon were rotated by a tiny angle, so horizontal edges are
+    /// handled like any other edge.
+    ///
+    /// Every piece is wound counterclockwise and its vertices are vertices of
+    /// this polygon. The pieces' areas sum to the polygon's area.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     // A "U" is not y-monotone because the line y = 1.5 crosses both
+    ///     // arms.
+    ///     let u = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![3.0, 0.0],
+    ///         point![3.0, 2.0],
+    ///         point![2.0, 2.0],
+    ///         point![2.0, 1.0],
+    ///         point![1.0, 1.0],
+    ///         point![1.0, 2.0],
+    ///         point![0.0, 2.0],
+    ///     ]);
+    ///
+    ///     assert_eq!(u.decompose_monotone().len(), 2);
+    ///
+    pub fn decompose_monotone(&self) -> Vec<Polygon> {
+        monotone_pieces(&self.vertices, self.counterclockwise_order())
+            .into_iter()
+            .map(|piece| {
+                Polygon::new(piece.iter().map(|&i| self.vertices[i]).collect())
+            })
+            .collect()
+    }
+
+    /// Split the polygon into triangles using a monotone decomposition.
+    ///
+    /// The polygon is split into y-monotone pieces with
+    /// [`Polygon::decompose_monotone`] and then each piece is triangulated
+    /// with [`triangulate_monotone`], which takes O(n log n) time for
+    /// typical polygons. Small polygons are ear clipped instead.
+    ///
+    /// The output follows the same rules as [`Polygon::triangulate`].
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // A star with 1000 points
+    ///     let star = Polygon::new(
+    ///         (0..2000)
+    ///             .map(|i| {
+    ///                 let angle = i as f32 * core::f32::consts::TAU / 2000.0;
+    ///                 let radius = if i % 2 == 0 { 10.0 } else { 5.0 };
+    ///                 point![radius * angle.cos(), radius * angle.sin()]
+    ///             })
+    ///             .collect(),
+    ///     );
+    ///
+    ///     let triangles = star.triangulate_fast();
+    ///
+    ///     assert_eq!(triangles.len(), 1998);
+    ///     let area: f32 = triangles
+    ///         .iter()
+    ///         .map(|&[a, b, c]| {
+    ///             let (a, b, c) =
+    ///                 (star.vertices[a], star.vertices[b], star.vertices[c]);
+    ///             (b - a).perp(&(c - a)) * 0.5
+    ///         })
+    ///         .sum();
+    ///     assert_relative_eq!(area, star.area(), max_relative = 1e-4);
+    ///
+    pub fn triangulate_fast(&self) -> Vec<[usize; 3]> {
+        if self.vertices.len() < EAR_CLIPPING_LIMIT {
+            self.triangulate()
+        } else {
+            self.triangulate_sweep()
+        }
+    }
+
+    /// Triangulate each monotone piece, regardless of the polygon's size.
+    fn triangulate_sweep(&self) -> Vec<[usize; 3]> {
+        monotone_pieces(&self.vertices, self.counterclockwise_order())
+            .iter()
+            .flat_map(|piece| triangulate_monotone_piece(&self.vertices, piece))
+            .collect()
+    }
+
+    /// The vertex indices in counterclockwise order around the boundary.
+    fn counterclockwise_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.vertices.len()).collect();
+        if self.signed_area() < 0.0 {
+            order.reverse();
+        }
+        order
+    }
+}
+
+/// Split a y-monotone polygon into triangles in linear time.
+///
+/// The polygon must be y-monotone, like the pieces produced by
+/// [`Polygon::decompose_monotone`]. Other polygons produce overlapping or
+/// missing triangles. The output follows the same rules as
+/// [`Polygon::triangulate`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{triangulate_monotone, Polygon},
+///         nalgebra::point,
+///     };
+///
+///     let diamond = Polygon::new(vec![
+///         point![0.0, -2.0],
+///         point![1.0, -1.0],
+///         point![1.0, 1.0],
+///         point![0.0, 2.0],
+///         point![-1.0, 1.0],
+///         point![-1.0, -1.0],
+///     ]);
+///
+///     assert_eq!(triangulate_monotone(&diamond).len(), 4);
+///
+pub fn triangulate_monotone(polygon: &Polygon) -> Vec<[usize; 3]> {
+    triangulate_monotone_piece(
+        &polygon.vertices,
+        &polygon.counterclockwise_order(),
+    )
+}
+
+/// Returns true when `a` is processed before `b` by the sweep, which moves
+/// from top to bottom and breaks ties from left to right.
+fn is_above(a: &Point2<f32>, b: &Point2<f32>) -> bool {
+    a.y > b.y || (a.y == b.y && a.x < b.x)
+}
+
+/// The order that the sweep processes points in.
+fn sweep_order(a: &Point2<f32>, b: &Point2<f32>) -> Ordering {
+    b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x))
+}
+
+/// Positive when a, b, c turn counterclockwise and negative when they turn
+/// clockwise.
+fn cross(a: &Point2<f32>, b: &Point2<f32>, c: &Point2<f32>) -> f32 {
+    (b - a).perp(&(c - a))
+}
+
+/// Clip ears from a counterclockwise ring of vertex indices.
+pub(super) fn ear_clip(
+    vertices: &[Point2<f32>],
+    mut ring: Vec<usize>,
+) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    let mut start = 0;
+    while ring.len() > 3 {
+        let n = ring.len();
+        let corner =
+            |i: usize| (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let turn = |i: usize| {
+            let (a, b, c) = corner(i);
+            cross(&vertices[a], &vertices[b], &vertices[c])
+        };
+        let is_ear = |i: usize| {
+            let (a, b, c) = corner(i);
+            let (pa, pb, pc) = (vertices[a], vertices[b], vertices[c]);
+            turn(i) > 0.0
+                && ring.iter().all(|&j| {
+                    let p = vertices[j];
+                    j == a
+                        || j == b
+                        || j == c
+                        || cross(&pa, &pb, &p) < 0.0
+                        || cross(&pb, &pc, &p) < 0.0
+                        || cross(&pc, &pa, &p) < 0.0
+                })
+        };
+
+        // Resume the search where the last ear was clipped. Degenerate rings
+        // with collinear or touching vertices might not have a strict ear, so
+        // fall back to any vertex which doesn't turn clockwise.
+        let candidates = || (0..n).map(|k| (start + k) % n);
+        let ear = candidates()
+            .find(|&i| is_ear(i))
+            .or_else(|| candidates().find(|&i| turn(i) >= 0.0))
+            .unwrap_or(start % n);
+        let (a, b, c) = corner(ear);
+        triangles.push([a, b, c]);
+        ring.remove(ear);
+        start = ear;
+    }
+    if let [a, b, c] = ring[..] {
+        triangles.push([a, b, c]);
+    }
+    triangles
+}
+
+/// How a vertex changes the sweep line's crossings with the polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VertexKind {
+    /// Both neighbors are below and the interior angle is convex.
+    Start,
+
+    /// Both neighbors are below and the interior angle is reflex.
+    Split,
+
+    /// Both neighbors are above and the interior angle is convex.
+    End,
+
+    /// Both neighbors are above and the interior angle is reflex.
+    Merge,
+
+    /// The boundary passes through downwards, with the interior on the right.
+    RegularLeft,
+
+    /// The boundary passes through upwards, with the interior on the left.
+    RegularRight,
+}
+
+/// Split a counterclockwise ring of vertex indices into y-monotone pieces.
+///
+/// Each piece is a counterclockwise list of vertex indices.
+fn monotone_pieces(
+    vertices: &[Point2<f32>],
+    ring: Vec<usize>,
+) -> Vec<Vec<usize>> {
+    let n = ring.len();
+    if n < 3 {
+        return vec![];
+    }
+    // Everything below works with positions in the ring. Edge k runs from
+    // ring position k to the next position.
+    let point = |k: usize| vertices[ring[k]];
+    let next = |k: usize| (k + 1) % n;
+    let prev = |k: usize| (k + n - 1) % n;
+
+    let kinds: Vec<VertexKind> = (0..n)
+        .map(|k| {
+            let (before, p, after) = (point(prev(k)), point(k), point(next(k)));
+            let convex = cross(&before, &p, &after) > 0.0;
+            match (is_above(&before, &p), is_above(&after, &p)) {
+                (false, false) if convex => VertexKind::Start,
+                (false, false) => VertexKind::Split,
+                (true, true) if convex => VertexKind::End,
+                (true, true) => VertexKind::Merge,
+                (true, false) => VertexKind::RegularLeft,
+                (false, true) => VertexKind::RegularRight,
+            }
+        })
+        .collect();
+
+    let x_at = |edge: usize, y: f32| {
+        let (a, b) = (point(edge), point(next(edge)));
+        if a.y == b.y {
+            // Horizontal edges are only in the status while the sweep is at
+            // one of their endpoints.
+            a.x
+        } else {
+            a.x + (b.x - a.x) * (y - a.y) / (b.y - a.y)
+        }
+    };
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| sweep_order(&point(a), &point(b)));
+
+    // The edges with the interior on their right which cross the sweep line,
+    // sorted from left to right, along with their helper vertex. The helper
+    // is the lowest vertex above the sweep line which can see the edge.
+    let mut status: Vec<(usize, usize)> = vec![];
+    let mut diagonals: Vec<(usize, usize)> = vec![];
+    for &k in &order {
+        let p = point(k);
+        let left_of_p = |status: &[(usize, usize)]| {
+            status
+                .partition_point(|&(edge, _)| x_at(edge, p.y) < p.x)
+                .checked_sub(1)
+        };
+        let insert = |status: &mut Vec<(usize, usize)>| {
+            let index = left_of_p(status).map_or(0, |i| i + 1);
+            status.insert(index, (k, k));
+        };
+        let finish =
+            |status: &mut Vec<(usize, usize)>,
+             diagonals: &mut Vec<(usize, usize)>| {
+                let edge = prev(k);
+                if let Some(index) = status.iter().position(|&(e, _)| e == edge)
+                {
+                    let (_, helper) = status.remove(index);
+                    if kinds[helper] == VertexKind::Merge {
+                        diagonals.push((k, helper));
+                    }
+                }
+            };
+        let update_left = |status: &mut Vec<(usize, usize)>,
+                           diagonals: &mut Vec<(usize, usize)>,
+                           always_connect: bool| {
+            if let Some(index) = left_of_p(status) {
+                let helper = status[index].1;
+                if always_connect || kinds[helper] == VertexKind::Merge {
+                    diagonals.push((k, helper));
+                }
+                status[index].1 = k;
+            }
+        };
+
+        match kinds[k] {
+            VertexKind::Start => insert(&mut status),
+            VertexKind::End => finish(&mut status, &mut diagonals),
+            VertexKind::Split => {
+                update_left(&mut status, &mut diagonals, true);
+                insert(&mut status);
+            }
+            VertexKind::Merge => {
+                finish(&mut status, &mut diagonals);
+                update_left(&mut status, &mut diagonals, false);
+            }
+            VertexKind::RegularLeft => {
+                finish(&mut status, &mut diagonals);
+                insert(&mut status);
+            }
+            VertexKind::RegularRight => {
+                update_left(&mut status, &mut diagonals, false);
+            }
+        }
+    }
+
+    // Walk the faces formed by the boundary and the diagonals. Leaving each
+    // vertex along the edge with the smallest clockwise turn from the way we
+    // came keeps the face on the left, so faces come out counterclockwise.
+    let mut outgoing: Vec<Vec<usize>> = (0..n).map(|k| vec![next(k)]).collect();
+    for &(a, b) in &diagonals {
+        outgoing[a].push(b);
+        outgoing[b].push(a);
+    }
+    let mut used: Vec<Vec<bool>> = outgoing
+        .iter()
+        .map(|edges| vec![false; edges.len()])
+        .collect();
+    let mut pieces = vec![];
+    for start in 0..n {
+        for first_slot in 0..outgoing[start].len() {
+            if used[start][first_slot] {
+                continue;
+            }
+            let mut piece = vec![];
+            let (mut from, mut slot) = (start, first_slot);
+            while !used[from][slot] {
+                used[from][slot] = true;
+                piece.push(ring[from]);
+                let to = outgoing[from][slot];
+                let back = point(from) - point(to);
+                let clockwise_turn = |s: usize| {
+                    let angle = signed_angle(
+                        &(point(outgoing[to][s]) - point(to)),
+                        &back,
+                    );
+                    if angle <= 0.0 {
+                        angle + TAU
+                    } else {
+                        angle
+                    }
+                };
+                slot = (0..outgoing[to].len())
+                    .min_by(|&a, &b| {
+                        clockwise_turn(a).total_cmp(&clockwise_turn(b))
+                    })
+                    .unwrap_or(0);
+                from = to;
+            }
+            pieces.push(piece);
+        }
+    }
+    pieces
+}
+
+/// Triangulate a y-monotone, counterclockwise piece given as vertex indices.
+fn triangulate_monotone_piece(
+    vertices: &[Point2<f32>],
+    piece: &[usize],
+) -> Vec<[usize; 3]> {
+    let n = piece.len();
+    if n < 3 {
+        return vec![];
+    }
+    let point = |k: usize| vertices[piece[k]];
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| sweep_order(&point(a), &point(b)));
+    let (top, bottom) = (order[0], order[n - 1]);
+
+    // Walking counterclockwise from the top vertex runs down the left chain.
+    let mut on_left = vec![false; n];
+    let mut k = (top + 1) % n;
+    while k != bottom {
+        on_left[k] = true;
+        k = (k + 1) % n;
+    }
+
+    let mut triangles = Vec::with_capacity(n - 2);
+    let mut push = |a: usize, b: usize, c: usize| {
+        let (b, c) = if cross(&point(a), &point(b), &point(c)) < 0.0 {
+            (c, b)
+        } else {
+            (b, c)
+        };
+        triangles.push([piece[a], piece[b], piece[c]]);
+    };
+
+    // The stack holds vertices which still need triangles. They form a
+    // reflex chain ending with the most recent vertex.
+    let mut stack = vec![order[0], order[1]];
+    for &current in &order[2..n - 1] {
+        let last = stack[stack.len() - 1];
+        if on_left[current] != on_left[last] {
+            // The current vertex is on the opposite chain, so it can see
+            // every vertex on the stack.
+            for pair in stack.windows(2) {
+                push(current, pair[0], pair[1]);
+            }
+            stack = vec![last, current];
+        } else {
+            let mut last = stack.pop().unwrap_or(current);
+            while let Some(&candidate) = stack.last() {
+                let turn =
+                    cross(&point(candidate), &point(last), &point(current));
+                let visible = if on_left[current] {
+                    turn > 0.0
+                } else {
+                    turn < 0.0
+                };
+                if !visible {
+                    break;
+                }
+                push(current, last, candidate);
+                last = candidate;
+                stack.pop();
+            }
+            stack.push(last);
+            stack.push(current);
+        }
+    }
+    for pair in stack.windows(2) {
+        push(bottom, pair[0], pair[1]);
+    }
+    triangles
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            polygon::{triangulate_monotone, Polygon},
+            test_support::polygon,
+        },
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    fn triangle_area(polygon: &Polygon, triangle: &[usize; 3]) -> f32 {
+        let [a, b, c] = triangle.map(|i| polygon.vertices[i]);
+        (b - a).perp(&(c - a)) * 0.5
+    }
+
+    /// Check the triangulation contract: n - 2 counterclockwise triangles
+    /// whose areas sum to the polygon's area.
+    fn check_triangles(polygon: &Polygon, triangles: &[[usize; 3]]) {
+        assert_eq!(triangles.len(), polygon.vertices.len() - 2);
+        let mut total = 0.0;
+        for triangle in triangles {
+            let area = triangle_area(polygon, triangle);
+            assert!(area >= -1e-6, "{triangle:?} is clockwise");
+            total += area;
+        }
+        assert_relative_eq!(total, polygon.area(), max_relative = 1e-4);
+    }
+
+    /// Returns true when the polygon has exactly one vertex whose neighbors
+    /// are both lower in the sweep order.
+    fn is_y_monotone(polygon: &Polygon) -> bool {
+        let n = polygon.vertices.len();
+        let tops = (0..n)
+            .filter(|&k| {
+                let p = polygon.vertices[k];
+                let before = polygon.vertices[(k + n - 1) % n];
+                let after = polygon.vertices[(k + 1) % n];
+                super::is_above(&p, &before) && super::is_above(&p, &after)
+            })
+            .count();
+        tops == 1
+    }
+
+    /// A polygon with a notch cut into the bottom and another cut into the
+    /// top, so it has both a split and a merge vertex. Every edge is
+    /// horizontal or vertical.
+    fn notched() -> Polygon {
+        Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 1.0],
+            point![3.0, 1.0],
+            point![3.0, 0.0],
+            point![6.0, 0.0],
+            point![6.0, 3.0],
+            point![4.0, 3.0],
+            point![4.0, 2.0],
+            point![2.0, 2.0],
+            point![2.0, 3.0],
+            point![0.0, 3.0],
+        ])
+    }
+
+    #[test]
+    pub fn triangles_should_triangulate_to_themselves() {
+        let triangle = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![0.0, 1.0],
+        ]);
+
+        assert_eq!(triangle.triangulate(), vec![[0, 1, 2]]);
+        check_triangles(&triangle, &triangulate_monotone(&triangle));
+        assert_eq!(triangle.decompose_monotone(), vec![triangle]);
+    }
+
+    #[test]
+    pub fn degenerate_polygons_should_have_no_triangles() {
+        let segment = Polygon::new(vec![point![0.0, 0.0], point![1.0, 0.0]]);
+
+        assert!(segment.triangulate().is_empty());
+        assert!(segment.triangulate_fast().is_empty());
+        assert!(segment.decompose_monotone().is_empty());
+    }
+
+    #[test]
+    pub fn notched_polygons_should_split_at_horizontal_edges() {
+        let notched = notched();
+
+        let pieces = notched.decompose_monotone();
+
+        assert!(pieces.len() > 1);
+        assert!(pieces.iter().all(is_y_monotone));
+        let area: f32 = pieces.iter().map(|piece| piece.signed_area()).sum();
+        assert_relative_eq!(area, notched.area());
+        check_triangles(&notched, &notched.triangulate_sweep());
+        check_triangles(&notched, &notched.triangulate());
+    }
+
+    #[test]
+    pub fn clockwise_polygons_should_produce_counterclockwise_triangles() {
+        let mut notched = notched();
+        notched.vertices.reverse();
+
+        check_triangles(&notched, &notched.triangulate_sweep());
+        check_triangles(&notched, &notched.triangulate());
+        for piece in notched.decompose_monotone() {
+            assert!(piece.signed_area() > 0.0);
+        }
+    }
+
+    #[test]
+    pub fn combs_should_triangulate_with_a_long_horizontal_edge() {
+        // A single long bottom edge with teeth sticking up, so every valley
+        // between the teeth is a merge vertex.
+        let mut vertices = vec![point![0.0, 0.0], point![20.0, 0.0]];
+        for tooth in (0..10).rev() {
+            let x = tooth as f32 * 2.0;
+            vertices.push(point![x + 2.0, 5.0]);
+            vertices.push(point![x + 1.0, 5.0]);
+            vertices.push(point![x + 1.0, 1.0]);
+        }
+        vertices.pop();
+        vertices.push(point![0.0, 5.0]);
+        let comb = Polygon::new(vertices);
+
+        let pieces = comb.decompose_monotone();
+
+        assert_eq!(pieces.len(), 10);
+        assert!(pieces.iter().all(is_y_monotone));
+        check_triangles(&comb, &comb.triangulate_fast());
+        check_triangles(&comb, &comb.triangulate());
+    }
+
+    proptest! {
+        #[test]
+        fn triangulations_should_cover_random_polygons(
+            polygon in polygon(-10.0..=10.0, 3..=40, false),
+        ) {
+            check_triangles(&polygon, &polygon.triangulate());
+            check_triangles(&polygon, &polygon.triangulate_sweep());
+        }
+
+        #[test]
+        fn monotone_pieces_should_cover_random_polygons(
+            polygon in polygon(-10.0..=10.0, 3..=40, false),
+        ) {
+            let pieces = polygon.decompose_monotone();
+
+            let area: f32 = pieces.iter().map(|piece| piece.signed_area()).sum();
+            assert_relative_eq!(area, polygon.area(), max_relative = 1e-4);
+            for piece in &pieces {
+                prop_assert!(is_y_monotone(piece));
+                check_triangles(piece, &triangulate_monotone(piece));
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use {
+    crate::{
+        operations::signed_angle,
+        polygon::{Polygon, Polyline},
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+impl Polyline {
+    /// The signed exterior angle at each interior vertex.
+    ///
+    /// Positive angles turn counterclockwise (left) and negative angles turn
+    /// clockwise (right). There is one angle for each vertex except the first
+    /// and the last.
+    ///
+    /// Collinear vertices have an angle of 0. When consecutive vertices are
+    /// duplicates, the turn is reported at the last copy and the other copies
+    /// have an angle of 0.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polyline,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let path = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![2.0, 0.0],
+    ///         point![2.0, 1.0],
+    ///     ]);
+    ///     let angles = path.turning_angles();
+    ///
+    ///     assert_eq!(angles[0], 0.0);
+    ///     assert_relative_eq!(angles[1], core::f32::consts::FRAC_PI_2);
+    ///
+    pub fn turning_angles(&self) -> Vec<f32> {
+        turns(&self.vertices, false)
+            .map(|turn| turn.angle)
+            .collect()
+    }
+
+    /// The sum of every turning angle along the polyline.
+    pub fn total_turning(&self) -> f32 {
+        turns(&self.vertices, false).map(|turn| turn.angle).sum()
+    }
+
+    /// The discrete curvature at each interior vertex.
+    ///
+    /// This is the turning angle divided by the average length of the edges
+    /// on either side of the vertex. Like [`Polyline::turning_angles`], zero
+    /// length edges are skipped and duplicate vertices have a curvature of 0.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polyline,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let path = Polyline::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![1.0, -3.0],
+    ///     ]);
+    ///
+    ///     // a quarter turn to the right over an average edge length of 2
+    ///     assert_relative_eq!(
+    ///         path.discrete_curvature()[0],
+    ///         -core::f32::consts::FRAC_PI_4
+    ///     );
+    ///
+    pub fn discrete_curvature(&self) -> Vec<f32> {
+        turns(&self.vertices, false)
+            .map(|turn| turn.curvature())
+            .collect()
+    }
+}
+
+impl Polygon {
+    /// The sum of the signed exterior angles at every vertex.
+    ///
+    /// For a simple polygon this is `2*pi` when the vertices are in
+    /// counterclockwise order and `-2*pi` when they are in clockwise order.
+    /// In general it's `2*pi` times the polygon's turning number, so a
+    /// figure-eight has a total turning of 0. This makes it a robust way to
+    /// find the winding of a polygon and a quick check for some kinds of
+    /// self-overlap.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::polygon::Polygon,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let triangle = Polygon::new(vec![
+    ///         point![0.0, 0.0],
+    ///         point![1.0, 0.0],
+    ///         point![0.0, 1.0],
+    ///     ]);
+    ///
+    ///     assert_relative_eq!(
+    ///         triangle.total_turning(),
+    ///         core::f32::consts::TAU
+    ///     );
+    ///
+    pub fn total_turning(&self) -> f32 {
+        turns(&self.vertices, true).map(|turn| turn.angle).sum()
+    }
+}
+
+/// The turn at a single vertex.
+struct Turn {
+    angle: f32,
+    average_edge_length: f32,
+}
+
+impl Turn {
+    fn curvature(&self) -> f32 {
+        if self.average_edge_length == 0.0 {
+            0.0
+        } else {
+            self.angle / self.average_edge_length
+        }
+    }
+}
+
+/// Compute the turn at each vertex where a path can change direction.
+///
+/// Open paths have a turn at every interior vertex. Closed paths have a turn
+/// at every vertex.
+fn turns(
+    vertices: &[Point2<f32>],
+    closed: bool,
+) -> impl Iterator<Item = Turn> + '_ {
+    let n = vertices.len();
+    let edge = move |i: usize| vertices[(i + 1) % n] - vertices[i % n];
+    let range = if closed { 0..n } else { 1..n.saturating_sub(1) };
+    range.map(move |i| {
+        let outgoing = edge(i);
+        // Walk backwards past zero-length edges to find the incoming
+        // direction. Closed paths can wrap around to the end.
+        let incoming = (1..=if closed { n } else { i })
+            .map(|back| edge((i + n - back) % n))
+            .find(|e| *e != Vector2::zeros());
+        match incoming {
+            Some(incoming) if outgoing != Vector2::zeros() => Turn {
+                angle: signed_angle(&incoming, &outgoing),
+                average_edge_length: (incoming.norm() + outgoing.norm()) * 0.5,
+            },
+            _ => Turn {
+                angle: 0.0,
+                average_edge_length: 0.0,
+            },
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::polygon::{Polygon, Polyline},
+        approx::assert_relative_eq,
+        core::f32::consts::TAU,
+        nalgebra::{point, Point2},
+    };
+
+    fn sample(
+        count: usize,
+        f: impl Fn(f32) -> Point2<f32>,
+    ) -> Vec<Point2<f32>> {
+        (0..count)
+            .map(|i| f(TAU * i as f32 / count as f32))
+            .collect()
+    }
+
+    #[test]
+    pub fn circles_should_turn_once() {
+        let circle = Polygon::new(sample(64, |t| point![t.cos(), t.sin()]));
+        assert_relative_eq!(circle.total_turning(), TAU, epsilon = 1e-4);
+
+        let mut clockwise = circle.clone();
+        clockwise.vertices.reverse();
+        assert_relative_eq!(clockwise.total_turning(), -TAU, epsilon = 1e-4);
+    }
+
+    #[test]
+    pub fn figure_eights_should_have_no_total_turning() {
+        let figure_eight =
+            Polygon::new(sample(64, |t| point![t.sin(), t.sin() * t.cos()]));
+
+        assert_relative_eq!(figure_eight.total_turning(), 0.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    pub fn duplicate_and_collinear_vertices_should_not_turn() {
+        let path = Polyline::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 1.0],
+        ]);
+
+        let angles = path.turning_angles();
+
+        assert_eq!(angles.len(), 5);
+        assert_eq!(&angles[..4], &[0.0, 0.0, 0.0, 0.0]);
+        assert_relative_eq!(angles[4], TAU / 4.0);
+        assert!(path.discrete_curvature().iter().all(|k| k.is_finite()));
+        assert_relative_eq!(path.total_turning(), TAU / 4.0);
+    }
+
+    #[test]
+    pub fn polygons_with_duplicate_vertices_should_still_turn_once() {
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+            point![0.0, 0.0],
+        ]);
+
+        assert_relative_eq!(square.total_turning(), TAU, epsilon = 1e-5);
+    }
+
+    #[test]
+    pub fn short_polylines_should_have_no_turns() {
+        let segment = Polyline::new(vec![point![0.0, 0.0], point![1.0, 0.0]]);
+
+        assert!(segment.turning_angles().is_empty());
+        assert!(Polyline::new(vec![]).turning_angles().is_empty());
+        assert_eq!(Polygon::new(vec![]).total_turning(), 0.0);
+    }
+}
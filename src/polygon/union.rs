@@ -0,0 +1,459 @@
+use {
+    crate::{
+        dcel::Dcel,
+        graph::segment_graph,
+        line::Segment,
+        polygon::{nesting_tree, Polygon, Region},
+        shape::{Aabb, BoundingBox},
+        spatial::Bvh,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// The tolerance for welding the vertices of overlapping polygons together,
+/// as a fraction of the size of the polygons' combined bounding box.
+const RELATIVE_TOLERANCE: f32 = 1e-5;
+
+/// Compute the union of many polygons, which may overlap.
+///
+/// The result is the set of regions covered by at least one polygon. Each
+/// region has a counterclockwise outer boundary and clockwise holes, where
+/// polygons surround an uncovered area without filling it. Polygons which
+/// share an edge or a stretch of boundary are merged, and exact duplicates
+/// or polygons nested inside another polygon add nothing.
+///
+/// Polygons are grouped by which bounding boxes overlap, and each group is
+/// handled separately, so scattered polygons are cheap. A polygon that
+/// doesn't overlap anything else is returned as it is. The boundaries in a
+/// group are cut wherever they cross, which takes time quadratic in the
+/// number of edges in the group. Vertices which end up in the middle of a
+/// straight stretch of the union's boundary are removed.
+///
+/// The polygons should be simple. Polygons with fewer than three vertices
+/// or no area are ignored.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{union_all, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = |x: f32, y: f32| {
+///         Polygon::new(vec![
+///             point![x, y],
+///             point![x + 2.0, y],
+///             point![x + 2.0, y + 2.0],
+///             point![x, y + 2.0],
+///         ])
+///     };
+///
+///     // Two overlapping squares and one off to the side.
+///     let regions =
+///         union_all(&[square(0.0, 0.0), square(1.0, 1.0), square(5.0, 5.0)]);
+///
+///     assert_eq!(regions.len(), 2);
+///     assert_eq!(regions[0].outer.vertices.len(), 8);
+///     assert_relative_eq!(regions[0].area(), 7.0, epsilon = 1e-4);
+///     assert_eq!(regions[1].outer, square(5.0, 5.0));
+///
+pub fn union_all(polygons: &[Polygon]) -> Vec<Region> {
+    let polygons: Vec<&Polygon> = polygons
+        .iter()
+        .filter(|polygon| polygon.vertices.len() >= 3 && polygon.area() > 0.0)
+        .collect();
+    let mut regions = Vec::new();
+    for group in overlapping_groups(&polygons) {
+        if let [single] = group[..] {
+            regions.push(Region::new(polygons[single].clone(), Vec::new()));
+            continue;
+        }
+        let members: Vec<&Polygon> =
+            group.iter().map(|&index| polygons[index]).collect();
+        regions.extend(union_group(&members));
+    }
+    regions
+}
+
+/// The total area covered by at least one of the polygons.
+///
+/// Overlaps are only counted once, so this is the area of
+/// [`union_all`]'s regions. It's handy for measuring how much of a canvas
+/// is covered by a pile of shapes.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::polygon::{covered_area, Polygon},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = |x: f32| {
+///         Polygon::new(vec![
+///             point![x, 0.0],
+///             point![x + 2.0, 0.0],
+///             point![x + 2.0, 2.0],
+///             point![x, 2.0],
+///         ])
+///     };
+///
+///     let area = covered_area(&[square(0.0), square(1.0), square(1.0)]);
+///
+///     assert_relative_eq!(area, 6.0, epsilon = 1e-4);
+///
+pub fn covered_area(polygons: &[Polygon]) -> f32 {
+    union_all(polygons).iter().map(Region::area).sum()
+}
+
+/// Group the polygons whose bounding boxes touch, directly or through a
+/// chain of other polygons. Groups are sorted, and come in the order of
+/// their first polygon.
+fn overlapping_groups(polygons: &[&Polygon]) -> Vec<Vec<usize>> {
+    let boxes: Vec<Aabb> = polygons
+        .iter()
+        .map(|polygon| polygon.bounding_box())
+        .collect();
+    let bvh = Bvh::new(boxes.clone());
+    let mut grouped = vec![false; polygons.len()];
+    let mut groups = Vec::new();
+    for first in 0..polygons.len() {
+        if grouped[first] {
+            continue;
+        }
+        grouped[first] = true;
+        let mut group = vec![first];
+        let mut next = 0;
+        while next < group.len() {
+            for other in bvh.query_aabb(&boxes[group[next]]) {
+                if !grouped[other] {
+                    grouped[other] = true;
+                    group.push(other);
+                }
+            }
+            next += 1;
+        }
+        group.sort_unstable();
+        groups.push(group);
+    }
+    groups
+}
+
+/// The union of a group of polygons whose boundaries may cross.
+fn union_group(polygons: &[&Polygon]) -> Vec<Region> {
+    let bounds = polygons
+        .iter()
+        .map(|polygon| polygon.bounding_box())
+        .fold(Aabb::empty(), |bounds, other| bounds.merged(&other));
+    let tolerance = bounds.width().max(bounds.height()) * RELATIVE_TOLERANCE;
+
+    // Cut the boundaries into pieces which only meet at their ends, and
+    // decide which faces of the resulting subdivision are covered.
+    let edges: Vec<Segment> = polygons
+        .iter()
+        .flat_map(|polygon| polygon.edges())
+        .collect();
+    let pieces: Vec<Segment> = segment_graph(&edges, tolerance)
+        .edges()
+        .iter()
+        .map(|edge| edge.segment)
+        .collect();
+    let dcel = Dcel::from_segments(&pieces, tolerance * 0.5);
+    let boxes = Bvh::new(
+        polygons
+            .iter()
+            .map(|polygon| polygon.bounding_box())
+            .collect(),
+    );
+    let covered: Vec<bool> = (0..dcel.faces().len())
+        .map(|face| {
+            let sample = face_sample(&dcel, face, tolerance * 0.5);
+            boxes
+                .query_point(&sample)
+                .into_iter()
+                .any(|index| polygons[index].contains_point(&sample))
+        })
+        .collect();
+
+    // The union's boundary is every half-edge with a covered face on its
+    // left and an uncovered face on its right.
+    let half_edges = dcel.half_edges();
+    let on_boundary = |e: usize| {
+        covered[half_edges[e].face]
+            && !covered[half_edges[half_edges[e].twin].face]
+    };
+    let mut visited = vec![false; half_edges.len()];
+    let mut loops = Vec::new();
+    for start in 0..half_edges.len() {
+        if visited[start] || !on_boundary(start) {
+            continue;
+        }
+        let mut vertices = Vec::new();
+        let mut e = start;
+        loop {
+            visited[e] = true;
+            vertices.push(dcel.vertices()[half_edges[e].origin]);
+            // Turn around the end vertex, through covered faces, until the
+            // boundary continues.
+            e = half_edges[e].next;
+            while !on_boundary(e) {
+                e = half_edges[half_edges[e].twin].next;
+            }
+            if e == start {
+                break;
+            }
+        }
+        let vertices = without_straight_vertices(vertices, tolerance);
+        if vertices.len() >= 3 {
+            loops.push(Polygon::new(vertices));
+        }
+    }
+    regions_from_loops(loops)
+}
+
+/// A point just to the left of the middle of a face's longest half-edge,
+/// which is inside the face.
+fn face_sample(dcel: &Dcel, face: usize, offset: f32) -> Point2<f32> {
+    let vertices = dcel.vertices();
+    let edge = |e: usize| {
+        let start = vertices[dcel.half_edges()[e].origin];
+        (start, vertices[dcel.destination(e)] - start)
+    };
+    let longest = dcel
+        .face_half_edges(face)
+        .into_iter()
+        .map(edge)
+        .max_by(|a, b| a.1.norm_squared().total_cmp(&b.1.norm_squared()))
+        .expect("faces always have at least two half-edges");
+    let (start, direction) = longest;
+    let left = Vector2::new(-direction.y, direction.x).normalize();
+    start + direction * 0.5 + left * offset
+}
+
+/// Remove the vertices which are within `tolerance` of a straight line
+/// between their neighbors.
+fn without_straight_vertices(
+    vertices: Vec<Point2<f32>>,
+    tolerance: f32,
+) -> Vec<Point2<f32>> {
+    let straight = |a: &Point2<f32>, b: &Point2<f32>, c: &Point2<f32>| {
+        let base = (c - a).norm();
+        base > 0.0
+            && (b - a).dot(&(c - b)) > 0.0
+            && ((b - a).perp(&(c - b)) / base).abs() <= tolerance
+    };
+    let mut kept: Vec<Point2<f32>> = Vec::with_capacity(vertices.len());
+    for vertex in vertices {
+        while kept.len() >= 2
+            && straight(&kept[kept.len() - 2], &kept[kept.len() - 1], &vertex)
+        {
+            kept.pop();
+        }
+        kept.push(vertex);
+    }
+    while kept.len() >= 3
+        && straight(&kept[kept.len() - 2], &kept[kept.len() - 1], &kept[0])
+    {
+        kept.pop();
+    }
+    while kept.len() >= 3 && straight(&kept[kept.len() - 1], &kept[0], &kept[1])
+    {
+        kept.remove(0);
+    }
+    kept
+}
+
+/// Sort boundary loops into regions. Counterclockwise loops are outer
+/// boundaries and clockwise loops are holes in the nearest counterclockwise
+/// loop around them.
+fn regions_from_loops(loops: Vec<Polygon>) -> Vec<Region> {
+    let tree = nesting_tree(&loops);
+    let mut region_of = vec![None; loops.len()];
+    let mut regions = Vec::new();
+    for (index, boundary) in loops.iter().enumerate() {
+        if boundary.signed_area() > 0.0 {
+            region_of[index] = Some(regions.len());
+            regions.push(Region::new(boundary.clone(), Vec::new()));
+        }
+    }
+    for (index, boundary) in loops.into_iter().enumerate() {
+        if region_of[index].is_some() {
+            continue;
+        }
+        let mut parent = tree.parent(index);
+        while let Some(candidate) = parent {
+            if let Some(region) = region_of[candidate] {
+                regions[region].holes.push(boundary);
+                break;
+            }
+            parent = tree.parent(candidate);
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{covered_area, union_all},
+        crate::{
+            line::DistanceToPoint,
+            polygon::Polygon,
+            test_support::{point2, polygon, rectangle},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn duplicates_should_only_count_once() {
+        let square = rectangle(point![0.0, 0.0], point![3.0, 3.0]);
+        let mut reversed = square.clone();
+        reversed.vertices.reverse();
+
+        let regions = union_all(&[square.clone(), reversed, square.clone()]);
+
+        assert_eq!(regions.len(), 1);
+        assert!(regions[0].holes.is_empty());
+        assert_eq!(regions[0].outer.vertices.len(), 4);
+        assert_relative_eq!(regions[0].area(), 9.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    pub fn nested_polygons_should_disappear_into_their_parent() {
+        let outer = rectangle(point![0.0, 0.0], point![10.0, 10.0]);
+        let inner = rectangle(point![2.0, 2.0], point![4.0, 4.0]);
+        let touching = rectangle(point![0.0, 5.0], point![3.0, 8.0]);
+
+        let regions = union_all(&[inner, outer, touching]);
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].outer.vertices.len(), 4);
+        assert_relative_eq!(regions[0].area(), 100.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    pub fn chains_of_overlaps_should_merge_into_one_region() {
+        // Each square only overlaps its neighbors.
+        let squares: Vec<Polygon> = (0..6)
+            .map(|i| {
+                let x = i as f32 * 1.5;
+                rectangle(point![x, 0.0], point![x + 2.0, 2.0])
+            })
+            .collect();
+
+        let regions = union_all(&squares);
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].outer.vertices.len(), 4);
+        assert_relative_eq!(regions[0].area(), 2.0 * 9.5, epsilon = 1e-3);
+    }
+
+    #[test]
+    pub fn rings_of_polygons_should_leave_holes() {
+        let frame = [
+            rectangle(point![0.0, 0.0], point![4.0, 1.0]),
+            rectangle(point![3.0, 0.0], point![4.0, 4.0]),
+            rectangle(point![0.0, 3.0], point![4.0, 4.0]),
+            rectangle(point![0.0, 0.0], point![1.0, 4.0]),
+            // An island in the middle of the hole.
+            rectangle(point![1.5, 1.5], point![2.5, 2.5]),
+        ];
+
+        let mut regions = union_all(&frame);
+        regions.sort_by(|a, b| a.area().total_cmp(&b.area()));
+
+        assert_eq!(regions.len(), 2);
+        assert_relative_eq!(regions[0].area(), 1.0, epsilon = 1e-4);
+        assert_eq!(regions[1].holes.len(), 1);
+        assert!(regions[1].holes[0].signed_area() < 0.0);
+        assert_relative_eq!(regions[1].area(), 12.0, epsilon = 1e-4);
+        assert!(regions[1].contains_point(&point![0.5, 2.0]));
+        assert!(!regions[1].contains_point(&point![1.2, 2.0]));
+    }
+
+    #[test]
+    pub fn empty_and_degenerate_polygons_should_be_ignored() {
+        let sliver = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![1.0, 1.0],
+            point![2.0, 2.0],
+        ]);
+
+        assert!(union_all(&[]).is_empty());
+        assert!(union_all(&[sliver, Polygon::new(vec![])]).is_empty());
+        assert_eq!(covered_area(&[]), 0.0);
+    }
+
+    /// Blobs scattered around a 16 by 16 canvas.
+    fn blobs() -> impl Strategy<Value = Vec<Polygon>> {
+        let blob = (polygon(-2.0..=2.0, 3..=8, false), point2(-6.0..=6.0))
+            .prop_map(|(mut blob, offset)| {
+                for vertex in &mut blob.vertices {
+                    *vertex += offset.coords;
+                }
+                blob
+            });
+        prop::collection::vec(blob, 1..12)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn covered_area_should_match_monte_carlo_sampling(
+            blobs in blobs(),
+            samples in prop::collection::vec(point2(-8.0..=8.0), 4000),
+        ) {
+            let canvas = 16.0 * 16.0;
+            let hits = samples
+                .iter()
+                .filter(|sample| {
+                    blobs.iter().any(|blob| blob.contains_point(sample))
+                })
+                .count();
+            let fraction = hits as f32 / samples.len() as f32;
+            let deviation =
+                (fraction * (1.0 - fraction) / samples.len() as f32).sqrt();
+
+            let area = covered_area(&blobs);
+
+            prop_assert!(
+                (area / canvas - fraction).abs() <= 5.0 * deviation + 2e-3,
+                "{} vs {}",
+                area,
+                fraction * canvas
+            );
+        }
+
+        #[test]
+        fn union_boundaries_should_separate_covered_points(
+            blobs in blobs(),
+            samples in prop::collection::vec(point2(-8.0..=8.0), 200),
+        ) {
+            let regions = union_all(&blobs);
+
+            for sample in &samples {
+                let covered = blobs.iter().any(|blob| blob.contains_point(sample));
+                let inside = regions
+                    .iter()
+                    .filter(|region| region.contains_point(sample))
+                    .count();
+                let near_boundary = blobs
+                    .iter()
+                    .flat_map(Polygon::edges)
+                    .any(|edge| edge.distance_to_point(sample) < 1e-3);
+                prop_assert!(
+                    near_boundary || inside == usize::from(covered),
+                    "{} is in {} regions",
+                    sample,
+                    inside
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Orthogonal projections onto lines and axes.
+//!
+//! Projecting shapes onto an axis reduces overlap questions to one
+//! dimension. This is the basis of the separating axis test and of measuring
+//! how much of a line is covered by a collection of segments.
+
+use {
+    crate::line::{Line, Segment},
+    nalgebra::Point2,
+};
+#[cfg(feature = "alloc")]
+use {
+    crate::{interval::Interval, polygon::Polygon},
+    nalgebra::{Unit, Vector2},
+};
+
+/// Project a point onto a line along the line's normal.
+///
+/// This is the same as [`Line::closest_point`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Line, projection::project_point_onto_line},
+///         nalgebra::{point, vector},
+///     };
+///
+///     let floor = Line::try_new(vector![0.0, 1.0], 0.0).unwrap();
+///
+///     assert_eq!(
+///         project_point_onto_line(&point![2.0, 3.0], &floor),
+///         point![2.0, 0.0]
+///     );
+///
+pub fn project_point_onto_line(
+    point: &Point2<f32>,
+    line: &Line,
+) -> Point2<f32> {
+    line.closest_point(point)
+}
+
+/// Project a segment onto a line along the line's normal.
+///
+/// The result is the shadow the segment casts onto the line when lit from
+/// directly above. It keeps the segment's direction, and has zero length
+/// when the segment is perpendicular to the line.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::{Line, Segment},
+///             projection::project_segment_onto_line,
+///         },
+///         nalgebra::{point, vector},
+///     };
+///
+///     let floor = Line::try_new(vector![0.0, 1.0], 0.0).unwrap();
+///     let stick = Segment::new(point![1.0, 1.0], point![4.0, 5.0]);
+///
+///     assert_eq!(
+///         project_segment_onto_line(&stick, &floor),
+///         Segment::new(point![1.0, 0.0], point![4.0, 0.0])
+///     );
+///
+pub fn project_segment_onto_line(segment: &Segment, line: &Line) -> Segment {
+    Segment::new(
+        line.closest_point(&segment.start),
+        line.closest_point(&segment.end),
+    )
+}
+
+/// The range of positions a polygon's vertices cover along an axis.
+///
+/// Positions are measured from the origin in units of the axis, so two
+/// polygons are separated along the axis when their intervals don't overlap.
+/// A polygon without vertices projects to an interval which starts at
+/// infinity and ends at negative infinity, which overlaps nothing.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{polygon::Polygon, projection::project_polygon_onto_axis},
+///         nalgebra::{point, vector, Unit},
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![1.0, 1.0],
+///         point![3.0, 1.0],
+///         point![3.0, 3.0],
+///         point![1.0, 3.0],
+///     ]);
+///     let x_axis = Unit::new_normalize(vector![1.0, 0.0]);
+///
+///     let interval = project_polygon_onto_axis(&square, &x_axis);
+///
+///     assert_eq!((interval.start, interval.end), (1.0, 3.0));
+///
+#[cfg(feature = "alloc")]
+pub fn project_polygon_onto_axis(
+    polygon: &Polygon,
+    axis: &Unit<Vector2<f32>>,
+) -> Interval {
+    polygon.vertices.iter().map(|v| v.coords.dot(axis)).fold(
+        Interval {
+            start: f32::INFINITY,
+            end: f32::NEG_INFINITY,
+        },
+        |interval, position| Interval {
+            start: interval.start.min(position),
+            end: interval.end.max(position),
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            project_point_onto_line, project_polygon_onto_axis,
+            project_segment_onto_line,
+        },
+        crate::{
+            line::{DistanceToPoint, Line, Segment},
+            polygon::Polygon,
+            test_support::{line, point2, polygon, segment, unit_vector},
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn segments_on_the_line_should_not_move() {
+        let line = Line::try_new(vector![1.0, 1.0], -2.0).unwrap();
+        let d = 2.0 * 2.0f32.sqrt();
+        let segment = Segment::new(point![0.0, d], point![d, 0.0]);
+
+        let projected = project_segment_onto_line(&segment, &line);
+
+        assert_relative_eq!(projected.start, segment.start, epsilon = 1e-6);
+        assert_relative_eq!(projected.end, segment.end, epsilon = 1e-6);
+    }
+
+    #[test]
+    pub fn empty_polygons_should_not_overlap_anything() {
+        let axis = Unit::new_normalize(vector![1.0, 0.0]);
+
+        let interval = project_polygon_onto_axis(&Polygon::new(vec![]), &axis);
+
+        assert!(!interval.overlaps(&interval));
+    }
+
+    proptest! {
+        #[test]
+        fn projected_points_should_be_on_the_line(
+            p in point2(-10.0..=10.0),
+            line in line(-10.0..=10.0),
+        ) {
+            let projected = project_point_onto_line(&p, &line);
+
+            prop_assert!(line.distance_to_point(&projected).abs() < 1e-4);
+            assert_relative_eq!(
+                (p - projected).norm(),
+                line.distance_to_point(&p).abs(),
+                epsilon = 1e-4
+            );
+        }
+
+        #[test]
+        fn projected_length_should_scale_by_the_cosine(
+            segment in segment(-10.0..=10.0, 0.1),
+            line in line(-10.0..=10.0),
+        ) {
+            let direction = vector![line.normal.y, -line.normal.x];
+            let along = (segment.end - segment.start).normalize();
+            let cos = along.dot(&direction).abs();
+
+            let projected = project_segment_onto_line(&segment, &line);
+
+            assert_relative_eq!(
+                projected.length(),
+                segment.length() * cos,
+                epsilon = 1e-3
+            );
+        }
+
+        #[test]
+        fn axis_intervals_should_contain_every_vertex(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            axis in unit_vector(),
+        ) {
+            let interval = project_polygon_onto_axis(&polygon, &axis);
+
+            for v in &polygon.vertices {
+                prop_assert!(interval.contains(v.coords.dot(&axis)));
+            }
+        }
+    }
+}
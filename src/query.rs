@@ -0,0 +1,575 @@
+//! Queries which operate on collections of geometry.
+//!
+//! # Signed Distances
+//!
+//! Some implementations of [`DistanceToPoint`] return signed values. For
+//! example, [`Line`](crate::line::Line) returns a negative distance for points
+//! 'below' the line and [`Ray`](crate::line::Ray) returns a negative distance
+//! (and a negative squared distance!) for points 'behind' the origin. The
+//! queries in this module compare the *absolute* values of these distances so
+//! that objects of every kind can be ranked against each other.
+
+use {
+    crate::{line::DistanceToPoint, math},
+    nalgebra::{Point2, Unit, Vector2},
+};
+#[cfg(feature = "alloc")]
+use {
+    crate::{operations::AngleInterval, shape::Aabb, spatial::Bvh},
+    alloc::vec::Vec,
+};
+
+/// Find the item which is nearest to the given point.
+///
+/// Returns the index of the nearest item along with its (unsigned) distance
+/// to the point. Items are compared using
+/// [`DistanceToPoint::distance_to_point_squared`] so only a single `sqrt` is
+/// computed for the entire scan. Ties are resolved in favor of the item with
+/// the lowest index.
+///
+/// Returns `None` when `items` is empty.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, query::nearest_in},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let segments = [
+///         Segment::new(point![0.0, 0.0], point![10.0, 0.0]),
+///         Segment::new(point![0.0, 5.0], point![10.0, 5.0]),
+///     ];
+///
+///     let (index, distance) = nearest_in(&segments, &point![3.0, 4.0])
+///         .unwrap();
+///
+///     assert_eq!(index, 1);
+///     assert_relative_eq!(distance, 1.0);
+///
+pub fn nearest_in<T: DistanceToPoint>(
+    items: &[T],
+    point: &Point2<f32>,
+) -> Option<(usize, f32)> {
+    let mut nearest: Option<(usize, f32)> = None;
+    for (index, item) in items.iter().enumerate() {
+        // Signed implementations can report negative squared distances, so
+        // always compare magnitudes.
+        let distance_squared = item.distance_to_point_squared(point).abs();
+        match nearest {
+            Some((_, best)) if best <= distance_squared => (),
+            _ => nearest = Some((index, distance_squared)),
+        }
+    }
+    nearest
+        .map(|(index, distance_squared)| (index, math::sqrt(distance_squared)))
+}
+
+/// Find the point which is furthest along a direction.
+///
+/// Returns the index of the point with the largest projection onto the
+/// direction. Ties are resolved in favor of the point with the lowest
+/// index. Returns `None` when `points` is empty.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::query::extreme_point,
+///         nalgebra::{point, vector, Unit},
+///     };
+///
+///     let points = [point![0.0, 0.0], point![1.0, 3.0], point![2.0, 3.0]];
+///     let up = Unit::new_normalize(vector![0.0, 1.0]);
+///     let right = Unit::new_normalize(vector![1.0, 0.0]);
+///
+///     assert_eq!(extreme_point(&points, &up), Some(1));
+///     assert_eq!(extreme_point(&points, &right), Some(2));
+///
+pub fn extreme_point(
+    points: &[Point2<f32>],
+    direction: &Unit<Vector2<f32>>,
+) -> Option<usize> {
+    let mut extreme: Option<(usize, f32)> = None;
+    for (index, point) in points.iter().enumerate() {
+        let projection = point.coords.dot(direction);
+        match extreme {
+            Some((_, best)) if projection <= best || projection.is_nan() => (),
+            _ => extreme = Some((index, projection)),
+        }
+    }
+    extreme.map(|(index, _)| index)
+}
+
+/// Compute the distance from a single item to every point in a slice.
+///
+/// The output buffer is cleared and then filled with one distance per point,
+/// in the same order as `points`. Reusing the same buffer across calls avoids
+/// allocating on every query.
+///
+/// Distances are reported exactly as [`DistanceToPoint::distance_to_point`]
+/// computes them, so signed implementations will produce negative values.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, query::distances_to_points},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let segment = Segment::new(point![0.0, 0.0], point![10.0, 0.0]);
+///     let points = [point![5.0, 2.0], point![-3.0, 0.0]];
+///
+///     let mut distances = Vec::new();
+///     distances_to_points(&segment, &points, &mut distances);
+///
+///     assert_relative_eq!(distances.as_slice(), [2.0, 3.0].as_slice());
+///
+#[cfg(feature = "alloc")]
+pub fn distances_to_points<T: DistanceToPoint>(
+    item: &T,
+    points: &[Point2<f32>],
+    out: &mut Vec<f32>,
+) {
+    out.clear();
+    out.extend(points.iter().map(|point| item.distance_to_point(point)));
+}
+
+/// Find the nearest item for every point in a slice.
+///
+/// The result has one entry per point, in the same order as `points`. Each
+/// entry is computed with [`nearest_in`].
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, query::nearest_for_points},
+///         nalgebra::point,
+///     };
+///
+///     let segments = [
+///         Segment::new(point![0.0, 0.0], point![10.0, 0.0]),
+///         Segment::new(point![0.0, 5.0], point![10.0, 5.0]),
+///     ];
+///     let points = [point![1.0, 4.0], point![1.0, 1.0]];
+///
+///     let nearest: Vec<usize> = nearest_for_points(&points, &segments)
+///         .into_iter()
+///         .map(|nearest| nearest.unwrap().0)
+///         .collect();
+///
+///     assert_eq!(nearest, vec![1, 0]);
+///
+#[cfg(feature = "alloc")]
+pub fn nearest_for_points<T: DistanceToPoint>(
+    points: &[Point2<f32>],
+    items: &[T],
+) -> Vec<Option<(usize, f32)>> {
+    points
+        .iter()
+        .map(|point| nearest_in(items, point))
+        .collect()
+}
+
+/// Find the points inside a sector of a circle, like a cone of vision.
+///
+/// The sector's tip is at `apex` and it opens toward `heading`, reaching
+/// `half_angle` radians to either side and out to `radius`. Points on the
+/// sector's boundary are included, and so is the apex itself. Directions
+/// are compared with dot products instead of angles, so sectors which
+/// cross the negative x axis, where `atan2` jumps from pi to minus pi, work
+/// like any other. A half angle of pi or more covers every direction, which
+/// makes this a plain radius query.
+///
+/// Returns the indices of the points in increasing order. Returns no points
+/// when `half_angle` or `radius` is negative.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::query::sector,
+///         nalgebra::{point, vector, Unit},
+///     };
+///
+///     let points = [
+///         point![-2.0, 0.5],
+///         point![-2.0, -0.5],
+///         point![0.0, 2.0],
+///         point![-9.0, 0.0],
+///     ];
+///     let left = Unit::new_normalize(vector![-1.0, 0.0]);
+///
+///     let seen = sector(
+///         &points,
+///         point![0.0, 0.0],
+///         left,
+///         core::f32::consts::FRAC_PI_4,
+///         5.0,
+///     );
+///
+///     assert_eq!(seen, vec![0, 1]);
+///
+#[cfg(feature = "alloc")]
+pub fn sector(
+    points: &[Point2<f32>],
+    apex: Point2<f32>,
+    heading: Unit<Vector2<f32>>,
+    half_angle: f32,
+    radius: f32,
+) -> Vec<usize> {
+    let Some(inside) = sector_test(apex, heading, half_angle, radius) else {
+        return Vec::new();
+    };
+    (0..points.len()).filter(|&i| inside(&points[i])).collect()
+}
+
+/// Find the points in a [`Bvh`] which are inside a sector of a circle.
+///
+/// This gives the same result as [`sector`] on the tree's primitives, but
+/// only the points near the sector are tested.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{query::sector_in_bvh, spatial::Bvh},
+///         nalgebra::{point, vector, Unit},
+///     };
+///
+///     let boids = Bvh::new(
+///         (0..100)
+///             .map(|i| point![(i % 10) as f32, (i / 10) as f32])
+///             .collect(),
+///     );
+///     let up = Unit::new_normalize(vector![0.0, 1.0]);
+///
+///     let seen = sector_in_bvh(&boids, point![4.0, 4.0], up, 0.1, 2.5);
+///
+///     assert_eq!(seen, vec![44, 54, 64]);
+///
+#[cfg(feature = "alloc")]
+pub fn sector_in_bvh(
+    bvh: &Bvh<Point2<f32>>,
+    apex: Point2<f32>,
+    heading: Unit<Vector2<f32>>,
+    half_angle: f32,
+    radius: f32,
+) -> Vec<usize> {
+    let Some(inside) = sector_test(apex, heading, half_angle, radius) else {
+        return Vec::new();
+    };
+    let reach = Vector2::new(radius, radius);
+    let mut found = bvh.query_aabb(&Aabb::new(apex - reach, apex + reach));
+    found.retain(|&i| inside(&bvh.primitives()[i]));
+    found
+}
+
+/// Find the points inside a sector which covers an interval of directions.
+///
+/// This is [`sector`] for callers which keep their directions as an
+/// [`AngleInterval`]. The interval is turned into a heading at its center
+/// and a half angle of half its sweep, so intervals which cross the
+/// positive x axis need no special handling, and the points are still
+/// tested without any trigonometry.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             operations::{Angle, AngleInterval},
+///             query::sector_interval,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let points = [
+///         point![2.0, 0.1],
+///         point![2.0, -0.1],
+///         point![-2.0, 0.0],
+///         point![0.0, 2.0],
+///     ];
+///     let ahead = AngleInterval::new(
+///         Angle::from_degrees(350.0),
+///         Angle::from_degrees(10.0),
+///     );
+///
+///     let seen = sector_interval(&points, point![0.0, 0.0], &ahead, 5.0);
+///
+///     assert_eq!(seen, vec![0, 1]);
+///
+#[cfg(feature = "alloc")]
+pub fn sector_interval(
+    points: &[Point2<f32>],
+    apex: Point2<f32>,
+    directions: &AngleInterval,
+    radius: f32,
+) -> Vec<usize> {
+    let heading = directions.center().to_unit();
+    sector(points, apex, heading, directions.sweep() / 2.0, radius)
+}
+
+/// Find the points in a [`Bvh`] which are inside a sector which covers an
+/// interval of directions.
+///
+/// This gives the same result as [`sector_interval`] on the tree's
+/// primitives, but only the points near the sector are tested.
+#[cfg(feature = "alloc")]
+pub fn sector_interval_in_bvh(
+    bvh: &Bvh<Point2<f32>>,
+    apex: Point2<f32>,
+    directions: &AngleInterval,
+    radius: f32,
+) -> Vec<usize> {
+    let heading = directions.center().to_unit();
+    sector_in_bvh(bvh, apex, heading, directions.sweep() / 2.0, radius)
+}
+
+/// A test for points inside a sector, or `None` when the sector is empty.
+///
+/// A point at `offset` from the apex is within `half_angle` of the heading
+/// when `offset . heading >= |offset| cos(half_angle)`. Both sides are
+/// squared to avoid a square root for every point.
+#[cfg(feature = "alloc")]
+fn sector_test(
+    apex: Point2<f32>,
+    heading: Unit<Vector2<f32>>,
+    half_angle: f32,
+    radius: f32,
+) -> Option<impl Fn(&Point2<f32>) -> bool> {
+    if !(half_angle >= 0.0 && radius >= 0.0) {
+        return None;
+    }
+    let full = half_angle >= core::f32::consts::PI;
+    let (_, cos) = math::sin_cos(half_angle);
+    let cos_squared = cos * cos;
+    Some(move |point: &Point2<f32>| {
+        let offset = point - apex;
+        let length_squared = offset.norm_squared();
+        if length_squared > radius * radius {
+            return false;
+        }
+        if full {
+            return true;
+        }
+        let along = offset.dot(&heading);
+        let (along_squared, limit) =
+            (along * along, length_squared * cos_squared);
+        if cos >= 0.0 {
+            along >= 0.0 && along_squared >= limit
+        } else {
+            along >= 0.0 || along_squared <= limit
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{DistanceToPoint, Ray, Segment},
+            operations::{Angle, AngleInterval},
+            query::{
+                distances_to_points, extreme_point, nearest_in, sector,
+                sector_in_bvh, sector_interval, sector_interval_in_bvh,
+            },
+            spatial::Bvh,
+            test_support::{point2, unit_vector},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::{FRAC_PI_2, PI},
+        nalgebra::{point, vector, Point2, Unit, Vector2},
+        proptest::prelude::*,
+    };
+
+    /// The angle between two directions, measured the slow way.
+    fn angle_between(a: &Vector2<f32>, b: &Vector2<f32>) -> f32 {
+        let difference = (a.y.atan2(a.x) - b.y.atan2(b.x)).abs();
+        difference.min(2.0 * PI - difference)
+    }
+
+    #[test]
+    pub fn extreme_point_should_prefer_the_lowest_index() {
+        let points = [
+            point![0.0, 0.0],
+            point![1.0, 2.0],
+            point![-1.0, 2.0],
+            point![0.0, 2.0],
+        ];
+        let up = Unit::new_normalize(vector![0.0, 1.0]);
+
+        assert_eq!(extreme_point(&points, &up), Some(1));
+        assert_eq!(extreme_point(&[], &up), None);
+    }
+
+    #[test]
+    pub fn nearest_in_should_return_none_for_empty_input() {
+        let segments: [Segment; 0] = [];
+        assert!(nearest_in(&segments, &point![0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    pub fn nearest_in_should_agree_with_unsquared_distances() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![1.0, 0.0]),
+            Segment::new(point![4.0, 4.0], point![5.0, 7.0]),
+            Segment::new(point![-3.0, 2.0], point![-1.0, 8.0]),
+        ];
+        let p = point![3.0, 5.0];
+
+        let (index, distance) = nearest_in(&segments, &p).unwrap();
+
+        let expected = segments
+            .iter()
+            .map(|s| s.distance_to_point(&p))
+            .fold(f32::INFINITY, f32::min);
+        assert_eq!(index, 1);
+        assert_relative_eq!(distance, expected);
+    }
+
+    #[test]
+    pub fn nearest_in_should_compare_signed_distances_by_magnitude() {
+        // The point is 3 units behind the first ray and 2 units behind the
+        // second, so both distances are negative.
+        let rays = [
+            Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![1.0, 0.0])),
+            Ray::new(point![-3.0, 2.0], Unit::new_normalize(vector![0.0, 1.0])),
+        ];
+        let p = point![-3.0, 0.0];
+
+        let (index, distance) = nearest_in(&rays, &p).unwrap();
+
+        assert_eq!(index, 1);
+        assert_relative_eq!(distance, 2.0);
+    }
+
+    #[test]
+    pub fn nearest_in_should_prefer_the_lowest_index_on_ties() {
+        let points = [point![1.0, 0.0], point![-1.0, 0.0], point![0.0, 1.0]];
+        let (index, distance) = nearest_in(&points, &point![0.0, 0.0]).unwrap();
+        assert_eq!(index, 0);
+        assert_relative_eq!(distance, 1.0);
+    }
+
+    #[test]
+    pub fn distances_to_points_should_reuse_the_output_buffer() {
+        let segment = Segment::new(point![0.0, 0.0], point![0.0, 10.0]);
+        let mut out = vec![99.0; 8];
+
+        distances_to_points(&segment, &[point![1.0, 1.0]], &mut out);
+        assert_relative_eq!(out.as_slice(), [1.0].as_slice());
+
+        distances_to_points(
+            &segment,
+            &[point![-2.0, 5.0], point![0.0, 13.0]],
+            &mut out,
+        );
+        assert_relative_eq!(out.as_slice(), [2.0, 3.0].as_slice());
+    }
+
+    #[test]
+    pub fn sectors_should_straddle_the_negative_x_axis() {
+        let points: Vec<Point2<f32>> = [170.0f32, -170.0, 150.0, 90.0, 180.0]
+            .iter()
+            .map(|degrees| {
+                let angle = degrees.to_radians();
+                point![3.0 * angle.cos(), 3.0 * angle.sin()]
+            })
+            .chain([point![-5.0, 0.0], point![0.0, 0.0]])
+            .collect();
+        let left = Unit::new_normalize(vector![-1.0, 0.0]);
+
+        let seen = sector(&points, point![0.0, 0.0], left, 0.2, 4.0);
+
+        assert_eq!(seen, vec![0, 1, 4, 6]);
+    }
+
+    #[test]
+    pub fn interval_sectors_should_straddle_the_positive_x_axis() {
+        let points: Vec<Point2<f32>> = [5.0f32, -5.0, 355.0, 180.0, 15.0, 90.0]
+            .iter()
+            .map(|degrees| {
+                let angle = degrees.to_radians();
+                point![3.0 * angle.cos(), 3.0 * angle.sin()]
+            })
+            .chain([point![9.0, 0.0], point![0.0, 0.0]])
+            .collect();
+        let ahead = AngleInterval::new(
+            Angle::from_degrees(350.0),
+            Angle::from_degrees(10.0),
+        );
+        let origin = point![0.0, 0.0];
+
+        let seen = sector_interval(&points, origin, &ahead, 4.0);
+
+        assert_eq!(seen, vec![0, 1, 2, 7]);
+        let bvh = Bvh::new(points.clone());
+        assert_eq!(sector_interval_in_bvh(&bvh, origin, &ahead, 4.0), seen);
+        let all = sector_interval(&points, origin, &AngleInterval::full(), 4.0);
+        assert_eq!(all, vec![0, 1, 2, 3, 4, 5, 7]);
+    }
+
+    #[test]
+    pub fn wide_sectors_should_become_radius_queries() {
+        let points = [
+            point![1.0, 0.0],
+            point![-1.0, 0.0],
+            point![0.0, -2.0],
+            point![3.0, 3.0],
+        ];
+        let up = Unit::new_normalize(vector![0.0, 1.0]);
+        let origin = point![0.0, 0.0];
+
+        assert_eq!(sector(&points, origin, up, PI, 2.0), vec![0, 1, 2]);
+        assert_eq!(sector(&points, origin, up, 10.0, 2.0), vec![0, 1, 2]);
+        // Just short of a full turn, straight behind is still excluded.
+        assert_eq!(sector(&points, origin, up, PI - 0.01, 2.0), vec![0, 1]);
+        assert_eq!(sector(&points, origin, up, FRAC_PI_2, 2.0), vec![0, 1]);
+        assert!(sector(&points, origin, up, -0.1, 2.0).is_empty());
+        assert!(sector(&points, origin, up, 1.0, -2.0).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn sector_should_agree_with_angles(
+            points in prop::collection::vec(point2(-10.0..=10.0), 0..40),
+            apex in point2(-10.0..=10.0),
+            heading in unit_vector(),
+            half_angle in 0.0f32..=3.5,
+            radius in 0.0f32..=15.0,
+        ) {
+            let seen = sector(&points, apex, heading, half_angle, radius);
+
+            for (i, point) in points.iter().enumerate() {
+                let offset = point - apex;
+                let distance = offset.norm();
+                let angle = angle_between(&offset, &heading);
+                // Skip points too close to the boundary to call.
+                if (distance - radius).abs() < 1e-3
+                    || distance < 1e-3
+                    || (half_angle < PI && (angle - half_angle).abs() < 1e-3)
+                {
+                    continue;
+                }
+                let expected = distance <= radius && angle <= half_angle;
+                prop_assert_eq!(seen.contains(&i), expected);
+            }
+        }
+
+        #[test]
+        fn sector_in_bvh_should_agree_with_sector(
+            points in prop::collection::vec(point2(-10.0..=10.0), 0..60),
+            apex in point2(-10.0..=10.0),
+            heading in unit_vector(),
+            half_angle in 0.0f32..=3.5,
+            radius in 0.0f32..=15.0,
+        ) {
+            let bvh = Bvh::new(points.clone());
+
+            prop_assert_eq!(
+                sector_in_bvh(&bvh, apex, heading, half_angle, radius),
+                sector(&points, apex, heading, half_angle, radius)
+            );
+        }
+    }
+}
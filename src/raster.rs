@@ -0,0 +1,767 @@
+//! Fill polygons into a grid of square cells, like the pixels of an image.
+//!
+//! Cell `(col, row)` covers the square from `(col, row) * cell_size` to
+//! `(col + 1, row + 1) * cell_size`, and is covered by a polygon when its
+//! center is inside. Rows grow with y, so with y pointing down, as it does
+//! in most image buffers, row zero is at the top. The same scanline finds
+//! the points of a lattice inside a shape, for halftones and LED matrices.
+
+use {
+    crate::{
+        generate::grid_cells,
+        line::Segment,
+        math,
+        polygon::{clip_to_half_plane, Polygon},
+        shape::{Aabb, BoundingBox, Circle},
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// Values laid out on a grid of equal cells which divide up a box.
+///
+/// Like [`grid_cells`], values are in row-major order starting from the
+/// bottom left, so the value for column `c` and row `r` is at index
+/// `r * cols + c`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    /// The box which the cells divide up.
+    pub bounds: Aabb,
+
+    /// The number of columns of cells.
+    pub cols: usize,
+
+    /// The number of rows of cells.
+    pub rows: usize,
+
+    /// One value for each cell.
+    pub values: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// The value for the cell in column `col` and row `row`, or `None` when
+    /// the cell is outside the grid.
+    pub fn get(&self, col: usize, row: usize) -> Option<&T> {
+        if col >= self.cols || row >= self.rows {
+            return None;
+        }
+        self.values.get(row * self.cols + col)
+    }
+}
+
+/// A run of covered cells in one row of the grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// The row of the cells.
+    pub row: i32,
+
+    /// The first covered column.
+    pub start: i32,
+
+    /// The last covered column, inclusive.
+    pub end: i32,
+}
+
+impl Span {
+    /// The number of cells in the span.
+    pub fn cell_count(&self) -> usize {
+        (self.end - self.start + 1) as usize
+    }
+}
+
+/// The cells covered by a polygon, as spans of cells in each row.
+///
+/// This is a scanline fill. Each row's center line is crossed with the
+/// edges which span it, and the crossings are paired with the even-odd
+/// rule. Cell centers exactly on the boundary follow the top-left fill
+/// rule: they're covered when they're on a top or left edge, and not when
+/// they're on a bottom or right edge. With y pointing down, top edges are
+/// the ones with the smaller y. This way polygons which share an edge cover
+/// every cell along it exactly once, with no gaps and no overlap.
+///
+/// Spans are sorted by row and then by column. Returns no spans when
+/// `cell_size` isn't positive.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             polygon::Polygon,
+///             raster::{fill_polygon, Span},
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let triangle = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![0.0, 4.0],
+///     ]);
+///
+///     let spans = fill_polygon(&triangle, 1.0);
+///
+///     // The cell centers on the diagonal are on a right edge, so they
+///     // aren't covered.
+///     assert_eq!(spans.len(), 3);
+///     assert_eq!(spans[0], Span { row: 0, start: 0, end: 2 });
+///     assert_eq!(spans[2], Span { row: 2, start: 0, end: 0 });
+///
+pub fn fill_polygon(poly: &Polygon, cell_size: f32) -> Vec<Span> {
+    if cell_size.is_nan() || cell_size <= 0.0 {
+        return Vec::new();
+    }
+    scanline(
+        poly,
+        &Lattice {
+            origin: Vector2::zeros(),
+            spacing: cell_size,
+            shift: 0.5,
+        },
+    )
+}
+
+/// The points of a square lattice which are inside a polygon.
+///
+/// The lattice has a point at `origin + (col, row) * spacing` for every
+/// pair of integers. Rather than testing every point in the polygon's
+/// bounding box, each row is crossed with the polygon's edges like
+/// [`fill_polygon`], so points exactly on the boundary follow the same
+/// top-left rule: they're inside on a top or left edge, and outside on a
+/// bottom or right edge. Polygons which share an edge split the lattice
+/// points along it, with none left out and none in both.
+///
+/// Points are sorted by row and then by column. Returns no points when
+/// `spacing` isn't positive.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{polygon::Polygon, raster::lattice_points_inside},
+///         nalgebra::point,
+///     };
+///
+///     let triangle = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![0.0, 2.0],
+///     ]);
+///
+///     let points = lattice_points_inside(&triangle, 1.0, point![0.0, 0.0]);
+///
+///     // The points on the diagonal are on a right edge.
+///     assert_eq!(
+///         points,
+///         vec![point![0.0, 0.0], point![1.0, 0.0], point![0.0, 1.0]]
+///     );
+///
+pub fn lattice_points_inside(
+    shape: &Polygon,
+    spacing: f32,
+    origin: Point2<f32>,
+) -> Vec<Point2<f32>> {
+    let Some(lattice) = Lattice::new(spacing, origin) else {
+        return Vec::new();
+    };
+    lattice.points(&scanline(shape, &lattice))
+}
+
+/// The points of a square lattice which are inside a circle.
+///
+/// The lattice is the same as for [`lattice_points_inside`], and the range
+/// of columns in each row comes straight from the circle's equation. Like
+/// polygons, the circle's boundary is half-open: a row through its lowest
+/// point and points on the left half of the circle are inside, while a
+/// row through its highest point and points on the right half aren't.
+/// Returns no points when `spacing` isn't positive.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{raster::lattice_points_inside_circle, shape::Circle},
+///         nalgebra::point,
+///     };
+///
+///     let circle = Circle::new(point![0.0, 0.0], 1.0);
+///
+///     let origin = point![0.0, 0.0];
+///     let points = lattice_points_inside_circle(&circle, 1.0, origin);
+///
+///     assert_eq!(points, vec![point![-1.0, 0.0], point![0.0, 0.0]]);
+///
+pub fn lattice_points_inside_circle(
+    circle: &Circle,
+    spacing: f32,
+    origin: Point2<f32>,
+) -> Vec<Point2<f32>> {
+    let Some(lattice) = Lattice::new(spacing, origin) else {
+        return Vec::new();
+    };
+    let (center, radius) = (circle.center, circle.radius);
+    let rows = lattice.first_at_or_after(1, center.y - radius)
+        ..lattice.first_at_or_after(1, center.y + radius);
+    let spans: Vec<Span> = rows
+        .filter_map(|row| {
+            let dy = lattice.position(1, row) - center.y;
+            let half_width = math::sqrt((radius * radius - dy * dy).max(0.0));
+            let start = lattice.first_at_or_after(0, center.x - half_width);
+            let end = lattice.first_at_or_after(0, center.x + half_width) - 1;
+            (start <= end).then_some(Span { row, start, end })
+        })
+        .collect();
+    lattice.points(&spans)
+}
+
+/// The points of a square lattice which are inside a box.
+///
+/// The lattice is the same as for [`lattice_points_inside`], and the box
+/// is half-open like a polygon: points on its bottom and left sides are
+/// inside, and points on its top and right sides aren't. Returns no points
+/// when `spacing` isn't positive.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{raster::lattice_points_inside_aabb, shape::Aabb},
+///         nalgebra::point,
+///     };
+///
+///     let aabb = Aabb::new(point![0.0, 0.0], point![2.0, 1.0]);
+///
+///     let points = lattice_points_inside_aabb(&aabb, 0.5, point![0.25, 0.0]);
+///
+///     assert_eq!(points.len(), 4 * 2);
+///     assert_eq!(points[0], point![0.25, 0.0]);
+///
+pub fn lattice_points_inside_aabb(
+    aabb: &Aabb,
+    spacing: f32,
+    origin: Point2<f32>,
+) -> Vec<Point2<f32>> {
+    let Some(lattice) = Lattice::new(spacing, origin) else {
+        return Vec::new();
+    };
+    let start = lattice.first_at_or_after(0, aabb.min.x);
+    let end = lattice.first_at_or_after(0, aabb.max.x) - 1;
+    let rows = lattice.first_at_or_after(1, aabb.min.y)
+        ..lattice.first_at_or_after(1, aabb.max.y);
+    let spans: Vec<Span> = rows
+        .filter(|_| start <= end)
+        .map(|row| Span { row, start, end })
+        .collect();
+    lattice.points(&spans)
+}
+
+/// Where the rows and columns of a scanline fill are. Index `k` along
+/// either axis is at `origin + (k + shift) * spacing`.
+struct Lattice {
+    origin: Vector2<f32>,
+    spacing: f32,
+    shift: f32,
+}
+
+impl Lattice {
+    /// A lattice of points with no shift, when the spacing is positive.
+    fn new(spacing: f32, origin: Point2<f32>) -> Option<Self> {
+        (spacing > 0.0).then_some(Self {
+            origin: origin.coords,
+            spacing,
+            shift: 0.0,
+        })
+    }
+
+    /// The position of an index along an axis.
+    fn position(&self, axis: usize, index: i32) -> f32 {
+        self.origin[axis] + (index as f32 + self.shift) * self.spacing
+    }
+
+    /// The first index along an axis whose position is at or after `value`.
+    fn first_at_or_after(&self, axis: usize, value: f32) -> i32 {
+        math::ceil((value - self.origin[axis]) / self.spacing - self.shift)
+            as i32
+    }
+
+    /// The points covered by spans, in order.
+    fn points(&self, spans: &[Span]) -> Vec<Point2<f32>> {
+        spans
+            .iter()
+            .flat_map(|span| {
+                let y = self.position(1, span.row);
+                (span.start..=span.end)
+                    .map(move |col| Point2::new(self.position(0, col), y))
+            })
+            .collect()
+    }
+}
+
+/// The spans of a lattice covered by a polygon, using the top-left rule
+/// described in [`fill_polygon`].
+fn scanline(poly: &Polygon, lattice: &Lattice) -> Vec<Span> {
+    let mut spans = Vec::new();
+    // Point every edge down the rows so both polygons on either side of a
+    // shared edge find exactly the same crossings. Horizontal edges never
+    // cross a row's center line, so they're left out.
+    let mut edges: Vec<Segment> = poly
+        .edges()
+        .filter(|edge| edge.start.y != edge.end.y)
+        .map(|edge| {
+            if edge.start.y < edge.end.y {
+                edge
+            } else {
+                Segment::new(edge.end, edge.start)
+            }
+        })
+        .collect();
+    if edges.is_empty() {
+        return spans;
+    }
+    edges.sort_by(|a, b| a.start.y.total_cmp(&b.start.y));
+
+    let first_row = lattice.first_at_or_after(1, edges[0].start.y);
+    let last_row = edges
+        .iter()
+        .map(|edge| lattice.first_at_or_after(1, edge.end.y))
+        .max()
+        .unwrap_or(first_row);
+
+    let mut active: Vec<Segment> = Vec::new();
+    let mut next = 0;
+    let mut crossings = Vec::new();
+    for row in first_row..last_row {
+        let y = lattice.position(1, row);
+
+        // Each edge covers the rows from its top, inclusive, to its
+        // bottom, exclusive, so a vertex on a center line is only crossed
+        // once.
+        while next < edges.len() && edges[next].start.y <= y {
+            active.push(edges[next]);
+            next += 1;
+        }
+        active.retain(|edge| edge.end.y > y);
+
+        crossings.clear();
+        crossings.extend(active.iter().map(|edge| {
+            let t = (y - edge.start.y) / (edge.end.y - edge.start.y);
+            edge.start.x + (edge.end.x - edge.start.x) * t
+        }));
+        crossings.sort_by(f32::total_cmp);
+
+        // Points on a left crossing are covered, points on a right
+        // crossing aren't.
+        for pair in crossings.chunks_exact(2) {
+            let start = lattice.first_at_or_after(0, pair[0]);
+            let end = lattice.first_at_or_after(0, pair[1]) - 1;
+            if start <= end {
+                spans.push(Span { row, start, end });
+            }
+        }
+    }
+    spans
+}
+
+/// The fraction of a cell's area which is covered by a polygon.
+///
+/// The polygon is clipped to the cell with the Sutherland-Hodgman algorithm
+/// and the clipped area is divided by the cell's area, so the result is
+/// exact up to rounding. The polygon can be wound either way and can be
+/// concave, but it should be simple. Cells which no edge passes through
+/// are entirely inside or entirely outside, and skip the clipping. Returns
+/// zero when the cell has no area.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{polygon::Polygon, raster::coverage, shape::Aabb},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let triangle = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![0.0, 4.0],
+///     ]);
+///
+///     // The diagonal cuts this cell in half.
+///     let cell = Aabb::new(point![1.0, 2.0], point![2.0, 3.0]);
+///
+///     assert_relative_eq!(coverage(&triangle, &cell), 0.5);
+///
+pub fn coverage(poly: &Polygon, cell: &Aabb) -> f32 {
+    let area = cell.width() * cell.height();
+    if area <= 0.0 || !poly.bounding_box().intersects(cell) {
+        return 0.0;
+    }
+    if !poly.edges().any(|edge| edge.clip_to_aabb(cell).is_some()) {
+        return if poly.contains_point(&cell.center()) {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    (clip_to_cell(&poly.vertices, cell).abs() / area).min(1.0)
+}
+
+/// The fraction of each cell of a grid which is covered by a polygon.
+///
+/// `bounds` is divided into `resolution.0` columns and `resolution.1` rows
+/// of cells, the same as [`grid_cells`], and each value is the
+/// [`coverage`] of that cell. Summing the values and multiplying by the
+/// area of a cell gives the area of the polygon inside the bounds.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             polygon::Polygon,
+///             raster::coverage_grid,
+///             shape::Aabb,
+///         },
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let triangle = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![0.0, 4.0],
+///     ]);
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 4.0]);
+///
+///     let grid = coverage_grid(&triangle, &bounds, (4, 4));
+///
+///     assert_eq!(grid.get(0, 0), Some(&1.0));
+///     assert_eq!(grid.get(3, 3), Some(&0.0));
+///     assert_relative_eq!(*grid.get(3, 0).unwrap(), 0.5);
+///     assert_relative_eq!(grid.values.iter().sum::<f32>(), 8.0);
+///
+pub fn coverage_grid(
+    poly: &Polygon,
+    bounds: &Aabb,
+    resolution: (usize, usize),
+) -> Grid<f32> {
+    let (cols, rows) = resolution;
+    let polygon_bounds = poly.bounding_box();
+    Grid {
+        bounds: *bounds,
+        cols,
+        rows,
+        values: grid_cells(bounds, cols, rows)
+            .iter()
+            .map(|cell| {
+                if cell.intersects(&polygon_bounds) {
+                    coverage(poly, cell)
+                } else {
+                    0.0
+                }
+            })
+            .collect(),
+    }
+}
+
+/// The signed area of a polygon after clipping it to a box, one side of the
+/// box at a time.
+fn clip_to_cell(vertices: &[Point2<f32>], cell: &Aabb) -> f32 {
+    // Each side keeps the points where `inside` isn't negative, and the
+    // crossing is where it's zero.
+    let sides: [(usize, f32, f32); 4] = [
+        (0, cell.min.x, 1.0),
+        (0, cell.max.x, -1.0),
+        (1, cell.min.y, 1.0),
+        (1, cell.max.y, -1.0),
+    ];
+    let mut clipped = vertices.to_vec();
+    for (axis, limit, sign) in sides {
+        let inside = |p: &Point2<f32>| (p[axis] - limit) * sign;
+        clipped = clip_to_half_plane(&clipped, inside);
+        // Rounding can leave crossings just outside the side, so land them
+        // exactly on it where the next sides will see it.
+        for vertex in &mut clipped {
+            if inside(vertex) < 0.0 {
+                vertex[axis] = limit;
+            }
+        }
+    }
+    Polygon::new(clipped).signed_area()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            coverage, coverage_grid, fill_polygon, lattice_points_inside,
+            lattice_points_inside_aabb, lattice_points_inside_circle, Span,
+        },
+        crate::{
+            line::DistanceToPoint,
+            polygon::Polygon,
+            shape::{Aabb, BoundingBox, Circle},
+            test_support::{point2, polygon},
+        },
+        alloc::{collections::BTreeSet, vec::Vec},
+        approx::assert_relative_eq,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    fn cells(spans: &[Span]) -> Vec<(i32, i32)> {
+        spans
+            .iter()
+            .flat_map(|span| (span.start..=span.end).map(|c| (span.row, c)))
+            .collect()
+    }
+
+    fn covered_area(polygon: &Polygon, cell_size: f32) -> f32 {
+        let count: usize = fill_polygon(polygon, cell_size)
+            .iter()
+            .map(Span::cell_count)
+            .sum();
+        count as f32 * cell_size * cell_size
+    }
+
+    #[test]
+    pub fn covered_area_should_converge_to_the_polygon_area() {
+        let shape = Polygon::new(vec![
+            point![0.3, 0.1],
+            point![7.9, 1.2],
+            point![5.1, 3.3],
+            point![6.2, 8.7],
+            point![1.1, 5.4],
+        ]);
+
+        let errors: Vec<f32> = [1.0, 0.1, 0.01]
+            .iter()
+            .map(|&size| (covered_area(&shape, size) - shape.area()).abs())
+            .collect();
+
+        assert!(errors[1] < errors[0]);
+        assert!(errors[2] < errors[1]);
+        assert!(errors[2] / shape.area() < 1e-3);
+    }
+
+    #[test]
+    pub fn split_squares_should_tile_without_gaps_or_overlap() {
+        let [a, b, c, d] = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]
+            .map(Point2::from);
+        // Cut along the diagonal and along lines through the cell
+        // centers, so every cut has centers exactly on it.
+        let (top, right) = (point![10.0, 4.5], point![5.5, 0.0]);
+        let splits = [
+            [Polygon::new(vec![a, b, c]), Polygon::new(vec![a, c, d])],
+            [
+                Polygon::new(vec![a, right, point![5.5, 10.0], d]),
+                Polygon::new(vec![right, b, c, point![5.5, 10.0]]),
+            ],
+            [
+                Polygon::new(vec![a, b, top, point![0.0, 4.5]]),
+                Polygon::new(vec![d, point![0.0, 4.5], top, c]),
+            ],
+        ];
+
+        for [first, second] in splits {
+            let mut covered = BTreeSet::new();
+            for cell in cells(&fill_polygon(&first, 1.0))
+                .into_iter()
+                .chain(cells(&fill_polygon(&second, 1.0)))
+            {
+                assert!(covered.insert(cell), "{:?} covered twice", cell);
+            }
+            assert_eq!(covered.len(), 100);
+            assert!(covered.iter().all(|&(row, col)| {
+                (0..10).contains(&row) && (0..10).contains(&col)
+            }));
+        }
+    }
+
+    #[test]
+    pub fn degenerate_polygons_should_cover_nothing() {
+        let flat = Polygon::new(vec![point![0.0, 1.0], point![5.0, 1.0]]);
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![2.0, 0.0],
+            point![2.0, 2.0],
+            point![0.0, 2.0],
+        ]);
+
+        assert!(fill_polygon(&flat, 0.5).is_empty());
+        assert!(fill_polygon(&Polygon::new(vec![]), 0.5).is_empty());
+        assert!(fill_polygon(&square, 0.0).is_empty());
+        assert_eq!(cells(&fill_polygon(&square, 0.5)).len(), 16);
+    }
+
+    #[test]
+    pub fn coverage_should_handle_concave_polygons_crossing_a_cell_twice() {
+        // A "U" whose arms both cross the bottom half of the cell, wound
+        // clockwise.
+        let u_shape = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![0.0, 3.0],
+            point![1.0, 3.0],
+            point![1.0, 1.0],
+            point![3.0, 1.0],
+            point![3.0, 3.0],
+            point![4.0, 3.0],
+            point![4.0, 0.0],
+        ]);
+        let cell = Aabb::new(point![0.5, 0.0], point![3.5, 2.0]);
+
+        let covered = coverage(&u_shape, &cell);
+
+        // 3 for the bottom and 0.5 for each arm, out of 6.
+        assert_relative_eq!(covered, 4.0 / 6.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    pub fn cells_without_edges_should_be_all_or_nothing() {
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![10.0, 0.0],
+            point![10.0, 10.0],
+            point![0.0, 10.0],
+        ]);
+        let inside = Aabb::new(point![2.0, 2.0], point![3.0, 3.0]);
+        let outside = Aabb::new(point![12.0, 2.0], point![13.0, 3.0]);
+        let around = Aabb::new(point![-1.0, -1.0], point![11.0, 11.0]);
+        let flat = Aabb::new(point![2.0, 2.0], point![3.0, 2.0]);
+
+        assert_eq!(coverage(&square, &inside), 1.0);
+        assert_eq!(coverage(&square, &outside), 0.0);
+        assert_relative_eq!(coverage(&square, &around), 100.0 / 144.0);
+        assert_eq!(coverage(&square, &flat), 0.0);
+        assert_eq!(coverage(&Polygon::new(vec![]), &inside), 0.0);
+    }
+
+    #[test]
+    pub fn big_circles_should_hold_about_their_area_in_points() {
+        let circle = Circle::new(point![0.3, -0.2], 40.0);
+        let spacing = 0.5;
+
+        let count =
+            lattice_points_inside_circle(&circle, spacing, point![0.1, 0.0])
+                .len() as f32;
+
+        // Only points along the boundary can be counted wrongly.
+        let expected =
+            core::f32::consts::PI * 40.0 * 40.0 / (spacing * spacing);
+        let boundary = core::f32::consts::TAU * 40.0 / spacing;
+        assert!((count - expected).abs() <= boundary, "{}", count);
+        assert!(lattice_points_inside_circle(&circle, 0.0, point![0.0, 0.0])
+            .is_empty());
+    }
+
+    #[test]
+    pub fn adjacent_squares_should_split_the_lattice() {
+        let square =
+            |x: f32, y: f32| Aabb::new(point![x, y], point![x + 5.0, y + 5.0]);
+        let squares = [
+            square(0.0, 0.0),
+            square(5.0, 0.0),
+            square(0.0, 5.0),
+            square(5.0, 5.0),
+        ];
+        let origin = point![0.0, 0.0];
+
+        let mut from_polygons = BTreeSet::new();
+        let mut from_boxes = BTreeSet::new();
+        for aabb in &squares {
+            let [a, b, c, d] = aabb.corners();
+            let polygon = Polygon::new(vec![a, b, c, d]);
+            let points = lattice_points_inside(&polygon, 0.5, origin);
+            assert_eq!(points, lattice_points_inside_aabb(aabb, 0.5, origin));
+            for point in points {
+                let key = (point.x.to_bits(), point.y.to_bits());
+                assert!(from_polygons.insert(key), "{:?} found twice", point);
+            }
+            for point in lattice_points_inside_aabb(aabb, 0.5, origin) {
+                from_boxes.insert((point.x.to_bits(), point.y.to_bits()));
+            }
+        }
+
+        // The points on the outer top and right sides are left out.
+        assert_eq!(from_polygons.len(), 20 * 20);
+        assert_eq!(from_polygons, from_boxes);
+    }
+
+    proptest! {
+        #[test]
+        fn covered_area_should_be_close_to_the_polygon_area(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            cell_size in 0.05f32..=0.5,
+        ) {
+            let error = (covered_area(&polygon, cell_size) - polygon.area())
+                .abs();
+
+            // Only cells along the boundary can be wrong.
+            let bound = polygon.perimeter() * cell_size * 1.5 + 1e-3;
+            prop_assert!(error <= bound, "{} > {}", error, bound);
+        }
+
+        #[test]
+        fn lattice_points_should_match_point_in_polygon_tests(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            origin in point2(-1.0..=1.0),
+        ) {
+            let spacing = 0.37;
+            let points = lattice_points_inside(&polygon, spacing, origin);
+
+            let found: BTreeSet<_> = points
+                .iter()
+                .map(|p| (p.x.to_bits(), p.y.to_bits()))
+                .collect();
+            prop_assert_eq!(found.len(), points.len());
+            // Away from the boundary, the scanline agrees with testing every
+            // point.
+            for col in -30..30 {
+                for row in -30..30 {
+                    let point = origin
+                        + nalgebra::vector![col as f32, row as f32] * spacing;
+                    let near_boundary = polygon
+                        .edges()
+                        .any(|edge| edge.distance_to_point(&point) < 1e-3);
+                    let key = (point.x.to_bits(), point.y.to_bits());
+                    if !near_boundary {
+                        prop_assert_eq!(
+                            found.contains(&key),
+                            polygon.contains_point(&point)
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn spans_should_be_sorted_and_not_overlap(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+        ) {
+            let spans = fill_polygon(&polygon, 0.25);
+
+            for pair in spans.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                prop_assert!(a.row < b.row || a.end < b.start);
+            }
+        }
+
+        #[test]
+        fn summed_coverage_should_equal_the_polygon_area(
+            polygon in polygon(-10.0..=10.0, 3..=8, false),
+            cols in 1usize..=24,
+            rows in 1usize..=24,
+        ) {
+            // Grow the box a little so no edge is exactly on its boundary.
+            let mut bounds = polygon.bounding_box();
+            bounds.min -= nalgebra::vector![0.5, 0.25];
+            bounds.max += nalgebra::vector![0.25, 0.5];
+            let grid = coverage_grid(&polygon, &bounds, (cols, rows));
+
+            let cell_area =
+                bounds.width() * bounds.height() / (cols * rows) as f32;
+            let total: f32 = grid.values.iter().sum::<f32>() * cell_area;
+
+            prop_assert_eq!(grid.values.len(), cols * rows);
+            prop_assert!(grid.values.iter().all(|v| (0.0..=1.0).contains(v)));
+            prop_assert!(
+                (total - polygon.area()).abs() <= polygon.area() * 1e-4 + 1e-3,
+                "{} != {}", total, polygon.area()
+            );
+        }
+    }
+}
@@ -0,0 +1,622 @@
+//! Simulated range scans, like a 2D lidar sweeping a fan of rays across a
+//! set of walls.
+//!
+//! A scan casts evenly spaced rays from an origin between two angles and
+//! records how far each ray travels before it hits a segment. Rays which
+//! don't hit anything within the sensor's range report the range itself.
+//! [`scan`] tests every segment for every ray, and [`scan_index`] uses a
+//! [`SegmentIndex`] so large maps stay fast.
+//!
+//! Occlusion passes which sweep around a point instead of casting rays can
+//! start from [`radial_sort_segments`], which orders segments by the angles
+//! they cover as seen from the point and handles the branch cut where
+//! angles jump from pi to -pi.
+
+use {
+    crate::{
+        line::{intersection::raycast, Ray, Segment},
+        math,
+        operations::{Angle, AngleInterval},
+        spatial::{SegmentId, SegmentIndex},
+    },
+    alloc::vec::Vec,
+    core::f32::consts::{PI, TAU},
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+/// The directions a segment covers as seen from a point, from
+/// [`radial_sort_segments`].
+///
+/// The interval runs counterclockwise from `start` to `end`, and never
+/// crosses the negative x axis, so `-pi <= start <= end <= pi`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RadialEntry {
+    /// The index of the segment.
+    pub index: usize,
+
+    /// The clockwise end of the interval, in radians.
+    pub start: f32,
+
+    /// The counterclockwise end of the interval, in radians.
+    pub end: f32,
+}
+
+impl RadialEntry {
+    /// The directions the entry covers as an [`AngleInterval`].
+    pub fn interval(&self) -> AngleInterval {
+        let start = Angle::from_radians(self.start);
+        AngleInterval::from_sweep(start, self.end - self.start)
+    }
+}
+
+/// The angles in radians of a segment's endpoints as seen from a point.
+///
+/// Returns `(start, end)` where sweeping counterclockwise from `start` to
+/// `end` passes over the segment, which covers less than a half turn. Both
+/// angles are in `[-pi, pi]`. When the segment crosses the negative x axis
+/// the sweep wraps from pi to -pi, so `start` is greater than `end`.
+/// Segments which point straight at the point cover a single angle, the
+/// direction of their nearest endpoint.
+///
+/// Segments which contain the point block every direction, so they cover
+/// the full circle `(-pi, pi)`.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, scan::angular_interval},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///         core::f32::consts::{FRAC_PI_4, PI},
+///     };
+///
+///     let origin = point![0.0, 0.0];
+///     let ahead = Segment::new(point![1.0, 1.0], point![1.0, -1.0]);
+///     let behind = Segment::new(point![-1.0, -1.0], point![-1.0, 1.0]);
+///
+///     let (start, end) = angular_interval(&origin, &ahead);
+///     assert_relative_eq!(start, -FRAC_PI_4);
+///     assert_relative_eq!(end, FRAC_PI_4);
+///
+///     // The interval behind the origin wraps from pi to -pi.
+///     let (start, end) = angular_interval(&origin, &behind);
+///     assert_relative_eq!(start, 3.0 * FRAC_PI_4);
+///     assert_relative_eq!(end, -3.0 * FRAC_PI_4);
+///
+///     let through = Segment::new(point![-1.0, 0.0], point![1.0, 0.0]);
+///     assert_eq!(angular_interval(&origin, &through), (-PI, PI));
+///
+pub fn angular_interval(origin: &Point2<f32>, segment: &Segment) -> (f32, f32) {
+    let (a, b) = (segment.start - origin, segment.end - origin);
+    let cross = a.perp(&b);
+    if cross == 0.0 {
+        if a.dot(&b) <= 0.0 {
+            return (-PI, PI);
+        }
+        let nearest = if a.norm_squared() <= b.norm_squared() {
+            a
+        } else {
+            b
+        };
+        let angle = math::atan2(nearest.y, nearest.x);
+        return (angle, angle);
+    }
+
+    let (first, second) = if cross > 0.0 { (a, b) } else { (b, a) };
+    let mut start = math::atan2(first.y, first.x);
+    let mut end = math::atan2(second.y, second.x);
+    if start > end {
+        // An endpoint on the negative x axis can come out of atan2 as
+        // either pi or -pi, so pick whichever avoids a needless wrap.
+        if start == PI {
+            start = -PI;
+        } else if end == -PI {
+            end = PI;
+        }
+    }
+    (start, end)
+}
+
+/// The directions a segment covers as seen from a point.
+///
+/// This is [`angular_interval`] as an [`AngleInterval`], which handles the
+/// wrap from pi to -pi itself. Segments which contain the point give the
+/// full interval.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::Segment,
+///             operations::Angle,
+///             scan::segment_interval,
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let origin = point![0.0, 0.0];
+///     let behind = Segment::new(point![-1.0, -1.0], point![-1.0, 1.0]);
+///
+///     let interval = segment_interval(&origin, &behind);
+///
+///     assert!(interval.contains(Angle::from_degrees(180.0)));
+///     assert!(interval.contains(Angle::from_degrees(-170.0)));
+///     assert!(!interval.contains(Angle::from_degrees(0.0)));
+///
+pub fn segment_interval(
+    origin: &Point2<f32>,
+    segment: &Segment,
+) -> AngleInterval {
+    let (start, end) = angular_interval(origin, segment);
+    let (start, end) = (Angle::from_radians(start), Angle::from_radians(end));
+    if start.radians() <= end.radians() {
+        AngleInterval::from_sweep(start, (end - start).radians())
+    } else {
+        AngleInterval::new(start, end)
+    }
+}
+
+/// Sort segments by the directions they cover as seen from a point.
+///
+/// Each segment's interval comes from [`angular_interval`]. Intervals
+/// which wrap across the negative x axis are split into two entries, one
+/// ending at pi and one starting at -pi, so every entry has
+/// `start <= end`. Segments which contain the point cover the full circle
+/// and have a single entry from -pi to pi. Entries are sorted by their
+/// start angle, with ties broken by segment index.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, scan::radial_sort_segments},
+///         nalgebra::point,
+///         core::f32::consts::PI,
+///     };
+///
+///     let segments = [
+///         Segment::new(point![-2.0, 1.0], point![-2.0, -1.0]),
+///         Segment::new(point![1.0, -1.0], point![1.0, 1.0]),
+///     ];
+///
+///     let entries = radial_sort_segments(&point![0.0, 0.0], &segments);
+///     let indices: Vec<usize> = entries.iter().map(|e| e.index).collect();
+///
+///     // The segment behind the origin straddles the branch cut.
+///     assert_eq!(indices, vec![0, 1, 0]);
+///     assert_eq!(entries[0].start, -PI);
+///     assert_eq!(entries[2].end, PI);
+///
+pub fn radial_sort_segments(
+    origin: &Point2<f32>,
+    segments: &[Segment],
+) -> Vec<RadialEntry> {
+    let mut entries = Vec::with_capacity(segments.len());
+    for (index, segment) in segments.iter().enumerate() {
+        let (start, end) = angular_interval(origin, segment);
+        if start <= end {
+            entries.push(RadialEntry { index, start, end });
+        } else {
+            entries.push(RadialEntry {
+                index,
+                start,
+                end: PI,
+            });
+            entries.push(RadialEntry {
+                index,
+                start: -PI,
+                end,
+            });
+        }
+    }
+    entries.sort_by(|a, b| {
+        a.start.total_cmp(&b.start).then(a.index.cmp(&b.index))
+    });
+    entries
+}
+
+/// One ray of a scan.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScanSample<Id = usize> {
+    /// The direction of the ray in radians, in `[-pi, pi)`, measured
+    /// counterclockwise from the positive x axis.
+    pub angle: f32,
+
+    /// How far the ray traveled before it hit a segment, or the scan's
+    /// maximum range when it missed.
+    pub distance: f32,
+
+    /// The segment which was hit, or `None` when the ray missed.
+    pub segment: Option<Id>,
+}
+
+impl<Id> ScanSample<Id> {
+    /// The point where the ray stopped, for a scan from `origin`.
+    ///
+    /// Misses give the point at the maximum range.
+    pub fn point(&self, origin: &Point2<f32>) -> Point2<f32> {
+        let (sin, cos) = math::sin_cos(self.angle);
+        origin + Vector2::new(cos, sin) * self.distance
+    }
+
+    /// Returns true when the ray hit a segment.
+    pub fn is_hit(&self) -> bool {
+        self.segment.is_some()
+    }
+}
+
+/// Sweep a fan of rays from a point and measure the distance to the first
+/// segment along each one.
+///
+/// The sweep turns counterclockwise from `start_angle` to `end_angle`. When
+/// `end_angle` is less than `start_angle` the sweep crosses the negative x
+/// axis, where angles wrap between pi and -pi, so a sweep from `3.0` to
+/// `-3.0` covers about 16 degrees. When the angles are equal, or the sweep
+/// covers a full turn, the rays are spread around the whole circle without
+/// repeating the first direction. Otherwise the first and last rays point
+/// exactly at the start and end angles.
+///
+/// Each sample records the segment index and distance from
+/// [`raycast`](crate::line::intersection::raycast), so ties go to the
+/// lowest index. Hits further away than `max_range` are misses. Segments
+/// which pass through the origin are hit by every ray at distance zero,
+/// so a sensor mounted on a wall should be nudged off it.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, scan::scan},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///         core::f32::consts::FRAC_PI_2,
+///     };
+///
+///     let wall = [Segment::new(point![3.0, -5.0], point![3.0, 5.0])];
+///     let origin = point![0.0, 0.0];
+///     let samples = scan(origin, &wall, 3, -FRAC_PI_2, FRAC_PI_2, 10.0);
+///
+///     assert_eq!(samples.len(), 3);
+///     assert_eq!(samples[0].segment, None);
+///     assert_relative_eq!(samples[0].distance, 10.0);
+///     assert_eq!(samples[1].segment, Some(0));
+///     assert_relative_eq!(samples[1].distance, 3.0);
+///     assert_relative_eq!(samples[2].angle, FRAC_PI_2);
+///
+pub fn scan(
+    origin: Point2<f32>,
+    segments: &[Segment],
+    rays: usize,
+    start_angle: f32,
+    end_angle: f32,
+    max_range: f32,
+) -> Vec<ScanSample> {
+    sweep(origin, rays, start_angle, end_angle, max_range, |ray| {
+        raycast(ray, segments).map(|hit| (hit.index, hit.t))
+    })
+}
+
+/// Sweep a fan of rays like [`scan`], finding hits with a segment index.
+///
+/// The index only tests segments in the grid cells each ray passes
+/// through, which is much faster than [`scan`] when there are many
+/// segments. Ties go to the smallest handle.
+pub fn scan_index(
+    origin: Point2<f32>,
+    index: &SegmentIndex,
+    rays: usize,
+    start_angle: f32,
+    end_angle: f32,
+    max_range: f32,
+) -> Vec<ScanSample<SegmentId>> {
+    sweep(origin, rays, start_angle, end_angle, max_range, |ray| {
+        index.query_ray(ray)
+    })
+}
+
+fn sweep<Id>(
+    origin: Point2<f32>,
+    rays: usize,
+    start_angle: f32,
+    end_angle: f32,
+    max_range: f32,
+    mut cast: impl FnMut(&Ray) -> Option<(Id, f32)>,
+) -> Vec<ScanSample<Id>> {
+    let mut span = end_angle - start_angle;
+    if span <= 0.0 {
+        span += TAU;
+    }
+    let full_turn = span >= TAU;
+    let step = match (full_turn, rays) {
+        (true, _) => TAU / rays as f32,
+        (false, 0 | 1) => 0.0,
+        (false, _) => span / (rays - 1) as f32,
+    };
+
+    (0..rays)
+        .map(|i| {
+            let angle = if !full_turn && i + 1 == rays && i > 0 {
+                // Land exactly on the end angle rather than accumulating
+                // rounding error.
+                start_angle + span
+            } else {
+                start_angle + step * i as f32
+            };
+            let (sin, cos) = math::sin_cos(angle);
+            let ray =
+                Ray::new(origin, Unit::new_unchecked(Vector2::new(cos, sin)));
+            let hit = cast(&ray).filter(|&(_, t)| t <= max_range);
+            ScanSample {
+                angle: wrap_angle(angle),
+                distance: hit.as_ref().map_or(max_range, |&(_, t)| t),
+                segment: hit.map(|(id, _)| id),
+            }
+        })
+        .collect()
+}
+
+/// Wrap an angle into the range `[-pi, pi)`.
+fn wrap_angle(angle: f32) -> f32 {
+    let turns = math::floor(((angle + PI) / TAU) as f64) as f32;
+    let wrapped = angle - turns * TAU;
+    if wrapped >= PI {
+        wrapped - TAU
+    } else {
+        wrapped.max(-PI)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            angular_interval, radial_sort_segments, scan, scan_index,
+            segment_interval, wrap_angle,
+        },
+        crate::{
+            line::{intersection::intersect_ray_segment, Ray, Segment},
+            math,
+            operations::{signed_angle, Angle, AngleInterval},
+            shape::Aabb,
+            spatial::SegmentIndex,
+            test_support::{point2, segment},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        core::f32::consts::{FRAC_PI_2, PI, TAU},
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn sweeps_across_pi_should_wrap_angles() {
+        let samples = scan(point![0.0, 0.0], &[], 5, 3.0, -3.0, 1.0);
+        let span = TAU - 6.0;
+
+        assert_eq!(samples.len(), 5);
+        for (i, sample) in samples.iter().enumerate() {
+            let expected = wrap_angle(3.0 + span * i as f32 / 4.0);
+            assert_relative_eq!(sample.angle, expected, epsilon = 1e-5);
+            assert!((-PI..PI).contains(&sample.angle));
+            assert!(!sample.is_hit());
+        }
+        assert_relative_eq!(samples[4].angle, -3.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    pub fn full_turns_should_not_repeat_the_first_ray() {
+        let samples = scan(point![0.0, 0.0], &[], 4, -PI, -PI, 1.0);
+        let angles: Vec<f32> = samples.iter().map(|s| s.angle).collect();
+
+        assert_eq!(angles.len(), 4);
+        for (angle, expected) in
+            angles.iter().zip([-PI, -FRAC_PI_2, 0.0, FRAC_PI_2])
+        {
+            assert_relative_eq!(*angle, expected, epsilon = 1e-5);
+        }
+        assert!(scan(point![0.0, 0.0], &[], 0, 0.0, 1.0, 1.0).is_empty());
+        let single = scan(point![0.0, 0.0], &[], 1, 0.5, 1.0, 1.0);
+        assert_eq!(single[0].angle, 0.5);
+    }
+
+    #[test]
+    pub fn origins_on_a_segment_should_hit_it_immediately() {
+        let wall = [
+            Segment::new(point![-1.0, 0.0], point![1.0, 0.0]),
+            Segment::new(point![-5.0, 2.0], point![5.0, 2.0]),
+        ];
+
+        for sample in scan(point![0.0, 0.0], &wall, 8, 0.0, 0.0, 10.0) {
+            assert_eq!(sample.segment, Some(0));
+            assert_eq!(sample.distance, 0.0);
+        }
+    }
+
+    #[test]
+    pub fn segments_straddling_negative_x_should_be_split() {
+        let origin = point![1.0, 0.0];
+        let segments = [
+            Segment::new(point![-1.0, -1.0], point![-1.0, 2.0]),
+            Segment::new(point![-3.0, 0.0], point![-3.0, -2.0]),
+            Segment::new(point![-3.0, 2.0], point![-3.0, 0.0]),
+        ];
+
+        let (start, end) = angular_interval(&origin, &segments[0]);
+        assert!(start > end);
+        let entries = radial_sort_segments(&origin, &segments);
+        let pieces: Vec<(usize, f32, f32)> =
+            entries.iter().map(|e| (e.index, e.start, e.end)).collect();
+
+        // Segments which end on the negative x axis aren't split into an
+        // empty piece on the other side of the cut.
+        assert_eq!(pieces.len(), 4);
+        assert_eq!((pieces[0].0, pieces[0].1), (0, -PI));
+        assert_relative_eq!(pieces[0].2, math::atan2(-1.0, -2.0));
+        assert_eq!(pieces[1].0, 1);
+        assert_eq!(pieces[1].1, -PI);
+        assert_eq!(pieces[2].0, 0);
+        assert_eq!(pieces[2].2, PI);
+        assert_eq!((pieces[3].0, pieces[3].2), (2, PI));
+    }
+
+    #[test]
+    pub fn segments_through_the_origin_should_cover_every_direction() {
+        let origin = point![0.0, 0.0];
+        let through = Segment::new(point![-1.0, -1.0], point![2.0, 2.0]);
+        let touching = Segment::new(point![0.0, 0.0], point![0.0, 3.0]);
+        let pointing = Segment::new(point![0.0, -5.0], point![0.0, -1.0]);
+
+        assert_eq!(angular_interval(&origin, &through), (-PI, PI));
+        assert_eq!(angular_interval(&origin, &touching), (-PI, PI));
+        let (start, end) = angular_interval(&origin, &pointing);
+        assert_eq!(start, end);
+        assert_relative_eq!(start, -FRAC_PI_2);
+
+        let entries = radial_sort_segments(&origin, &[pointing, through]);
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].index, entries[0].end), (1, PI));
+    }
+
+    #[test]
+    pub fn segments_behind_the_origin_should_wrap_their_intervals() {
+        let origin = point![0.0, 0.0];
+        let behind = Segment::new(point![-1.0, -0.2], point![-1.0, 0.2]);
+        let through = Segment::new(point![-1.0, 0.0], point![1.0, 0.0]);
+
+        let interval = segment_interval(&origin, &behind);
+        let entries = radial_sort_segments(&origin, &[behind]);
+
+        assert!(interval.contains(Angle::from_degrees(180.0)));
+        assert!(!interval.contains(Angle::from_degrees(0.0)));
+        assert_relative_eq!(interval.sweep(), 2.0 * 0.2f32.atan());
+        // The split entries are the two halves of the same interval.
+        assert_eq!(entries.len(), 2);
+        for entry in &entries {
+            assert!(interval.contains_interval(&entry.interval()));
+        }
+        assert_eq!(segment_interval(&origin, &through), AngleInterval::full());
+        let full = radial_sort_segments(&origin, &[through]);
+        assert!(full[0].interval().is_full());
+    }
+
+    proptest! {
+        #[test]
+        fn segment_intervals_should_contain_both_endpoints(
+            origin in point2(-10.0..=10.0),
+            segment in segment(-10.0..=10.0, 0.1),
+        ) {
+            let interval = segment_interval(&origin, &segment);
+            let (start, end) = angular_interval(&origin, &segment);
+
+            for point in [segment.start, segment.end] {
+                let offset = point - origin;
+                prop_assume!(offset.norm() > 1e-3);
+                let angle =
+                    Angle::from_radians(math::atan2(offset.y, offset.x));
+                // Endpoints are on the boundary, where rounding can put
+                // them just outside.
+                let nearby = AngleInterval::from_center(angle, 1e-4);
+                prop_assert!(interval.overlaps(&nearby));
+            }
+            let middle = Angle::from_radians(start)
+                .lerp_shortest(Angle::from_radians(end), 0.5);
+            prop_assert!(interval.is_full() || interval.contains(middle));
+        }
+
+        #[test]
+        fn radial_entries_should_cover_their_segments(
+            origin in point2(-10.0..=10.0),
+            segments in prop::collection::vec(segment(-10.0..=10.0, 0.1), ..12),
+        ) {
+            let entries = radial_sort_segments(&origin, &segments);
+
+            for pair in entries.windows(2) {
+                prop_assert!(pair[0].start <= pair[1].start);
+            }
+            for (index, segment) in segments.iter().enumerate() {
+                let covered: f32 = entries
+                    .iter()
+                    .filter(|entry| entry.index == index)
+                    .map(|entry| {
+                        prop_assert!(-PI <= entry.start);
+                        prop_assert!(entry.start <= entry.end);
+                        prop_assert!(entry.end <= PI);
+                        Ok(entry.end - entry.start)
+                    })
+                    .sum::<Result<f32, TestCaseError>>()?;
+                let subtended = signed_angle(
+                    &(segment.start - origin),
+                    &(segment.end - origin),
+                )
+                .abs();
+                if covered < TAU {
+                    prop_assert!((covered - subtended).abs() <= 1e-3);
+                }
+            }
+            for entry in &entries {
+                if entry.end - entry.start < 1e-3 {
+                    continue;
+                }
+                // A ray through the middle of the entry hits its segment.
+                let angle = (entry.start + entry.end) / 2.0;
+                let (sin, cos) = math::sin_cos(angle);
+                let direction = Unit::new_normalize(vector![cos, sin]);
+                let ray = Ray::new(origin, direction);
+                let hit = intersect_ray_segment(&ray, &segments[entry.index]);
+                prop_assert!(hit.is_some());
+            }
+        }
+
+        #[test]
+        fn scans_inside_a_box_should_match_the_box_distances(
+            origin in point2(-9.0..=9.0),
+            start in -PI..PI,
+            end in -PI..PI,
+            rays in 1usize..32,
+        ) {
+            let bounds = Aabb::new(point![-10.0, -10.0], point![10.0, 10.0]);
+            let walls = bounds.edges();
+            let samples = scan(origin, &walls, rays, start, end, 100.0);
+
+            prop_assert_eq!(samples.len(), rays);
+            for sample in &samples {
+                let (sin, cos) = math::sin_cos(sample.angle);
+                // The distance to the nearest wall along each axis.
+                let x = if cos > 0.0 { (10.0 - origin.x) / cos }
+                    else { (-10.0 - origin.x) / cos };
+                let y = if sin > 0.0 { (10.0 - origin.y) / sin }
+                    else { (-10.0 - origin.y) / sin };
+                let expected = x.min(y);
+                prop_assert!(sample.is_hit());
+                prop_assert!(
+                    (sample.distance - expected).abs() <= 1e-3 * expected,
+                    "{} != {}", sample.distance, expected
+                );
+                let point = sample.point(&origin);
+                prop_assert!(
+                    point.x.abs().max(point.y.abs()) > 10.0 - 1e-3
+                );
+            }
+        }
+
+        #[test]
+        fn indexed_scans_should_match_brute_force_scans(
+            origin in point2(-20.0..=20.0),
+            segments in prop::collection::vec(segment(-20.0..=20.0, 0.0), ..24),
+            rays in 1usize..24,
+            start in -PI..PI,
+            end in -PI..PI,
+        ) {
+            let mut index = SegmentIndex::new(4.0);
+            let ids: Vec<_> =
+                segments.iter().map(|s| index.insert(*s)).collect();
+
+            let brute = scan(origin, &segments, rays, start, end, 15.0);
+            let indexed = scan_index(origin, &index, rays, start, end, 15.0);
+
+            for (a, b) in brute.iter().zip(&indexed) {
+                prop_assert_eq!(a.angle, b.angle);
+                prop_assert_eq!(a.distance, b.distance);
+                prop_assert_eq!(a.segment.map(|i| ids[i]), b.segment);
+            }
+        }
+    }
+}
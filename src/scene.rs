@@ -0,0 +1,713 @@
+//! A container for mixed geometry with stable handles and spatial queries.
+//!
+//! A [`Scene`] holds segments, polylines, polygons, and circles side by
+//! side, each with a user payload, and answers box, ray, and nearest
+//! queries across all of them at once. Handles stay valid while other
+//! shapes are added and removed, which makes the scene a natural backbone
+//! for an editor's document.
+//!
+//! Polygons and circles are treated as filled regions by every query, so a
+//! box inside a polygon touches it, a ray which starts inside a circle hits
+//! it immediately, and points inside either are at distance zero.
+
+use {
+    crate::{
+        line::{
+            intersection::intersect_ray_segment, DistanceToPoint, Ray, Segment,
+        },
+        math,
+        polygon::{Polygon, Polyline},
+        shape::{Aabb, BoundingBox, Circle},
+        spatial::Bvh,
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// The scene always tolerates this many changes before rebuilding its
+/// tree, so small scenes aren't rebuilt on every edit.
+const MIN_CHANGES_BEFORE_REBUILD: usize = 16;
+
+/// A handle to a shape in a [`Scene`].
+///
+/// Handles stay valid until their shape is removed. A handle to a removed
+/// shape never refers to a different shape, even if the scene reuses its
+/// storage. Handles are ordered, and the scene iterates and breaks ties in
+/// that order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GeoId {
+    slot: usize,
+    generation: u32,
+}
+
+/// Any of the shapes a [`Scene`] can hold.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// A single segment.
+    Segment(Segment),
+
+    /// An open chain of segments.
+    Polyline(Polyline),
+
+    /// A filled polygon.
+    Polygon(Polygon),
+
+    /// A filled circle.
+    Circle(Circle),
+}
+
+impl Geometry {
+    /// Returns true when the shape touches a box.
+    fn touches_aabb(&self, aabb: &Aabb) -> bool {
+        match self {
+            Geometry::Segment(segment) => segment_touches_aabb(segment, aabb),
+            Geometry::Polyline(polyline) => {
+                if let [point] = polyline.vertices[..] {
+                    return aabb.contains_point(&point);
+                }
+                polyline
+                    .segments()
+                    .any(|segment| segment_touches_aabb(&segment, aabb))
+            }
+            Geometry::Polygon(polygon) => {
+                // Either the outline crosses the box, or the box is entirely
+                // inside the polygon.
+                polygon
+                    .edges()
+                    .any(|edge| segment_touches_aabb(&edge, aabb))
+                    || polygon.contains_point(&aabb.center())
+            }
+            Geometry::Circle(circle) => {
+                let nearest = circle.center.sup(&aabb.min).inf(&aabb.max);
+                (nearest - circle.center).norm_squared()
+                    <= circle.radius * circle.radius
+            }
+        }
+    }
+
+    /// The ray parameter where a ray first touches the shape.
+    fn ray_hit(&self, ray: &Ray) -> Option<f32> {
+        let first = |segments: &mut dyn Iterator<Item = Segment>| {
+            segments
+                .filter_map(|segment| intersect_ray_segment(ray, &segment))
+                .min_by(f32::total_cmp)
+        };
+        match self {
+            Geometry::Segment(segment) => intersect_ray_segment(ray, segment),
+            Geometry::Polyline(polyline) => {
+                if let [point] = polyline.vertices[..] {
+                    return intersect_ray_segment(
+                        ray,
+                        &Segment::new(point, point),
+                    );
+                }
+                first(&mut polyline.segments())
+            }
+            Geometry::Polygon(polygon) => {
+                if polygon.contains_point(&ray.origin) {
+                    return Some(0.0);
+                }
+                first(&mut polygon.edges())
+            }
+            Geometry::Circle(circle) => {
+                let offset = ray.origin - circle.center;
+                let c = offset.norm_squared() - circle.radius * circle.radius;
+                if c <= 0.0 {
+                    return Some(0.0);
+                }
+                let b = offset.dot(&ray.direction);
+                let discriminant = b * b - c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                // The origin is outside, so both roots have the same sign.
+                let t = -b - math::sqrt(discriminant);
+                (t >= 0.0).then_some(t)
+            }
+        }
+    }
+
+    /// The distance from the shape to a point, which is zero inside
+    /// polygons and circles.
+    fn distance_to_point(&self, point: &Point2<f32>) -> f32 {
+        fn nearest(
+            segments: impl Iterator<Item = Segment>,
+            point: &Point2<f32>,
+        ) -> f32 {
+            math::sqrt(
+                segments
+                    .map(|segment| segment.distance_to_point_squared(point))
+                    .fold(f32::INFINITY, f32::min),
+            )
+        }
+        match self {
+            Geometry::Segment(segment) => segment.distance_to_point(point),
+            Geometry::Polyline(polyline) => {
+                if let [vertex] = polyline.vertices[..] {
+                    return (point - vertex).norm();
+                }
+                nearest(polyline.segments(), point)
+            }
+            Geometry::Polygon(polygon) => {
+                if polygon.contains_point(point) {
+                    return 0.0;
+                }
+                nearest(polygon.edges(), point)
+            }
+            Geometry::Circle(circle) => {
+                circle.distance_to_point(point).max(0.0)
+            }
+        }
+    }
+}
+
+fn segment_touches_aabb(segment: &Segment, aabb: &Aabb) -> bool {
+    aabb.slab_parameters(&segment.start, &(segment.end - segment.start))
+        .is_some_and(|(enter, exit)| enter <= 1.0 && exit >= 0.0)
+}
+
+impl BoundingBox for Geometry {
+    /// The bounding box of the shape inside.
+    fn bounding_box(&self) -> Aabb {
+        match self {
+            Geometry::Segment(segment) => segment.bounding_box(),
+            Geometry::Polyline(polyline) => polyline.bounding_box(),
+            Geometry::Polygon(polygon) => polygon.bounding_box(),
+            Geometry::Circle(circle) => circle.bounding_box(),
+        }
+    }
+}
+
+impl From<Segment> for Geometry {
+    /// Wrap a segment.
+    fn from(segment: Segment) -> Self {
+        Geometry::Segment(segment)
+    }
+}
+
+impl From<Polyline> for Geometry {
+    /// Wrap a polyline.
+    fn from(polyline: Polyline) -> Self {
+        Geometry::Polyline(polyline)
+    }
+}
+
+impl From<Polygon> for Geometry {
+    /// Wrap a polygon.
+    fn from(polygon: Polygon) -> Self {
+        Geometry::Polygon(polygon)
+    }
+}
+
+impl From<Circle> for Geometry {
+    /// Wrap a circle.
+    fn from(circle: Circle) -> Self {
+        Geometry::Circle(circle)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    entry: Option<(Geometry, T)>,
+
+    /// True when the slot's shape has changed since the tree was built, so
+    /// it's in the pending list and its entry in the tree is out of date.
+    pending: bool,
+}
+
+/// A shape's bounding box in the tree, along with its slot.
+#[derive(Debug, Copy, Clone)]
+struct Indexed {
+    slot: usize,
+    bounds: Aabb,
+}
+
+impl BoundingBox for Indexed {
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
+
+/// A collection of shapes, each with a payload, which supports box, ray,
+/// and nearest queries across every kind of shape.
+///
+/// Shapes are kept in a [`Bvh`] which is rebuilt after enough edits. Shapes
+/// which were inserted or updated since the last rebuild are kept in a
+/// pending list which every query checks in full, and shapes which were
+/// removed are skipped, so edits stay cheap and queries are always exact.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::{Ray, Segment},
+///             scene::Scene,
+///             shape::Circle,
+///         },
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let mut scene = Scene::new();
+///     let wall = scene.insert(
+///         Segment::new(point![4.0, -5.0], point![4.0, 5.0]),
+///         "wall",
+///     );
+///     let ball = scene.insert(Circle::new(point![2.0, 0.0], 1.0), "ball");
+///
+///     let east = Unit::new_normalize(vector![1.0, 0.0]);
+///     let ray = Ray::new(point![0.0, 0.0], east);
+///     let (hit, t) = scene.raycast(&ray).unwrap();
+///     assert_eq!((hit, scene.payload(hit)), (ball, Some(&"ball")));
+///     assert_relative_eq!(t, 1.0);
+///
+///     scene.remove(ball);
+///     let (hit, t) = scene.raycast(&ray).unwrap();
+///     assert_eq!(hit, wall);
+///     assert_relative_eq!(t, 4.0);
+///
+#[derive(Debug, Clone)]
+pub struct Scene<T = ()> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    tree: Bvh<Indexed>,
+
+    /// Slots whose shapes aren't in the tree yet. Some of them may have
+    /// been removed since.
+    pending: Vec<usize>,
+    changes: usize,
+    len: usize,
+}
+
+impl<T> Default for Scene<T> {
+    /// An empty scene.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Scene<T> {
+    /// Create an empty scene.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            tree: Bvh::new(Vec::new()),
+            pending: Vec::new(),
+            changes: 0,
+            len: 0,
+        }
+    }
+
+    /// The number of shapes in the scene.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true when the scene has no shapes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a shape and its payload to the scene.
+    pub fn insert(
+        &mut self,
+        geometry: impl Into<Geometry>,
+        payload: T,
+    ) -> GeoId {
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: None,
+                    pending: false,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.slots[slot].entry = Some((geometry.into(), payload));
+        self.len += 1;
+        self.changed(slot);
+        self.id(slot)
+    }
+
+    /// Remove a shape from the scene, returning it and its payload.
+    ///
+    /// Returns `None` when the shape was already removed. Handles to other
+    /// shapes are unaffected.
+    pub fn remove(&mut self, id: GeoId) -> Option<(Geometry, T)> {
+        self.get(id)?;
+        let slot = &mut self.slots[id.slot];
+        let entry = slot.entry.take();
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.slot);
+        self.len -= 1;
+        self.changed(id.slot);
+        entry
+    }
+
+    /// Replace a shape, keeping its handle and payload, and return the old
+    /// shape.
+    ///
+    /// Returns `None` and changes nothing when the shape was already
+    /// removed.
+    pub fn update(
+        &mut self,
+        id: GeoId,
+        geometry: impl Into<Geometry>,
+    ) -> Option<Geometry> {
+        self.get(id)?;
+        let (old, _) = self.slots[id.slot].entry.as_mut()?;
+        let old = core::mem::replace(old, geometry.into());
+        self.changed(id.slot);
+        Some(old)
+    }
+
+    /// The shape with a handle, or `None` when it was removed.
+    pub fn get(&self, id: GeoId) -> Option<&Geometry> {
+        self.entry(id).map(|(geometry, _)| geometry)
+    }
+
+    /// The payload of the shape with a handle, or `None` when it was
+    /// removed.
+    pub fn payload(&self, id: GeoId) -> Option<&T> {
+        self.entry(id).map(|(_, payload)| payload)
+    }
+
+    /// Change the payload of the shape with a handle, or get `None` when it
+    /// was removed.
+    pub fn payload_mut(&mut self, id: GeoId) -> Option<&mut T> {
+        self.slots
+            .get_mut(id.slot)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.entry.as_mut())
+            .map(|(_, payload)| payload)
+    }
+
+    /// Iterate over every shape in the scene with its handle and payload,
+    /// in handle order.
+    pub fn iter(&self) -> impl Iterator<Item = (GeoId, &Geometry, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(slot, entry)| {
+            let id = GeoId {
+                slot,
+                generation: entry.generation,
+            };
+            let (geometry, payload) = entry.entry.as_ref()?;
+            Some((id, geometry, payload))
+        })
+    }
+
+    /// Find every shape which touches a box, sorted by handle.
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<GeoId> {
+        let touches = |&slot: &usize| {
+            self.geometry(slot)
+                .is_some_and(|geometry| geometry.touches_aabb(aabb))
+        };
+        let mut found: Vec<usize> = self
+            .tree
+            .query_aabb(aabb)
+            .into_iter()
+            .map(|index| self.tree.primitives()[index].slot)
+            .filter(|slot| !self.slots[*slot].pending)
+            .chain(self.pending.iter().copied())
+            .filter(touches)
+            .collect();
+        found.sort_unstable();
+        found.into_iter().map(|slot| self.id(slot)).collect()
+    }
+
+    /// Find the first shape hit by a ray.
+    ///
+    /// Returns the shape's handle and the ray parameter of the hit, which is
+    /// also its distance from the ray's origin. Ties are resolved in favor
+    /// of the smallest handle.
+    pub fn raycast(&self, ray: &Ray) -> Option<(GeoId, f32)> {
+        let indexed = self.tree.query_ray(ray, |indexed| {
+            self.indexed_geometry(indexed.slot)?.ray_hit(ray)
+        });
+        let pending = self.pending.iter().filter_map(|&slot| {
+            let t = self.geometry(slot)?.ray_hit(ray)?;
+            Some((slot, t))
+        });
+        self.best(indexed, pending)
+    }
+
+    /// Find the shape which is nearest to a point.
+    ///
+    /// Returns the shape's handle and its distance to the point, which is
+    /// zero inside polygons and circles, or `None` when the scene has no
+    /// shapes with vertices.
+    /// Ties are resolved in favor of the smallest handle.
+    pub fn nearest(&self, point: &Point2<f32>) -> Option<(GeoId, f32)> {
+        let indexed = self.tree.closest(point, |indexed| {
+            self.indexed_geometry(indexed.slot)
+                .map_or(f32::NAN, |geometry| geometry.distance_to_point(point))
+        });
+        let pending = self.pending.iter().filter_map(|&slot| {
+            let distance = self.geometry(slot)?.distance_to_point(point);
+            Some((slot, distance))
+        });
+        self.best(indexed, pending)
+    }
+
+    /// The smallest of the tree's best result and every pending result.
+    fn best(
+        &self,
+        indexed: Option<(usize, f32)>,
+        pending: impl Iterator<Item = (usize, f32)>,
+    ) -> Option<(GeoId, f32)> {
+        let indexed = indexed
+            .map(|(index, value)| (self.tree.primitives()[index].slot, value));
+        indexed
+            .into_iter()
+            .chain(pending)
+            .filter(|(_, value)| value.is_finite())
+            .min_by(|(a, x), (b, y)| x.total_cmp(y).then(a.cmp(b)))
+            .map(|(slot, value)| (self.id(slot), value))
+    }
+
+    fn id(&self, slot: usize) -> GeoId {
+        GeoId {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    fn entry(&self, id: GeoId) -> Option<&(Geometry, T)> {
+        self.slots
+            .get(id.slot)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.entry.as_ref())
+    }
+
+    /// The shape in a pending slot, or `None` when it was removed.
+    fn geometry(&self, slot: usize) -> Option<&Geometry> {
+        self.slots[slot]
+            .entry
+            .as_ref()
+            .map(|(geometry, _)| geometry)
+    }
+
+    /// The shape in a slot when the tree's entry for it is up to date.
+    fn indexed_geometry(&self, slot: usize) -> Option<&Geometry> {
+        let slot = &self.slots[slot];
+        if slot.pending {
+            return None;
+        }
+        slot.entry.as_ref().map(|(geometry, _)| geometry)
+    }
+
+    /// Note that a slot no longer matches the tree, and rebuild the tree if
+    /// it's too far out of date.
+    fn changed(&mut self, slot: usize) {
+        if !self.slots[slot].pending {
+            self.slots[slot].pending = true;
+            self.pending.push(slot);
+        }
+        self.changes += 1;
+        if self.changes > MIN_CHANGES_BEFORE_REBUILD.max(self.len / 4) {
+            self.rebuild();
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let mut indexed = Vec::with_capacity(self.len);
+        for (slot, entry) in self.slots.iter_mut().enumerate() {
+            entry.pending = false;
+            let Some((geometry, _)) = &entry.entry else {
+                continue;
+            };
+            // Empty shapes touch nothing, and their inverted boxes would
+            // confuse the tree.
+            let bounds = geometry.bounding_box();
+            if bounds.min.x <= bounds.max.x && bounds.min.y <= bounds.max.y {
+                indexed.push(Indexed { slot, bounds });
+            }
+        }
+        self.tree = Bvh::new(indexed);
+        self.pending.clear();
+        self.changes = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{GeoId, Geometry, Scene},
+        crate::{
+            line::Ray,
+            polygon::{Polygon, Polyline},
+            shape::{Aabb, Circle},
+            test_support::{aabb, circle, point2, polygon, ray, segment},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Point2, Unit},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn removed_handles_should_stay_dead_when_slots_are_reused() {
+        let mut scene = Scene::new();
+        let a = scene.insert(Circle::new(point![0.0, 0.0], 1.0), 'a');
+        let b = scene.insert(Circle::new(point![5.0, 0.0], 1.0), 'b');
+
+        assert_eq!(scene.remove(a).map(|(_, tag)| tag), Some('a'));
+        let c = scene.insert(Circle::new(point![0.0, 0.0], 2.0), 'c');
+
+        assert_ne!(a, c);
+        assert!(scene.get(a).is_none());
+        assert!(scene.remove(a).is_none());
+        assert_eq!(scene.payload(b), Some(&'b'));
+        assert_eq!(scene.payload(c), Some(&'c'));
+        *scene.payload_mut(c).unwrap() = 'd';
+        let tags: Vec<char> = scene.iter().map(|(_, _, &tag)| tag).collect();
+        assert_eq!(tags, vec!['d', 'b']);
+    }
+
+    #[test]
+    pub fn filled_shapes_should_contain_their_insides() {
+        let mut scene = Scene::new();
+        let square = scene.insert(
+            Polygon::new(vec![
+                point![0.0, 0.0],
+                point![10.0, 0.0],
+                point![10.0, 10.0],
+                point![0.0, 10.0],
+            ]),
+            (),
+        );
+        let inside = Aabb::new(point![4.0, 4.0], point![6.0, 6.0]);
+        let ray =
+            Ray::new(point![5.0, 5.0], Unit::new_normalize(vector![1.0, 0.0]));
+
+        assert_eq!(scene.query_aabb(&inside), vec![square]);
+        assert_eq!(scene.raycast(&ray), Some((square, 0.0)));
+        assert_eq!(scene.nearest(&point![5.0, 5.0]), Some((square, 0.0)));
+        let (_, distance) = scene.nearest(&point![13.0, 14.0]).unwrap();
+        assert_relative_eq!(distance, 5.0);
+    }
+
+    #[test]
+    pub fn empty_shapes_should_never_be_found() {
+        let mut scene = Scene::new();
+        for _ in 0..40 {
+            scene.insert(Polyline::new(vec![]), ());
+        }
+        let everything = Aabb::new(point![-1e9, -1e9], point![1e9, 1e9]);
+
+        assert!(scene.query_aabb(&everything).is_empty());
+        assert!(scene.nearest(&point![0.0, 0.0]).is_none());
+        assert_eq!(scene.len(), 40);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(Geometry),
+        Remove(usize),
+        Update(usize, Geometry),
+        QueryAabb(Aabb),
+        Raycast(Ray),
+        Nearest(Point2<f32>),
+    }
+
+    fn geometry() -> impl Strategy<Value = Geometry> {
+        prop_oneof![
+            segment(-50.0..=50.0, 0.0).prop_map(Geometry::from),
+            prop::collection::vec(point2(-50.0..=50.0), 1..5)
+                .prop_map(|vertices| Polyline::new(vertices).into()),
+            polygon(-50.0..=50.0, 3..=8, false).prop_map(Geometry::from),
+            circle(-50.0..=50.0, 0.0..=10.0).prop_map(Geometry::from),
+        ]
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            3 => geometry().prop_map(Op::Insert),
+            1 => any::<usize>().prop_map(Op::Remove),
+            1 => (any::<usize>(), geometry())
+                .prop_map(|(i, geometry)| Op::Update(i, geometry)),
+            1 => aabb(-60.0..=60.0).prop_map(Op::QueryAabb),
+            1 => ray(-60.0..=60.0).prop_map(Op::Raycast),
+            1 => point2(-60.0..=60.0).prop_map(Op::Nearest),
+        ]
+    }
+
+    /// The smallest value, breaking ties with the smallest handle.
+    fn best(
+        candidates: impl Iterator<Item = (GeoId, f32)>,
+    ) -> Option<(GeoId, f32)> {
+        candidates
+            .filter(|(_, value)| value.is_finite())
+            .min_by(|(a, x), (b, y)| x.total_cmp(y).then(a.cmp(b)))
+    }
+
+    proptest! {
+        #[test]
+        fn scenes_should_match_a_linear_scan(
+            ops in prop::collection::vec(op(), 1..120),
+        ) {
+            let mut scene = Scene::new();
+            let mut reference: Vec<(GeoId, Geometry, usize)> = Vec::new();
+            for (step, op) in ops.into_iter().enumerate() {
+                match op {
+                    Op::Insert(geometry) => {
+                        let id = scene.insert(geometry.clone(), step);
+                        reference.push((id, geometry, step));
+                    }
+                    Op::Remove(i) if !reference.is_empty() => {
+                        let (id, geometry, tag) =
+                            reference.remove(i % reference.len());
+                        let removed = Some((geometry, tag));
+                        prop_assert_eq!(scene.remove(id), removed);
+                        prop_assert_eq!(scene.remove(id), None);
+                    }
+                    Op::Update(i, geometry) if !reference.is_empty() => {
+                        let i = i % reference.len();
+                        let old = core::mem::replace(
+                            &mut reference[i].1,
+                            geometry.clone(),
+                        );
+                        let id = reference[i].0;
+                        prop_assert_eq!(scene.update(id, geometry), Some(old));
+                    }
+                    Op::QueryAabb(aabb) => {
+                        let mut expected: Vec<GeoId> = reference
+                            .iter()
+                            .filter(|(_, shape, _)| shape.touches_aabb(&aabb))
+                            .map(|(id, _, _)| *id)
+                            .collect();
+                        expected.sort_unstable();
+                        prop_assert_eq!(scene.query_aabb(&aabb), expected);
+                    }
+                    Op::Raycast(ray) => {
+                        let expected = best(reference.iter().filter_map(
+                            |(id, shape, _)| Some((*id, shape.ray_hit(&ray)?)),
+                        ));
+                        prop_assert_eq!(scene.raycast(&ray), expected);
+                    }
+                    Op::Nearest(point) => {
+                        let expected = best(reference.iter().map(
+                            |(id, geometry, _)| {
+                                (*id, geometry.distance_to_point(&point))
+                            },
+                        ));
+                        prop_assert_eq!(scene.nearest(&point), expected);
+                    }
+                    _ => {}
+                }
+
+                prop_assert_eq!(scene.len(), reference.len());
+                let mut sorted = reference.clone();
+                sorted.sort_by_key(|(id, _, _)| *id);
+                let contents: Vec<(GeoId, Geometry, usize)> = scene
+                    .iter()
+                    .map(|(id, geometry, &tag)| (id, geometry.clone(), tag))
+                    .collect();
+                prop_assert_eq!(contents, sorted);
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Hard shadows cast by segments in front of a point light.
+//!
+//! The shadow of an occluder is the region where the straight line to the
+//! light crosses the occluder. For a single segment this is a wedge which
+//! starts at the segment and widens as it moves away from the light. The
+//! functions in this module truncate the wedge so it can be returned as a
+//! polygon, either at a fixed distance behind the occluder or at the edges
+//! of a bounding box.
+//!
+//! Every shadow polygon is wound counterclockwise, so the shadows of many
+//! occluders can be combined with a polygon union to build a shadow map.
+
+use {
+    crate::{line::Segment, polygon::Polygon, shape::Aabb},
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// The shadow cast by an occluder, truncated `extent` units behind it.
+///
+/// The shadow is the quadrilateral formed by the occluder and its endpoints
+/// pushed radially away from the light by `extent`. Points behind the
+/// occluder are inside the shadow, and points between the light and the
+/// occluder are not.
+///
+/// Returns `None` when there is no shadow with any area: when the light is
+/// on the occluder's supporting line (including when the light is one of
+/// its endpoints), when the occluder has no length, or when `extent` is not
+/// positive.
+///
+/// # Example
+///
+/// ```none
+///              shadow
+///        +--------------------+
+///         \                  /
+///          \                /
+///           +--------------+  occluder
+///
+///                  *  light
+/// ```
+///
+///     use ::{
+///         compgeo::{line::Segment, shadow::shadow_volume},
+///         nalgebra::point,
+///     };
+///
+///     let light = point![0.0, 0.0];
+///     let occluder = Segment::new(point![-1.0, 1.0], point![1.0, 1.0]);
+///
+///     let shadow = shadow_volume(&light, &occluder, 10.0).unwrap();
+///
+///     assert!(shadow.contains_point(&point![0.0, 5.0]));
+///     assert!(!shadow.contains_point(&point![0.0, 0.5]));
+///     assert!(shadow_volume(&point![5.0, 1.0], &occluder, 10.0).is_none());
+///
+pub fn shadow_volume(
+    light: &Point2<f32>,
+    occluder: &Segment,
+    extent: f32,
+) -> Option<Polygon> {
+    if extent.is_nan() || extent <= 0.0 {
+        return None;
+    }
+    let (start, end) = facing_light(light, occluder)?;
+    let push = |p: Point2<f32>| p + (p - light).normalize() * extent;
+    Some(Polygon::new(vec![start, end, push(end), push(start)]))
+}
+
+/// The shadow cast by an occluder, clipped to a bounding box.
+///
+/// This is like [`shadow_volume`], except that the shadow extends all the
+/// way to the edges of `bounds`. The polygon includes any corners of the box
+/// which are in shadow, so it can have up to eight vertices.
+///
+/// Returns `None` in the same cases as [`shadow_volume`], and also when
+/// either end of the occluder is outside of `bounds` or the box has no area.
+/// The light doesn't need to be inside the box.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, shadow::shadow_volume_in, shape::Aabb},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let bounds = Aabb::new(point![-10.0, -10.0], point![10.0, 10.0]);
+///     let light = point![0.0, 0.0];
+///     let occluder = Segment::new(point![-1.0, 1.0], point![1.0, 1.0]);
+///
+///     let shadow = shadow_volume_in(&light, &occluder, &bounds).unwrap();
+///
+///     // The shadow reaches the top corners of the box.
+///     assert!(shadow.vertices.contains(&point![10.0, 10.0]));
+///     assert!(shadow.vertices.contains(&point![-10.0, 10.0]));
+///     assert_relative_eq!(shadow.area(), 99.0);
+///
+pub fn shadow_volume_in(
+    light: &Point2<f32>,
+    occluder: &Segment,
+    bounds: &Aabb,
+) -> Option<Polygon> {
+    if !(bounds.width() > 0.0 && bounds.height() > 0.0) {
+        return None;
+    }
+    let (start, end) = facing_light(light, occluder)?;
+    if !bounds.contains_point(&start) || !bounds.contains_point(&end) {
+        return None;
+    }
+
+    let (end_exit, end_parameter) = exit(bounds, &end, &(end - light));
+    let (start_exit, start_parameter) = exit(bounds, &start, &(start - light));
+
+    // Walk counterclockwise around the box from where the end's shadow
+    // leaves it to where the start's shadow leaves it, picking up corners.
+    let mut vertices = vec![start, end, end_exit];
+    let mut stop = start_parameter;
+    if stop <= end_parameter {
+        stop += 4.0;
+    }
+    let corners = bounds.corners();
+    for corner in 0..8 {
+        let parameter = corner as f32;
+        if end_parameter < parameter && parameter < stop {
+            vertices.push(corners[corner % 4]);
+        }
+    }
+    vertices.push(start_exit);
+    Some(Polygon::new(vertices))
+}
+
+/// The shadows cast by many occluders, each truncated `extent` units behind
+/// its occluder.
+///
+/// Occluders which don't cast a shadow with any area are skipped, so the
+/// shadows don't line up with the occluders by index. See
+/// [`shadow_volume`].
+pub fn shadow_volumes(
+    light: &Point2<f32>,
+    occluders: &[Segment],
+    extent: f32,
+) -> Vec<Polygon> {
+    occluders
+        .iter()
+        .filter_map(|occluder| shadow_volume(light, occluder, extent))
+        .collect()
+}
+
+/// The shadows cast by many occluders, each clipped to a bounding box.
+///
+/// Occluders which don't cast a shadow with any area, or which aren't inside
+/// the box, are skipped. See [`shadow_volume_in`].
+pub fn shadow_volumes_in(
+    light: &Point2<f32>,
+    occluders: &[Segment],
+    bounds: &Aabb,
+) -> Vec<Polygon> {
+    occluders
+        .iter()
+        .filter_map(|occluder| shadow_volume_in(light, occluder, bounds))
+        .collect()
+}
+
+/// The occluder's endpoints, ordered so the light is on the right of the
+/// segment from the first to the second. This makes shadows wind
+/// counterclockwise.
+///
+/// Returns `None` when the light is on the occluder's supporting line.
+fn facing_light(
+    light: &Point2<f32>,
+    occluder: &Segment,
+) -> Option<(Point2<f32>, Point2<f32>)> {
+    let side = (occluder.end - occluder.start).perp(&(light - occluder.start));
+    if side < 0.0 {
+        Some((occluder.start, occluder.end))
+    } else if side > 0.0 {
+        Some((occluder.end, occluder.start))
+    } else {
+        None
+    }
+}
+
+/// Where a ray from a point inside the box leaves the box, along with how
+/// far the exit is around the box's boundary. The boundary is parameterized
+/// counterclockwise from `min`, with the corners at 0, 1, 2, and 3.
+fn exit(
+    bounds: &Aabb,
+    origin: &Point2<f32>,
+    direction: &Vector2<f32>,
+) -> (Point2<f32>, f32) {
+    let distance = |p: f32, d: f32, min: f32, max: f32| {
+        if d > 0.0 {
+            (max - p) / d
+        } else if d < 0.0 {
+            (min - p) / d
+        } else {
+            f32::INFINITY
+        }
+    };
+    let (min, max) = (bounds.min, bounds.max);
+    let tx = distance(origin.x, direction.x, min.x, max.x);
+    let ty = distance(origin.y, direction.y, min.y, max.y);
+    let (width, height) = (bounds.width(), bounds.height());
+    if tx <= ty {
+        let y = (origin.y + direction.y * tx).clamp(min.y, max.y);
+        if direction.x > 0.0 {
+            (Point2::new(max.x, y), 1.0 + (y - min.y) / height)
+        } else {
+            (Point2::new(min.x, y), 3.0 + (max.y - y) / height)
+        }
+    } else {
+        let x = (origin.x + direction.x * ty).clamp(min.x, max.x);
+        if direction.y > 0.0 {
+            (Point2::new(x, max.y), 2.0 + (max.x - x) / width)
+        } else {
+            (Point2::new(x, min.y), (x - min.x) / width)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            shadow::{
+                shadow_volume, shadow_volume_in, shadow_volumes,
+                shadow_volumes_in,
+            },
+            shape::Aabb,
+            test_support::{point2, segment},
+        },
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn lights_on_the_supporting_line_should_not_cast_shadows() {
+        let occluder = Segment::new(point![0.0, 0.0], point![2.0, 2.0]);
+        let bounds = Aabb::new(point![-5.0, -5.0], point![5.0, 5.0]);
+
+        for light in [point![1.0, 1.0], point![0.0, 0.0], point![-3.0, -3.0]] {
+            assert_eq!(shadow_volume(&light, &occluder, 1.0), None);
+            assert_eq!(shadow_volume_in(&light, &occluder, &bounds), None);
+        }
+        assert_eq!(shadow_volume(&point![0.0, 1.0], &occluder, 0.0), None);
+    }
+
+    #[test]
+    pub fn shadows_should_wind_counterclockwise() {
+        let bounds = Aabb::new(point![-5.0, -5.0], point![5.0, 5.0]);
+        let mut occluder = Segment::new(point![-1.0, 1.0], point![1.0, 1.0]);
+
+        for _ in 0..2 {
+            let shadow = shadow_volume(&point![0.0, 0.0], &occluder, 2.0);
+            assert!(shadow.unwrap().signed_area() > 0.0);
+            let shadow =
+                shadow_volume_in(&point![0.0, 0.0], &occluder, &bounds);
+            assert!(shadow.unwrap().signed_area() > 0.0);
+            occluder = Segment::new(occluder.end, occluder.start);
+        }
+    }
+
+    #[test]
+    pub fn shadows_should_wrap_around_the_corners_of_the_bounds() {
+        // The light is just below a wide occluder, so the shadow covers the
+        // whole top half of the box and then some.
+        let bounds = Aabb::new(point![0.0, 0.0], point![10.0, 10.0]);
+        let occluder = Segment::new(point![1.0, 5.0], point![9.0, 5.0]);
+
+        let shadow =
+            shadow_volume_in(&point![5.0, 4.9], &occluder, &bounds).unwrap();
+
+        assert_eq!(shadow.vertices.len(), 6);
+        assert!(shadow.contains_point(&point![0.5, 9.5]));
+        assert!(shadow.contains_point(&point![9.5, 9.5]));
+        assert!(!shadow.contains_point(&point![5.0, 4.0]));
+    }
+
+    #[test]
+    pub fn batches_should_skip_occluders_without_shadows() {
+        let light = point![0.0, 0.0];
+        let occluders = [
+            Segment::new(point![-1.0, 1.0], point![1.0, 1.0]),
+            Segment::new(point![1.0, 0.0], point![2.0, 0.0]),
+            Segment::new(point![-1.0, -1.0], point![1.0, -1.0]),
+            Segment::new(point![20.0, 1.0], point![21.0, 1.0]),
+        ];
+        let bounds = Aabb::new(point![-5.0, -5.0], point![5.0, 5.0]);
+
+        assert_eq!(shadow_volumes(&light, &occluders, 1.0).len(), 3);
+        assert_eq!(shadow_volumes_in(&light, &occluders, &bounds).len(), 2);
+    }
+
+    #[test]
+    pub fn bounded_shadows_should_have_the_expected_area() {
+        let bounds = Aabb::new(point![-10.0, -10.0], point![10.0, 10.0]);
+        let occluder = Segment::new(point![-1.0, 1.0], point![1.0, 1.0]);
+
+        let shadow =
+            shadow_volume_in(&point![0.0, 0.0], &occluder, &bounds).unwrap();
+
+        // A trapezoid from y = 1 to y = 10 widening from 2 to 20
+        assert_relative_eq!(shadow.area(), 9.0 * (2.0 + 20.0) / 2.0);
+    }
+
+    proptest! {
+        #[test]
+        fn points_behind_occluders_should_be_in_shadow(
+            light in point2(-10.0..=10.0),
+            occluder in segment(-10.0..=10.0, 0.5),
+            u in 0.1f32..0.9,
+            behind in 0.05f32..0.95,
+        ) {
+            let bounds = Aabb::new(point![-20.0, -20.0], point![20.0, 20.0]);
+            let target = occluder.start + (occluder.end - occluder.start) * u;
+            let to_target = target - light;
+            // Keep the light clearly off of the occluder's line.
+            let direction = (occluder.end - occluder.start).normalize();
+            prop_assume!(direction.perp(&to_target.normalize()).abs() > 0.05);
+            prop_assume!(to_target.norm() > 0.1);
+
+            let shadow = shadow_volume(&light, &occluder, 100.0).unwrap();
+            let bounded = shadow_volume_in(&light, &occluder, &bounds).unwrap();
+
+            let in_front = light + to_target * behind;
+            prop_assert!(!shadow.contains_point(&in_front));
+            prop_assert!(!bounded.contains_point(&in_front));
+
+            let behind = target + to_target.normalize() * 0.1;
+            prop_assert!(shadow.contains_point(&behind));
+            prop_assert!(bounded.contains_point(&behind));
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use {
+    crate::{
+        display::{write_compact_debug, write_scalar},
+        error::check_finite,
+        line::Segment,
+        Error,
+    },
+    core::fmt,
+    nalgebra::{Point2, Vector2},
+};
+
+/// An axis-aligned bounding box.
+///
+/// The box is closed, so points on its boundary are inside it. Boxes are
+/// displayed as the product of their ranges, like `[1, 3] x [2, 4]`.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Aabb {
+    /// The corner with the smallest coordinates.
+    pub min: Point2<f32>,
+
+    /// The corner with the largest coordinates.
+    pub max: Point2<f32>,
+}
+
+impl Aabb {
+    /// A box which contains nothing, with `min` at positive infinity and
+    /// `max` at negative infinity. Merging it with any box gives that box.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn empty() -> Aabb {
+        Aabb {
+            min: Point2::new(f32::INFINITY, f32::INFINITY),
+            max: Point2::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// Create a new box from two opposite corners.
+    ///
+    /// The corners can be provided in any order. They aren't validated, use
+    /// [`Aabb::try_new`] to reject non-finite coordinates.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Aabb,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let aabb = Aabb::new(point![3.0, 1.0], point![1.0, 2.0]);
+    ///
+    ///     assert_eq!(aabb.min, point![1.0, 1.0]);
+    ///     assert_eq!(aabb.max, point![3.0, 2.0]);
+    ///
+    pub fn new(a: Point2<f32>, b: Point2<f32>) -> Self {
+        Self {
+            min: a.inf(&b),
+            max: a.sup(&b),
+        }
+    }
+
+    /// Create a new box, checking that every coordinate is finite.
+    pub fn try_new(a: Point2<f32>, b: Point2<f32>) -> Result<Self, Error> {
+        check_finite(&[a.x, a.y, b.x, b.y])?;
+        Ok(Self::new(a, b))
+    }
+
+    /// The smallest box which contains every point.
+    ///
+    /// Returns `None` when there are no points.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Aabb,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let aabb = Aabb::from_points(&[
+    ///         point![0.0, 2.0],
+    ///         point![1.0, -1.0],
+    ///         point![-3.0, 0.0],
+    ///     ])
+    ///     .unwrap();
+    ///
+    ///     assert_eq!(aabb, Aabb::new(point![-3.0, -1.0], point![1.0, 2.0]));
+    ///
+    pub fn from_points(points: &[Point2<f32>]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        Some(
+            rest.iter()
+                .fold(Self::new(*first, *first), |aabb, point| Self {
+                    min: aabb.min.inf(point),
+                    max: aabb.max.sup(point),
+                }),
+        )
+    }
+
+    /// The size of the box along the x axis.
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    /// The size of the box along the y axis.
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    /// The point in the middle of the box.
+    pub fn center(&self) -> Point2<f32> {
+        nalgebra::center(&self.min, &self.max)
+    }
+
+    /// Returns true when the point is inside the box or on its boundary.
+    pub fn contains_point(&self, point: &Point2<f32>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+    }
+
+    /// Returns true when the boxes share at least one point.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    /// The smallest box which contains both boxes.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn merged(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    /// The box's corners in counterclockwise order, starting with `min`.
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        [
+            self.min,
+            Point2::new(self.max.x, self.min.y),
+            self.max,
+            Point2::new(self.min.x, self.max.y),
+        ]
+    }
+
+    /// The box's edges in counterclockwise order, starting at `min`.
+    pub fn edges(&self) -> [Segment; 4] {
+        let [a, b, c, d] = self.corners();
+        [
+            Segment::new(a, b),
+            Segment::new(b, c),
+            Segment::new(c, d),
+            Segment::new(d, a),
+        ]
+    }
+
+    /// The range of parameters `t` where `origin + direction * t` is inside
+    /// the box, using the slab method.
+    ///
+    /// Returns `None` when the line misses the box. The range can be
+    /// unbounded when the direction is zero.
+    pub(crate) fn slab_parameters(
+        &self,
+        origin: &Point2<f32>,
+        direction: &Vector2<f32>,
+    ) -> Option<(f32, f32)> {
+        let (mut enter, mut exit) = (f32::NEG_INFINITY, f32::INFINITY);
+        for axis in 0..2 {
+            let (o, d) = (origin[axis], direction[axis]);
+            let (min, max) = (self.min[axis], self.max[axis]);
+            if d == 0.0 {
+                if o < min || o > max {
+                    return None;
+                }
+                continue;
+            }
+            let (a, b) = ((min - o) / d, (max - o) / d);
+            enter = enter.max(a.min(b));
+            exit = exit.min(a.max(b));
+        }
+        (enter <= exit).then_some((enter, exit))
+    }
+}
+
+impl fmt::Display for Aabb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        write_scalar(f, self.min.x)?;
+        f.write_str(", ")?;
+        write_scalar(f, self.max.x)?;
+        f.write_str("] x [")?;
+        write_scalar(f, self.min.y)?;
+        f.write_str(", ")?;
+        write_scalar(f, self.max.y)?;
+        f.write_str("]")
+    }
+}
+
+impl fmt::Debug for Aabb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write_compact_debug(f, "Aabb", self);
+        }
+        f.debug_struct("Aabb")
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .finish()
+    }
+}
@@ -0,0 +1,416 @@
+#[cfg(feature = "alloc")]
+use {crate::polygon::Polyline, core::f32::consts::TAU};
+use {
+    crate::{
+        error::check_finite,
+        line::DistanceToPoint,
+        math,
+        operations::{Angle, AngleInterval},
+        Error,
+    },
+    nalgebra::{Point2, Vector2},
+};
+
+/// The fewest segments used to approximate a full circle.
+#[cfg(feature = "alloc")]
+const MIN_CIRCLE_SEGMENTS: usize = 8;
+
+/// The most segments used to approximate a full circle.
+///
+/// This is roughly where the chords of a circle become shorter than f32 can
+/// resolve, so smaller errors aren't meaningful.
+#[cfg(feature = "alloc")]
+const MAX_CIRCLE_SEGMENTS: usize = 8192;
+
+/// A piece of a circle's boundary.
+///
+/// The arc starts at `start_angle` and sweeps through `sweep` radians.
+/// Positive sweeps run counterclockwise and negative sweeps run clockwise.
+/// Angles are measured counterclockwise from the positive x axis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Arc {
+    /// The center of the arc's circle.
+    pub center: Point2<f32>,
+
+    /// The distance from the center to every point on the arc.
+    pub radius: f32,
+
+    /// The angle where the arc starts, in radians.
+    pub start_angle: f32,
+
+    /// The signed angle the arc sweeps through, in radians.
+    pub sweep: f32,
+}
+
+impl Arc {
+    /// Create a new arc.
+    ///
+    /// The values aren't validated. Use [`Arc::try_new`] to reject negative
+    /// radii and non-finite values.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Arc,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     // The top half of the unit circle
+    ///     let arc = Arc::new(point![0.0, 0.0], 1.0, 0.0, core::f32::consts::PI);
+    ///
+    pub fn new(
+        center: Point2<f32>,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    ) -> Self {
+        Self {
+            center,
+            radius,
+            start_angle,
+            sweep,
+        }
+    }
+
+    /// Create a new arc, checking that the radius isn't negative and that
+    /// every value is finite.
+    pub fn try_new(
+        center: Point2<f32>,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    ) -> Result<Self, Error> {
+        check_finite(&[center.x, center.y, radius, start_angle, sweep])?;
+        if radius < 0.0 {
+            return Err(Error::NegativeRadius);
+        }
+        Ok(Self::new(center, radius, start_angle, sweep))
+    }
+
+    /// Create a new arc from typed angles.
+    ///
+    /// This is [`Arc::new`] for callers which keep their angles as
+    /// [`Angle`]s. The sweep isn't wrapped, so sweeps of more than a full
+    /// turn still describe a full circle.
+    pub fn from_angles(
+        center: Point2<f32>,
+        radius: f32,
+        start: Angle,
+        sweep: Angle,
+    ) -> Self {
+        Self::new(center, radius, start.radians(), sweep.radians())
+    }
+
+    /// Create an arc which runs counterclockwise across an interval of
+    /// directions.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             operations::{Angle, AngleInterval},
+    ///             shape::Arc,
+    ///         },
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // Across the positive x axis, from 350 to 10 degrees.
+    ///     let interval = AngleInterval::new(
+    ///         Angle::from_degrees(350.0),
+    ///         Angle::from_degrees(10.0),
+    ///     );
+    ///     let arc = Arc::from_interval(point![0.0, 0.0], 2.0, &interval);
+    ///
+    ///     let middle = arc.point_at(0.5);
+    ///     assert_relative_eq!(middle, point![2.0, 0.0], epsilon = 1e-5);
+    ///     assert_relative_eq!(arc.end().degrees(), 370.0, epsilon = 1e-3);
+    ///
+    pub fn from_interval(
+        center: Point2<f32>,
+        radius: f32,
+        interval: &AngleInterval,
+    ) -> Self {
+        Self::new(center, radius, interval.start().radians(), interval.sweep())
+    }
+
+    /// The angle where the arc starts.
+    pub fn start(&self) -> Angle {
+        Angle::from_radians(self.start_angle)
+    }
+
+    /// The angle where the arc ends, which is `start + sweep` without any
+    /// wrapping.
+    pub fn end(&self) -> Angle {
+        Angle::from_radians(self.start_angle + self.sweep)
+    }
+
+    /// The directions the arc covers as seen from its center.
+    ///
+    /// Clockwise arcs cover the same directions as the counterclockwise
+    /// arc from their end to their start.
+    pub fn interval(&self) -> AngleInterval {
+        AngleInterval::from_sweep(self.start(), self.sweep)
+    }
+
+    /// The point which is a fraction `t` of the way along the arc.
+    ///
+    /// `t = 0` is the start of the arc and `t = 1` is the end.
+    pub fn point_at(&self, t: f32) -> Point2<f32> {
+        point_on_circle(
+            &self.center,
+            self.radius,
+            self.start_angle + self.sweep * t,
+        )
+    }
+
+    /// The length of the arc.
+    pub fn length(&self) -> f32 {
+        self.radius * self.sweep.abs()
+    }
+
+    /// Approximate the arc with a polyline whose chords stay within
+    /// `max_error` of the arc.
+    ///
+    /// The number of segments is chosen from the sagitta of each chord, and
+    /// is never less than the arc's share of the 8 segments used for a full
+    /// circle. The first and last vertices are the ends of the arc.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Arc,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let arc = Arc::new(point![0.0, 0.0], 2.0, 0.0, core::f32::consts::PI);
+    ///     let polyline = arc.to_polyline(0.01);
+    ///
+    ///     assert_eq!(polyline.vertices[0], point![2.0, 0.0]);
+    ///     assert_relative_eq!(
+    ///         polyline.vertices.last().unwrap(),
+    ///         &point![-2.0, 0.0],
+    ///         epsilon = 1e-5,
+    ///     );
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn to_polyline(&self, max_error: f32) -> Polyline {
+        self.to_polyline_n(segments_for_sweep(
+            self.radius,
+            self.sweep.abs(),
+            max_error,
+        ))
+    }
+
+    /// Approximate the arc with a polyline of `n` segments, which is
+    /// clamped to at least one.
+    #[cfg(feature = "alloc")]
+    pub fn to_polyline_n(&self, n: usize) -> Polyline {
+        let n = n.max(1);
+        Polyline::new(
+            (0..=n)
+                .map(|i| self.point_at(i as f32 / n as f32))
+                .collect(),
+        )
+    }
+}
+
+impl DistanceToPoint for Arc {
+    /// The distance from the nearest point on the arc to a point.
+    ///
+    /// Unlike [`Circle`](crate::shape::Circle), the distance is never
+    /// negative. Points which are off to the side of the arc are measured
+    /// to the nearest end.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::DistanceToPoint, shape::Arc},
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // The top half of a circle with radius 2.
+    ///     let arc = Arc::new(point![0.0, 0.0], 2.0, 0.0, core::f32::consts::PI);
+    ///
+    ///     assert_relative_eq!(arc.distance_to_point(&point![0.0, 3.0]), 1.0);
+    ///     assert_relative_eq!(arc.distance_to_point(&point![0.0, 0.5]), 1.5);
+    ///     assert_relative_eq!(arc.distance_to_point(&point![2.0, -1.0]), 1.0);
+    ///
+    fn distance_to_point(&self, point: &Point2<f32>) -> f32 {
+        math::sqrt(self.distance_to_point_squared(point))
+    }
+
+    fn distance_to_point_squared(&self, point: &Point2<f32>) -> f32 {
+        let offset = point - self.center;
+        let angle = Angle::from_radians(math::atan2(offset.y, offset.x));
+        if self.interval().contains(angle) {
+            let distance = offset.norm() - self.radius;
+            return distance * distance;
+        }
+        (point - self.point_at(0.0))
+            .norm_squared()
+            .min((point - self.point_at(1.0)).norm_squared())
+    }
+}
+
+/// The point on a circle at an angle, measured counterclockwise from the
+/// positive x axis.
+pub(crate) fn point_on_circle(
+    center: &Point2<f32>,
+    radius: f32,
+    angle: f32,
+) -> Point2<f32> {
+    let (sin, cos) = math::sin_cos(angle);
+    center + Vector2::new(cos, sin) * radius
+}
+
+/// The number of chords needed to keep the sagitta, the largest distance
+/// between a chord and its arc, within `max_error`.
+///
+/// A chord spanning `theta` radians has a sagitta of
+/// `radius * (1 - cos(theta / 2))`. Solving for `theta` gives the widest
+/// chord which stays within the error.
+#[cfg(feature = "alloc")]
+pub(crate) fn segments_for_sweep(
+    radius: f32,
+    sweep: f32,
+    max_error: f32,
+) -> usize {
+    let turns = sweep / TAU;
+    let min = math::ceil(MIN_CIRCLE_SEGMENTS as f32 * turns).max(1.0);
+    let max = MAX_CIRCLE_SEGMENTS as f32 * turns;
+    let max_error = max_error.max(0.0);
+    if radius <= 0.0 || max_error >= radius {
+        return min as usize;
+    }
+    let widest = 2.0 * math::acos(1.0 - max_error / radius);
+    if widest <= 0.0 {
+        return math::ceil(max).max(min) as usize;
+    }
+    math::ceil(sweep / widest).clamp(min, max.max(min)) as usize
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::DistanceToPoint,
+            operations::{Angle, AngleInterval},
+            shape::Arc,
+            test_support::{circle, point2},
+        },
+        approx::assert_relative_eq,
+        core::f32::consts::{FRAC_PI_2, PI},
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn polylines_should_stay_within_the_error() {
+        let arc = Arc::new(point![1.0, -2.0], 5.0, 0.3, -2.5);
+
+        for max_error in [1.0, 0.1, 0.01, 0.001] {
+            let polyline = arc.to_polyline(max_error);
+
+            for pair in polyline.vertices.windows(2) {
+                let middle = nalgebra::center(&pair[0], &pair[1]);
+                let deviation = arc.radius - (middle - arc.center).norm();
+                assert!(deviation <= max_error * 1.001 + 1e-5);
+            }
+            assert_relative_eq!(polyline.vertices[0], arc.point_at(0.0));
+            assert_relative_eq!(
+                *polyline.vertices.last().unwrap(),
+                arc.point_at(1.0)
+            );
+        }
+    }
+
+    #[test]
+    pub fn short_arcs_should_use_their_share_of_the_minimum_segments() {
+        let quarter = Arc::new(point![0.0, 0.0], 1.0, 0.0, FRAC_PI_2);
+
+        assert_eq!(quarter.to_polyline(10.0).vertices.len(), 3);
+        assert_eq!(quarter.to_polyline_n(0).vertices.len(), 2);
+        assert_relative_eq!(quarter.length(), FRAC_PI_2);
+    }
+
+    #[test]
+    pub fn clockwise_arcs_should_run_clockwise() {
+        let arc = Arc::new(point![0.0, 0.0], 1.0, PI, -FRAC_PI_2);
+
+        let polyline = arc.to_polyline_n(2);
+
+        assert_relative_eq!(polyline.vertices[0], point![-1.0, 0.0]);
+        assert_relative_eq!(
+            polyline.vertices[2],
+            point![0.0, 1.0],
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    pub fn clockwise_arcs_should_measure_the_side_they_cover() {
+        // The bottom half of the unit circle, swept clockwise from the left.
+        let arc = Arc::new(point![0.0, 0.0], 1.0, PI, PI);
+        let clockwise = Arc::new(point![0.0, 0.0], 1.0, PI, -PI);
+
+        assert_relative_eq!(arc.distance_to_point(&point![0.0, -3.0]), 2.0);
+        assert_relative_eq!(
+            clockwise.distance_to_point(&point![0.0, 3.0]),
+            2.0
+        );
+        assert_relative_eq!(
+            clockwise.distance_to_point(&point![0.0, -3.0]),
+            10.0f32.sqrt()
+        );
+    }
+
+    #[test]
+    pub fn interval_arcs_should_cross_the_positive_x_axis() {
+        let interval = AngleInterval::new(
+            Angle::from_degrees(350.0),
+            Angle::from_degrees(10.0),
+        );
+        let arc = Arc::from_interval(point![0.0, 0.0], 1.0, &interval);
+        let clockwise = Arc::from_angles(
+            point![0.0, 0.0],
+            1.0,
+            Angle::from_degrees(10.0),
+            Angle::from_degrees(-20.0),
+        );
+
+        for arc in [arc, clockwise] {
+            assert_relative_eq!(arc.length(), interval.sweep(), epsilon = 1e-6);
+            assert!(arc.interval().contains(Angle::from_degrees(5.0)));
+            assert!(!arc.interval().contains(Angle::from_degrees(180.0)));
+            assert_relative_eq!(arc.distance_to_point(&point![2.0, 0.0]), 1.0);
+            assert_relative_eq!(
+                arc.distance_to_point(&point![-2.0, 0.0]),
+                (point![-2.0, 0.0] - arc.point_at(0.0)).norm(),
+                epsilon = 1e-5
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn arc_distances_should_match_a_dense_sampling(
+            circle in circle(-10.0..=10.0, 0.1..=5.0),
+            start_angle in -10.0f32..10.0,
+            sweep in -7.0f32..7.0,
+            point in point2(-20.0..=20.0),
+        ) {
+            let arc = Arc::new(circle.center, circle.radius, start_angle, sweep);
+            let sampled = (0..=4096)
+                .map(|i| (arc.point_at(i as f32 / 4096.0) - point).norm())
+                .fold(f32::INFINITY, f32::min);
+
+            let distance = arc.distance_to_point(&point);
+
+            prop_assert!(distance <= sampled + 1e-3);
+            prop_assert!(distance >= sampled - arc.length() / 4096.0 - 1e-3);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use {crate::shape::Aabb, nalgebra::Point2};
+
+/// Types which implement this trait can compute an axis-aligned box which
+/// contains every one of their points.
+///
+/// The box should be as small as is practical, since spatial indexes like
+/// [`Bvh`](crate::spatial::Bvh) use it to skip primitives which are
+/// obviously too far away from a query.
+pub trait BoundingBox {
+    /// The smallest axis-aligned box which contains this object.
+    fn bounding_box(&self) -> Aabb;
+}
+
+impl BoundingBox for Point2<f32> {
+    /// A point's box has no area, with both corners at the point.
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(*self, *self)
+    }
+}
+
+impl BoundingBox for Aabb {
+    fn bounding_box(&self) -> Aabb {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            shape::BoundingBox,
+            test_support::{circle, point2},
+        },
+        nalgebra::Point2,
+        proptest::prelude::*,
+    };
+
+    #[cfg(feature = "alloc")]
+    use crate::{polygon::Polygon, test_support::polygon};
+
+    fn assert_contains(item: &impl BoundingBox, points: &[Point2<f32>]) {
+        let bounds = item.bounding_box();
+        for point in points {
+            assert!(
+                bounds.contains_point(point),
+                "{} misses {}",
+                bounds,
+                point
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    pub fn empty_polygons_should_have_boxes_which_touch_nothing() {
+        let bounds = Polygon::new(vec![]).bounding_box();
+
+        assert!(!bounds.contains_point(&Point2::origin()));
+        assert!(!bounds.intersects(&Point2::origin().bounding_box()));
+    }
+
+    proptest! {
+        #[test]
+        fn segment_boxes_should_contain_their_endpoints(
+            segment in any::<Segment>(),
+        ) {
+            assert_contains(&segment, &[segment.start, segment.end]);
+        }
+
+        #[test]
+        fn circle_boxes_should_contain_the_circle(
+            circle in circle(-100.0..=100.0, 0.0..=10.0),
+            direction in point2(-1.0..=1.0),
+        ) {
+            let direction = direction.coords.try_normalize(1e-3);
+            prop_assume!(direction.is_some());
+            let boundary =
+                circle.center + direction.unwrap() * circle.radius * 0.9999;
+
+            assert_contains(&circle, &[circle.center, boundary]);
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn polygon_boxes_should_contain_every_vertex(
+            polygon in polygon(-100.0..=100.0, 3..=8, false),
+        ) {
+            assert_contains(&polygon, &polygon.vertices);
+        }
+    }
+}
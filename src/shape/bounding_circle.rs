@@ -0,0 +1,128 @@
+use {
+    crate::shape::{Aabb, Circle},
+    nalgebra::Point2,
+};
+
+/// Types which implement this trait can compute a circle which contains
+/// every one of their points.
+///
+/// Like [`BoundingBox`](crate::shape::BoundingBox), the circle should be
+/// as small as is practical. Circles are tighter than boxes for round or
+/// compact shapes, and checking two circles for overlap doesn't depend on
+/// how either shape is rotated.
+pub trait BoundingCircle {
+    /// A small circle which contains this object.
+    fn bounding_circle(&self) -> Circle;
+}
+
+impl BoundingCircle for Point2<f32> {
+    /// A point's circle has no radius and is centered on the point.
+    fn bounding_circle(&self) -> Circle {
+        Circle::new(*self, 0.0)
+    }
+}
+
+impl BoundingCircle for Aabb {
+    /// The circle through the box's corners.
+    fn bounding_circle(&self) -> Circle {
+        Circle::new(self.center(), (self.max - self.min).norm() / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            shape::{BoundingCircle, Circle},
+            test_support::{aabb, circle, segment},
+        },
+        alloc::{boxed::Box, vec::Vec},
+        nalgebra::Point2,
+        proptest::prelude::*,
+    };
+
+    #[cfg(feature = "alloc")]
+    use crate::{
+        polygon::{Polygon, Polyline},
+        test_support::polygon,
+    };
+
+    /// Points along the straight edges between consecutive points.
+    fn along_edges(points: &[Point2<f32>]) -> Vec<Point2<f32>> {
+        points
+            .windows(2)
+            .flat_map(|pair| {
+                (0..=8).map(move |i| {
+                    pair[0] + (pair[1] - pair[0]) * (i as f32 / 8.0)
+                })
+            })
+            .collect()
+    }
+
+    fn encloses(circle: &Circle, points: &[Point2<f32>]) -> bool {
+        let tolerance =
+            1e-5 * (1.0 + circle.radius + circle.center.coords.norm());
+        points
+            .iter()
+            .all(|p| (p - circle.center).norm() <= circle.radius + tolerance)
+    }
+
+    #[test]
+    pub fn bounding_circles_should_be_usable_as_trait_objects() {
+        let shapes: Vec<Box<dyn BoundingCircle>> = vec![
+            Box::new(Point2::new(1.0, 2.0)),
+            Box::new(Segment::new(
+                Point2::new(0.0, 0.0),
+                Point2::new(0.0, 4.0),
+            )),
+            Box::new(Circle::new(Point2::new(3.0, 3.0), 0.5)),
+        ];
+
+        let radii: Vec<_> = shapes
+            .iter()
+            .map(|shape| shape.bounding_circle().radius)
+            .collect();
+
+        assert_eq!(radii, vec![0.0, 2.0, 0.5]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    pub fn empty_polygons_should_have_circles_which_contain_nothing() {
+        let circle = Polygon::new(vec![]).bounding_circle();
+
+        assert!(!circle.contains_point(&Point2::origin()));
+    }
+
+    proptest! {
+        #[test]
+        fn segment_and_box_circles_should_contain_them(
+            segment in segment(-100.0..=100.0, 0.0),
+            aabb in aabb(-100.0..=100.0),
+            circle in circle(-100.0..=100.0, 0.0..=10.0),
+        ) {
+            let points = along_edges(&[segment.start, segment.end]);
+            prop_assert!(encloses(&segment.bounding_circle(), &points));
+            let mut corners = aabb.corners().to_vec();
+            corners.push(corners[0]);
+            let boundary = along_edges(&corners);
+            prop_assert!(encloses(&aabb.bounding_circle(), &boundary));
+            prop_assert_eq!(circle.bounding_circle(), circle);
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn polygon_circles_should_contain_their_boundaries(
+            polygon in polygon(-100.0..=100.0, 3..=8, false),
+        ) {
+            let mut closed = polygon.vertices.clone();
+            closed.push(closed[0]);
+            let boundary = along_edges(&closed);
+
+            prop_assert!(encloses(&polygon.bounding_circle(), &boundary));
+            let polyline = Polyline::new(polygon.vertices.clone());
+            prop_assert!(encloses(&polyline.bounding_circle(), &boundary));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use {
+    crate::shape::{Aabb, Obb},
+    nalgebra::{Point2, Vector2},
+};
+
+/// Types which implement this trait can compute an oriented box which
+/// contains every one of their points.
+///
+/// Like [`BoundingBox`](crate::shape::BoundingBox), the box should be as
+/// small as is practical. Oriented boxes can turn to follow long, thin
+/// shapes which run diagonally, where axis-aligned boxes and circles would
+/// be mostly empty.
+pub trait BoundingObb {
+    /// A small oriented box which contains this object.
+    fn bounding_obb(&self) -> Obb;
+}
+
+impl BoundingObb for Point2<f32> {
+    /// A point's box has no area, is centered on the point, and runs along
+    /// the x axis.
+    fn bounding_obb(&self) -> Obb {
+        Obb::new(*self, Vector2::x_axis(), Vector2::zeros())
+    }
+}
+
+impl BoundingObb for Aabb {
+    /// The same box, running along the x axis.
+    fn bounding_obb(&self) -> Obb {
+        let half_extents = (self.max - self.min) / 2.0;
+        Obb::new(self.center(), Vector2::x_axis(), half_extents)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            shape::{BoundingBox, BoundingObb, Obb},
+            test_support::{aabb, circle, segment},
+        },
+        alloc::{boxed::Box, vec::Vec},
+        nalgebra::{Point2, Vector2},
+        proptest::prelude::*,
+    };
+
+    #[cfg(feature = "alloc")]
+    use crate::{
+        polygon::{Polygon, Polyline},
+        test_support::polygon,
+    };
+
+    fn encloses(obb: &Obb, points: &[Point2<f32>]) -> bool {
+        let normal = Vector2::new(-obb.axis.y, obb.axis.x);
+        let tolerance =
+            1e-5 * (1.0 + obb.half_extents.norm() + obb.center.coords.norm());
+        points.iter().all(|p| {
+            let offset = p - obb.center;
+            offset.dot(&obb.axis).abs() <= obb.half_extents.x + tolerance
+                && offset.dot(&normal).abs() <= obb.half_extents.y + tolerance
+        })
+    }
+
+    #[test]
+    pub fn bounding_obbs_should_be_usable_as_trait_objects() {
+        let shapes: Vec<Box<dyn BoundingObb>> = vec![
+            Box::new(Point2::new(1.0, 2.0)),
+            Box::new(Segment::new(
+                Point2::new(0.0, 0.0),
+                Point2::new(3.0, 4.0),
+            )),
+        ];
+
+        let areas: Vec<_> = shapes
+            .iter()
+            .map(|shape| shape.bounding_obb().area())
+            .collect();
+
+        assert_eq!(areas, vec![0.0, 0.0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    pub fn empty_polygons_should_have_obbs_which_contain_nothing() {
+        let obb = Polygon::new(vec![]).bounding_obb();
+
+        assert!(!obb.contains_point(&Point2::origin()));
+    }
+
+    proptest! {
+        #[test]
+        fn simple_shape_obbs_should_contain_them(
+            segment in segment(-100.0..=100.0, 0.0),
+            aabb in aabb(-100.0..=100.0),
+            circle in circle(-100.0..=100.0, 0.0..=10.0),
+        ) {
+            let ends = [segment.start, segment.end, segment.centroid()];
+            prop_assert!(encloses(&segment.bounding_obb(), &ends));
+            prop_assert!(encloses(&aabb.bounding_obb(), &aabb.corners()));
+            let obb = circle.bounding_obb();
+            prop_assert_eq!(obb.bounding_box(), circle.bounding_box());
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn polygon_obbs_should_contain_them_and_beat_their_boxes(
+            polygon in polygon(-100.0..=100.0, 3..=8, false),
+        ) {
+            let obb = polygon.bounding_obb();
+
+            prop_assert!(encloses(&obb, &polygon.vertices));
+            let aabb = polygon.bounding_box();
+            let area = aabb.width() * aabb.height();
+            prop_assert!(obb.area() <= area * (1.0 + 1e-4));
+            let polyline = Polyline::new(polygon.vertices.clone());
+            prop_assert_eq!(polyline.bounding_obb(), obb);
+        }
+    }
+}
@@ -0,0 +1,814 @@
+use {
+    crate::{
+        display::{write_compact_debug, write_point, write_scalar},
+        error::check_finite,
+        line::{DistanceToPoint, Line},
+        math,
+        shape::{
+            Aabb, BoundingBox, BoundingCircle, BoundingObb, Obb, SupportMap,
+        },
+        Error,
+    },
+    core::{f32::consts::PI, fmt},
+    nalgebra::{Point2, Unit, Vector2},
+};
+#[cfg(feature = "alloc")]
+use {
+    crate::{
+        polygon::Polygon,
+        random::SplitMix64,
+        shape::arc::{point_on_circle, segments_for_sweep},
+    },
+    alloc::vec::Vec,
+    core::f32::consts::TAU,
+};
+
+/// A circle defined by its center and radius.
+///
+/// Circles are displayed as an equation, like `|p - (1, -2)| = 3.5`.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Circle {
+    /// The point in the middle of the circle.
+    pub center: Point2<f32>,
+
+    /// The distance from the center to every point on the circle.
+    pub radius: f32,
+}
+
+impl Circle {
+    /// Create a new circle.
+    ///
+    /// The values aren't validated. Use [`Circle::try_new`] to reject
+    /// negative radii and non-finite values.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Circle,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let unit_circle = Circle::new(point![0.0, 0.0], 1.0);
+    ///
+    pub fn new(center: Point2<f32>, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Create a new circle, checking that the radius isn't negative and that
+    /// every value is finite.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{shape::Circle, Error},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     assert_eq!(
+    ///         Circle::try_new(point![0.0, 0.0], -1.0),
+    ///         Err(Error::NegativeRadius)
+    ///     );
+    ///
+    pub fn try_new(center: Point2<f32>, radius: f32) -> Result<Self, Error> {
+        check_finite(&[center.x, center.y, radius])?;
+        if radius < 0.0 {
+            return Err(Error::NegativeRadius);
+        }
+        Ok(Self::new(center, radius))
+    }
+
+    /// The circle which passes through three points.
+    ///
+    /// Returns `None` when the points are collinear, including when any two
+    /// of them are the same point.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Circle,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let circle = Circle::circumscribe(
+    ///         &point![0.0, 0.0],
+    ///         &point![4.0, 0.0],
+    ///         &point![0.0, 2.0],
+    ///     )
+    ///     .unwrap();
+    ///
+    ///     assert_relative_eq!(circle.center, point![2.0, 1.0]);
+    ///     assert_relative_eq!(circle.radius, 5.0f32.sqrt());
+    ///
+    pub fn circumscribe(
+        a: &Point2<f32>,
+        b: &Point2<f32>,
+        c: &Point2<f32>,
+    ) -> Option<Self> {
+        // Solve relative to `a` in f64 to limit cancellation for nearly
+        // collinear points.
+        let (bx, by) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+        let (cx, cy) = (c.x as f64 - a.x as f64, c.y as f64 - a.y as f64);
+        let d = 2.0 * (bx * cy - by * cx);
+        if d == 0.0 {
+            return None;
+        }
+        let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+        let x = (cy * b2 - by * c2) / d;
+        let y = (bx * c2 - cx * b2) / d;
+        let center =
+            Point2::new((a.x as f64 + x) as f32, (a.y as f64 + y) as f32);
+        let radius = math::sqrt((x * x + y * y) as f32);
+        (center.x.is_finite() && center.y.is_finite())
+            .then(|| Self::new(center, radius))
+    }
+
+    /// A circle which contains nothing, with a NaN radius.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn empty() -> Circle {
+        Circle::new(Point2::origin(), f32::NAN)
+    }
+
+    /// The smallest circle which contains every point.
+    ///
+    /// Uses Welzl's algorithm, which takes expected O(n) time, with the
+    /// points shuffled in the same order on every call so the result is
+    /// repeatable. The radius is then grown to the furthest point from the
+    /// center, so every point is inside the circle despite rounding.
+    /// Returns `None` when there are no points.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Circle,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let circle = Circle::enclosing(&[
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![2.0, 1.0],
+    ///     ])
+    ///     .unwrap();
+    ///
+    ///     // The two furthest points are on opposite sides.
+    ///     assert_relative_eq!(circle.center, point![2.0, 0.0]);
+    ///     assert_relative_eq!(circle.radius, 2.0);
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn enclosing(points: &[Point2<f32>]) -> Option<Self> {
+        let mut points = points.to_vec();
+        SplitMix64(points.len() as u64).shuffle(&mut points);
+        let diameter = |a: &Point2<f32>, b: &Point2<f32>| {
+            Self::new(nalgebra::center(a, b), (b - a).norm() / 2.0)
+        };
+        // Allow for rounding when checking points against circles through
+        // other points.
+        let outside = |circle: &Self, point: &Point2<f32>| {
+            (point - circle.center).norm() > circle.radius * (1.0 + 1e-6)
+        };
+
+        let mut circle = Self::new(*points.first()?, 0.0);
+        for i in 1..points.len() {
+            if !outside(&circle, &points[i]) {
+                continue;
+            }
+            circle = Self::new(points[i], 0.0);
+            for j in 0..i {
+                if !outside(&circle, &points[j]) {
+                    continue;
+                }
+                circle = diameter(&points[i], &points[j]);
+                for k in 0..j {
+                    if outside(&circle, &points[k]) {
+                        circle = Self::circumscribe(
+                            &points[i], &points[j], &points[k],
+                        )
+                        .unwrap_or_else(|| {
+                            // Nearly collinear points are spanned by their
+                            // furthest pair.
+                            [(i, k), (j, k)]
+                                .map(|(a, b)| diameter(&points[a], &points[b]))
+                                .into_iter()
+                                .fold(circle, |a, b| {
+                                    if b.radius > a.radius {
+                                        b
+                                    } else {
+                                        a
+                                    }
+                                })
+                        });
+                    }
+                }
+            }
+        }
+        circle.radius = points
+            .iter()
+            .map(|point| (point - circle.center).norm())
+            .fold(circle.radius, f32::max);
+        Some(circle)
+    }
+
+    /// Returns true when the point is inside the circle or on its boundary.
+    pub fn contains_point(&self, point: &Point2<f32>) -> bool {
+        (point - self.center).norm_squared() <= self.radius * self.radius
+    }
+
+    /// The area of the part of the circle on the negative side of a line,
+    /// where [`DistanceToPoint::distance_to_point`] is negative.
+    ///
+    /// This is a circular segment, the region between a chord and the arc
+    /// it cuts off, computed exactly rather than from a polygon. It's the
+    /// whole area when the line passes beyond the circle on the positive
+    /// side, and zero when it passes beyond on the negative side.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Line, shape::Circle},
+    ///         nalgebra::{point, vector},
+    ///         approx::assert_relative_eq,
+    ///         core::f32::consts::PI,
+    ///     };
+    ///
+    ///     let circle = Circle::new(point![0.0, 0.0], 2.0);
+    ///     let through_center = Line::try_new(vector![0.0, 1.0], 0.0).unwrap();
+    ///     let above = Line::try_new(vector![0.0, 1.0], -5.0).unwrap();
+    ///
+    ///     assert_relative_eq!(circle.segment_area(&through_center), 2.0 * PI);
+    ///     assert_relative_eq!(circle.segment_area(&above), 4.0 * PI);
+    ///
+    pub fn segment_area(&self, line: &Line) -> f32 {
+        let distance = line.distance_to_point(&self.center);
+        cap_area(self.radius as f64, distance as f64) as f32
+    }
+
+    /// Approximate the circle with a polygon whose edges stay within
+    /// `max_error` of the circle.
+    ///
+    /// The number of edges is chosen from the sagitta of each edge, the
+    /// largest distance between the edge and the circle, with at least 8
+    /// edges. Errors too small for f32 to resolve are clamped. The polygon
+    /// is inscribed in the circle, counterclockwise, and starts at
+    /// `center + (radius, 0)`.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Circle,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let circle = Circle::new(point![1.0, 1.0], 10.0);
+    ///
+    ///     assert_eq!(circle.to_polygon(10.0).vertices.len(), 8);
+    ///     assert_eq!(circle.to_polygon(0.01).vertices.len(), 71);
+    ///     assert_eq!(circle.to_polygon(0.01).vertices[0], point![11.0, 1.0]);
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn to_polygon(&self, max_error: f32) -> Polygon {
+        self.to_polygon_n(segments_for_sweep(self.radius, TAU, max_error))
+    }
+
+    /// Approximate the circle with a regular polygon of `n` edges, which is
+    /// clamped to at least three.
+    ///
+    /// The polygon is inscribed in the circle, counterclockwise, and starts
+    /// at `center + (radius, 0)`.
+    #[cfg(feature = "alloc")]
+    pub fn to_polygon_n(&self, n: usize) -> Polygon {
+        let n = n.max(3);
+        Polygon::new(
+            (0..n)
+                .map(|i| {
+                    let angle = i as f32 * TAU / n as f32;
+                    point_on_circle(&self.center, self.radius, angle)
+                })
+                .collect(),
+        )
+    }
+
+    /// Sample points evenly around the circle, each paired with the outward
+    /// unit normal there.
+    ///
+    /// The samples are at most `spacing` apart, counterclockwise from
+    /// `center + (radius, 0)`, and their normals point straight away from
+    /// the center. Returns no samples when the spacing isn't positive.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Circle,
+    ///         nalgebra::{point, vector},
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let circle = Circle::new(point![1.0, 1.0], 2.0);
+    ///
+    ///     let samples = circle.boundary_samples(1.0);
+    ///
+    ///     assert_eq!(samples.len(), 13);
+    ///     assert_eq!(samples[0].0, point![3.0, 1.0]);
+    ///     assert_eq!(samples[0].1.into_inner(), vector![1.0, 0.0]);
+    ///     for (point, normal) in &samples {
+    ///         let along_normal = point![1.0, 1.0] + normal.into_inner() * 2.0;
+    ///         assert_relative_eq!(along_normal, point);
+    ///     }
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn boundary_samples(
+        &self,
+        spacing: f32,
+    ) -> Vec<(Point2<f32>, Unit<Vector2<f32>>)> {
+        if spacing.is_nan() || spacing <= 0.0 {
+            return Vec::new();
+        }
+        let circumference = TAU * self.radius.abs();
+        let n = math::ceil(circumference / spacing).max(1.0) as usize;
+        (0..n)
+            .map(|i| {
+                let angle = i as f32 * TAU / n as f32;
+                let (sin, cos) = math::sin_cos(angle);
+                (
+                    point_on_circle(&self.center, self.radius, angle),
+                    Unit::new_unchecked(Vector2::new(cos, sin)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The area of the lens where two circles overlap, computed exactly.
+///
+/// Circles which are apart or only touch have no overlap, and a circle
+/// inside the other overlaps with its whole area, including concentric
+/// circles.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::shape::{circle_circle_intersection_area, Circle},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///         core::f32::consts::PI,
+///     };
+///
+///     let a = Circle::new(point![0.0, 0.0], 1.0);
+///     let b = Circle::new(point![1.0, 0.0], 1.0);
+///
+///     let lens = circle_circle_intersection_area(&a, &b);
+///
+///     assert_relative_eq!(lens, 2.0 * PI / 3.0 - 0.75f32.sqrt());
+///
+pub fn circle_circle_intersection_area(a: &Circle, b: &Circle) -> f32 {
+    let (ra, rb) = (a.radius.max(0.0), b.radius.max(0.0));
+    let d = (b.center - a.center).norm();
+    if d >= ra + rb {
+        return 0.0;
+    }
+    if d <= (ra - rb).abs() {
+        let r = ra.min(rb);
+        return PI * r * r;
+    }
+    // The chord through both crossings splits the lens into a cap of each
+    // circle.
+    let (ra, rb, d) = (ra as f64, rb as f64, d as f64);
+    let da = (d * d + ra * ra - rb * rb) / (2.0 * d);
+    (cap_area(ra, da) + cap_area(rb, d - da)) as f32
+}
+
+/// The area where a circle and a polygon overlap, computed exactly.
+///
+/// The overlap is found with Green's theorem, adding up the signed area
+/// where the circle overlaps the triangle between its center and each
+/// edge. Each edge is cut where it crosses the circle, and pieces inside
+/// the circle add a triangle while pieces outside add a sector, so edges
+/// which are inside, outside, or cross the circle once or twice are all
+/// handled the same way. The polygon should be simple, and can wind either
+/// way.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             polygon::Polygon,
+///             shape::{circle_polygon_intersection_area, Circle},
+///         },
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///         core::f32::consts::PI,
+///     };
+///
+///     // A quarter of the circle is inside the square.
+///     let circle = Circle::new(point![0.0, 0.0], 1.0);
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![2.0, 0.0],
+///         point![2.0, 2.0],
+///         point![0.0, 2.0],
+///     ]);
+///
+///     let overlap = circle_polygon_intersection_area(&circle, &square);
+///
+///     assert_relative_eq!(overlap, PI / 4.0, epsilon = 1e-6);
+///
+#[cfg(feature = "alloc")]
+pub fn circle_polygon_intersection_area(
+    circle: &Circle,
+    polygon: &Polygon,
+) -> f32 {
+    let r = circle.radius.max(0.0);
+    let signed: f32 = polygon
+        .edges()
+        .map(|edge| {
+            let a = edge.start - circle.center;
+            let b = edge.end - circle.center;
+            wedge_overlap(r, a, b)
+        })
+        .sum();
+    signed.abs()
+}
+
+/// The signed area where a circle at the origin overlaps the triangle
+/// between the origin and the points `a` and `b`.
+#[cfg(feature = "alloc")]
+fn wedge_overlap(r: f32, a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    // Solve |a + t (b - a)| = r for the parameters where the edge crosses
+    // the circle.
+    let along = b - a;
+    let qa = along.norm_squared();
+    let mut cuts = [0.0, 0.0, 0.0, 1.0];
+    let mut count = 1;
+    if qa > 0.0 {
+        let qb = a.dot(&along);
+        let qc = a.norm_squared() - r * r;
+        let discriminant = qb * qb - qa * qc;
+        if discriminant > 0.0 {
+            let root = math::sqrt(discriminant);
+            for t in [(-qb - root) / qa, (-qb + root) / qa] {
+                if t > 0.0 && t < 1.0 {
+                    cuts[count] = t;
+                    count += 1;
+                }
+            }
+        }
+    }
+    cuts[count] = 1.0;
+
+    let point = |t: f32| a + along * t;
+    cuts[..=count]
+        .windows(2)
+        .map(|pair| {
+            let (p, q) = (point(pair[0]), point(pair[1]));
+            let middle = point((pair[0] + pair[1]) / 2.0);
+            // Tangent edges only touch the circle, so they're outside.
+            if middle.norm_squared() < r * r {
+                p.perp(&q) / 2.0
+            } else {
+                r * r * math::atan2(p.perp(&q), p.dot(&q)) / 2.0
+            }
+        })
+        .sum()
+}
+
+/// The area of the part of a circle more than `distance` from its center
+/// along some direction, which is the whole circle for distances of
+/// `-radius` or less and nothing for distances of `radius` or more.
+///
+/// Thin caps subtract nearly equal terms, so this works in double
+/// precision.
+fn cap_area(radius: f64, distance: f64) -> f64 {
+    let r = radius.max(0.0);
+    if distance >= r {
+        return 0.0;
+    }
+    if distance <= -r {
+        return core::f64::consts::PI * r * r;
+    }
+    let half_chord = math::sqrt_f64((r * r - distance * distance).max(0.0));
+    let angle = math::acos_f64((distance / r).clamp(-1.0, 1.0));
+    r * r * angle - distance * half_chord
+}
+
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("|p - ")?;
+        write_point(f, &self.center)?;
+        f.write_str("| = ")?;
+        write_scalar(f, self.radius)
+    }
+}
+
+impl fmt::Debug for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write_compact_debug(f, "Circle", self);
+        }
+        f.debug_struct("Circle")
+            .field("center", &self.center)
+            .field("radius", &self.radius)
+            .finish()
+    }
+}
+
+impl DistanceToPoint for Circle {
+    /// Compute the signed distance from the circle's boundary to a point.
+    ///
+    /// The distance is negative for points inside the circle and positive
+    /// for points outside it.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::DistanceToPoint, shape::Circle},
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     let circle = Circle::new(point![1.0, 1.0], 2.0);
+    ///
+    ///     assert_relative_eq!(circle.distance_to_point(&point![1.0, 4.0]), 1.0);
+    ///     assert_relative_eq!(circle.distance_to_point(&point![1.0, 1.5]), -1.5);
+    ///
+    fn distance_to_point(&self, point: &Point2<f32>) -> f32 {
+        (point - self.center).norm() - self.radius
+    }
+
+    /// The squared distance from the circle's boundary to a point.
+    ///
+    /// The value is always positive, so compare against
+    /// [`Circle::contains_point`] to tell inside from outside.
+    fn distance_to_point_squared(&self, point: &Point2<f32>) -> f32 {
+        let distance = self.distance_to_point(point);
+        distance * distance
+    }
+}
+
+impl BoundingBox for Circle {
+    fn bounding_box(&self) -> Aabb {
+        let half = Vector2::repeat(self.radius);
+        Aabb::new(self.center - half, self.center + half)
+    }
+}
+
+impl BoundingCircle for Circle {
+    /// The circle itself.
+    fn bounding_circle(&self) -> Circle {
+        *self
+    }
+}
+
+impl BoundingObb for Circle {
+    /// The square around the circle, running along the x axis.
+    fn bounding_obb(&self) -> Obb {
+        let half_extents = Vector2::repeat(self.radius);
+        Obb::new(self.center, Vector2::x_axis(), half_extents)
+    }
+}
+
+impl SupportMap for Circle {
+    fn support_point(&self, direction: &Unit<Vector2<f32>>) -> Point2<f32> {
+        self.center + direction.scale(self.radius)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Line,
+            polygon::{intersect_convex, Polygon},
+            shape::{
+                circle_circle_intersection_area,
+                circle_polygon_intersection_area, Circle,
+            },
+            test_support::{circle, line, point2, polygon},
+        },
+        approx::assert_relative_eq,
+        core::f32::consts::PI,
+        nalgebra::{point, Point2, Vector2},
+        proptest::prelude::*,
+    };
+
+    /// The overlap of two convex polygons, where a circle is approximated
+    /// closely enough for exact areas to match within a relative 1e-3.
+    fn overlap(a: &Polygon, b: &Polygon) -> f32 {
+        intersect_convex(a, b).map_or(0.0, |overlap| overlap.area())
+    }
+
+    fn fine(circle: &Circle) -> Polygon {
+        circle.to_polygon_n(4096)
+    }
+
+    #[test]
+    pub fn polygons_should_start_on_the_x_axis_and_wind_counterclockwise() {
+        let circle = Circle::new(point![-3.0, 2.0], 0.7);
+
+        for polygon in [circle.to_polygon(0.01), circle.to_polygon_n(5)] {
+            assert_eq!(polygon.vertices[0], point![-2.3, 2.0]);
+            assert!(polygon.signed_area() > 0.0);
+        }
+        assert_eq!(circle.to_polygon_n(1).vertices.len(), 3);
+        assert_eq!(circle.to_polygon(f32::NAN).vertices.len(), 8192);
+    }
+
+    #[test]
+    pub fn polygon_areas_should_converge_on_the_circle() {
+        let circle = Circle::new(point![0.0, 0.0], 2.0);
+        let exact = PI * 4.0;
+
+        let mut previous = f32::INFINITY;
+        for max_error in [0.5, 0.1, 0.01, 0.001, 0.0001] {
+            let difference = exact - circle.to_polygon(max_error).area();
+
+            assert!(difference >= 0.0);
+            assert!(difference < previous);
+            previous = difference;
+        }
+        assert!(previous < 1e-3);
+    }
+
+    #[test]
+    pub fn boundary_samples_should_be_spaced_around_the_circle() {
+        let circle = Circle::new(point![-1.0, 2.0], 3.0);
+
+        let samples = circle.boundary_samples(0.4);
+
+        assert_eq!(samples.len(), 48);
+        for pair in samples.windows(2) {
+            assert!((pair[1].0 - pair[0].0).norm() <= 0.4);
+            let turn = pair[0].1.angle(&pair[1].1);
+            assert_relative_eq!(turn, 2.0 * PI / 48.0, epsilon = 1e-4);
+        }
+        assert!(circle.boundary_samples(-1.0).is_empty());
+        assert_eq!(circle.boundary_samples(f32::INFINITY).len(), 1);
+    }
+
+    #[test]
+    pub fn touching_and_concentric_circles_should_have_exact_overlaps() {
+        let unit = Circle::new(point![0.0, 0.0], 1.0);
+        let touching = Circle::new(point![3.0, 0.0], 2.0);
+        let inner = Circle::new(point![0.0, 0.0], 0.5);
+        let tangent = Line::try_new(Vector2::new(1.0, 0.0), -1.0).unwrap();
+        let square = Polygon::new(vec![
+            point![1.0, -1.0],
+            point![3.0, -1.0],
+            point![3.0, 1.0],
+            point![1.0, 1.0],
+        ]);
+
+        assert_eq!(circle_circle_intersection_area(&unit, &touching), 0.0);
+        assert_eq!(circle_circle_intersection_area(&unit, &inner), PI / 4.0);
+        assert_eq!(circle_circle_intersection_area(&unit, &unit), PI);
+        assert_eq!(unit.segment_area(&tangent), PI);
+        assert_relative_eq!(
+            circle_polygon_intersection_area(&unit, &square),
+            0.0,
+            epsilon = 1e-6
+        );
+        // The circle's center is on the grown square's edge.
+        assert_relative_eq!(
+            circle_polygon_intersection_area(&inner, &square.offset(1.0)),
+            PI / 8.0,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    pub fn enclosing_circles_should_use_the_longest_side_of_obtuse_triangles() {
+        let points = [point![0.0, 0.0], point![4.0, 0.0], point![1.0, 1.0]];
+
+        let circle = Circle::enclosing(&points).unwrap();
+
+        assert_relative_eq!(circle.center, point![2.0, 0.0], epsilon = 1e-5);
+        assert_relative_eq!(circle.radius, 2.0, epsilon = 1e-5);
+        assert!(Circle::enclosing(&[]).is_none());
+        let single = Circle::enclosing(&[point![1.0, 2.0]]).unwrap();
+        assert_eq!(single, Circle::new(point![1.0, 2.0], 0.0));
+    }
+
+    proptest! {
+        #[test]
+        fn segment_areas_should_match_clipped_polygons(
+            circle in circle(-5.0..=5.0, 0.5..=5.0),
+            line in line(-5.0..=5.0),
+        ) {
+            // A big box on the negative side of the line.
+            let origin = Point2::from(line.normal.into_inner() * -line.c);
+            let along = Vector2::new(line.normal.y, -line.normal.x) * 100.0;
+            let behind = line.normal.into_inner() * -100.0;
+            let half_plane = Polygon::new(vec![
+                origin + along,
+                origin - along,
+                origin - along + behind,
+                origin + along + behind,
+            ]);
+
+            let exact = circle.segment_area(&line);
+
+            let approximate = overlap(&fine(&circle), &half_plane);
+            let scale = circle.radius * circle.radius;
+            assert_relative_eq!(
+                exact, approximate, max_relative = 1e-3, epsilon = scale * 1e-3
+            );
+        }
+
+        #[test]
+        fn lens_areas_should_match_clipped_polygons(
+            a in circle(-5.0..=5.0, 0.5..=5.0),
+            b in circle(-5.0..=5.0, 0.5..=5.0),
+        ) {
+            let exact = circle_circle_intersection_area(&a, &b);
+
+            let approximate = overlap(&fine(&a), &fine(&b));
+            let scale = a.radius.min(b.radius).powi(2);
+            assert_relative_eq!(
+                exact, approximate, max_relative = 1e-3, epsilon = scale * 1e-3
+            );
+            let swapped = circle_circle_intersection_area(&b, &a);
+            assert_relative_eq!(exact, swapped, max_relative = 1e-5);
+        }
+
+        #[test]
+        fn polygon_overlaps_should_match_clipped_polygons(
+            circle in circle(-5.0..=5.0, 0.5..=5.0),
+            polygon in polygon(-5.0..=5.0, 3..=8, true),
+        ) {
+            let mut reversed = polygon.clone();
+            reversed.vertices.reverse();
+
+            let exact = circle_polygon_intersection_area(&circle, &polygon);
+
+            let approximate = overlap(&fine(&circle), &polygon);
+            let scale = circle.radius * circle.radius;
+            assert_relative_eq!(
+                exact, approximate, max_relative = 1e-3, epsilon = scale * 1e-3
+            );
+            assert_relative_eq!(
+                exact,
+                circle_polygon_intersection_area(&circle, &reversed),
+                max_relative = 1e-5,
+                epsilon = scale * 1e-5
+            );
+        }
+
+        #[test]
+        fn polygon_edges_should_stay_within_the_error(
+            radius in 0.01f32..100.0,
+            max_error in 0.001f32..10.0,
+        ) {
+            let circle = Circle::new(point![3.0, -4.0], radius);
+
+            let polygon = circle.to_polygon(max_error);
+
+            prop_assert!(polygon.vertices.len() >= 8);
+            for edge in polygon.edges() {
+                let middle = nalgebra::center(&edge.start, &edge.end);
+                let deviation = radius - (middle - circle.center).norm();
+                prop_assert!(deviation <= max_error + radius * 1e-5);
+            }
+            assert_relative_eq!(
+                polygon.vertices[0],
+                point![3.0 + radius, -4.0],
+            );
+        }
+
+        #[test]
+        fn enclosing_circles_should_be_the_smallest_which_contain_the_points(
+            points in prop::collection::vec(point2(-100.0..=100.0), 1..12),
+        ) {
+            let circle = Circle::enclosing(&points).unwrap();
+
+            let tolerance = 1e-4 * (1.0 + circle.radius);
+            let encloses = |candidate: &Circle| {
+                points.iter().all(|p| {
+                    let distance = (p - candidate.center).norm();
+                    distance <= candidate.radius + tolerance
+                })
+            };
+            prop_assert!(encloses(&circle));
+            // The smallest circle passes through two or three of the points,
+            // so no circle like that which contains them all is smaller.
+            for (i, a) in points.iter().enumerate() {
+                for (j, b) in points.iter().enumerate().skip(i + 1) {
+                    let middle = nalgebra::center(a, b);
+                    let diameter = Circle::new(middle, (b - a).norm() / 2.0);
+                    if encloses(&diameter) {
+                        let limit = diameter.radius + tolerance;
+                        prop_assert!(circle.radius <= limit);
+                    }
+                    for c in &points[j + 1..] {
+                        let Some(through) = Circle::circumscribe(a, b, c) else {
+                            continue;
+                        };
+                        if encloses(&through) {
+                            let limit = through.radius + tolerance;
+                            prop_assert!(circle.radius <= limit);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
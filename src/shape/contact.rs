@@ -0,0 +1,398 @@
+//! Contact points between circles and the segments and polygons they roll
+//! against.
+
+use {
+    crate::{line::Segment, operations::perp_vec2d, shape::Circle},
+    nalgebra::{Point2, Unit, Vector2},
+};
+#[cfg(feature = "alloc")]
+use {
+    crate::{math, polygon::Polygon},
+    alloc::vec::Vec,
+};
+
+/// Where a circle touches another shape, and how far it has sunk in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Contact {
+    /// The point on the other shape which is closest to the circle's
+    /// center.
+    pub point: Point2<f32>,
+
+    /// The direction to push the circle to separate it from the other
+    /// shape.
+    pub normal: Unit<Vector2<f32>>,
+
+    /// How far the circle must move along the normal to only just touch
+    /// the other shape. This is zero for a circle which is resting on it.
+    pub depth: f32,
+}
+
+/// Find where a circle touches a segment.
+///
+/// The contact point is the point on the segment closest to the circle's
+/// center and the normal points from there to the center, so circles
+/// touching the segment's interior are pushed straight off it and circles
+/// touching an end are pushed radially away from that end. Returns `None`
+/// when the circle doesn't reach the segment. A circle which just touches
+/// the segment has a depth of zero.
+///
+/// When the center is exactly on the segment there's no direction from
+/// the segment to the center, so the normal is the segment's left normal
+/// in the crate's y-up convention. When the segment also has no length the
+/// normal is the positive y axis.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::Segment,
+///             shape::{contact_circle_segment, Circle},
+///         },
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let floor = Segment::new(point![0.0, 0.0], point![10.0, 0.0]);
+///     let ball = Circle::new(point![4.0, 0.5], 1.0);
+///
+///     let contact = contact_circle_segment(&ball, &floor).unwrap();
+///
+///     assert_relative_eq!(contact.point, point![4.0, 0.0]);
+///     assert_relative_eq!(contact.normal.into_inner(), vector![0.0, 1.0]);
+///     assert_relative_eq!(contact.depth, 0.5);
+///
+pub fn contact_circle_segment(
+    circle: &Circle,
+    segment: &Segment,
+) -> Option<Contact> {
+    let point = segment.closest_point(&circle.center);
+    let offset = circle.center - point;
+    let distance = offset.norm();
+    let depth = circle.radius - distance;
+    let touching = depth >= 0.0;
+    if !touching {
+        return None;
+    }
+    let normal = if distance > 0.0 {
+        Unit::new_unchecked(offset / distance)
+    } else {
+        Unit::try_new(perp_vec2d(&(segment.end - segment.start)), 0.0)
+            .unwrap_or_else(Vector2::y_axis)
+    };
+    Some(Contact {
+        point,
+        normal,
+        depth,
+    })
+}
+
+/// Find the deepest contact between a circle and a filled polygon.
+///
+/// The contact point is the point on the polygon's boundary closest to the
+/// circle's center, and the normal points out of the polygon. When the
+/// center is outside this matches [`contact_circle_segment`] for the
+/// nearest edge. When the center is inside the polygon the normal points
+/// from the center to the boundary instead, and the depth includes the
+/// distance from the center to the boundary, so moving the circle by the
+/// depth along the normal always leaves it just touching the polygon.
+///
+/// Returns `None` when the circle doesn't reach the polygon or the polygon
+/// has no vertices.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             polygon::Polygon,
+///             shape::{contact_circle_polygon, Circle},
+///         },
+///         nalgebra::{point, vector},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///         point![0.0, 4.0],
+///     ]);
+///     let ball = Circle::new(point![3.5, 2.0], 1.0);
+///
+///     let contact = contact_circle_polygon(&ball, &square).unwrap();
+///
+///     assert_relative_eq!(contact.point, point![4.0, 2.0]);
+///     assert_relative_eq!(contact.normal.into_inner(), vector![1.0, 0.0]);
+///     assert_relative_eq!(contact.depth, 1.5);
+///
+#[cfg(feature = "alloc")]
+pub fn contact_circle_polygon(
+    circle: &Circle,
+    polygon: &Polygon,
+) -> Option<Contact> {
+    let (edge, point, distance_squared) = polygon
+        .edges()
+        .map(|edge| {
+            let point = edge.closest_point(&circle.center);
+            (edge, point, (circle.center - point).norm_squared())
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))?;
+    let distance = math::sqrt(distance_squared);
+
+    let inside = polygon.contains_point(&circle.center);
+    let depth = if inside {
+        circle.radius + distance
+    } else {
+        circle.radius - distance
+    };
+    let touching = depth >= 0.0;
+    if !touching {
+        return None;
+    }
+    let normal = if distance > 0.0 {
+        let outward = (circle.center - point) / distance;
+        Unit::new_unchecked(if inside { -outward } else { outward })
+    } else {
+        // The center is on the boundary, so push it out through the edge.
+        let left = perp_vec2d(&(edge.end - edge.start));
+        let outward = if polygon.signed_area() > 0.0 {
+            -left
+        } else {
+            left
+        };
+        Unit::try_new(outward, 0.0).unwrap_or_else(Vector2::y_axis)
+    };
+    Some(Contact {
+        point,
+        normal,
+        depth,
+    })
+}
+
+/// Find every edge and corner of a polygon's boundary which a circle
+/// touches.
+///
+/// Each contact is from [`contact_circle_segment`] for one of the
+/// polygon's edges, so normals point from the boundary toward the circle's
+/// center. A corner is only reported once, and only when the center is
+/// beyond the ends of both edges which meet there. When the center is
+/// closer to the middle of one of those edges, that edge's contact is
+/// reported instead, which keeps a circle rolling along a polygon from
+/// catching on the corners between its edges.
+///
+/// Edges are listed in order, so each corner appears in place of the edge
+/// that starts there. A circle which is entirely inside the polygon doesn't
+/// touch its boundary; use [`contact_circle_polygon`] to push it out.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             polygon::Polygon,
+///             shape::{contacts_circle_polygon, Circle},
+///         },
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let square = Polygon::new(vec![
+///         point![0.0, 0.0],
+///         point![4.0, 0.0],
+///         point![4.0, 4.0],
+///         point![0.0, 4.0],
+///     ]);
+///
+///     // In the corner of a square room, touching two walls.
+///     let ball = Circle::new(point![3.5, 3.5], 1.0);
+///     let contacts = contacts_circle_polygon(&ball, &square);
+///
+///     assert_eq!(contacts.len(), 2);
+///     assert_relative_eq!(contacts[0].point, point![4.0, 3.5]);
+///     assert_relative_eq!(contacts[1].point, point![3.5, 4.0]);
+///
+///     // Just beyond a corner, touching only the corner itself.
+///     let ball = Circle::new(point![4.5, 4.5], 1.0);
+///     let contacts = contacts_circle_polygon(&ball, &square);
+///
+///     assert_eq!(contacts.len(), 1);
+///     assert_relative_eq!(contacts[0].point, point![4.0, 4.0]);
+///
+#[cfg(feature = "alloc")]
+pub fn contacts_circle_polygon(
+    circle: &Circle,
+    polygon: &Polygon,
+) -> Vec<Contact> {
+    let edges: Vec<Segment> = polygon.edges().collect();
+    let n = edges.len();
+    // Whether the circle's center projects past the start or end of each
+    // edge, in which case its closest point is that corner.
+    let beyond = |edge: &Segment| {
+        let direction = edge.end - edge.start;
+        let along = (circle.center - edge.start).dot(&direction);
+        (along <= 0.0, along >= direction.norm_squared())
+    };
+
+    let mut contacts = Vec::new();
+    for (i, edge) in edges.iter().enumerate() {
+        let Some(contact) = contact_circle_segment(circle, edge) else {
+            continue;
+        };
+        let (before_start, after_end) = beyond(edge);
+        if after_end && !before_start {
+            // The corner at the end is reported with the next edge.
+            continue;
+        }
+        if before_start {
+            let (_, previous_after_end) = beyond(&edges[(i + n - 1) % n]);
+            if !previous_after_end {
+                // The previous edge is closer, so this isn't a real
+                // corner contact.
+                continue;
+            }
+        }
+        contacts.push(contact);
+    }
+    contacts
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{
+            contact_circle_polygon, contact_circle_segment,
+            contacts_circle_polygon,
+        },
+        crate::{
+            line::{DistanceToPoint, Segment},
+            polygon::Polygon,
+            shape::Circle,
+            test_support::{circle, polygon, segment},
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn circles_resting_on_a_floor_should_touch_with_no_depth() {
+        let floor = Segment::new(point![-5.0, 0.0], point![5.0, 0.0]);
+        let ball = Circle::new(point![1.0, 1.0], 1.0);
+
+        let contact = contact_circle_segment(&ball, &floor).unwrap();
+
+        assert_eq!(contact.point, point![1.0, 0.0]);
+        assert_eq!(contact.depth, 0.0);
+        assert_eq!(contact.normal.into_inner(), vector![0.0, 1.0]);
+        let lifted = Circle::new(point![1.0, 1.001], 1.0);
+        assert!(contact_circle_segment(&lifted, &floor).is_none());
+    }
+
+    #[test]
+    pub fn corners_should_push_radially() {
+        let floor = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+        let ball = Circle::new(point![5.0, 1.0], 2.0);
+
+        let contact = contact_circle_segment(&ball, &floor).unwrap();
+
+        assert_eq!(contact.point, point![4.0, 0.0]);
+        let diagonal = vector![1.0, 1.0].normalize();
+        assert_relative_eq!(contact.normal.into_inner(), diagonal);
+        assert_relative_eq!(contact.depth, 2.0 - 2.0f32.sqrt());
+    }
+
+    #[test]
+    pub fn centers_on_the_segment_should_use_a_fallback_normal() {
+        let floor = Segment::new(point![0.0, 0.0], point![4.0, 0.0]);
+        let ceiling = Segment::new(point![4.0, 2.0], point![0.0, 2.0]);
+        let dot = Segment::new(point![1.0, 1.0], point![1.0, 1.0]);
+
+        let on_floor = Circle::new(point![2.0, 0.0], 0.5);
+        let on_ceiling = Circle::new(point![2.0, 2.0], 0.5);
+        let on_dot = Circle::new(point![1.0, 1.0], 0.5);
+        let near_dot = Circle::new(point![1.0, 0.6], 0.5);
+
+        let contact = contact_circle_segment(&on_floor, &floor).unwrap();
+        assert_eq!(contact.normal.into_inner(), vector![0.0, 1.0]);
+        assert_eq!(contact.depth, 0.5);
+        let contact = contact_circle_segment(&on_ceiling, &ceiling).unwrap();
+        assert_eq!(contact.normal.into_inner(), vector![0.0, -1.0]);
+        let contact = contact_circle_segment(&on_dot, &dot).unwrap();
+        assert_eq!(contact.normal.into_inner(), vector![0.0, 1.0]);
+        let contact = contact_circle_segment(&near_dot, &dot).unwrap();
+        assert_relative_eq!(contact.normal.into_inner(), vector![0.0, -1.0]);
+    }
+
+    #[test]
+    pub fn centers_inside_a_polygon_should_be_pushed_out() {
+        let square = Polygon::new(vec![
+            point![0.0, 0.0],
+            point![4.0, 0.0],
+            point![4.0, 4.0],
+            point![0.0, 4.0],
+        ]);
+        let inside = Circle::new(point![1.0, 2.0], 0.5);
+        let on_edge = Circle::new(point![2.0, 4.0], 0.5);
+
+        let contact = contact_circle_polygon(&inside, &square).unwrap();
+        assert_eq!(contact.point, point![0.0, 2.0]);
+        assert_eq!(contact.normal.into_inner(), vector![-1.0, 0.0]);
+        assert_eq!(contact.depth, 1.5);
+        assert!(contacts_circle_polygon(&inside, &square).is_empty());
+
+        let contact = contact_circle_polygon(&on_edge, &square).unwrap();
+        assert_eq!(contact.normal.into_inner(), vector![0.0, 1.0]);
+        let reversed =
+            Polygon::new(square.vertices.iter().rev().copied().collect());
+        let contact = contact_circle_polygon(&on_edge, &reversed).unwrap();
+        assert_eq!(contact.normal.into_inner(), vector![0.0, 1.0]);
+    }
+
+    proptest! {
+        #[test]
+        fn pushing_by_the_depth_should_leave_the_circle_touching(
+            ball in circle(-10.0..=10.0, 0.1..=5.0),
+            floor in segment(-10.0..=10.0, 0.1),
+        ) {
+            let gap = floor.distance_to_point(&ball.center);
+            let Some(contact) = contact_circle_segment(&ball, &floor) else {
+                prop_assert!(gap > ball.radius);
+                return Ok(());
+            };
+            prop_assert!(contact.depth >= 0.0);
+            let push = contact.normal.into_inner() * contact.depth;
+            let moved = ball.center + push;
+            let distance = floor.distance_to_point(&moved);
+            prop_assert!((distance - ball.radius).abs() <= 1e-3);
+        }
+
+        #[test]
+        fn polygon_contacts_should_agree_with_the_deepest_contact(
+            ball in circle(-60.0..=60.0, 1.0..=20.0),
+            shape in polygon(-50.0..=50.0, 3..=8, false),
+        ) {
+            let deepest = contact_circle_polygon(&ball, &shape);
+            let contacts = contacts_circle_polygon(&ball, &shape);
+            let inside = shape.contains_point(&ball.center);
+
+            match deepest {
+                None => prop_assert!(contacts.is_empty()),
+                Some(deepest) => {
+                    if !inside {
+                        // The deepest contact is one of the boundary
+                        // contacts.
+                        let found = contacts.iter().any(|contact| {
+                            (contact.depth - deepest.depth).abs() <= 1e-4
+                        });
+                        prop_assert!(found);
+                    }
+                    let push = deepest.normal.into_inner() * deepest.depth;
+                    let moved = ball.center + push;
+                    let gap = (moved - deepest.point).norm();
+                    prop_assert!((gap - ball.radius).abs() <= 1e-3);
+                }
+            }
+            for contact in &contacts {
+                prop_assert!(contact.depth >= 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Structs and algorithms for simple shapes in two dimensions.
+
+mod aabb;
+mod arc;
+mod bounding_box;
+mod bounding_circle;
+mod bounding_obb;
+mod circle;
+mod contact;
+mod obb;
+mod support;
+mod wedge;
+
+#[cfg(feature = "alloc")]
+pub(crate) use self::arc::segments_for_sweep;
+pub use self::{
+    aabb::Aabb,
+    arc::Arc,
+    bounding_box::BoundingBox,
+    bounding_circle::BoundingCircle,
+    bounding_obb::BoundingObb,
+    circle::{circle_circle_intersection_area, Circle},
+    contact::{contact_circle_segment, Contact},
+    obb::Obb,
+    support::SupportMap,
+    wedge::Wedge,
+};
+#[cfg(feature = "alloc")]
+pub use self::{
+    circle::circle_polygon_intersection_area,
+    contact::{contact_circle_polygon, contacts_circle_polygon},
+};
@@ -0,0 +1,349 @@
+use {
+    crate::{
+        error::check_finite,
+        shape::{Aabb, BoundingBox, BoundingCircle, BoundingObb, Circle},
+        Error,
+    },
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+#[cfg(feature = "alloc")]
+use crate::hull::convex_hull;
+
+/// A box which can be rotated to any angle, called an oriented bounding
+/// box.
+///
+/// The box is centered on `center`, with its sides along `axis` and the
+/// perpendicular direction counterclockwise from it. It reaches
+/// `half_extents.x` from the center along the axis and `half_extents.y`
+/// across it. The box is closed, so points on its boundary are inside it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Obb {
+    /// The point in the middle of the box.
+    pub center: Point2<f32>,
+
+    /// The direction of the box's first pair of sides.
+    pub axis: Unit<Vector2<f32>>,
+
+    /// Half the box's size along the axis and across it.
+    pub half_extents: Vector2<f32>,
+}
+
+impl Obb {
+    /// A box which contains nothing, with negative infinite extents.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn empty() -> Obb {
+        Obb {
+            center: Point2::origin(),
+            axis: Vector2::x_axis(),
+            half_extents: Vector2::repeat(f32::NEG_INFINITY),
+        }
+    }
+
+    /// Create a new box.
+    ///
+    /// The extents can have either sign, and their sizes are used. The
+    /// values aren't validated, use [`Obb::try_new`] to reject non-finite
+    /// values.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Obb,
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let diamond = Obb::new(
+    ///         point![0.0, 0.0],
+    ///         Unit::new_normalize(vector![1.0, 1.0]),
+    ///         vector![1.0, 1.0],
+    ///     );
+    ///
+    ///     assert!(diamond.contains_point(&point![0.0, 1.4]));
+    ///     assert!(!diamond.contains_point(&point![1.0, 1.0]));
+    ///
+    pub fn new(
+        center: Point2<f32>,
+        axis: Unit<Vector2<f32>>,
+        half_extents: Vector2<f32>,
+    ) -> Self {
+        Self {
+            center,
+            axis,
+            half_extents: half_extents.abs(),
+        }
+    }
+
+    /// Create a new box, checking that every value is finite.
+    pub fn try_new(
+        center: Point2<f32>,
+        axis: Unit<Vector2<f32>>,
+        half_extents: Vector2<f32>,
+    ) -> Result<Self, Error> {
+        check_finite(&[
+            center.x,
+            center.y,
+            axis.x,
+            axis.y,
+            half_extents.x,
+            half_extents.y,
+        ])?;
+        Ok(Self::new(center, axis, half_extents))
+    }
+
+    /// The smallest box which contains every point, found with rotating
+    /// calipers.
+    ///
+    /// The smallest box has a side along an edge of the points' convex
+    /// hull, so the calipers check each edge of the hull while keeping
+    /// track of the furthest vertices in the other three directions. Points
+    /// which are all on one line get a box with no width across the line.
+    /// Returns `None` when there are no points.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Obb,
+    ///         nalgebra::point,
+    ///         approx::assert_relative_eq,
+    ///     };
+    ///
+    ///     // A long thin diagonal strip of points
+    ///     let points: Vec<_> = (0..10)
+    ///         .flat_map(|i| {
+    ///             let x = i as f32;
+    ///             [point![x, x], point![x + 0.5, x - 0.5]]
+    ///         })
+    ///         .collect();
+    ///
+    ///     let obb = Obb::enclosing(&points).unwrap();
+    ///
+    ///     assert_relative_eq!(obb.area(), 9.0, epsilon = 1e-4);
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn enclosing(points: &[Point2<f32>]) -> Option<Self> {
+        let first = *points.first()?;
+        let Some(hull) = convex_hull(points) else {
+            // Every point is on a line through the first one, so the box
+            // runs between the furthest points along it.
+            let furthest = points.iter().max_by(|a, b| {
+                let (a, b) = ((*a - first).norm(), (*b - first).norm());
+                a.total_cmp(&b)
+            })?;
+            let axis = Unit::try_new(furthest - first, 0.0)
+                .unwrap_or_else(Vector2::x_axis);
+            return Some(Self::spanning(points, axis));
+        };
+
+        let hull = hull.vertices;
+        let n = hull.len();
+        let along = |i: usize, direction: &Vector2<f32>| {
+            hull[i % n].coords.dot(direction)
+        };
+        // The furthest vertex in a direction, found by scanning or by
+        // walking forwards from the furthest vertex for the previous edge,
+        // since the calipers only ever turn counterclockwise.
+        let furthest = |direction: &Vector2<f32>| {
+            (0..n)
+                .max_by(|&a, &b| {
+                    along(a, direction).total_cmp(&along(b, direction))
+                })
+                .unwrap()
+        };
+        let advance = |mut i: usize, direction: &Vector2<f32>| {
+            for _ in 0..n {
+                if along(i + 1, direction) < along(i, direction) {
+                    break;
+                }
+                i = (i + 1) % n;
+            }
+            i
+        };
+
+        let mut best: Option<(f32, Obb)> = None;
+        let mut calipers: Option<[usize; 3]> = None;
+        for i in 0..n {
+            let axis = Unit::new_normalize(hull[(i + 1) % n] - hull[i]);
+            let normal = Vector2::new(-axis.y, axis.x);
+            let directions = [axis.into_inner(), normal, -axis.into_inner()];
+            let [ahead, across, behind] = match calipers {
+                None => directions.map(|direction| furthest(&direction)),
+                Some(previous) => {
+                    let mut next = previous;
+                    for (index, direction) in next.iter_mut().zip(&directions) {
+                        *index = advance(*index, direction);
+                    }
+                    next
+                }
+            };
+            calipers = Some([ahead, across, behind]);
+
+            let origin = hull[i];
+            let start = (hull[behind] - origin).dot(&axis);
+            let end = (hull[ahead] - origin).dot(&axis);
+            let height = (hull[across] - origin).dot(&normal);
+            let area = (end - start) * height;
+            if best.is_none_or(|(smallest, _)| area < smallest) {
+                let center = origin
+                    + axis.into_inner() * ((start + end) / 2.0)
+                    + normal * (height / 2.0);
+                let half_extents = Vector2::new(end - start, height) / 2.0;
+                best = Some((area, Obb::new(center, axis, half_extents)));
+            }
+        }
+        best.map(|(_, obb)| obb)
+    }
+
+    /// The box along an axis which spans every point.
+    #[cfg(feature = "alloc")]
+    fn spanning(points: &[Point2<f32>], axis: Unit<Vector2<f32>>) -> Self {
+        let normal = Vector2::new(-axis.y, axis.x);
+        let range = |direction: &Vector2<f32>| {
+            points.iter().fold(
+                (f32::INFINITY, f32::NEG_INFINITY),
+                |(low, high), point| {
+                    let t = point.coords.dot(direction);
+                    (low.min(t), high.max(t))
+                },
+            )
+        };
+        let (along, across) = (range(&axis), range(&normal));
+        let center = axis.into_inner() * ((along.0 + along.1) / 2.0)
+            + normal * ((across.0 + across.1) / 2.0);
+        let half_extents =
+            Vector2::new(along.1 - along.0, across.1 - across.0) / 2.0;
+        Self::new(center.into(), axis, half_extents)
+    }
+
+    /// The corners of the box, counterclockwise.
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        let along = self.axis.into_inner() * self.half_extents.x;
+        let across =
+            Vector2::new(-self.axis.y, self.axis.x) * self.half_extents.y;
+        [
+            self.center - along - across,
+            self.center + along - across,
+            self.center + along + across,
+            self.center - along + across,
+        ]
+    }
+
+    /// The area of the box.
+    pub fn area(&self) -> f32 {
+        4.0 * self.half_extents.x * self.half_extents.y
+    }
+
+    /// Returns true when the point is inside the box or on its boundary.
+    pub fn contains_point(&self, point: &Point2<f32>) -> bool {
+        let offset = point - self.center;
+        let normal = Vector2::new(-self.axis.y, self.axis.x);
+        offset.dot(&self.axis).abs() <= self.half_extents.x
+            && offset.dot(&normal).abs() <= self.half_extents.y
+    }
+}
+
+impl BoundingBox for Obb {
+    /// The axis-aligned box around the corners.
+    fn bounding_box(&self) -> Aabb {
+        let reach = self.axis.abs() * self.half_extents.x
+            + Vector2::new(self.axis.y.abs(), self.axis.x.abs())
+                * self.half_extents.y;
+        Aabb::new(self.center - reach, self.center + reach)
+    }
+}
+
+impl BoundingCircle for Obb {
+    /// The circle through the corners.
+    fn bounding_circle(&self) -> Circle {
+        Circle::new(self.center, self.half_extents.norm())
+    }
+}
+
+impl BoundingObb for Obb {
+    /// The box itself.
+    fn bounding_obb(&self) -> Obb {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::shape::Obb,
+        approx::assert_relative_eq,
+        nalgebra::{point, Rotation2, Vector2},
+        proptest::prelude::*,
+    };
+    #[cfg(feature = "alloc")]
+    use {crate::test_support::polygon, alloc::vec::Vec, nalgebra::Unit};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    pub fn enclosing_should_recover_rotated_rectangles() {
+        let rotation = Rotation2::new(0.4);
+        let corners: Vec<_> =
+            [[-3.0, -1.0], [3.0, -1.0], [3.0, 1.0], [-3.0, 1.0]]
+                .iter()
+                .map(|&[x, y]| point![5.0, 2.0] + rotation * Vector2::new(x, y))
+                .collect();
+
+        let obb = Obb::enclosing(&corners).unwrap();
+
+        assert_relative_eq!(obb.area(), 12.0, epsilon = 1e-4);
+        assert_relative_eq!(obb.center, point![5.0, 2.0], epsilon = 1e-4);
+        assert!(Obb::enclosing(&[]).is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    pub fn collinear_points_should_have_flat_boxes() {
+        let points = [point![1.0, 1.0], point![3.0, 3.0], point![2.0, 2.0]];
+
+        let obb = Obb::enclosing(&points).unwrap();
+
+        assert_relative_eq!(obb.half_extents.x, 2f32.sqrt(), epsilon = 1e-5);
+        assert_relative_eq!(obb.half_extents.y, 0.0, epsilon = 1e-5);
+        assert!(obb.contains_point(&point![2.0, 2.0]));
+        assert!(!Obb::empty().contains_point(&point![0.0, 0.0]));
+    }
+
+    #[test]
+    pub fn bounding_boxes_should_touch_the_corners() {
+        let obb = Obb::new(
+            point![1.0, 1.0],
+            Vector2::y_axis(),
+            Vector2::new(2.0, -1.0),
+        );
+
+        let aabb = crate::shape::BoundingBox::bounding_box(&obb);
+
+        assert_relative_eq!(aabb.min, point![0.0, -1.0]);
+        assert_relative_eq!(aabb.max, point![2.0, 3.0]);
+        assert_eq!(obb.corners()[0], point![2.0, -1.0]);
+    }
+
+    proptest! {
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn enclosing_boxes_should_be_no_larger_than_rotated_ones(
+            polygon in polygon(-100.0..=100.0, 3..=8, false),
+            angle in 0.0f32..core::f32::consts::PI,
+        ) {
+            let obb = Obb::enclosing(&polygon.vertices).unwrap();
+
+            let (sin, cos) = crate::math::sin_cos(angle);
+            let axis = Unit::new_normalize(Vector2::new(cos, sin));
+            let rotated = Obb::spanning(&polygon.vertices, axis);
+            prop_assert!(obb.area() <= rotated.area() * (1.0 + 1e-4) + 1e-3);
+            let tolerance = 1e-3;
+            let grown = Obb::new(
+                obb.center,
+                obb.axis,
+                obb.half_extents.add_scalar(tolerance),
+            );
+            for vertex in &polygon.vertices {
+                prop_assert!(grown.contains_point(vertex));
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use {
+    crate::shape::Aabb,
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+/// Types which implement this trait can find their point which is furthest
+/// in any direction.
+///
+/// This is the support mapping used by collision algorithms like GJK, and
+/// it's also handy for finding the topmost or leftmost part of a shape.
+/// When several points are equally far, implementations return the first
+/// one, like the lowest index for shapes made of vertices.
+pub trait SupportMap {
+    /// The point of this object which is furthest along a direction.
+    fn support_point(&self, direction: &Unit<Vector2<f32>>) -> Point2<f32>;
+}
+
+impl SupportMap for Point2<f32> {
+    fn support_point(&self, _direction: &Unit<Vector2<f32>>) -> Point2<f32> {
+        *self
+    }
+}
+
+impl SupportMap for Aabb {
+    /// The corner furthest along the direction. Ties go to the first
+    /// corner in [`Aabb::corners`].
+    fn support_point(&self, direction: &Unit<Vector2<f32>>) -> Point2<f32> {
+        let corners = self.corners();
+        let mut best = corners[0];
+        for corner in &corners[1..] {
+            if corner.coords.dot(direction) > best.coords.dot(direction) {
+                best = *corner;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            shape::{Aabb, SupportMap},
+            test_support::{aabb, circle, unit_vector},
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    #[test]
+    pub fn ties_should_go_to_the_first_point() {
+        let up = Unit::new_normalize(vector![0.0, 1.0]);
+        let segment = Segment::new(point![0.0, 1.0], point![5.0, 1.0]);
+        let bounds = Aabb::new(point![0.0, 0.0], point![2.0, 3.0]);
+
+        assert_eq!(segment.support_point(&up), segment.start);
+        assert_eq!(bounds.support_point(&up), point![2.0, 3.0]);
+        assert_eq!(
+            bounds.support_point(&Unit::new_normalize(vector![-1.0, 0.0])),
+            point![0.0, 0.0]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn box_support_points_should_be_the_furthest_corner(
+            bounds in aabb(-10.0..=10.0),
+            direction in unit_vector(),
+        ) {
+            let support = bounds.support_point(&direction);
+
+            prop_assert!(bounds.corners().contains(&support));
+            for corner in bounds.corners() {
+                prop_assert!(
+                    corner.coords.dot(&direction)
+                        <= support.coords.dot(&direction)
+                );
+            }
+        }
+
+        #[test]
+        fn circle_support_points_should_be_on_the_circle(
+            circle in circle(-10.0..=10.0, 0.0..=5.0),
+            direction in unit_vector(),
+        ) {
+            let support = circle.support_point(&direction);
+
+            assert_relative_eq!(
+                (support - circle.center).norm(),
+                circle.radius,
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(
+                (support - circle.center).dot(&direction),
+                circle.radius,
+                epsilon = 1e-4
+            );
+        }
+    }
+}
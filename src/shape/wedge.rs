@@ -0,0 +1,503 @@
+use {
+    crate::{
+        error::check_finite,
+        line::{intersection::intersect_ray_segment, Ray, Segment},
+        shape::Aabb,
+        Error,
+    },
+    core::f32::consts::PI,
+    nalgebra::{Point2, Rotation2, Unit, Vector2},
+};
+
+/// The region swept counterclockwise between two rays with the same origin.
+///
+/// A wedge is defined by its apex and the directions of its two bounding
+/// rays. The wedge contains every point swept counterclockwise from the
+/// `start` direction to the `end` direction, including points on either
+/// bounding ray. When the sweep is larger than pi the wedge is reflex (and is
+/// no longer convex).
+///
+/// When `start` and `end` are the same direction, the wedge covers the full
+/// circle and contains every point in the plane.
+///
+/// ```none
+/// +----------------------------------------------------+
+/// |    ^                                               |
+/// |  5 +           end                                 |
+/// |    |         /                                     |
+/// |  4 +       /   inside                              |
+/// |    |     /                                         |
+/// |  3 +   +-------------- start                       |
+/// |    |  apex                                         |
+/// |  2 +             outside                           |
+/// |    |                                               |
+/// |x---+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+> |
+/// |    |                                               |
+/// |  y |                                               |
+/// +----------------------------------------------------+
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Wedge {
+    /// The point where the wedge's bounding rays begin.
+    pub apex: Point2<f32>,
+
+    /// The direction of the ray where the counterclockwise sweep begins.
+    pub start: Unit<Vector2<f32>>,
+
+    /// The direction of the ray where the counterclockwise sweep ends.
+    pub end: Unit<Vector2<f32>>,
+}
+
+impl Wedge {
+    /// Create a new wedge swept counterclockwise from `start` to `end`.
+    ///
+    /// The apex isn't validated. Use [`Wedge::try_new`] to reject non-finite
+    /// values.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::Wedge,
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     // The first quadrant
+    ///     let wedge = Wedge::new(
+    ///         point![0.0, 0.0],
+    ///         Unit::new_normalize(vector![1.0, 0.0]),
+    ///         Unit::new_normalize(vector![0.0, 1.0]),
+    ///     );
+    ///
+    ///     assert!(wedge.contains_point(&point![1.0, 1.0]));
+    ///     assert!(!wedge.contains_point(&point![-1.0, 1.0]));
+    ///
+    pub fn new(
+        apex: Point2<f32>,
+        start: Unit<Vector2<f32>>,
+        end: Unit<Vector2<f32>>,
+    ) -> Self {
+        Self { apex, start, end }
+    }
+
+    /// Create a new wedge, normalizing both directions and checking that
+    /// every value is finite.
+    pub fn try_new(
+        apex: Point2<f32>,
+        start: Vector2<f32>,
+        end: Vector2<f32>,
+    ) -> Result<Self, Error> {
+        check_finite(&[apex.x, apex.y, start.x, start.y, end.x, end.y])?;
+        let start = Unit::try_new(start, 0.0).ok_or(Error::ZeroVector)?;
+        let end = Unit::try_new(end, 0.0).ok_or(Error::ZeroVector)?;
+        Ok(Self::new(apex, start, end))
+    }
+
+    /// Create a wedge which covers every direction in the plane.
+    pub fn full(apex: Point2<f32>) -> Self {
+        let direction = Unit::new_unchecked(Vector2::x());
+        Self::new(apex, direction, direction)
+    }
+
+    /// Create a wedge centered on a ray, like a cone of vision.
+    ///
+    /// The wedge extends `half_angle` radians to either side of the ray's
+    /// direction. Half angles of pi or more produce a full wedge.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Ray, shape::Wedge},
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let view = Wedge::from_ray(
+    ///         &Ray::new(point![0.0, 0.0], Unit::new_normalize(vector![0.0, 1.0])),
+    ///         core::f32::consts::FRAC_PI_4,
+    ///     );
+    ///
+    ///     assert!(view.contains_point(&point![0.5, 2.0]));
+    ///     assert!(!view.contains_point(&point![2.0, 0.5]));
+    ///
+    pub fn from_ray(ray: &Ray, half_angle: f32) -> Self {
+        if half_angle >= PI {
+            return Self::full(ray.origin);
+        }
+        let rotation = Rotation2::new(half_angle);
+        Self::new(
+            ray.origin,
+            Unit::new_unchecked(rotation.inverse() * ray.direction.as_ref()),
+            Unit::new_unchecked(rotation * ray.direction.as_ref()),
+        )
+    }
+
+    /// Returns true when the wedge covers every direction.
+    pub fn is_full(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns true when the wedge sweeps more than pi radians.
+    ///
+    /// Reflex wedges are not convex, so a segment with both endpoints inside
+    /// can still leave the wedge.
+    pub fn is_reflex(&self) -> bool {
+        !self.is_full() && self.start.perp(&self.end) < 0.0
+    }
+
+    /// Returns true when the point is inside the wedge or on either of its
+    /// bounding rays.
+    pub fn contains_point(&self, point: &Point2<f32>) -> bool {
+        if self.is_full() {
+            return true;
+        }
+        let v = point - self.apex;
+        let after_start = self.start.perp(&v) >= 0.0;
+        let before_end = v.perp(&self.end) >= 0.0;
+        if self.is_reflex() {
+            after_start || before_end
+        } else {
+            after_start && before_end
+        }
+    }
+
+    /// Returns true when every point on the segment is inside the wedge.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, shape::Wedge},
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     // Everything except the third quadrant
+    ///     let wedge = Wedge::new(
+    ///         point![0.0, 0.0],
+    ///         Unit::new_normalize(vector![0.0, -1.0]),
+    ///         Unit::new_normalize(vector![-1.0, 0.0]),
+    ///     );
+    ///     let segment = Segment::new(point![-2.0, 1.0], point![1.0, -2.0]);
+    ///
+    ///     // both endpoints are inside, but the segment cuts the corner
+    ///     assert!(wedge.contains_point(&segment.start));
+    ///     assert!(wedge.contains_point(&segment.end));
+    ///     assert!(!wedge.contains_segment(&segment));
+    ///
+    pub fn contains_segment(&self, segment: &Segment) -> bool {
+        matches!(
+            self.clip_parameters(segment),
+            [Some((t0, t1)), None] if t0 <= 0.0 && t1 >= 1.0
+        )
+    }
+
+    /// The portion of a segment which is inside the wedge.
+    ///
+    /// Returns `None` when the segment is entirely outside the wedge. The
+    /// clipped segment has the same direction as the original.
+    ///
+    /// The part of a segment inside a reflex wedge can be split in two when
+    /// the segment passes through the region outside the wedge. In that case
+    /// this returns the piece nearest the segment's start, and
+    /// [`Wedge::clip_segment_pieces`] returns both.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{line::Segment, shape::Wedge},
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let wedge = Wedge::new(
+    ///         point![0.0, 0.0],
+    ///         Unit::new_normalize(vector![1.0, 1.0]),
+    ///         Unit::new_normalize(vector![-1.0, 1.0]),
+    ///     );
+    ///     let segment = Segment::new(point![-4.0, 2.0], point![4.0, 2.0]);
+    ///
+    ///     assert_eq!(
+    ///         wedge.clip_segment(&segment),
+    ///         Some(Segment::new(point![-2.0, 2.0], point![2.0, 2.0]))
+    ///     );
+    ///
+    pub fn clip_segment(&self, segment: &Segment) -> Option<Segment> {
+        self.clip_segment_pieces(segment)[0]
+    }
+
+    /// Every piece of a segment which is inside the wedge.
+    ///
+    /// Convex wedges produce at most one piece. Reflex wedges can produce
+    /// two. Pieces are ordered from the segment's start to its end, and the
+    /// second piece is only present when the first is.
+    pub fn clip_segment_pieces(
+        &self,
+        segment: &Segment,
+    ) -> [Option<Segment>; 2] {
+        let direction = segment.end - segment.start;
+        let piece = |(t0, t1): (f32, f32)| {
+            Segment::new(
+                segment.start + direction * t0,
+                segment.start + direction * t1,
+            )
+        };
+        let [first, second] = self.clip_parameters(segment);
+        [first.map(piece), second.map(piece)]
+    }
+
+    /// Returns true when the wedge and the box share at least one point.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::shape::{Aabb, Wedge},
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let wedge = Wedge::new(
+    ///         point![0.0, 0.0],
+    ///         Unit::new_normalize(vector![1.0, 0.0]),
+    ///         Unit::new_normalize(vector![1.0, 1.0]),
+    ///     );
+    ///
+    ///     assert!(wedge.intersects_aabb(&Aabb::new(
+    ///         point![2.0, -1.0],
+    ///         point![3.0, 1.0]
+    ///     )));
+    ///     assert!(!wedge.intersects_aabb(&Aabb::new(
+    ///         point![-3.0, -1.0],
+    ///         point![-2.0, 1.0]
+    ///     )));
+    ///
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        // If the box overlaps the wedge without containing the apex or having
+        // a corner inside the wedge, then one of the wedge's bounding rays
+        // must cross the box.
+        if aabb.contains_point(&self.apex)
+            || aabb.corners().iter().any(|c| self.contains_point(c))
+        {
+            return true;
+        }
+        let rays = [
+            Ray::new(self.apex, self.start),
+            Ray::new(self.apex, self.end),
+        ];
+        rays.iter().any(|ray| {
+            aabb.edges()
+                .iter()
+                .any(|edge| intersect_ray_segment(ray, edge).is_some())
+        })
+    }
+
+    /// The parameter ranges, from 0 at the segment's start to 1 at its end,
+    /// for the pieces of the segment inside the wedge.
+    fn clip_parameters(&self, segment: &Segment) -> [Option<(f32, f32)>; 2] {
+        if self.is_full() {
+            return [Some((0.0, 1.0)), None];
+        }
+
+        // Both half-plane tests are linear in the segment parameter.
+        let v0 = segment.start - self.apex;
+        let direction = segment.end - segment.start;
+        let after_start = (self.start.perp(&v0), self.start.perp(&direction));
+        let before_end = (v0.perp(&self.end), direction.perp(&self.end));
+
+        if !self.is_reflex() {
+            // A convex wedge is the intersection of the two half-planes.
+            let range = clip_to_half_plane(Some((0.0, 1.0)), after_start);
+            return [clip_to_half_plane(range, before_end), None];
+        }
+
+        // A reflex wedge is the union of the two half-planes, so remove the
+        // part of the segment which is outside both of them.
+        let negate = |(a, b): (f32, f32)| (-a, -b);
+        let outside = clip_to_half_plane(
+            clip_to_half_plane(Some((0.0, 1.0)), negate(after_start)),
+            negate(before_end),
+        );
+        match outside {
+            None => [Some((0.0, 1.0)), None],
+            Some((t0, t1)) => {
+                let before = (t0 > 0.0).then_some((0.0, t0));
+                let after = (t1 < 1.0).then_some((t1, 1.0));
+                match before {
+                    Some(_) => [before, after],
+                    None => [after, None],
+                }
+            }
+        }
+    }
+}
+
+/// Restrict a parameter range to where `a + b * t >= 0`.
+fn clip_to_half_plane(
+    range: Option<(f32, f32)>,
+    (a, b): (f32, f32),
+) -> Option<(f32, f32)> {
+    let (mut t0, mut t1) = range?;
+    if b == 0.0 {
+        return (a >= 0.0).then_some((t0, t1));
+    }
+    let t = -a / b;
+    if b > 0.0 {
+        t0 = t0.max(t);
+    } else {
+        t1 = t1.min(t);
+    }
+    (t0 <= t1).then_some((t0, t1))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::Segment,
+            shape::{Aabb, Wedge},
+            test_support::{aabb, point2, segment, unit_vector},
+        },
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    fn quadrant() -> Wedge {
+        Wedge::new(
+            point![0.0, 0.0],
+            Unit::new_normalize(vector![1.0, 0.0]),
+            Unit::new_normalize(vector![0.0, 1.0]),
+        )
+    }
+
+    fn three_quadrants() -> Wedge {
+        Wedge::new(
+            point![0.0, 0.0],
+            Unit::new_normalize(vector![0.0, -1.0]),
+            Unit::new_normalize(vector![-1.0, 0.0]),
+        )
+    }
+
+    #[test]
+    pub fn points_on_an_edge_should_be_inside() {
+        let wedge = quadrant();
+
+        assert!(wedge.contains_point(&point![3.0, 0.0]));
+        assert!(wedge.contains_point(&point![0.0, 3.0]));
+        assert!(wedge.contains_point(&point![0.0, 0.0]));
+        assert!(!wedge.contains_point(&point![-3.0, 0.0]));
+        assert!(!wedge.contains_point(&point![0.0, -3.0]));
+    }
+
+    #[test]
+    pub fn reflex_wedges_should_contain_three_quadrants() {
+        let wedge = three_quadrants();
+
+        assert!(wedge.is_reflex());
+        assert!(wedge.contains_point(&point![1.0, 1.0]));
+        assert!(wedge.contains_point(&point![-1.0, 1.0]));
+        assert!(wedge.contains_point(&point![1.0, -1.0]));
+        assert!(!wedge.contains_point(&point![-1.0, -1.0]));
+    }
+
+    #[test]
+    pub fn full_wedges_should_contain_everything() {
+        let wedge = Wedge::full(point![1.0, 1.0]);
+
+        assert!(wedge.is_full());
+        assert!(!wedge.is_reflex());
+        assert!(wedge.contains_point(&point![-10.0, -10.0]));
+        let segment = Segment::new(point![-5.0, 3.0], point![7.0, 1.0]);
+        assert_eq!(wedge.clip_segment(&segment), Some(segment));
+    }
+
+    #[test]
+    pub fn a_half_plane_wedge_should_be_convex() {
+        let wedge = Wedge::new(
+            point![0.0, 0.0],
+            Unit::new_normalize(vector![1.0, 0.0]),
+            Unit::new_normalize(vector![-1.0, 0.0]),
+        );
+
+        assert!(!wedge.is_reflex());
+        assert!(wedge.contains_point(&point![-5.0, 0.1]));
+        assert!(!wedge.contains_point(&point![-5.0, -0.1]));
+    }
+
+    #[test]
+    pub fn segments_crossing_both_edges_should_be_clipped_in_the_middle() {
+        let wedge = quadrant();
+        let segment = Segment::new(point![4.0, -1.0], point![-1.0, 4.0]);
+
+        assert_eq!(
+            wedge.clip_segment(&segment),
+            Some(Segment::new(point![3.0, 0.0], point![0.0, 3.0]))
+        );
+        assert!(!wedge.contains_segment(&segment));
+    }
+
+    #[test]
+    pub fn segments_outside_the_wedge_should_not_be_clipped() {
+        let wedge = quadrant();
+        let segment = Segment::new(point![-1.0, -1.0], point![-4.0, 2.0]);
+
+        assert_eq!(wedge.clip_segment(&segment), None);
+        assert!(wedge.contains_segment(&Segment::new(
+            point![1.0, 1.0],
+            point![2.0, 5.0]
+        )));
+    }
+
+    #[test]
+    pub fn reflex_wedges_should_clip_segments_into_two_pieces() {
+        let wedge = three_quadrants();
+        let segment = Segment::new(point![-4.0, 2.0], point![2.0, -4.0]);
+
+        assert_eq!(
+            wedge.clip_segment_pieces(&segment),
+            [
+                Some(Segment::new(point![-4.0, 2.0], point![-2.0, 0.0])),
+                Some(Segment::new(point![0.0, -2.0], point![2.0, -4.0])),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn boxes_should_intersect_when_a_bounding_ray_crosses_them() {
+        let wedge = Wedge::new(
+            point![0.0, 0.0],
+            Unit::new_normalize(vector![1.0, 0.1]),
+            Unit::new_normalize(vector![1.0, 0.2]),
+        );
+
+        // the box straddles the wedge without any corners inside it
+        let aabb = Aabb::new(point![5.0, -10.0], point![6.0, 10.0]);
+        assert!(aabb.corners().iter().all(|c| !wedge.contains_point(c)));
+        assert!(wedge.intersects_aabb(&aabb));
+
+        let behind = Aabb::new(point![-6.0, -10.0], point![-5.0, 10.0]);
+        assert!(!wedge.intersects_aabb(&behind));
+    }
+
+    proptest! {
+        #[test]
+        fn clipped_pieces_should_be_inside_the_wedge(
+            apex in point2(-10.0..=10.0),
+            start in unit_vector(),
+            end in unit_vector(),
+            s in segment(-10.0..=10.0, 0.1),
+        ) {
+            let wedge = Wedge::new(apex, start, end);
+            for piece in wedge.clip_segment_pieces(&s).iter().flatten() {
+                let midpoint = nalgebra::center(&piece.start, &piece.end);
+                prop_assert!(wedge.contains_point(&midpoint));
+            }
+        }
+
+        #[test]
+        fn boxes_with_points_inside_the_wedge_should_intersect(
+            apex in point2(-10.0..=10.0),
+            start in unit_vector(),
+            end in unit_vector(),
+            b in aabb(-10.0..=10.0),
+        ) {
+            let wedge = Wedge::new(apex, start, end);
+            if wedge.contains_point(&b.center()) {
+                prop_assert!(wedge.intersects_aabb(&b));
+            }
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Small physics simulations built from the crate's geometry.
+//!
+//! A [`Rope`] is a chain of particles joined by distance constraints and
+//! moved with Verlet integration. Each step moves every particle by its
+//! velocity and gravity, then relaxes the constraints a few times while
+//! pushing particles out of obstacles with
+//! [`contact_circle_segment`](crate::shape::contact_circle_segment).
+
+use {
+    crate::{
+        line::Segment,
+        operations::perp_vec2d,
+        shape::{contact_circle_segment, Circle},
+        Error,
+    },
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// A chain of particles joined by distance constraints, like a rope or a
+/// chain of beads.
+///
+/// Particles are circles with a shared radius, and collide with the
+/// segments in [`obstacles`](Rope::obstacles). Particles which are pinned
+/// stay where they are until they're moved with [`Rope::move_to`].
+/// Particles which move further than their radius in a single step can't
+/// pass through an obstacle either, since a particle which would cross one
+/// is stopped where it crosses.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::{DistanceToPoint, Segment},
+///             sim::Rope,
+///         },
+///         nalgebra::{point, vector},
+///     };
+///
+///     // A rope hanging from one end above a ledge.
+///     let particles = (0..10).map(|i| point![i as f32, 5.0]).collect();
+///     let ledge = Segment::new(point![-5.0, 0.0], point![5.0, 0.0]);
+///     let mut rope = Rope::new(particles, 0.1);
+///     rope.pin(0);
+///     rope.obstacles.push(ledge);
+///
+///     for _ in 0..600 {
+///         rope.step(1.0 / 60.0, vector![0.0, -9.8], 8);
+///     }
+///
+///     assert_eq!(rope.particles()[0], point![0.0, 5.0]);
+///     for particle in rope.particles() {
+///         assert!(ledge.distance_to_point(particle) > 0.09);
+///     }
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rope {
+    particles: Vec<Point2<f32>>,
+    previous: Vec<Point2<f32>>,
+    rest_lengths: Vec<f32>,
+    pinned: Vec<bool>,
+
+    /// The radius of each particle, used for collisions.
+    pub radius: f32,
+
+    /// The fraction of each particle's velocity which is lost every step,
+    /// from 0 for no damping to 1 for no momentum at all.
+    pub damping: f32,
+
+    /// Segments which the particles can't pass through.
+    pub obstacles: Vec<Segment>,
+}
+
+impl Rope {
+    /// Create a rope at rest, with rest lengths equal to the current
+    /// distances between neighboring particles.
+    ///
+    /// The rope starts with no obstacles, no pinned particles, and a little
+    /// damping.
+    pub fn new(particles: Vec<Point2<f32>>, radius: f32) -> Self {
+        let rest_lengths = particles
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).norm())
+            .collect();
+        Self {
+            previous: particles.clone(),
+            pinned: vec![false; particles.len()],
+            particles,
+            rest_lengths,
+            radius,
+            damping: 0.01,
+            obstacles: Vec::new(),
+        }
+    }
+
+    /// Create a rope at rest with a rest length for each pair of
+    /// neighboring particles.
+    ///
+    /// Returns [`Error::MismatchedVertexCounts`] when there isn't exactly
+    /// one rest length for each pair of neighbors.
+    pub fn with_rest_lengths(
+        particles: Vec<Point2<f32>>,
+        rest_lengths: Vec<f32>,
+        radius: f32,
+    ) -> Result<Self, Error> {
+        if rest_lengths.len() != particles.len().saturating_sub(1) {
+            return Err(Error::MismatchedVertexCounts);
+        }
+        Ok(Self {
+            rest_lengths,
+            ..Self::new(particles, radius)
+        })
+    }
+
+    /// The positions of the particles.
+    pub fn particles(&self) -> &[Point2<f32>] {
+        &self.particles
+    }
+
+    /// The distance each pair of neighboring particles is held at.
+    pub fn rest_lengths(&self) -> &[f32] {
+        &self.rest_lengths
+    }
+
+    /// Change the distance between particles `index` and `index + 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index + 1` isn't a particle.
+    pub fn set_rest_length(&mut self, index: usize, length: f32) {
+        self.rest_lengths[index] = length;
+    }
+
+    /// Hold a particle in place.
+    pub fn pin(&mut self, index: usize) {
+        self.pinned[index] = true;
+    }
+
+    /// Let a pinned particle move again.
+    pub fn unpin(&mut self, index: usize) {
+        self.pinned[index] = false;
+    }
+
+    /// Returns true when a particle is pinned.
+    pub fn is_pinned(&self, index: usize) -> bool {
+        self.pinned[index]
+    }
+
+    /// Move a particle, pinned or not, and stop it.
+    pub fn move_to(&mut self, index: usize, point: Point2<f32>) {
+        self.particles[index] = point;
+        self.previous[index] = point;
+    }
+
+    /// Advance the simulation by `dt` seconds.
+    ///
+    /// Every unpinned particle moves by its velocity and accelerates with
+    /// `gravity`. Then the distance constraints are relaxed `iterations`
+    /// times, with collisions resolved after each pass so constraints
+    /// can't drag particles into obstacles. More iterations make the rope
+    /// stretch less.
+    pub fn step(&mut self, dt: f32, gravity: Vector2<f32>, iterations: usize) {
+        let start = self.particles.clone();
+        let keep = 1.0 - self.damping;
+        for i in 0..self.particles.len() {
+            if self.pinned[i] {
+                continue;
+            }
+            let velocity = (self.particles[i] - self.previous[i]) * keep;
+            self.particles[i] += velocity + gravity * (dt * dt);
+        }
+        self.previous = start.clone();
+
+        for _ in 0..iterations.max(1) {
+            self.relax();
+            for (i, from) in start.iter().enumerate() {
+                if !self.pinned[i] {
+                    self.particles[i] = self.collide(from, self.particles[i]);
+                }
+            }
+        }
+    }
+
+    /// Move each pair of neighbors toward their rest length, splitting the
+    /// correction between them unless one is pinned.
+    fn relax(&mut self) {
+        for i in 0..self.rest_lengths.len() {
+            let delta = self.particles[i + 1] - self.particles[i];
+            let distance = delta.norm();
+            if distance == 0.0 {
+                continue;
+            }
+            let weights = (
+                if self.pinned[i] { 0.0 } else { 1.0 },
+                if self.pinned[i + 1] { 0.0 } else { 1.0 },
+            );
+            let total = weights.0 + weights.1;
+            if total == 0.0 {
+                continue;
+            }
+            let stretch =
+                delta * ((distance - self.rest_lengths[i]) / distance);
+            self.particles[i] += stretch * (weights.0 / total);
+            self.particles[i + 1] -= stretch * (weights.1 / total);
+        }
+    }
+
+    /// Resolve a particle's collisions with every obstacle, given where it
+    /// was at the start of the step.
+    fn collide(&self, from: &Point2<f32>, mut to: Point2<f32>) -> Point2<f32> {
+        for obstacle in &self.obstacles {
+            if let Some(crossing) = crossing(from, &to, obstacle) {
+                // Stop the particle where it crossed, on the side it came
+                // from.
+                let direction = obstacle.end - obstacle.start;
+                let mut normal = perp_vec2d(&direction).normalize();
+                if normal.dot(&(from - obstacle.start)) < 0.0 {
+                    normal = -normal;
+                }
+                to = crossing + normal * self.radius;
+            }
+            let particle = Circle::new(to, self.radius);
+            if let Some(contact) = contact_circle_segment(&particle, obstacle) {
+                to += contact.normal.into_inner() * contact.depth;
+            }
+        }
+        to
+    }
+}
+
+/// Where the path from one point to another crosses to the other side of
+/// a segment, if it does.
+fn crossing(
+    from: &Point2<f32>,
+    to: &Point2<f32>,
+    segment: &Segment,
+) -> Option<Point2<f32>> {
+    let direction = segment.end - segment.start;
+    let before = direction.perp(&(from - segment.start));
+    let after = direction.perp(&(to - segment.start));
+    if before == 0.0 || before.signum() == after.signum() || after == 0.0 {
+        return None;
+    }
+    let t = before / (before - after);
+    let point = from + (to - from) * t;
+    let along = (point - segment.start).dot(&direction);
+    (0.0..=direction.norm_squared())
+        .contains(&along)
+        .then_some(point)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{crossing, Rope},
+        crate::{line::Segment, Error},
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Point2},
+    };
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn horizontal(count: usize, y: f32) -> Vec<Point2<f32>> {
+        (0..count)
+            .map(|i| point![-5.0 + 10.0 * i as f32 / (count - 1) as f32, y])
+            .collect()
+    }
+
+    #[test]
+    pub fn ropes_dropped_on_a_floor_should_come_to_rest_on_it() {
+        let mut rope = Rope::new(horizontal(21, 3.0), 0.1);
+        rope.obstacles
+            .push(Segment::new(point![-10.0, 0.0], point![10.0, 0.0]));
+
+        for _ in 0..600 {
+            rope.step(DT, vector![0.0, -9.8], 8);
+        }
+        let before = rope.particles().to_vec();
+        rope.step(DT, vector![0.0, -9.8], 8);
+
+        for (particle, previous) in rope.particles().iter().zip(&before) {
+            assert!(
+                particle.y >= 0.1 - 1e-4,
+                "{} is below the floor",
+                particle
+            );
+            assert!((particle - previous).norm() < 1e-3);
+        }
+    }
+
+    #[test]
+    pub fn pinned_ropes_should_hang_at_their_rest_lengths() {
+        let particles = horizontal(6, 0.0);
+        let lengths = vec![0.5, 1.0, 1.5, 2.0, 2.5];
+        let mut rope =
+            Rope::with_rest_lengths(particles, lengths, 0.1).unwrap();
+        rope.pin(0);
+        rope.damping = 0.05;
+
+        for _ in 0..1200 {
+            rope.step(DT, vector![0.0, -9.8], 40);
+        }
+
+        assert_eq!(rope.particles()[0], point![-5.0, 0.0]);
+        assert!(rope.is_pinned(0) && !rope.is_pinned(1));
+        for (pair, length) in rope.particles().windows(2).zip([0.5, 1.0, 1.5]) {
+            assert_relative_eq!(
+                (pair[1] - pair[0]).norm(),
+                length,
+                epsilon = 1e-2
+            );
+            // Hanging straight down from the pin.
+            assert_relative_eq!(pair[1].x, -5.0, epsilon = 1e-2);
+        }
+
+        rope.move_to(0, point![0.0, 0.0]);
+        assert_eq!(rope.particles()[0], point![0.0, 0.0]);
+        assert_eq!(
+            Rope::with_rest_lengths(horizontal(3, 0.0), vec![1.0], 0.1),
+            Err(Error::MismatchedVertexCounts)
+        );
+    }
+
+    #[test]
+    pub fn ropes_should_drape_over_corners_without_passing_through() {
+        // A wedge pointing up, with the rope dropped across its tip.
+        let wedge = [
+            Segment::new(point![-4.0, -4.0], point![0.0, 0.0]),
+            Segment::new(point![0.0, 0.0], point![4.0, -4.0]),
+        ];
+        let mut rope = Rope::new(horizontal(31, 2.0), 0.05);
+        rope.obstacles.extend(wedge);
+
+        for _ in 0..300 {
+            // Fast steps move particles further than their radius.
+            let before = rope.particles().to_vec();
+            rope.step(DT * 2.0, vector![0.0, -9.8], 4);
+            for (from, to) in before.iter().zip(rope.particles()) {
+                for side in &wedge {
+                    let crossed = crossing(from, to, side);
+                    assert!(crossed.is_none(), "{} crossed to {}", from, to);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Snap rounding of segment arrangements to a grid.
+//!
+//! Snap rounding converts an arbitrary arrangement of segments into one where
+//! every vertex lies on a grid and segments only meet at shared vertices.
+//! This makes downstream algorithms (boolean operations, planar graph
+//! extraction) robust because they never have to deal with near-degenerate
+//! intersections.
+//!
+//! The grid is divided into square "pixels" centered on the grid points. A
+//! pixel is "hot" when it contains a segment endpoint or an intersection
+//! between two segments. Every segment which passes through a hot pixel is
+//! rerouted through the pixel's center.
+
+use {
+    crate::{
+        exact::{intersect_isegments, ExactIntersection, ISegment},
+        line::Segment,
+        math,
+    },
+    alloc::{collections::BTreeSet, vec::Vec},
+    nalgebra::{point, Point2},
+};
+
+/// A grid point, in units of the grid spacing.
+type GridPoint = (i64, i64);
+
+/// Snap round a collection of segments to a grid with the given spacing.
+///
+/// This implements iterated snap rounding: hot pixels are computed from the
+/// input endpoints and intersections, every segment is routed through the
+/// hot pixels it touches, and the process repeats on the resulting fragments
+/// until no fragment passes through a hot pixel other than at its endpoints.
+///
+/// The output has the following guarantees:
+///
+/// - every output endpoint lies on the grid (an integer multiple of `grid`)
+/// - output segments only intersect at shared endpoints
+/// - no output segment is repeated (in either direction)
+///
+/// Segments which collapse into a single grid point are dropped from the
+/// output, though their endpoints still create hot pixels. The output is
+/// sorted so the result is deterministic for a given input.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, snap::snap_round},
+///         nalgebra::point,
+///     };
+///
+///     // Two segments which cross at (2.1, 1.9)
+///     let segments = [
+///         Segment::new(point![0.2, 0.0], point![4.0, 3.8]),
+///         Segment::new(point![0.2, 3.8], point![4.0, 0.0]),
+///     ];
+///
+///     let snapped = snap_round(&segments, 1.0);
+///
+///     // Both segments are split where they cross, so every output segment
+///     // ends at the snapped crossing point.
+///     assert_eq!(snapped.len(), 4);
+///     assert!(snapped
+///         .iter()
+///         .all(|s| s.start == point![2.0, 2.0] || s.end == point![2.0, 2.0]));
+///
+pub fn snap_round(segments: &[Segment], grid: f32) -> Vec<Segment> {
+    let scale = 1.0 / grid as f64;
+    let originals: Vec<[Point2<f64>; 2]> = segments
+        .iter()
+        .map(|s| [scaled(&s.start, scale), scaled(&s.end, scale)])
+        .collect();
+
+    let mut hot = initial_hot_pixels(&originals);
+    let mut fragments = BTreeSet::new();
+    for [p, q] in &originals {
+        let path = route(
+            pixel_of(p),
+            pixel_of(q),
+            hot_pixels_near(&hot, p, q)
+                .filter(|h| passes_through(p, q, h))
+                .map(|h| (h, (to_f64(&h) - p).dot(&(q - p)))),
+        );
+        add_fragments(&mut fragments, &path);
+    }
+
+    // Iterate on the integer fragments until nothing changes.
+    loop {
+        let mut changed = false;
+
+        let current: Vec<ISegment> = fragments
+            .iter()
+            .map(|(a, b): &(GridPoint, GridPoint)| {
+                ISegment::new(point![a.0, a.1], point![b.0, b.1])
+            })
+            .collect();
+        for (i, a) in current.iter().enumerate() {
+            for b in &current[i + 1..] {
+                if let ExactIntersection::Crossing(p) =
+                    intersect_isegments(a, b)
+                {
+                    let p = p.round();
+                    changed |= hot.insert((p.x, p.y));
+                }
+            }
+        }
+
+        let mut rerouted = BTreeSet::new();
+        for fragment in &current {
+            let (p, q) = (fragment.start, fragment.end);
+            let d = (q.x as i128 - p.x as i128, q.y as i128 - p.y as i128);
+            let path = route(
+                (p.x, p.y),
+                (q.x, q.y),
+                hot_pixels_near(
+                    &hot,
+                    &to_f64(&(p.x, p.y)),
+                    &to_f64(&(q.x, q.y)),
+                )
+                .filter(|h| passes_through_exact(fragment, h))
+                .map(|h| {
+                    let w =
+                        (h.0 as i128 - p.x as i128, h.1 as i128 - p.y as i128);
+                    (h, w.0 * d.0 + w.1 * d.1)
+                }),
+            );
+            changed |= path.len() > 2;
+            add_fragments(&mut rerouted, &path);
+        }
+        fragments = rerouted;
+
+        if !changed {
+            break;
+        }
+    }
+
+    fragments
+        .iter()
+        .map(|(a, b)| {
+            Segment::new(
+                point![a.0 as f32 * grid, a.1 as f32 * grid],
+                point![b.0 as f32 * grid, b.1 as f32 * grid],
+            )
+        })
+        .collect()
+}
+
+/// The hot pixels for the original segments: every endpoint and every
+/// pairwise intersection point.
+fn initial_hot_pixels(originals: &[[Point2<f64>; 2]]) -> BTreeSet<GridPoint> {
+    let mut hot = BTreeSet::new();
+    for [p, q] in originals {
+        hot.insert(pixel_of(p));
+        hot.insert(pixel_of(q));
+    }
+    for (i, [p1, q1]) in originals.iter().enumerate() {
+        for [p2, q2] in &originals[i + 1..] {
+            let d1 = q1 - p1;
+            let d2 = q2 - p2;
+            let denominator = d1.perp(&d2);
+            if denominator == 0.0 {
+                // Parallel segments only touch at endpoints, which are
+                // already hot.
+                continue;
+            }
+            let w = p2 - p1;
+            let s = w.perp(&d2) / denominator;
+            let t = w.perp(&d1) / denominator;
+            if (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t) {
+                hot.insert(pixel_of(&(p1 + d1 * s)));
+            }
+        }
+    }
+    hot
+}
+
+/// Build the path from `start` to `end` through the hot pixels which a
+/// segment passes through. Hot pixels are visited in order of the given
+/// parameter along the segment.
+fn route<T: PartialOrd>(
+    start: GridPoint,
+    end: GridPoint,
+    hot_pixels: impl Iterator<Item = (GridPoint, T)>,
+) -> Vec<GridPoint> {
+    let mut interior: Vec<(GridPoint, T)> = hot_pixels
+        .filter(|(h, _)| *h != start && *h != end)
+        .collect();
+    interior.sort_by(|a, b| {
+        a.1.partial_cmp(&b.1)
+            .unwrap_or(core::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+
+    let mut path = Vec::with_capacity(interior.len() + 2);
+    path.push(start);
+    path.extend(interior.into_iter().map(|(h, _)| h));
+    path.push(end);
+    path.dedup();
+    path
+}
+
+/// Add every edge of a path to the set of fragments.
+fn add_fragments(
+    fragments: &mut BTreeSet<(GridPoint, GridPoint)>,
+    path: &[GridPoint],
+) {
+    for pair in path.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if a != b {
+            fragments.insert((a.min(b), a.max(b)));
+        }
+    }
+}
+
+/// Every hot pixel whose closed square could touch the segment `pq`.
+fn hot_pixels_near<'a>(
+    hot: &'a BTreeSet<GridPoint>,
+    p: &Point2<f64>,
+    q: &Point2<f64>,
+) -> impl Iterator<Item = GridPoint> + 'a {
+    let (min_y, max_y) = (p.y.min(q.y) - 0.5, p.y.max(q.y) + 0.5);
+    let min_x = math::floor(p.x.min(q.x) - 0.5) as i64;
+    let max_x = math::floor(p.x.max(q.x) + 0.5) as i64 + 1;
+    hot.range((min_x, i64::MIN)..=(max_x, i64::MAX))
+        .copied()
+        .filter(move |h| (min_y..=max_y).contains(&(h.1 as f64)))
+}
+
+/// Returns true when the segment `pq` touches the closed square pixel
+/// centered at `h`.
+fn passes_through(p: &Point2<f64>, q: &Point2<f64>, h: &GridPoint) -> bool {
+    let (cx, cy) = (h.0 as f64, h.1 as f64);
+    if p.x.max(q.x) < cx - 0.5
+        || p.x.min(q.x) > cx + 0.5
+        || p.y.max(q.y) < cy - 0.5
+        || p.y.min(q.y) > cy + 0.5
+    {
+        return false;
+    }
+    let d = q - p;
+    let sides = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]
+        .map(|(dx, dy)| d.perp(&(point![cx + dx, cy + dy] - p)));
+    !(sides.iter().all(|&s| s > 0.0) || sides.iter().all(|&s| s < 0.0))
+}
+
+/// Exactly determine if an integer segment touches the closed square pixel
+/// centered at `h`.
+fn passes_through_exact(segment: &ISegment, h: &GridPoint) -> bool {
+    // Double every coordinate so the pixel boundaries are integers.
+    let p = (2 * segment.start.x as i128, 2 * segment.start.y as i128);
+    let q = (2 * segment.end.x as i128, 2 * segment.end.y as i128);
+    let (cx, cy) = (2 * h.0 as i128, 2 * h.1 as i128);
+    if p.0.max(q.0) < cx - 1
+        || p.0.min(q.0) > cx + 1
+        || p.1.max(q.1) < cy - 1
+        || p.1.min(q.1) > cy + 1
+    {
+        return false;
+    }
+    let d = (q.0 - p.0, q.1 - p.1);
+    let sides = [(-1, -1), (1, -1), (1, 1), (-1, 1)].map(|(dx, dy)| {
+        let w = (cx + dx - p.0, cy + dy - p.1);
+        (d.0 * w.1 - d.1 * w.0).signum()
+    });
+    !(sides.iter().all(|&s| s > 0) || sides.iter().all(|&s| s < 0))
+}
+
+/// The grid point whose pixel contains `p`. Pixels are half-open, so points
+/// exactly half-way between grid points round towards positive infinity.
+fn pixel_of(p: &Point2<f64>) -> GridPoint {
+    (math::floor(p.x + 0.5) as i64, math::floor(p.y + 0.5) as i64)
+}
+
+fn scaled(p: &Point2<f32>, scale: f64) -> Point2<f64> {
+    point![p.x as f64 * scale, p.y as f64 * scale]
+}
+
+fn to_f64(h: &GridPoint) -> Point2<f64> {
+    point![h.0 as f64, h.1 as f64]
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{
+                intersection::{intersect_all_pairs, SegmentIntersection},
+                Segment,
+            },
+            snap::snap_round,
+        },
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    /// Assert that every endpoint is on the grid and that segments only
+    /// intersect at shared endpoints.
+    fn assert_consistent(segments: &[Segment], grid: f32) {
+        for s in segments {
+            for c in [s.start.x, s.start.y, s.end.x, s.end.y] {
+                assert_eq!((c / grid).round() * grid, c, "{:?} off grid", s);
+            }
+        }
+        for (i, j, intersection) in intersect_all_pairs(segments) {
+            let (a, b) = (segments[i], segments[j]);
+            match intersection {
+                SegmentIntersection::Point(p) => {
+                    let shared = (p == a.start || p == a.end)
+                        && (p == b.start || p == b.end);
+                    assert!(shared, "{:?} and {:?} cross at {:?}", a, b, p);
+                }
+                _ => panic!("{:?} and {:?} overlap", a, b),
+            }
+        }
+    }
+
+    #[test]
+    pub fn crossing_segments_should_be_split_at_the_snapped_crossing() {
+        let segments = [
+            Segment::new(point![0.0, 0.0], point![4.0, 4.0]),
+            Segment::new(point![0.0, 4.0], point![4.0, 0.0]),
+        ];
+        let snapped = snap_round(&segments, 1.0);
+        assert_eq!(
+            snapped,
+            vec![
+                Segment::new(point![0.0, 0.0], point![2.0, 2.0]),
+                Segment::new(point![0.0, 4.0], point![2.0, 2.0]),
+                Segment::new(point![2.0, 2.0], point![4.0, 0.0]),
+                Segment::new(point![2.0, 2.0], point![4.0, 4.0]),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn segments_near_a_hot_pixel_should_be_routed_through_it() {
+        // The horizontal segment passes within half a pixel of the vertical
+        // segment's endpoint, so it must be snapped through that point.
+        let segments = [
+            Segment::new(point![0.0, 0.3], point![10.0, 0.3]),
+            Segment::new(point![5.2, 0.1], point![5.0, 6.0]),
+        ];
+        let snapped = snap_round(&segments, 1.0);
+        assert_eq!(
+            snapped,
+            vec![
+                Segment::new(point![0.0, 0.0], point![5.0, 0.0]),
+                Segment::new(point![5.0, 0.0], point![5.0, 6.0]),
+                Segment::new(point![5.0, 0.0], point![10.0, 0.0]),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn collapsed_and_duplicate_segments_should_be_removed() {
+        let segments = [
+            Segment::new(point![0.1, 0.1], point![0.2, 0.2]),
+            Segment::new(point![0.0, 0.0], point![3.0, 0.0]),
+            Segment::new(point![3.1, 0.1], point![-0.1, 0.2]),
+        ];
+        let snapped = snap_round(&segments, 1.0);
+        assert_eq!(
+            snapped,
+            vec![Segment::new(point![0.0, 0.0], point![3.0, 0.0])]
+        );
+    }
+
+    #[test]
+    pub fn a_dense_arrangement_should_be_consistent() {
+        // A fan of segments which all cross near each other.
+        let segments: Vec<Segment> = (0..12)
+            .map(|i| {
+                let angle = i as f32 * 0.26;
+                let d = nalgebra::vector![angle.cos(), angle.sin()] * 7.3;
+                Segment::new(point![0.13, 0.07] - d, point![0.4, -0.2] + d)
+            })
+            .collect();
+        assert_consistent(&snap_round(&segments, 0.5), 0.5);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn snap_rounding_should_produce_consistent_arrangements(
+            coordinates in prop::collection::vec(
+                (0.0f32..20.0, 0.0f32..20.0, 0.0f32..20.0, 0.0f32..20.0),
+                1..12,
+            )
+        ) {
+            let segments: Vec<Segment> = coordinates
+                .iter()
+                .map(|&(a, b, c, d)| Segment::new(point![a, b], point![c, d]))
+                .collect();
+            assert_consistent(&snap_round(&segments, 1.0), 1.0);
+        }
+    }
+}
@@ -0,0 +1,602 @@
+use {
+    crate::{
+        line::Ray,
+        shape::{Aabb, BoundingBox},
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// Nodes with this many primitives or fewer can become leaves.
+const MAX_LEAF_SIZE: usize = 4;
+
+/// The number of buckets used to estimate the surface area heuristic.
+const BINS: usize = 12;
+
+/// How a [`Bvh`] decides where to split its nodes while it's being built.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Split {
+    /// Choose the split which minimizes the surface area heuristic, an
+    /// estimate of the cost of a query based on the perimeters of the
+    /// children's boxes. This takes longer to build than a median split,
+    /// but usually makes faster trees, especially when the primitives have
+    /// very different sizes or are clustered together.
+    #[default]
+    SurfaceArea,
+
+    /// Split each node in half at the median of its primitives' centers,
+    /// along the axis where the centers are most spread out. This is quick
+    /// to build and always makes a balanced tree.
+    Median,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    bounds: Aabb,
+    contents: Contents,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Contents {
+    /// The node's primitives are `order[start..end]`.
+    Leaf { start: usize, end: usize },
+
+    /// The indices of the node's children in `nodes`.
+    Branch { left: usize, right: usize },
+}
+
+/// A bounding volume hierarchy, a binary tree of nested boxes over any
+/// primitives which have a [`BoundingBox`].
+///
+/// Queries walk down the tree and skip every branch whose box can't
+/// matter, so they take roughly O(log n) time instead of testing every
+/// primitive. The tree only knows about boxes, so the ray and nearest
+/// neighbor queries take a function which does the exact test for a single
+/// primitive. Primitives are referred to by their index in the original
+/// list.
+///
+/// The tree is built once and can't be changed afterwards. Building is
+/// deterministic, so the same primitives always give the same tree. See
+/// [`SegmentIndex`](super::SegmentIndex) for segments which move around.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::{
+///                 intersection::intersect_ray_segment, DistanceToPoint, Ray,
+///                 Segment,
+///             },
+///             spatial::Bvh,
+///         },
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let walls: Vec<Segment> = (0..100)
+///         .map(|i| {
+///             let x = i as f32;
+///             Segment::new(point![x, 0.0], point![x + 0.5, 1.0])
+///         })
+///         .collect();
+///     let bvh = Bvh::new(walls);
+///
+///     let query = point![41.0, -2.0];
+///     let (nearest, distance) = bvh
+///         .closest(&query, |wall| wall.distance_to_point(&query))
+///         .unwrap();
+///     assert_eq!(nearest, 41);
+///     assert_relative_eq!(distance, 2.0);
+///
+///     let ray =
+///         Ray::new(point![-1.0, 0.5], Unit::new_normalize(vector![1.0, 0.0]));
+///     let (hit, t) = bvh
+///         .query_ray(&ray, |wall| intersect_ray_segment(&ray, wall))
+///         .unwrap();
+///     assert_eq!(hit, 0);
+///     assert_relative_eq!(t, 1.25);
+///
+#[derive(Debug, Clone)]
+pub struct Bvh<T> {
+    primitives: Vec<T>,
+    boxes: Vec<Aabb>,
+    nodes: Vec<Node>,
+
+    /// Primitive indices, arranged so each leaf's primitives are next to
+    /// each other.
+    order: Vec<usize>,
+}
+
+impl<T: BoundingBox> Bvh<T> {
+    /// Build a tree over the primitives using the surface area heuristic.
+    pub fn new(primitives: Vec<T>) -> Self {
+        Self::with_split(primitives, Split::SurfaceArea)
+    }
+
+    /// Build a tree over the primitives, choosing how nodes are split.
+    pub fn with_split(primitives: Vec<T>, split: Split) -> Self {
+        let boxes: Vec<Aabb> =
+            primitives.iter().map(BoundingBox::bounding_box).collect();
+        let centers: Vec<Point2<f32>> =
+            boxes.iter().map(Aabb::center).collect();
+        let mut order: Vec<usize> = (0..primitives.len()).collect();
+        let mut nodes = Vec::new();
+
+        let placeholder = Node {
+            bounds: Aabb::empty(),
+            contents: Contents::Leaf { start: 0, end: 0 },
+        };
+        let mut pending = Vec::new();
+        if !primitives.is_empty() {
+            nodes.push(placeholder.clone());
+            pending.push((0, 0, primitives.len()));
+        }
+        while let Some((node, start, end)) = pending.pop() {
+            let bounds = order[start..end]
+                .iter()
+                .fold(Aabb::empty(), |bounds, &i| bounds.merged(&boxes[i]));
+            let parts = Partition {
+                boxes: &boxes,
+                centers: &centers,
+                bounds: &bounds,
+            };
+            let contents = match parts.split(&mut order[start..end], split) {
+                Some(middle) => {
+                    let left = nodes.len();
+                    nodes.extend([placeholder.clone(), placeholder.clone()]);
+                    pending.push((left + 1, start + middle, end));
+                    pending.push((left, start, start + middle));
+                    Contents::Branch {
+                        left,
+                        right: left + 1,
+                    }
+                }
+                None => Contents::Leaf { start, end },
+            };
+            nodes[node] = Node { bounds, contents };
+        }
+
+        Self {
+            primitives,
+            boxes,
+            nodes,
+            order,
+        }
+    }
+}
+
+impl<T> Bvh<T> {
+    /// The number of primitives in the tree.
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    /// Returns true when the tree has no primitives.
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// The primitives, in the order they were provided.
+    pub fn primitives(&self) -> &[T] {
+        &self.primitives
+    }
+
+    /// The indices of every primitive whose bounding box touches a box,
+    /// sorted in increasing order.
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<usize> {
+        self.collect(|bounds| bounds.intersects(aabb))
+    }
+
+    /// The indices of every primitive whose bounding box contains a point,
+    /// sorted in increasing order.
+    pub fn query_point(&self, point: &Point2<f32>) -> Vec<usize> {
+        self.collect(|bounds| bounds.contains_point(point))
+    }
+
+    /// Find the first primitive hit by a ray.
+    ///
+    /// `hit` is called for primitives whose boxes the ray passes through and
+    /// should return the ray parameter `t` where the ray first hits the
+    /// primitive, or `None` when it misses. Hits should be at `t >= 0`.
+    /// Returns the index of the nearest hit and its parameter. Ties are
+    /// resolved in favor of the smallest index.
+    pub fn query_ray(
+        &self,
+        ray: &Ray,
+        hit: impl FnMut(&T) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        self.query_ray_within(ray, f32::INFINITY, hit)
+    }
+
+    /// Find the first primitive hit by a ray within `max_t` of its origin.
+    ///
+    /// This is [`Bvh::query_ray`] for rays with a limited range. Branches
+    /// whose boxes the ray enters beyond `max_t` are skipped, as are hits
+    /// beyond it, so a short ray only visits the part of the tree near its
+    /// origin. Hits at exactly `max_t` are included.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::{
+    ///             line::{intersection::intersect_ray_segment, Ray, Segment},
+    ///             spatial::Bvh,
+    ///         },
+    ///         nalgebra::{point, vector, Unit},
+    ///     };
+    ///
+    ///     let walls: Vec<Segment> = (1..=10)
+    ///         .map(|x| {
+    ///             let x = x as f32 * 10.0;
+    ///             Segment::new(point![x, -1.0], point![x, 1.0])
+    ///         })
+    ///         .collect();
+    ///     let bvh = Bvh::new(walls);
+    ///     let right = Unit::new_normalize(vector![1.0, 0.0]);
+    ///     let ray = Ray::new(point![0.0, 0.0], right);
+    ///     let hit = |wall: &Segment| intersect_ray_segment(&ray, wall);
+    ///
+    ///     assert_eq!(bvh.query_ray_within(&ray, 10.0, hit), Some((0, 10.0)));
+    ///     assert_eq!(bvh.query_ray_within(&ray, 9.0, hit), None);
+    ///
+    pub fn query_ray_within(
+        &self,
+        ray: &Ray,
+        max_t: f32,
+        mut hit: impl FnMut(&T) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let direction = ray.direction.into_inner();
+        let enter = |node: usize| {
+            self.nodes[node]
+                .bounds
+                .slab_parameters(&ray.origin, &direction)
+                .filter(|&(enter, exit)| exit >= 0.0 && enter <= max_t)
+                .map(|(enter, _)| (node, enter.max(0.0)))
+        };
+        self.search(enter, |primitive| {
+            hit(primitive).filter(|&t| !t.is_nan() && t <= max_t)
+        })
+    }
+
+    /// Find the primitive which is closest to a point.
+    ///
+    /// `distance` is called for primitives which might be the closest and
+    /// should return the primitive's distance to the point. Branches are
+    /// skipped when their boxes are further away than the closest primitive
+    /// so far, so the distance to a primitive must never be less than the
+    /// distance to its bounding box. Signed distances which are negative
+    /// inside a shape don't meet this requirement. Returns the index of the
+    /// closest primitive and its distance. Ties are resolved in favor of
+    /// the smallest index.
+    pub fn closest(
+        &self,
+        point: &Point2<f32>,
+        mut distance: impl FnMut(&T) -> f32,
+    ) -> Option<(usize, f32)> {
+        let bound = |node: usize| {
+            let bounds = &self.nodes[node].bounds;
+            let outside = (bounds.min - point)
+                .sup(&(point - bounds.max))
+                .sup(&nalgebra::Vector2::zeros());
+            Some((node, outside.norm()))
+        };
+        self.search(bound, |primitive| {
+            Some(distance(primitive)).filter(|d| !d.is_nan())
+        })
+    }
+
+    /// Branch and bound search for the primitive with the smallest cost.
+    ///
+    /// `bound` gives a lower bound on the cost of every primitive in a node,
+    /// or `None` when the node can be skipped entirely.
+    fn search(
+        &self,
+        bound: impl Fn(usize) -> Option<(usize, f32)>,
+        mut cost: impl FnMut(&T) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let mut best: Option<(f32, usize)> = None;
+        let mut pending: Vec<(usize, f32)> = Vec::new();
+        if !self.nodes.is_empty() {
+            pending.extend(bound(0));
+        }
+        while let Some((node, lower)) = pending.pop() {
+            if best.is_some_and(|(best, _)| lower > best) {
+                continue;
+            }
+            match self.nodes[node].contents {
+                Contents::Leaf { start, end } => {
+                    for &i in &self.order[start..end] {
+                        let Some(c) = cost(&self.primitives[i]) else {
+                            continue;
+                        };
+                        if best.is_none_or(|best| (c, i) < best) {
+                            best = Some((c, i));
+                        }
+                    }
+                }
+                Contents::Branch { left, right } => {
+                    let (left, right) = (bound(left), bound(right));
+                    // Push the further child first, so the nearer one is
+                    // searched first and tightens the bound sooner.
+                    let right_first = right
+                        .is_some_and(|(_, r)| left.is_none_or(|(_, l)| r < l));
+                    if right_first {
+                        pending.extend(left);
+                        pending.extend(right);
+                    } else {
+                        pending.extend(right);
+                        pending.extend(left);
+                    }
+                }
+            }
+        }
+        best.map(|(cost, i)| (i, cost))
+    }
+
+    /// The sorted indices of every primitive whose box overlaps a region.
+    fn collect(&self, overlaps: impl Fn(&Aabb) -> bool) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut pending = Vec::new();
+        if !self.nodes.is_empty() {
+            pending.push(0);
+        }
+        while let Some(node) = pending.pop() {
+            let node = &self.nodes[node];
+            if !overlaps(&node.bounds) {
+                continue;
+            }
+            match node.contents {
+                Contents::Leaf { start, end } => found.extend(
+                    self.order[start..end]
+                        .iter()
+                        .filter(|&&i| overlaps(&self.boxes[i])),
+                ),
+                Contents::Branch { left, right } => {
+                    pending.extend([right, left])
+                }
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+}
+
+/// The information needed to split one node's primitives.
+struct Partition<'a> {
+    boxes: &'a [Aabb],
+    centers: &'a [Point2<f32>],
+    bounds: &'a Aabb,
+}
+
+impl Partition<'_> {
+    /// Rearrange primitive indices so the first `middle` go to the left
+    /// child and the rest go to the right child, returning `middle`.
+    ///
+    /// Returns `None` when the primitives should stay together in a leaf.
+    fn split(&self, order: &mut [usize], split: Split) -> Option<usize> {
+        let count = order.len();
+        let centers = order.iter().fold(Aabb::empty(), |bounds, &i| {
+            bounds.merged(&self.centers[i].bounding_box())
+        });
+        let axis = if centers.width() >= centers.height() {
+            0
+        } else {
+            1
+        };
+        let extent = centers.max[axis] - centers.min[axis];
+
+        if count <= MAX_LEAF_SIZE && (split == Split::Median || extent <= 0.0) {
+            return None;
+        }
+        if extent.is_nan() || extent <= 0.0 {
+            // Every center is the same, so any split is as good as another.
+            return Some(count / 2);
+        }
+        match split {
+            Split::Median => Some(self.median(order, axis)),
+            Split::SurfaceArea => {
+                self.surface_area(order, axis, centers.min[axis], extent)
+            }
+        }
+    }
+
+    /// Split at the median center along an axis, breaking ties by index so
+    /// the result doesn't depend on how the sort works.
+    fn median(&self, order: &mut [usize], axis: usize) -> usize {
+        let middle = order.len() / 2;
+        order.select_nth_unstable_by(middle, |&a, &b| {
+            let (a_center, b_center) =
+                (self.centers[a][axis], self.centers[b][axis]);
+            a_center.total_cmp(&b_center).then(a.cmp(&b))
+        });
+        middle
+    }
+
+    /// Bucket the centers along an axis and split between the buckets with
+    /// the lowest surface area heuristic.
+    fn surface_area(
+        &self,
+        order: &mut [usize],
+        axis: usize,
+        min: f32,
+        extent: f32,
+    ) -> Option<usize> {
+        let bin = |i: usize| {
+            let t = (self.centers[i][axis] - min) / extent;
+            ((t * BINS as f32) as usize).min(BINS - 1)
+        };
+        let mut bins = [(0, Aabb::empty()); BINS];
+        for &i in order.iter() {
+            let (count, bounds) = &mut bins[bin(i)];
+            *count += 1;
+            *bounds = bounds.merged(&self.boxes[i]);
+        }
+        let merge = |(a, a_bounds): (usize, Aabb),
+                     (b, b_bounds): (usize, Aabb)| {
+            (a + b, a_bounds.merged(&b_bounds))
+        };
+        let mut after = bins;
+        for k in (0..BINS - 1).rev() {
+            after[k] = merge(after[k], after[k + 1]);
+        }
+
+        // In 2D the "surface area" of a box is its perimeter. Visiting a
+        // node costs about as much as testing one primitive.
+        let cost = |(count, bounds): (usize, Aabb)| {
+            half_perimeter(&bounds) * count as f32
+        };
+        let mut best: Option<(f32, usize)> = None;
+        let mut before = (0, Aabb::empty());
+        for k in 1..BINS {
+            before = merge(before, bins[k - 1]);
+            if before.0 == 0 || after[k].0 == 0 {
+                continue;
+            }
+            let split =
+                half_perimeter(self.bounds) + cost(before) + cost(after[k]);
+            if best.is_none_or(|(best, _)| split < best) {
+                best = Some((split, k));
+            }
+        }
+
+        let leaf = cost((order.len(), *self.bounds));
+        match best {
+            Some((split, k)) if order.len() > MAX_LEAF_SIZE || split < leaf => {
+                // A stable sort keeps the order deterministic.
+                order.sort_by_key(|&i| bin(i) >= k);
+                Some(order.iter().take_while(|&&i| bin(i) < k).count())
+            }
+            Some(_) => None,
+            None => Some(order.len() / 2),
+        }
+    }
+}
+
+/// Half the perimeter of a box, which is proportional to the chance that a
+/// random line passes through it.
+fn half_perimeter(bounds: &Aabb) -> f32 {
+    bounds.width() + bounds.height()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{Bvh, Split},
+        crate::{
+            line::{
+                intersection::intersect_ray_segment, DistanceToPoint, Segment,
+            },
+            shape::{BoundingBox, Circle},
+            test_support::{aabb, circle, point2, ray, segment},
+        },
+        alloc::vec::Vec,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    fn brute_force<T>(
+        primitives: &[T],
+        mut cost: impl FnMut(&T) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        primitives
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| cost(p).map(|c| (c, i)))
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(c, i)| (i, c))
+    }
+
+    #[test]
+    pub fn empty_trees_should_find_nothing() {
+        let bvh: Bvh<Segment> = Bvh::new(Vec::new());
+
+        assert!(bvh.is_empty());
+        assert!(bvh.query_point(&point![0.0, 0.0]).is_empty());
+        assert_eq!(bvh.closest(&point![0.0, 0.0], |_| 0.0), None);
+    }
+
+    #[test]
+    pub fn identical_primitives_should_still_split() {
+        let circles = vec![Circle::new(point![1.0, 1.0], 1.0); 100];
+
+        let bvh = Bvh::new(circles);
+
+        assert_eq!(bvh.query_point(&point![0.5, 0.5]).len(), 100);
+        assert_eq!(
+            bvh.closest(&point![5.0, 1.0], |c| c
+                .distance_to_point(&point![5.0, 1.0]))
+                .unwrap()
+                .0,
+            0
+        );
+        assert!(bvh.nodes.len() > 1);
+    }
+
+    proptest! {
+        #[test]
+        fn construction_should_be_deterministic(
+            segments in prop::collection::vec(segment(-50.0..=50.0, 0.0), 0..200),
+        ) {
+            for split in [Split::SurfaceArea, Split::Median] {
+                let a = Bvh::with_split(segments.clone(), split);
+                let b = Bvh::with_split(segments.clone(), split);
+
+                prop_assert_eq!(&a.nodes, &b.nodes);
+                prop_assert_eq!(&a.order, &b.order);
+            }
+        }
+
+        #[test]
+        fn segment_queries_should_match_brute_force(
+            segments in prop::collection::vec(segment(-50.0..=50.0, 0.0), 1..200),
+            point in point2(-60.0..=60.0),
+            ray in ray(-60.0..=60.0),
+            bounds in aabb(-60.0..=60.0),
+            median in any::<bool>(),
+            max_t in 0.0f32..=100.0,
+        ) {
+            let split = if median { Split::Median } else { Split::SurfaceArea };
+            let bvh = Bvh::with_split(segments.clone(), split);
+
+            let distance = |s: &Segment| s.distance_to_point(&point);
+            prop_assert_eq!(
+                bvh.closest(&point, distance),
+                brute_force(&segments, |s| Some(distance(s)))
+            );
+
+            let hit = |s: &Segment| intersect_ray_segment(&ray, s);
+            prop_assert_eq!(
+                bvh.query_ray(&ray, hit),
+                brute_force(&segments, hit)
+            );
+            prop_assert_eq!(
+                bvh.query_ray_within(&ray, max_t, hit),
+                brute_force(&segments, hit).filter(|&(_, t)| t <= max_t)
+            );
+
+            let expected: Vec<usize> = (0..segments.len())
+                .filter(|&i| segments[i].bounding_box().intersects(&bounds))
+                .collect();
+            prop_assert_eq!(bvh.query_aabb(&bounds), expected);
+        }
+
+        #[test]
+        fn circle_queries_should_match_brute_force(
+            circles in prop::collection::vec(circle(-50.0..=50.0, 0.0..=20.0), 1..200),
+            point in point2(-60.0..=60.0),
+        ) {
+            let bvh = Bvh::new(circles.clone());
+
+            let distance = |c: &Circle| c.distance_to_point(&point).max(0.0);
+            prop_assert_eq!(
+                bvh.closest(&point, distance),
+                brute_force(&circles, |c| Some(distance(c)))
+            );
+
+            let expected: Vec<usize> = (0..circles.len())
+                .filter(|&i| circles[i].bounding_box().contains_point(&point))
+                .collect();
+            prop_assert_eq!(bvh.query_point(&point), expected);
+        }
+    }
+}
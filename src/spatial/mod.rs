@@ -0,0 +1,18 @@
+//! Spatial indexes for fast queries over collections of geometry.
+//!
+//! A [`SegmentIndex`] buckets segments into a sparse uniform grid. Segments
+//! can be inserted, moved, and removed at any time, and each change only
+//! touches the grid cells the segment covers, so there's never a rebuild.
+//!
+//! A [`Bvh`] is a tree of nested bounding boxes over any type which
+//! implements [`BoundingBox`](crate::shape::BoundingBox). It's built once
+//! for a fixed set of primitives, and leaves the exact tests for each
+//! primitive to the caller.
+
+mod bvh;
+mod segment_index;
+
+pub use self::{
+    bvh::{Bvh, Split},
+    segment_index::{SegmentId, SegmentIndex},
+};
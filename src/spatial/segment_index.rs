@@ -0,0 +1,646 @@
+use {
+    crate::{
+        line::{
+            intersection::intersect_ray_segment_within, DistanceToPoint, Ray,
+            Segment,
+        },
+        math,
+        shape::Aabb,
+    },
+    alloc::{collections::BTreeMap, vec::Vec},
+    nalgebra::Point2,
+};
+
+/// Segments which cover more grid cells than this are kept in a separate
+/// list which every query checks, so one long segment can't flood the grid.
+const MAX_CELLS_PER_SEGMENT: i64 = 64;
+
+/// A handle to a segment in a [`SegmentIndex`].
+///
+/// Handles stay valid until their segment is removed. A handle to a removed
+/// segment never refers to a different segment, even if the index reuses
+/// its storage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SegmentId {
+    slot: usize,
+    generation: u32,
+}
+
+/// A range of grid cells, inclusive on both ends.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Cells {
+    min: (i64, i64),
+    max: (i64, i64),
+}
+
+impl Cells {
+    fn count(&self) -> i64 {
+        (self.max.0 - self.min.0 + 1) * (self.max.1 - self.min.1 + 1)
+    }
+
+    fn iter(self) -> impl Iterator<Item = (i64, i64)> {
+        (self.min.1..=self.max.1)
+            .flat_map(move |y| (self.min.0..=self.max.0).map(move |x| (x, y)))
+    }
+
+    fn contains(&self, (x, y): (i64, i64)) -> bool {
+        self.min.0 <= x && x <= self.max.0 && self.min.1 <= y && y <= self.max.1
+    }
+
+    fn union(&self, other: &Cells) -> Cells {
+        Cells {
+            min: (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    generation: u32,
+    segment: Option<Segment>,
+
+    /// The cells the segment is stored in, or `None` when it's in the
+    /// oversized list.
+    cells: Option<Cells>,
+}
+
+/// A collection of segments which supports nearest neighbor, box, and ray
+/// queries while segments are added, moved, and removed.
+///
+/// Segments are stored in every cell of a uniform grid which their bounding
+/// box touches. Queries only look at the cells near the query, so they're
+/// fast when segments are roughly as long as a cell or shorter. Segments
+/// which would cover more than a few dozen cells, or which aren't finite,
+/// are kept in a list which every query checks in full.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Segment, spatial::SegmentIndex},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let mut index = SegmentIndex::new(1.0);
+///     let floor = index.insert(Segment::new(point![0.0, 0.0], point![9.0, 0.0]));
+///     let wall = index.insert(Segment::new(point![9.0, 0.0], point![9.0, 9.0]));
+///
+///     let (nearest, distance) = index.nearest_segment(&point![8.0, 2.0]).unwrap();
+///     assert_eq!(nearest, wall);
+///     assert_relative_eq!(distance, 1.0);
+///
+///     index.update(wall, Segment::new(point![0.0, 9.0], point![9.0, 9.0]));
+///     let (nearest, _) = index.nearest_segment(&point![8.0, 2.0]).unwrap();
+///     assert_eq!(nearest, floor);
+///
+#[derive(Debug, Clone)]
+pub struct SegmentIndex {
+    cell_size: f32,
+    slots: Vec<Slot>,
+    free: Vec<usize>,
+    grid: BTreeMap<(i64, i64), Vec<usize>>,
+    oversized: Vec<usize>,
+
+    /// A range which contains every occupied cell. It grows as segments are
+    /// inserted and is only reset when the index becomes empty.
+    occupied: Option<Cells>,
+    len: usize,
+}
+
+impl SegmentIndex {
+    /// Create an empty index whose grid cells are squares with sides of
+    /// `cell_size`.
+    ///
+    /// A good cell size is about the length of a typical segment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` isn't positive and finite.
+    pub fn new(cell_size: f32) -> Self {
+        assert!(
+            cell_size > 0.0 && cell_size.is_finite(),
+            "cell size must be positive and finite, got {}",
+            cell_size
+        );
+        Self {
+            cell_size,
+            slots: Vec::new(),
+            free: Vec::new(),
+            grid: BTreeMap::new(),
+            oversized: Vec::new(),
+            occupied: None,
+            len: 0,
+        }
+    }
+
+    /// The number of segments in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true when the index has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a segment to the index.
+    pub fn insert(&mut self, segment: Segment) -> SegmentId {
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    segment: None,
+                    cells: None,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.place(slot, segment);
+        self.len += 1;
+        SegmentId {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    /// Remove a segment from the index, returning it.
+    ///
+    /// Returns `None` when the segment was already removed.
+    pub fn remove(&mut self, id: SegmentId) -> Option<Segment> {
+        self.get(id)?;
+        let segment = self.unplace(id.slot);
+        let slot = &mut self.slots[id.slot];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.slot);
+        self.len -= 1;
+        if self.len == 0 {
+            self.occupied = None;
+        }
+        segment
+    }
+
+    /// Replace a segment in the index, returning the old segment.
+    ///
+    /// The handle stays the same. Returns `None` and changes nothing when
+    /// the segment was already removed.
+    pub fn update(
+        &mut self,
+        id: SegmentId,
+        segment: Segment,
+    ) -> Option<Segment> {
+        self.get(id)?;
+        let old = self.unplace(id.slot);
+        self.place(id.slot, segment);
+        old
+    }
+
+    /// The segment with a handle, or `None` when it was removed.
+    pub fn get(&self, id: SegmentId) -> Option<&Segment> {
+        self.slots
+            .get(id.slot)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.segment.as_ref())
+    }
+
+    /// Iterate over every segment in the index along with its handle.
+    pub fn iter(&self) -> impl Iterator<Item = (SegmentId, &Segment)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(slot, entry)| {
+            let id = SegmentId {
+                slot,
+                generation: entry.generation,
+            };
+            entry.segment.as_ref().map(|segment| (id, segment))
+        })
+    }
+
+    /// Find the segment which is nearest to a point.
+    ///
+    /// Returns the segment's handle and its distance to the point, or `None`
+    /// when the index is empty. Ties are resolved in favor of the smallest
+    /// handle.
+    pub fn nearest_segment(
+        &self,
+        point: &Point2<f32>,
+    ) -> Option<(SegmentId, f32)> {
+        let mut best: Option<(f32, SegmentId)> = None;
+        let consider = |best: &mut Option<(f32, SegmentId)>, slot: usize| {
+            let id = self.id(slot);
+            let segment = self.slots[slot].segment.as_ref().unwrap();
+            let distance_squared = segment.distance_to_point_squared(point);
+            if best.is_none_or(|best| (distance_squared, id) < best) {
+                *best = Some((distance_squared, id));
+            }
+        };
+        for &slot in &self.oversized {
+            consider(&mut best, slot);
+        }
+
+        if let Some(occupied) = self.occupied {
+            let (x, y) = self.cell(point);
+            let reach = [
+                x - occupied.min.0,
+                occupied.max.0 - x,
+                y - occupied.min.1,
+                occupied.max.1 - y,
+            ]
+            .into_iter()
+            .max()
+            .unwrap()
+            .max(0);
+            let searched = (2 * reach + 1).saturating_mul(2 * reach + 1);
+            if searched > 4 * self.grid.len() as i64 {
+                for &slot in self.grid.values().flatten() {
+                    consider(&mut best, slot);
+                }
+            } else {
+                for ring in 0..=reach {
+                    // Every cell in this ring or beyond is at least this far
+                    // from any point in the center cell.
+                    let gap = (ring - 1).max(0) as f32 * self.cell_size;
+                    if best.is_some_and(|(d, _)| d <= gap * gap) {
+                        break;
+                    }
+                    for cell in ring_cells((x, y), ring) {
+                        for &slot in self.grid.get(&cell).into_iter().flatten()
+                        {
+                            consider(&mut best, slot);
+                        }
+                    }
+                }
+            }
+        }
+        best.map(|(distance_squared, id)| (id, math::sqrt(distance_squared)))
+    }
+
+    /// Find every segment which touches a box, sorted by handle.
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<SegmentId> {
+        let touches = |slot: usize| {
+            let segment = self.slots[slot].segment.as_ref().unwrap();
+            aabb.slab_parameters(&segment.start, &(segment.end - segment.start))
+                .is_some_and(|(enter, exit)| enter <= 1.0 && exit >= 0.0)
+        };
+        let mut found: Vec<usize> = self
+            .oversized
+            .iter()
+            .copied()
+            .filter(|&s| touches(s))
+            .collect();
+
+        let query = self.cells(aabb);
+        if let Some(occupied) = self.occupied {
+            if query.count() > 4 * self.grid.len() as i64 {
+                for (cell, slots) in &self.grid {
+                    if query.contains(*cell) {
+                        found.extend(slots.iter().filter(|&&s| touches(s)));
+                    }
+                }
+            } else {
+                for cell in query.iter().filter(|&c| occupied.contains(c)) {
+                    if let Some(slots) = self.grid.get(&cell) {
+                        found.extend(slots.iter().filter(|&&s| touches(s)));
+                    }
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found.into_iter().map(|slot| self.id(slot)).collect()
+    }
+
+    /// Find the first segment hit by a ray.
+    ///
+    /// Returns the segment's handle and the ray parameter of the hit, which
+    /// is also its distance from the ray's origin. The grid is walked cell
+    /// by cell from the ray's origin and the walk stops as soon as a hit is
+    /// closer than the next cell. Ties are resolved in favor of the
+    /// smallest handle.
+    pub fn query_ray(&self, ray: &Ray) -> Option<(SegmentId, f32)> {
+        self.query_ray_within(ray, f32::INFINITY)
+    }
+
+    /// Find the first segment hit by a ray within `max_t` of its origin.
+    ///
+    /// This is [`SegmentIndex::query_ray`] for rays with a limited range.
+    /// The walk through the grid stops at `max_t`, so a short ray only
+    /// visits the cells it passes through. Hits at exactly `max_t` are
+    /// included.
+    pub fn query_ray_within(
+        &self,
+        ray: &Ray,
+        max_t: f32,
+    ) -> Option<(SegmentId, f32)> {
+        let mut best: Option<(f32, SegmentId)> = None;
+        let consider = |best: &mut Option<(f32, SegmentId)>, slot: usize| {
+            let id = self.id(slot);
+            let segment = self.slots[slot].segment.as_ref().unwrap();
+            if let Some(t) = intersect_ray_segment_within(ray, segment, max_t) {
+                if best.is_none_or(|best| (t, id) < best) {
+                    *best = Some((t, id));
+                }
+            }
+        };
+        for &slot in &self.oversized {
+            consider(&mut best, slot);
+        }
+
+        if let Some(occupied) = self.occupied {
+            let size = self.cell_size;
+            let bounds = Aabb::new(
+                Point2::new(occupied.min.0 as f32, occupied.min.1 as f32)
+                    * size,
+                Point2::new(
+                    (occupied.max.0 + 1) as f32,
+                    (occupied.max.1 + 1) as f32,
+                ) * size,
+            );
+            let direction = ray.direction.into_inner();
+            if let Some((enter, exit)) =
+                bounds.slab_parameters(&ray.origin, &direction)
+            {
+                let exit = exit.min(max_t);
+                let start = enter.max(0.0);
+                let mut cell = self.cell(&(ray.origin + direction * start));
+                cell.0 = cell.0.clamp(occupied.min.0, occupied.max.0);
+                cell.1 = cell.1.clamp(occupied.min.1, occupied.max.1);
+
+                let axis = |origin: f32, d: f32, cell: i64| {
+                    if d > 0.0 {
+                        (1, ((cell + 1) as f32 * size - origin) / d, size / d)
+                    } else if d < 0.0 {
+                        (-1, (cell as f32 * size - origin) / d, -size / d)
+                    } else {
+                        (0, f32::INFINITY, f32::INFINITY)
+                    }
+                };
+                let (step_x, mut next_x, delta_x) =
+                    axis(ray.origin.x, direction.x, cell.0);
+                let (step_y, mut next_y, delta_y) =
+                    axis(ray.origin.y, direction.y, cell.1);
+
+                while start <= exit && occupied.contains(cell) {
+                    for &slot in self.grid.get(&cell).into_iter().flatten() {
+                        consider(&mut best, slot);
+                    }
+                    let leave = next_x.min(next_y);
+                    if best.is_some_and(|(t, _)| t <= leave) || leave > exit {
+                        break;
+                    }
+                    if next_x < next_y {
+                        cell.0 += step_x;
+                        next_x += delta_x;
+                    } else {
+                        cell.1 += step_y;
+                        next_y += delta_y;
+                    }
+                }
+            }
+        }
+        best.map(|(t, id)| (id, t))
+    }
+
+    /// The handle for an occupied slot.
+    fn id(&self, slot: usize) -> SegmentId {
+        SegmentId {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    /// The grid cell which contains a point.
+    fn cell(&self, point: &Point2<f32>) -> (i64, i64) {
+        let coordinate = |value: f32| {
+            math::floor(value as f64 / self.cell_size as f64) as i64
+        };
+        (coordinate(point.x), coordinate(point.y))
+    }
+
+    /// The range of grid cells which a box touches.
+    fn cells(&self, aabb: &Aabb) -> Cells {
+        Cells {
+            min: self.cell(&aabb.min),
+            max: self.cell(&aabb.max),
+        }
+    }
+
+    /// Store a segment in an empty slot and in the grid.
+    fn place(&mut self, slot: usize, segment: Segment) {
+        let finite = segment
+            .start
+            .iter()
+            .chain(segment.end.iter())
+            .all(|c| c.is_finite());
+        let cells = finite
+            .then(|| self.cells(&Aabb::new(segment.start, segment.end)))
+            .filter(|cells| cells.count() <= MAX_CELLS_PER_SEGMENT);
+        match cells {
+            Some(cells) => {
+                for cell in cells.iter() {
+                    self.grid.entry(cell).or_default().push(slot);
+                }
+                self.occupied = Some(match self.occupied {
+                    Some(occupied) => occupied.union(&cells),
+                    None => cells,
+                });
+            }
+            None => self.oversized.push(slot),
+        }
+        self.slots[slot].segment = Some(segment);
+        self.slots[slot].cells = cells;
+    }
+
+    /// Take a segment out of its slot and the grid.
+    fn unplace(&mut self, slot: usize) -> Option<Segment> {
+        let entry = &mut self.slots[slot];
+        let (segment, cells) = (entry.segment.take(), entry.cells.take());
+        match cells {
+            Some(cells) => {
+                for cell in cells.iter() {
+                    if let Some(slots) = self.grid.get_mut(&cell) {
+                        slots.retain(|&s| s != slot);
+                        if slots.is_empty() {
+                            self.grid.remove(&cell);
+                        }
+                    }
+                }
+            }
+            None => self.oversized.retain(|&s| s != slot),
+        }
+        segment
+    }
+}
+
+/// The cells on the square ring `ring` cells away from a center cell.
+fn ring_cells(
+    (x, y): (i64, i64),
+    ring: i64,
+) -> impl Iterator<Item = (i64, i64)> {
+    let horizontal = (x - ring..=x + ring)
+        .flat_map(move |cx| [(cx, y - ring), (cx, y + ring)])
+        .take(if ring == 0 { 1 } else { usize::MAX });
+    let vertical = (y - ring + 1..=y + ring - 1)
+        .flat_map(move |cy| [(x - ring, cy), (x + ring, cy)]);
+    horizontal.chain(vertical)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{SegmentId, SegmentIndex},
+        crate::{
+            line::{
+                intersection::intersect_ray_segment, DistanceToPoint, Segment,
+            },
+            test_support::{aabb, point2, ray, segment},
+        },
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    #[derive(Debug, Clone)]
+    enum Operation {
+        Insert(Segment),
+        Remove(usize),
+        Update(usize, Segment),
+        Query(crate::line::Ray, crate::shape::Aabb),
+    }
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        let segment = segment(-20.0..=20.0, 0.0);
+        prop_oneof![
+            3 => segment.clone().prop_map(Operation::Insert),
+            1 => any::<usize>().prop_map(Operation::Remove),
+            1 => (any::<usize>(), segment).prop_map(|(i, s)| Operation::Update(i, s)),
+            2 => (ray(-25.0..=25.0), aabb(-25.0..=25.0))
+                .prop_map(|(r, b)| Operation::Query(r, b)),
+        ]
+    }
+
+    #[test]
+    pub fn removed_handles_should_stay_invalid() {
+        let mut index = SegmentIndex::new(1.0);
+        let segment = Segment::new(point![0.0, 0.0], point![1.0, 1.0]);
+
+        let first = index.insert(segment);
+        assert_eq!(index.remove(first), Some(segment));
+        let second = index.insert(segment);
+
+        assert_ne!(first, second);
+        assert_eq!(index.get(first), None);
+        assert_eq!(index.remove(first), None);
+        assert_eq!(index.update(first, segment), None);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    pub fn empty_indexes_should_find_nothing() {
+        let mut index = SegmentIndex::new(2.0);
+        let id = index.insert(Segment::new(point![0.0, 0.0], point![1.0, 1.0]));
+        index.remove(id);
+
+        assert!(index.is_empty());
+        assert_eq!(index.nearest_segment(&point![0.0, 0.0]), None);
+        assert_eq!(index.query_ray(&ray_right()), None);
+    }
+
+    fn ray_right() -> crate::line::Ray {
+        crate::line::Ray::new(
+            point![-5.0, 0.5],
+            nalgebra::Unit::new_normalize(nalgebra::vector![1.0, 0.0]),
+        )
+    }
+
+    #[test]
+    pub fn long_and_far_segments_should_be_found() {
+        let mut index = SegmentIndex::new(0.5);
+        let long = index
+            .insert(Segment::new(point![-1000.0, 0.0], point![1000.0, 0.0]));
+        let far =
+            index.insert(Segment::new(point![1e6, 1e6], point![1e6, 2e6]));
+
+        assert_eq!(index.nearest_segment(&point![3.0, 2.0]).unwrap().0, long);
+        assert_eq!(index.nearest_segment(&point![1e6, 3e6]).unwrap().0, far);
+        assert_eq!(index.query_ray(&ray_right()), None);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn queries_should_match_a_brute_force_reference(
+            operations in prop::collection::vec(operation(), 100..300),
+            points in prop::collection::vec(point2(-30.0..=30.0), 4),
+        ) {
+            let mut index = SegmentIndex::new(1.5);
+            let mut reference: Vec<(SegmentId, Segment)> = Vec::new();
+
+            for operation in operations {
+                match operation {
+                    Operation::Insert(segment) => {
+                        reference.push((index.insert(segment), segment));
+                    }
+                    Operation::Remove(i) if !reference.is_empty() => {
+                        let (id, segment) =
+                            reference.swap_remove(i % reference.len());
+                        prop_assert_eq!(index.remove(id), Some(segment));
+                    }
+                    Operation::Update(i, segment) if !reference.is_empty() => {
+                        let i = i % reference.len();
+                        let old = index.update(reference[i].0, segment);
+                        prop_assert_eq!(old, Some(reference[i].1));
+                        reference[i].1 = segment;
+                    }
+                    Operation::Query(ray, bounds) => {
+                        for p in &points {
+                            let expected = reference
+                                .iter()
+                                .map(|(_, s)| s.distance_to_point(p))
+                                .fold(f32::INFINITY, f32::min);
+                            match index.nearest_segment(p) {
+                                Some((id, distance)) => {
+                                    assert_relative_eq!(distance, expected);
+                                    let segment = index.get(id).unwrap();
+                                    assert_relative_eq!(
+                                        segment.distance_to_point(p),
+                                        expected
+                                    );
+                                }
+                                None => prop_assert!(reference.is_empty()),
+                            }
+                        }
+
+                        let expected = reference
+                            .iter()
+                            .filter_map(|(_, s)| intersect_ray_segment(&ray, s))
+                            .fold(f32::INFINITY, f32::min);
+                        match index.query_ray(&ray) {
+                            Some((_, t)) => assert_relative_eq!(t, expected),
+                            None => prop_assert_eq!(expected, f32::INFINITY),
+                        }
+                        match index.query_ray_within(&ray, 10.0) {
+                            Some((_, t)) => assert_relative_eq!(t, expected),
+                            None => prop_assert!(expected > 10.0),
+                        }
+
+                        let mut expected: Vec<SegmentId> = reference
+                            .iter()
+                            .filter(|(_, s)| {
+                                bounds
+                                    .slab_parameters(&s.start, &(s.end - s.start))
+                                    .is_some_and(|(a, b)| a <= 1.0 && b >= 0.0)
+                            })
+                            .map(|(id, _)| *id)
+                            .collect();
+                        expected.sort();
+                        prop_assert_eq!(index.query_aabb(&bounds), expected);
+                    }
+                    _ => {}
+                }
+                prop_assert_eq!(index.len(), reference.len());
+            }
+        }
+    }
+}
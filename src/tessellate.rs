@@ -0,0 +1,413 @@
+//! Cover a box with tiles: squares, triangles, or hexagons.
+//!
+//! Every tile's vertices are computed from integer lattice coordinates
+//! rather than accumulated offsets, so tiles which meet at a vertex share
+//! exactly the same point and the tiling has no cracks. Each generator takes
+//! an optional clip polygon. Tiles entirely inside it are kept as they are,
+//! tiles crossing its boundary are cut down to the part inside it, and tiles
+//! outside it are dropped, along with slivers cut off with less than a
+//! millionth of a tile's area.
+
+use {
+    crate::{
+        math,
+        polygon::{clip_to_half_plane, Polygon},
+        shape::{Aabb, BoundingBox},
+    },
+    alloc::vec::Vec,
+    nalgebra::{point, Point2},
+};
+
+/// Which way hexagons point in [`hexagons`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HexOrientation {
+    /// A vertex at the top and bottom, with rows of hexagons running along
+    /// the x axis.
+    PointyTop,
+
+    /// A flat edge at the top and bottom, with columns of hexagons running
+    /// along the y axis.
+    FlatTop,
+}
+
+/// Cover a box with a grid of squares.
+///
+/// The squares have sides `size` long and the first one has its lower left
+/// corner at the box's lower left corner. Every square which overlaps the
+/// box with some area is included, so squares on the far sides may stick
+/// out of the box. Squares are counterclockwise and run along the x axis,
+/// then along the y axis.
+///
+/// When `clip` is given, tiles are clipped to it as described in the
+/// [module documentation](self). Returns no tiles when `size` isn't
+/// positive and finite.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{shape::Aabb, tessellate::squares},
+///         nalgebra::point,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![3.0, 2.0]);
+///
+///     let tiles = squares(&bounds, 1.0, None);
+///
+///     assert_eq!(tiles.len(), 6);
+///     assert_eq!(tiles[1].vertices[0], point![1.0, 0.0]);
+///
+pub fn squares(
+    bounds: &Aabb,
+    size: f32,
+    clip: Option<&Polygon>,
+) -> Vec<Polygon> {
+    let Some((columns, rows)) = lattice_extent(bounds, size, size) else {
+        return Vec::new();
+    };
+    let candidates = (0..rows).flat_map(|y| {
+        (0..columns)
+            .map(move |x| [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)])
+    });
+    tiles(bounds, size, size, clip, candidates)
+}
+
+/// Cover a box with equilateral triangles.
+///
+/// The triangles have sides `size` long, with rows along the x axis and
+/// the first row's bottom edge along the bottom of the box. Every triangle
+/// which overlaps the box with some area is included. Triangles are
+/// counterclockwise.
+///
+/// When `clip` is given, tiles are clipped to it as described in the
+/// [module documentation](self). Returns no tiles when `size` isn't
+/// positive and finite.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{shape::Aabb, tessellate::triangles},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![4.0, 3.0]);
+///
+///     let tiles = triangles(&bounds, 1.0, None);
+///
+///     let area = 3.0f32.sqrt() / 4.0;
+///     for tile in &tiles {
+///         assert_eq!(tile.vertices.len(), 3);
+///         assert_relative_eq!(tile.signed_area(), area, epsilon = 1e-5);
+///     }
+///
+pub fn triangles(
+    bounds: &Aabb,
+    size: f32,
+    clip: Option<&Polygon>,
+) -> Vec<Polygon> {
+    let step = (size / 2.0, size * math::sqrt(3.0) / 2.0);
+    let Some((columns, rows)) = lattice_extent(bounds, step.0, step.1) else {
+        return Vec::new();
+    };
+    let candidates = (0..rows).flat_map(move |y| {
+        // Lattice points on a row have the row's parity.
+        let shift = y % 2;
+        (-1..=columns / 2).flat_map(move |column| {
+            let x = 2 * column + shift;
+            let up = [(x, y), (x + 2, y), (x + 1, y + 1)];
+            let down = [(x + 2, y), (x + 3, y + 1), (x + 1, y + 1)];
+            [up.to_vec(), down.to_vec()]
+        })
+    });
+    tiles(bounds, step.0, step.1, clip, candidates)
+}
+
+/// Cover a box with regular hexagons.
+///
+/// The hexagons have vertices `circumradius` from their centers, and the
+/// first one is centered on the box's lower left corner. Every hexagon
+/// which overlaps the box with some area is included. Hexagons are
+/// counterclockwise, starting at their lowest vertex for
+/// [`HexOrientation::PointyTop`] and at their rightmost vertex for
+/// [`HexOrientation::FlatTop`].
+///
+/// When `clip` is given, tiles are clipped to it as described in the
+/// [module documentation](self). Returns no tiles when `circumradius` isn't
+/// positive and finite.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             shape::Aabb,
+///             tessellate::{hexagons, HexOrientation},
+///         },
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let bounds = Aabb::new(point![0.0, 0.0], point![5.0, 5.0]);
+///
+///     let tiles = hexagons(&bounds, 1.0, HexOrientation::FlatTop, None);
+///
+///     let area = 1.5 * 3.0f32.sqrt();
+///     for tile in &tiles {
+///         assert_eq!(tile.vertices.len(), 6);
+///         assert_relative_eq!(tile.signed_area(), area, epsilon = 1e-4);
+///     }
+///
+pub fn hexagons(
+    bounds: &Aabb,
+    circumradius: f32,
+    orientation: HexOrientation,
+    clip: Option<&Polygon>,
+) -> Vec<Polygon> {
+    // Measured in lattice steps, centers in a row are two steps apart,
+    // rows are three steps apart, and every other row is shifted by one
+    // step.
+    let half = circumradius / 2.0;
+    let apothem = circumradius * math::sqrt(3.0) / 2.0;
+    let pointy = orientation == HexOrientation::PointyTop;
+    let (across, along) = if pointy {
+        (apothem, half)
+    } else {
+        (half, apothem)
+    };
+    let Some((columns, rows)) = lattice_extent(bounds, across, along) else {
+        return Vec::new();
+    };
+    let (rows_extent, columns_extent) = if pointy {
+        (rows, columns)
+    } else {
+        (columns, rows)
+    };
+    let candidates = (-1..=rows_extent / 3 + 1).flat_map(move |row| {
+        (-1..=columns_extent / 2 + 1).map(move |column| {
+            let (a, b) = (2 * column + row % 2, 3 * row);
+            let offsets = [(0, -2), (1, -1), (1, 1), (0, 2), (-1, 1), (-1, -1)];
+            offsets.map(|(da, db)| {
+                if pointy {
+                    (a + da, b + db)
+                } else {
+                    (b - db, a + da)
+                }
+            })
+        })
+    });
+    tiles(bounds, across, along, clip, candidates)
+}
+
+/// The number of lattice steps needed to cover the box along each axis, or
+/// nothing when a step isn't positive and finite.
+fn lattice_extent(bounds: &Aabb, dx: f32, dy: f32) -> Option<(i32, i32)> {
+    let valid = |step: f32| step.is_finite() && step > 0.0;
+    if !valid(dx) || !valid(dy) {
+        return None;
+    }
+    let steps = |length: f32, step: f32| math::ceil(length / step) as i32;
+    Some((steps(bounds.width(), dx), steps(bounds.height(), dy)))
+}
+
+/// Build the candidate tiles from their lattice coordinates, keep the ones
+/// overlapping the box, and clip them.
+fn tiles<T>(
+    bounds: &Aabb,
+    dx: f32,
+    dy: f32,
+    clip: Option<&Polygon>,
+    candidates: impl Iterator<Item = T>,
+) -> Vec<Polygon>
+where
+    T: AsRef<[(i32, i32)]>,
+{
+    let at = |(x, y): (i32, i32)| {
+        point![bounds.min.x + x as f32 * dx, bounds.min.y + y as f32 * dy]
+    };
+    let clip_bounds = clip.map(|clip| clip.bounding_box());
+    candidates
+        .map(|corners| {
+            Polygon::new(corners.as_ref().iter().map(|&c| at(c)).collect())
+        })
+        .filter(|tile| overlaps(&tile.bounding_box(), bounds))
+        .filter_map(|tile| match (clip, &clip_bounds) {
+            (Some(clip), Some(clip_bounds)) => {
+                if !overlaps(&tile.bounding_box(), clip_bounds) {
+                    return None;
+                }
+                clip_tile(tile, clip)
+            }
+            _ => Some(tile),
+        })
+        .collect()
+}
+
+/// True when the boxes overlap with some area, not just along an edge.
+fn overlaps(a: &Aabb, b: &Aabb) -> bool {
+    a.min.x < b.max.x
+        && b.min.x < a.max.x
+        && a.min.y < b.max.y
+        && b.min.y < a.max.y
+}
+
+/// The part of the clip polygon inside the tile, or the tile itself when
+/// it's entirely inside the clip polygon so its shared vertices stay exact.
+fn clip_tile(tile: Polygon, clip: &Polygon) -> Option<Polygon> {
+    let mut clipped = clip.vertices.clone();
+    for (i, &a) in tile.vertices.iter().enumerate() {
+        let b = tile.vertices[(i + 1) % tile.vertices.len()];
+        if a == b {
+            continue;
+        }
+        // The tile is counterclockwise, so its inside is to the left.
+        clipped = clip_to_half_plane(&clipped, |p: &Point2<f32>| {
+            (b - a).perp(&(p - a))
+        });
+        if clipped.is_empty() {
+            return None;
+        }
+    }
+    clipped.dedup();
+    if clipped.len() > 1 && clipped.first() == clipped.last() {
+        clipped.pop();
+    }
+
+    let mut piece = Polygon::new(clipped);
+    let area = piece.signed_area();
+    let tile_area = tile.signed_area();
+    // Slivers along the clip region's edges have so little area that
+    // rounding can flip its sign, so they're dropped.
+    if piece.vertices.len() < 3 || area.abs() <= tile_area * 1e-6 {
+        None
+    } else if area.abs() >= tile_area * (1.0 - 1e-5) {
+        Some(tile)
+    } else {
+        if area < 0.0 {
+            piece.vertices.reverse();
+        }
+        Some(piece)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{hexagons, squares, triangles, HexOrientation},
+        crate::{polygon::Polygon, shape::Aabb, test_support::polygon},
+        alloc::vec::Vec,
+        approx::assert_relative_eq,
+        nalgebra::point,
+        proptest::prelude::*,
+    };
+
+    fn bounds() -> Aabb {
+        Aabb::new(point![0.0, 0.0], point![10.0, 10.0])
+    }
+
+    fn total_area(tiles: &[Polygon]) -> f32 {
+        tiles.iter().map(Polygon::signed_area).sum()
+    }
+
+    fn shared_vertices(a: &Polygon, b: &Polygon) -> usize {
+        a.vertices
+            .iter()
+            .filter(|p| b.vertices.iter().any(|q| p.coords == q.coords))
+            .count()
+    }
+
+    #[test]
+    pub fn squares_should_cover_the_bounds() {
+        let tiles = squares(&bounds(), 0.3, None);
+
+        // 34 squares are needed to reach past 10 along each axis.
+        assert_eq!(tiles.len(), 34 * 34);
+        assert_relative_eq!(
+            total_area(&tiles),
+            0.3 * 0.3 * 34.0 * 34.0,
+            max_relative = 1e-4
+        );
+        assert!(squares(&bounds(), 0.0, None).is_empty());
+        assert!(squares(&bounds(), f32::NAN, None).is_empty());
+        assert!(
+            hexagons(&bounds(), -1.0, HexOrientation::FlatTop, None).is_empty()
+        );
+    }
+
+    #[test]
+    pub fn adjacent_hexagons_should_share_exactly_two_vertices() {
+        for orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop]
+        {
+            let radius = 0.7;
+            let tiles = hexagons(&bounds(), radius, orientation, None);
+            let centers: Vec<_> = tiles
+                .iter()
+                .map(|tile| {
+                    tile.vertices
+                        .iter()
+                        .map(|p| p.coords)
+                        .sum::<nalgebra::Vector2<f32>>()
+                        / 6.0
+                })
+                .collect();
+
+            let mut neighbors = 0;
+            for (i, a) in tiles.iter().enumerate() {
+                for (j, b) in tiles.iter().enumerate().skip(i + 1) {
+                    let distance = (centers[i] - centers[j]).norm();
+                    if distance < 2.0 * radius {
+                        assert_eq!(shared_vertices(a, b), 2);
+                        neighbors += 1;
+                    } else {
+                        assert_eq!(shared_vertices(a, b), 0);
+                    }
+                }
+            }
+            assert!(neighbors > tiles.len());
+        }
+    }
+
+    #[test]
+    pub fn tiles_inside_the_clip_should_be_kept_whole() {
+        let clip = Polygon::new(vec![
+            point![0.5, 0.5],
+            point![3.5, 0.5],
+            point![3.5, 3.5],
+            point![0.5, 3.5],
+        ]);
+
+        let tiles = squares(&bounds(), 1.0, Some(&clip));
+
+        assert_eq!(tiles.len(), 16);
+        let whole = tiles.iter().filter(|tile| tile.area() == 1.0).count();
+        assert_eq!(whole, 4);
+        assert_relative_eq!(total_area(&tiles), 9.0, epsilon = 1e-5);
+    }
+
+    proptest! {
+        #[test]
+        fn clipped_tiles_should_add_up_to_the_clip_area(
+            clip in polygon(1.0..=9.0, 3..=8, false),
+            size in 0.3f32..=2.0,
+        ) {
+            let expected = clip.area();
+            let orientations =
+                [HexOrientation::PointyTop, HexOrientation::FlatTop];
+            let mut tilings = vec![
+                squares(&bounds(), size, Some(&clip)),
+                triangles(&bounds(), size, Some(&clip)),
+            ];
+            for orientation in orientations {
+                tilings.push(
+                    hexagons(&bounds(), size, orientation, Some(&clip)),
+                );
+            }
+
+            for tiles in tilings {
+                for tile in &tiles {
+                    prop_assert!(tile.signed_area() > 0.0);
+                }
+                let area = total_area(&tiles);
+                prop_assert!((area - expected).abs() <= expected * 1e-3);
+            }
+        }
+    }
+}
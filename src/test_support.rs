@@ -0,0 +1,334 @@
+//! Proptest strategies for generating random geometry.
+//!
+//! These strategies are used by the crate's own property tests and are
+//! exported so downstream crates can reuse them for fuzzing algorithms built
+//! on top of compgeo.
+//!
+//! This module is only available when the `proptest-support` feature is
+//! enabled.
+//!
+//! # Example
+//!
+//!     use ::{
+//!         compgeo::{line::Segment, test_support::segment},
+//!         proptest::prelude::*,
+//!     };
+//!
+//!     proptest!(|(s in segment(-10.0..=10.0, 0.5))| {
+//!         prop_assert!(s.length() >= 0.5);
+//!     });
+//!
+
+use {
+    crate::{
+        line::{Line, Ray, Segment},
+        polygon::Polygon,
+        shape::{Aabb, Circle},
+    },
+    core::ops::RangeInclusive,
+    nalgebra::{point, vector, Point2, Unit, Vector2},
+    proptest::{
+        arbitrary::Arbitrary,
+        strategy::{BoxedStrategy, Strategy},
+    },
+};
+
+/// The coordinate range used by the `Arbitrary` implementations.
+pub const DEFAULT_COORDINATE_RANGE: RangeInclusive<f32> = -100.0..=100.0;
+
+/// Generate points with both coordinates in the given range.
+pub fn point2(
+    range: RangeInclusive<f32>,
+) -> impl Strategy<Value = Point2<f32>> + Clone {
+    (range.clone(), range).prop_map(|(x, y)| point![x, y])
+}
+
+/// Generate unit vectors pointing in any direction.
+pub fn unit_vector() -> impl Strategy<Value = Unit<Vector2<f32>>> + Clone {
+    (0.0..core::f32::consts::TAU).prop_map(|angle: f32| {
+        Unit::new_normalize(vector![angle.cos(), angle.sin()])
+    })
+}
+
+/// Generate segments whose endpoints lie in the given range.
+///
+/// Every generated segment is at least `min_length` long. Segments are
+/// generated by rejection, so `min_length` should be small relative to the
+/// size of the coordinate range.
+pub fn segment(
+    range: RangeInclusive<f32>,
+    min_length: f32,
+) -> impl Strategy<Value = Segment> + Clone {
+    (point2(range.clone()), point2(range))
+        .prop_map(|(start, end)| Segment::new(start, end))
+        .prop_filter("segment is shorter than the minimum length", move |s| {
+            s.length() >= min_length
+        })
+}
+
+/// Generate rays with an origin in the given range.
+pub fn ray(range: RangeInclusive<f32>) -> impl Strategy<Value = Ray> + Clone {
+    (point2(range), unit_vector())
+        .prop_map(|(origin, direction)| Ray::new(origin, direction))
+}
+
+/// Generate lines which pass through a point in the given range.
+pub fn line(range: RangeInclusive<f32>) -> impl Strategy<Value = Line> + Clone {
+    ray(range).prop_map(Line::from)
+}
+
+/// Generate axis-aligned boxes with corners in the given range.
+pub fn aabb(range: RangeInclusive<f32>) -> impl Strategy<Value = Aabb> + Clone {
+    (point2(range.clone()), point2(range)).prop_map(|(a, b)| Aabb::new(a, b))
+}
+
+/// Generate circles with a center in the given range.
+pub fn circle(
+    range: RangeInclusive<f32>,
+    radius: RangeInclusive<f32>,
+) -> impl Strategy<Value = Circle> + Clone {
+    (point2(range), radius).prop_map(|(center, r)| Circle::new(center, r))
+}
+
+/// Generate simple polygons with vertices in the given coordinate range.
+///
+/// Polygons are generated by sorting random angles around the center of the
+/// range, so every polygon is star-shaped (and therefore simple) and wound
+/// counterclockwise. When `convex` is true, every vertex lies on the same
+/// circle which makes the polygon convex.
+///
+/// # Panics
+///
+/// Panics when `vertex_count` allows fewer than three vertices, since no
+/// simple polygon has fewer.
+pub fn polygon(
+    range: RangeInclusive<f32>,
+    vertex_count: RangeInclusive<usize>,
+    convex: bool,
+) -> impl Strategy<Value = Polygon> + Clone {
+    assert!(
+        *vertex_count.start() >= 3,
+        "polygons need at least 3 vertices, but the vertex count range is {:?}",
+        vertex_count
+    );
+    let center = (range.start() + range.end()) * 0.5;
+    let radius = (range.end() - range.start()) * 0.5;
+    let min_radius = if convex { 1.0 } else { 0.1 };
+    let vertex = (0.0..core::f32::consts::TAU, min_radius..=1.0f32);
+    proptest::collection::vec(vertex, vertex_count)
+        .prop_map(move |mut vertices| {
+            vertices.sort_by(|a, b| a.0.total_cmp(&b.0));
+            vertices
+        })
+        .prop_filter("polygon vertices are too close together", |vertices| {
+            vertices.windows(2).all(|pair| pair[1].0 - pair[0].0 > 1e-3)
+        })
+        .prop_filter("polygon does not surround its center", |vertices| {
+            // Every angular gap (including the one which wraps around) must be
+            // less than pi for the polygon to be star-shaped from the center.
+            let first = vertices[0].0 + core::f32::consts::TAU;
+            let last = vertices[vertices.len() - 1].0;
+            first - last < core::f32::consts::PI
+                && vertices
+                    .windows(2)
+                    .all(|pair| pair[1].0 - pair[0].0 < core::f32::consts::PI)
+        })
+        .prop_map(move |vertices| {
+            Polygon::new(
+                vertices
+                    .iter()
+                    .map(|(angle, r)| {
+                        let r = r * radius;
+                        point![
+                            center + r * angle.cos(),
+                            center + r * angle.sin()
+                        ]
+                    })
+                    .collect(),
+            )
+        })
+}
+
+/// A counterclockwise axis-aligned rectangle with the given corners.
+///
+/// Unlike the other functions in this module, this is a fixed fixture rather
+/// than a strategy. It saves tests from spelling out the same four vertices.
+pub fn rectangle(min: Point2<f32>, max: Point2<f32>) -> Polygon {
+    Polygon::new(Aabb::new(min, max).corners().to_vec())
+}
+
+/// Parameters for generating arbitrary segments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentParameters {
+    /// The range for both coordinates of both endpoints.
+    pub coordinate_range: RangeInclusive<f32>,
+
+    /// The minimum length of a generated segment.
+    pub min_length: f32,
+}
+
+impl Default for SegmentParameters {
+    fn default() -> Self {
+        Self {
+            coordinate_range: DEFAULT_COORDINATE_RANGE,
+            min_length: 0.0,
+        }
+    }
+}
+
+/// Parameters for generating arbitrary polygons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolygonParameters {
+    /// The range for both coordinates of every vertex.
+    pub coordinate_range: RangeInclusive<f32>,
+
+    /// The range for the number of vertices.
+    pub vertex_count: RangeInclusive<usize>,
+
+    /// When true, only convex polygons are generated.
+    pub convex: bool,
+}
+
+impl Default for PolygonParameters {
+    fn default() -> Self {
+        Self {
+            coordinate_range: DEFAULT_COORDINATE_RANGE,
+            vertex_count: 3..=16,
+            convex: false,
+        }
+    }
+}
+
+impl Arbitrary for Polygon {
+    type Parameters = PolygonParameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(parameters: Self::Parameters) -> Self::Strategy {
+        polygon(
+            parameters.coordinate_range,
+            parameters.vertex_count,
+            parameters.convex,
+        )
+        .boxed()
+    }
+}
+
+impl Arbitrary for Aabb {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        aabb(DEFAULT_COORDINATE_RANGE).boxed()
+    }
+}
+
+impl Arbitrary for Circle {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        circle(DEFAULT_COORDINATE_RANGE, 0.0..=100.0).boxed()
+    }
+}
+
+impl Arbitrary for Segment {
+    type Parameters = SegmentParameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(parameters: Self::Parameters) -> Self::Strategy {
+        segment(parameters.coordinate_range, parameters.min_length).boxed()
+    }
+}
+
+impl Arbitrary for Ray {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        ray(DEFAULT_COORDINATE_RANGE).boxed()
+    }
+}
+
+impl Arbitrary for Line {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        line(DEFAULT_COORDINATE_RANGE).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{Ray, Segment},
+            test_support::{line, polygon, segment, SegmentParameters},
+        },
+        approx::assert_relative_eq,
+        proptest::prelude::*,
+    };
+
+    #[test]
+    #[should_panic(expected = "polygons need at least 3 vertices")]
+    pub fn polygons_should_reject_vertex_counts_below_three() {
+        let _ = polygon(-1.0..=1.0, 0..=5, false);
+    }
+
+    proptest! {
+        #[test]
+        fn segments_should_respect_the_coordinate_range(
+            s in segment(-1.0..=1.0, 0.0)
+        ) {
+            for p in [s.start, s.end] {
+                prop_assert!((-1.0..=1.0).contains(&p.x));
+                prop_assert!((-1.0..=1.0).contains(&p.y));
+            }
+        }
+
+        #[test]
+        fn arbitrary_segments_should_respect_the_parameters(
+            s in any_with::<Segment>(SegmentParameters {
+                coordinate_range: 0.0..=10.0,
+                min_length: 2.0,
+            })
+        ) {
+            prop_assert!(s.length() >= 2.0);
+        }
+
+        #[test]
+        fn arbitrary_rays_should_have_unit_directions(r in any::<Ray>()) {
+            assert_relative_eq!(r.direction.norm(), 1.0, epsilon = 1e-6);
+        }
+
+        #[test]
+        fn polygons_should_be_counterclockwise_and_in_range(
+            p in polygon(-10.0..=10.0, 3..=8, false)
+        ) {
+            prop_assert!((3..=8).contains(&p.vertices.len()));
+            prop_assert!(p.signed_area() > 0.0);
+            for v in &p.vertices {
+                prop_assert!((-10.0..=10.0).contains(&v.x));
+                prop_assert!((-10.0..=10.0).contains(&v.y));
+            }
+        }
+
+        #[test]
+        fn convex_polygons_should_only_turn_left(
+            p in polygon(-10.0..=10.0, 3..=8, true)
+        ) {
+            let n = p.vertices.len();
+            for i in 0..n {
+                let a = p.vertices[i];
+                let b = p.vertices[(i + 1) % n];
+                let c = p.vertices[(i + 2) % n];
+                prop_assert!((b - a).perp(&(c - b)) >= -1e-4);
+            }
+        }
+
+        #[test]
+        fn lines_should_have_unit_normals(l in line(-5.0..=5.0)) {
+            assert_relative_eq!(l.normal.norm(), 1.0, epsilon = 1e-6);
+        }
+    }
+}
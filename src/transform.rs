@@ -0,0 +1,445 @@
+//! Geometric transformations which map points, lines, and circles to each
+//! other.
+//!
+//! # Circle Inversion
+//!
+//! Inversion in a circle with center `O` and radius `r` maps each point `P`
+//! to the point `P'` on the ray from `O` through `P` where
+//! `|OP| * |OP'| = r^2`. Points inside the circle map outside and vice versa,
+//! and points on the circle stay where they are. Inversion is its own
+//! inverse, so inverting twice returns the original geometry.
+//!
+//! Circles and lines map to circles and lines: anything which passes through
+//! `O` becomes a line, and anything which doesn't becomes a circle.
+//!
+//! # Duality
+//!
+//! The duality functions use polarity with respect to the unit circle at the
+//! origin. The point `(a, b)` corresponds to the line `a*x + b*y = 1`.
+//! A point lies on a line exactly when the line's dual point lies on the
+//! point's dual line, so incidence problems about points can be rewritten as
+//! problems about lines. The origin and lines through the origin have no
+//! dual.
+
+use {
+    crate::{
+        line::{DistanceToPoint, Line},
+        shape::Circle,
+    },
+    nalgebra::{Point2, Unit, Vector2},
+};
+
+/// The result of inverting a circle or a line.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InversionResult {
+    /// The input didn't pass through the center of inversion.
+    Circle(Circle),
+
+    /// The input passed through the center of inversion.
+    Line(Line),
+}
+
+/// Invert a point in a circle.
+///
+/// Returns `None` for the circle's center, which maps to infinity.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{shape::Circle, transform::invert_point},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let circle = Circle::new(point![0.0, 0.0], 2.0);
+///
+///     assert_relative_eq!(
+///         invert_point(&point![1.0, 0.0], &circle).unwrap(),
+///         point![4.0, 0.0]
+///     );
+///     assert_eq!(invert_point(&point![0.0, 0.0], &circle), None);
+///
+pub fn invert_point(
+    point: &Point2<f32>,
+    circle: &Circle,
+) -> Option<Point2<f32>> {
+    let offset = point - circle.center;
+    let distance_squared = offset.norm_squared();
+    if distance_squared == 0.0 {
+        return None;
+    }
+    let scale = circle.radius * circle.radius / distance_squared;
+    Some(circle.center + offset * scale)
+}
+
+/// Invert a circle in another circle.
+///
+/// The result is a line when `c` passes through the center of `circle`, and
+/// a circle otherwise.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::DistanceToPoint,
+///             shape::Circle,
+///             transform::{invert_circle, InversionResult},
+///         },
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let circle = Circle::new(point![0.0, 0.0], 2.0);
+///     let through_center = Circle::new(point![1.0, 0.0], 1.0);
+///
+///     let InversionResult::Line(line) = invert_circle(&through_center, &circle)
+///     else {
+///         panic!("expected a line");
+///     };
+///     assert_relative_eq!(line.distance_to_point(&point![2.0, 5.0]), 0.0);
+///
+pub fn invert_circle(c: &Circle, circle: &Circle) -> InversionResult {
+    let r_squared = circle.radius * circle.radius;
+    let offset = c.center - circle.center;
+    let d = offset.norm();
+    if d == 0.0 {
+        // Concentric circles stay concentric.
+        return InversionResult::Circle(Circle::new(
+            circle.center,
+            r_squared / c.radius,
+        ));
+    }
+    let direction = Unit::new_unchecked(offset / d);
+
+    if passes_through(d, c.radius) {
+        // The point on `c` farthest from the center maps to the closest point
+        // on the line, and the line is perpendicular to `direction`.
+        let closest =
+            circle.center + direction.as_ref() * (r_squared / (2.0 * c.radius));
+        return InversionResult::Line(line_through(&closest, direction));
+    }
+
+    // The points on `c` nearest and farthest from the center lie on the same
+    // diameter of `c` before and after inversion.
+    let near = r_squared / (d - c.radius);
+    let far = r_squared / (d + c.radius);
+    InversionResult::Circle(Circle::new(
+        circle.center + direction.as_ref() * ((near + far) * 0.5),
+        ((near - far) * 0.5).abs(),
+    ))
+}
+
+/// Invert a line in a circle.
+///
+/// The result is a circle through the center of inversion unless the line
+/// passes through the center, in which case the line maps to itself.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             line::Line,
+///             shape::Circle,
+///             transform::{invert_line, InversionResult},
+///         },
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     let circle = Circle::new(point![0.0, 0.0], 2.0);
+///     // the line x = 2
+///     let line = Line::new(Unit::new_normalize(vector![1.0, 0.0]), -2.0);
+///
+///     let InversionResult::Circle(image) = invert_line(&line, &circle) else {
+///         panic!("expected a circle");
+///     };
+///     assert_relative_eq!(image.center, point![1.0, 0.0]);
+///     assert_relative_eq!(image.radius, 1.0);
+///
+pub fn invert_line(line: &Line, circle: &Circle) -> InversionResult {
+    let distance = line.distance_to_point(&circle.center);
+    if distance == 0.0 {
+        return InversionResult::Line(*line);
+    }
+    // The closest point on the line maps to the point on the image circle
+    // which is farthest from the center.
+    let r_squared = circle.radius * circle.radius;
+    let diameter = r_squared / distance;
+    InversionResult::Circle(Circle::new(
+        circle.center - line.normal.as_ref() * (diameter * 0.5),
+        (diameter * 0.5).abs(),
+    ))
+}
+
+/// The line dual to a point.
+///
+/// The point `(a, b)` corresponds to the line `a*x + b*y = 1`. Returns `None`
+/// for the origin, whose dual is the line at infinity.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::DistanceToPoint, transform::dual_point_to_line},
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///     };
+///
+///     let line = dual_point_to_line(&point![2.0, 0.0]).unwrap();
+///
+///     // the vertical line x = 0.5
+///     assert_relative_eq!(line.distance_to_point(&point![0.5, 7.0]), 0.0);
+///
+pub fn dual_point_to_line(point: &Point2<f32>) -> Option<Line> {
+    let norm = point.coords.norm();
+    if norm == 0.0 {
+        return None;
+    }
+    Some(Line::new(
+        Unit::new_unchecked(point.coords / norm),
+        -1.0 / norm,
+    ))
+}
+
+/// The point dual to a line.
+///
+/// This is the inverse of [`dual_point_to_line`]. Returns `None` for lines
+/// through the origin, whose duals are points at infinity.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Line, transform::dual_line_to_point},
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     // the horizontal line y = 4
+///     let line = Line::new(Unit::new_normalize(vector![0.0, 1.0]), -4.0);
+///
+///     assert_relative_eq!(dual_line_to_point(&line).unwrap(), point![0.0, 0.25]);
+///
+pub fn dual_line_to_point(line: &Line) -> Option<Point2<f32>> {
+    if line.c == 0.0 {
+        return None;
+    }
+    // n.x * x + n.y * y + c = 0 is the same as (-n / c) . (x, y) = 1
+    Some(Point2::from(line.normal.as_ref() * (-1.0 / line.c)))
+}
+
+/// Reflect a point across a line, like a mirror.
+///
+/// Points on the line stay where they are, and mirroring twice returns the
+/// original point.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{line::Line, transform::mirror_across},
+///         nalgebra::{point, vector, Unit},
+///         approx::assert_relative_eq,
+///     };
+///
+///     // the vertical line x = 1
+///     let line = Line::new(Unit::new_normalize(vector![1.0, 0.0]), -1.0);
+///
+///     assert_relative_eq!(
+///         mirror_across(&point![3.0, 5.0], &line),
+///         point![-1.0, 5.0]
+///     );
+///
+pub fn mirror_across(point: &Point2<f32>, line: &Line) -> Point2<f32> {
+    point - line.normal.scale(2.0 * line.distance_to_point(point))
+}
+
+/// Returns true when a circle with radius `radius`, whose center is
+/// `distance` away from the center of inversion, passes through the center
+/// of inversion.
+fn passes_through(distance: f32, radius: f32) -> bool {
+    // A relative tolerance keeps round trips stable: inverting a line gives
+    // a circle whose radius and center distance differ by rounding error.
+    (distance - radius).abs() <= 1e-5 * distance.max(radius)
+}
+
+/// The line through a point with the given normal.
+fn line_through(point: &Point2<f32>, normal: Unit<Vector2<f32>>) -> Line {
+    Line::new(normal, -normal.dot(&point.coords))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            line::{DistanceToPoint, Line},
+            shape::Circle,
+            test_support::{circle, line, point2},
+            transform::{
+                dual_line_to_point, dual_point_to_line, invert_circle,
+                invert_line, invert_point, mirror_across, InversionResult,
+            },
+        },
+        approx::assert_relative_eq,
+        nalgebra::{point, vector, Unit},
+        proptest::prelude::*,
+    };
+
+    fn inversion_circle() -> Circle {
+        Circle::new(point![1.0, -2.0], 3.0)
+    }
+
+    #[test]
+    pub fn points_on_the_circle_should_not_move() {
+        let circle = inversion_circle();
+        let p = point![4.0, -2.0];
+
+        assert_relative_eq!(invert_point(&p, &circle).unwrap(), p);
+    }
+
+    #[test]
+    pub fn circles_through_the_center_should_become_lines() {
+        let circle = inversion_circle();
+        let through_center = Circle::new(point![1.0, 0.0], 2.0);
+
+        let InversionResult::Line(image) =
+            invert_circle(&through_center, &circle)
+        else {
+            panic!("expected a line");
+        };
+
+        // every point on the original circle (except the center of inversion)
+        // should map onto the line
+        for p in [point![3.0, 0.0], point![-1.0, 0.0], point![1.0, 2.0]] {
+            let inverted = invert_point(&p, &circle).unwrap();
+            assert_relative_eq!(
+                image.distance_to_point(&inverted),
+                0.0,
+                epsilon = 1e-5
+            );
+        }
+    }
+
+    #[test]
+    pub fn lines_through_the_center_should_map_to_themselves() {
+        let circle = Circle::new(point![0.0, 0.0], 2.0);
+        let line = Line::new(Unit::new_normalize(vector![1.0, 1.0]), 0.0);
+
+        assert_eq!(invert_line(&line, &circle), InversionResult::Line(line));
+    }
+
+    #[test]
+    pub fn concentric_circles_should_stay_concentric() {
+        let circle = inversion_circle();
+        let c = Circle::new(circle.center, 1.5);
+
+        assert_eq!(
+            invert_circle(&c, &circle),
+            InversionResult::Circle(Circle::new(circle.center, 6.0))
+        );
+    }
+
+    #[test]
+    pub fn the_origin_and_lines_through_it_should_have_no_dual() {
+        assert_eq!(dual_point_to_line(&point![0.0, 0.0]), None);
+        let line = Line::new(Unit::new_normalize(vector![1.0, 2.0]), 0.0);
+        assert_eq!(dual_line_to_point(&line), None);
+    }
+
+    proptest! {
+        #[test]
+        fn inverting_a_point_twice_should_return_it(
+            p in point2(-10.0..=10.0)
+        ) {
+            let circle = inversion_circle();
+            prop_assume!((p - circle.center).norm() > 0.1);
+
+            let twice = invert_point(&p, &circle)
+                .and_then(|p| invert_point(&p, &circle))
+                .unwrap();
+
+            assert_relative_eq!(twice, p, epsilon = 1e-4);
+        }
+
+        #[test]
+        fn inverting_a_circle_twice_should_return_it(
+            c in circle(-10.0..=10.0, 0.5..=5.0)
+        ) {
+            let circle = inversion_circle();
+            let distance = (c.center - circle.center).norm();
+            prop_assume!((distance - c.radius).abs() > 0.1);
+
+            let InversionResult::Circle(once) = invert_circle(&c, &circle)
+            else {
+                panic!("expected a circle");
+            };
+            let InversionResult::Circle(twice) = invert_circle(&once, &circle)
+            else {
+                panic!("expected a circle");
+            };
+
+            assert_relative_eq!(twice.center, c.center, epsilon = 1e-2);
+            assert_relative_eq!(twice.radius, c.radius, epsilon = 1e-2);
+        }
+
+        #[test]
+        fn inverting_a_line_twice_should_return_it(l in line(-10.0..=10.0)) {
+            let circle = inversion_circle();
+            prop_assume!(l.distance_to_point(&circle.center).abs() > 0.1);
+
+            let InversionResult::Circle(once) = invert_line(&l, &circle) else {
+                panic!("expected a circle");
+            };
+            let InversionResult::Line(twice) = invert_circle(&once, &circle)
+            else {
+                panic!("expected a line");
+            };
+
+            // Inversion doesn't preserve which side of the line the normal
+            // points to, so compare the lines up to sign.
+            let sign = twice.normal.dot(&l.normal).signum();
+            assert_relative_eq!(
+                twice.normal.into_inner() * sign,
+                l.normal.into_inner(),
+                epsilon = 1e-4
+            );
+            assert_relative_eq!(twice.c * sign, l.c, epsilon = 1e-3);
+        }
+
+        #[test]
+        fn duals_should_preserve_incidence(
+            p in point2(-10.0..=10.0),
+            q in point2(-10.0..=10.0),
+        ) {
+            prop_assume!(p.coords.norm() > 0.1 && q.coords.norm() > 0.1);
+            let (p_dual, q_dual) = (
+                dual_point_to_line(&p).unwrap(),
+                dual_point_to_line(&q).unwrap(),
+            );
+
+            // p lies on dual(q) exactly when q lies on dual(p)
+            assert_relative_eq!(
+                p_dual.distance_to_point(&q) * p.coords.norm(),
+                q_dual.distance_to_point(&p) * q.coords.norm(),
+                epsilon = 1e-3
+            );
+            assert_relative_eq!(
+                dual_line_to_point(&p_dual).unwrap(),
+                p,
+                epsilon = 1e-4
+            );
+        }
+
+        #[test]
+        fn mirroring_twice_should_return_the_point(
+            point in point2(-100.0..=100.0),
+            line in line(-100.0..=100.0),
+        ) {
+            let mirrored = mirror_across(&point, &line);
+
+            let moved = (mirrored - point).norm();
+            let distance = line.distance_to_point(&point).abs();
+            prop_assert!((moved - 2.0 * distance).abs() < 1e-3);
+            prop_assert!(
+                (mirror_across(&mirrored, &line) - point).norm() < 1e-3
+            );
+        }
+    }
+}
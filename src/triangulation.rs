@@ -0,0 +1,649 @@
+//! Triangle meshes with fast point location.
+//!
+//! A [`Triangulation`] stores triangles as indices into a list of points and
+//! links each triangle to the triangles across its edges. The adjacency lets
+//! [`Triangulation::locate`] find the triangle which contains a point by
+//! walking from a nearby triangle instead of testing every triangle.
+//!
+//! Triangles are always stored counterclockwise. Edge `i` of a triangle runs
+//! from its corner `i` to its corner `(i + 1) % 3`.
+
+use {
+    crate::{polygon::Polygon, shape::Aabb},
+    alloc::{collections::BTreeMap, vec::Vec},
+    nalgebra::{Point2, Vector2},
+};
+
+/// Where a point is relative to a [`Triangulation`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LocateResult {
+    /// The point is strictly inside the triangle with this index.
+    Inside(usize),
+
+    /// The point is on an edge of a triangle, but not at either end. The
+    /// triangle on the other side of the edge, if there is one, contains the
+    /// point too.
+    OnEdge {
+        /// The index of the triangle.
+        triangle: usize,
+
+        /// Which of the triangle's edges the point is on, from 0 to 2.
+        edge: usize,
+    },
+
+    /// The point is exactly at a vertex of a triangle. Every triangle which
+    /// shares the vertex contains the point too.
+    OnVertex {
+        /// The index of the triangle.
+        triangle: usize,
+
+        /// The index of the vertex in [`Triangulation::points`].
+        vertex: usize,
+    },
+
+    /// The point is not inside any triangle.
+    Outside,
+}
+
+impl LocateResult {
+    /// The triangle which contains the point, if any.
+    ///
+    /// This is a good hint for the next call to [`Triangulation::locate`]
+    /// when queries are close together.
+    pub fn triangle(&self) -> Option<usize> {
+        match *self {
+            LocateResult::Inside(triangle)
+            | LocateResult::OnEdge { triangle, .. }
+            | LocateResult::OnVertex { triangle, .. } => Some(triangle),
+            LocateResult::Outside => None,
+        }
+    }
+}
+
+/// A set of triangles which share vertices and edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangulation {
+    points: Vec<Point2<f32>>,
+    triangles: Vec<[usize; 3]>,
+    neighbors: Vec<[Option<usize>; 3]>,
+    is_convex: bool,
+}
+
+impl Triangulation {
+    /// Create a triangulation from points and triangles which index into
+    /// them.
+    ///
+    /// Clockwise triangles are reversed so that every triangle is stored
+    /// counterclockwise. Triangles which share an edge become neighbors. The
+    /// triangles should not overlap, and each edge should be shared by at
+    /// most two triangles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a triangle refers to a point which doesn't exist.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::triangulation::Triangulation,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = Triangulation::new(
+    ///         vec![
+    ///             point![0.0, 0.0],
+    ///             point![1.0, 0.0],
+    ///             point![1.0, 1.0],
+    ///             point![0.0, 1.0],
+    ///         ],
+    ///         vec![[0, 1, 2], [0, 2, 3]],
+    ///     );
+    ///
+    ///     assert_eq!(square.neighbors(0), [None, None, Some(1)]);
+    ///
+    pub fn new(points: Vec<Point2<f32>>, triangles: Vec<[usize; 3]>) -> Self {
+        let triangles: Vec<[usize; 3]> = triangles
+            .into_iter()
+            .map(|[a, b, c]| {
+                if orientation(&points[a], &points[b], &points[c]) < 0.0 {
+                    [a, c, b]
+                } else {
+                    [a, b, c]
+                }
+            })
+            .collect();
+
+        let mut edges = BTreeMap::new();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                edges.insert((triangle[i], triangle[(i + 1) % 3]), (t, i));
+            }
+        }
+        let mut neighbors = vec![[None; 3]; triangles.len()];
+        let mut boundary = BTreeMap::new();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                match edges.get(&(b, a)) {
+                    Some(&(other, _)) => neighbors[t][i] = Some(other),
+                    None => {
+                        boundary.insert(a, b);
+                    }
+                }
+            }
+        }
+
+        let is_convex = is_convex_boundary(&points, &boundary);
+        Self {
+            points,
+            triangles,
+            neighbors,
+            is_convex,
+        }
+    }
+
+    /// Triangulate a polygon with [`Polygon::triangulate_fast`].
+    pub fn from_polygon(polygon: &Polygon) -> Self {
+        Self::new(polygon.vertices.clone(), polygon.triangulate_fast())
+    }
+
+    /// Build the Delaunay triangulation of a set of points.
+    ///
+    /// No point is inside the circumcircle of any triangle, which avoids
+    /// long thin triangles where possible. The triangles cover the convex
+    /// hull of the points, up to the limits of the large enclosing triangle
+    /// the construction starts from.
+    ///
+    /// Points are inserted one at a time with the Bowyer-Watson algorithm,
+    /// which takes O(n²) time. Duplicate points, and points which are all
+    /// collinear, are left out of the triangles.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::triangulation::Triangulation,
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let triangulation = Triangulation::delaunay(vec![
+    ///         point![0.0, 0.0],
+    ///         point![4.0, 0.0],
+    ///         point![4.0, 1.0],
+    ///         point![0.0, 1.0],
+    ///         point![2.0, 0.5],
+    ///     ]);
+    ///
+    ///     assert_eq!(triangulation.triangles().len(), 4);
+    ///
+    pub fn delaunay(points: Vec<Point2<f32>>) -> Self {
+        let n = points.len();
+        let Some(bounds) = Aabb::from_points(&points) else {
+            return Self::new(points, Vec::new());
+        };
+        let center = bounds.center();
+        let size = bounds.width().max(bounds.height()).max(1.0);
+        let mut all = points;
+        all.extend([
+            center + Vector2::new(-20.0, -10.0) * size,
+            center + Vector2::new(20.0, -10.0) * size,
+            center + Vector2::new(0.0, 20.0) * size,
+        ]);
+
+        let mut triangles = vec![[n, n + 1, n + 2]];
+        let mut boundary: Vec<(usize, usize)> = Vec::new();
+        for index in 0..n {
+            let point = all[index];
+            let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) =
+                triangles.iter().partition(|&&[a, b, c]| {
+                    in_circumcircle(&all[a], &all[b], &all[c], &point)
+                });
+            if bad.is_empty() {
+                continue;
+            }
+            // The edges which only belong to one bad triangle outline the
+            // cavity. They're counterclockwise around it, so joining each
+            // one to the new point makes a counterclockwise triangle.
+            boundary.clear();
+            for [a, b, c] in &bad {
+                for (from, to) in [(*a, *b), (*b, *c), (*c, *a)] {
+                    match boundary.iter().position(|&edge| edge == (to, from)) {
+                        Some(shared) => {
+                            boundary.swap_remove(shared);
+                        }
+                        None => boundary.push((from, to)),
+                    }
+                }
+            }
+            triangles = good;
+            triangles
+                .extend(boundary.iter().map(|&(from, to)| [from, to, index]));
+        }
+
+        triangles.retain(|triangle| triangle.iter().all(|&i| i < n));
+        all.truncate(n);
+        Self::new(all, triangles)
+    }
+
+    /// All of the points which the triangles index into.
+    pub fn points(&self) -> &[Point2<f32>] {
+        &self.points
+    }
+
+    /// All of the triangles, each wound counterclockwise.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// The triangles across each edge of a triangle. Edges on the boundary
+    /// of the triangulation have no neighbor.
+    pub fn neighbors(&self, triangle: usize) -> [Option<usize>; 3] {
+        self.neighbors[triangle]
+    }
+
+    /// Find the triangle which contains a point.
+    ///
+    /// The search walks from the `hint` triangle toward the point, crossing
+    /// whichever edge the point is behind. Queries are fastest when the hint
+    /// is close to the point, like the triangle from the previous query in a
+    /// sequence of nearby queries. Without a hint, the walk starts from the
+    /// first triangle.
+    ///
+    /// Walks can circle forever in some triangulations, and they can't cross
+    /// gaps in a non-convex triangulation, so the walk gives up after
+    /// visiting as many triangles as the triangulation has and falls back to
+    /// checking every triangle. The walk also falls back when it reaches a
+    /// triangle with no area.
+    ///
+    /// # Example
+    ///
+    ///     use ::{
+    ///         compgeo::triangulation::{LocateResult, Triangulation},
+    ///         nalgebra::point,
+    ///     };
+    ///
+    ///     let square = Triangulation::new(
+    ///         vec![
+    ///             point![0.0, 0.0],
+    ///             point![1.0, 0.0],
+    ///             point![1.0, 1.0],
+    ///             point![0.0, 1.0],
+    ///         ],
+    ///         vec![[0, 1, 2], [0, 2, 3]],
+    ///     );
+    ///
+    ///     assert_eq!(
+    ///         square.locate(&point![0.25, 0.75], None),
+    ///         LocateResult::Inside(1)
+    ///     );
+    ///     assert_eq!(
+    ///         square.locate(&point![0.5, 0.5], Some(1)),
+    ///         LocateResult::OnEdge { triangle: 1, edge: 0 }
+    ///     );
+    ///     assert_eq!(
+    ///         square.locate(&point![1.0, 1.0], None),
+    ///         LocateResult::OnVertex { triangle: 0, vertex: 2 }
+    ///     );
+    ///     assert_eq!(
+    ///         square.locate(&point![2.0, 0.5], None),
+    ///         LocateResult::Outside
+    ///     );
+    ///
+    pub fn locate(
+        &self,
+        point: &Point2<f32>,
+        hint: Option<usize>,
+    ) -> LocateResult {
+        if self.triangles.is_empty() {
+            return LocateResult::Outside;
+        }
+        let mut current =
+            hint.filter(|&t| t < self.triangles.len()).unwrap_or(0);
+        for _ in 0..self.triangles.len() {
+            let Some(sides) = self.sides(current, point) else {
+                break;
+            };
+            let exit = (0..3).find(|&i| sides[i] < 0.0);
+            let Some(exit) = exit else {
+                return self.classify(current, sides);
+            };
+            match self.neighbors[current][exit] {
+                Some(next) => current = next,
+                None if self.is_convex => return LocateResult::Outside,
+                None => break,
+            }
+        }
+        self.locate_by_scan(point)
+    }
+
+    /// Find the triangle which contains a point by checking every triangle.
+    fn locate_by_scan(&self, point: &Point2<f32>) -> LocateResult {
+        (0..self.triangles.len())
+            .find_map(|t| {
+                let sides = self.sides(t, point)?;
+                sides
+                    .iter()
+                    .all(|&side| side >= 0.0)
+                    .then(|| self.classify(t, sides))
+            })
+            .unwrap_or(LocateResult::Outside)
+    }
+
+    /// Which side of each edge of a triangle the point is on: positive on
+    /// the inside, negative on the outside, and zero on the edge's line.
+    ///
+    /// Returns `None` when the triangle has no area.
+    fn sides(&self, triangle: usize, point: &Point2<f32>) -> Option<[f64; 3]> {
+        let [a, b, c] = self.triangles[triangle].map(|i| self.points[i]);
+        if orientation(&a, &b, &c) == 0.0 {
+            return None;
+        }
+        Some([
+            orientation(&a, &b, point),
+            orientation(&b, &c, point),
+            orientation(&c, &a, point),
+        ])
+    }
+
+    /// Describe where a point is in a triangle which contains it.
+    fn classify(&self, triangle: usize, sides: [f64; 3]) -> LocateResult {
+        for i in 0..3 {
+            if sides[i] == 0.0 && sides[(i + 2) % 3] == 0.0 {
+                return LocateResult::OnVertex {
+                    triangle,
+                    vertex: self.triangles[triangle][i],
+                };
+            }
+        }
+        match (0..3).find(|&i| sides[i] == 0.0) {
+            Some(edge) => LocateResult::OnEdge { triangle, edge },
+            None => LocateResult::Inside(triangle),
+        }
+    }
+}
+
+/// Twice the signed area of the triangle a, b, c.
+///
+/// The products are computed with f64 so the sign is exact for f32 inputs
+/// whose differences are representable, which makes the walk's decisions
+/// consistent across shared edges.
+fn orientation(a: &Point2<f32>, b: &Point2<f32>, c: &Point2<f32>) -> f64 {
+    let (abx, aby) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+    let (acx, acy) = (c.x as f64 - a.x as f64, c.y as f64 - a.y as f64);
+    abx * acy - aby * acx
+}
+
+/// Returns true when `d` is strictly inside the circumcircle of the
+/// counterclockwise triangle a, b, c.
+fn in_circumcircle(
+    a: &Point2<f32>,
+    b: &Point2<f32>,
+    c: &Point2<f32>,
+    d: &Point2<f32>,
+) -> bool {
+    let lift = |p: &Point2<f32>| {
+        let (x, y) = (p.x as f64 - d.x as f64, p.y as f64 - d.y as f64);
+        (x, y, x * x + y * y)
+    };
+    let (ax, ay, a2) = lift(a);
+    let (bx, by, b2) = lift(b);
+    let (cx, cy, c2) = lift(c);
+    let determinant = ax * (by * c2 - b2 * cy) - ay * (bx * c2 - b2 * cx)
+        + a2 * (bx * cy - by * cx);
+    determinant > 0.0
+}
+
+/// Returns true when the boundary edges form a single loop which never
+/// turns clockwise.
+fn is_convex_boundary(
+    points: &[Point2<f32>],
+    boundary: &BTreeMap<usize, usize>,
+) -> bool {
+    let Some((&first, _)) = boundary.iter().next() else {
+        return false;
+    };
+    let mut loop_length = 0;
+    let mut current = first;
+    loop {
+        let Some(&next) = boundary.get(&current) else {
+            return false;
+        };
+        let Some(&after) = boundary.get(&next) else {
+            return false;
+        };
+        if orientation(&points[current], &points[next], &points[after]) < 0.0 {
+            return false;
+        }
+        loop_length += 1;
+        current = next;
+        if current == first || loop_length > boundary.len() {
+            break;
+        }
+    }
+    current == first && loop_length == boundary.len()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            polygon::Polygon,
+            shape::Circle,
+            test_support::{point2, polygon},
+            triangulation::{LocateResult, Triangulation},
+        },
+        alloc::collections::BTreeSet,
+        nalgebra::{point, Point2},
+        proptest::prelude::*,
+    };
+
+    /// A triangulated grid of unit squares, with `size` squares per side.
+    fn grid(size: usize) -> Triangulation {
+        let stride = size + 1;
+        let points = (0..stride * stride)
+            .map(|i| point![(i % stride) as f32, (i / stride) as f32])
+            .collect();
+        let mut triangles = vec![];
+        for y in 0..size {
+            for x in 0..size {
+                let corner = y * stride + x;
+                triangles.push([corner, corner + 1, corner + stride + 1]);
+                triangles.push([corner, corner + stride + 1, corner + stride]);
+            }
+        }
+        Triangulation::new(points, triangles)
+    }
+
+    /// Describe a result without depending on which of the triangles that
+    /// share an edge or vertex was found.
+    fn feature(
+        triangulation: &Triangulation,
+        result: LocateResult,
+    ) -> (u8, BTreeSet<usize>) {
+        match result {
+            LocateResult::Inside(t) => (0, BTreeSet::from([t])),
+            LocateResult::OnEdge { triangle, edge } => {
+                let corners = triangulation.triangles()[triangle];
+                (1, BTreeSet::from([corners[edge], corners[(edge + 1) % 3]]))
+            }
+            LocateResult::OnVertex { vertex, .. } => {
+                (2, BTreeSet::from([vertex]))
+            }
+            LocateResult::Outside => (3, BTreeSet::new()),
+        }
+    }
+
+    #[test]
+    pub fn clockwise_triangles_should_be_reversed() {
+        let triangulation = Triangulation::new(
+            vec![point![0.0, 0.0], point![1.0, 0.0], point![0.0, 1.0]],
+            vec![[0, 2, 1]],
+        );
+
+        assert_eq!(triangulation.triangles(), &[[0, 1, 2]]);
+        assert!(triangulation.is_convex);
+    }
+
+    #[test]
+    pub fn walks_should_find_every_feature_of_a_grid() {
+        let grid = grid(10);
+
+        // The last square's lower right triangle
+        assert_eq!(
+            grid.locate(&point![9.7, 9.2], Some(0)),
+            LocateResult::Inside(198)
+        );
+        let on_edge = grid.locate(&point![3.5, 7.0], Some(0));
+        assert_eq!(feature(&grid, on_edge), (1, BTreeSet::from([80, 81])));
+        let on_vertex = grid.locate(&point![4.0, 4.0], Some(150));
+        assert_eq!(feature(&grid, on_vertex), (2, BTreeSet::from([48])));
+        assert_eq!(
+            grid.locate(&point![-1.0, 4.0], Some(150)),
+            LocateResult::Outside
+        );
+    }
+
+    #[test]
+    pub fn walks_should_not_cross_gaps_in_concave_triangulations() {
+        // A "U" where the walk from one arm to the other has to leave the
+        // triangulation.
+        let u = Triangulation::from_polygon(&Polygon::new(vec![
+            point![0.0, 0.0],
+            point![3.0, 0.0],
+            point![3.0, 3.0],
+            point![2.0, 3.0],
+            point![2.0, 1.0],
+            point![1.0, 1.0],
+            point![1.0, 3.0],
+            point![0.0, 3.0],
+        ]));
+        assert!(!u.is_convex);
+
+        let left = u.locate(&point![0.5, 2.5], None);
+        let right = u.locate(&point![2.5, 2.5], left.triangle());
+
+        assert!(matches!(left, LocateResult::Inside(_)));
+        assert!(matches!(right, LocateResult::Inside(_)));
+        assert_eq!(
+            u.locate(&point![1.5, 2.5], right.triangle()),
+            LocateResult::Outside
+        );
+    }
+
+    #[test]
+    pub fn slivers_should_fall_back_to_a_scan() {
+        // The middle triangle has no area.
+        let triangulation = Triangulation::new(
+            vec![
+                point![0.0, 0.0],
+                point![2.0, 0.0],
+                point![1.0, 0.0],
+                point![1.0, 1.0],
+                point![1.0, -1.0],
+            ],
+            vec![[0, 2, 3], [0, 1, 2], [2, 1, 3], [0, 4, 1]],
+        );
+
+        for hint in 0..4 {
+            assert_eq!(
+                triangulation.locate(&point![1.5, 0.25], Some(hint)),
+                LocateResult::Inside(2)
+            );
+        }
+    }
+
+    #[test]
+    pub fn empty_triangulations_should_contain_nothing() {
+        let empty = Triangulation::new(vec![], vec![]);
+
+        assert_eq!(
+            empty.locate(&Point2::origin(), Some(3)),
+            LocateResult::Outside
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn walks_should_agree_with_a_scan_in_grids(
+            queries in proptest::collection::vec(point2(-1.0..=9.0), 1..20),
+            snap in any::<bool>(),
+        ) {
+            let grid = grid(8);
+            let mut hint = None;
+            for query in queries {
+                // Snapping to a half-unit lattice exercises edges and vertices.
+                let query = if snap {
+                    (query * 2.0).map(f32::round) / 2.0
+                } else {
+                    query
+                };
+                let walked = grid.locate(&query, hint);
+                let scanned = grid.locate_by_scan(&query);
+                prop_assert_eq!(feature(&grid, walked), feature(&grid, scanned));
+                hint = walked.triangle();
+            }
+        }
+
+        #[test]
+        fn walks_should_agree_with_a_scan_in_polygons(
+            polygon in polygon(-10.0..=10.0, 3..=30, false),
+            queries in proptest::collection::vec(point2(-11.0..=11.0), 1..20),
+        ) {
+            let triangulation = Triangulation::from_polygon(&polygon);
+            let mut hint = None;
+            for query in &queries {
+                let walked = triangulation.locate(query, hint);
+                let scanned = triangulation.locate_by_scan(query);
+                prop_assert_eq!(
+                    feature(&triangulation, walked),
+                    feature(&triangulation, scanned)
+                );
+                hint = walked.triangle();
+            }
+            for vertex in &polygon.vertices {
+                let result = triangulation.locate(vertex, hint);
+                let is_on_vertex =
+                    matches!(result, LocateResult::OnVertex { .. });
+                prop_assert!(is_on_vertex);
+            }
+        }
+    }
+
+    #[test]
+    pub fn delaunay_should_skip_duplicate_and_collinear_points() {
+        let line = (0..5).map(|i| point![i as f32, 2.0 * i as f32]).collect();
+        let square = vec![
+            point![0.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 0.0],
+            point![1.0, 1.0],
+            point![0.0, 1.0],
+        ];
+
+        assert!(Triangulation::delaunay(line).triangles().is_empty());
+        assert!(Triangulation::delaunay(vec![]).triangles().is_empty());
+        let triangulation = Triangulation::delaunay(square);
+        assert_eq!(triangulation.triangles().len(), 2);
+        assert_eq!(triangulation.points().len(), 5);
+    }
+
+    proptest! {
+        #[test]
+        fn delaunay_triangles_should_have_empty_circumcircles(
+            points in prop::collection::vec(point2(-10.0..=10.0), 3..40),
+        ) {
+            let triangulation = Triangulation::delaunay(points.clone());
+
+            for &[a, b, c] in triangulation.triangles() {
+                let [a, b, c] = [a, b, c].map(|i| points[i]);
+                prop_assert!((b - a).perp(&(c - a)) > 0.0);
+                let circle = Circle::circumscribe(&a, &b, &c).unwrap();
+                for p in &points {
+                    prop_assert!(
+                        (p - circle.center).norm()
+                            >= circle.radius * (1.0 - 1e-4) - 1e-4
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Turtle graphics, which draw paths by walking and turning.
+//!
+//! A turtle has a position and a heading, and draws while its pen is down.
+//! Its state can be pushed and popped to draw branches, which is how
+//! [L-systems](crate::lsystem) draw plants.
+
+use {
+    crate::{math, polygon::Polyline},
+    alloc::vec::Vec,
+    nalgebra::{Point2, Vector2},
+};
+
+/// A turtle which draws polylines.
+///
+/// Each stroke drawn without lifting the pen or jumping back to a pushed
+/// state becomes one polyline. The heading is in radians counterclockwise
+/// from the x axis.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::turtle::Turtle,
+///         nalgebra::point,
+///         approx::assert_relative_eq,
+///         core::f32::consts::FRAC_PI_2,
+///     };
+///
+///     let mut turtle = Turtle::new(point![0.0, 0.0], 0.0);
+///     turtle.forward(2.0).turn(FRAC_PI_2).forward(1.0);
+///     turtle.pen_up().forward(1.0).pen_down().forward(1.0);
+///
+///     let strokes = turtle.finish();
+///
+///     assert_eq!(strokes.len(), 2);
+///     assert_eq!(strokes[0].vertices.len(), 3);
+///     assert_relative_eq!(strokes[1].vertices[1], point![2.0, 3.0]);
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turtle {
+    state: State,
+    stack: Vec<State>,
+    stroke: Vec<Point2<f32>>,
+    strokes: Vec<Polyline>,
+}
+
+/// Everything which is saved by [`Turtle::push`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct State {
+    position: Point2<f32>,
+    heading: f32,
+    pen_down: bool,
+}
+
+impl Turtle {
+    /// Create a turtle at a position with its pen down.
+    pub fn new(position: Point2<f32>, heading: f32) -> Self {
+        Self {
+            state: State {
+                position,
+                heading,
+                pen_down: true,
+            },
+            stack: Vec::new(),
+            stroke: Vec::new(),
+            strokes: Vec::new(),
+        }
+    }
+
+    /// The turtle's position.
+    pub fn position(&self) -> Point2<f32> {
+        self.state.position
+    }
+
+    /// The turtle's heading in radians counterclockwise from the x axis.
+    pub fn heading(&self) -> f32 {
+        self.state.heading
+    }
+
+    /// Returns true when the turtle draws as it moves.
+    pub fn is_pen_down(&self) -> bool {
+        self.state.pen_down
+    }
+
+    /// Move along the heading, drawing when the pen is down. Negative
+    /// distances move backwards.
+    pub fn forward(&mut self, distance: f32) -> &mut Self {
+        let (sin, cos) = math::sin_cos(self.state.heading);
+        let next = self.state.position + Vector2::new(cos, sin) * distance;
+        if self.state.pen_down {
+            if self.stroke.is_empty() {
+                self.stroke.push(self.state.position);
+            }
+            self.stroke.push(next);
+        }
+        self.state.position = next;
+        self
+    }
+
+    /// Turn counterclockwise by an angle in radians. Negative angles turn
+    /// clockwise.
+    pub fn turn(&mut self, angle: f32) -> &mut Self {
+        self.state.heading += angle;
+        self
+    }
+
+    /// Stop drawing, which ends the current stroke.
+    pub fn pen_up(&mut self) -> &mut Self {
+        self.end_stroke();
+        self.state.pen_down = false;
+        self
+    }
+
+    /// Start drawing again.
+    pub fn pen_down(&mut self) -> &mut Self {
+        self.state.pen_down = true;
+        self
+    }
+
+    /// Save the position, heading, and pen so [`Turtle::pop`] can return to
+    /// them.
+    pub fn push(&mut self) -> &mut Self {
+        self.stack.push(self.state);
+        self
+    }
+
+    /// Jump back to the most recently pushed state, which ends the current
+    /// stroke. Does nothing when no state has been pushed.
+    pub fn pop(&mut self) -> &mut Self {
+        if let Some(state) = self.stack.pop() {
+            self.end_stroke();
+            self.state = state;
+        }
+        self
+    }
+
+    /// Every stroke the turtle has drawn, in the order they were started.
+    pub fn finish(mut self) -> Vec<Polyline> {
+        self.end_stroke();
+        self.strokes
+    }
+
+    fn end_stroke(&mut self) {
+        let stroke = core::mem::take(&mut self.stroke);
+        if stroke.len() > 1 {
+            self.strokes.push(Polyline::new(stroke));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::Turtle,
+        approx::assert_relative_eq,
+        core::f32::consts::{FRAC_PI_2, PI},
+        nalgebra::point,
+    };
+
+    #[test]
+    pub fn pop_should_restore_the_position_and_heading_exactly() {
+        let mut turtle = Turtle::new(point![0.3, -1.7], 0.1);
+        turtle.forward(1.3).turn(0.7);
+
+        let (position, heading) = (turtle.position(), turtle.heading());
+        turtle.push();
+        turtle
+            .turn(1.1)
+            .forward(2.9)
+            .pen_up()
+            .turn(-0.4)
+            .forward(0.5);
+        turtle.pop();
+
+        assert_eq!(turtle.position(), position);
+        assert_eq!(turtle.heading(), heading);
+        assert!(turtle.is_pen_down());
+        turtle.forward(1.0);
+        let strokes = turtle.finish();
+        assert_eq!(strokes.len(), 2);
+        assert_eq!(strokes[1].vertices[0], position);
+    }
+
+    #[test]
+    pub fn squares_should_close() {
+        let mut turtle = Turtle::new(point![1.0, 1.0], PI);
+        for _ in 0..4 {
+            turtle.forward(2.0).turn(FRAC_PI_2);
+        }
+        // Popping without a pushed state, lifting the pen, and moving
+        // without drawing don't add strokes.
+        turtle.pop().pen_up().forward(3.0);
+
+        let strokes = turtle.finish();
+
+        assert_eq!(strokes.len(), 1);
+        assert_eq!(strokes[0].vertices.len(), 5);
+        assert_relative_eq!(
+            strokes[0].vertices[2],
+            point![-1.0, -1.0],
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            strokes[0].vertices[4],
+            point![1.0, 1.0],
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(strokes[0].length(), 8.0);
+    }
+}
@@ -0,0 +1,297 @@
+//! Visibility graphs and shortest paths around polygonal obstacles.
+
+use {
+    crate::{
+        graph::parameter_of,
+        line::{
+            intersection::{intersect_segments, SegmentIntersection},
+            DistanceToPoint, Segment,
+        },
+        polygon::{Polygon, Polyline},
+    },
+    alloc::vec::Vec,
+    nalgebra::Point2,
+};
+
+/// A graph connecting every pair of points which can see each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisGraph {
+    points: Vec<Point2<f32>>,
+    neighbors: Vec<Vec<usize>>,
+    first_extra_point: usize,
+}
+
+/// Build the visibility graph for a set of obstacles.
+///
+/// The graph's nodes are every obstacle vertex followed by every extra point,
+/// such as the start and goal for a path. Two nodes are connected when the
+/// segment between them doesn't pass through the interior of any obstacle.
+/// Segments which graze an obstacle's boundary, including segments which run
+/// along an obstacle's edge, are allowed.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{polygon::Polygon, visibility::visibility_graph},
+///         nalgebra::point,
+///     };
+///
+///     let wall = Polygon::new(vec![
+///         point![1.0, -1.0],
+///         point![2.0, -1.0],
+///         point![2.0, 1.0],
+///         point![1.0, 1.0],
+///     ]);
+///     let graph = visibility_graph(
+///         &[wall],
+///         &[point![0.0, 0.0], point![3.0, 0.0]],
+///     );
+///     let (start, goal) = (graph.extra_point(0), graph.extra_point(1));
+///
+///     assert!(!graph.neighbors(start).contains(&goal));
+///     assert_eq!(graph.neighbors(start).len(), 2);
+///
+pub fn visibility_graph(
+    obstacles: &[Polygon],
+    extra_points: &[Point2<f32>],
+) -> VisGraph {
+    let mut points: Vec<Point2<f32>> = obstacles
+        .iter()
+        .flat_map(|obstacle| obstacle.vertices.iter().copied())
+        .collect();
+    let first_extra_point = points.len();
+    points.extend_from_slice(extra_points);
+
+    let mut neighbors = vec![Vec::new(); points.len()];
+    for a in 0..points.len() {
+        for b in (a + 1)..points.len() {
+            let sight_line = Segment::new(points[a], points[b]);
+            if is_visible(&sight_line, obstacles) {
+                neighbors[a].push(b);
+                neighbors[b].push(a);
+            }
+        }
+    }
+
+    VisGraph {
+        points,
+        neighbors,
+        first_extra_point,
+    }
+}
+
+/// Find the shortest path between two nodes in a visibility graph.
+///
+/// Returns `None` when there is no path, for example when the goal is
+/// completely enclosed by obstacles. The path is a polyline from the start
+/// node to the goal node, which always turns at obstacle vertices.
+///
+/// # Example
+///
+///     use ::{
+///         compgeo::{
+///             polygon::Polygon,
+///             visibility::{shortest_path, visibility_graph},
+///         },
+///         nalgebra::point,
+///     };
+///
+///     let wall = Polygon::new(vec![
+///         point![1.0, -1.0],
+///         point![2.0, -1.0],
+///         point![2.0, 1.0],
+///         point![1.0, 1.0],
+///     ]);
+///     let graph = visibility_graph(
+///         &[wall],
+///         &[point![0.0, 0.0], point![3.0, 0.0]],
+///     );
+///     let path = shortest_path(
+///         &graph,
+///         graph.extra_point(0),
+///         graph.extra_point(1),
+///     )
+///     .unwrap();
+///
+///     assert_eq!(path.vertices.len(), 4);
+///
+pub fn shortest_path(
+    graph: &VisGraph,
+    start: usize,
+    goal: usize,
+) -> Option<Polyline> {
+    // Visibility graphs are dense, so a simple array-based Dijkstra is as
+    // fast as a heap-based one.
+    let n = graph.points.len();
+    let mut distance = vec![f32::INFINITY; n];
+    let mut previous: Vec<Option<usize>> = vec![None; n];
+    let mut done = vec![false; n];
+    distance[start] = 0.0;
+
+    loop {
+        let node = (0..n)
+            .filter(|&node| !done[node] && distance[node].is_finite())
+            .min_by(|&a, &b| distance[a].total_cmp(&distance[b]))?;
+        if node == goal {
+            break;
+        }
+        done[node] = true;
+        for &neighbor in &graph.neighbors[node] {
+            let d = distance[node]
+                + graph.points[node].distance_to_point(&graph.points[neighbor]);
+            if d < distance[neighbor] {
+                distance[neighbor] = d;
+                previous[neighbor] = Some(node);
+            }
+        }
+    }
+
+    let mut vertices = vec![graph.points[goal]];
+    let mut node = goal;
+    while let Some(prev) = previous[node] {
+        vertices.push(graph.points[prev]);
+        node = prev;
+    }
+    vertices.reverse();
+    Some(Polyline::new(vertices))
+}
+
+impl VisGraph {
+    /// The location of every node in the graph.
+    pub fn points(&self) -> &[Point2<f32>] {
+        &self.points
+    }
+
+    /// The nodes which can be seen from the given node.
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.neighbors[node]
+    }
+
+    /// The node for one of the extra points used to build the graph.
+    pub fn extra_point(&self, index: usize) -> usize {
+        self.first_extra_point + index
+    }
+}
+
+/// Returns true when the segment doesn't pass through any obstacle's
+/// interior.
+fn is_visible(sight_line: &Segment, obstacles: &[Polygon]) -> bool {
+    // Split the sight line everywhere it touches an obstacle's boundary. Each
+    // piece is then either entirely inside or entirely outside every
+    // obstacle, so checking its midpoint is enough.
+    let mut splits = vec![0.0, 1.0];
+    for edge in obstacles.iter().flat_map(Polygon::edges) {
+        match intersect_segments(sight_line, &edge) {
+            SegmentIntersection::None => {}
+            SegmentIntersection::Point(p) => {
+                splits.push(parameter_of(sight_line, &p))
+            }
+            SegmentIntersection::Overlap(s) => {
+                splits.push(parameter_of(sight_line, &s.start));
+                splits.push(parameter_of(sight_line, &s.end));
+            }
+        }
+    }
+    splits.sort_by(f32::total_cmp);
+
+    let tolerance = 1e-5 * sight_line.length().max(1.0);
+    let direction = sight_line.end - sight_line.start;
+    splits.windows(2).all(|pair| {
+        let midpoint =
+            sight_line.start + direction * ((pair[0] + pair[1]) * 0.5);
+        !obstacles
+            .iter()
+            .any(|obstacle| is_strictly_inside(obstacle, &midpoint, tolerance))
+    })
+}
+
+/// Returns true when the point is inside the polygon and not within
+/// `tolerance` of its boundary.
+fn is_strictly_inside(
+    polygon: &Polygon,
+    point: &Point2<f32>,
+    tolerance: f32,
+) -> bool {
+    polygon.contains_point(point)
+        && polygon
+            .edges()
+            .all(|edge| edge.distance_to_point(point) > tolerance)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        crate::{
+            test_support::rectangle,
+            visibility::{shortest_path, visibility_graph},
+        },
+        approx::assert_relative_eq,
+        nalgebra::point,
+    };
+
+    #[test]
+    pub fn paths_should_hug_the_corners_of_an_obstacle() {
+        let graph = visibility_graph(
+            &[rectangle(point![1.0, 1.0], point![3.0, 3.0])],
+            &[point![0.0, 2.0], point![4.0, 2.0]],
+        );
+
+        let path =
+            shortest_path(&graph, graph.extra_point(0), graph.extra_point(1))
+                .unwrap();
+
+        assert_eq!(path.vertices.len(), 4);
+        assert_relative_eq!(path.length(), 2.0 + 2.0 * 2.0f32.sqrt());
+        assert!(
+            path.vertices[1] == point![1.0, 1.0]
+                || path.vertices[1] == point![1.0, 3.0]
+        );
+    }
+
+    #[test]
+    pub fn unobstructed_paths_should_be_straight() {
+        let graph = visibility_graph(
+            &[rectangle(point![1.0, 1.0], point![3.0, 3.0])],
+            &[point![0.0, 0.0], point![4.0, 0.0]],
+        );
+
+        let path =
+            shortest_path(&graph, graph.extra_point(0), graph.extra_point(1))
+                .unwrap();
+
+        assert_eq!(path.vertices, vec![point![0.0, 0.0], point![4.0, 0.0]]);
+    }
+
+    #[test]
+    pub fn obstacle_diagonals_should_not_be_visible() {
+        let graph = visibility_graph(
+            &[rectangle(point![0.0, 0.0], point![1.0, 1.0])],
+            &[],
+        );
+
+        // each corner can see its two neighbors, but not the opposite corner
+        for node in 0..4 {
+            let mut neighbors = graph.neighbors(node).to_vec();
+            neighbors.sort();
+            let expected = {
+                let mut expected = vec![(node + 1) % 4, (node + 3) % 4];
+                expected.sort();
+                expected
+            };
+            assert_eq!(neighbors, expected);
+        }
+    }
+
+    #[test]
+    pub fn enclosed_goals_should_be_unreachable() {
+        let graph = visibility_graph(
+            &[rectangle(point![0.0, 0.0], point![10.0, 10.0])],
+            &[point![-1.0, -1.0], point![5.0, 5.0]],
+        );
+
+        assert_eq!(
+            shortest_path(&graph, graph.extra_point(0), graph.extra_point(1)),
+            None
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Welding nearby points into shared vertices.
+
+use {
+    crate::operations::{snap, SnappedPoint},
+    alloc::{collections::BTreeMap, vec::Vec},
+    nalgebra::Point2,
+};
+
+/// Assigns an index to every unique point, treating points within a
+/// tolerance of each other as the same point.
+///
+/// Points are snapped to a lattice with steps the size of the tolerance so
+/// each lookup only needs to check the neighboring lattice points. Points
+/// within the tolerance of each other are never more than one step apart.
+/// The first point seen for a location becomes the representative for every
+/// point welded to it.
+pub(crate) struct Welder {
+    tolerance_squared: f32,
+    cell_size: f32,
+    cells: BTreeMap<SnappedPoint, Vec<usize>>,
+    points: Vec<Point2<f32>>,
+}
+
+impl Welder {
+    pub(crate) fn new(tolerance: f32) -> Self {
+        Self {
+            tolerance_squared: tolerance * tolerance,
+            cell_size: tolerance.max(f32::MIN_POSITIVE),
+            cells: BTreeMap::new(),
+            points: Vec::new(),
+        }
+    }
+
+    /// Get the index for a point, adding it if no existing point is within
+    /// the tolerance.
+    pub(crate) fn weld(&mut self, point: &Point2<f32>) -> usize {
+        let cell = snap(point, self.cell_size);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let Some(bucket) = self.cells.get(&cell.offset(dx, dy)) else {
+                    continue;
+                };
+                for &index in bucket {
+                    let distance = (self.points[index] - point).norm_squared();
+                    if distance <= self.tolerance_squared {
+                        return index;
+                    }
+                }
+            }
+        }
+        let index = self.points.len();
+        self.points.push(*point);
+        self.cells.entry(cell).or_default().push(index);
+        index
+    }
+
+    /// The welded points, indexed by the values returned from
+    /// [`Welder::weld`].
+    pub(crate) fn into_points(self) -> Vec<Point2<f32>> {
+        self.points
+    }
+}